@@ -28,6 +28,14 @@ test("process and inject global", () => {
   expect(process_code("return CONSTANT", options)).toEqual("return true")
 })
 
+test("process invalid code throws", () => {
+  expect(() => process_code("return +")).toThrow()
+})
+
+test("process with invalid configuration throws", () => {
+  expect(() => process_code("return 1", "{ rules: [")).toThrow()
+})
+
 test("`get_all_rule_names` returns an array", () => {
   const names = get_all_rule_names()
   expect(names).toEqual(expect.any(Array))
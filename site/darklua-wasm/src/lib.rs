@@ -1,6 +1,5 @@
 mod utils;
 
-use darklua_core::{Configuration, Options, Resources};
 use utils::set_panic_hook;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
@@ -9,44 +8,22 @@ pub fn process_code(code: &str, opt_config: JsValue) -> Result<String, JsValue>
     set_panic_hook();
 
     let config = if opt_config.is_undefined() {
-        Configuration::default()
+        String::new()
     } else if opt_config.is_object() {
-        let config_string = String::from(js_sys::JSON::stringify(&opt_config)?);
-        json5::from_str(&config_string)
-            .map_err(|err| format!("unable to parse configuration: {}", err))?
+        String::from(js_sys::JSON::stringify(&opt_config)?)
     } else {
         opt_config
             .as_string()
-            .ok_or_else(|| format!("unsupported type passed as configuration"))
-            .and_then(|config| {
-                json5::from_str(&config)
-                    .map_err(|err| format!("unable to parse configuration: {}", err))
-            })?
+            .ok_or_else(|| "unsupported type passed as configuration".to_owned())?
     };
 
-    let resources = Resources::from_memory();
-    const LOCATION: &str = "file.lua";
-    resources.write(LOCATION, code).unwrap();
-
-    let worker_tree = darklua_core::process(
-        &resources,
-        Options::new(LOCATION).with_configuration(config),
-    )
-    .map_err(|err| err.to_string())?;
-
-    let errors = worker_tree.collect_errors();
-
-    if errors.is_empty() {
-        let lua_code = resources.get(LOCATION).unwrap();
-
-        Ok(lua_code)
-    } else {
-        let errors: Vec<_> = errors
-            .into_iter()
-            .map(|error| format!("-> {}", error))
+    darklua_core::process_code(code, &config).map_err(|diagnostics| {
+        let errors: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| format!("-> {}", diagnostic.message()))
             .collect();
-        Err(format!("unable to process code:\n{}", errors.join("\n")).into())
-    }
+        JsValue::from(format!("unable to process code:\n{}", errors.join("\n")))
+    })
 }
 
 #[wasm_bindgen]
@@ -2271,6 +2271,8 @@ impl<'a> AstConverter<'a> {
 
                 self.push_work(inner.as_ref());
             }
+            // keep the error branch next to the conversion of single types
+            #[allow(clippy::collapsible_match)]
             TypeInfo::Tuple { types, parentheses } => {
                 if types.len() == 1 {
                     self.work_stack
@@ -2659,6 +2661,11 @@ impl<'a> AstConverter<'a> {
             ast::BinOp::TildeEqual(_) => BinaryOperator::NotEqual,
             ast::BinOp::TwoDots(_) => BinaryOperator::Concat,
             ast::BinOp::TwoEqual(_) => BinaryOperator::Equal,
+            ast::BinOp::Ampersand(_) => BinaryOperator::BitwiseAnd,
+            ast::BinOp::Pipe(_) => BinaryOperator::BitwiseOr,
+            ast::BinOp::Tilde(_) => BinaryOperator::BitwiseXor,
+            ast::BinOp::DoubleLessThan(_) => BinaryOperator::LeftShift,
+            ast::BinOp::DoubleGreaterThan(_) => BinaryOperator::RightShift,
             _ => {
                 return Err(ConvertError::BinaryOperator {
                     operator: operator.to_string(),
@@ -2673,6 +2680,7 @@ impl<'a> AstConverter<'a> {
             ast::UnOp::Minus(_) => UnaryOperator::Minus,
             ast::UnOp::Not(_) => UnaryOperator::Not,
             ast::UnOp::Hash(_) => UnaryOperator::Length,
+            ast::UnOp::Tilde(_) => UnaryOperator::BitwiseNot,
             _ => {
                 return Err(ConvertError::UnaryOperator {
                     operator: operator.to_string(),
@@ -3210,7 +3218,12 @@ fn get_binary_operator_token(
         | BinOp::Star(token)
         | BinOp::TildeEqual(token)
         | BinOp::TwoDots(token)
-        | BinOp::TwoEqual(token) => Ok(token),
+        | BinOp::TwoEqual(token)
+        | BinOp::Ampersand(token)
+        | BinOp::Pipe(token)
+        | BinOp::Tilde(token)
+        | BinOp::DoubleLessThan(token)
+        | BinOp::DoubleGreaterThan(token) => Ok(token),
         _ => Err(ConvertError::BinaryOperator {
             operator: operator.to_string(),
         }),
@@ -3223,7 +3236,7 @@ fn get_unary_operator_token(
     use ast::UnOp;
 
     match operator {
-        UnOp::Minus(token) | UnOp::Not(token) | UnOp::Hash(token) => Ok(token),
+        UnOp::Minus(token) | UnOp::Not(token) | UnOp::Hash(token) | UnOp::Tilde(token) => Ok(token),
         _ => Err(ConvertError::UnaryOperator {
             operator: operator.to_string(),
         }),
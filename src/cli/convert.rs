@@ -1,4 +1,5 @@
-use crate::cli::{CommandResult, GlobalOptions};
+use crate::cli::utils::report_file_error;
+use crate::cli::{CommandResult, GlobalOptions, OutputFormat};
 
 use anstyle::Style;
 use clap::Args;
@@ -41,9 +42,12 @@ impl FromStr for DataFormat {
     }
 }
 
-pub fn run(options: &Options, _: &GlobalOptions) -> CommandResult {
+pub fn run(options: &Options, global: &GlobalOptions) -> CommandResult {
     convert_data(options).map_err(|err| {
-        eprintln!("an error happened: {}", err);
+        match global.get_output_format() {
+            OutputFormat::Human => eprintln!("an error happened: {}", err),
+            OutputFormat::Json => report_file_error(&err, &options.input, OutputFormat::Json),
+        }
         CliError::new(1)
     })
 }
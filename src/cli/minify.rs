@@ -1,5 +1,5 @@
 use crate::cli::error::CliError;
-use crate::cli::utils::report_process;
+use crate::cli::utils::{report_error, report_process};
 use crate::cli::{CommandResult, GlobalOptions};
 
 use clap::Args;
@@ -18,9 +18,11 @@ pub struct Options {
     column_span: Option<usize>,
 }
 
-pub fn run(options: &Options, _global: &GlobalOptions) -> CommandResult {
+pub fn run(options: &Options, global: &GlobalOptions) -> CommandResult {
     log::debug!("running `minify`: {:?}", options);
 
+    let output_format = global.get_output_format();
+
     let resources = Resources::from_file_system();
     let process_options = darklua_core::Options::new(&options.input_path)
         .with_output(&options.output_path)
@@ -36,9 +38,15 @@ pub fn run(options: &Options, _global: &GlobalOptions) -> CommandResult {
     let process_start_time = Instant::now();
 
     let result = darklua_core::process(&resources, process_options).map_err(|err| {
-        log::error!("{}", err);
+        report_error(&err, output_format);
         CliError::new(1)
     })?;
 
-    report_process("minified", &result, process_start_time.elapsed()).map_err(|_| CliError::new(1))
+    report_process(
+        "minified",
+        &result,
+        process_start_time.elapsed(),
+        output_format,
+    )
+    .map_err(|_| CliError::new(1))
 }
@@ -1,9 +1,13 @@
 pub mod convert;
 pub mod error;
 pub mod minify;
+pub mod parse;
 pub mod process;
+pub mod serve;
 pub mod utils;
 
+use std::str::FromStr;
+
 use clap::{Args, Parser, Subcommand};
 use log::LevelFilter;
 
@@ -16,9 +20,39 @@ pub struct GlobalOptions {
     /// Sets verbosity level (can be specified multiple times)
     #[arg(long, short, global(true), action = clap::ArgAction::Count)]
     verbose: u8,
+    /// Choose how errors are reported ('human' or 'json')
+    #[arg(long, global(true), default_value = "human")]
+    output_format: OutputFormat,
+}
+
+/// The format used to report the errors of a command.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Human,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "human" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "output format '{}' does not exist! (possible options are: 'human' or 'json'",
+                format
+            )),
+        }
+    }
 }
 
 impl GlobalOptions {
+    pub fn get_output_format(&self) -> OutputFormat {
+        self.output_format
+    }
+
     pub fn get_log_level_filter(&self) -> LevelFilter {
         match self.verbose {
             0 => LevelFilter::Warn,
@@ -41,6 +75,16 @@ pub enum Command {
     Process(process::Options),
     /// Convert a data file [json, json5, yaml, toml] into a Lua file
     Convert(convert::Options),
+    /// Parse a lua file and print its syntax tree
+    ///
+    /// The syntax tree contains the nodes used by darklua to represent Lua code.
+    Parse(parse::Options),
+    /// Process Lua code sent through a JSON-RPC interface over stdio
+    ///
+    /// Messages use the base protocol of the language server protocol. The
+    /// syntax trees are kept in memory between requests, so processing the
+    /// same code again is faster.
+    Serve(serve::Options),
 }
 
 impl Command {
@@ -49,6 +93,8 @@ impl Command {
             Command::Minify(options) => minify::run(options, global_options),
             Command::Process(options) => process::run(options, global_options),
             Command::Convert(options) => convert::run(options, global_options),
+            Command::Parse(options) => parse::run(options, global_options),
+            Command::Serve(options) => serve::run(options, global_options),
         }
     }
 }
@@ -0,0 +1,85 @@
+use crate::cli::utils::report_error;
+use crate::cli::{CommandResult, GlobalOptions, OutputFormat};
+
+use clap::Args;
+use darklua_core::{DarkluaError, Parser, Resources};
+use std::{path::PathBuf, str::FromStr};
+
+use super::error::CliError;
+
+#[derive(Debug, Args)]
+pub struct Options {
+    /// Path to the lua file to parse
+    input: PathBuf,
+    /// Path where to write the syntax tree
+    output: Option<PathBuf>,
+    /// Format of the syntax tree ('json')
+    #[arg(short, long, default_value = "json")]
+    format: SyntaxTreeFormat,
+    /// Include the tokens of each node (with their positions, comments and whitespaces)
+    #[arg(long)]
+    preserve_tokens: bool,
+}
+
+#[derive(Debug, Copy, Clone)]
+enum SyntaxTreeFormat {
+    Json,
+}
+
+impl FromStr for SyntaxTreeFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "invalid syntax tree format '{}' (possible options are: 'json')",
+                format
+            )),
+        }
+    }
+}
+
+pub fn run(options: &Options, global: &GlobalOptions) -> CommandResult {
+    log::debug!("running `parse`: {:?}", options);
+
+    parse_file(options).map_err(|err| {
+        match global.get_output_format() {
+            OutputFormat::Human => eprintln!("an error happened: {}", err),
+            OutputFormat::Json => report_error(&err, OutputFormat::Json),
+        }
+        CliError::new(1)
+    })
+}
+
+fn parse_file(options: &Options) -> Result<(), DarkluaError> {
+    let resources = Resources::from_file_system();
+
+    let code = resources.get(&options.input).map_err(DarkluaError::from)?;
+
+    let parser = if options.preserve_tokens {
+        Parser::default().preserve_tokens()
+    } else {
+        Parser::default()
+    };
+
+    let block = parser
+        .parse(&code)
+        .map_err(|err| DarkluaError::parser_error(&options.input, err))?;
+
+    let syntax_tree = match options.format {
+        SyntaxTreeFormat::Json => serde_json::to_string_pretty(&block).map_err(|err| {
+            DarkluaError::custom(format!("unable to serialize syntax tree: {}", err))
+        })?,
+    };
+
+    if let Some(output) = &options.output {
+        resources
+            .write(output, &syntax_tree)
+            .map_err(DarkluaError::from)?;
+    } else {
+        println!("{}", syntax_tree);
+    }
+
+    Ok(())
+}
@@ -1,38 +1,79 @@
 use crate::cli::error::CliError;
-use crate::cli::utils::report_process;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::cli::utils::FileWatcher;
-use crate::cli::{CommandResult, GlobalOptions};
+use crate::cli::utils::{maybe_plural, report_error, report_errors, report_process};
+use crate::cli::{CommandResult, GlobalOptions, OutputFormat};
 
 use clap::Args;
-use darklua_core::{GeneratorParameters, Resources};
-use std::path::PathBuf;
+use darklua_core::{
+    GeneratorParameters, ProcessingReport, ResourceChange, Resources, SyntaxError, WorkerTree,
+};
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 use std::time::Instant;
 
 #[derive(Debug, Args, Clone)]
 pub struct Options {
-    /// Path to the lua file to process.
+    /// Path to the lua file to process (use `-` to read the code from stdin).
     pub(crate) input_path: PathBuf,
-    /// Where to output the result.
+    /// Where to output the result (use `-` to write the code to stdout).
     output_path: PathBuf,
     /// Choose a specific configuration file.
     #[arg(long, short, alias = "config-path")]
     pub(crate) config: Option<PathBuf>,
-    /// Choose how Lua code is formatted ('dense', 'readable' or 'retain_lines').
+    /// Choose how Lua code is formatted ('dense', 'readable', 'retain_lines' or 'minimal_diff').
     /// This will override the format given by the configuration file.
     #[arg(long)]
     format: Option<LuaFormat>,
     /// Watch files and directories for changes and automatically re-run
     #[arg(long, short)]
     watch: bool,
+    /// Write a source map next to each generated file (using the `.map` extension)
+    #[arg(long)]
+    sourcemap: bool,
+    /// Do not write any file and exit with an error if an output file would change
+    #[arg(long, conflicts_with = "watch")]
+    check: bool,
+    /// Print a diff of each output file that would change (requires `--check`)
+    #[arg(long, requires = "check")]
+    diff: bool,
+    /// Do not write any file and exit with an error if an output file does not match
+    /// its snapshot in the given directory
+    #[arg(long, value_name = "SNAPSHOT_DIR", conflicts_with_all = ["watch", "check"])]
+    verify: Option<PathBuf>,
+    /// Write the output files to the snapshot directory instead of verifying them
+    /// (requires `--verify`)
+    #[arg(long, requires = "verify")]
+    update_snapshots: bool,
+    /// Maximum number of files processed at the same time (defaults to the available parallelism)
+    #[arg(long, short)]
+    jobs: Option<usize>,
+    /// Print the metrics of each rule applied to each file ('json' or 'human')
+    #[arg(long)]
+    report: Option<ReportFormat>,
+    /// Print the syntax errors of all files as JSON, while still processing the files
+    /// that can be parsed
+    #[arg(long)]
+    continue_on_error: bool,
 }
 
+/// The path given to read the input from stdin or to write the output to stdout.
+const STANDARD_STREAM_PATH: &str = "-";
+/// The files used to hold the code from stdin and the code written to stdout.
+const STDIN_FILE_PATH: &str = "stdin.lua";
+const STDOUT_FILE_PATH: &str = "stdout.lua";
+
 #[derive(Debug, Copy, Clone)]
 enum LuaFormat {
     Dense,
     Readable,
     RetainLines,
+    MinimalDiff,
 }
 
 impl FromStr for LuaFormat {
@@ -44,50 +85,513 @@ impl FromStr for LuaFormat {
             "readable" => Ok(Self::Readable),
             // keep "retain-lines" for back-compatibility
             "retain_lines" | "retain-lines" => Ok(Self::RetainLines),
+            "minimal_diff" | "minimal-diff" => Ok(Self::MinimalDiff),
+            _ => Err(format!(
+                "format '{}' does not exist! (possible options are: 'dense', 'readable', 'retain_lines' or 'minimal_diff'",
+                format
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum ReportFormat {
+    Json,
+    Human,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Self::Json),
+            "human" => Ok(Self::Human),
             _ => Err(format!(
-                "format '{}' does not exist! (possible options are: 'dense', 'readable' or 'retain_lines'",
+                "report format '{}' does not exist! (possible options are: 'json' or 'human'",
                 format
             )),
         }
     }
 }
 
-fn process(resources: Resources, process_options: darklua_core::Options) -> CommandResult {
+impl ReportFormat {
+    fn format(&self, report: &ProcessingReport) -> String {
+        match self {
+            Self::Json => format!("{}\n", report.to_json()),
+            Self::Human => report.to_string(),
+        }
+    }
+}
+
+fn process(resources: Resources, options: &Options, output_format: OutputFormat) -> CommandResult {
+    let process_start_time = Instant::now();
+
+    let result =
+        darklua_core::process(&resources, options.get_process_options()).map_err(|err| {
+            report_error(&err, output_format);
+            CliError::new(1)
+        })?;
+
+    options.print_report(&result);
+    options.print_syntax_errors(&result);
+
+    report_process(
+        "processed",
+        &result,
+        process_start_time.elapsed(),
+        output_format,
+    )
+    .map_err(|_| CliError::new(1))
+}
+
+fn check(options: &Options, output_format: OutputFormat) -> CommandResult {
+    let resources = Resources::from_file_system_dry_run();
+
+    process(resources.clone(), options, output_format)?;
+
+    let changes = resources.collect_changes();
+
+    if changes.is_empty() {
+        println!("no output file would change");
+        return Ok(());
+    }
+
+    for change in changes.iter() {
+        if options.diff {
+            print!("{}", format_diff(change));
+        } else {
+            println!("would change `{}`", change.path().display());
+        }
+    }
+
+    let change_count = changes.len();
+    eprintln!(
+        "{} output file{} would change",
+        change_count,
+        maybe_plural(change_count)
+    );
+
+    Err(CliError::new(1))
+}
+
+fn format_diff(change: &ResourceChange) -> String {
+    let path = change.path().display().to_string();
+
+    unified_diff(&path, change.original_content(), &path, change.content())
+}
+
+/// Formats a unified diff from the original content (or `/dev/null` when there is no
+/// original content) to the new content.
+fn unified_diff(
+    original_label: &str,
+    original: Option<&str>,
+    label: &str,
+    content: &str,
+) -> String {
+    TextDiff::from_lines(original.unwrap_or_default(), content)
+        .unified_diff()
+        .header(
+            if original.is_some() {
+                original_label
+            } else {
+                "/dev/null"
+            },
+            label,
+        )
+        .missing_newline_hint(false)
+        .to_string()
+}
+
+/// An output file compared with its snapshot.
+struct SnapshotComparison {
+    output_path: PathBuf,
+    snapshot_path: PathBuf,
+    snapshot: Option<String>,
+    content: String,
+}
+
+impl SnapshotComparison {
+    fn format_diff(&self) -> String {
+        unified_diff(
+            &self.snapshot_path.display().to_string(),
+            self.snapshot.as_deref(),
+            &self.output_path.display().to_string(),
+            &self.content,
+        )
+    }
+
+    fn format_summary(&self) -> String {
+        let path = self.output_path.display();
+
+        match self.snapshot.as_deref() {
+            Some(snapshot) => {
+                let (mut added, mut removed) = (0, 0);
+                for change in TextDiff::from_lines(snapshot, &self.content).iter_all_changes() {
+                    match change.tag() {
+                        ChangeTag::Insert => added += 1,
+                        ChangeTag::Delete => removed += 1,
+                        ChangeTag::Equal => {}
+                    }
+                }
+                format!(
+                    "`{}` does not match its snapshot ({} line{} added, {} line{} removed)",
+                    path,
+                    added,
+                    maybe_plural(added),
+                    removed,
+                    maybe_plural(removed)
+                )
+            }
+            None => format!("`{}` has no snapshot", path),
+        }
+    }
+}
+
+/// Returns the path of an output file relative to the output path given to the
+/// command. When the output path is a file, the file name is used.
+fn relative_output_path<'a>(output_root: &Path, path: &'a Path) -> &'a Path {
+    path.strip_prefix(output_root)
+        .ok()
+        .filter(|relative| !relative.as_os_str().is_empty())
+        .or_else(|| path.file_name().map(Path::new))
+        .unwrap_or(path)
+}
+
+fn verify(options: &Options, snapshot_dir: &Path, output_format: OutputFormat) -> CommandResult {
+    let resources = Resources::from_file_system_dry_run();
+
+    process(resources.clone(), options, output_format)?;
+
+    let output_root: PathBuf = options
+        .output_path
+        .components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect();
+
+    let outputs = resources.memory_files();
+
+    let snapshot_paths: HashSet<PathBuf> = outputs
+        .keys()
+        .map(|path| snapshot_dir.join(relative_output_path(&output_root, path)))
+        .collect();
+
+    let mismatches: Vec<_> = outputs
+        .into_iter()
+        .filter_map(|(output_path, content)| {
+            let snapshot_path = snapshot_dir.join(relative_output_path(&output_root, &output_path));
+            let snapshot = fs::read_to_string(&snapshot_path).ok();
+
+            (snapshot.as_ref() != Some(&content)).then_some(SnapshotComparison {
+                output_path,
+                snapshot_path,
+                snapshot,
+                content,
+            })
+        })
+        .collect();
+
+    let mut obsolete_snapshots: Vec<_> = if snapshot_dir.exists() {
+        Resources::from_file_system()
+            .walk(snapshot_dir)
+            .filter(|path| !snapshot_paths.contains(path))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    obsolete_snapshots.sort();
+
+    if options.update_snapshots {
+        return update_snapshots(&mismatches, &obsolete_snapshots);
+    }
+
+    if mismatches.is_empty() && obsolete_snapshots.is_empty() {
+        let snapshot_count = snapshot_paths.len();
+        println!(
+            "{} snapshot{} verified",
+            snapshot_count,
+            maybe_plural(snapshot_count)
+        );
+        return Ok(());
+    }
+
+    for mismatch in mismatches.iter() {
+        println!("{}", mismatch.format_summary());
+        print!("{}", mismatch.format_diff());
+    }
+
+    for snapshot in obsolete_snapshots.iter() {
+        println!("snapshot `{}` has no output file", snapshot.display());
+    }
+
+    let failure_count = mismatches.len() + obsolete_snapshots.len();
+    eprintln!(
+        "{} snapshot{} did not match (run with `--update-snapshots` to record the current output)",
+        failure_count,
+        maybe_plural(failure_count)
+    );
+
+    Err(CliError::new(1))
+}
+
+fn update_snapshots(
+    mismatches: &[SnapshotComparison],
+    obsolete_snapshots: &[PathBuf],
+) -> CommandResult {
+    for mismatch in mismatches {
+        write_file(&mismatch.snapshot_path, &mismatch.content)?;
+    }
+
+    for snapshot in obsolete_snapshots {
+        fs::remove_file(snapshot).map_err(|err| {
+            log::error!("unable to remove `{}`: {}", snapshot.display(), err);
+            CliError::new(1)
+        })?;
+    }
+
+    let update_count = mismatches.len() + obsolete_snapshots.len();
+    if update_count == 0 {
+        println!("snapshots are up to date");
+    } else {
+        println!(
+            "updated {} snapshot{}",
+            update_count,
+            maybe_plural(update_count)
+        );
+    }
+
+    Ok(())
+}
+
+fn process_standard_streams(options: &Options, output_format: OutputFormat) -> CommandResult {
+    if options.watch || options.check || options.verify.is_some() {
+        log::error!(
+            "unable to use `--watch`, `--check` or `--verify` when reading from stdin or writing to stdout"
+        );
+        return Err(CliError::new(1));
+    }
+
+    let read_stdin = is_standard_stream(&options.input_path);
+    let write_stdout = is_standard_stream(&options.output_path);
+
+    if write_stdout && options.sourcemap {
+        log::error!("unable to write a source map when writing to stdout");
+        return Err(CliError::new(1));
+    }
+
+    // the files are read from the file system, but the written files are kept in
+    // memory so that they can be sent to stdout
+    let resources = Resources::from_file_system_dry_run();
+
+    let input_path = if read_stdin {
+        let mut code = String::new();
+        io::stdin().read_to_string(&mut code).map_err(|err| {
+            log::error!("unable to read stdin: {}", err);
+            CliError::new(1)
+        })?;
+        resources.write(STDIN_FILE_PATH, &code).map_err(|err| {
+            log::error!("{}", darklua_core::DarkluaError::from(err));
+            CliError::new(1)
+        })?;
+        Path::new(STDIN_FILE_PATH)
+    } else {
+        options.input_path.as_path()
+    };
+
+    if write_stdout && !resources.is_file(input_path).unwrap_or_default() {
+        log::error!(
+            "unable to write to stdout: `{}` is not a file",
+            input_path.display()
+        );
+        return Err(CliError::new(1));
+    }
+
+    let output_path = if write_stdout {
+        Path::new(STDOUT_FILE_PATH)
+    } else {
+        options.output_path.as_path()
+    };
+
     let process_start_time = Instant::now();
 
-    let result = darklua_core::process(&resources, process_options).map_err(|err| {
-        log::error!("{}", err);
+    let result = darklua_core::process(
+        &resources,
+        options.build_process_options(input_path, output_path),
+    )
+    .map_err(|err| {
+        report_error(&err, output_format);
         CliError::new(1)
     })?;
 
-    report_process("processed", &result, process_start_time.elapsed()).map_err(|_| CliError::new(1))
+    if write_stdout {
+        // the code is written to stdout, so the report goes to stderr
+        if let Some(format) = options.report {
+            eprint!("{}", format.format(&result.report()));
+        }
+        if options.continue_on_error {
+            eprint!("{}", format_syntax_errors(&result));
+        }
+
+        report_errors(&result, output_format).map_err(|_| CliError::new(1))?;
+
+        let code = resources.get(STDOUT_FILE_PATH).map_err(|err| {
+            log::error!("{}", darklua_core::DarkluaError::from(err));
+            CliError::new(1)
+        })?;
+
+        let mut stdout = io::stdout();
+        stdout
+            .write_all(code.as_bytes())
+            .and_then(|()| stdout.flush())
+            .map_err(|err| {
+                log::error!("unable to write to stdout: {}", err);
+                CliError::new(1)
+            })?;
+
+        Ok(())
+    } else {
+        for change in resources.collect_changes() {
+            write_file(change.path(), change.content())?;
+        }
+
+        options.print_report(&result);
+        options.print_syntax_errors(&result);
+
+        report_process(
+            "processed",
+            &result,
+            process_start_time.elapsed(),
+            output_format,
+        )
+        .map_err(|_| CliError::new(1))
+    }
+}
+
+fn write_file(path: &Path, content: &str) -> CommandResult {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).map_err(|err| {
+            log::error!("unable to create directory `{}`: {}", parent.display(), err);
+            CliError::new(1)
+        })?;
+    }
+
+    fs::write(path, content).map_err(|err| {
+        log::error!("unable to write `{}`: {}", path.display(), err);
+        CliError::new(1)
+    })
+}
+
+#[derive(Serialize)]
+struct FileSyntaxError {
+    file: String,
+    #[serde(flatten)]
+    error: SyntaxError,
+}
+
+#[derive(Serialize)]
+struct SyntaxErrorsReport {
+    syntax_errors: Vec<FileSyntaxError>,
+}
+
+/// Formats the syntax errors of every file that could not be parsed as a JSON document.
+fn format_syntax_errors(worker_tree: &WorkerTree) -> String {
+    let syntax_errors = worker_tree
+        .collect_errors()
+        .into_iter()
+        .filter_map(|error| error.as_parser_error())
+        .flat_map(|(path, parser_error)| {
+            let file = path.display().to_string();
+            parser_error
+                .syntax_errors()
+                .into_iter()
+                .map(move |error| FileSyntaxError {
+                    file: file.clone(),
+                    error,
+                })
+        })
+        .collect();
+
+    format!(
+        "{}\n",
+        serde_json::to_string_pretty(&SyntaxErrorsReport { syntax_errors })
+            .expect("syntax errors should serialize to json")
+    )
+}
+
+fn is_standard_stream(path: &Path) -> bool {
+    path == Path::new(STANDARD_STREAM_PATH)
 }
 
 impl Options {
     pub(crate) fn get_process_options(&self) -> darklua_core::Options {
-        let mut process_options =
-            darklua_core::Options::new(&self.input_path).with_output(&self.output_path);
+        self.build_process_options(&self.input_path, &self.output_path)
+    }
+
+    /// Prints the processing report when the `--report` option is used.
+    pub(crate) fn print_report(&self, worker_tree: &WorkerTree) {
+        if let Some(format) = self.report {
+            print!("{}", format.format(&worker_tree.report()));
+        }
+    }
+
+    /// Prints the syntax errors of all files when the `--continue-on-error` option is used.
+    pub(crate) fn print_syntax_errors(&self, worker_tree: &WorkerTree) {
+        if self.continue_on_error {
+            print!("{}", format_syntax_errors(worker_tree));
+        }
+    }
+
+    fn uses_standard_streams(&self) -> bool {
+        is_standard_stream(&self.input_path) || is_standard_stream(&self.output_path)
+    }
+
+    fn build_process_options(&self, input: &Path, output: &Path) -> darklua_core::Options {
+        let mut process_options = darklua_core::Options::new(input).with_output(output);
 
         if let Some(config) = self.config.as_ref() {
             process_options = process_options.with_configuration_at(config);
         }
 
+        if self.sourcemap {
+            process_options = process_options.with_source_map();
+        }
+
+        if self.report.is_some() {
+            process_options = process_options.with_report();
+        }
+
+        if let Some(jobs) = self.jobs {
+            process_options = process_options.with_jobs(jobs);
+        }
+
         if let Some(format) = self.format {
             process_options = process_options.with_generator_override(match format {
                 LuaFormat::Dense => GeneratorParameters::default_dense(),
                 LuaFormat::Readable => GeneratorParameters::default_readable(),
                 LuaFormat::RetainLines => GeneratorParameters::RetainLines,
+                LuaFormat::MinimalDiff => GeneratorParameters::default_minimal_diff(),
             })
         }
         process_options
     }
 }
 
-pub fn run(options: &Options, _global: &GlobalOptions) -> CommandResult {
+pub fn run(options: &Options, global: &GlobalOptions) -> CommandResult {
     log::debug!("running `process`: {:?}", options);
 
-    if cfg!(not(target_arch = "wasm32")) && options.watch {
-        let file_watcher = FileWatcher::new(options);
+    let output_format = global.get_output_format();
+
+    if options.uses_standard_streams() {
+        process_standard_streams(options, output_format)
+    } else if options.check {
+        check(options, output_format)
+    } else if let Some(snapshot_dir) = options.verify.as_ref() {
+        verify(options, snapshot_dir, output_format)
+    } else if cfg!(not(target_arch = "wasm32")) && options.watch {
+        let file_watcher = FileWatcher::new(options, output_format);
 
         file_watcher.start()?;
 
@@ -95,6 +599,6 @@ pub fn run(options: &Options, _global: &GlobalOptions) -> CommandResult {
     } else {
         let resources = Resources::from_file_system();
 
-        process(resources, options.get_process_options())
+        process(resources, options, output_format)
     }
 }
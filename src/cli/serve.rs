@@ -0,0 +1,278 @@
+use crate::cli::error::CliError;
+use crate::cli::{CommandResult, GlobalOptions};
+
+use clap::Args;
+use darklua_core::{Configuration, Diagnostic, ParseCache, Resources};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+#[derive(Debug, Args)]
+pub struct Options {
+    /// Choose the configuration file used by requests that do not provide a configuration.
+    #[arg(long, short)]
+    config: Option<PathBuf>,
+    /// The maximum number of syntax trees kept in memory between requests.
+    #[arg(long, default_value = "256")]
+    cache_size: usize,
+}
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProcessParams {
+    path: PathBuf,
+    code: String,
+    #[serde(default)]
+    config: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct ProcessResult {
+    code: Option<String>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug)]
+struct ResponseError {
+    code: i64,
+    message: String,
+}
+
+impl ResponseError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+enum Handled {
+    Respond(Value),
+    Stop(Option<Value>),
+    Ignore,
+}
+
+struct Server {
+    config: Option<PathBuf>,
+    parse_cache: ParseCache,
+}
+
+impl Server {
+    fn new(options: &Options) -> Self {
+        Self {
+            config: options.config.clone(),
+            parse_cache: ParseCache::new(options.cache_size),
+        }
+    }
+
+    /// Handles a JSON-RPC message and returns the response to send, if any.
+    fn handle_message(&self, message: &str) -> Handled {
+        let value: Value = match serde_json::from_str(message) {
+            Ok(value) => value,
+            Err(err) => {
+                return Handled::Respond(error_response(
+                    Value::Null,
+                    ResponseError::new(PARSE_ERROR, err.to_string()),
+                ))
+            }
+        };
+
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(err) => {
+                return Handled::Respond(error_response(
+                    Value::Null,
+                    ResponseError::new(INVALID_REQUEST, err.to_string()),
+                ))
+            }
+        };
+
+        log::debug!("received `{}` request", request.method);
+
+        let result = match request.method.as_str() {
+            "process" => self.process(request.params),
+            "shutdown" => {
+                return Handled::Stop(
+                    request
+                        .id
+                        .map(|id| json!({ "jsonrpc": "2.0", "id": id, "result": null })),
+                )
+            }
+            "exit" => return Handled::Stop(None),
+            method => Err(ResponseError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method `{}`", method),
+            )),
+        };
+
+        // requests without an id are notifications, which do not get a response
+        let Some(id) = request.id else {
+            return Handled::Ignore;
+        };
+
+        Handled::Respond(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(err) => error_response(id, err),
+        })
+    }
+
+    fn process(&self, params: Value) -> Result<Value, ResponseError> {
+        let params: ProcessParams = serde_json::from_value(params)
+            .map_err(|err| ResponseError::new(INVALID_PARAMS, err.to_string()))?;
+
+        let configuration = params
+            .config
+            .map(serde_json::from_value::<Configuration>)
+            .transpose()
+            .map_err(|err| {
+                ResponseError::new(INVALID_PARAMS, format!("invalid configuration: {}", err))
+            })?;
+
+        // files are read from the file system, but the buffer and the generated code
+        // are only kept in memory
+        let resources = Resources::from_file_system_dry_run();
+        resources
+            .write(&params.path, &params.code)
+            .map_err(|err| ResponseError::new(INVALID_PARAMS, err.to_string()))?;
+
+        let mut process_options =
+            darklua_core::Options::new(&params.path).with_parse_cache(self.parse_cache.clone());
+
+        if let Some(configuration) = configuration {
+            process_options = process_options.with_configuration(configuration);
+        } else if let Some(config) = self.config.as_ref() {
+            process_options = process_options.with_configuration_at(config);
+        }
+
+        let diagnostics: Vec<Diagnostic> = match darklua_core::process(&resources, process_options)
+        {
+            Ok(worker_tree) => worker_tree
+                .collect_errors()
+                .into_iter()
+                .flat_map(|error| error.diagnostics())
+                .collect(),
+            Err(err) => err.diagnostics(),
+        };
+
+        let code = if diagnostics.is_empty() {
+            Some(
+                resources
+                    .get(&params.path)
+                    .map_err(|err| ResponseError::new(INVALID_PARAMS, err.to_string()))?,
+            )
+        } else {
+            None
+        };
+
+        Ok(serde_json::to_value(ProcessResult { code, diagnostics })
+            .expect("process result should serialize to json"))
+    }
+}
+
+fn error_response(id: Value, error: ResponseError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+/// Reads a message using the base protocol of the language server protocol: a
+/// `Content-Length` header, an empty line and the content. Returns `None` when the
+/// input is closed.
+fn read_message(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut content_length = None;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = line.trim_end();
+
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>().map_err(|err| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("invalid content length: {}", err),
+                    )
+                })?);
+            }
+        }
+    }
+
+    let mut content = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut content)?;
+
+    String::from_utf8(content)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+pub fn run(options: &Options, _global: &GlobalOptions) -> CommandResult {
+    log::debug!("running `serve`: {:?}", options);
+
+    let server = Server::new(options);
+
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let stdout = io::stdout();
+    let mut writer = stdout.lock();
+
+    let io_error = |err: io::Error| {
+        log::error!("unable to communicate with the client: {}", err);
+        CliError::new(1)
+    };
+
+    while let Some(message) = read_message(&mut reader).map_err(io_error)? {
+        match server.handle_message(&message) {
+            Handled::Respond(response) => {
+                write_message(&mut writer, &response).map_err(io_error)?
+            }
+            Handled::Stop(response) => {
+                if let Some(response) = response {
+                    write_message(&mut writer, &response).map_err(io_error)?;
+                }
+                break;
+            }
+            Handled::Ignore => {}
+        }
+    }
+
+    Ok(())
+}
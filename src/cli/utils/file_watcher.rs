@@ -12,9 +12,11 @@ use darklua_core::{Options, Resources, WorkerTree};
 use notify::{EventKind, RecursiveMode};
 use notify_debouncer_full::{new_debouncer, DebounceEventResult, DebouncedEvent};
 
-use crate::cli::{error::CliError, process::Options as ProcessOptions, CommandResult};
+use crate::cli::{
+    error::CliError, process::Options as ProcessOptions, CommandResult, OutputFormat,
+};
 
-use super::report_process;
+use super::{report_error, report_process};
 
 const FILE_WATCHING_DEBOUNCE_DURATION_MILLIS: u64 = 400;
 const DEFAULT_CONFIG_PATHS: [&str; 2] = [".darklua.json", ".darklua.json5"];
@@ -32,13 +34,14 @@ pub struct FileWatcher {
     receiver: Option<Receiver<WatcherSignal>>,
     worker_tree: Option<WorkerTree>,
     process_option: ProcessOptions,
+    output_format: OutputFormat,
     extra_file_watch: HashSet<PathBuf>,
     links_file_watch: HashSet<(PathBuf, PathBuf)>,
     current_working_path: Option<PathBuf>,
 }
 
 impl FileWatcher {
-    pub fn new(process_option: &ProcessOptions) -> Self {
+    pub fn new(process_option: &ProcessOptions, output_format: OutputFormat) -> Self {
         let (sender, receiver) = mpsc::channel();
 
         Self {
@@ -48,6 +51,7 @@ impl FileWatcher {
             receiver: Some(receiver),
             worker_tree: None,
             process_option: process_option.clone(),
+            output_format,
             extra_file_watch: Default::default(),
             links_file_watch: Default::default(),
             current_working_path: env::current_dir().ok(),
@@ -60,16 +64,29 @@ impl FileWatcher {
         let process_start_time = Instant::now();
 
         if let Some(worker_tree) = self.worker_tree.as_mut() {
-            log_darklua_error(worker_tree.process(&self.resources, options), || ());
+            log_darklua_error(
+                worker_tree.process(&self.resources, options),
+                self.output_format,
+                || (),
+            );
         } else {
             self.worker_tree = log_darklua_error(
                 darklua_core::process(&self.resources, options).map(Some),
+                self.output_format,
                 || None,
             );
         }
 
         if let Some(worker_tree) = self.worker_tree.as_mut() {
-            report_process("processed", worker_tree, process_start_time.elapsed()).ok();
+            self.process_option.print_report(worker_tree);
+            self.process_option.print_syntax_errors(worker_tree);
+            report_process(
+                "processed",
+                worker_tree,
+                process_start_time.elapsed(),
+                self.output_format,
+            )
+            .ok();
         }
 
         self.update_extra_file_watch();
@@ -320,7 +337,11 @@ impl FileWatcher {
     fn worker_collect_work(&mut self) {
         let options = self.build_options();
         if let Some(worker_tree) = self.worker_tree.as_mut() {
-            log_darklua_error(worker_tree.collect_work(&self.resources, &options), || ());
+            log_darklua_error(
+                worker_tree.collect_work(&self.resources, &options),
+                self.output_format,
+                || (),
+            );
         }
     }
 
@@ -445,11 +466,10 @@ fn iter_all_links(location: PathBuf) -> impl Iterator<Item = PathBuf> {
 
 fn log_darklua_error<T>(
     result: Result<T, darklua_core::DarkluaError>,
+    output_format: OutputFormat,
     else_result: impl Fn() -> T,
 ) -> T {
     result
-        .inspect_err(|err| {
-            log::error!("{}", err);
-        })
+        .inspect_err(|err| report_error(err, output_format))
         .unwrap_or_else(|_| else_result())
 }
@@ -1,11 +1,14 @@
 #[cfg(not(target_arch = "wasm32"))]
 mod file_watcher;
 
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 
-use darklua_core::WorkerTree;
+use darklua_core::{DarkluaError, Diagnostic, WorkerTree};
 #[cfg(not(target_arch = "wasm32"))]
 pub use file_watcher::FileWatcher;
+use serde::Serialize;
+
+use crate::cli::OutputFormat;
 
 pub fn maybe_plural(count: usize) -> &'static str {
     if count > 1 {
@@ -19,7 +22,14 @@ pub fn report_process(
     command: &'static str,
     worker_tree: &WorkerTree,
     duration: Duration,
+    output_format: OutputFormat,
 ) -> Result<(), ()> {
+    if output_format == OutputFormat::Json {
+        let errors = worker_tree.collect_errors();
+        println!("{}", format_diagnostics(&errors));
+        return if errors.is_empty() { Ok(()) } else { Err(()) };
+    }
+
     let process_duration = durationfmt::to_string(duration);
 
     let success_count = worker_tree.success_count();
@@ -32,6 +42,52 @@ pub fn report_process(
         process_duration
     );
 
+    print_errors(worker_tree, success_count > 0)
+}
+
+/// Prints the errors of a process without printing the number of processed files.
+/// Since this is used when the code is written to stdout, the JSON diagnostics are
+/// written to stderr.
+pub fn report_errors(worker_tree: &WorkerTree, output_format: OutputFormat) -> Result<(), ()> {
+    match output_format {
+        OutputFormat::Human => print_errors(worker_tree, false),
+        OutputFormat::Json => {
+            let errors = worker_tree.collect_errors();
+            if errors.is_empty() {
+                Ok(())
+            } else {
+                eprintln!("{}", format_diagnostics(&errors));
+                Err(())
+            }
+        }
+    }
+}
+
+/// Reports an error that stopped a command.
+pub fn report_error(error: &DarkluaError, output_format: OutputFormat) {
+    match output_format {
+        OutputFormat::Human => log::error!("{}", error),
+        OutputFormat::Json => println!("{}", format_diagnostics(&[error])),
+    }
+}
+
+/// Reports an error that stopped a command working on a single file. The JSON
+/// diagnostics that are not related to another file are reported on the given file.
+pub fn report_file_error(error: &DarkluaError, path: &Path, output_format: OutputFormat) {
+    match output_format {
+        OutputFormat::Human => log::error!("{}", error),
+        OutputFormat::Json => {
+            let diagnostics = error
+                .diagnostics()
+                .into_iter()
+                .map(|diagnostic| diagnostic.with_default_file(path))
+                .collect();
+            println!("{}", serialize_diagnostics(diagnostics));
+        }
+    }
+}
+
+fn print_errors(worker_tree: &WorkerTree, has_success: bool) -> Result<(), ()> {
     let errors = worker_tree.collect_errors();
 
     if errors.is_empty() {
@@ -40,7 +96,7 @@ pub fn report_process(
         let error_count = errors.len();
         eprintln!(
             "{}{} error{} happened:",
-            if success_count > 0 { "but " } else { "" },
+            if has_success { "but " } else { "" },
             error_count,
             maybe_plural(error_count)
         );
@@ -53,6 +109,26 @@ pub fn report_process(
     }
 }
 
+#[derive(Serialize)]
+struct DiagnosticsReport {
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// Formats the diagnostics of the given errors as a single line JSON document.
+fn format_diagnostics(errors: &[&DarkluaError]) -> String {
+    serialize_diagnostics(
+        errors
+            .iter()
+            .flat_map(|error| error.diagnostics())
+            .collect(),
+    )
+}
+
+fn serialize_diagnostics(diagnostics: Vec<Diagnostic>) -> String {
+    serde_json::to_string(&DiagnosticsReport { diagnostics })
+        .expect("diagnostics should serialize to json")
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1,18 +1,30 @@
 use std::{
     collections::HashSet,
+    fmt,
     path::{Path, PathBuf},
     str::FromStr,
 };
 
 use serde::{Deserialize, Serialize};
+use wax::Pattern;
 
 use crate::{
-    generator::{DenseLuaGenerator, LuaGenerator, ReadableLuaGenerator, TokenBasedLuaGenerator},
+    generator::{
+        DenseLuaGenerator, KeepComments, LuaGenerator, ReadableLuaGenerator, SourceMap,
+        TokenBasedLuaGenerator,
+    },
     nodes::Block,
     rules::{
         bundle::{BundleRequireMode, Bundler},
-        get_default_rules, Rule,
+        get_default_rules, ComputeExpression, ConvertIndexToField, ConvertLuauNumber,
+        FilterAfterEarlyReturn, GroupLocalAssignment, RemoveAssertions, RemoveComments,
+        RemoveCompoundAssignment, RemoveContinue, RemoveDebugProfiling, RemoveEmptyDo,
+        RemoveFloorDivision, RemoveFunctionCallParens, RemoveGeneralizedIteration,
+        RemoveIfExpression, RemoveInterpolatedString, RemoveMethodDefinition, RemoveNilDeclaration,
+        RemoveSpaces, RemoveTypes, RemoveUnusedIfBranch, RemoveUnusedVariable, RemoveUnusedWhile,
+        RenameVariables, Rule,
     },
+    utils::normalize_path,
     Parser,
 };
 
@@ -23,25 +35,239 @@ fn get_default_column_span() -> usize {
 }
 
 /// Configuration for processing files (rules, generator, bundling).
+///
+/// When deserialized, a configuration can define a `preset` that expands into a list of
+/// rules (see [`RulePreset`]). The rules given in the `rules` field are then merged with the
+/// rules of the preset: a rule replaces the preset rule with the same name, or it is added
+/// after the preset rules.
+///
+/// The `overrides` field can define different rules for some files (see [`RuleOverride`]).
 #[derive(Serialize, Deserialize)]
-#[serde(deny_unknown_fields)]
+#[serde(from = "ConfigurationData")]
 pub struct Configuration {
-    #[serde(alias = "process", default = "get_default_rules")]
     rules: Vec<Box<dyn Rule>>,
-    #[serde(default, deserialize_with = "crate::utils::string_or_struct")]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    overrides: Vec<RuleOverride>,
     generator: GeneratorParameters,
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "LuaTarget::is_luau")]
+    target: LuaTarget,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    bitwise_operators: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     bundle: Option<BundleConfiguration>,
-    #[serde(default, skip)]
+    #[serde(skip)]
     location: Option<PathBuf>,
 }
 
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigurationData {
+    #[serde(default)]
+    preset: Option<RulePreset>,
+    #[serde(alias = "process", default)]
+    rules: Option<Vec<Box<dyn Rule>>>,
+    #[serde(default)]
+    overrides: Vec<RuleOverride>,
+    #[serde(default, deserialize_with = "crate::utils::string_or_struct")]
+    generator: GeneratorParameters,
+    #[serde(default)]
+    target: LuaTarget,
+    #[serde(default)]
+    bitwise_operators: bool,
+    #[serde(default)]
+    bundle: Option<BundleConfiguration>,
+}
+
+impl From<ConfigurationData> for Configuration {
+    fn from(data: ConfigurationData) -> Self {
+        let rules = match (data.preset, data.rules) {
+            (Some(preset), Some(rules)) => merge_rules(preset.rules(), rules),
+            (Some(preset), None) => preset.rules(),
+            (None, Some(rules)) => rules,
+            (None, None) => get_default_rules(),
+        };
+
+        Self {
+            rules,
+            overrides: data.overrides,
+            generator: data.generator,
+            target: data.target,
+            bitwise_operators: data.bitwise_operators,
+            bundle: data.bundle,
+            location: None,
+        }
+    }
+}
+
+fn add_required_rules(rules: &mut Vec<Box<dyn Rule>>, target: LuaTarget) {
+    for rule in target.required_rules() {
+        if rules
+            .iter()
+            .all(|existing| existing.get_name() != rule.get_name())
+        {
+            log::debug!(
+                "add rule `{}` required by target `{}`",
+                rule.get_name(),
+                target
+            );
+            rules.push(rule);
+        }
+    }
+}
+
+fn merge_rules(
+    mut preset_rules: Vec<Box<dyn Rule>>,
+    rules: Vec<Box<dyn Rule>>,
+) -> Vec<Box<dyn Rule>> {
+    let preset_len = preset_rules.len();
+    let mut replaced = vec![false; preset_len];
+
+    for rule in rules {
+        let replace_index = (0..preset_len)
+            .find(|index| !replaced[*index] && preset_rules[*index].get_name() == rule.get_name());
+
+        if let Some(index) = replace_index {
+            replaced[index] = true;
+            preset_rules[index] = rule;
+        } else {
+            preset_rules.push(rule);
+        }
+    }
+
+    preset_rules
+}
+
+const EXTENDS_FIELD: &str = "extends";
+const RULES_FIELD: &str = "rules";
+const RULES_FIELD_ALIAS: &str = "process";
+const OVERRIDES_FIELD: &str = "overrides";
+const BUNDLE_FIELD: &str = "bundle";
+
+/// Removes the `extends` field from a configuration value and returns the paths
+/// of the configuration files it lists.
+pub(crate) fn take_extended_paths(value: &mut serde_json::Value) -> Result<Vec<String>, String> {
+    let extends = match value.as_object_mut() {
+        Some(object) => object.remove(EXTENDS_FIELD),
+        None => None,
+    };
+
+    match extends {
+        None => Ok(Vec::new()),
+        Some(serde_json::Value::String(path)) => Ok(vec![path]),
+        Some(serde_json::Value::Array(paths)) => paths
+            .into_iter()
+            .map(|path| match path {
+                serde_json::Value::String(path) => Ok(path),
+                _ => Err(format!(
+                    "expected `{}` to contain only paths to configuration files",
+                    EXTENDS_FIELD
+                )),
+            })
+            .collect(),
+        Some(_) => Err(format!(
+            "expected `{}` to be a path or a list of paths to configuration files",
+            EXTENDS_FIELD
+        )),
+    }
+}
+
+/// Merges a configuration value into the value of the configuration it extends:
+/// - the rules are merged like rules merged into a preset: a rule replaces the
+///   first base rule with the same name, or it is added after the base rules
+/// - the overrides are placed before the base overrides, so they are matched first
+/// - the bundle fields replace the fields of the base bundle configuration
+/// - any other field replaces the base field
+pub(crate) fn extend_configuration_value(
+    base: serde_json::Value,
+    value: serde_json::Value,
+) -> serde_json::Value {
+    let (mut base, value) = match (base, value) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(value)) => (base, value),
+        (_, value) => return value,
+    };
+
+    if let Some(rules) = base.remove(RULES_FIELD_ALIAS) {
+        base.insert(RULES_FIELD.to_owned(), rules);
+    }
+
+    for (key, value) in value {
+        let key = if key == RULES_FIELD_ALIAS {
+            RULES_FIELD.to_owned()
+        } else {
+            key
+        };
+
+        let merged = match (key.as_str(), base.remove(&key)) {
+            (RULES_FIELD, Some(serde_json::Value::Array(base_rules))) => match value {
+                serde_json::Value::Array(rules) => {
+                    serde_json::Value::Array(merge_rule_values(base_rules, rules))
+                }
+                value => value,
+            },
+            (OVERRIDES_FIELD, Some(serde_json::Value::Array(base_overrides))) => match value {
+                serde_json::Value::Array(mut overrides) => {
+                    overrides.extend(base_overrides);
+                    serde_json::Value::Array(overrides)
+                }
+                value => value,
+            },
+            (BUNDLE_FIELD, Some(serde_json::Value::Object(mut base_bundle))) => match value {
+                serde_json::Value::Object(bundle) => {
+                    base_bundle.extend(bundle);
+                    serde_json::Value::Object(base_bundle)
+                }
+                value => value,
+            },
+            _ => value,
+        };
+
+        base.insert(key, merged);
+    }
+
+    serde_json::Value::Object(base)
+}
+
+fn get_rule_value_name(rule: &serde_json::Value) -> Option<&str> {
+    match rule {
+        serde_json::Value::String(name) => Some(name),
+        serde_json::Value::Object(object) => object.get("rule").and_then(serde_json::Value::as_str),
+        _ => None,
+    }
+}
+
+fn merge_rule_values(
+    mut base_rules: Vec<serde_json::Value>,
+    rules: Vec<serde_json::Value>,
+) -> Vec<serde_json::Value> {
+    let base_len = base_rules.len();
+    let mut replaced = vec![false; base_len];
+
+    for rule in rules {
+        let name = get_rule_value_name(&rule);
+        let replace_index = (0..base_len).find(|index| {
+            !replaced[*index] && name.is_some() && get_rule_value_name(&base_rules[*index]) == name
+        });
+
+        if let Some(index) = replace_index {
+            replaced[index] = true;
+            base_rules[index] = rule;
+        } else {
+            base_rules.push(rule);
+        }
+    }
+
+    base_rules
+}
+
 impl Configuration {
     /// Creates a configuration object without any rules and with the default generator.
     pub fn empty() -> Self {
         Self {
             rules: Vec::new(),
+            overrides: Vec::new(),
             generator: GeneratorParameters::default(),
+            target: LuaTarget::default(),
+            bitwise_operators: false,
             bundle: None,
             location: None,
         }
@@ -60,6 +286,21 @@ impl Configuration {
         self.generator = generator;
     }
 
+    /// Sets the Lua dialect that the generated code must be compatible with.
+    #[inline]
+    pub fn with_target(mut self, target: LuaTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Enables parsing the bitwise operators of Lua 5.3 (`&`, `|`, `~`, `<<` and `>>`)
+    /// in the processed files.
+    #[inline]
+    pub fn with_bitwise_operators(mut self) -> Self {
+        self.bitwise_operators = true;
+        self
+    }
+
     /// Adds a rule to this configuration.
     #[inline]
     pub fn with_rule(mut self, rule: impl Into<Box<dyn Rule>>) -> Self {
@@ -67,6 +308,15 @@ impl Configuration {
         self
     }
 
+    /// Adds a rule override to this configuration. Files matching the override
+    /// are processed with the rules of the first matching override instead of
+    /// the rules of the configuration.
+    #[inline]
+    pub fn with_override(mut self, rule_override: RuleOverride) -> Self {
+        self.overrides.push(rule_override);
+        self
+    }
+
     /// Sets the bundle configuration for this configuration.
     #[inline]
     pub fn with_bundle_configuration(mut self, configuration: BundleConfiguration) -> Self {
@@ -87,14 +337,84 @@ impl Configuration {
         self.rules.push(rule.into());
     }
 
+    /// Adds the rules required by the target that are missing from the rule list.
+    pub(crate) fn add_target_rules(&mut self) {
+        let target = self.target;
+        add_required_rules(&mut self.rules, target);
+
+        for rule_override in self.overrides.iter_mut() {
+            add_required_rules(&mut rule_override.rules, target);
+        }
+    }
+
+    #[cfg(test)]
     #[inline]
     pub(crate) fn rules<'a, 'b: 'a>(&'b self) -> impl Iterator<Item = &'a dyn Rule> {
         self.rules.iter().map(AsRef::as_ref)
     }
 
+    /// Returns the rules to apply on the given file: the rules of the first override
+    /// matching the file, or the rules of the configuration when no override matches.
+    pub(crate) fn rules_for<'a, 'b: 'a>(
+        &'b self,
+        path: &Path,
+    ) -> impl Iterator<Item = &'a dyn Rule> {
+        self.get_rule_list(path).iter().map(AsRef::as_ref)
+    }
+
+    /// Returns the rules of the configuration and the rules of all its overrides.
+    pub(crate) fn all_rules<'a, 'b: 'a>(&'b self) -> impl Iterator<Item = &'a dyn Rule> {
+        self.rules
+            .iter()
+            .chain(
+                self.overrides
+                    .iter()
+                    .flat_map(|rule_override| rule_override.rules.iter()),
+            )
+            .map(AsRef::as_ref)
+    }
+
+    #[inline]
+    pub(crate) fn rules_len_for(&self, path: &Path) -> usize {
+        self.get_rule_list(path).len()
+    }
+
+    fn get_rule_list(&self, path: &Path) -> &[Box<dyn Rule>] {
+        if self.overrides.is_empty() {
+            return &self.rules;
+        }
+
+        let path = normalize_path(path);
+        // globs are relative to the configuration file location
+        let relative_path = self
+            .location
+            .as_ref()
+            .and_then(|location| path.strip_prefix(normalize_path(location)).ok())
+            .unwrap_or(&path);
+
+        self.overrides
+            .iter()
+            .find(|rule_override| rule_override.is_match(relative_path))
+            .map(|rule_override| {
+                log::trace!(
+                    "use rules from override `{}` for `{}`",
+                    rule_override.include.join("`, `"),
+                    path.display()
+                );
+                rule_override.rules.as_slice()
+            })
+            .unwrap_or(&self.rules)
+    }
+
     #[inline]
     pub(crate) fn build_parser(&self) -> Parser {
-        self.generator.build_parser()
+        let parser = self.generator.build_parser();
+
+        if self.bitwise_operators {
+            parser.with_bitwise_operators()
+        } else {
+            parser
+        }
     }
 
     #[inline]
@@ -102,6 +422,15 @@ impl Configuration {
         self.generator.generate_lua(block, code)
     }
 
+    #[inline]
+    pub(crate) fn generate_lua_with_source_map(
+        &self,
+        block: &Block,
+        code: &str,
+    ) -> (String, SourceMap) {
+        self.generator.generate_lua_with_source_map(block, code)
+    }
+
     pub(crate) fn bundle(&self) -> Option<Bundler> {
         if let Some(bundle_config) = self.bundle.as_ref() {
             let bundler = Bundler::new(
@@ -109,7 +438,10 @@ impl Configuration {
                 bundle_config.require_mode().clone(),
                 bundle_config.excludes(),
             )
-            .with_modules_identifier(bundle_config.modules_identifier());
+            .with_modules_identifier(bundle_config.modules_identifier())
+            .with_tree_shaking(bundle_config.is_tree_shaking())
+            .with_require_polyfill(bundle_config.is_require_polyfill())
+            .with_chunks(bundle_config.chunk_size(), bundle_config.lazy_modules());
             Some(bundler)
         } else {
             None
@@ -117,8 +449,8 @@ impl Configuration {
     }
 
     #[inline]
-    pub(crate) fn rules_len(&self) -> usize {
-        self.rules.len()
+    pub(crate) fn target(&self) -> LuaTarget {
+        self.target
     }
 
     #[inline]
@@ -131,7 +463,10 @@ impl Default for Configuration {
     fn default() -> Self {
         Self {
             rules: get_default_rules(),
+            overrides: Vec::new(),
             generator: Default::default(),
+            target: Default::default(),
+            bitwise_operators: false,
             bundle: None,
             location: None,
         }
@@ -142,6 +477,8 @@ impl std::fmt::Debug for Configuration {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Config")
             .field("generator", &self.generator)
+            .field("target", &self.target)
+            .field("bitwise_operators", &self.bitwise_operators)
             .field(
                 "rules",
                 &self
@@ -159,6 +496,174 @@ impl std::fmt::Debug for Configuration {
     }
 }
 
+/// The Lua dialect that the generated code must be compatible with.
+///
+/// When the target does not support some Luau syntax, the rules that remove this syntax
+/// are automatically added at the end of the rule list (unless they are already present).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LuaTarget {
+    /// Lua 5.1
+    Lua51,
+    /// Lua 5.3, which supports the floor division operator (`//`).
+    Lua53,
+    /// LuaJIT, which supports the same syntax as Lua 5.1.
+    LuaJIT,
+    /// Luau, which supports all the syntax that darklua can parse.
+    #[default]
+    Luau,
+}
+
+impl LuaTarget {
+    /// Returns the name used to refer to this target in configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lua51 => "lua51",
+            Self::Lua53 => "lua53",
+            Self::LuaJIT => "luajit",
+            Self::Luau => "luau",
+        }
+    }
+
+    /// Returns true if the target supports the floor division operator (`//`).
+    pub fn supports_floor_division(&self) -> bool {
+        matches!(self, Self::Lua53 | Self::Luau)
+    }
+
+    /// Returns true if the target supports the syntax specific to Luau, like types,
+    /// compound assignments, if expressions, interpolated strings or `continue` statements.
+    pub fn supports_luau_syntax(&self) -> bool {
+        matches!(self, Self::Luau)
+    }
+
+    /// Returns the ordered list of rules needed to remove the syntax that this target
+    /// does not support.
+    pub fn required_rules(&self) -> Vec<Box<dyn Rule>> {
+        let mut rules: Vec<Box<dyn Rule>> = Vec::new();
+
+        if !self.supports_luau_syntax() {
+            rules.push(Box::<RemoveTypes>::default());
+            rules.push(Box::<RemoveInterpolatedString>::default());
+            rules.push(Box::<RemoveCompoundAssignment>::default());
+        }
+        if !self.supports_floor_division() {
+            rules.push(Box::<RemoveFloorDivision>::default());
+        }
+        if !self.supports_luau_syntax() {
+            rules.push(Box::<RemoveIfExpression>::default());
+            rules.push(Box::<RemoveContinue>::default());
+            rules.push(Box::<RemoveGeneralizedIteration>::default());
+            rules.push(Box::<ConvertLuauNumber>::default());
+        }
+
+        rules
+    }
+
+    fn is_luau(&self) -> bool {
+        *self == Self::Luau
+    }
+}
+
+impl fmt::Display for LuaTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for LuaTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "lua51" => Self::Lua51,
+            "lua53" => Self::Lua53,
+            "luajit" => Self::LuaJIT,
+            "luau" => Self::Luau,
+            _ => return Err(format!("invalid target name `{}`", s)),
+        })
+    }
+}
+
+/// A named list of rules that can be used in a configuration file with the `preset` field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RulePreset {
+    /// Rules that reduce the size of the code as much as possible without
+    /// changing its behavior.
+    Minify,
+    /// Rules that remove development code (like assertions and profiling calls) and
+    /// reduce the size of the code for Roblox games.
+    RobloxProduction,
+    /// Rules that convert Luau syntax into code that can run with Lua 5.1.
+    Lua51Compat,
+}
+
+impl RulePreset {
+    /// Returns the name used to refer to this preset in configuration files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Minify => "minify",
+            Self::RobloxProduction => "roblox-production",
+            Self::Lua51Compat => "lua51-compat",
+        }
+    }
+
+    /// Returns the ordered list of rules this preset expands to.
+    pub fn rules(&self) -> Vec<Box<dyn Rule>> {
+        match self {
+            Self::Minify => vec![
+                Box::<RemoveComments>::default(),
+                Box::<RemoveSpaces>::default(),
+                Box::<ComputeExpression>::default(),
+                Box::<RemoveUnusedIfBranch>::default(),
+                Box::<RemoveUnusedWhile>::default(),
+                Box::<FilterAfterEarlyReturn>::default(),
+                Box::<RemoveEmptyDo>::default(),
+                Box::<RemoveUnusedVariable>::default(),
+                Box::<RemoveMethodDefinition>::default(),
+                Box::<ConvertIndexToField>::default(),
+                Box::<RemoveNilDeclaration>::default(),
+                Box::<GroupLocalAssignment>::default(),
+                Box::<RenameVariables>::default(),
+                Box::<RemoveFunctionCallParens>::default(),
+            ],
+            Self::RobloxProduction => vec![
+                Box::<RemoveComments>::default(),
+                Box::<RemoveSpaces>::default(),
+                Box::<RemoveAssertions>::default(),
+                Box::<RemoveDebugProfiling>::default(),
+                Box::<ComputeExpression>::default(),
+                Box::<RemoveUnusedIfBranch>::default(),
+                Box::<RemoveUnusedWhile>::default(),
+                Box::<FilterAfterEarlyReturn>::default(),
+                Box::<RemoveEmptyDo>::default(),
+                Box::<RemoveUnusedVariable>::default(),
+                Box::<RemoveNilDeclaration>::default(),
+            ],
+            Self::Lua51Compat => LuaTarget::Lua51.required_rules(),
+        }
+    }
+}
+
+impl fmt::Display for RulePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for RulePreset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "minify" => Self::Minify,
+            "roblox-production" => Self::RobloxProduction,
+            "lua51-compat" => Self::Lua51Compat,
+            _ => return Err(format!("invalid preset name `{}`", s)),
+        })
+    }
+}
+
 /// Parameters for configuring the Lua code generator.
 ///
 /// This enum defines different modes for generating Lua code, each with its own
@@ -181,6 +686,17 @@ pub enum GeneratorParameters {
         /// The maximum number of characters per line.
         #[serde(default = "get_default_column_span")]
         column_span: usize,
+        /// The comments of the original code that are written.
+        #[serde(default, skip_serializing_if = "KeepComments::is_none")]
+        keep_comments: KeepComments,
+    },
+    /// Writes the unchanged statements exactly as they appear in the original code and
+    /// only generates the statements that were added or modified.
+    #[serde(alias = "minimal-diff")]
+    MinimalDiff {
+        /// The maximum number of characters per line of the generated statements.
+        #[serde(default = "get_default_column_span")]
+        column_span: usize,
     },
 }
 
@@ -196,6 +712,14 @@ impl GeneratorParameters {
     pub fn default_readable() -> Self {
         Self::Readable {
             column_span: DEFAULT_COLUMN_SPAN,
+            keep_comments: KeepComments::None,
+        }
+    }
+
+    /// Creates a new minimal diff generator with default column span.
+    pub fn default_minimal_diff() -> Self {
+        Self::MinimalDiff {
+            column_span: DEFAULT_COLUMN_SPAN,
         }
     }
 
@@ -211,17 +735,62 @@ impl GeneratorParameters {
                 generator.write_block(block);
                 generator.into_string()
             }
-            Self::Readable { column_span } => {
-                let mut generator = ReadableLuaGenerator::new(*column_span);
+            Self::Readable {
+                column_span,
+                keep_comments,
+            } => {
+                let mut generator =
+                    ReadableLuaGenerator::new(*column_span).with_comments(code, *keep_comments);
                 generator.write_block(block);
                 generator.into_string()
             }
+            Self::MinimalDiff { column_span } => {
+                let mut generator =
+                    TokenBasedLuaGenerator::new(code).with_minimal_diff(*column_span);
+                generator.write_block(block);
+                generator.into_string()
+            }
+        }
+    }
+
+    fn generate_lua_with_source_map(&self, block: &Block, code: &str) -> (String, SourceMap) {
+        match self {
+            Self::RetainLines => {
+                let mut generator = TokenBasedLuaGenerator::new(code).with_source_map();
+                generator.write_block(block);
+                generator.into_string_and_source_map()
+            }
+            Self::Dense { column_span } => {
+                let mut generator = DenseLuaGenerator::new(*column_span).with_source_map();
+                generator.write_block(block);
+                generator.into_string_and_source_map()
+            }
+            Self::Readable {
+                column_span,
+                keep_comments,
+            } => {
+                let mut generator = ReadableLuaGenerator::new(*column_span)
+                    .with_comments(code, *keep_comments)
+                    .with_source_map();
+                generator.write_block(block);
+                generator.into_string_and_source_map()
+            }
+            Self::MinimalDiff { column_span } => {
+                let mut generator = TokenBasedLuaGenerator::new(code)
+                    .with_minimal_diff(*column_span)
+                    .with_source_map();
+                generator.write_block(block);
+                generator.into_string_and_source_map()
+            }
         }
     }
 
     fn build_parser(&self) -> Parser {
         match self {
-            Self::RetainLines => Parser::default().preserve_tokens(),
+            Self::RetainLines | Self::MinimalDiff { .. } => Parser::default().preserve_tokens(),
+            Self::Readable { keep_comments, .. } if !keep_comments.is_none() => {
+                Parser::default().preserve_tokens()
+            }
             Self::Dense { .. } | Self::Readable { .. } => Parser::default(),
         }
     }
@@ -237,14 +806,112 @@ impl FromStr for GeneratorParameters {
             "dense" => Self::Dense {
                 column_span: DEFAULT_COLUMN_SPAN,
             },
-            "readable" => Self::Readable {
-                column_span: DEFAULT_COLUMN_SPAN,
-            },
+            "readable" => Self::default_readable(),
+            "minimal_diff" | "minimal-diff" => Self::default_minimal_diff(),
             _ => return Err(format!("invalid generator name `{}`", s)),
         })
     }
 }
 
+/// A list of rules that replaces the rules of the configuration for the files
+/// matching any of the `include` globs. The globs are matched against the file
+/// paths relative to the configuration file.
+#[derive(Serialize, Deserialize)]
+#[serde(from = "RuleOverrideData")]
+pub struct RuleOverride {
+    include: Vec<String>,
+    rules: Vec<Box<dyn Rule>>,
+    #[serde(skip)]
+    matcher: Option<wax::Any<'static>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleOverrideData {
+    include: Vec<String>,
+    #[serde(default)]
+    rules: Vec<Box<dyn Rule>>,
+}
+
+impl From<RuleOverrideData> for RuleOverride {
+    fn from(data: RuleOverrideData) -> Self {
+        data.include
+            .into_iter()
+            .fold(Self::new(), |rule_override, include| {
+                rule_override.with_include(include)
+            })
+            .with_rules(data.rules)
+    }
+}
+
+impl RuleOverride {
+    /// Creates an override that does not match any file.
+    pub fn new() -> Self {
+        Self {
+            include: Vec::new(),
+            rules: Vec::new(),
+            matcher: None,
+        }
+    }
+
+    /// Adds a glob matching the files that use the rules of this override.
+    pub fn with_include(mut self, include: impl Into<String>) -> Self {
+        self.include.push(include.into());
+        self.matcher = build_matcher(&self.include);
+        self
+    }
+
+    /// Adds a rule to this override.
+    pub fn with_rule(mut self, rule: impl Into<Box<dyn Rule>>) -> Self {
+        self.rules.push(rule.into());
+        self
+    }
+
+    fn with_rules(mut self, rules: Vec<Box<dyn Rule>>) -> Self {
+        self.rules.extend(rules);
+        self
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        self.matcher
+            .as_ref()
+            .map(|matcher| matcher.is_match(path))
+            .unwrap_or(false)
+    }
+}
+
+impl Default for RuleOverride {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn build_matcher(includes: &[String]) -> Option<wax::Any<'static>> {
+    let globs: Vec<_> = includes
+        .iter()
+        .filter_map(|include| match wax::Glob::new(include) {
+            Ok(glob) => Some(glob.into_owned()),
+            Err(err) => {
+                log::warn!(
+                    "unable to create override matcher from `{}`: {}",
+                    include,
+                    err
+                );
+                None
+            }
+        })
+        .collect();
+
+    if globs.is_empty() {
+        None
+    } else {
+        Some(
+            wax::any(globs)
+                .expect("override globs errors should be filtered and only emit a warning"),
+        )
+    }
+}
+
 /// Configuration for bundling modules.
 ///
 /// This struct defines how modules should be bundled together, including
@@ -258,6 +925,14 @@ pub struct BundleConfiguration {
     modules_identifier: Option<String>,
     #[serde(default, skip_serializing_if = "HashSet::is_empty")]
     excludes: HashSet<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    tree_shaking: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    require_polyfill: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    chunk_size: Option<usize>,
+    #[serde(default, skip_serializing_if = "HashSet::is_empty")]
+    lazy_modules: HashSet<String>,
 }
 
 impl BundleConfiguration {
@@ -267,6 +942,10 @@ impl BundleConfiguration {
             require_mode: require_mode.into(),
             modules_identifier: None,
             excludes: Default::default(),
+            tree_shaking: false,
+            require_polyfill: false,
+            chunk_size: None,
+            lazy_modules: Default::default(),
         }
     }
 
@@ -282,6 +961,36 @@ impl BundleConfiguration {
         self
     }
 
+    /// Enables tree shaking, which removes the fields of bundled modules that are
+    /// never used by the modules requiring them.
+    pub fn with_tree_shaking(mut self) -> Self {
+        self.tree_shaking = true;
+        self
+    }
+
+    /// Injects a small module loader in the bundle, so that modules requiring each
+    /// other are loaded when the bundle runs (like `require` in Lua) instead of making
+    /// the bundling fail.
+    pub fn with_require_polyfill(mut self) -> Self {
+        self.require_polyfill = true;
+        self
+    }
+
+    /// Splits the bundle into chunks of at most the given size (in bytes of module
+    /// source code). The modules that do not fit in the main bundle are moved to
+    /// chunks written next to it and loaded the first time they are required.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+
+    /// Adds a glob matching require paths of modules to always move into their own
+    /// chunk, loaded the first time they are required.
+    pub fn with_lazy_module(mut self, lazy_module: impl Into<String>) -> Self {
+        self.lazy_modules.insert(lazy_module.into());
+        self
+    }
+
     pub(crate) fn require_mode(&self) -> &BundleRequireMode {
         &self.require_mode
     }
@@ -296,6 +1005,22 @@ impl BundleConfiguration {
     pub(crate) fn excludes(&self) -> impl Iterator<Item = &str> {
         self.excludes.iter().map(AsRef::as_ref)
     }
+
+    pub(crate) fn is_tree_shaking(&self) -> bool {
+        self.tree_shaking
+    }
+
+    pub(crate) fn is_require_polyfill(&self) -> bool {
+        self.require_polyfill
+    }
+
+    pub(crate) fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    pub(crate) fn lazy_modules(&self) -> impl Iterator<Item = &str> {
+        self.lazy_modules.iter().map(AsRef::as_ref)
+    }
 }
 
 #[cfg(test)]
@@ -351,9 +1076,7 @@ mod test {
 
             pretty_assertions::assert_eq!(
                 config.generator,
-                GeneratorParameters::Readable {
-                    column_span: DEFAULT_COLUMN_SPAN
-                }
+                GeneratorParameters::default_readable()
             );
         }
 
@@ -364,19 +1087,79 @@ mod test {
 
             pretty_assertions::assert_eq!(
                 config.generator,
-                GeneratorParameters::Readable { column_span: 110 }
+                GeneratorParameters::Readable {
+                    column_span: 110,
+                    keep_comments: KeepComments::None,
+                }
             );
         }
 
         #[test]
-        fn deserialize_retain_lines_params_as_string() {
-            let config: Configuration = json5::from_str("{generator: 'retain_lines'}").unwrap();
-
-            pretty_assertions::assert_eq!(config.generator, GeneratorParameters::RetainLines);
-        }
+        fn deserialize_readable_params_with_keep_comments() {
+            let config: Configuration =
+                json5::from_str("{ generator: { name: 'readable', keep_comments: 'banner' }}")
+                    .unwrap();
 
-        #[test]
-        fn deserialize_dense_params_as_string() {
+            pretty_assertions::assert_eq!(
+                config.generator,
+                GeneratorParameters::Readable {
+                    column_span: DEFAULT_COLUMN_SPAN,
+                    keep_comments: KeepComments::Banner,
+                }
+            );
+        }
+
+        #[test]
+        fn deserialize_readable_params_with_invalid_keep_comments() {
+            let result = json5::from_str::<Configuration>(
+                "{ generator: { name: 'readable', keep_comments: 'some' }}",
+            );
+
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn deserialize_minimal_diff_params() {
+            let config: Configuration =
+                json5::from_str("{ generator: { name: 'minimal_diff' } }").unwrap();
+
+            pretty_assertions::assert_eq!(
+                config.generator,
+                GeneratorParameters::default_minimal_diff()
+            );
+        }
+
+        #[test]
+        fn deserialize_minimal_diff_params_with_column_span() {
+            let config: Configuration =
+                json5::from_str("{ generator: { name: 'minimal_diff', column_span: 100 } }")
+                    .unwrap();
+
+            pretty_assertions::assert_eq!(
+                config.generator,
+                GeneratorParameters::MinimalDiff { column_span: 100 }
+            );
+        }
+
+        #[test]
+        fn deserialize_minimal_diff_params_as_string() {
+            let config: Configuration = json5::from_str("{generator: 'minimal_diff'}").unwrap();
+
+            pretty_assertions::assert_eq!(
+                config.generator,
+                GeneratorParameters::default_minimal_diff()
+            );
+        }
+
+        #[test]
+        fn deserialize_retain_lines_params_as_string() {
+            let config: Configuration = json5::from_str("{generator: 'retain_lines'}").unwrap();
+
+            pretty_assertions::assert_eq!(config.generator, GeneratorParameters::RetainLines);
+        }
+
+        #[test]
+        fn deserialize_dense_params_as_string() {
             let config: Configuration = json5::from_str("{generator: 'dense'}").unwrap();
 
             pretty_assertions::assert_eq!(
@@ -393,9 +1176,7 @@ mod test {
 
             pretty_assertions::assert_eq!(
                 config.generator,
-                GeneratorParameters::Readable {
-                    column_span: DEFAULT_COLUMN_SPAN
-                }
+                GeneratorParameters::default_readable()
             );
         }
 
@@ -491,6 +1272,29 @@ mod test {
             );
         }
 
+        #[test]
+        fn deserialize_path_require_mode_with_tree_shaking() {
+            let config: Configuration =
+                json5::from_str("{bundle: { require_mode: 'path', tree_shaking: true } }").unwrap();
+
+            pretty_assertions::assert_eq!(
+                config.bundle.unwrap(),
+                BundleConfiguration::new(PathRequireMode::default()).with_tree_shaking()
+            );
+        }
+
+        #[test]
+        fn deserialize_path_require_mode_with_require_polyfill() {
+            let config: Configuration =
+                json5::from_str("{bundle: { require_mode: 'path', require_polyfill: true } }")
+                    .unwrap();
+
+            pretty_assertions::assert_eq!(
+                config.bundle.unwrap(),
+                BundleConfiguration::new(PathRequireMode::default()).with_require_polyfill()
+            );
+        }
+
         #[test]
         fn deserialize_unknown_require_mode_name() {
             let result: Result<Configuration, _> =
@@ -502,4 +1306,508 @@ mod test {
             );
         }
     }
+
+    mod rule_preset {
+        use super::*;
+
+        fn rule_names(config: &Configuration) -> Vec<&'static str> {
+            config.rules().map(|rule| rule.get_name()).collect()
+        }
+
+        fn preset_rule_names(preset: RulePreset) -> Vec<&'static str> {
+            preset.rules().iter().map(|rule| rule.get_name()).collect()
+        }
+
+        #[test]
+        fn deserialize_minify_preset() {
+            let config: Configuration = json5::from_str("{ preset: 'minify' }").unwrap();
+
+            pretty_assertions::assert_eq!(
+                rule_names(&config),
+                preset_rule_names(RulePreset::Minify)
+            );
+        }
+
+        #[test]
+        fn deserialize_roblox_production_preset() {
+            let config: Configuration = json5::from_str("{ preset: 'roblox-production' }").unwrap();
+
+            pretty_assertions::assert_eq!(
+                rule_names(&config),
+                preset_rule_names(RulePreset::RobloxProduction)
+            );
+        }
+
+        #[test]
+        fn deserialize_lua51_compat_preset() {
+            let config: Configuration = json5::from_str("{ preset: 'lua51-compat' }").unwrap();
+
+            pretty_assertions::assert_eq!(
+                rule_names(&config),
+                preset_rule_names(RulePreset::Lua51Compat)
+            );
+        }
+
+        #[test]
+        fn preset_with_rules_appends_new_rules() {
+            let config: Configuration = json5::from_str(
+                "{ preset: 'lua51-compat', rules: ['remove_comments', 'remove_spaces'] }",
+            )
+            .unwrap();
+
+            let mut expected = preset_rule_names(RulePreset::Lua51Compat);
+            expected.push("remove_comments");
+            expected.push("remove_spaces");
+
+            pretty_assertions::assert_eq!(rule_names(&config), expected);
+        }
+
+        #[test]
+        fn preset_with_rules_overrides_preset_rule() {
+            let config: Configuration = json5::from_str(
+                "{ preset: 'roblox-production', rules: [{ rule: 'remove_assertions', preserve_arguments_side_effects: false }] }",
+            )
+            .unwrap();
+
+            pretty_assertions::assert_eq!(
+                rule_names(&config),
+                preset_rule_names(RulePreset::RobloxProduction)
+            );
+
+            let remove_assertions = config
+                .rules
+                .iter()
+                .find(|rule| rule.get_name() == "remove_assertions")
+                .unwrap();
+
+            insta::assert_json_snapshot!(remove_assertions, @r###"
+            {
+              "rule": "remove_assertions",
+              "preserve_arguments_side_effects": false
+            }
+            "###);
+        }
+
+        #[test]
+        fn serialize_configuration_with_expanded_preset() {
+            let config: Configuration =
+                json5::from_str("{ preset: 'lua51-compat', generator: 'dense' }").unwrap();
+
+            insta::assert_json_snapshot!(config, @r###"
+            {
+              "rules": [
+                "remove_types",
+                "remove_interpolated_string",
+                "remove_compound_assignment",
+                "remove_floor_division",
+                "remove_if_expression",
+                "remove_continue",
+                "remove_generalized_iteration",
+                "convert_luau_number"
+              ],
+              "generator": {
+                "name": "dense",
+                "column_span": 80
+              }
+            }
+            "###);
+        }
+
+        #[test]
+        fn deserialize_unknown_preset_name() {
+            let result: Result<Configuration, _> = json5::from_str("{ preset: 'oops' }");
+
+            insta::assert_snapshot!(
+                result.expect_err("deserialization should fail").to_string(),
+                @"unknown variant `oops`, expected one of `minify`, `roblox-production`, `lua51-compat` at line 1 column 11"
+            );
+        }
+
+        #[test]
+        fn parse_preset_from_name() {
+            for preset in [
+                RulePreset::Minify,
+                RulePreset::RobloxProduction,
+                RulePreset::Lua51Compat,
+            ] {
+                pretty_assertions::assert_eq!(preset.name().parse::<RulePreset>(), Ok(preset));
+            }
+        }
+    }
+
+    mod rule_overrides {
+        use super::*;
+
+        fn rule_names_for(config: &Configuration, path: &str) -> Vec<&'static str> {
+            config
+                .rules_for(Path::new(path))
+                .map(|rule| rule.get_name())
+                .collect()
+        }
+
+        #[test]
+        fn deserialize_override() {
+            let config: Configuration = json5::from_str(
+                "{ rules: ['remove_spaces'], overrides: [{ include: ['tests/**'], rules: ['remove_comments'] }] }",
+            )
+            .unwrap();
+
+            pretty_assertions::assert_eq!(config.overrides.len(), 1);
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "tests/a.lua"),
+                vec!["remove_comments"]
+            );
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "src/a.lua"),
+                vec!["remove_spaces"]
+            );
+        }
+
+        #[test]
+        fn override_without_rules_matches_with_empty_rules() {
+            let config: Configuration = json5::from_str(
+                "{ rules: ['remove_spaces'], overrides: [{ include: ['tests/**'] }] }",
+            )
+            .unwrap();
+
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "tests/a.lua"),
+                Vec::<&str>::new()
+            );
+        }
+
+        #[test]
+        fn override_with_multiple_globs() {
+            let config = Configuration::empty()
+                .with_rule(Box::new(RemoveSpaces::default()) as Box<dyn Rule>)
+                .with_override(
+                    RuleOverride::new()
+                        .with_include("tests/**")
+                        .with_include("**/*.spec.lua")
+                        .with_rule(Box::new(RemoveComments::default()) as Box<dyn Rule>),
+                );
+
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "src/a.spec.lua"),
+                vec!["remove_comments"]
+            );
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "./tests/a.lua"),
+                vec!["remove_comments"]
+            );
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "src/a.lua"),
+                vec!["remove_spaces"]
+            );
+        }
+
+        #[test]
+        fn override_is_relative_to_location() {
+            let config = Configuration::empty()
+                .with_location("project")
+                .with_override(
+                    RuleOverride::new()
+                        .with_include("src/**")
+                        .with_rule(Box::new(RemoveComments::default()) as Box<dyn Rule>),
+                );
+
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "project/src/a.lua"),
+                vec!["remove_comments"]
+            );
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "project/lib/a.lua"),
+                Vec::<&str>::new()
+            );
+        }
+
+        #[test]
+        fn override_with_invalid_glob_does_not_match() {
+            let config = Configuration::empty().with_override(
+                RuleOverride::new()
+                    .with_include("a/**b")
+                    .with_rule(Box::new(RemoveComments::default()) as Box<dyn Rule>),
+            );
+
+            pretty_assertions::assert_eq!(rule_names_for(&config, "a/b"), Vec::<&str>::new());
+        }
+
+        #[test]
+        fn serialize_override() {
+            let config: Configuration = json5::from_str(
+                "{ rules: [], overrides: [{ include: ['tests/**'], rules: ['remove_comments'] }] }",
+            )
+            .unwrap();
+
+            insta::assert_snapshot!(json5::to_string(&config).unwrap(), @r###"
+            {
+              rules: [],
+              overrides: [
+                {
+                  include: [
+                    "tests/**",
+                  ],
+                  rules: [
+                    "remove_comments",
+                  ],
+                },
+              ],
+              generator: {
+                name: "retain_lines",
+              },
+            }
+            "###);
+        }
+
+        #[test]
+        fn deserialize_override_with_unknown_field() {
+            let result: Result<Configuration, _> =
+                json5::from_str("{ overrides: [{ include: [], exclude: [] }] }");
+
+            insta::assert_snapshot!(
+                result.expect_err("deserialization should fail").to_string(),
+                @"unknown field `exclude`, expected `include` or `rules` at line 1 column 30"
+            );
+        }
+
+        #[test]
+        fn target_adds_rules_to_overrides() {
+            let mut config: Configuration = json5::from_str(
+                "{ rules: [], target: 'lua53', overrides: [{ include: ['tests/**'], rules: ['remove_types'] }] }",
+            )
+            .unwrap();
+
+            config.add_target_rules();
+
+            pretty_assertions::assert_eq!(
+                rule_names_for(&config, "tests/a.lua"),
+                vec![
+                    "remove_types",
+                    "remove_interpolated_string",
+                    "remove_compound_assignment",
+                    "remove_if_expression",
+                    "remove_continue",
+                    "remove_generalized_iteration",
+                    "convert_luau_number",
+                ]
+            );
+        }
+    }
+
+    mod lua_target {
+        use super::*;
+
+        fn rule_names(config: &Configuration) -> Vec<&'static str> {
+            config.rules().map(|rule| rule.get_name()).collect()
+        }
+
+        #[test]
+        fn deserialize_default_target() {
+            let config: Configuration = json5::from_str("{}").unwrap();
+
+            pretty_assertions::assert_eq!(config.target, LuaTarget::Luau);
+        }
+
+        #[test]
+        fn deserialize_lua51_target() {
+            let config: Configuration = json5::from_str("{ target: 'lua51' }").unwrap();
+
+            pretty_assertions::assert_eq!(config.target, LuaTarget::Lua51);
+        }
+
+        #[test]
+        fn deserialize_luajit_target() {
+            let config: Configuration = json5::from_str("{ target: 'luajit' }").unwrap();
+
+            pretty_assertions::assert_eq!(config.target, LuaTarget::LuaJIT);
+        }
+
+        #[test]
+        fn deserialize_unknown_target() {
+            let result: Result<Configuration, _> = json5::from_str("{ target: 'lua54' }");
+
+            insta::assert_snapshot!(
+                result.expect_err("deserialization should fail").to_string(),
+                @"unknown variant `lua54`, expected one of `lua51`, `lua53`, `luajit`, `luau` at line 1 column 11"
+            );
+        }
+
+        #[test]
+        fn luau_target_does_not_add_rules() {
+            let mut config: Configuration = json5::from_str("{ rules: [] }").unwrap();
+
+            config.add_target_rules();
+
+            pretty_assertions::assert_eq!(rule_names(&config), Vec::<&str>::new());
+        }
+
+        #[test]
+        fn lua51_target_adds_missing_rules() {
+            let mut config: Configuration =
+                json5::from_str("{ rules: ['remove_spaces', 'remove_types'], target: 'lua51' }")
+                    .unwrap();
+
+            config.add_target_rules();
+
+            pretty_assertions::assert_eq!(
+                rule_names(&config),
+                vec![
+                    "remove_spaces",
+                    "remove_types",
+                    "remove_interpolated_string",
+                    "remove_compound_assignment",
+                    "remove_floor_division",
+                    "remove_if_expression",
+                    "remove_continue",
+                    "remove_generalized_iteration",
+                    "convert_luau_number",
+                ]
+            );
+        }
+
+        #[test]
+        fn lua53_target_keeps_floor_division() {
+            let rules = LuaTarget::Lua53.required_rules();
+
+            assert!(rules
+                .iter()
+                .all(|rule| rule.get_name() != "remove_floor_division"));
+            assert!(rules.iter().any(|rule| rule.get_name() == "remove_types"));
+        }
+
+        #[test]
+        fn parse_target_from_name() {
+            for target in [
+                LuaTarget::Lua51,
+                LuaTarget::Lua53,
+                LuaTarget::LuaJIT,
+                LuaTarget::Luau,
+            ] {
+                pretty_assertions::assert_eq!(target.name().parse::<LuaTarget>(), Ok(target));
+            }
+        }
+    }
+
+    mod extends {
+        use super::*;
+
+        use serde_json::json;
+
+        fn extend(base: &str, value: &str) -> serde_json::Value {
+            extend_configuration_value(
+                json5::from_str(base).unwrap(),
+                json5::from_str(value).unwrap(),
+            )
+        }
+
+        #[test]
+        fn take_single_extended_path() {
+            let mut value = json!({ "extends": "./base.json", "target": "lua51" });
+
+            pretty_assertions::assert_eq!(
+                take_extended_paths(&mut value),
+                Ok(vec!["./base.json".to_owned()])
+            );
+            pretty_assertions::assert_eq!(value, json!({ "target": "lua51" }));
+        }
+
+        #[test]
+        fn take_list_of_extended_paths() {
+            let mut value = json!({ "extends": ["./a.json", "./b.json"] });
+
+            pretty_assertions::assert_eq!(
+                take_extended_paths(&mut value),
+                Ok(vec!["./a.json".to_owned(), "./b.json".to_owned()])
+            );
+        }
+
+        #[test]
+        fn take_extended_paths_without_extends() {
+            let mut value = json!({ "target": "lua51" });
+
+            pretty_assertions::assert_eq!(take_extended_paths(&mut value), Ok(Vec::new()));
+        }
+
+        #[test]
+        fn take_invalid_extended_paths() {
+            let mut value = json!({ "extends": true });
+
+            assert!(take_extended_paths(&mut value).is_err());
+        }
+
+        #[test]
+        fn fields_replace_base_fields() {
+            pretty_assertions::assert_eq!(
+                extend(
+                    "{ generator: 'dense', target: 'lua51', preset: 'minify' }",
+                    "{ generator: { name: 'readable' }, target: 'luau' }"
+                ),
+                json!({ "generator": { "name": "readable" }, "target": "luau", "preset": "minify" })
+            );
+        }
+
+        #[test]
+        fn rules_are_merged_by_name() {
+            pretty_assertions::assert_eq!(
+                extend(
+                    "{ rules: ['remove_comments', 'remove_spaces'] }",
+                    "{ rules: [{ rule: 'remove_comments', except: ['^!'] }, 'compute_expression'] }"
+                ),
+                json!({ "rules": [
+                    { "rule": "remove_comments", "except": ["^!"] },
+                    "remove_spaces",
+                    "compute_expression",
+                ] })
+            );
+        }
+
+        #[test]
+        fn rules_are_merged_with_process_alias() {
+            pretty_assertions::assert_eq!(
+                extend(
+                    "{ process: ['remove_comments'] }",
+                    "{ process: ['remove_spaces'] }"
+                ),
+                json!({ "rules": ["remove_comments", "remove_spaces"] })
+            );
+        }
+
+        #[test]
+        fn overrides_are_placed_before_base_overrides() {
+            pretty_assertions::assert_eq!(
+                extend(
+                    "{ overrides: [{ include: ['tests/**'], rules: [] }] }",
+                    "{ overrides: [{ include: ['src/**'], rules: [] }] }"
+                ),
+                json!({ "overrides": [
+                    { "include": ["src/**"], "rules": [] },
+                    { "include": ["tests/**"], "rules": [] },
+                ] })
+            );
+        }
+
+        #[test]
+        fn bundle_fields_are_merged() {
+            pretty_assertions::assert_eq!(
+                extend(
+                    "{ bundle: { require_mode: 'path', excludes: ['@lune/**'] } }",
+                    "{ bundle: { tree_shaking: true, excludes: [] } }"
+                ),
+                json!({ "bundle": { "require_mode": "path", "excludes": [], "tree_shaking": true } })
+            );
+        }
+
+        #[test]
+        fn extended_value_deserializes_into_configuration() {
+            let value = extend(
+                "{ preset: 'minify', generator: 'dense' }",
+                "{ rules: [{ rule: 'rename_variables', globals: ['$default', 'game'] }] }",
+            );
+
+            let config: Configuration = serde_json::from_value(value).unwrap();
+
+            pretty_assertions::assert_eq!(config.generator, GeneratorParameters::default_dense());
+            pretty_assertions::assert_eq!(config.rules().count(), RulePreset::Minify.rules().len());
+        }
+    }
 }
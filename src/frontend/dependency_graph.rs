@@ -0,0 +1,145 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use crate::utils::normalize_path;
+
+/// The files that each processed file depends on, like the modules included in a
+/// bundle or the files read by rules.
+///
+/// The dependencies of a file are known once it was processed. Get the graph after
+/// processing with [`WorkerTree::dependency_graph`](crate::WorkerTree::dependency_graph).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DependencyGraph {
+    dependencies: BTreeMap<PathBuf, BTreeSet<PathBuf>>,
+}
+
+impl DependencyGraph {
+    pub(crate) fn insert_source(
+        &mut self,
+        source: &Path,
+        dependencies: impl Iterator<Item = PathBuf>,
+    ) {
+        self.dependencies
+            .entry(normalize_path(source))
+            .or_default()
+            .extend(dependencies.map(normalize_path));
+    }
+
+    /// Returns an iterator over the processed files, sorted by path.
+    pub fn iter_sources(&self) -> impl Iterator<Item = &Path> {
+        self.dependencies.keys().map(PathBuf::as_path)
+    }
+
+    /// Returns an iterator over the files that the given file directly depends on.
+    pub fn iter_dependencies(&self, source: impl AsRef<Path>) -> impl Iterator<Item = &Path> {
+        self.dependencies
+            .get(&normalize_path(source))
+            .into_iter()
+            .flatten()
+            .map(PathBuf::as_path)
+    }
+
+    /// Returns an iterator over the processed files that directly depend on the
+    /// given file.
+    pub fn iter_dependents(&self, path: impl AsRef<Path>) -> impl Iterator<Item = &Path> {
+        let path = normalize_path(path);
+
+        self.dependencies
+            .iter()
+            .filter(move |(_, dependencies)| dependencies.contains(&path))
+            .map(|(source, _)| source.as_path())
+    }
+
+    /// Returns the processed files that need to be processed again when the given
+    /// file changes: the file itself when it is processed, and every file that
+    /// depends on it directly or transitively. The files are sorted by path.
+    pub fn affected_sources(&self, path: impl AsRef<Path>) -> Vec<&Path> {
+        let path = normalize_path(path);
+
+        let mut affected = BTreeSet::new();
+        let mut queue = vec![path.clone()];
+
+        if let Some((source, _)) = self.dependencies.get_key_value(&path) {
+            affected.insert(source.as_path());
+        }
+
+        while let Some(current) = queue.pop() {
+            for dependent in self.iter_dependents(&current) {
+                if affected.insert(dependent) {
+                    queue.push(dependent.to_path_buf());
+                }
+            }
+        }
+
+        affected.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn graph() -> DependencyGraph {
+        let mut graph = DependencyGraph::default();
+        graph.insert_source(
+            Path::new("src/a.lua"),
+            vec![PathBuf::from("src/shared.lua")].into_iter(),
+        );
+        graph.insert_source(
+            Path::new("src/b.lua"),
+            vec![PathBuf::from("src/a.lua")].into_iter(),
+        );
+        graph.insert_source(
+            Path::new("src/c.lua"),
+            vec![PathBuf::from("src/other.lua")].into_iter(),
+        );
+        graph
+    }
+
+    #[test]
+    fn iter_dependencies_of_source() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.iter_dependencies("src/a.lua").collect::<Vec<_>>(),
+            vec![Path::new("src/shared.lua")]
+        );
+    }
+
+    #[test]
+    fn iter_dependencies_of_unknown_source_is_empty() {
+        assert_eq!(graph().iter_dependencies("src/unknown.lua").count(), 0);
+    }
+
+    #[test]
+    fn iter_dependents_of_file() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.iter_dependents("src/a.lua").collect::<Vec<_>>(),
+            vec![Path::new("src/b.lua")]
+        );
+    }
+
+    #[test]
+    fn affected_sources_include_transitive_dependents() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.affected_sources("src/shared.lua"),
+            vec![Path::new("src/a.lua"), Path::new("src/b.lua")]
+        );
+    }
+
+    #[test]
+    fn affected_sources_include_changed_source() {
+        let graph = graph();
+
+        assert_eq!(
+            graph.affected_sources("src/c.lua"),
+            vec![Path::new("src/c.lua")]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::SourceSpan;
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// A problem that prevents a file from being processed.
+    Error,
+    /// A problem that does not prevent a file from being processed.
+    Warning,
+}
+
+/// A machine-readable description of a problem found while processing files,
+/// obtained with [`DarkluaError::diagnostics`](crate::DarkluaError::diagnostics).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    file: Option<String>,
+    span: Option<SourceSpan>,
+    rule: Option<String>,
+    message: String,
+    severity: Severity,
+}
+
+impl Diagnostic {
+    pub(crate) fn error(message: impl Into<String>) -> Self {
+        Self {
+            file: None,
+            span: None,
+            rule: None,
+            message: message.into(),
+            severity: Severity::Error,
+        }
+    }
+
+    pub(crate) fn with_file(mut self, path: &Path) -> Self {
+        self.file = Some(path.display().to_string());
+        self
+    }
+
+    /// Sets the file of the diagnostic when it does not already have one, like for the
+    /// problems found in the content of a file given to a command.
+    pub fn with_default_file(self, path: &Path) -> Self {
+        if self.file.is_some() {
+            self
+        } else {
+            self.with_file(path)
+        }
+    }
+
+    pub(crate) fn with_span(mut self, span: Option<SourceSpan>) -> Self {
+        self.span = span;
+        self
+    }
+
+    pub(crate) fn with_rule(mut self, rule: impl Into<String>) -> Self {
+        self.rule = Some(rule.into());
+        self
+    }
+
+    /// Returns the path of the file where the problem was found, if the problem is
+    /// related to a file.
+    pub fn file(&self) -> Option<&str> {
+        self.file.as_deref()
+    }
+
+    /// Returns the location of the code that caused the problem, if it is known.
+    pub fn span(&self) -> Option<&SourceSpan> {
+        self.span.as_ref()
+    }
+
+    /// Returns the name of the rule that failed, if the problem comes from a rule.
+    pub fn rule(&self) -> Option<&str> {
+        self.rule.as_deref()
+    }
+
+    /// Returns the message describing the problem.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the severity of the problem.
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::{rules::RemoveComments, DarkluaError, Parser};
+
+    #[test]
+    fn parser_error_produces_a_diagnostic_for_each_syntax_error() {
+        let parser_error = Parser::default().parse("local = 1 return +").unwrap_err();
+        let error = DarkluaError::parser_error("src/init.lua", parser_error);
+
+        let diagnostics = error.diagnostics();
+
+        assert!(!diagnostics.is_empty());
+        for diagnostic in diagnostics {
+            assert_eq!(diagnostic.file(), Some("src/init.lua"));
+            assert!(diagnostic.span().is_some());
+            assert_eq!(diagnostic.rule(), None);
+            assert_eq!(diagnostic.severity(), Severity::Error);
+        }
+    }
+
+    #[test]
+    fn rule_error_diagnostic_has_rule_name() {
+        let error = DarkluaError::rule_error(
+            "src/init.lua",
+            &RemoveComments::default(),
+            0,
+            "something went wrong",
+        );
+
+        insta::assert_json_snapshot!(error.diagnostics(), @r###"
+        [
+          {
+            "file": "src/init.lua",
+            "span": null,
+            "rule": "remove_comments",
+            "message": "something went wrong",
+            "severity": "error"
+          }
+        ]
+        "###);
+    }
+
+    #[test]
+    fn resource_not_found_diagnostic_has_file() {
+        let error = DarkluaError::resource_not_found("src/missing.lua");
+
+        insta::assert_json_snapshot!(error.diagnostics(), @r###"
+        [
+          {
+            "file": "src/missing.lua",
+            "span": null,
+            "rule": null,
+            "message": "unable to find `src/missing.lua`",
+            "severity": "error"
+          }
+        ]
+        "###);
+    }
+
+    #[test]
+    fn custom_error_diagnostic_has_message() {
+        let error = DarkluaError::custom("oops");
+
+        insta::assert_json_snapshot!(error.diagnostics(), @r###"
+        [
+          {
+            "file": null,
+            "span": null,
+            "rule": null,
+            "message": "oops",
+            "severity": "error"
+          }
+        ]
+        "###);
+    }
+}
@@ -4,12 +4,13 @@ use std::{
     collections::HashSet,
     ffi::{OsStr, OsString},
     fmt::{self, Display},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 use crate::{process::LuaSerializerError, rules::Rule, ParserError};
 
 use super::{
+    diagnostic::Diagnostic,
     resources::ResourceError,
     work_item::{WorkData, WorkItem, WorkStatus},
 };
@@ -95,7 +96,8 @@ impl DarkluaError {
         self
     }
 
-    pub(crate) fn parser_error(path: impl Into<PathBuf>, error: ParserError) -> Self {
+    /// Creates an error for a file that could not be parsed.
+    pub fn parser_error(path: impl Into<PathBuf>, error: ParserError) -> Self {
         Self::new(ErrorKind::Parser {
             path: path.into(),
             error,
@@ -220,6 +222,46 @@ impl DarkluaError {
         })
     }
 
+    /// Returns the path of the file that could not be parsed and the parser error,
+    /// when this error comes from parsing a file.
+    pub fn as_parser_error(&self) -> Option<(&Path, &ParserError)> {
+        match &*self.kind {
+            ErrorKind::Parser { path, error } => Some((path.as_path(), error)),
+            _ => None,
+        }
+    }
+
+    /// Converts this error into diagnostics. Parser errors produce one diagnostic
+    /// for each syntax error, and the other errors produce a single diagnostic.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match &*self.kind {
+            ErrorKind::Parser { path, error } => error
+                .syntax_errors()
+                .into_iter()
+                .map(|syntax_error| {
+                    Diagnostic::error(syntax_error.message())
+                        .with_file(path)
+                        .with_span(syntax_error.span().copied())
+                })
+                .collect(),
+            ErrorKind::RuleError {
+                path,
+                rule_name,
+                error,
+                ..
+            } => vec![Diagnostic::error(error.as_str())
+                .with_file(path)
+                .with_rule(rule_name.as_str())],
+            ErrorKind::ResourceNotFound { path }
+            | ErrorKind::InvalidConfiguration { path }
+            | ErrorKind::IO { path, .. }
+            | ErrorKind::UncachedWork { path } => {
+                vec![Diagnostic::error(self.to_string()).with_file(path)]
+            }
+            _ => vec![Diagnostic::error(self.to_string())],
+        }
+    }
+
     /// Creates a custom error with the given message.
     pub fn custom(message: impl Into<Cow<'static, str>>) -> Self {
         Self::new(ErrorKind::Custom {
@@ -1,6 +1,11 @@
 mod configuration;
+mod dependency_graph;
+mod diagnostic;
 mod error;
 mod options;
+mod parse_cache;
+mod process_cache;
+mod report;
 mod resources;
 mod utils;
 mod work_cache;
@@ -8,10 +13,16 @@ mod work_item;
 mod worker;
 mod worker_tree;
 
-pub use configuration::{BundleConfiguration, Configuration, GeneratorParameters};
+pub use configuration::{
+    BundleConfiguration, Configuration, GeneratorParameters, LuaTarget, RuleOverride, RulePreset,
+};
+pub use dependency_graph::DependencyGraph;
+pub use diagnostic::{Diagnostic, Severity};
 pub use error::{DarkluaError, DarkluaResult};
 pub use options::Options;
-pub use resources::Resources;
+pub use parse_cache::ParseCache;
+pub use report::{FileReport, ProcessingReport, RuleReport};
+pub use resources::{ResourceChange, ResourceError, Resources};
 use serde::Serialize;
 use work_item::WorkItem;
 use worker::Worker;
@@ -73,3 +84,53 @@ pub fn process(resources: &Resources, options: Options) -> DarkluaResult<WorkerT
 
     Ok(worker_tree)
 }
+
+/// The location of the code given to [`process_code`].
+#[cfg(feature = "wasm")]
+const PROCESS_CODE_PATH: &str = "main.lua";
+/// The location of the configuration given to [`process_code`].
+#[cfg(feature = "wasm")]
+const PROCESS_CODE_CONFIGURATION_PATH: &str = ".darklua.json";
+
+/// Process Lua code with a configuration written in JSON (or JSON5), and return the
+/// generated code. When the configuration is empty, the default configuration is used.
+///
+/// The code and the configuration are only kept in memory, which makes this function
+/// suitable for environments without a file system (like a WebAssembly module running
+/// in a browser). This function is only available with the `wasm` feature.
+///
+/// When the code cannot be processed, the diagnostics describing each problem are
+/// returned instead.
+///
+/// ```rust
+/// let code = darklua_core::process_code("return 1 + 1", "{ rules: [] }").unwrap();
+///
+/// assert_eq!(code, "return 1 + 1");
+/// ```
+#[cfg(feature = "wasm")]
+pub fn process_code(code: &str, config_json: &str) -> Result<String, Vec<Diagnostic>> {
+    let resources = Resources::from_memory_files([(PROCESS_CODE_PATH, code)]);
+
+    if !config_json.trim().is_empty() {
+        resources
+            .write(PROCESS_CODE_CONFIGURATION_PATH, config_json)
+            .map_err(|err| DarkluaError::from(err).diagnostics())?;
+    }
+
+    let worker_tree =
+        process(&resources, Options::new(PROCESS_CODE_PATH)).map_err(|err| err.diagnostics())?;
+
+    let diagnostics: Vec<_> = worker_tree
+        .collect_errors()
+        .into_iter()
+        .flat_map(DarkluaError::diagnostics)
+        .collect();
+
+    if !diagnostics.is_empty() {
+        return Err(diagnostics);
+    }
+
+    resources
+        .get(PROCESS_CODE_PATH)
+        .map_err(|err| DarkluaError::from(err).diagnostics())
+}
@@ -1,6 +1,13 @@
-use std::path::{Path, PathBuf};
+use std::{
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    thread,
+};
 
-use super::configuration::{Configuration, GeneratorParameters};
+use super::{
+    configuration::{Configuration, GeneratorParameters},
+    parse_cache::ParseCache,
+};
 
 /// Options for configuring the darklua process function. This is not
 /// the [`Configuration`] data itself.
@@ -12,6 +19,10 @@ pub struct Options {
     config_generator_override: Option<GeneratorParameters>,
     output: Option<PathBuf>,
     fail_fast: bool,
+    source_map: bool,
+    report: bool,
+    jobs: Option<usize>,
+    parse_cache: Option<ParseCache>,
 }
 
 impl Options {
@@ -23,7 +34,11 @@ impl Options {
             config: None,
             output: None,
             fail_fast: false,
+            source_map: false,
+            report: false,
+            jobs: None,
             config_generator_override: None,
+            parse_cache: None,
         }
     }
 
@@ -53,6 +68,43 @@ impl Options {
         self
     }
 
+    /// Enables source map generation.
+    ///
+    /// When enabled, a source map file (using the output path with an additional `.map`
+    /// extension) is written next to each generated file.
+    pub fn with_source_map(mut self) -> Self {
+        self.source_map = true;
+        self
+    }
+
+    /// Enables the collection of a [`ProcessingReport`](crate::ProcessingReport).
+    ///
+    /// When enabled, the time spent, the number of nodes added or removed and the number
+    /// of bytes saved by each rule is measured for every processed file. Measuring the
+    /// bytes saved generates the code after each rule, so processing is slower.
+    pub fn with_report(mut self) -> Self {
+        self.report = true;
+        self
+    }
+
+    /// Sets the maximum number of files processed at the same time.
+    ///
+    /// By default, the number of files processed at the same time matches the available
+    /// parallelism of the machine. The generated files do not depend on this value.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = Some(jobs.max(1));
+        self
+    }
+
+    /// Sets a cache for the syntax trees of the processed files.
+    ///
+    /// Files with the same content as a previously parsed file reuse its syntax tree.
+    /// The cache can be shared between multiple calls to [`process`](crate::process).
+    pub fn with_parse_cache(mut self, parse_cache: ParseCache) -> Self {
+        self.parse_cache = Some(parse_cache);
+        self
+    }
+
     /// Sets a generator override for the configuration.
     ///
     /// This will override any generator settings in the configuration file.
@@ -76,11 +128,35 @@ impl Options {
         self.fail_fast
     }
 
+    /// Checks if source maps should be generated.
+    pub fn should_generate_source_map(&self) -> bool {
+        self.source_map
+    }
+
+    /// Checks if a processing report should be collected.
+    pub fn should_report(&self) -> bool {
+        self.report
+    }
+
+    /// Gets the maximum number of files processed at the same time.
+    pub fn jobs(&self) -> usize {
+        self.jobs.unwrap_or_else(|| {
+            thread::available_parallelism()
+                .map(NonZeroUsize::get)
+                .unwrap_or(1)
+        })
+    }
+
     /// Gets the configuration file path, if set.
     pub fn configuration_path(&self) -> Option<&Path> {
         self.config_path.as_ref().map(AsRef::as_ref)
     }
 
+    /// Gets the cache for the syntax trees, if set.
+    pub fn parse_cache(&self) -> Option<&ParseCache> {
+        self.parse_cache.as_ref()
+    }
+
     /// Gets the generator override, if set.
     pub fn generator_override(&self) -> Option<&GeneratorParameters> {
         self.config_generator_override.as_ref()
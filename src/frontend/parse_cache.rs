@@ -0,0 +1,160 @@
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use indexmap::IndexMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::{nodes::Block, Parser, ParserError};
+
+const DEFAULT_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    content_hash: u64,
+    parser: Parser,
+}
+
+/// Keeps the syntax trees of the parsed files, so that processing the same content
+/// again can skip parsing.
+///
+/// Clones of a cache share the same entries. When the cache is full, the oldest
+/// entries are removed first.
+#[derive(Clone)]
+pub struct ParseCache {
+    blocks: Arc<Mutex<IndexMap<CacheKey, Block>>>,
+    capacity: usize,
+}
+
+impl Default for ParseCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl fmt::Debug for ParseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseCache")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl ParseCache {
+    /// Creates a cache that keeps at most `capacity` syntax trees.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            blocks: Default::default(),
+            capacity,
+        }
+    }
+
+    /// Returns the number of syntax trees in the cache.
+    pub fn len(&self) -> usize {
+        self.blocks.lock().unwrap().len()
+    }
+
+    /// Returns true if the cache does not contain any syntax tree.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the syntax trees from the cache.
+    pub fn clear(&self) {
+        self.blocks.lock().unwrap().clear();
+    }
+
+    pub(crate) fn parse(&self, parser: &Parser, content: &str) -> Result<Block, ParserError> {
+        let key = CacheKey {
+            content_hash: xxh3_64(content.as_bytes()),
+            parser: parser.clone(),
+        };
+
+        if let Some(block) = self.blocks.lock().unwrap().get(&key) {
+            log::trace!("found cached syntax tree");
+            return Ok(block.clone());
+        }
+
+        let block = parser.parse(content)?;
+
+        if self.capacity > 0 {
+            let mut blocks = self.blocks.lock().unwrap();
+            while blocks.len() >= self.capacity {
+                blocks.shift_remove_index(0);
+            }
+            blocks.insert(key, block.clone());
+        }
+
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_same_content_reuses_syntax_tree() {
+        let cache = ParseCache::default();
+        let parser = Parser::default();
+
+        let first = cache.parse(&parser, "return 1").unwrap();
+        let second = cache.parse(&parser, "return 1").unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn parse_with_different_parsers_stores_both_syntax_trees() {
+        let cache = ParseCache::default();
+
+        cache.parse(&Parser::default(), "return 1").unwrap();
+        cache
+            .parse(&Parser::default().preserve_tokens(), "return 1")
+            .unwrap();
+
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn parse_invalid_content_is_not_cached() {
+        let cache = ParseCache::default();
+
+        assert!(cache.parse(&Parser::default(), "return +").is_err());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn full_cache_removes_oldest_syntax_tree() {
+        let cache = ParseCache::new(2);
+        let parser = Parser::default();
+
+        cache.parse(&parser, "return 1").unwrap();
+        cache.parse(&parser, "return 2").unwrap();
+        cache.parse(&parser, "return 3").unwrap();
+
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn clones_share_syntax_trees() {
+        let cache = ParseCache::default();
+
+        cache.clone().parse(&Parser::default(), "return 1").unwrap();
+
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn clear_removes_syntax_trees() {
+        let cache = ParseCache::default();
+        cache.parse(&Parser::default(), "return 1").unwrap();
+
+        cache.clear();
+
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,159 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::{work_item::WorkItem, Resources};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ContentHash {
+    path: PathBuf,
+    hash: u64,
+}
+
+impl ContentHash {
+    fn read(path: &Path, resources: &Resources) -> Option<Self> {
+        resources.get(path).ok().map(|content| Self {
+            path: path.to_path_buf(),
+            hash: xxh3_64(content.as_bytes()),
+        })
+    }
+
+    fn is_up_to_date(&self, resources: &Resources) -> bool {
+        resources
+            .get(&self.path)
+            .map(|content| xxh3_64(content.as_bytes()) == self.hash)
+            .unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    configuration_hash: u64,
+    // `None` when processing in place, since the source is overwritten by the output
+    source: Option<ContentHash>,
+    output: ContentHash,
+    extra_outputs: Vec<PathBuf>,
+    required_content: Vec<ContentHash>,
+    external_dependencies: Vec<ContentHash>,
+}
+
+/// Remembers the results of successfully processed files, so that files can be
+/// skipped when processing them again would produce the same output.
+///
+/// An entry is reused only if the configuration, the content of the file, the
+/// content of its dependencies and its generated output are all unchanged.
+#[derive(Debug, Default)]
+pub(crate) struct ProcessCache {
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ProcessCache {
+    /// Stores the result of a work item that completed successfully.
+    pub(crate) fn insert(
+        &mut self,
+        work_item: &WorkItem,
+        configuration_hash: u64,
+        extra_outputs: Vec<PathBuf>,
+        required_content: impl Iterator<Item = PathBuf>,
+        resources: &Resources,
+    ) {
+        let source = work_item.source();
+
+        let entry = Self::read_entry(
+            work_item,
+            configuration_hash,
+            extra_outputs,
+            required_content,
+            resources,
+        );
+
+        if let Some(entry) = entry {
+            self.entries.insert(source.to_path_buf(), entry);
+        } else {
+            log::trace!("unable to cache result of `{}`", source.display());
+            self.entries.remove(source);
+        }
+    }
+
+    fn read_entry(
+        work_item: &WorkItem,
+        configuration_hash: u64,
+        extra_outputs: Vec<PathBuf>,
+        required_content: impl Iterator<Item = PathBuf>,
+        resources: &Resources,
+    ) -> Option<CacheEntry> {
+        Some(CacheEntry {
+            configuration_hash,
+            source: if work_item.data.is_in_place() {
+                None
+            } else {
+                Some(ContentHash::read(work_item.source(), resources)?)
+            },
+            output: ContentHash::read(work_item.data.output(), resources)?,
+            extra_outputs,
+            required_content: required_content
+                .map(|path| ContentHash::read(&path, resources))
+                .collect::<Option<_>>()?,
+            external_dependencies: work_item
+                .external_file_dependencies
+                .iter()
+                .map(|path| ContentHash::read(path, resources))
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// Completes the given work item from the cache if none of its inputs changed.
+    /// Returns true when the cached result was reused.
+    pub(crate) fn restore(
+        &self,
+        work_item: &mut WorkItem,
+        configuration_hash: u64,
+        resources: &Resources,
+    ) -> bool {
+        let entry = match self.entries.get(work_item.source()) {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        if entry.configuration_hash != configuration_hash
+            || entry.output.path != work_item.data.output()
+        {
+            return false;
+        }
+
+        let is_up_to_date = entry
+            .source
+            .as_ref()
+            .map(|source| source.is_up_to_date(resources))
+            .unwrap_or(true)
+            && entry.output.is_up_to_date(resources)
+            && entry
+                .extra_outputs
+                .iter()
+                .all(|path| resources.exists(path).unwrap_or(false))
+            && entry
+                .required_content
+                .iter()
+                .chain(entry.external_dependencies.iter())
+                .all(|dependency| dependency.is_up_to_date(resources));
+
+        if is_up_to_date {
+            work_item.external_file_dependencies.extend(
+                entry
+                    .external_dependencies
+                    .iter()
+                    .map(|dependency| dependency.path.clone()),
+            );
+        }
+
+        is_up_to_date
+    }
+
+    /// Removes the cached result of a source file.
+    pub(crate) fn remove(&mut self, source: &Path) {
+        self.entries.remove(source);
+    }
+}
@@ -0,0 +1,367 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{
+    nodes::{Block, Expression, LastStatement, Statement, Type},
+    process::{DefaultVisitor, NodeProcessor, NodeVisitor},
+};
+
+#[derive(Default)]
+struct NodeCount {
+    count: usize,
+}
+
+impl NodeProcessor for NodeCount {
+    fn process_statement(&mut self, _: &mut Statement) {
+        self.count += 1;
+    }
+
+    fn process_last_statement(&mut self, _: &mut LastStatement) {
+        self.count += 1;
+    }
+
+    fn process_expression(&mut self, _: &mut Expression) {
+        self.count += 1;
+    }
+
+    fn process_type(&mut self, _: &mut Type) {
+        self.count += 1;
+    }
+}
+
+/// Counts the statements, expressions and types of a block.
+pub(crate) fn count_nodes(block: &mut Block) -> usize {
+    let mut counter = NodeCount::default();
+    DefaultVisitor::visit_block(block, &mut counter);
+    counter.count
+}
+
+/// The metrics collected while applying a rule to a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleReport {
+    rule: String,
+    duration: Duration,
+    node_count_difference: usize,
+    bytes_before: usize,
+    bytes_after: usize,
+}
+
+impl RuleReport {
+    pub(crate) fn new(
+        rule: impl Into<String>,
+        duration: Duration,
+        nodes_before: usize,
+        nodes_after: usize,
+        bytes_before: usize,
+        bytes_after: usize,
+    ) -> Self {
+        Self {
+            rule: rule.into(),
+            duration,
+            node_count_difference: nodes_before.abs_diff(nodes_after),
+            bytes_before,
+            bytes_after,
+        }
+    }
+
+    /// Returns the name of the rule.
+    pub fn rule_name(&self) -> &str {
+        &self.rule
+    }
+
+    /// Returns the time spent applying the rule.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Returns the difference between the number of nodes (statements, expressions
+    /// and types) before and after the rule was applied. Rules that only rewrite
+    /// nodes without adding or removing any (like renaming variables) have a
+    /// difference of zero.
+    pub fn node_count_difference(&self) -> usize {
+        self.node_count_difference
+    }
+
+    /// Returns the size of the generated code before the rule was applied.
+    pub fn bytes_before(&self) -> usize {
+        self.bytes_before
+    }
+
+    /// Returns the size of the generated code after the rule was applied.
+    pub fn bytes_after(&self) -> usize {
+        self.bytes_after
+    }
+
+    /// Returns the number of bytes removed from the generated code by the rule. The
+    /// value is negative when the rule made the generated code larger.
+    pub fn bytes_saved(&self) -> i64 {
+        self.bytes_before as i64 - self.bytes_after as i64
+    }
+
+    fn merge(&mut self, other: &Self) {
+        self.duration += other.duration;
+        self.node_count_difference += other.node_count_difference;
+        self.bytes_before += other.bytes_before;
+        self.bytes_after += other.bytes_after;
+    }
+}
+
+impl Serialize for RuleReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RuleReport", 6)?;
+        state.serialize_field("rule", &self.rule)?;
+        state.serialize_field("node_count_difference", &self.node_count_difference)?;
+        state.serialize_field("bytes_before", &self.bytes_before)?;
+        state.serialize_field("bytes_after", &self.bytes_after)?;
+        state.serialize_field("bytes_saved", &self.bytes_saved())?;
+        state.serialize_field("duration_ms", &(self.duration.as_secs_f64() * 1000.0))?;
+        state.end()
+    }
+}
+
+/// The metrics collected for each rule applied to a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    source: PathBuf,
+    rules: Vec<RuleReport>,
+}
+
+impl FileReport {
+    pub(crate) fn new(source: impl Into<PathBuf>, rules: Vec<RuleReport>) -> Self {
+        Self {
+            source: source.into(),
+            rules,
+        }
+    }
+
+    /// Returns the path of the processed file.
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Returns an iterator over the reports of each rule, in the order the rules
+    /// were applied.
+    pub fn iter_rules(&self) -> impl Iterator<Item = &RuleReport> {
+        self.rules.iter()
+    }
+}
+
+/// A report of the metrics collected for each rule while processing files.
+///
+/// Reports are only collected when the [`Options`](crate::Options) enable them with
+/// [`Options::with_report`](crate::Options::with_report). Get the report after
+/// processing with [`WorkerTree::report`](crate::WorkerTree::report).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProcessingReport {
+    files: Vec<FileReport>,
+}
+
+impl ProcessingReport {
+    pub(crate) fn new(mut files: Vec<FileReport>) -> Self {
+        files.sort_by(|a, b| a.source.cmp(&b.source));
+        Self { files }
+    }
+
+    /// Returns an iterator over the reports of each processed file, sorted by path.
+    pub fn iter_files(&self) -> impl Iterator<Item = &FileReport> {
+        self.files.iter()
+    }
+
+    /// Returns the metrics of each rule added up over all the processed files, in
+    /// the order the rules were first applied.
+    pub fn rule_totals(&self) -> Vec<RuleReport> {
+        let mut totals: Vec<RuleReport> = Vec::new();
+
+        for rule in self.files.iter().flat_map(FileReport::iter_rules) {
+            if let Some(total) = totals
+                .iter_mut()
+                .find(|total| total.rule_name() == rule.rule_name())
+            {
+                total.merge(rule);
+            } else {
+                totals.push(rule.clone());
+            }
+        }
+
+        totals
+    }
+
+    /// Returns true when no file reports were collected.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Serializes the report to a JSON string.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("report should serialize to json")
+    }
+}
+
+impl Serialize for ProcessingReport {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("ProcessingReport", 2)?;
+        state.serialize_field("files", &self.files)?;
+        state.serialize_field("rules", &self.rule_totals())?;
+        state.end()
+    }
+}
+
+fn write_rules<'a>(
+    f: &mut fmt::Formatter<'_>,
+    rules: impl Iterator<Item = &'a RuleReport>,
+) -> fmt::Result {
+    for rule in rules {
+        writeln!(
+            f,
+            "  {:<32} {:>8} nodes added or removed {:>10} bytes saved {:>12}",
+            rule.rule_name(),
+            rule.node_count_difference(),
+            rule.bytes_saved(),
+            durationfmt::to_string(rule.duration()),
+        )?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for ProcessingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for file in self.files.iter() {
+            writeln!(f, "`{}`", file.source().display())?;
+            write_rules(f, file.iter_rules())?;
+        }
+
+        if !self.files.is_empty() {
+            writeln!(f, "total")?;
+            write_rules(f, self.rule_totals().iter())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn report() -> ProcessingReport {
+        ProcessingReport::new(vec![
+            FileReport::new(
+                "src/b.lua",
+                vec![
+                    RuleReport::new("remove_comments", Duration::from_millis(2), 10, 10, 50, 30),
+                    RuleReport::new("remove_spaces", Duration::from_millis(1), 10, 10, 30, 20),
+                ],
+            ),
+            FileReport::new(
+                "src/a.lua",
+                vec![RuleReport::new(
+                    "remove_comments",
+                    Duration::from_millis(3),
+                    12,
+                    8,
+                    40,
+                    45,
+                )],
+            ),
+        ])
+    }
+
+    #[test]
+    fn files_are_sorted_by_path() {
+        let report = report();
+
+        let sources: Vec<_> = report.iter_files().map(FileReport::source).collect();
+
+        assert_eq!(
+            sources,
+            vec![Path::new("src/a.lua"), Path::new("src/b.lua")]
+        );
+    }
+
+    #[test]
+    fn bytes_saved_is_negative_when_code_grows() {
+        let rule = RuleReport::new("rule", Duration::default(), 0, 0, 10, 12);
+
+        assert_eq!(rule.bytes_saved(), -2);
+    }
+
+    #[test]
+    fn rule_totals_add_up_files() {
+        let totals = report().rule_totals();
+
+        assert_eq!(
+            totals,
+            vec![
+                RuleReport::new("remove_comments", Duration::from_millis(5), 4, 0, 90, 75),
+                RuleReport::new("remove_spaces", Duration::from_millis(1), 0, 0, 30, 20),
+            ]
+        );
+    }
+
+    #[test]
+    fn serialize_to_json() {
+        insta::assert_snapshot!(report().to_json(), @r###"
+        {
+          "files": [
+            {
+              "source": "src/a.lua",
+              "rules": [
+                {
+                  "rule": "remove_comments",
+                  "node_count_difference": 4,
+                  "bytes_before": 40,
+                  "bytes_after": 45,
+                  "bytes_saved": -5,
+                  "duration_ms": 3.0
+                }
+              ]
+            },
+            {
+              "source": "src/b.lua",
+              "rules": [
+                {
+                  "rule": "remove_comments",
+                  "node_count_difference": 0,
+                  "bytes_before": 50,
+                  "bytes_after": 30,
+                  "bytes_saved": 20,
+                  "duration_ms": 2.0
+                },
+                {
+                  "rule": "remove_spaces",
+                  "node_count_difference": 0,
+                  "bytes_before": 30,
+                  "bytes_after": 20,
+                  "bytes_saved": 10,
+                  "duration_ms": 1.0
+                }
+              ]
+            }
+          ],
+          "rules": [
+            {
+              "rule": "remove_comments",
+              "node_count_difference": 4,
+              "bytes_before": 90,
+              "bytes_after": 75,
+              "bytes_saved": 15,
+              "duration_ms": 5.0
+            },
+            {
+              "rule": "remove_spaces",
+              "node_count_difference": 0,
+              "bytes_before": 30,
+              "bytes_after": 20,
+              "bytes_saved": 10,
+              "duration_ms": 1.0
+            }
+          ]
+        }
+        "###);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ffi::OsStr,
+    fmt,
     fs::{self, File},
     io::{self, BufWriter, ErrorKind as IOErrorKind, Write},
     iter,
@@ -14,6 +15,8 @@ use crate::utils::normalize_path;
 enum Source {
     FileSystem,
     Memory(Arc<Mutex<HashMap<PathBuf, String>>>),
+    /// Reads from the file system, but keeps written files in memory.
+    DryRun(Arc<Mutex<HashMap<PathBuf, String>>>),
 }
 
 impl Source {
@@ -21,6 +24,10 @@ impl Source {
         match self {
             Self::FileSystem => Ok(location.exists()),
             Self::Memory(data) => Ok(data.lock().unwrap().contains_key(&normalize_path(location))),
+            Self::DryRun(data) => {
+                Ok(data.lock().unwrap().contains_key(&normalize_path(location))
+                    || location.exists())
+            }
         }
     }
 
@@ -34,6 +41,15 @@ impl Source {
                 data.iter()
                     .any(|(path, _content)| path != &location && path.starts_with(&location))
             }
+            Source::DryRun(data) => {
+                location.is_dir() || {
+                    let data = data.lock().unwrap();
+                    let location = normalize_path(location);
+
+                    data.iter()
+                        .any(|(path, _content)| path != &location && path.starts_with(&location))
+                }
+            }
         };
         Ok(is_directory)
     }
@@ -47,16 +63,16 @@ impl Source {
 
                 data.contains_key(&location)
             }
+            Source::DryRun(data) => {
+                data.lock().unwrap().contains_key(&normalize_path(location)) || location.is_file()
+            }
         };
         Ok(is_file)
     }
 
     pub fn get(&self, location: &Path) -> ResourceResult<String> {
         match self {
-            Self::FileSystem => fs::read_to_string(location).map_err(|err| match err.kind() {
-                IOErrorKind::NotFound => ResourceError::not_found(location),
-                _ => ResourceError::io_error(location, err),
-            }),
+            Self::FileSystem => read_file_system(location),
             Self::Memory(data) => {
                 let data = data.lock().unwrap();
                 let location = normalize_path(location);
@@ -65,6 +81,14 @@ impl Source {
                     .map(String::from)
                     .ok_or_else(|| ResourceError::not_found(location))
             }
+            Self::DryRun(data) => {
+                let written = data.lock().unwrap().get(&normalize_path(location)).cloned();
+
+                match written {
+                    Some(content) => Ok(content),
+                    None => read_file_system(location),
+                }
+            }
         }
     }
 
@@ -83,7 +107,7 @@ impl Source {
                 file.write_all(content.as_bytes())
                     .map_err(|err| ResourceError::io_error(location, err))
             }
-            Self::Memory(data) => {
+            Self::Memory(data) | Self::DryRun(data) => {
                 let mut data = data.lock().unwrap();
                 data.insert(normalize_path(location), content.to_string());
                 Ok(())
@@ -103,6 +127,25 @@ impl Source {
 
                 Box::new(paths.into_iter())
             }
+            Self::DryRun(data) => {
+                let data = data.lock().unwrap();
+                let normalized_location = normalize_path(location);
+                let mut written_paths: Vec<_> = data
+                    .keys()
+                    .filter(|path| path.starts_with(&normalized_location) && !path.exists())
+                    .cloned()
+                    .collect();
+                written_paths.sort();
+
+                // the location may only exist in memory
+                let file_system_paths = location
+                    .exists()
+                    .then(|| walk_file_system(location.to_path_buf()))
+                    .into_iter()
+                    .flatten();
+
+                Box::new(file_system_paths.chain(written_paths))
+            }
         }
     }
 
@@ -120,7 +163,8 @@ impl Source {
                     Ok(())
                 }
             }
-            Self::Memory(data) => {
+            // nothing is removed from the file system, only the written files are forgotten
+            Self::Memory(data) | Self::DryRun(data) => {
                 if self.is_file(location)? {
                     let mut data = data.lock().unwrap();
                     data.remove(&normalize_path(location));
@@ -136,6 +180,13 @@ impl Source {
     }
 }
 
+fn read_file_system(location: &Path) -> ResourceResult<String> {
+    fs::read_to_string(location).map_err(|err| match err.kind() {
+        IOErrorKind::NotFound => ResourceError::not_found(location),
+        _ => ResourceError::io_error(location, err),
+    })
+}
+
 fn walk_file_system(location: PathBuf) -> impl Iterator<Item = PathBuf> {
     let mut unknown_paths = vec![location];
     let mut file_paths = Vec::new();
@@ -231,6 +282,41 @@ impl Resources {
         }
     }
 
+    /// Creates a new resource manager that operates in memory, starting with the
+    /// given files.
+    ///
+    /// ```
+    /// # use darklua_core::Resources;
+    /// let resources = Resources::from_memory_files([
+    ///     ("src/main.lua", "return require('./value')"),
+    ///     ("src/value.lua", "return true"),
+    /// ]);
+    ///
+    /// assert_eq!(resources.get("src/value.lua").unwrap(), "return true");
+    /// ```
+    pub fn from_memory_files<P: Into<PathBuf>, S: Into<String>>(
+        files: impl IntoIterator<Item = (P, S)>,
+    ) -> Self {
+        let data = files
+            .into_iter()
+            .map(|(path, content)| (normalize_path(path.into()), content.into()))
+            .collect();
+
+        Self {
+            source: Source::Memory(Arc::new(Mutex::new(data))),
+        }
+    }
+
+    /// Creates a new resource manager that reads from the file system, but keeps
+    /// written files in memory instead of writing them to disk.
+    ///
+    /// Use [`Resources::collect_changes`] to find which files would change.
+    pub fn from_file_system_dry_run() -> Self {
+        Self {
+            source: Source::DryRun(Arc::new(Mutex::new(HashMap::new()))),
+        }
+    }
+
     /// Collects all Lua and Luau files in the specified location.
     pub fn collect_work(&self, location: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
         self.source.walk(location.as_ref()).filter(|path| {
@@ -275,9 +361,82 @@ impl Resources {
     pub fn walk(&self, location: impl AsRef<Path>) -> impl Iterator<Item = PathBuf> {
         self.source.walk(location.as_ref())
     }
+
+    /// Returns the content of every file kept in memory, sorted by path. For a resource
+    /// manager created with [`Resources::from_file_system_dry_run`], only the written
+    /// files are returned. Returns nothing for a resource manager operating on the file
+    /// system.
+    pub fn memory_files(&self) -> BTreeMap<PathBuf, String> {
+        match &self.source {
+            Source::Memory(data) | Source::DryRun(data) => data
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(path, content)| (path.clone(), content.clone()))
+                .collect(),
+            Source::FileSystem => BTreeMap::new(),
+        }
+    }
+
+    /// Returns the files written to a resource manager created with
+    /// [`Resources::from_file_system_dry_run`] that have a different content than
+    /// the files on disk, sorted by path. Returns nothing for other resource managers.
+    pub fn collect_changes(&self) -> Vec<ResourceChange> {
+        let data = match &self.source {
+            Source::DryRun(data) => data.lock().unwrap(),
+            Source::FileSystem | Source::Memory(_) => return Vec::new(),
+        };
+
+        let mut changes: Vec<_> = data
+            .iter()
+            .filter_map(|(path, content)| {
+                let original = fs::read_to_string(path).ok();
+
+                if original.as_ref() == Some(content) {
+                    None
+                } else {
+                    Some(ResourceChange {
+                        path: path.clone(),
+                        original,
+                        content: content.clone(),
+                    })
+                }
+            })
+            .collect();
+
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+}
+
+/// A file that would be created or modified by a dry run.
+///
+/// See [`Resources::collect_changes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceChange {
+    path: PathBuf,
+    original: Option<String>,
+    content: String,
+}
+
+impl ResourceChange {
+    /// The path of the changed file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The current content of the file, or `None` if the file does not exist.
+    pub fn original_content(&self) -> Option<&str> {
+        self.original.as_deref()
+    }
+
+    /// The content that would be written to the file.
+    pub fn content(&self) -> &str {
+        &self.content
+    }
 }
 
-/// An error that can occur during operations on [`Resource`].
+/// An error that can occur during operations on [`Resources`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResourceError {
     /// The requested resource was not found.
@@ -299,6 +458,19 @@ impl ResourceError {
     }
 }
 
+impl fmt::Display for ResourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "unable to find `{}`", path.display()),
+            Self::IO { path, error } => {
+                write!(f, "IO error with `{}`: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ResourceError {}
+
 /// A type alias for `Result<T, ResourceError>`.
 type ResourceResult<T> = Result<T, ResourceError>;
 
@@ -368,6 +540,34 @@ mod test {
             assert_eq!(resources.get(any_path()), Ok(ANY_CONTENT.to_string()));
         }
 
+        #[test]
+        fn read_content_of_initial_file() {
+            let resources = Resources::from_memory_files([(any_path(), ANY_CONTENT)]);
+
+            assert_eq!(resources.get(any_path()), Ok(ANY_CONTENT.to_string()));
+        }
+
+        #[test]
+        fn memory_files_contains_written_files() {
+            let resources = Resources::from_memory_files([("src/a.lua", "return 1")]);
+            resources.write("out/b.lua", "return 2").unwrap();
+
+            assert_eq!(
+                resources.memory_files(),
+                BTreeMap::from_iter([
+                    (PathBuf::from("out/b.lua"), "return 2".to_owned()),
+                    (PathBuf::from("src/a.lua"), "return 1".to_owned()),
+                ])
+            );
+        }
+
+        #[test]
+        fn read_missing_file_error() {
+            let error = new().get(any_path()).unwrap_err();
+
+            assert_eq!(error.to_string(), "unable to find `test.lua`");
+        }
+
         #[test]
         fn collect_work_contains_created_files() {
             let resources = new();
@@ -379,4 +579,86 @@ mod test {
             );
         }
     }
+
+    mod dry_run {
+        use super::*;
+
+        fn new() -> Resources {
+            Resources::from_file_system_dry_run()
+        }
+
+        #[test]
+        fn written_file_is_not_created_on_disk() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            let resources = new();
+
+            resources.write(&path, ANY_CONTENT).unwrap();
+
+            assert_eq!(resources.get(&path), Ok(ANY_CONTENT.to_string()));
+            assert!(!path.exists());
+        }
+
+        #[test]
+        fn read_existing_file_from_disk() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            fs::write(&path, ANY_CONTENT).unwrap();
+
+            assert_eq!(new().get(&path), Ok(ANY_CONTENT.to_string()));
+        }
+
+        #[test]
+        fn collect_changes_of_new_file() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            let resources = new();
+
+            resources.write(&path, ANY_CONTENT).unwrap();
+
+            let changes = resources.collect_changes();
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].path(), normalize_path(&path));
+            assert_eq!(changes[0].original_content(), None);
+            assert_eq!(changes[0].content(), ANY_CONTENT);
+        }
+
+        #[test]
+        fn collect_changes_ignores_unchanged_file() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            fs::write(&path, ANY_CONTENT).unwrap();
+            let resources = new();
+
+            resources.write(&path, ANY_CONTENT).unwrap();
+
+            assert_eq!(resources.collect_changes(), Vec::new());
+        }
+
+        #[test]
+        fn collect_changes_of_modified_file() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            fs::write(&path, "return false").unwrap();
+            let resources = new();
+
+            resources.write(&path, ANY_CONTENT).unwrap();
+
+            let changes = resources.collect_changes();
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].original_content(), Some("return false"));
+            assert_eq!(changes[0].content(), ANY_CONTENT);
+        }
+
+        #[test]
+        fn remove_does_not_delete_file_on_disk() {
+            let directory = tempfile::tempdir().unwrap();
+            let path = directory.path().join(any_path());
+            fs::write(&path, ANY_CONTENT).unwrap();
+
+            new().remove(&path).unwrap();
+
+            assert!(path.exists());
+        }
+    }
 }
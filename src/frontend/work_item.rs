@@ -3,9 +3,9 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::{nodes::Block, utils::Timer};
+use crate::{nodes::Block, rules::RuleDirectives, utils::Timer};
 
-use super::{DarkluaError, DarkluaResult};
+use super::{report::RuleReport, DarkluaError, DarkluaResult};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct Progress {
@@ -54,13 +54,15 @@ impl Progress {
 pub(crate) struct WorkProgress {
     pub(crate) content: String,
     pub(crate) progress: Progress,
+    pub(crate) directives: RuleDirectives,
 }
 
 impl WorkProgress {
-    pub(crate) fn new(content: String, block: Block) -> Self {
+    pub(crate) fn new(content: String, block: Block, directives: RuleDirectives) -> Self {
         Self {
             content,
             progress: Progress::new(block),
+            directives,
         }
     }
 
@@ -122,6 +124,7 @@ pub(crate) struct WorkItem {
     pub(crate) data: WorkData,
     pub(crate) status: WorkStatus,
     pub(crate) external_file_dependencies: HashSet<PathBuf>,
+    pub(crate) rule_reports: Vec<RuleReport>,
 }
 
 impl WorkItem {
@@ -133,6 +136,7 @@ impl WorkItem {
             },
             status: Default::default(),
             external_file_dependencies: Default::default(),
+            rule_reports: Vec::new(),
         }
     }
 
@@ -155,5 +159,6 @@ impl WorkItem {
     pub(crate) fn reset(&mut self) {
         self.status = WorkStatus::NotStarted;
         self.external_file_dependencies.clear();
+        self.rule_reports.clear();
     }
 }
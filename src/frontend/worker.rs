@@ -1,38 +1,62 @@
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
 
 use super::{
-    configuration::Configuration,
+    configuration::{self, Configuration},
+    parse_cache::ParseCache,
+    report::{count_nodes, RuleReport},
     resources::Resources,
     utils::maybe_plural,
     work_cache::WorkCache,
-    work_item::{WorkItem, WorkProgress, WorkStatus},
+    work_item::{WorkData, WorkItem, WorkProgress, WorkStatus},
     DarkluaError, DarkluaResult, Options,
 };
 
 use crate::{
+    generator::SourceMap,
     nodes::Block,
-    rules::{bundle::Bundler, ContextBuilder, Rule, RuleConfiguration},
+    rules::{
+        bundle::Bundler, may_contain_directives, writes_name_map, ContextBuilder, GitValues, Rule,
+        RuleConfiguration, RuleDirectives,
+    },
     utils::{normalize_path, Timer},
     GeneratorParameters,
 };
 
 const DEFAULT_CONFIG_PATHS: [&str; 2] = [".darklua.json", ".darklua.json5"];
 
+fn parse_configuration_value(config: &Path, content: &str) -> DarkluaResult<serde_json::Value> {
+    json5::from_str(content)
+        .map_err(|err| DarkluaError::invalid_configuration_file(config).context(err.to_string()))
+}
+
 #[derive(Debug)]
 pub(crate) struct Worker<'a> {
     resources: &'a Resources,
-    cache: WorkCache<'a>,
     configuration: Configuration,
     cached_bundler: Option<Bundler>,
+    generate_source_map: bool,
+    report: bool,
+    parse_cache: Option<ParseCache>,
+    git_values: Arc<GitValues>,
 }
 
 impl<'a> Worker<'a> {
     pub(crate) fn new(resources: &'a Resources) -> Self {
         Self {
             resources,
-            cache: WorkCache::new(resources),
             configuration: Configuration::default(),
             cached_bundler: None,
+            generate_source_map: false,
+            report: false,
+            parse_cache: None,
+            git_values: Default::default(),
         }
     }
 
@@ -88,6 +112,21 @@ impl<'a> Worker<'a> {
             }
         };
 
+        self.configuration.add_target_rules();
+
+        // git information is read again for each process, so that it stays up to date
+        // when watching files
+        self.git_values = Arc::new(GitValues::read(
+            self.configuration.all_rules(),
+            self.configuration
+                .location()
+                .unwrap_or_else(|| Path::new("")),
+        ));
+
+        self.generate_source_map = options.should_generate_source_map();
+        self.report = options.should_report();
+        self.parse_cache = options.parse_cache().cloned();
+
         if let Some(generator) = options.generator_override() {
             log::trace!(
                 "override with {} generator",
@@ -95,13 +134,23 @@ impl<'a> Worker<'a> {
                     GeneratorParameters::RetainLines => "`retain_lines`".to_owned(),
                     GeneratorParameters::Dense { column_span } =>
                         format!("dense ({})", column_span),
-                    GeneratorParameters::Readable { column_span } =>
+                    GeneratorParameters::Readable { column_span, .. } =>
                         format!("readable ({})", column_span),
+                    GeneratorParameters::MinimalDiff { column_span } =>
+                        format!("minimal_diff ({})", column_span),
                 }
             );
             self.configuration.set_generator(generator.clone());
         }
 
+        self.cached_bundler = self.configuration.bundle().map(|bundler| {
+            if self.generate_source_map {
+                bundler.with_preserved_tokens()
+            } else {
+                bundler
+            }
+        });
+
         log::trace!(
             "configuration setup in {}",
             configuration_setup_timer.duration_label()
@@ -120,57 +169,268 @@ impl<'a> Worker<'a> {
         &self.configuration
     }
 
-    pub(crate) fn advance_work(&mut self, work_item: &mut WorkItem) -> DarkluaResult<()> {
+    pub(crate) fn generates_source_map(&self) -> bool {
+        self.generate_source_map
+    }
+
+    pub(crate) fn git_values(&self) -> &GitValues {
+        &self.git_values
+    }
+
+    /// Returns the files written next to the output of a work item.
+    pub(crate) fn extra_outputs(&self, data: &WorkData) -> Vec<PathBuf> {
+        if self.generate_source_map {
+            vec![get_source_map_path(data.output())]
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Advances the given work items, spreading them over at most `jobs` threads.
+    ///
+    /// The work items are split into contiguous chunks (one per thread) and each chunk
+    /// is processed in order, so the work done only depends on the items and the number
+    /// of jobs. Errors are stored in the status of the failing work items. When `fail_fast`
+    /// is true, only the first error (in the order of the work items) is stored, like when
+    /// the items are processed one by one: the threads stop before processing an item
+    /// that comes after an item that failed.
+    pub(crate) fn advance_batch(
+        &self,
+        cache: &WorkCache,
+        work_items: &mut [&mut WorkItem],
+        jobs: usize,
+        fail_fast: bool,
+    ) {
+        let first_error = AtomicUsize::new(usize::MAX);
+
+        let mut errors = if jobs <= 1 || work_items.len() <= 1 {
+            self.advance_chunk(cache, work_items, 0, fail_fast.then_some(&first_error))
+        } else {
+            let chunk_size = work_items.len().div_ceil(jobs);
+
+            log::trace!(
+                "advance {} work items using {} threads",
+                work_items.len(),
+                work_items.len().div_ceil(chunk_size)
+            );
+
+            let first_error = fail_fast.then_some(&first_error);
+
+            thread::scope(|scope| {
+                let handles: Vec<_> = work_items
+                    .chunks_mut(chunk_size)
+                    .enumerate()
+                    .map(|(chunk_index, chunk)| {
+                        // the cache of parsed blocks is not shared between threads, so each
+                        // one receives a copy of the outputs available from the main cache
+                        let cache = cache.clone();
+                        let offset = chunk_index * chunk_size;
+                        scope.spawn(move || self.advance_chunk(&cache, chunk, offset, first_error))
+                    })
+                    .collect();
+
+                handles
+                    .into_iter()
+                    .flat_map(|handle| handle.join().expect("worker thread should not panic"))
+                    .collect::<Vec<_>>()
+            })
+        };
+
+        if fail_fast {
+            // the errors are ordered by work item, so the first error is the same
+            // as when the items are processed one by one
+            errors.truncate(1);
+        }
+
+        for (index, err) in errors {
+            let work_item = &mut work_items[index];
+            log::error!(
+                "an error happened while processing {}: {}",
+                work_item.source().display(),
+                err
+            );
+            work_item.status = WorkStatus::err(err);
+        }
+    }
+
+    /// Advances the work items of a chunk and returns the errors with the index of their
+    /// work item in the batch. When `first_error` is given, the chunk stops after an error
+    /// or when an item that comes before in the batch has failed.
+    fn advance_chunk(
+        &self,
+        cache: &WorkCache,
+        work_items: &mut [&mut WorkItem],
+        offset: usize,
+        first_error: Option<&AtomicUsize>,
+    ) -> Vec<(usize, DarkluaError)> {
+        let mut errors = Vec::new();
+
+        for (chunk_index, work_item) in work_items.iter_mut().enumerate() {
+            let index = offset + chunk_index;
+
+            if first_error.is_some_and(|first_error| first_error.load(Ordering::SeqCst) < index) {
+                break;
+            }
+
+            if let Err(err) = self.advance_work(cache, work_item) {
+                errors.push((index, err));
+
+                if let Some(first_error) = first_error {
+                    first_error.fetch_min(index, Ordering::SeqCst);
+                    break;
+                }
+            }
+        }
+
+        errors
+    }
+
+    fn advance_work(&self, cache: &WorkCache, work_item: &mut WorkItem) -> DarkluaResult<()> {
         match &work_item.status {
             WorkStatus::NotStarted => {
                 let source_display = work_item.source().display();
 
                 let content = self.resources.get(work_item.source())?;
 
-                let parser = self.configuration.build_parser();
+                let has_directives = may_contain_directives(&content);
+                let writes_name_map = self
+                    .configuration
+                    .rules_for(work_item.source())
+                    .any(writes_name_map);
+
+                let parser = if self.generate_source_map || has_directives || writes_name_map {
+                    // the generators need the tokens to find the original lines,
+                    // directives are read from the comments and name maps include
+                    // the lines of the renamed variables
+                    self.configuration.build_parser().preserve_tokens()
+                } else {
+                    self.configuration.build_parser()
+                };
 
                 log::debug!("beginning work on `{}`", source_display);
 
                 let parser_timer = Timer::now();
 
-                let mut block = parser.parse(&content).map_err(|parser_error| {
+                let parsed = match &self.parse_cache {
+                    Some(parse_cache) => parse_cache.parse(&parser, &content),
+                    None => parser.parse(&content),
+                };
+
+                let mut block = parsed.map_err(|parser_error| {
                     DarkluaError::parser_error(work_item.source(), parser_error)
                 })?;
 
                 let parser_time = parser_timer.duration_label();
                 log::debug!("parsed `{}` in {}", source_display, parser_time);
 
+                let directives = if has_directives {
+                    RuleDirectives::from_block(&mut block, &content)
+                } else {
+                    RuleDirectives::default()
+                };
+
                 self.bundle(work_item, &mut block, &content)?;
 
-                work_item.status = WorkProgress::new(content, block).into();
+                work_item.status = WorkProgress::new(content, block, directives).into();
 
-                self.apply_rules(work_item)
+                self.apply_rules(cache, work_item)
             }
-            WorkStatus::InProgress(_work_progress) => self.apply_rules(work_item),
+            WorkStatus::InProgress(_work_progress) => self.apply_rules(cache, work_item),
             WorkStatus::Done(_) => Ok(()),
         }
     }
 
     fn read_configuration(&self, config: &Path) -> DarkluaResult<Configuration> {
         let config_content = self.resources.get(config)?;
-        json5::from_str(&config_content)
-            .map_err(|err| {
+        let value = parse_configuration_value(config, &config_content)?;
+
+        let configuration: Configuration = if value.get("extends").is_none() {
+            json5::from_str(&config_content).map_err(|err| {
                 DarkluaError::invalid_configuration_file(config).context(err.to_string())
+            })?
+        } else {
+            let value = self.resolve_extended_configuration(
+                config,
+                value,
+                &mut vec![normalize_path(config)],
+            )?;
+            serde_json::from_value(value).map_err(|err| {
+                DarkluaError::invalid_configuration_file(config).context(err.to_string())
+            })?
+        };
+
+        Ok(configuration.with_location({
+            config.parent().unwrap_or_else(|| {
+                log::warn!(
+                    "unexpected configuration path `{}` (unable to extract parent path)",
+                    config.display()
+                );
+                config
             })
-            .map(|configuration: Configuration| {
-                configuration.with_location({
-                    config.parent().unwrap_or_else(|| {
-                        log::warn!(
-                            "unexpected configuration path `{}` (unable to extract parent path)",
-                            config.display()
-                        );
-                        config
-                    })
-                })
-            })
+        }))
     }
 
-    fn apply_rules(&mut self, work_item: &mut WorkItem) -> DarkluaResult<()> {
+    /// Merges the configuration value into the configurations listed in its `extends`
+    /// field. The `visited` list contains the configuration files currently being
+    /// extended, to detect configurations extending themselves.
+    fn resolve_extended_configuration(
+        &self,
+        config: &Path,
+        mut value: serde_json::Value,
+        visited: &mut Vec<PathBuf>,
+    ) -> DarkluaResult<serde_json::Value> {
+        let extended_paths = configuration::take_extended_paths(&mut value)
+            .map_err(|message| DarkluaError::invalid_configuration_file(config).context(message))?;
+
+        let parent = config.parent().unwrap_or_else(|| Path::new(""));
+        let mut base = None;
+
+        for extended_path in extended_paths {
+            let path = normalize_path(parent.join(extended_path));
+
+            if visited.contains(&path) {
+                return Err(
+                    DarkluaError::invalid_configuration_file(config).context(format!(
+                        "configuration extends itself through `{}`",
+                        path.display()
+                    )),
+                );
+            }
+
+            if !self.resources.exists(&path)? {
+                return Err(DarkluaError::resource_not_found(&path).context(format!(
+                    "expected to find configuration file extended by `{}`",
+                    config.display()
+                )));
+            }
+
+            let content = self.resources.get(&path)?;
+            let extended_value = parse_configuration_value(&path, &content)?;
+
+            visited.push(path.clone());
+            let extended_value =
+                self.resolve_extended_configuration(&path, extended_value, visited)?;
+            visited.pop();
+
+            log::debug!(
+                "configuration `{}` extends `{}`",
+                config.display(),
+                path.display()
+            );
+
+            base = Some(match base {
+                Some(base) => configuration::extend_configuration_value(base, extended_value),
+                None => extended_value,
+            });
+        }
+
+        Ok(match base {
+            Some(base) => configuration::extend_configuration_value(base, value),
+            None => value,
+        })
+    }
+
+    fn apply_rules(&self, cache: &WorkCache, work_item: &mut WorkItem) -> DarkluaResult<()> {
         let work_progress = match &mut work_item.status {
             WorkStatus::InProgress(progress) => progress.as_mut(),
             _ => return Ok(()),
@@ -183,14 +443,26 @@ impl<'a> Worker<'a> {
 
         progress.duration().start();
 
+        // the metrics of the current code are computed again when resuming the work,
+        // since the rules may have been paused to wait for other work items
+        let mut current_metrics = if self.report {
+            let bytes = self
+                .configuration
+                .generate_lua(progress.block(), &work_progress.content)
+                .len();
+            Some((count_nodes(progress.mutate_block()), bytes))
+        } else {
+            None
+        };
+
         for (index, rule) in self
             .configuration
-            .rules()
+            .rules_for(work_item.data.source())
             .enumerate()
             .skip(progress.next_rule())
         {
             let mut context_builder =
-                self.create_rule_context(work_item.data.source(), &work_progress.content);
+                self.create_rule_context(&work_item.data, &work_progress.content);
             log::trace!(
                 "[{}] apply rule `{}`{}",
                 source_display,
@@ -218,13 +490,10 @@ impl<'a> Worker<'a> {
             required_content.dedup();
 
             if !required_content.is_empty() {
-                if required_content
-                    .iter()
-                    .all(|path| self.cache.contains(path))
-                {
+                if required_content.iter().all(|path| cache.contains(path)) {
                     let parser = self.configuration.build_parser();
                     for path in required_content.iter() {
-                        let block = self.cache.get_block(path, &parser)?;
+                        let block = cache.get_block(path, &parser)?;
                         context_builder.insert_block(path, block);
                     }
                 } else {
@@ -260,26 +529,53 @@ impl<'a> Worker<'a> {
 
             let source = work_item.data.source();
 
-            let rule_result = rule.process(block, &context).map_err(|rule_error| {
-                let error = DarkluaError::rule_error(source, rule, index, rule_error);
+            let rule_result = work_progress
+                .directives
+                .process_rule(rule, block, &context)
+                .map_err(|rule_error| {
+                    let error = DarkluaError::rule_error(source, rule, index, rule_error);
+
+                    log::trace!(
+                        "[{}] rule `{}` errored: {}",
+                        source_display,
+                        rule.get_name(),
+                        error
+                    );
 
-                log::trace!(
-                    "[{}] rule `{}` errored: {}",
-                    source_display,
-                    rule.get_name(),
                     error
-                );
+                });
 
-                error
-            });
+            let output_files = context.take_output_files();
 
             work_item
                 .external_file_dependencies
                 .extend(context.into_dependencies());
 
+            let rule_duration = rule_timer.duration();
+
             rule_result?;
 
-            let rule_duration = rule_timer.duration_label();
+            self.write_output_files(output_files)?;
+
+            if let Some((nodes_before, bytes_before)) = current_metrics {
+                let nodes_after = count_nodes(progress.mutate_block());
+                let bytes_after = self
+                    .configuration
+                    .generate_lua(progress.block(), &work_progress.content)
+                    .len();
+
+                work_item.rule_reports.push(RuleReport::new(
+                    rule.get_name(),
+                    rule_duration,
+                    nodes_before,
+                    nodes_after,
+                    bytes_before,
+                    bytes_after,
+                ));
+                current_metrics = Some((nodes_after, bytes_after));
+            }
+
+            let rule_duration = durationfmt::to_string(rule_duration);
             log::trace!(
                 "[{}] ⨽completed `{}` in {}",
                 source_display,
@@ -289,7 +585,7 @@ impl<'a> Worker<'a> {
         }
 
         let rule_time = progress.duration().duration_label();
-        let total_rules = self.configuration.rules_len();
+        let total_rules = self.configuration.rules_len_for(work_item.data.source());
         log::debug!(
             "{} rule{} applied in {} for `{}`",
             total_rules,
@@ -311,9 +607,17 @@ impl<'a> Worker<'a> {
 
         let generator_timer = Timer::now();
 
-        let lua_code = self
-            .configuration
-            .generate_lua(progress.block(), &work_progress.content);
+        let (lua_code, source_map) = if self.generate_source_map {
+            let (lua_code, source_map) = self
+                .configuration
+                .generate_lua_with_source_map(progress.block(), &work_progress.content);
+            (lua_code, Some(source_map))
+        } else {
+            let lua_code = self
+                .configuration
+                .generate_lua(progress.block(), &work_progress.content);
+            (lua_code, None)
+        };
 
         let generator_time = generator_timer.duration_label();
         log::debug!(
@@ -324,19 +628,81 @@ impl<'a> Worker<'a> {
 
         self.resources.write(work_item.data.output(), &lua_code)?;
 
-        self.cache
-            .link_source_to_output(normalized_source, work_item.data.output());
+        if let Some(source_map) = source_map {
+            self.write_source_map(&work_item.data, &source_map, &work_progress.content)?;
+        }
 
         work_item.status = WorkStatus::done();
         Ok(())
     }
 
+    fn write_source_map(
+        &self,
+        data: &WorkData,
+        source_map: &SourceMap,
+        content: &str,
+    ) -> DarkluaResult<()> {
+        let output = data.output();
+        let source_map_path = get_source_map_path(output);
+
+        let file_name = output
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let output_parent = output.parent().map(normalize_path);
+        let get_source_path = |path: &Path| {
+            let path = normalize_path(path);
+            output_parent
+                .as_ref()
+                .and_then(|parent| pathdiff::diff_paths(&path, parent))
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+
+        let sources: Vec<_> = source_map
+            .iter_sources()
+            .map(|source| match source {
+                Some(path) => (get_source_path(path), self.resources.get(path).ok()),
+                None => (get_source_path(data.source()), Some(content.to_owned())),
+            })
+            .collect();
+        let sources: Vec<_> = sources
+            .iter()
+            .map(|(path, content)| (path.as_str(), content.as_deref()))
+            .collect();
+
+        log::trace!("write source map at `{}`", source_map_path.display());
+
+        self.resources
+            .write(&source_map_path, &source_map.to_json(&file_name, &sources))?;
+
+        Ok(())
+    }
+
+    fn write_output_files(&self, output_files: Vec<(PathBuf, String)>) -> DarkluaResult<()> {
+        for (path, content) in output_files {
+            log::trace!("write output file at `{}`", path.display());
+            self.resources.write(&path, &content)?;
+        }
+
+        Ok(())
+    }
+
     fn create_rule_context<'block, 'src>(
         &self,
-        source: &Path,
+        work_data: &WorkData,
         original_code: &'src str,
     ) -> ContextBuilder<'block, 'a, 'src> {
-        let builder = ContextBuilder::new(normalize_path(source), self.resources, original_code);
+        let builder = ContextBuilder::new(
+            normalize_path(work_data.source()),
+            self.resources,
+            original_code,
+        )
+        .with_output_path(normalize_path(work_data.output()))
+        .with_target(self.configuration.target())
+        .with_git_values(self.git_values.clone());
         if let Some(project_location) = self.configuration.location() {
             builder.with_project_location(project_location)
         } else {
@@ -344,17 +710,45 @@ impl<'a> Worker<'a> {
         }
     }
 
+    /// Applies the rules to a chunk of a bundle and writes it at the given path.
+    fn write_bundle_chunk(
+        &self,
+        work_data: &WorkData,
+        chunk_path: &Path,
+        mut chunk: Block,
+    ) -> DarkluaResult<()> {
+        log::debug!(
+            "process bundle chunk `{}` of `{}`",
+            chunk_path.display(),
+            work_data.source().display()
+        );
+
+        for (index, rule) in self.configuration.rules_for(work_data.source()).enumerate() {
+            let context = self
+                .create_rule_context(work_data, "")
+                .with_output_path(chunk_path)
+                .build();
+
+            rule.process(&mut chunk, &context).map_err(|rule_error| {
+                DarkluaError::rule_error(work_data.source(), rule, index, rule_error)
+            })?;
+
+            self.write_output_files(context.take_output_files())?;
+        }
+
+        let code = self.configuration.generate_lua(&chunk, "");
+
+        self.resources.write(chunk_path, &code)?;
+
+        Ok(())
+    }
+
     fn bundle(
-        &mut self,
+        &self,
         work_item: &mut WorkItem,
         block: &mut Block,
         original_code: &str,
     ) -> DarkluaResult<()> {
-        if self.cached_bundler.is_none() {
-            if let Some(bundler) = self.configuration.bundle() {
-                self.cached_bundler = Some(bundler);
-            }
-        }
         let bundler = match self.cached_bundler.as_ref() {
             Some(bundler) => bundler,
             None => return Ok(()),
@@ -365,7 +759,7 @@ impl<'a> Worker<'a> {
         let bundle_timer = Timer::now();
 
         let context = self
-            .create_rule_context(work_item.source(), original_code)
+            .create_rule_context(&work_item.data, original_code)
             .build();
 
         let rule_result = bundler.process(block, &context).map_err(|rule_error| {
@@ -381,12 +775,18 @@ impl<'a> Worker<'a> {
             error
         });
 
+        let chunks = context.take_output_chunks();
+
         work_item
             .external_file_dependencies
             .extend(context.into_dependencies());
 
         rule_result?;
 
+        for (chunk_path, chunk) in chunks {
+            self.write_bundle_chunk(&work_item.data, &chunk_path, chunk)?;
+        }
+
         let bundle_time = bundle_timer.duration_label();
         log::debug!(
             "bundled `{}` in {}",
@@ -397,3 +797,9 @@ impl<'a> Worker<'a> {
         Ok(())
     }
 }
+
+fn get_source_map_path(output: &Path) -> PathBuf {
+    let mut source_map_path = output.as_os_str().to_owned();
+    source_map_path.push(".map");
+    PathBuf::from(source_map_path)
+}
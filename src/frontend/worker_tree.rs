@@ -3,18 +3,22 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use petgraph::{algo::toposort, graph::NodeIndex, stable_graph::StableDiGraph, visit::Dfs};
+use petgraph::{
+    algo::toposort, graph::NodeIndex, stable_graph::StableDiGraph, visit::Dfs, Direction,
+};
 use xxhash_rust::xxh3::xxh3_64;
 
 use crate::{
     frontend::utils::maybe_plural,
+    rules::{is_declaration_file, writes_declaration_file},
     utils::{clear_luau_configuration_cache, Timer},
     DarkluaError,
 };
 
 use super::{
-    normalize_path, work_item::WorkStatus, Configuration, DarkluaResult, Options, Resources,
-    WorkItem, Worker,
+    normalize_path, process_cache::ProcessCache, work_cache::WorkCache, work_item::WorkStatus,
+    DarkluaResult, DependencyGraph, FileReport, Options, ProcessingReport, Resources, WorkItem,
+    Worker,
 };
 
 /// A structure that manages the processing of Lua/Luau files and their dependencies.
@@ -23,6 +27,10 @@ use super::{
 /// represents a file to be processed and edges represent dependencies between files. It handles
 /// the collection and processing of work items, manages file dependencies, and tracks the status
 /// of each work item.
+///
+/// The results of completed work items are cached, so processing the tree again (for example
+/// when watching for changes) only re-processes files when their content, the content of their
+/// dependencies or the configuration changed.
 #[derive(Debug, Default)]
 pub struct WorkerTree {
     graph: StableDiGraph<WorkItem, ()>,
@@ -30,6 +38,7 @@ pub struct WorkerTree {
     external_dependencies: HashMap<PathBuf, HashSet<NodeIndex>>,
     remove_files: Vec<PathBuf>,
     last_configuration_hash: Option<u64>,
+    process_cache: ProcessCache,
 }
 
 impl WorkerTree {
@@ -100,7 +109,9 @@ impl WorkerTree {
     /// Processes all collected work items according to the provided options.
     ///
     /// This method performs the actual processing of work items in topological order,
-    /// respecting dependencies between files.
+    /// respecting dependencies between files. The work items that do not wait on other
+    /// work items are processed in parallel (see [`Options::with_jobs`]), and their results
+    /// are collected in the same order regardless of the number of jobs.
     pub fn process(&mut self, resources: &Resources, mut options: Options) -> DarkluaResult<()> {
         clear_luau_configuration_cache();
 
@@ -122,7 +133,11 @@ impl WorkerTree {
         let mut worker = Worker::new(resources);
         worker.setup_worker(&mut options)?;
 
-        if self.has_configuration_changed(worker.configuration()) {
+        self.skip_replaced_declaration_files(&worker);
+
+        let configuration_hash = hash_configuration(&worker);
+
+        if self.has_configuration_changed(configuration_hash) {
             log::debug!("configuration change detected");
             self.reset();
         }
@@ -138,98 +153,117 @@ impl WorkerTree {
         }
 
         let work_timer = Timer::now();
+        let jobs = options.jobs();
+        let fail_fast = options.should_fail_fast();
+        let mut cache = WorkCache::new(resources);
+        let mut done_count = 0;
+
+        loop {
+            let node_indexes = toposort(&self.graph, None).map_err(|_cycle_err| {
+                DarkluaError::cyclic_work(
+                    self.graph
+                        .node_weights()
+                        .filter(|item| !item.status.is_done())
+                        .collect(),
+                )
+            })?;
+
+            // a work item is ready when all the work items it depends on are done
+            let ready_nodes: Vec<_> = node_indexes
+                .into_iter()
+                .filter(|node_index| {
+                    !self.graph[*node_index].status.is_done()
+                        && self
+                            .graph
+                            .neighbors_directed(*node_index, Direction::Incoming)
+                            .all(|required_index| self.graph[required_index].status.is_done())
+                })
+                .collect();
 
-        'work_loop: loop {
-            let mut add_edges = Vec::new();
+            let mut batch_nodes = Vec::new();
+
+            for node_index in ready_nodes {
+                let work_item = self
+                    .graph
+                    .node_weight_mut(node_index)
+                    .expect("node index should exist");
+
+                if matches!(work_item.status, WorkStatus::NotStarted)
+                    && self
+                        .process_cache
+                        .restore(work_item, configuration_hash, resources)
+                {
+                    log::debug!(
+                        "`{}` is unchanged, reusing previous output",
+                        work_item.source().display()
+                    );
+                    cache.link_source_to_output(
+                        normalize_path(work_item.source()),
+                        work_item.data.output(),
+                    );
+                    work_item.status = WorkStatus::done();
+                    done_count += 1;
+                    self.link_external_dependencies(node_index);
+                } else {
+                    batch_nodes.push(node_index);
+                }
+            }
 
-            match toposort(&self.graph, None) {
-                Ok(node_indexes) => {
-                    let mut done_count = 0;
+            self.advance_batch(&batch_nodes, &worker, &cache, jobs, fail_fast);
 
-                    for node_index in node_indexes {
-                        let work_item = self
-                            .graph
-                            .node_weight_mut(node_index)
-                            .expect("node index should exist");
-
-                        if !work_item.status.is_done() {
-                            match worker.advance_work(work_item) {
-                                Ok(()) => match &work_item.status {
-                                    WorkStatus::Done(result) => {
-                                        done_count += 1;
-                                        if result.is_ok() {
-                                            log::info!(
-                                                "successfully processed `{}`",
-                                                work_item.source().display()
-                                            );
-                                        }
-                                    }
-                                    WorkStatus::InProgress(progress) => {
-                                        for content in progress.required_content() {
-                                            if let Some(content_node_index) =
-                                                self.node_map.get(content)
-                                            {
-                                                add_edges.push((*content_node_index, node_index));
-                                            }
-                                        }
-                                        log::trace!(
-                                            "work on `{}` has not completed",
-                                            work_item.source().display()
-                                        );
-                                    }
-                                    WorkStatus::NotStarted => {}
-                                },
-                                Err(err) => {
-                                    log::error!(
-                                        "an error happened while processing {}: {}",
-                                        work_item.source().display(),
-                                        err
-                                    );
-                                    work_item.status = WorkStatus::err(err);
-                                    done_count += 1;
-                                    if options.should_fail_fast() {
-                                        log::debug!(
-                                            "dropping all work because the fail-fast option is enabled"
-                                        );
-                                        break 'work_loop;
-                                    }
-                                }
-                            }
+            let mut add_edges = Vec::new();
+            let mut has_errors = false;
+
+            for node_index in batch_nodes {
+                let work_item = &self.graph[node_index];
+                let mut completed = false;
+
+                match &work_item.status {
+                    WorkStatus::Done(result) => {
+                        done_count += 1;
+                        if result.is_ok() {
+                            completed = true;
+                            log::info!("successfully processed `{}`", work_item.source().display());
+                            cache.link_source_to_output(
+                                normalize_path(work_item.source()),
+                                work_item.data.output(),
+                            );
+                        } else {
+                            has_errors = true;
                         }
-
-                        for path in work_item.external_file_dependencies.iter() {
-                            let container = self
-                                .external_dependencies
-                                .entry(path.to_path_buf())
-                                .or_default();
-
-                            if !container.contains(&node_index) {
-                                log::trace!(
-                                    "link external dependency {} to {}",
-                                    path.display(),
-                                    work_item.source().display()
-                                );
-                                container.insert(node_index);
+                    }
+                    WorkStatus::InProgress(progress) => {
+                        for content in progress.required_content() {
+                            if let Some(content_node_index) = self.node_map.get(content) {
+                                add_edges.push((*content_node_index, node_index));
                             }
                         }
+                        log::trace!(
+                            "work on `{}` has not completed",
+                            work_item.source().display()
+                        );
                     }
+                    WorkStatus::NotStarted => {}
+                }
 
-                    log::debug!("process batch of tasks ({}/{})", done_count, total_not_done);
+                self.link_external_dependencies(node_index);
 
-                    if done_count == total_not_done {
-                        break;
-                    }
-                }
-                Err(_cycle_err) => {
-                    return Err(DarkluaError::cyclic_work(
-                        self.graph
-                            .node_weights()
-                            .filter(|item| !item.status.is_done())
-                            .collect(),
-                    ));
+                if completed {
+                    self.cache_work(node_index, configuration_hash, &worker, resources);
                 }
             }
 
+            log::debug!("process batch of tasks ({}/{})", done_count, total_not_done);
+
+            if has_errors && fail_fast {
+                log::debug!("dropping all work because the fail-fast option is enabled");
+                break;
+            }
+
+            if done_count == total_not_done {
+                break;
+            }
+
             for (from, to) in add_edges {
                 self.graph.add_edge(from, to, ());
             }
@@ -278,6 +312,53 @@ impl WorkerTree {
             .count()
     }
 
+    /// Returns the metrics collected for each rule applied to the processed files.
+    ///
+    /// The report is empty unless the options given to [`WorkerTree::process`] enabled
+    /// it with [`Options::with_report`]. Files whose previous output was reused without
+    /// applying the rules are not part of the report.
+    pub fn report(&self) -> ProcessingReport {
+        ProcessingReport::new(
+            self.graph
+                .node_weights()
+                .filter(|work_item| !work_item.rule_reports.is_empty())
+                .map(|work_item| {
+                    FileReport::new(work_item.source(), work_item.rule_reports.clone())
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the files that each processed file depends on.
+    ///
+    /// A file depends on the files read while applying its rules (like the modules
+    /// included in a bundle) and on the files it waited for. When a file changes,
+    /// [`WorkerTree::source_changed`] only restarts the work of the files that
+    /// depend on it (see [`DependencyGraph::affected_sources`]).
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let mut dependency_graph = DependencyGraph::default();
+
+        for node_index in self.graph.node_indices() {
+            let work_item = &self.graph[node_index];
+
+            let required_sources = self
+                .graph
+                .neighbors_directed(node_index, Direction::Incoming)
+                .map(|required_index| self.graph[required_index].source().to_path_buf());
+
+            dependency_graph.insert_source(
+                work_item.source(),
+                work_item
+                    .external_file_dependencies
+                    .iter()
+                    .cloned()
+                    .chain(required_sources),
+            );
+        }
+
+        dependency_graph
+    }
+
     /// Returns an iterator over all external dependencies.
     pub fn iter_external_dependencies(&self) -> impl Iterator<Item = &Path> {
         self.external_dependencies
@@ -347,12 +428,15 @@ impl WorkerTree {
 
             self.graph.remove_node(node_index);
             self.node_map.remove(&path);
+            self.process_cache.remove(&path);
         } else {
             let mut remove_nodes = Vec::new();
+            let process_cache = &mut self.process_cache;
 
             self.node_map.retain(|node_path, node_index| {
                 if node_path.starts_with(&path) {
                     remove_nodes.push(*node_index);
+                    process_cache.remove(node_path);
                     false
                 } else {
                     true
@@ -372,6 +456,34 @@ impl WorkerTree {
         self.update_external_dependencies(&path);
     }
 
+    /// Removes the declaration files (ending with `.d.luau`) processed with rules that
+    /// write declaration files, since their outputs would be replaced by the generated
+    /// declaration files.
+    fn skip_replaced_declaration_files(&mut self, worker: &Worker) {
+        let skipped: Vec<_> = self
+            .node_map
+            .iter()
+            .filter(|(path, _)| {
+                is_declaration_file(path)
+                    && worker
+                        .configuration()
+                        .rules_for(path)
+                        .any(writes_declaration_file)
+            })
+            .map(|(path, node_index)| (path.clone(), *node_index))
+            .collect();
+
+        for (path, node_index) in skipped {
+            log::debug!(
+                "skip declaration file `{}` (declaration files are generated)",
+                path.display()
+            );
+            self.graph.remove_node(node_index);
+            self.node_map.remove(&path);
+            self.process_cache.remove(&path);
+        }
+    }
+
     /// Checks if a source file is present in the worker tree.
     pub fn contains(&mut self, path: impl AsRef<Path>) -> bool {
         let path = normalize_path(path.as_ref());
@@ -420,18 +532,95 @@ impl WorkerTree {
             log::debug!("restart work for {}", item.source().display());
             for path in item.external_file_dependencies.iter() {
                 if let Some(container) = self.external_dependencies.get_mut(path) {
-                    container.remove(&node_index);
+                    container.remove(&dependent_node);
                 }
             }
             item.reset();
         }
     }
 
-    fn has_configuration_changed(&mut self, config: &Configuration) -> bool {
-        let input = serde_json::to_vec(config).ok().unwrap_or_default();
+    /// Advances the work items of the given nodes, in the order of the given nodes.
+    fn advance_batch(
+        &mut self,
+        node_indexes: &[NodeIndex],
+        worker: &Worker,
+        cache: &WorkCache,
+        jobs: usize,
+        fail_fast: bool,
+    ) {
+        let mut positions: HashMap<NodeIndex, usize> = node_indexes
+            .iter()
+            .enumerate()
+            .map(|(position, node_index)| (*node_index, position))
+            .collect();
+
+        // node weights are iterated in the same order as node indices
+        let all_node_indexes: Vec<_> = self.graph.node_indices().collect();
+
+        let mut work_items: Vec<_> = all_node_indexes
+            .into_iter()
+            .zip(self.graph.node_weights_mut())
+            .filter_map(|(node_index, work_item)| {
+                positions
+                    .remove(&node_index)
+                    .map(|position| (position, work_item))
+            })
+            .collect();
+
+        work_items.sort_by_key(|(position, _)| *position);
+
+        let mut work_items: Vec<_> = work_items
+            .into_iter()
+            .map(|(_, work_item)| work_item)
+            .collect();
+
+        worker.advance_batch(cache, &mut work_items, jobs, fail_fast);
+    }
+
+    fn link_external_dependencies(&mut self, node_index: NodeIndex) {
+        let work_item = &self.graph[node_index];
+
+        for path in work_item.external_file_dependencies.iter() {
+            let container = self
+                .external_dependencies
+                .entry(path.to_path_buf())
+                .or_default();
+
+            if !container.contains(&node_index) {
+                log::trace!(
+                    "link external dependency {} to {}",
+                    path.display(),
+                    work_item.source().display()
+                );
+                container.insert(node_index);
+            }
+        }
+    }
 
-        let new_hash = xxh3_64(&input);
+    fn cache_work(
+        &mut self,
+        node_index: NodeIndex,
+        configuration_hash: u64,
+        worker: &Worker,
+        resources: &Resources,
+    ) {
+        let graph = &self.graph;
+        let required_content = graph
+            .neighbors_directed(node_index, Direction::Incoming)
+            .map(|required_index| graph[required_index].source().to_path_buf());
+
+        let work_item = &graph[node_index];
+
+        self.process_cache.insert(
+            work_item,
+            configuration_hash,
+            worker.extra_outputs(&work_item.data),
+            required_content,
+            resources,
+        );
+    }
 
+    fn has_configuration_changed(&mut self, new_hash: u64) -> bool {
         let last_hash = self.last_configuration_hash.replace(new_hash);
 
         last_hash
@@ -439,3 +628,18 @@ impl WorkerTree {
             .unwrap_or_default()
     }
 }
+
+fn hash_configuration(worker: &Worker) -> u64 {
+    let mut input = serde_json::to_vec(worker.configuration())
+        .ok()
+        .unwrap_or_default();
+
+    input.push(u8::from(worker.generates_source_map()));
+
+    // the git information injected by rules is not part of the configuration
+    for (git_value, result) in worker.git_values().sorted_values() {
+        input.extend(format!("{:?}:{:?}", git_value, result).into_bytes());
+    }
+
+    xxh3_64(&input)
+}
@@ -1,4 +1,4 @@
-use crate::generator::{utils, LuaGenerator};
+use crate::generator::{source_map::SourceMapRecorder, utils, LuaGenerator, SourceMap};
 use crate::nodes;
 
 /// This implementation of [LuaGenerator](trait.LuaGenerator.html) attempts to produce Lua code as
@@ -9,6 +9,8 @@ pub struct DenseLuaGenerator {
     current_line_length: usize,
     output: String,
     last_push_length: usize,
+    source_map: Option<SourceMapRecorder>,
+    block_depth: usize,
 }
 
 impl DenseLuaGenerator {
@@ -20,6 +22,8 @@ impl DenseLuaGenerator {
             current_line_length: 0,
             output: String::new(),
             last_push_length: 0,
+            source_map: None,
+            block_depth: 0,
         }
     }
 
@@ -48,6 +52,7 @@ impl DenseLuaGenerator {
             self.raw_push_char(character);
         } else {
             let last_push_content = self.get_last_push_str().to_owned();
+            let last_push_offset = self.output.len() - self.last_push_length;
             (0..self.last_push_length).for_each(|_| {
                 self.output.pop();
             });
@@ -63,6 +68,9 @@ impl DenseLuaGenerator {
             }
 
             self.output.push('\n');
+            if let Some(source_map) = &mut self.source_map {
+                source_map.move_entries(last_push_offset, self.output.len());
+            }
             self.output.push_str(&last_push_content);
             self.output.push(character);
             self.last_push_length += 1;
@@ -101,6 +109,26 @@ impl DenseLuaGenerator {
         }
     }
 
+    fn write_block_content(&mut self, block: &nodes::Block) {
+        let mut statements = block.iter_statements().peekable();
+
+        while let Some(statement) = statements.next() {
+            self.write_statement(statement);
+
+            if let Some(next_statement) = statements.peek() {
+                if utils::starts_with_parenthese(next_statement)
+                    && utils::ends_with_prefix(statement)
+                {
+                    self.push_char(';');
+                }
+            }
+        }
+
+        if let Some(last_statement) = block.get_last_statement() {
+            self.write_last_statement(last_statement);
+        }
+    }
+
     #[inline]
     fn push_new_line(&mut self) {
         self.output.push('\n');
@@ -132,6 +160,33 @@ impl DenseLuaGenerator {
         self.output
     }
 
+    /// Enables the generation of a source map, obtained with
+    /// [`into_string_and_source_map`](Self::into_string_and_source_map). The
+    /// generator relies on the tokens of the AST nodes to find the original lines,
+    /// so the code must be parsed with [`Parser::preserve_tokens`](crate::Parser::preserve_tokens).
+    pub fn with_source_map(mut self) -> Self {
+        self.source_map = Some(SourceMapRecorder::default());
+        self
+    }
+
+    /// Consumes the generator and produce the generated code with its source map. The
+    /// source map is empty if it was not enabled with [`with_source_map`](Self::with_source_map).
+    pub fn into_string_and_source_map(self) -> (String, SourceMap) {
+        let output = self.output;
+        let source_map = self
+            .source_map
+            .map(|recorder| recorder.build(&output))
+            .unwrap_or_default();
+        (output, source_map)
+    }
+
+    /// Records the token of the content that was just pushed.
+    fn record_token(&mut self, token: Option<&nodes::Token>, pushed_length: usize) {
+        if let (Some(source_map), Some(token)) = (&mut self.source_map, token) {
+            source_map.record_token(self.output.len().saturating_sub(pushed_length), token);
+        }
+    }
+
     #[inline]
     fn raw_push_str(&mut self, content: &str) {
         self.output.push_str(content);
@@ -217,7 +272,9 @@ impl DenseLuaGenerator {
     }
 
     fn write_typed_identifier(&mut self, typed_identifier: &nodes::TypedIdentifier) {
-        self.push_str(typed_identifier.get_name());
+        let name = typed_identifier.get_name();
+        self.push_str(name);
+        self.record_token(typed_identifier.get_token(), name.len());
 
         if let Some(r#type) = typed_identifier.get_type() {
             self.push_char(':');
@@ -289,23 +346,30 @@ impl LuaGenerator for DenseLuaGenerator {
     }
 
     fn write_block(&mut self, block: &nodes::Block) {
-        let mut statements = block.iter_statements().peekable();
+        // the comments inserted by rules are written around the root block, since
+        // they would be lost otherwise
+        let inserted_comments =
+            (self.block_depth == 0).then(|| utils::get_block_inserted_comments(block));
+
+        if let Some((start_comments, _)) = &inserted_comments {
+            for comment in start_comments {
+                self.raw_push_str(comment);
+                self.push_new_line();
+            }
+        }
 
-        while let Some(statement) = statements.next() {
-            self.write_statement(statement);
+        self.block_depth += 1;
+        self.write_block_content(block);
+        self.block_depth -= 1;
 
-            if let Some(next_statement) = statements.peek() {
-                if utils::starts_with_parenthese(next_statement)
-                    && utils::ends_with_prefix(statement)
-                {
-                    self.push_char(';');
+        if let Some((_, end_comments)) = &inserted_comments {
+            for comment in end_comments {
+                if !self.output.is_empty() && !self.output.ends_with('\n') {
+                    self.push_new_line();
                 }
+                self.raw_push_str(comment);
             }
         }
-
-        if let Some(last_statement) = block.get_last_statement() {
-            self.write_last_statement(last_statement);
-        }
     }
 
     fn write_assign_statement(&mut self, assign: &nodes::AssignStatement) {
@@ -446,6 +510,10 @@ impl LuaGenerator for DenseLuaGenerator {
             Continue(_) => self.push_str("continue"),
             Return(expressions) => {
                 self.push_str("return");
+                self.record_token(
+                    expressions.get_tokens().map(|tokens| &tokens.r#return),
+                    "return".len(),
+                );
                 let last_index = expressions.len().saturating_sub(1);
 
                 expressions
@@ -464,6 +532,10 @@ impl LuaGenerator for DenseLuaGenerator {
 
     fn write_local_assign(&mut self, assign: &nodes::LocalAssignStatement) {
         self.push_str("local");
+        self.record_token(
+            assign.get_tokens().map(|tokens| &tokens.local),
+            "local".len(),
+        );
 
         let variables = assign.get_variables();
         let last_variable_index = variables.len().saturating_sub(1);
@@ -673,16 +745,19 @@ impl LuaGenerator for DenseLuaGenerator {
         self.push_str("end");
     }
 
-    fn write_false_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_false_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("false");
+        self.record_token(token.as_ref(), "false".len());
     }
 
-    fn write_true_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_true_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("true");
+        self.record_token(token.as_ref(), "true".len());
     }
 
-    fn write_nil_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_nil_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("nil");
+        self.record_token(token.as_ref(), "nil".len());
     }
 
     fn write_variable_arguments_expression(&mut self, _token: &Option<nodes::Token>) {
@@ -721,6 +796,7 @@ impl LuaGenerator for DenseLuaGenerator {
             Length => self.push_char('#'),
             Minus => self.push_char_and_break_if('-', utils::break_minus),
             Not => self.push_str("not"),
+            BitwiseNot => self.push_char('~'),
         }
 
         let expression = unary.get_expression();
@@ -846,6 +922,8 @@ impl LuaGenerator for DenseLuaGenerator {
     fn write_number(&mut self, number: &nodes::NumberExpression) {
         use nodes::NumberExpression::*;
 
+        let start = self.output.len();
+
         match number {
             Decimal(decimal) => {
                 let float = decimal.get_raw_float();
@@ -897,6 +975,9 @@ impl LuaGenerator for DenseLuaGenerator {
                 ));
             }
         }
+
+        let pushed_length = self.output.len().saturating_sub(start);
+        self.record_token(number.get_token(), pushed_length);
     }
 
     fn write_tuple_arguments(&mut self, arguments: &nodes::TupleArguments) {
@@ -924,6 +1005,7 @@ impl LuaGenerator for DenseLuaGenerator {
         } else {
             self.push_str(&result);
         }
+        self.record_token(string.get_token(), result.len());
     }
 
     fn write_interpolated_string(
@@ -954,7 +1036,9 @@ impl LuaGenerator for DenseLuaGenerator {
     }
 
     fn write_identifier(&mut self, identifier: &nodes::Identifier) {
-        self.push_str(identifier.get_name());
+        let name = identifier.get_name();
+        self.push_str(name);
+        self.record_token(identifier.get_token(), name.len());
     }
 
     fn write_parenthese(&mut self, parenthese: &nodes::ParentheseExpression) {
@@ -3,11 +3,13 @@
 
 mod dense;
 mod readable;
+mod source_map;
 mod token_based;
 pub(crate) mod utils;
 
 pub use dense::DenseLuaGenerator;
-pub use readable::ReadableLuaGenerator;
+pub use readable::{KeepComments, ReadableLuaGenerator};
+pub use source_map::{SourceMap, SourceMapping};
 pub use token_based::TokenBasedLuaGenerator;
 
 use crate::nodes;
@@ -346,11 +348,20 @@ mod test {
             $generator:expr => (
                 $($name:ident => $code:literal),+,
             )
+        ) => {
+            blocks_consistency!($generator, $crate::Parser::default() => (
+                $($name => $code),+,
+            ));
+        };
+        (
+            $generator:expr, $parser:expr => (
+                $($name:ident => $code:literal),+,
+            )
         ) => {
             $(
                 #[test]
                 fn $name() {
-                    let parser = $crate::Parser::default();
+                    let parser = $parser;
 
                     let expected_block = parser.parse($code)
                         .expect(&format!("unable to parse `{}`", $code));
@@ -439,6 +450,23 @@ mod $mod_name {
         ));
     }
 
+    mod bitwise_operators {
+        use super::*;
+
+        blocks_consistency!($generator, $crate::Parser::default().with_bitwise_operators() => (
+            bitwise_and_or_xor => "return a & b | c ~ d",
+            bitwise_or_in_bitwise_and => "return (a | b) & c",
+            bitwise_not_in_bitwise_xor => "return ~a ~ ~b",
+            shifts => "return a << 1 >> 2",
+            bitwise_not_on_bitwise_and => "return ~(a & b)",
+            concat_in_left_shift => "return a .. b << c",
+            left_shift_in_concat => "return a .. (b << c)",
+            bitwise_xor_with_unary_minus => "return 1 ~ -1",
+            unary_minus_on_bitwise_not => "return - ~a",
+            comparison_of_bitwise_and => "return a & 1 == 0",
+        ));
+    }
+
     mod numbers {
         use super::*;
 
@@ -1193,4 +1221,95 @@ mod $mod_name {
     snapshot_generator!(dense, |_| DenseLuaGenerator::default(), false);
     snapshot_generator!(readable, |_| ReadableLuaGenerator::default(), false);
     snapshot_generator!(token_based, TokenBasedLuaGenerator::new, true);
+
+    mod readable_keep_comments {
+        use super::*;
+
+        fn generate(code: &str, keep_comments: KeepComments) -> String {
+            let block = crate::Parser::default()
+                .preserve_tokens()
+                .parse(code)
+                .expect("unable to parse code");
+
+            let mut generator = ReadableLuaGenerator::default().with_comments(code, keep_comments);
+            generator.write_block(&block);
+            generator.into_string()
+        }
+
+        const CODE: &str = "--!strict\n-- license banner\nlocal a = 1\n-- compute value\nlocal function compute()\n    -- inside function\n    return a --[[ trailing ]]\nend\n--!native\nreturn compute()\n-- end of file\n";
+
+        #[test]
+        fn keep_no_comments() {
+            insta::assert_snapshot!(generate(CODE, KeepComments::None), @r###"
+            local a = 1
+
+            local function compute()
+                return a
+            end
+
+            return compute()
+            "###);
+        }
+
+        #[test]
+        fn keep_all_comments() {
+            insta::assert_snapshot!(generate(CODE, KeepComments::All), @r###"
+            --!strict
+            -- license banner
+            local a = 1
+
+            -- compute value
+            local function compute()
+                -- inside function
+                return a
+            end
+
+            --!native
+            return compute()
+            -- end of file
+            "###);
+        }
+
+        #[test]
+        fn keep_banner_comments() {
+            insta::assert_snapshot!(generate(CODE, KeepComments::Banner), @r###"
+            --!strict
+            -- license banner
+            local a = 1
+
+            local function compute()
+                return a
+            end
+
+            return compute()
+            "###);
+        }
+
+        #[test]
+        fn keep_directive_comments() {
+            insta::assert_snapshot!(generate(CODE, KeepComments::Directives), @r###"
+            --!strict
+            local a = 1
+
+            local function compute()
+                return a
+            end
+
+            --!native
+            return compute()
+            "###);
+        }
+
+        #[test]
+        fn keep_comments_without_tokens() {
+            let code = "-- comment\nreturn true";
+            let block = crate::Parser::default().parse(code).unwrap();
+
+            let mut generator =
+                ReadableLuaGenerator::default().with_comments(code, KeepComments::All);
+            generator.write_block(&block);
+
+            assert_eq!(generator.into_string(), "return true\n");
+        }
+    }
 }
@@ -1,6 +1,8 @@
-use crate::generator::{utils, LuaGenerator};
+use crate::generator::{source_map::SourceMapRecorder, utils, LuaGenerator, SourceMap};
 use crate::nodes;
 
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum StatementType {
     Assign,
@@ -55,6 +57,39 @@ impl From<&nodes::LastStatement> for StatementType {
     }
 }
 
+/// Defines which comments of the original code are written by the
+/// [ReadableLuaGenerator](struct.ReadableLuaGenerator.html).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeepComments {
+    /// Removes all comments.
+    #[default]
+    None,
+    /// Keeps all the comments placed before statements.
+    All,
+    /// Keeps the comments placed before the first statement of the file (usually
+    /// a license banner).
+    Banner,
+    /// Keeps the directive comments starting with `--!` (like `--!strict`).
+    Directives,
+}
+
+impl KeepComments {
+    /// Returns true if no comments are kept.
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
+    }
+
+    fn keeps(&self, comment: &str, is_banner: bool) -> bool {
+        match self {
+            Self::None => false,
+            Self::All => true,
+            Self::Banner => is_banner,
+            Self::Directives => comment.starts_with("--!"),
+        }
+    }
+}
+
 /// This implementation of [LuaGenerator](trait.LuaGenerator.html) attempts to produce Lua code as
 /// readable as possible.
 #[derive(Debug, Clone)]
@@ -65,7 +100,12 @@ pub struct ReadableLuaGenerator {
     current_indentation: usize,
     output: String,
     last_push_length: usize,
+    source_map: Option<SourceMapRecorder>,
     can_add_new_line_stack: Vec<bool>,
+    comments: Option<(String, KeepComments)>,
+    block_depth: usize,
+    banner_end: usize,
+    base_indentation: String,
 }
 
 impl ReadableLuaGenerator {
@@ -77,7 +117,96 @@ impl ReadableLuaGenerator {
             current_indentation: 0,
             output: String::new(),
             last_push_length: 0,
+            source_map: None,
             can_add_new_line_stack: Vec::new(),
+            comments: None,
+            block_depth: 0,
+            banner_end: 0,
+            base_indentation: String::new(),
+        }
+    }
+
+    /// Writes the given indentation at the start of every line, before the indentation
+    /// of the nested blocks.
+    pub(crate) fn with_base_indentation(mut self, indentation: &str) -> Self {
+        self.base_indentation = indentation.to_owned();
+        self
+    }
+
+    /// Writes the comments of the original code selected by `keep_comments`. Only the
+    /// comments placed before a statement are written, each one on its own line. The
+    /// generator reads the comments from the tokens of the AST nodes, so the code must be
+    /// parsed with [`Parser::preserve_tokens`](crate::Parser::preserve_tokens).
+    pub fn with_comments(mut self, code: &str, keep_comments: KeepComments) -> Self {
+        self.comments = (!keep_comments.is_none()).then(|| (code.to_owned(), keep_comments));
+        self
+    }
+
+    fn write_leading_comments(&mut self, token: Option<&nodes::Token>) {
+        let ((code, keep_comments), token) = match (&self.comments, token) {
+            (Some(comments), Some(token)) => (comments, token),
+            _ => return,
+        };
+        let is_banner = self.output.len() == self.banner_end;
+
+        // the comments inserted by rules are written by `write_block`
+        let comments: Vec<_> = token
+            .iter_leading_trivia()
+            .filter(|trivia| trivia.kind() == nodes::TriviaKind::Comment && !trivia.is_inserted())
+            .map(|trivia| trivia.read(code).to_owned())
+            .filter(|comment| keep_comments.keeps(comment, is_banner))
+            .collect();
+
+        for comment in comments {
+            if self.current_line_length == 0 && self.is_indented() {
+                self.write_indentation();
+            }
+            self.raw_push_str(&comment);
+            self.push_new_line();
+        }
+    }
+
+    fn write_block_content(&mut self, block: &nodes::Block) {
+        let mut statements = block.iter_statements().peekable();
+
+        while let Some(statement) = statements.next() {
+            let current_type: StatementType = statement.into();
+
+            self.write_leading_comments(utils::get_statement_first_token(statement));
+
+            self.push_can_add_new_line(false);
+            self.write_statement(statement);
+
+            if let Some(next_statement) = statements.peek() {
+                if utils::starts_with_parenthese(next_statement)
+                    && utils::ends_with_prefix(statement)
+                {
+                    self.push_char(';');
+                }
+
+                if current_type != (*next_statement).into() {
+                    self.push_new_line();
+                }
+            }
+
+            self.pop_can_add_new_line();
+            self.push_new_line();
+        }
+
+        if let Some(last_statement) = block.get_last_statement() {
+            if block.iter_statements().next().is_some() {
+                self.push_new_line();
+            }
+            self.write_leading_comments(utils::get_last_statement_first_token(last_statement));
+            self.write_last_statement(last_statement);
+            self.push_new_line();
+        }
+
+        if let Some(final_token) = block
+            .get_tokens()
+            .and_then(|tokens| tokens.final_token.as_ref())
+        {
+            self.write_leading_comments(Some(final_token));
         }
     }
 
@@ -106,9 +235,18 @@ impl ReadableLuaGenerator {
         self.current_indentation -= 1;
     }
 
+    #[inline]
+    fn is_indented(&self) -> bool {
+        self.current_indentation != 0 || !self.base_indentation.is_empty()
+    }
+
     #[inline]
     fn write_indentation(&mut self) {
-        let indentation = " ".repeat(self.indentation * self.current_indentation);
+        let indentation = format!(
+            "{}{}",
+            self.base_indentation,
+            " ".repeat(self.indentation * self.current_indentation)
+        );
         self.raw_push_str(&indentation);
     }
 
@@ -161,6 +299,33 @@ impl ReadableLuaGenerator {
         self.current_line_length += 1;
     }
 
+    /// Enables the generation of a source map, obtained with
+    /// [`into_string_and_source_map`](Self::into_string_and_source_map). The
+    /// generator relies on the tokens of the AST nodes to find the original lines,
+    /// so the code must be parsed with [`Parser::preserve_tokens`](crate::Parser::preserve_tokens).
+    pub fn with_source_map(mut self) -> Self {
+        self.source_map = Some(SourceMapRecorder::default());
+        self
+    }
+
+    /// Consumes the generator and produce the generated code with its source map. The
+    /// source map is empty if it was not enabled with [`with_source_map`](Self::with_source_map).
+    pub fn into_string_and_source_map(self) -> (String, SourceMap) {
+        let output = self.output;
+        let source_map = self
+            .source_map
+            .map(|recorder| recorder.build(&output))
+            .unwrap_or_default();
+        (output, source_map)
+    }
+
+    /// Records the token of the content that was just pushed.
+    fn record_token(&mut self, token: Option<&nodes::Token>, pushed_length: usize) {
+        if let (Some(source_map), Some(token)) = (&mut self.source_map, token) {
+            source_map.record_token(self.output.len().saturating_sub(pushed_length), token);
+        }
+    }
+
     #[inline]
     fn needs_space(&self, next_character: char) -> bool {
         if let Some(previous) = self.output.chars().last() {
@@ -178,7 +343,7 @@ impl ReadableLuaGenerator {
     }
 
     fn push_new_line_if_needed(&mut self, pushed_length: usize) {
-        if self.current_line_length == 0 && self.current_indentation != 0 {
+        if self.current_line_length == 0 && self.is_indented() {
             self.write_indentation();
         }
 
@@ -196,7 +361,7 @@ impl ReadableLuaGenerator {
     }
 
     fn push_space_if_needed(&mut self, next_character: char, pushed_length: usize) {
-        if self.current_line_length == 0 && self.current_indentation != 0 {
+        if self.current_line_length == 0 && self.is_indented() {
             self.write_indentation();
         }
 
@@ -370,7 +535,9 @@ impl ReadableLuaGenerator {
     }
 
     fn write_typed_identifier(&mut self, typed_identifier: &nodes::TypedIdentifier) {
-        self.push_str(typed_identifier.get_name());
+        let name = typed_identifier.get_name();
+        self.push_str(name);
+        self.record_token(typed_identifier.get_token(), name.len());
 
         if let Some(r#type) = typed_identifier.get_type() {
             self.push_char(':');
@@ -450,36 +617,28 @@ impl LuaGenerator for ReadableLuaGenerator {
     }
 
     fn write_block(&mut self, block: &nodes::Block) {
-        let mut statements = block.iter_statements().peekable();
-
-        while let Some(statement) = statements.next() {
-            let current_type: StatementType = statement.into();
-
-            self.push_can_add_new_line(false);
-            self.write_statement(statement);
-
-            if let Some(next_statement) = statements.peek() {
-                if utils::starts_with_parenthese(next_statement)
-                    && utils::ends_with_prefix(statement)
-                {
-                    self.push_char(';');
-                }
-
-                if current_type != (*next_statement).into() {
-                    self.push_new_line();
-                }
+        // the comments inserted by rules are written around the root block, since
+        // they would be lost when the comments of the original code are not kept
+        let inserted_comments =
+            (self.block_depth == 0).then(|| utils::get_block_inserted_comments(block));
+
+        if let Some((start_comments, _)) = &inserted_comments {
+            for comment in start_comments {
+                self.raw_push_str(comment);
+                self.push_new_line();
             }
-
-            self.pop_can_add_new_line();
-            self.push_new_line();
+            self.banner_end = self.output.len();
         }
 
-        if let Some(last_statement) = block.get_last_statement() {
-            if block.iter_statements().next().is_some() {
+        self.block_depth += 1;
+        self.write_block_content(block);
+        self.block_depth -= 1;
+
+        if let Some((_, end_comments)) = &inserted_comments {
+            for comment in end_comments {
+                self.raw_push_str(comment);
                 self.push_new_line();
             }
-            self.write_last_statement(last_statement);
-            self.push_new_line();
         }
     }
 
@@ -491,6 +650,10 @@ impl LuaGenerator for ReadableLuaGenerator {
             Continue(_) => self.push_str("continue"),
             Return(expressions) => {
                 self.push_str("return");
+                self.record_token(
+                    expressions.get_tokens().map(|tokens| &tokens.r#return),
+                    "return".len(),
+                );
                 self.push_can_add_new_line(false);
                 let last_index = expressions.len().saturating_sub(1);
 
@@ -546,6 +709,10 @@ impl LuaGenerator for ReadableLuaGenerator {
 
     fn write_local_assign(&mut self, assign: &nodes::LocalAssignStatement) {
         self.push_str("local ");
+        self.record_token(
+            assign.get_tokens().map(|tokens| &tokens.local),
+            "local ".len(),
+        );
 
         self.push_can_add_new_line(false);
 
@@ -912,16 +1079,19 @@ impl LuaGenerator for ReadableLuaGenerator {
         }
     }
 
-    fn write_false_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_false_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("false");
+        self.record_token(token.as_ref(), "false".len());
     }
 
-    fn write_true_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_true_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("true");
+        self.record_token(token.as_ref(), "true".len());
     }
 
-    fn write_nil_expression(&mut self, _token: &Option<nodes::Token>) {
+    fn write_nil_expression(&mut self, token: &Option<nodes::Token>) {
         self.push_str("nil");
+        self.record_token(token.as_ref(), "nil".len());
     }
 
     fn write_variable_arguments_expression(&mut self, _token: &Option<nodes::Token>) {
@@ -957,6 +1127,7 @@ impl LuaGenerator for ReadableLuaGenerator {
             Length => self.push_char('#'),
             Minus => self.push_str_and_break_if("-", utils::break_minus),
             Not => self.push_str("not "),
+            BitwiseNot => self.push_char('~'),
         }
 
         let expression = unary.get_expression();
@@ -1154,7 +1325,9 @@ impl LuaGenerator for ReadableLuaGenerator {
     }
 
     fn write_number(&mut self, number: &nodes::NumberExpression) {
-        self.push_str(&utils::write_number(number));
+        let result = utils::write_number(number);
+        self.push_str(&result);
+        self.record_token(number.get_token(), result.len());
     }
 
     fn write_string(&mut self, string: &nodes::StringExpression) {
@@ -1164,6 +1337,7 @@ impl LuaGenerator for ReadableLuaGenerator {
         } else {
             self.push_str(&result);
         }
+        self.record_token(string.get_token(), result.len());
     }
 
     fn write_interpolated_string(
@@ -1194,7 +1368,9 @@ impl LuaGenerator for ReadableLuaGenerator {
     }
 
     fn write_identifier(&mut self, identifier: &nodes::Identifier) {
-        self.push_str(identifier.get_name());
+        let name = identifier.get_name();
+        self.push_str(name);
+        self.record_token(identifier.get_token(), name.len());
     }
 
     fn write_parenthese(&mut self, parenthese: &nodes::ParentheseExpression) {
@@ -0,0 +1,435 @@
+use std::iter;
+use std::path::Path;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::nodes::Token;
+
+const BASE64_CHARACTERS: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Associates a position in the generated code with a line of an original file.
+///
+/// Lines are 1-based (like the line numbers found in tokens) and columns are 0-based,
+/// counted in UTF-16 code units (as expected by the source map format).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceMapping {
+    generated_line: usize,
+    generated_column: usize,
+    source_index: usize,
+    source_line: usize,
+}
+
+impl SourceMapping {
+    /// Returns the line in the generated code.
+    #[inline]
+    pub fn generated_line(&self) -> usize {
+        self.generated_line
+    }
+
+    /// Returns the column in the generated code.
+    #[inline]
+    pub fn generated_column(&self) -> usize {
+        self.generated_column
+    }
+
+    /// Returns the index of the original file, in the order of
+    /// [`SourceMap::iter_sources`].
+    #[inline]
+    pub fn source_index(&self) -> usize {
+        self.source_index
+    }
+
+    /// Returns the line in the original code.
+    #[inline]
+    pub fn source_line(&self) -> usize {
+        self.source_line
+    }
+}
+
+/// A source map produced by a generator, that maps positions in the generated code back to
+/// the lines of the original code.
+///
+/// Use [`SourceMap::to_json`] to obtain a [source map (revision 3)](https://sourcemaps.info/spec.html)
+/// compatible with existing tooling.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    mappings: Vec<SourceMapping>,
+    sources: Vec<Arc<Path>>,
+}
+
+impl SourceMap {
+    /// Returns an iterator over the mappings, ordered by their position in the generated code.
+    pub fn iter_mappings(&self) -> impl Iterator<Item = &SourceMapping> {
+        self.mappings.iter()
+    }
+
+    /// Returns an iterator over the original files, in the order of their index. The
+    /// first file (`None`) is the file that was processed, and the following ones are
+    /// the files that were bundled into it.
+    pub fn iter_sources(&self) -> impl Iterator<Item = Option<&Path>> {
+        iter::once(None).chain(self.sources.iter().map(|source| Some(source.as_ref())))
+    }
+
+    /// Returns true if the source map does not contain any mapping.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty()
+    }
+
+    /// Returns the original line associated with the first mapping of the given
+    /// generated line.
+    pub fn find_source_line(&self, generated_line: usize) -> Option<usize> {
+        self.mappings
+            .iter()
+            .find(|mapping| mapping.generated_line == generated_line)
+            .map(SourceMapping::source_line)
+    }
+
+    /// Serializes the source map to JSON. The `file` parameter is the name of the generated
+    /// file and `sources` contains the path to each original file (usually relative to the
+    /// location of the source map) with its content, in the order of
+    /// [`SourceMap::iter_sources`].
+    pub fn to_json(&self, file: &str, sources: &[(&str, Option<&str>)]) -> String {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct SourceMapJson<'a> {
+            version: u8,
+            file: &'a str,
+            sources: Vec<&'a str>,
+            sources_content: Vec<Option<&'a str>>,
+            names: [&'a str; 0],
+            mappings: String,
+        }
+
+        serde_json::to_string(&SourceMapJson {
+            version: 3,
+            file,
+            sources: sources.iter().map(|(source, _)| *source).collect(),
+            sources_content: sources.iter().map(|(_, content)| *content).collect(),
+            names: [],
+            mappings: self.encode_mappings(),
+        })
+        .expect("source map should serialize to json")
+    }
+
+    fn encode_mappings(&self) -> String {
+        let mut result = String::new();
+        let mut current_line = 1;
+        let mut previous_column = 0;
+        let mut previous_source_index = 0;
+        let mut previous_source_line = 1;
+        let mut first_segment = true;
+
+        for mapping in self.mappings.iter() {
+            while current_line < mapping.generated_line {
+                result.push(';');
+                current_line += 1;
+                previous_column = 0;
+                first_segment = true;
+            }
+
+            if !first_segment {
+                result.push(',');
+            }
+            first_segment = false;
+
+            encode_vlq(
+                &mut result,
+                mapping.generated_column as i64 - previous_column as i64,
+            );
+            encode_vlq(
+                &mut result,
+                mapping.source_index as i64 - previous_source_index as i64,
+            );
+            encode_vlq(
+                &mut result,
+                mapping.source_line as i64 - previous_source_line as i64,
+            );
+            // the column in the original file (only lines are tracked)
+            encode_vlq(&mut result, 0);
+
+            previous_column = mapping.generated_column;
+            previous_source_index = mapping.source_index;
+            previous_source_line = mapping.source_line;
+        }
+
+        result
+    }
+}
+
+fn encode_vlq(output: &mut String, value: i64) {
+    let mut value = if value < 0 {
+        ((-value) << 1) | 1
+    } else {
+        value << 1
+    };
+
+    loop {
+        let mut digit = value & 0b11111;
+        value >>= 5;
+
+        if value > 0 {
+            digit |= 0b100000;
+        }
+
+        output.push(BASE64_CHARACTERS[digit as usize] as char);
+
+        if value == 0 {
+            break;
+        }
+    }
+}
+
+/// Used by generators to keep track of the tokens written at a given offset of their output.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SourceMapRecorder {
+    entries: Vec<RecordedEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct RecordedEntry {
+    offset: usize,
+    source: Option<Arc<Path>>,
+    source_line: usize,
+}
+
+impl SourceMapRecorder {
+    /// Records that the content found at `offset` in the output was generated from the
+    /// given token.
+    pub(crate) fn record_token(&mut self, offset: usize, token: &Token) {
+        if let Some(line_number) = token.get_source_line_number() {
+            self.record(offset, token.get_source(), line_number);
+        }
+    }
+
+    fn record(&mut self, offset: usize, source: Option<&Arc<Path>>, source_line: usize) {
+        if let Some(last) = self.entries.last() {
+            if last.offset == offset
+                && last.source.as_ref() == source
+                && last.source_line == source_line
+            {
+                return;
+            }
+        }
+        self.entries.push(RecordedEntry {
+            offset,
+            source: source.cloned(),
+            source_line,
+        });
+    }
+
+    /// Moves the entries located at or after `from` to the new offset `to`. This is used
+    /// when a generator moves content it already wrote.
+    pub(crate) fn move_entries(&mut self, from: usize, to: usize) {
+        for entry in self.entries.iter_mut().rev() {
+            if entry.offset < from {
+                break;
+            }
+            entry.offset = entry.offset - from + to;
+        }
+    }
+
+    pub(crate) fn build(mut self, output: &str) -> SourceMap {
+        self.entries.sort_by_key(|entry| entry.offset);
+
+        let bytes = output.as_bytes();
+        let mut mappings = Vec::with_capacity(self.entries.len());
+        let mut sources: Vec<Arc<Path>> = Vec::new();
+        let mut line = 1;
+        let mut line_start = 0;
+        let mut position = 0;
+
+        for entry in self.entries {
+            let offset = entry.offset.min(bytes.len());
+
+            while position < offset {
+                if bytes[position] == b'\n' {
+                    line += 1;
+                    line_start = position + 1;
+                }
+                position += 1;
+            }
+
+            let source_index = match entry.source {
+                Some(source) => {
+                    if let Some(index) = sources.iter().position(|existing| *existing == source) {
+                        index + 1
+                    } else {
+                        sources.push(source);
+                        sources.len()
+                    }
+                }
+                None => 0,
+            };
+
+            let generated_column = output
+                .get(line_start..offset)
+                .map(|line_content| line_content.encode_utf16().count())
+                .unwrap_or(offset - line_start);
+
+            let mapping = SourceMapping {
+                generated_line: line,
+                generated_column,
+                source_index,
+                source_line: entry.source_line,
+            };
+
+            let is_redundant = mappings.last().is_some_and(|last: &SourceMapping| {
+                last.generated_line == mapping.generated_line
+                    && last.source_index == mapping.source_index
+                    && last.source_line == mapping.source_line
+            });
+
+            if !is_redundant {
+                mappings.push(mapping);
+            }
+        }
+
+        SourceMap { mappings, sources }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn encode(value: i64) -> String {
+        let mut result = String::new();
+        encode_vlq(&mut result, value);
+        result
+    }
+
+    #[test]
+    fn encode_vlq_values() {
+        assert_eq!(encode(0), "A");
+        assert_eq!(encode(1), "C");
+        assert_eq!(encode(-1), "D");
+        assert_eq!(encode(15), "e");
+        assert_eq!(encode(16), "gB");
+        assert_eq!(encode(-17), "jB");
+        assert_eq!(encode(1000), "w+B");
+    }
+
+    #[test]
+    fn build_mappings_from_offsets() {
+        let mut recorder = SourceMapRecorder::default();
+        recorder.record(0, None, 1);
+        recorder.record(6, None, 1);
+        recorder.record(8, None, 3);
+        recorder.record(12, None, 4);
+
+        let source_map = recorder.build("local a\nb=1\nc()");
+
+        pretty_assertions::assert_eq!(
+            source_map.iter_mappings().copied().collect::<Vec<_>>(),
+            vec![
+                SourceMapping {
+                    generated_line: 1,
+                    generated_column: 0,
+                    source_index: 0,
+                    source_line: 1
+                },
+                SourceMapping {
+                    generated_line: 2,
+                    generated_column: 0,
+                    source_index: 0,
+                    source_line: 3
+                },
+                SourceMapping {
+                    generated_line: 3,
+                    generated_column: 0,
+                    source_index: 0,
+                    source_line: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn move_entries_after_offset() {
+        let mut recorder = SourceMapRecorder::default();
+        recorder.record(0, None, 1);
+        recorder.record(4, None, 2);
+        recorder.move_entries(4, 5);
+
+        let source_map = recorder.build("abc\n\nd");
+
+        assert_eq!(source_map.find_source_line(1), Some(1));
+        assert_eq!(source_map.find_source_line(2), None);
+        assert_eq!(source_map.find_source_line(3), Some(2));
+    }
+
+    #[test]
+    fn serialize_to_json() {
+        let mut recorder = SourceMapRecorder::default();
+        recorder.record(0, None, 1);
+        recorder.record(2, None, 4);
+        recorder.record(4, None, 2);
+
+        let source_map = recorder.build("a b\nc");
+
+        insta::assert_snapshot!(
+            source_map.to_json("out.lua", &[("src/main.lua", None)]),
+            @r###"
+        {"version":3,"file":"out.lua","sources":["src/main.lua"],"sourcesContent":[null],"names":[],"mappings":"AAAA,EAGA;AAFA"}
+        "###
+        );
+    }
+
+    #[test]
+    fn build_mappings_with_utf16_columns() {
+        let mut recorder = SourceMapRecorder::default();
+        recorder.record(0, None, 1);
+        recorder.record(11, None, 2);
+
+        let source_map = recorder.build("s='é😀' b()");
+
+        assert_eq!(
+            source_map
+                .iter_mappings()
+                .map(SourceMapping::generated_column)
+                .collect::<Vec<_>>(),
+            vec![0, 8]
+        );
+    }
+
+    #[test]
+    fn serialize_to_json_with_bundled_sources() {
+        let module: Arc<Path> = Path::new("src/module.lua").into();
+        let other_module: Arc<Path> = Path::new("src/other.lua").into();
+
+        let mut recorder = SourceMapRecorder::default();
+        recorder.record(0, None, 1);
+        recorder.record(2, Some(&other_module), 3);
+        recorder.record(4, Some(&module), 1);
+        recorder.record(6, Some(&other_module), 4);
+        recorder.record(8, None, 2);
+
+        let source_map = recorder.build("a b\nc d\ne");
+
+        assert_eq!(
+            source_map.iter_sources().collect::<Vec<_>>(),
+            vec![
+                None,
+                Some(Path::new("src/other.lua")),
+                Some(Path::new("src/module.lua"))
+            ]
+        );
+
+        insta::assert_snapshot!(
+            source_map.to_json(
+                "out.lua",
+                &[
+                    ("src/main.lua", Some("a()\nb()")),
+                    ("src/other.lua", Some("return 1")),
+                    ("src/module.lua", None),
+                ]
+            ),
+            @r###"
+        {"version":3,"file":"out.lua","sources":["src/main.lua","src/other.lua","src/module.lua"],"sourcesContent":["a()\nb()","return 1",null],"names":[],"mappings":"AAAA,ECEA;ACFA,EDGA;ADFA"}
+        "###
+        );
+    }
+}
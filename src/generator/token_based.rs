@@ -1,7 +1,14 @@
 use std::iter;
 
+use full_moon::{
+    tokenizer::{Lexer, LexerResult, TokenType},
+    LuaVersion,
+};
+
 use crate::{
-    generator::{utils, LuaGenerator},
+    generator::{
+        source_map::SourceMapRecorder, utils, LuaGenerator, ReadableLuaGenerator, SourceMap,
+    },
     nodes::*,
 };
 
@@ -13,6 +20,15 @@ pub struct TokenBasedLuaGenerator<'a> {
     output: String,
     currently_commenting: bool,
     current_line: usize,
+    source_map: Option<SourceMapRecorder>,
+    // the column span used to write new statements, when minimal diff is enabled
+    minimal_diff: Option<usize>,
+    // the line of the last original token written, or `None` when the next token
+    // must start on a new line
+    previous_line: Option<usize>,
+    // the position in the original code after the last original token written, when
+    // minimal diff is enabled
+    last_token_end: Option<usize>,
 }
 
 impl<'a> TokenBasedLuaGenerator<'a> {
@@ -22,9 +38,43 @@ impl<'a> TokenBasedLuaGenerator<'a> {
             output: String::new(),
             currently_commenting: false,
             current_line: 1,
+            source_map: None,
+            minimal_diff: None,
+            previous_line: None,
+            last_token_end: None,
         }
     }
 
+    /// Writes the statements that still have their tokens exactly as they appear in the
+    /// original code, without keeping their original line numbers. The statements without
+    /// tokens (usually added by rules) are written on their own lines with the
+    /// [`ReadableLuaGenerator`], using the given column span.
+    ///
+    /// When a rule replaces the tokens at the end of a statement, the comment that ended
+    /// the line of the statement in the original code is written after it.
+    pub fn with_minimal_diff(mut self, column_span: usize) -> Self {
+        self.minimal_diff = Some(column_span);
+        self
+    }
+
+    /// Enables the generation of a source map, obtained with
+    /// [`into_string_and_source_map`](Self::into_string_and_source_map).
+    pub fn with_source_map(mut self) -> Self {
+        self.source_map = Some(SourceMapRecorder::default());
+        self
+    }
+
+    /// Consumes the generator and produce the generated code with its source map. The
+    /// source map is empty if it was not enabled with [`with_source_map`](Self::with_source_map).
+    pub fn into_string_and_source_map(self) -> (String, SourceMap) {
+        let output = self.output;
+        let source_map = self
+            .source_map
+            .map(|recorder| recorder.build(&output))
+            .unwrap_or_default();
+        (output, source_map)
+    }
+
     fn push_str(&mut self, string: &str) {
         self.current_line += utils::count_new_lines(string.as_bytes());
         self.output.push_str(string);
@@ -41,6 +91,16 @@ impl<'a> TokenBasedLuaGenerator<'a> {
             self.uncomment();
         }
 
+        // comments of removed statements are moved before the next token without the
+        // new line that used to follow them, so the indentation must move to a new line
+        if self.minimal_diff.is_some()
+            && self.currently_commenting
+            && trivia.kind() == TriviaKind::Whitespace
+            && !content.contains('\n')
+        {
+            self.uncomment();
+        }
+
         self.push_str(content);
 
         match trivia.kind() {
@@ -62,7 +122,41 @@ impl<'a> TokenBasedLuaGenerator<'a> {
         self.write_token_options(token, true)
     }
 
+    /// Moves a token to a new line when it was originally on a different line than the
+    /// previous token, but the trivia that separated them was removed.
+    fn break_line_before(&mut self, token: &Token) {
+        let Some(line_number) = token.get_line_number() else {
+            return;
+        };
+
+        if self.output.is_empty()
+            || self.output.ends_with('\n')
+            || self
+                .previous_line
+                .is_some_and(|previous_line| line_number <= previous_line)
+        {
+            return;
+        }
+
+        let starts_with_new_line = token.iter_leading_trivia().next().is_some_and(|trivia| {
+            trivia.kind() == TriviaKind::Whitespace
+                && trivia.read(self.original_code).contains('\n')
+        });
+
+        if !starts_with_new_line {
+            if self.currently_commenting {
+                self.uncomment();
+            } else {
+                self.push_str("\n");
+            }
+        }
+    }
+
     fn write_token_options(&mut self, token: &Token, space_check: bool) {
+        if self.minimal_diff.is_some() {
+            self.break_line_before(token);
+        }
+
         for trivia in token.iter_leading_trivia() {
             self.write_trivia(trivia);
         }
@@ -75,9 +169,14 @@ impl<'a> TokenBasedLuaGenerator<'a> {
             }
 
             if let Some(line_number) = token.get_line_number() {
-                while line_number > self.current_line {
-                    self.output.push('\n');
-                    self.current_line += 1;
+                if self.minimal_diff.is_some() {
+                    self.previous_line =
+                        Some(line_number + utils::count_new_lines(content.as_bytes()));
+                } else {
+                    while line_number > self.current_line {
+                        self.output.push('\n');
+                        self.current_line += 1;
+                    }
                 }
             }
 
@@ -89,19 +188,156 @@ impl<'a> TokenBasedLuaGenerator<'a> {
                 }
             }
 
+            if let Some(source_map) = &mut self.source_map {
+                source_map.record_token(self.output.len(), token);
+            }
+
             self.push_str(content);
         }
 
         for trivia in token.iter_trailing_trivia() {
             self.write_trivia(trivia);
         }
+
+        if self.minimal_diff.is_some() && token.get_source().is_none() {
+            if let Some((_, end)) = token.get_range() {
+                self.last_token_end = Some(end);
+            }
+        }
+    }
+
+    /// Writes the comment that ended the line of the last original token written by a
+    /// statement, when the tokens that held it were replaced by a rule (like the comment
+    /// of `local x = 1 + 1 -- two` when the expression is computed).
+    fn write_replaced_trailing_comment(
+        &mut self,
+        block: &Block,
+        tokens: &BlockTokens,
+        previous_token_end: Option<usize>,
+    ) {
+        let Some(start) = self.last_token_end else {
+            return;
+        };
+        if previous_token_end == Some(start) {
+            return;
+        }
+
+        let rest = &self.original_code[start..];
+        let line = rest.find('\n').map_or(rest, |index| &rest[..index]);
+        if line.trim().is_empty() {
+            return;
+        }
+
+        // the comment must not belong to a statement that is still written
+        let end = block
+            .iter_statements()
+            .map(utils::get_statement_first_token)
+            .chain(iter::once(
+                block
+                    .get_last_statement()
+                    .and_then(utils::get_last_statement_first_token),
+            ))
+            .chain(tokens.semicolons.iter().map(Option::as_ref))
+            .chain(iter::once(tokens.final_token.as_ref()))
+            .flatten()
+            .filter_map(Token::get_range)
+            .map(|(token_start, _)| token_start)
+            .filter(|token_start| *token_start >= start)
+            .fold(start + line.len(), usize::min);
+
+        if let Some(comment) = find_trailing_comment(&self.original_code[start..end]) {
+            if self.currently_commenting {
+                self.uncomment();
+            }
+            self.push_str(" ");
+            self.push_str(&comment);
+            self.currently_commenting = true;
+        }
+    }
+
+    /// Writes a statement that does not come from the original code on its own line.
+    fn write_new_statement(
+        &mut self,
+        column_span: usize,
+        indentation: &str,
+        write: impl FnOnce(&mut ReadableLuaGenerator),
+    ) {
+        let mut generator =
+            ReadableLuaGenerator::new(column_span).with_base_indentation(indentation);
+        write(&mut generator);
+        let code = generator.into_string();
+
+        if self.currently_commenting {
+            self.uncomment();
+        } else if !self.output.is_empty() && !self.output.ends_with('\n') {
+            self.push_str("\n");
+        }
+
+        self.push_str(code.trim_end());
+        self.previous_line = None;
+    }
+
+    /// Finds the indentation of the statements of a block from the whitespace placed
+    /// before their first token, or from the current line of the output.
+    fn get_block_indentation(&self, block: &Block) -> String {
+        let from_tokens = block
+            .iter_statements()
+            .map(utils::get_statement_first_token)
+            .chain(iter::once(
+                block
+                    .get_last_statement()
+                    .and_then(utils::get_last_statement_first_token),
+            ))
+            .flatten()
+            .find_map(|token| {
+                let leading_trivia: String = token
+                    .iter_leading_trivia()
+                    .map(|trivia| trivia.read(self.original_code))
+                    .collect();
+
+                // the trivia placed after a token on the same line belongs to that
+                // token, so the leading trivia ends with the indentation of the line
+                let indentation = leading_trivia
+                    .rfind('\n')
+                    .map_or(leading_trivia.as_str(), |index| {
+                        &leading_trivia[index + 1..]
+                    });
+
+                (!indentation.is_empty() && indentation.chars().all(is_indentation))
+                    .then(|| indentation.to_owned())
+            });
+
+        from_tokens.unwrap_or_else(|| {
+            let current_line = &self.output[self.output.rfind('\n').map_or(0, |index| index + 1)..];
+            current_line
+                .chars()
+                .take_while(|character| is_indentation(*character))
+                .collect()
+        })
     }
 
     fn write_block_with_tokens(&mut self, block: &Block, tokens: &BlockTokens) {
         let mut iterator = block.iter_statements().enumerate().peekable();
+        let mut indentation = None;
 
         while let Some((index, statement)) = iterator.next() {
-            self.write_statement(statement);
+            let previous_token_end = self.last_token_end;
+            let mut keeps_tokens = false;
+
+            match self.minimal_diff {
+                Some(column_span) if utils::get_statement_first_token(statement).is_none() => {
+                    let indentation =
+                        indentation.get_or_insert_with(|| self.get_block_indentation(block));
+                    let indentation = indentation.clone();
+                    self.write_new_statement(column_span, &indentation, |generator| {
+                        generator.write_statement(statement)
+                    });
+                }
+                _ => {
+                    self.write_statement(statement);
+                    keeps_tokens = self.minimal_diff.is_some();
+                }
+            }
 
             if let Some(semicolon) = tokens.semicolons.get(index).unwrap_or(&None) {
                 self.write_token(semicolon);
@@ -112,10 +348,29 @@ impl<'a> TokenBasedLuaGenerator<'a> {
                     self.write_symbol(";");
                 }
             };
+
+            if keeps_tokens {
+                self.write_replaced_trailing_comment(block, tokens, previous_token_end);
+            }
         }
 
         if let Some(statement) = block.get_last_statement() {
-            self.write_last_statement(statement);
+            match self.minimal_diff {
+                Some(column_span) if utils::get_last_statement_first_token(statement).is_none() => {
+                    let indentation =
+                        indentation.unwrap_or_else(|| self.get_block_indentation(block));
+                    self.write_new_statement(column_span, &indentation, |generator| {
+                        generator.write_last_statement(statement)
+                    });
+                }
+                _ => {
+                    let previous_token_end = self.last_token_end;
+                    self.write_last_statement(statement);
+                    if self.minimal_diff.is_some() {
+                        self.write_replaced_trailing_comment(block, tokens, previous_token_end);
+                    }
+                }
+            }
         }
 
         if let Some(token) = &tokens.final_token {
@@ -1768,6 +2023,35 @@ impl<'a> TokenBasedLuaGenerator<'a> {
     }
 }
 
+/// Returns the single line comment that ends the given code, when it follows other
+/// tokens. A comment that is not preceded by any token was removed with its trivia
+/// (like with the `remove_comments` rule) and is not returned.
+fn find_trailing_comment(code: &str) -> Option<String> {
+    let LexerResult::Ok(tokens) = Lexer::new(code, LuaVersion::luau()).collect() else {
+        return None;
+    };
+
+    let mut tokens = tokens.into_iter().rev().filter(|token| {
+        !matches!(
+            token.token_type(),
+            TokenType::Whitespace { .. } | TokenType::Eof
+        )
+    });
+
+    let comment = tokens
+        .next()
+        .filter(|token| matches!(token.token_type(), TokenType::SingleLineComment { .. }))?;
+
+    tokens
+        .any(|token| !token.token_type().is_trivia())
+        .then(|| comment.to_string().trim_end().to_owned())
+}
+
+#[inline]
+fn is_indentation(character: char) -> bool {
+    character == ' ' || character == '\t'
+}
+
 fn is_single_line_comment(content: &str) -> bool {
     let is_multiline_comment = content.starts_with("--[") && {
         if let Some((closing_bracket_index, _)) =
@@ -2586,4 +2870,203 @@ mod test {
         next comment ]]
         "###);
     }
+    mod minimal_diff {
+        use super::*;
+
+        fn parse(code: &str) -> Block {
+            crate::Parser::default()
+                .preserve_tokens()
+                .parse(code)
+                .unwrap_or_else(|_| panic!("failed to parse `{}`", code))
+        }
+
+        fn generate(block: &Block, code: &str) -> String {
+            let mut generator = TokenBasedLuaGenerator::new(code).with_minimal_diff(80);
+            generator.write_block(block);
+            generator.into_string()
+        }
+
+        #[test]
+        fn unchanged_code_is_written_verbatim() {
+            let code = "local a = 1 -- one\n\n\tlocal  b=a;\nreturn { a , b }\n";
+            let block = parse(code);
+
+            assert_eq!(generate(&block, code), code);
+        }
+
+        #[test]
+        fn removed_statement_does_not_leave_empty_lines() {
+            let code = "local a = 1\nlocal b = 2 -- keep\nprint(b)\n";
+            let mut block = parse(code);
+
+            block.remove_statement(0);
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local b = 2 -- keep
+            print(b)
+            "###);
+        }
+
+        #[test]
+        fn replaced_value_keeps_next_statement_on_its_own_line() {
+            let code = "local a = b\nreturn a\n";
+            let mut block = parse(code);
+
+            match block.first_mut_statement() {
+                Some(Statement::LocalAssign(assign)) => {
+                    *assign.iter_mut_values().next().unwrap() = Expression::from(1);
+                }
+                _ => panic!("unexpected statement"),
+            }
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local a = 1
+            return a
+            "###);
+        }
+
+        #[test]
+        fn new_statement_is_written_on_its_own_line_with_indentation() {
+            let code = "local function f()\n\tprint(1)\nend\n";
+            let mut block = parse(code);
+
+            match block.first_mut_statement() {
+                Some(Statement::LocalFunction(function)) => {
+                    function.mutate_block().insert_statement(
+                        0,
+                        LocalAssignStatement::from_variable("value")
+                            .with_value(TableExpression::default()),
+                    );
+                }
+                _ => panic!("unexpected statement"),
+            }
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local function f()
+            	local value = {}
+            	print(1)
+            end
+            "###);
+        }
+
+        fn replace_first_value(block: &mut Block, value: impl Into<Expression>) {
+            match block.first_mut_statement() {
+                Some(Statement::LocalAssign(assign)) => {
+                    *assign.iter_mut_values().next().unwrap() = value.into();
+                }
+                _ => panic!("unexpected statement"),
+            }
+        }
+
+        #[test]
+        fn replaced_value_keeps_trailing_comment() {
+            let code = "local x = 1 + 1 -- two\nreturn x\n";
+            let mut block = parse(code);
+
+            replace_first_value(&mut block, 2);
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local x = 2 -- two
+            return x
+            "###);
+        }
+
+        #[test]
+        fn replaced_value_at_end_of_code_keeps_trailing_comment() {
+            let code = "local x = 1 + 1 -- two";
+            let mut block = parse(code);
+
+            replace_first_value(&mut block, 2);
+
+            insta::assert_snapshot!(generate(&block, code), @"local x = 2 -- two");
+        }
+
+        #[test]
+        fn replaced_value_in_nested_block_keeps_trailing_comment() {
+            let code = "do\n\tlocal x = 1 + 1 -- two\n\tprint(x)\nend\n";
+            let mut block = parse(code);
+
+            match block.first_mut_statement() {
+                Some(Statement::Do(do_statement)) => {
+                    replace_first_value(do_statement.mutate_block(), 2);
+                }
+                _ => panic!("unexpected statement"),
+            }
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            do
+            	local x = 2 -- two
+            	print(x)
+            end
+            "###);
+        }
+
+        #[test]
+        fn replaced_return_value_keeps_trailing_comment() {
+            let code = "return 1 + 1 -- two\n";
+            let mut block = parse(code);
+
+            match block.mutate_last_statement() {
+                Some(LastStatement::Return(statement)) => {
+                    *statement.iter_mut_expressions().next().unwrap() = Expression::from(2);
+                }
+                _ => panic!("unexpected statement"),
+            }
+
+            insta::assert_snapshot!(generate(&block, code), @"return 2 -- two");
+        }
+
+        #[test]
+        fn replaced_value_does_not_take_comment_of_next_statement() {
+            let code = "local x = 1 + 1 print(x) -- print\n";
+            let mut block = parse(code);
+
+            replace_first_value(&mut block, 2);
+
+            insta::assert_snapshot!(generate(&block, code), @"local x = 2 print(x) -- print");
+        }
+
+        #[test]
+        fn replaced_value_does_not_restore_comment_in_string() {
+            let code = "local x = '-- text' .. 'b'\nreturn x\n";
+            let mut block = parse(code);
+
+            replace_first_value(&mut block, StringExpression::from_value("-- textb"));
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local x = '-- textb'
+            return x
+            "###);
+        }
+
+        #[test]
+        fn removed_comment_is_not_restored() {
+            let code = "local x = 1 -- one\nreturn x\n";
+            let mut block = parse(code);
+
+            match block.first_mut_statement() {
+                Some(Statement::LocalAssign(assign)) => {
+                    if let Some(Expression::Number(number)) = assign.iter_mut_values().next() {
+                        number.clear_comments();
+                    }
+                }
+                _ => panic!("unexpected statement"),
+            }
+
+            assert_eq!(generate(&block, code), "local x = 1 \nreturn x\n");
+        }
+
+        #[test]
+        fn new_last_statement_is_written_on_its_own_line() {
+            let code = "local a = 1 -- comment";
+            let mut block = parse(code);
+
+            block.set_last_statement(ReturnStatement::one(Identifier::new("a")));
+
+            insta::assert_snapshot!(generate(&block, code), @r###"
+            local a = 1 -- comment
+            return a
+            "###);
+        }
+    }
 }
@@ -6,8 +6,9 @@ use std::convert::TryInto;
 use bstr::ByteSlice;
 
 use crate::nodes::{
-    Expression, FieldExpression, FunctionCall, IndexExpression, NumberExpression, Prefix,
-    Statement, StringSegment, TableExpression, Variable,
+    Block, Expression, FieldExpression, FunctionCall, IndexExpression, LastStatement,
+    NumberExpression, Prefix, Statement, StringSegment, TableExpression, Token, TriviaKind,
+    Variable,
 };
 
 const QUOTED_STRING_MAX_LENGTH: usize = 60;
@@ -149,6 +150,101 @@ pub fn starts_with_parenthese(statement: &Statement) -> bool {
     }
 }
 
+/// Returns the first token of a statement, or `None` if the statement does not
+/// have its tokens (like when it was not parsed with tokens).
+pub fn get_statement_first_token(statement: &Statement) -> Option<&Token> {
+    match statement {
+        Statement::Assign(assign) => assign
+            .get_variables()
+            .first()
+            .and_then(get_variable_first_token),
+        Statement::Do(do_statement) => do_statement.get_tokens().map(|tokens| &tokens.r#do),
+        Statement::Call(call) => get_prefix_first_token(call.get_prefix()),
+        Statement::CompoundAssign(assign) => get_variable_first_token(assign.get_variable()),
+        Statement::Function(function) => function.get_tokens().map(|tokens| &tokens.function),
+        Statement::GenericFor(generic_for) => generic_for.get_tokens().map(|tokens| &tokens.r#for),
+        Statement::If(if_statement) => if_statement.get_tokens().map(|tokens| &tokens.r#if),
+        Statement::LocalAssign(assign) => assign.get_tokens().map(|tokens| &tokens.local),
+        Statement::LocalFunction(function) => function.get_tokens().map(|tokens| &tokens.local),
+        Statement::NumericFor(numeric_for) => numeric_for.get_tokens().map(|tokens| &tokens.r#for),
+        Statement::Repeat(repeat) => repeat.get_tokens().map(|tokens| &tokens.repeat),
+        Statement::While(while_statement) => {
+            while_statement.get_tokens().map(|tokens| &tokens.r#while)
+        }
+        Statement::TypeDeclaration(declaration) => declaration
+            .get_tokens()
+            .map(|tokens| tokens.export.as_ref().unwrap_or(&tokens.r#type)),
+        Statement::TypeFunction(function) => function
+            .get_tokens()
+            .map(|tokens| tokens.export.as_ref().unwrap_or(&tokens.r#type)),
+    }
+}
+
+/// Returns the first token of a last statement, or `None` if the statement does not
+/// have its tokens.
+pub fn get_last_statement_first_token(statement: &LastStatement) -> Option<&Token> {
+    match statement {
+        LastStatement::Break(token) | LastStatement::Continue(token) => token.as_ref(),
+        LastStatement::Return(statement) => statement.get_tokens().map(|tokens| &tokens.r#return),
+    }
+}
+
+/// Returns the comments inserted by rules (like `append_text_comment`) at the start
+/// and at the end of a block. These comments are not read from the original code, so
+/// generators that do not write comments can still write them.
+pub fn get_block_inserted_comments(block: &Block) -> (Vec<&str>, Vec<&str>) {
+    let final_token = block
+        .get_tokens()
+        .and_then(|tokens| tokens.final_token.as_ref());
+
+    let first_token = if let Some(statement) = block.iter_statements().next() {
+        get_statement_first_token(statement)
+    } else if let Some(statement) = block.get_last_statement() {
+        get_last_statement_first_token(statement)
+    } else {
+        // the comments of an empty block are all placed on its final token
+        return (get_inserted_comments(final_token), Vec::new());
+    };
+
+    (
+        get_inserted_comments(first_token),
+        get_inserted_comments(final_token),
+    )
+}
+
+fn get_inserted_comments(token: Option<&Token>) -> Vec<&str> {
+    token
+        .into_iter()
+        .flat_map(Token::iter_leading_trivia)
+        .filter(|trivia| trivia.kind() == TriviaKind::Comment && trivia.is_inserted())
+        .filter_map(|trivia| trivia.try_read())
+        .collect()
+}
+
+fn get_variable_first_token(variable: &Variable) -> Option<&Token> {
+    match variable {
+        Variable::Identifier(identifier) => identifier.get_token(),
+        Variable::Field(field) => get_prefix_first_token(field.get_prefix()),
+        Variable::Index(index) => get_prefix_first_token(index.get_prefix()),
+    }
+}
+
+fn get_prefix_first_token(mut prefix: &Prefix) -> Option<&Token> {
+    loop {
+        match prefix {
+            Prefix::Call(call) => prefix = call.get_prefix(),
+            Prefix::Field(field) => prefix = field.get_prefix(),
+            Prefix::Index(index) => prefix = index.get_prefix(),
+            Prefix::Identifier(identifier) => break identifier.get_token(),
+            Prefix::Parenthese(parenthese) => {
+                break parenthese
+                    .get_tokens()
+                    .map(|tokens| &tokens.left_parenthese)
+            }
+        }
+    }
+}
+
 fn expression_ends_with_prefix(expression: &Expression) -> bool {
     match expression {
         Expression::Binary(binary) => expression_ends_with_prefix(binary.right()),
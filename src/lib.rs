@@ -52,6 +52,85 @@
 //!     "print('Hello, world!')"
 //! );
 //! ```
+//!
+//! Tools embedding darklua (like playgrounds or test harnesses) can create the in-memory
+//! files with [`Resources::from_memory_files`] and collect every file once processing is
+//! done with [`Resources::memory_files`]. Nothing is read from or written to the disk.
+//!
+//! ```rust
+//! use std::path::PathBuf;
+//! use darklua_core::{Options, Resources};
+//!
+//! let resources = Resources::from_memory_files([
+//!     ("src/main.lua", "local value = 1 return value"),
+//!     ("src/other.lua", "return 'other'"),
+//!     (".darklua.json", "{ rules: ['remove_spaces'], generator: 'dense' }"),
+//! ]);
+//!
+//! darklua_core::process(&resources, Options::new("src").with_output("out"))
+//!     .expect("failed to process with darklua")
+//!     .result()
+//!     .expect("failed to process files");
+//!
+//! let files = resources.memory_files();
+//! assert_eq!(
+//!     files.get(&PathBuf::from("out/main.lua")).map(String::as_str),
+//!     Some("local value=1 return value")
+//! );
+//! ```
+//!
+//! # WebAssembly
+//!
+//! The `wasm` feature adds the `process_code` function, for environments without a file
+//! system like WebAssembly modules built for the `wasm32-unknown-unknown` target. It
+//! processes code given as a string with a configuration given as a JSON string, using
+//! resources kept in memory. It returns the generated code, or the [`Diagnostic`] of each
+//! problem found, so it can be exposed to JavaScript with a thin `wasm-bindgen` wrapper.
+//! The feature does not change how the other functions access the file system.
+//!
+//! ```toml
+//! [dependencies]
+//! darklua = { version = "0.17.3", features = ["wasm"] }
+//! ```
+//!
+//! # Custom Rules
+//!
+//! Rules defined outside of darklua can implement the [`Rule`](rules::Rule) trait (or the
+//! [`FlawlessRule`](rules::FlawlessRule) trait when they cannot fail). To use them by name in
+//! configuration files, register them with [`register_rule`] before processing files.
+//!
+//! # Dependency Graph
+//!
+//! After processing, [`WorkerTree::dependency_graph`] gives the files that each processed
+//! file depends on, like the modules included in a bundle. Build tools can use it to find
+//! the outputs that change when a file is edited.
+//!
+//! ```rust
+//! use std::path::Path;
+//! use darklua_core::{
+//!     rules::PathRequireMode, BundleConfiguration, Configuration, Options, Resources,
+//! };
+//!
+//! let resources = Resources::from_memory();
+//! resources.write("src/main.lua", "local value = require('./value') return value");
+//! resources.write("src/value.lua", "return true");
+//!
+//! let config = Configuration::empty()
+//!     .with_bundle_configuration(BundleConfiguration::new(PathRequireMode::default()));
+//!
+//! let worker_tree = darklua_core::process(
+//!     &resources,
+//!     Options::new("src/main.lua")
+//!         .with_output("main.lua")
+//!         .with_configuration(config),
+//! )
+//! .expect("failed to process with darklua");
+//!
+//! assert_eq!(
+//!     worker_tree.dependency_graph().affected_sources("src/value.lua"),
+//!     vec![Path::new("src/main.lua")]
+//! );
+//! ```
 
 mod ast_converter;
 mod frontend;
@@ -62,8 +141,13 @@ pub mod process;
 pub mod rules;
 mod utils;
 
+#[cfg(feature = "wasm")]
+pub use frontend::process_code;
 pub use frontend::{
-    convert_data, process, BundleConfiguration, Configuration, DarkluaError, GeneratorParameters,
-    Options, Resources, WorkerTree,
+    convert_data, process, BundleConfiguration, Configuration, DarkluaError, DependencyGraph,
+    Diagnostic, FileReport, GeneratorParameters, LuaTarget, Options, ParseCache, ProcessingReport,
+    ResourceChange, ResourceError, Resources, RuleOverride, RulePreset, RuleReport, Severity,
+    WorkerTree,
 };
-pub use parser::{Parser, ParserError};
+pub use parser::{Parser, ParserError, SourcePosition, SourceSpan, SyntaxError};
+pub use rules::{bundle::register_data_converter, register_rule};
@@ -1,9 +1,10 @@
+use serde::Serialize;
 use std::{iter, mem};
 
 use crate::nodes::{Expression, StringExpression, TableExpression, Token};
 
 /// Tokens associated with tuple arguments.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TupleArgumentsTokens {
     pub opening_parenthese: Token,
     pub closing_parenthese: Token,
@@ -18,9 +19,10 @@ impl TupleArgumentsTokens {
 }
 
 /// Represents a list of arguments enclosed in parentheses.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct TupleArguments {
     values: Vec<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TupleArgumentsTokens>,
 }
 
@@ -156,7 +158,7 @@ impl iter::FromIterator<Expression> for TupleArguments {
 }
 
 /// Represents the different ways arguments can be passed to a function call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Arguments {
     /// Multiple arguments in parentheses: `func(arg1, arg2)`
     Tuple(TupleArguments),
@@ -282,6 +284,13 @@ impl Arguments {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        match self {
+            Arguments::Tuple(tuple) => tuple.set_token_source(source),
+            Arguments::String(_) | Arguments::Table(_) => {}
+        }
+    }
+
     /// Filters comments using the provided predicate.
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&super::Trivia) -> bool) {
         match self {
@@ -1,4 +1,5 @@
 use crate::nodes::{LastStatement, ReturnStatement, Statement, Token, Trivia, TriviaKind};
+use serde::Serialize;
 
 /// Represents the tokens associated with a Lua code block, maintaining
 /// syntax information like semicolons that separate statements.
@@ -9,10 +10,11 @@ use crate::nodes::{LastStatement, ReturnStatement, Statement, Token, Trivia, Tri
 /// - `final_token`: Optional token at the end of the block (e.g., `end` or `until`)
 ///
 /// Typically created by the parser to preserve source formatting for roundtrip transformations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BlockTokens {
     pub semicolons: Vec<Option<Token>>,
     pub last_semicolon: Option<Token>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub final_token: Option<Token>,
 }
 
@@ -24,10 +26,11 @@ impl BlockTokens {
 }
 
 /// Represents a block, a collection of [`Statement`]s that can end with a [`LastStatement`].
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Block {
     statements: Vec<Statement>,
     last_statement: Option<LastStatement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<Box<BlockTokens>>,
 }
 
@@ -404,10 +407,18 @@ impl Block {
         self.statements.last_mut().unwrap().mutate_last_token()
     }
 
+    /// Returns a mutable reference to the final token of this block, creating the
+    /// block tokens if needed. Comments placed before this token are written at the
+    /// end of the block.
+    pub fn mutate_final_token(&mut self) -> &mut Token {
+        self.set_default_tokens();
+        self.tokens.as_mut().unwrap().final_token.as_mut().unwrap()
+    }
+
     fn set_default_tokens(&mut self) {
         if self.get_tokens().is_none() {
             self.set_tokens(BlockTokens {
-                semicolons: Vec::new(),
+                semicolons: vec![None; self.statements.len()],
                 last_semicolon: None,
                 final_token: Some(Token::from_content("")),
             });
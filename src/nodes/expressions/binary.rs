@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, FunctionReturnType, Token, Type};
+use serde::Serialize;
 
 /// Represents binary operators used in a binary expression.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum BinaryOperator {
     /// Logical AND operator (`and`)
     And,
@@ -35,6 +36,16 @@ pub enum BinaryOperator {
     Caret,
     /// String concatenation operator (`..`)
     Concat,
+    /// Bitwise AND operator (`&`) from Lua 5.3
+    BitwiseAnd,
+    /// Bitwise OR operator (`|`) from Lua 5.3
+    BitwiseOr,
+    /// Bitwise exclusive OR operator (`~`) from Lua 5.3
+    BitwiseXor,
+    /// Left shift operator (`<<`) from Lua 5.3
+    LeftShift,
+    /// Right shift operator (`>>`) from Lua 5.3
+    RightShift,
 }
 
 #[inline]
@@ -176,7 +187,7 @@ impl BinaryOperator {
         };
         needs_parentheses
             || ends_with_if_expression(left)
-            || (matches!(self, BinaryOperator::LowerThan)
+            || (matches!(self, BinaryOperator::LowerThan | BinaryOperator::LeftShift)
                 && ends_with_type_cast_to_type_name_without_type_parameters(left))
     }
 
@@ -214,6 +225,11 @@ impl BinaryOperator {
             Self::Percent => "%",
             Self::Caret => "^",
             Self::Concat => "..",
+            Self::BitwiseAnd => "&",
+            Self::BitwiseOr => "|",
+            Self::BitwiseXor => "~",
+            Self::LeftShift => "<<",
+            Self::RightShift => ">>",
         }
     }
 
@@ -228,20 +244,25 @@ impl BinaryOperator {
             | Self::LowerOrEqualThan
             | Self::GreaterThan
             | Self::GreaterOrEqualThan => 2,
-            Self::Concat => 3,
-            Self::Plus | Self::Minus => 4,
-            Self::Asterisk | Self::Slash | Self::DoubleSlash | Self::Percent => 5,
-            Self::Caret => 7,
+            Self::BitwiseOr => 3,
+            Self::BitwiseXor => 4,
+            Self::BitwiseAnd => 5,
+            Self::LeftShift | Self::RightShift => 6,
+            Self::Concat => 7,
+            Self::Plus | Self::Minus => 8,
+            Self::Asterisk | Self::Slash | Self::DoubleSlash | Self::Percent => 9,
+            Self::Caret => 11,
         }
     }
 }
 
 /// Represents a binary operation in expressions.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BinaryExpression {
     operator: BinaryOperator,
     left: Expression,
     right: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
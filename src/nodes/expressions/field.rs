@@ -1,14 +1,16 @@
 use crate::nodes::{Identifier, Prefix, Token};
+use serde::Serialize;
 
 /// Represents a field access expression.
 ///
 /// A field access expression accesses a member of a table using dot notation,
 /// such as `table.field`. It consists of a prefix (the table being accessed)
 /// and a field identifier.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FieldExpression {
     prefix: Prefix,
     field: Identifier,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -55,6 +57,11 @@ impl FieldExpression {
         &self.field
     }
 
+    /// Returns a mutable reference to the field identifier of this field expression.
+    pub fn mutate_field(&mut self) -> &mut Identifier {
+        &mut self.field
+    }
+
     /// Returns a mutable reference to the prefix of this field expression.
     pub fn mutate_prefix(&mut self) -> &mut Prefix {
         &mut self.prefix
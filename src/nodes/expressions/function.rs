@@ -2,6 +2,7 @@ use crate::nodes::{
     Block, FunctionBodyTokens, FunctionReturnType, FunctionVariadicType, GenericParameters, Token,
     TypedIdentifier,
 };
+use serde::Serialize;
 
 /// Represents a function expression.
 ///
@@ -13,7 +14,7 @@ use crate::nodes::{
 ///     return a + b
 /// end
 /// ```
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct FunctionExpression {
     block: Block,
     parameters: Vec<TypedIdentifier>,
@@ -21,6 +22,7 @@ pub struct FunctionExpression {
     variadic_type: Option<FunctionVariadicType>,
     return_type: Option<FunctionReturnType>,
     generic_parameters: Option<GenericParameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<Box<FunctionBodyTokens>>,
 }
 
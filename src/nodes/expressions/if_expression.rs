@@ -1,14 +1,16 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::Expression;
 
 /// Represents an if expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfExpression {
     condition: Expression,
     result: Expression,
     else_result: Expression,
     branches: Vec<ElseIfExpressionBranch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<IfExpressionTokens>,
 }
 
@@ -166,10 +168,11 @@ impl IfExpression {
 /// Represents an elseif branch in an if expression.
 ///
 /// Each branch has a condition and a result expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ElseIfExpressionBranch {
     condition: Expression,
     result: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ElseIfExpressionBranchTokens>,
 }
 
@@ -228,7 +231,7 @@ impl ElseIfExpressionBranch {
 }
 
 /// Contains token information for an if expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfExpressionTokens {
     /// The 'if' keyword token
     pub r#if: Token,
@@ -243,7 +246,7 @@ impl IfExpressionTokens {
 }
 
 /// Contains token information for an elseif branch in an if expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ElseIfExpressionBranchTokens {
     /// The 'elseif' keyword token
     pub elseif: Token,
@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Prefix, Token};
+use serde::Serialize;
 
 /// Contains token information for an index expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IndexExpressionTokens {
     /// The opening bracket token
     pub opening_bracket: Token,
@@ -18,10 +19,11 @@ impl IndexExpressionTokens {
 /// An index expression accesses a value in a table using square bracket notation,
 /// such as `table[key]`. It consists of a prefix (the table being accessed)
 /// and an index expression that evaluates to the key.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IndexExpression {
     prefix: Prefix,
     index: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<IndexExpressionTokens>,
 }
 
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::iter::FromIterator;
 
 use crate::nodes::{IntoLuaStringValue, StringError, Token, Trivia};
@@ -8,9 +9,11 @@ use super::{string_utils, Expression};
 ///
 /// String segments are the literal text parts of an interpolated string,
 /// appearing between expression segments.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize)]
 pub struct StringSegment {
+    #[serde(serialize_with = "string_utils::serialize_string_value")]
     value: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -107,9 +110,10 @@ impl StringSegment {
 ///
 /// Value segments contain expressions that are evaluated and converted to strings
 /// when the interpolated string is evaluated.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ValueSegment {
     value: Box<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ValueSegmentTokens>,
 }
 
@@ -152,7 +156,7 @@ impl ValueSegment {
 }
 
 /// Contains token information for a value segment in an interpolated string.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ValueSegmentTokens {
     /// The opening brace token (`{`)
     pub opening_brace: Token,
@@ -165,7 +169,7 @@ impl ValueSegmentTokens {
 }
 
 /// Represents a segment in an interpolated string.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum InterpolationSegment {
     /// A literal string segment
     String(StringSegment),
@@ -204,6 +208,13 @@ impl InterpolationSegment {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        match self {
+            InterpolationSegment::String(segment) => segment.set_token_source(source),
+            InterpolationSegment::Value(segment) => segment.set_token_source(source),
+        }
+    }
+
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&Trivia) -> bool) {
         match self {
             InterpolationSegment::String(segment) => segment.filter_comments(filter),
@@ -249,9 +260,10 @@ impl From<String> for InterpolationSegment {
 }
 
 /// Represents an interpolated string expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct InterpolatedStringExpression {
     segments: Vec<InterpolationSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<InterpolatedStringTokens>,
 }
 
@@ -375,7 +387,7 @@ impl FromIterator<InterpolationSegment> for InterpolatedStringExpression {
 }
 
 /// Contains token information for an interpolated string expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct InterpolatedStringTokens {
     /// The opening backtick token
     pub opening_tick: Token,
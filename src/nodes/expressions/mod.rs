@@ -29,13 +29,14 @@ pub use type_cast::*;
 pub use unary::*;
 
 use crate::nodes::{FunctionCall, Identifier, Token, Variable};
+use serde::Serialize;
 
 use super::impl_token_fns;
 
 use std::num::FpCategory;
 
 /// Represents all possible expressions.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Expression {
     /// A binary operation (e.g., `a + b`, `x == y`)
     Binary(Box<BinaryExpression>),
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::str::FromStr;
 
@@ -5,10 +6,11 @@ use crate::generator::utils::write_number;
 use crate::nodes::{Token, Trivia};
 
 /// Represents a decimal number.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct DecimalNumber {
     float: f64,
     exponent: Option<(i64, bool)>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -93,11 +95,12 @@ impl DecimalNumber {
 ///
 /// Hexadecimal numbers are prefixed with '0x' or '0X' and can include
 /// optional binary exponents.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct HexNumber {
     integer: u64,
     exponent: Option<(u32, bool)>,
     is_x_uppercase: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -193,10 +196,11 @@ impl HexNumber {
 /// Represents a binary number.
 ///
 /// Binary numbers are prefixed with '0b' or '0B' and consist of 0s and 1s.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct BinaryNumber {
     value: u64,
     is_b_uppercase: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -263,7 +267,7 @@ impl BinaryNumber {
 }
 
 /// Represents a numeric literal expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum NumberExpression {
     /// A decimal number (e.g., `123.45`, `1e10`)
     Decimal(DecimalNumber),
@@ -366,6 +370,14 @@ impl NumberExpression {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        match self {
+            NumberExpression::Decimal(number) => number.set_token_source(source),
+            NumberExpression::Hex(number) => number.set_token_source(source),
+            NumberExpression::Binary(number) => number.set_token_source(source),
+        }
+    }
+
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&Trivia) -> bool) {
         match self {
             NumberExpression::Decimal(number) => number.filter_comments(filter),
@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Token};
+use serde::Serialize;
 
 /// Contains token information for a parenthesized expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ParentheseTokens {
     /// The left (opening) parenthesis token
     pub left_parenthese: Token,
@@ -14,9 +15,10 @@ impl ParentheseTokens {
 }
 
 /// Represents a parenthesized expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ParentheseExpression {
     expression: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ParentheseTokens>,
 }
 
@@ -2,12 +2,13 @@ use crate::nodes::{
     Expression, FieldExpression, FunctionCall, Identifier, IndexExpression, ParentheseExpression,
     Token,
 };
+use serde::Serialize;
 
 /// Represents a prefix expression.
 ///
 /// Prefix expressions form the base for more complex expressions like method calls
 /// and property access chains.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Prefix {
     /// A function call expression (e.g., `func()`)
     Call(Box<FunctionCall>),
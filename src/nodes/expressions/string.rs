@@ -2,6 +2,7 @@ use crate::{
     generator::utils::write_string,
     nodes::{StringError, Token},
 };
+use serde::Serialize;
 
 use super::string_utils;
 
@@ -9,9 +10,11 @@ use super::string_utils;
 ///
 /// String literals in Lua can be written with single quotes, double quotes,
 /// or with long brackets (`[[...]]` or `[=[...]=]` etc.) for multi-line strings.
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone, PartialEq, Eq, Serialize)]
 pub struct StringExpression {
+    #[serde(serialize_with = "string_utils::serialize_string_value")]
     value: Vec<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
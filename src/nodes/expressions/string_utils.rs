@@ -43,6 +43,18 @@ impl StringError {
     }
 }
 
+/// Serializes the value of a string as text when it is valid UTF-8, or as a
+/// list of bytes otherwise.
+pub(crate) fn serialize_string_value<S: serde::Serializer>(
+    value: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match std::str::from_utf8(value) {
+        Ok(string) => serializer.serialize_str(string),
+        Err(_) => serializer.serialize_bytes(value),
+    }
+}
+
 pub(crate) fn read_escaped_string(
     chars: CharIndices,
     reserve_size: Option<usize>,
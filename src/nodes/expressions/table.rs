@@ -2,16 +2,18 @@ use crate::{
     nodes::{Expression, Identifier, Token, Trivia},
     process::utils::is_valid_identifier,
 };
+use serde::Serialize;
 
 use super::StringExpression;
 
 /// Represents a field entry in a table literal where the key is an identifier.
 ///
 /// This corresponds to the form: `{ field = value }`
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableFieldEntry {
     field: Identifier,
     value: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -74,7 +76,7 @@ impl TableFieldEntry {
 }
 
 /// Contains tokens for a table index entry.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableIndexEntryTokens {
     /// Token for the opening bracket `[`
     pub opening_bracket: Token,
@@ -89,10 +91,11 @@ impl TableIndexEntryTokens {
 }
 
 /// Represents an index entry in a table literal where the key is a computed expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableIndexEntry {
     key: Expression,
     value: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<Box<TableIndexEntryTokens>>,
 }
 
@@ -152,7 +155,7 @@ impl TableIndexEntry {
 }
 
 /// Represents a single entry in a table literal.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TableEntry {
     /// A named field entry (e.g., `{ field = value }`)
     Field(Box<TableFieldEntry>),
@@ -224,6 +227,14 @@ impl TableEntry {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        match self {
+            TableEntry::Field(entry) => entry.set_token_source(source),
+            TableEntry::Index(entry) => entry.set_token_source(source),
+            TableEntry::Value(_) => {}
+        }
+    }
+
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&Trivia) -> bool) {
         match self {
             TableEntry::Field(entry) => entry.filter_comments(filter),
@@ -246,7 +257,7 @@ impl From<TableIndexEntry> for TableEntry {
 }
 
 /// Contains tokens for a table expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableTokens {
     /// Token for the opening brace `{`
     pub opening_brace: Token,
@@ -264,9 +275,10 @@ impl TableTokens {
 }
 
 /// Represents a table expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableExpression {
     entries: Vec<TableEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TableTokens>,
 }
 
@@ -1,12 +1,14 @@
 use crate::nodes::{Expression, Token, Type};
+use serde::Serialize;
 
 /// Represents a type cast expression.
 ///
 /// This corresponds to expressions like: `expression :: type`
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeCastExpression {
     expression: Box<Expression>,
     r#type: Box<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
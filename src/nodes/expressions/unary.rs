@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Token};
+use serde::Serialize;
 
 /// Represents the type of operator in a unary expression.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum UnaryOperator {
     /// The length operator (`#`)
     Length,
@@ -9,6 +10,8 @@ pub enum UnaryOperator {
     Minus,
     /// The not operator (`not`)
     Not,
+    /// The bitwise not operator (`~`) from Lua 5.3
+    BitwiseNot,
 }
 
 impl UnaryOperator {
@@ -18,15 +21,17 @@ impl UnaryOperator {
             Self::Length => "#",
             Self::Minus => "-",
             Self::Not => "not",
+            Self::BitwiseNot => "~",
         }
     }
 }
 
 /// Represents a unary operation applied to an expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct UnaryExpression {
     operator: UnaryOperator,
     expression: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
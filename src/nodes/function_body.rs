@@ -4,6 +4,7 @@ use super::{
     LocalFunctionTokens, Token, TypeFunctionStatement, TypeFunctionStatementTokens,
     TypedIdentifier,
 };
+use serde::Serialize;
 
 pub(crate) struct FunctionBuilder {
     block: Block,
@@ -279,7 +280,7 @@ impl FunctionBuilder {
 }
 
 /// Represents the token information associated with a function body.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionBodyTokens {
     /// The 'function' keyword token.
     pub function: Token,
@@ -1,7 +1,8 @@
 use crate::nodes::{Arguments, Expression, Identifier, Prefix, Token};
+use serde::Serialize;
 
 /// Tokens associated with a function call.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionCallTokens {
     pub colon: Option<Token>,
 }
@@ -11,11 +12,12 @@ impl FunctionCallTokens {
 }
 
 /// Represents a function call expression (e.g., `func()`, `obj:method()`, `a.b.c()`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionCall {
     prefix: Box<Prefix>,
     arguments: Arguments,
     method: Option<Identifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<FunctionCallTokens>,
 }
 
@@ -98,6 +100,12 @@ impl FunctionCall {
         self.method.as_ref()
     }
 
+    /// Returns a mutable reference to the method name, if this is a method call.
+    #[inline]
+    pub fn mutate_method(&mut self) -> Option<&mut Identifier> {
+        self.method.as_mut()
+    }
+
     /// Returns if this call uses a method.
     #[inline]
     pub fn has_method(&self) -> bool {
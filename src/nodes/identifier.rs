@@ -1,11 +1,13 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::{Type, TypedIdentifier};
 
 /// Represents an identifier (variable name).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Identifier {
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
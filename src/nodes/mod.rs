@@ -96,6 +96,22 @@ macro_rules! impl_token_fns {
             )*)?
         }
 
+        pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+            $(
+                self.$field.set_token_source(source);
+            )*
+            $($(
+                for token in self.$iter_field.iter_mut() {
+                    token.set_token_source(source);
+                }
+            )*)?
+            $($(
+                for token in self.$iter_flatten_field.iter_mut().flatten() {
+                    token.set_token_source(source);
+                }
+            )*)?
+        }
+
         pub(crate) fn filter_comments(&mut self, filter: impl Fn(&crate::nodes::Trivia) -> bool) {
             $(
                 self.$field.filter_comments(&filter);
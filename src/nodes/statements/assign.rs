@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Token, Variable};
+use serde::Serialize;
 
 /// Tokens associated with an assignment statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct AssignTokens {
     pub equal: Token,
     pub variable_commas: Vec<Token>,
@@ -16,10 +17,11 @@ impl AssignTokens {
 }
 
 /// Represents a variable assignment statement (e.g., `a, b = 1, 2`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct AssignStatement {
     variables: Vec<Variable>,
     values: Vec<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<AssignTokens>,
 }
 
@@ -1,7 +1,8 @@
 use crate::nodes::{BinaryOperator, Expression, Token, Variable};
+use serde::Serialize;
 
 /// Represents compound assignment operators (e.g., `+=`, `-=`, etc.).
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum CompoundOperator {
     /// Addition and assignment (`+=`)
     Plus,
@@ -49,10 +50,25 @@ impl CompoundOperator {
             Self::Concat => BinaryOperator::Concat,
         }
     }
+
+    /// Returns the compound operator of a binary operator, if it has one.
+    pub fn from_binary_operator(operator: BinaryOperator) -> Option<Self> {
+        match operator {
+            BinaryOperator::Plus => Some(Self::Plus),
+            BinaryOperator::Minus => Some(Self::Minus),
+            BinaryOperator::Asterisk => Some(Self::Asterisk),
+            BinaryOperator::Slash => Some(Self::Slash),
+            BinaryOperator::DoubleSlash => Some(Self::DoubleSlash),
+            BinaryOperator::Percent => Some(Self::Percent),
+            BinaryOperator::Caret => Some(Self::Caret),
+            BinaryOperator::Concat => Some(Self::Concat),
+            _ => None,
+        }
+    }
 }
 
 /// Tokens associated with a compound assignment statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CompoundAssignTokens {
     /// The operator token for the compound assignment.
     pub operator: Token,
@@ -63,11 +79,12 @@ impl CompoundAssignTokens {
 }
 
 /// Represents a compound assignment statement (e.g., `a += 1`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct CompoundAssignStatement {
     operator: CompoundOperator,
     variable: Variable,
     value: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<CompoundAssignTokens>,
 }
 
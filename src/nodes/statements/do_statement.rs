@@ -1,7 +1,8 @@
 use crate::nodes::{Block, Token};
+use serde::Serialize;
 
 /// Tokens associated with a do statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct DoTokens {
     pub r#do: Token,
     pub end: Token,
@@ -12,9 +13,10 @@ impl DoTokens {
 }
 
 /// Represents a do statement (e.g., `do ... end`).
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct DoStatement {
     block: Block,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<DoTokens>,
 }
 
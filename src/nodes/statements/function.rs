@@ -2,9 +2,10 @@ use crate::nodes::{
     Block, FunctionBodyTokens, FunctionReturnType, FunctionVariadicType, GenericParameters,
     Identifier, Token, TypedIdentifier,
 };
+use serde::Serialize;
 
 /// Tokens associated with a function name.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionNameTokens {
     /// The tokens for the periods in the function name.
     pub periods: Vec<Token>,
@@ -20,11 +21,12 @@ impl FunctionNameTokens {
 ///
 /// Function names can include table fields and methods
 /// ([e.g., `module.table:method`]).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionName {
     name: Identifier,
     field_names: Vec<Identifier>,
     method: Option<Identifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<FunctionNameTokens>,
 }
 
@@ -102,6 +104,12 @@ impl FunctionName {
         self.method.as_ref()
     }
 
+    /// Returns a mutable reference to the method, if any.
+    #[inline]
+    pub fn mutate_method(&mut self) -> Option<&mut Identifier> {
+        self.method.as_mut()
+    }
+
     /// Returns whether this function name has a method component.
     #[inline]
     pub fn has_method(&self) -> bool {
@@ -126,6 +134,12 @@ impl FunctionName {
         &self.field_names
     }
 
+    /// Returns a mutable reference to the field names.
+    #[inline]
+    pub fn mutate_field_names(&mut self) -> &mut Vec<Identifier> {
+        &mut self.field_names
+    }
+
     /// Returns a mutable reference to the base identifier.
     #[inline]
     pub fn mutate_identifier(&mut self) -> &mut Identifier {
@@ -136,7 +150,7 @@ impl FunctionName {
 }
 
 /// Represents a function declaration statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionStatement {
     name: FunctionName,
     block: Block,
@@ -145,6 +159,7 @@ pub struct FunctionStatement {
     variadic_type: Option<FunctionVariadicType>,
     return_type: Option<FunctionReturnType>,
     generic_parameters: Option<GenericParameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<Box<FunctionBodyTokens>>,
 }
 
@@ -1,7 +1,8 @@
 use crate::nodes::{Block, Expression, Token, TypedIdentifier};
+use serde::Serialize;
 
 /// Tokens associated with a generic for statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericForTokens {
     pub r#for: Token,
     pub r#in: Token,
@@ -21,11 +22,12 @@ impl GenericForTokens {
 }
 
 /// Represents a generic for loop statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericForStatement {
     identifiers: Vec<TypedIdentifier>,
     expressions: Vec<Expression>,
     block: Block,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<GenericForTokens>,
 }
 
@@ -1,9 +1,10 @@
+use serde::Serialize;
 use std::mem;
 
 use crate::nodes::{Block, Expression, Token};
 
 /// Tokens associated with an if branch.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfBranchTokens {
     pub elseif: Token,
     pub then: Token,
@@ -14,10 +15,11 @@ impl IfBranchTokens {
 }
 
 /// Represents a conditional branch in an if statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfBranch {
     condition: Expression,
     block: Block,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<IfBranchTokens>,
 }
 
@@ -92,7 +94,7 @@ impl IfBranch {
 }
 
 /// Tokens associated with an if statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfStatementTokens {
     pub r#if: Token,
     pub then: Token,
@@ -108,10 +110,11 @@ impl IfStatementTokens {
 }
 
 /// Represents an if statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IfStatement {
     branches: Vec<IfBranch>,
     else_block: Option<Block>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<IfStatementTokens>,
 }
 
@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Token};
+use serde::Serialize;
 
 /// Tokens associated with a return statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ReturnTokens {
     pub r#return: Token,
     /// The tokens for the commas between expressions.
@@ -16,9 +17,10 @@ impl ReturnTokens {
 }
 
 /// Represents a return statement.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct ReturnStatement {
     expressions: Vec<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ReturnTokens>,
 }
 
@@ -137,7 +139,7 @@ impl ReturnStatement {
 }
 
 /// Represents a statement that can appear as the last statement in a block.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum LastStatement {
     Break(Option<Token>),
     Continue(Option<Token>),
@@ -1,7 +1,8 @@
 use crate::nodes::{Expression, Token, TypedIdentifier};
+use serde::Serialize;
 
 /// Tokens associated with a local variable assignment statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct LocalAssignTokens {
     pub local: Token,
     /// The token for the equal sign, if any.
@@ -20,10 +21,11 @@ impl LocalAssignTokens {
 }
 
 /// Represents a local variable assignment statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct LocalAssignStatement {
     variables: Vec<TypedIdentifier>,
     values: Vec<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<LocalAssignTokens>,
 }
 
@@ -2,9 +2,10 @@ use crate::nodes::{
     Block, FunctionBodyTokens, FunctionReturnType, FunctionVariadicType, GenericParameters,
     Identifier, Token, TypedIdentifier,
 };
+use serde::Serialize;
 
 /// Tokens associated with a local function statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct LocalFunctionTokens {
     pub local: Token,
     pub function_body: FunctionBodyTokens,
@@ -29,7 +30,7 @@ impl std::ops::DerefMut for LocalFunctionTokens {
 }
 
 /// Represents a local function declaration statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct LocalFunctionStatement {
     identifier: Identifier,
     block: Block,
@@ -38,6 +39,7 @@ pub struct LocalFunctionStatement {
     variadic_type: Option<FunctionVariadicType>,
     return_type: Option<FunctionReturnType>,
     generic_parameters: Option<GenericParameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<Box<LocalFunctionTokens>>,
 }
 
@@ -29,11 +29,12 @@ pub use type_function::*;
 pub use while_statement::*;
 
 use crate::nodes::FunctionCall;
+use serde::Serialize;
 
 use super::impl_token_fns;
 
 /// Represents all possible statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Statement {
     /// An assignment statement (e.g., `a = 1`)
     Assign(AssignStatement),
@@ -1,7 +1,8 @@
 use crate::nodes::{Block, Expression, Token, TypedIdentifier};
+use serde::Serialize;
 
 /// Tokens associated with a numeric for statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct NumericForTokens {
     pub r#for: Token,
     pub equal: Token,
@@ -21,13 +22,14 @@ impl NumericForTokens {
 }
 
 /// Represents a numeric for loop statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct NumericForStatement {
     identifier: TypedIdentifier,
     start: Expression,
     end: Expression,
     step: Option<Expression>,
     block: Block,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<NumericForTokens>,
 }
 
@@ -1,7 +1,8 @@
 use crate::nodes::{Block, Expression, Token};
+use serde::Serialize;
 
 /// Tokens associated with a repeat statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct RepeatTokens {
     pub repeat: Token,
     pub until: Token,
@@ -12,10 +13,11 @@ impl RepeatTokens {
 }
 
 /// Represents a repeat loop statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct RepeatStatement {
     block: Block,
     condition: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<RepeatTokens>,
 }
 
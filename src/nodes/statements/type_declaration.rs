@@ -1,9 +1,10 @@
 use crate::nodes::{
     GenericParameterMutRef, GenericParametersWithDefaults, Identifier, Token, Trivia, Type,
 };
+use serde::Serialize;
 
 /// Tokens associated with a type declaration statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeDeclarationTokens {
     pub r#type: Token,
     pub equal: Token,
@@ -18,12 +19,13 @@ impl TypeDeclarationTokens {
 }
 
 /// Represents a type declaration statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeDeclarationStatement {
     name: Identifier,
     r#type: Box<Type>,
     exported: bool,
     generic_parameters: Option<GenericParametersWithDefaults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TypeDeclarationTokens>,
 }
 
@@ -251,6 +253,33 @@ impl TypeDeclarationStatement {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        self.name.set_token_source(source);
+        if let Some(tokens) = &mut self.tokens {
+            tokens.set_token_source(source);
+        }
+        if let Some(parameters) = self.generic_parameters.as_mut() {
+            parameters.set_token_source(source);
+
+            for parameter in parameters {
+                match parameter {
+                    GenericParameterMutRef::TypeVariable(variable) => {
+                        variable.set_token_source(source);
+                    }
+                    GenericParameterMutRef::TypeVariableWithDefault(variable_with_default) => {
+                        variable_with_default.set_token_source(source);
+                    }
+                    GenericParameterMutRef::GenericTypePack(_) => {}
+                    GenericParameterMutRef::GenericTypePackWithDefault(
+                        generic_pack_with_default,
+                    ) => {
+                        generic_pack_with_default.set_token_source(source);
+                    }
+                }
+            }
+        }
+    }
+
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&Trivia) -> bool) {
         self.name.filter_comments(&filter);
         if let Some(tokens) = &mut self.tokens {
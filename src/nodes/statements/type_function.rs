@@ -2,9 +2,10 @@ use crate::nodes::{
     Block, FunctionBodyTokens, FunctionReturnType, FunctionVariadicType, GenericParameters,
     Identifier, Token, TypedIdentifier,
 };
+use serde::Serialize;
 
 /// Represents a type function statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeFunctionStatement {
     identifier: Identifier,
     block: Block,
@@ -14,6 +15,7 @@ pub struct TypeFunctionStatement {
     return_type: Option<FunctionReturnType>,
     generic_parameters: Option<GenericParameters>,
     exported: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TypeFunctionStatementTokens>,
 }
 
@@ -293,7 +295,7 @@ impl TypeFunctionStatement {
 }
 
 /// Tokens associated with a type function statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeFunctionStatementTokens {
     pub r#type: Token,
     pub function_body: FunctionBodyTokens,
@@ -1,7 +1,8 @@
 use crate::nodes::{token::Token, Block, Expression};
+use serde::Serialize;
 
 /// Tokens associated with a while statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct WhileTokens {
     pub r#while: Token,
     pub r#do: Token,
@@ -13,10 +14,11 @@ impl WhileTokens {
 }
 
 /// Represents a while loop statement.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct WhileStatement {
     block: Block,
     condition: Expression,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<WhileTokens>,
 }
 
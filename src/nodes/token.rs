@@ -1,7 +1,11 @@
+use serde::Serialize;
 use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
 
 /// Represents a position in the source code.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Position {
     /// A position that references a specific range in the source code
     /// with line number information.
@@ -32,7 +36,7 @@ impl Position {
 }
 
 /// An enum to represent source code text.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TriviaKind {
     /// A comment.
     Comment,
@@ -65,7 +69,7 @@ impl TriviaKind {
 }
 
 /// Represents a piece of trivia (whitespace or comments) in the source code.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct Trivia {
     position: Position,
     kind: TriviaKind,
@@ -97,6 +101,12 @@ impl Trivia {
         }
     }
 
+    /// Returns true when the trivia content was not read from the source code, like a
+    /// comment inserted by a rule.
+    pub(crate) fn is_inserted(&self) -> bool {
+        matches!(self.position, Position::Any { .. })
+    }
+
     /// Returns the kind of trivia.
     pub fn kind(&self) -> TriviaKind {
         self.kind.clone()
@@ -112,12 +122,46 @@ impl Trivia {
     }
 }
 
+/// Where a token was originally parsed, used to generate source maps.
+#[derive(Clone, Debug, Default)]
+struct TokenOrigin {
+    /// The file of the token, when it is not the file being processed.
+    source: Option<Arc<Path>>,
+    /// The number of lines the token was moved by since it was parsed.
+    line_shift: isize,
+}
+
 /// Represents a token in the source code with its position and associated comments or whitespaces.
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// Tokens are compared and formatted with their position and trivia only (not with
+/// the file they were originally parsed from).
+#[derive(Clone, Serialize)]
 pub struct Token {
     position: Position,
     leading_trivia: Vec<Trivia>,
     trailing_trivia: Vec<Trivia>,
+    #[serde(skip_serializing)]
+    origin: Option<Box<TokenOrigin>>,
+}
+
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.position == other.position
+            && self.leading_trivia == other.leading_trivia
+            && self.trailing_trivia == other.trailing_trivia
+    }
+}
+
+impl Eq for Token {}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Token")
+            .field("position", &self.position)
+            .field("leading_trivia", &self.leading_trivia)
+            .field("trailing_trivia", &self.trailing_trivia)
+            .finish()
+    }
 }
 
 impl Token {
@@ -132,6 +176,7 @@ impl Token {
             },
             leading_trivia: Vec::new(),
             trailing_trivia: Vec::new(),
+            origin: None,
         }
     }
 
@@ -143,6 +188,7 @@ impl Token {
             },
             leading_trivia: Vec::new(),
             trailing_trivia: Vec::new(),
+            origin: None,
         }
     }
 
@@ -152,6 +198,7 @@ impl Token {
             position,
             leading_trivia: Vec::new(),
             trailing_trivia: Vec::new(),
+            origin: None,
         }
     }
 
@@ -231,6 +278,35 @@ impl Token {
         }
     }
 
+    /// Returns the range of bytes of the token in the original code, if the token
+    /// references it.
+    pub(crate) fn get_range(&self) -> Option<(usize, usize)> {
+        match &self.position {
+            Position::LineNumberReference { start, end, .. } => Some((*start, *end)),
+            Position::LineNumber { .. } | Position::Any { .. } => None,
+        }
+    }
+
+    /// Returns the file where the token was parsed, when it comes from another file
+    /// than the one being processed (like the modules inlined by the bundler).
+    pub(crate) fn get_source(&self) -> Option<&Arc<Path>> {
+        self.origin
+            .as_ref()
+            .and_then(|origin| origin.source.as_ref())
+    }
+
+    /// Returns the line number where the token was parsed, even if the token was
+    /// moved to another line since then.
+    pub(crate) fn get_source_line_number(&self) -> Option<usize> {
+        let line_shift = self
+            .origin
+            .as_ref()
+            .map(|origin| origin.line_shift)
+            .unwrap_or_default();
+        self.get_line_number()
+            .map(|line_number| line_number.saturating_add_signed(-line_shift))
+    }
+
     /// Returns the line number of the token, if available.
     pub fn get_line_number(&self) -> Option<usize> {
         match &self.position {
@@ -320,11 +396,16 @@ impl Token {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &Arc<Path>) {
+        self.origin.get_or_insert_with(Default::default).source = Some(source.clone());
+    }
+
     pub(crate) fn shift_token_line(&mut self, amount: isize) {
         match &mut self.position {
             Position::LineNumberReference { line_number, .. }
             | Position::LineNumber { line_number, .. } => {
                 *line_number = line_number.saturating_add_signed(amount);
+                self.origin.get_or_insert_with(Default::default).line_shift += amount;
             }
             Position::Any { .. } => {}
         }
@@ -1,14 +1,16 @@
 use crate::nodes::{Identifier, Token, Type};
+use serde::Serialize;
 
 /// Represents an identifier with an optional type annotation.
 ///
 /// TypedIdentifier extends the basic Identifier to support Luau's type system, where
 /// variables and parameters can have explicit type annotations. It stores the
 /// identifier itself, the optional type, and the colon token for source preservation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypedIdentifier {
     name: Identifier,
     r#type: Option<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
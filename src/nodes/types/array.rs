@@ -1,11 +1,13 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::Type;
 
 /// Represents an array type annotation (e.g. `{ ElementType }`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ArrayType {
     inner_type: Box<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ArrayTypeTokens>,
 }
 
@@ -64,7 +66,7 @@ impl ArrayType {
 /// Contains the tokens that define the array type syntax.
 ///
 /// These tokens represent the opening and closing braces in an array type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ArrayTypeTokens {
     /// The opening brace token.
     pub opening_brace: Token,
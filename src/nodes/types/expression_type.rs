@@ -1,9 +1,11 @@
 use crate::nodes::{Expression, Token};
+use serde::Serialize;
 
 /// Represents a `typeof(expression)` type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ExpressionType {
     expression: Box<Expression>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ExpressionTypeTokens>,
 }
 
@@ -65,7 +67,7 @@ impl ExpressionType {
 /// Contains the tokens that define the `typeof` expression syntax.
 ///
 /// These tokens represent the `typeof` keyword and the parentheses around the expression.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ExpressionTypeTokens {
     /// The `typeof` keyword token.
     pub r#typeof: Token,
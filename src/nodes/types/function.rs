@@ -1,12 +1,14 @@
 use crate::nodes::{Identifier, Token};
+use serde::Serialize;
 
 use super::{GenericParameters, GenericTypePack, Type, TypePack, VariadicTypePack};
 
 /// Represents a single argument in a function type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionArgumentType {
     argument_type: Type,
     name: Option<Identifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -83,7 +85,7 @@ impl FunctionArgumentType {
 }
 
 /// Represents the return type of a function type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum FunctionReturnType {
     /// A single type return value.
     Type(Box<Type>),
@@ -138,7 +140,7 @@ impl From<VariadicTypePack> for FunctionReturnType {
 }
 
 /// Represents a variadic argument type in a function annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum VariadicArgumentType {
     /// A generic type pack used as a variadic argument.
     GenericTypePack(GenericTypePack),
@@ -159,12 +161,13 @@ impl From<VariadicTypePack> for VariadicArgumentType {
 }
 
 /// Represents a function type annotation in Luau.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionType {
     arguments: Vec<FunctionArgumentType>,
     variadic_argument_type: Option<VariadicArgumentType>,
     return_type: FunctionReturnType,
     generic_parameters: Option<GenericParameters>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<FunctionTypeTokens>,
 }
 
@@ -307,7 +310,7 @@ impl FunctionType {
 }
 
 /// Represents the tokens associated with a function type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct FunctionTypeTokens {
     /// The opening parenthesis token.
     pub opening_parenthese: Token,
@@ -1,7 +1,8 @@
 use super::{GenericTypePack, Type};
+use serde::Serialize;
 
 /// Represents a variadic type in a function signature.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum FunctionVariadicType {
     /// A specific type for variadic arguments.
     Type(Box<Type>),
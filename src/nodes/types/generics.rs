@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::marker::PhantomData;
 
 use crate::nodes::{Identifier, Token, TypePack, VariadicTypePack};
@@ -8,10 +9,11 @@ use super::Type;
 ///
 /// Generic type packs represent a pack of types that can be specified later,
 /// written as `T...` where T is a type pack parameter name.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericTypePack {
     // name ...
     name: Identifier,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -73,11 +75,12 @@ impl GenericTypePack {
 ///
 /// Generic parameters allow type signatures to be parameterized,
 /// written as `<T, U...>` in Luau type annotations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericParameters {
     // generic type list
     type_variables: Vec<Identifier>,
     generic_type_packs: Vec<GenericTypePack>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<GenericParametersTokens>,
 }
 
@@ -184,7 +187,7 @@ impl GenericParameters {
 /// Contains the tokens that define the generic parameters syntax.
 ///
 /// These tokens represent the angle brackets and commas in generic parameters.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericParametersTokens {
     /// The opening angle bracket token.
     pub opening_list: Token,
@@ -202,7 +205,7 @@ impl GenericParametersTokens {
 }
 
 /// Represents the default value for a generic type pack.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum GenericTypePackDefault {
     /// A type pack default.
     TypePack(Box<TypePack>),
@@ -231,11 +234,12 @@ impl From<GenericTypePack> for GenericTypePackDefault {
 }
 
 /// Represents a generic type pack with a default value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericTypePackWithDefault {
     generic_type_pack: GenericTypePack,
     default: GenericTypePackDefault,
     // equal sign token
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -298,11 +302,12 @@ impl GenericTypePackWithDefault {
 }
 
 /// Represents a type variable with a default value.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeVariableWithDefault {
     variable: Identifier,
     default: Type,
     // equal sign token
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -365,11 +370,12 @@ impl TypeVariableWithDefault {
 }
 
 /// Represents a collection of generic parameters that may include default values.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct GenericParametersWithDefaults {
     type_variables: Vec<Identifier>,
     middle: GenericParametersWithDefaultsMiddle,
     generic_type_packs_with_default: Vec<GenericTypePackWithDefault>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<GenericParametersTokens>,
 }
 
@@ -559,7 +565,7 @@ impl GenericParametersWithDefaults {
 }
 
 /// Represents a generic parameter in a type or function signature.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum GenericParameter {
     /// A simple type variable like `T`.
     TypeVariable(Identifier),
@@ -845,7 +851,7 @@ impl<'a> IntoIterator for &'a GenericParametersWithDefaults {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 enum GenericParametersWithDefaultsMiddle {
     Empty,
     GenericTypePacks(Vec<GenericTypePack>),
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::iter;
 
 use crate::nodes::Token;
@@ -5,10 +6,11 @@ use crate::nodes::Token;
 use super::Type;
 
 /// Represents an intersection type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IntersectionType {
     types: Vec<Type>,
     leading_operator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<IntersectionTypeTokens>,
 }
 
@@ -161,9 +163,10 @@ impl iter::FromIterator<Type> for IntersectionType {
 /// Contains the tokens that define the intersection type syntax.
 ///
 /// These tokens represent the `&` operators that separate type components.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct IntersectionTypeTokens {
     /// Optional leading `&` token before the first type.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub leading_token: Option<Token>,
     /// The `&` tokens separating the type components.
     pub separators: Vec<Token>,
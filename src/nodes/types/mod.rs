@@ -31,11 +31,12 @@ pub use union::*;
 pub use variadic_type_pack::*;
 
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::impl_token_fns;
 
 /// Represents a type annotation in Luau.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Type {
     /// A named type, such as `string` or a user-defined type.
     Name(TypeName),
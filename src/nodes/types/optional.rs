@@ -1,11 +1,13 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::Type;
 
 /// Represents an optional type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct OptionalType {
     inner_type: Box<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
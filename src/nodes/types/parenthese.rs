@@ -1,11 +1,13 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::Type;
 
 /// Represents a parenthesized type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ParentheseType {
     inner_type: Box<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<ParentheseTypeTokens>,
 }
 
@@ -74,7 +76,7 @@ impl ParentheseType {
 }
 
 /// Contains the tokens that define the parenthesized type syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ParentheseTypeTokens {
     /// The left parenthesis token.
     pub left_parenthese: Token,
@@ -1,7 +1,8 @@
 use crate::nodes::{StringError, StringExpression, Token};
+use serde::Serialize;
 
 /// Represents a string literal used in type annotations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct StringType {
     value: StringExpression,
 }
@@ -1,13 +1,15 @@
 use crate::nodes::{Identifier, Token, Trivia};
+use serde::Serialize;
 
 use super::{StringType, Type};
 
 /// Represents an indexer in a table type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableIndexerType {
     key_type: Box<Type>,
     value_type: Box<Type>,
     modifier: Option<TablePropertyModifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TableIndexTypeTokens>,
 }
 
@@ -99,7 +101,7 @@ impl TableIndexerType {
 }
 
 /// Contains the tokens that define an indexer's syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableIndexTypeTokens {
     /// The opening bracket token.
     pub opening_bracket: Token,
@@ -119,16 +121,17 @@ impl TableIndexTypeTokens {
 }
 
 /// Represents a named property in a table type annotation (i.e. `name: Type`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TablePropertyType {
     property: Identifier,
     r#type: Box<Type>,
     modifier: Option<TablePropertyModifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TablePropertyTypeTokens>,
 }
 
 /// Contains the tokens that define a property's syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TablePropertyTypeTokens {
     /// The colon token.
     pub colon: Token,
@@ -231,11 +234,12 @@ impl TablePropertyType {
 }
 
 /// Represents a string literal property in a table type annotation (i.e. `["key"]: Type`).
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableLiteralPropertyType {
     string: StringType,
     r#type: Box<Type>,
     modifier: Option<TablePropertyModifier>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TableIndexTypeTokens>,
 }
 
@@ -327,14 +331,14 @@ impl TableLiteralPropertyType {
 }
 
 /// Represents an entry in a table type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TablePropertyModifier {
     Read,
     Write,
 }
 
 /// Represents an entry in a table type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TableEntryType {
     /// A named property entry.
     Property(TablePropertyType),
@@ -416,6 +420,14 @@ impl TableEntryType {
         }
     }
 
+    pub(crate) fn set_token_source(&mut self, source: &std::sync::Arc<std::path::Path>) {
+        match self {
+            TableEntryType::Property(property) => property.set_token_source(source),
+            TableEntryType::Literal(literal) => literal.set_token_source(source),
+            TableEntryType::Indexer(indexer) => indexer.set_token_source(source),
+        }
+    }
+
     pub(crate) fn filter_comments(&mut self, filter: impl Fn(&Trivia) -> bool) {
         match self {
             TableEntryType::Property(property) => property.filter_comments(filter),
@@ -444,9 +456,10 @@ impl From<TableIndexerType> for TableEntryType {
 }
 
 /// Represents a table type annotation.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct TableType {
     entries: Vec<TableEntryType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TableTypeTokens>,
 }
 
@@ -589,7 +602,7 @@ impl TableType {
 }
 
 /// Contains the tokens that define a table type's syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TableTypeTokens {
     /// The opening brace token.
     pub opening_brace: Token,
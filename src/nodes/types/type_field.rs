@@ -1,12 +1,14 @@
 use crate::nodes::{Identifier, Token};
+use serde::Serialize;
 
 use super::TypeName;
 
 /// Represents a field access on a type.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeField {
     namespace: Identifier,
     name: TypeName,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::iter::FromIterator;
 
 use crate::nodes::{Identifier, Token};
@@ -5,7 +6,7 @@ use crate::nodes::{Identifier, Token};
 use super::{GenericTypePack, Type, TypePack, VariadicTypePack};
 
 /// Represents a named type.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeName {
     type_name: Identifier,
     type_parameters: Option<Box<TypeParameters>>,
@@ -89,9 +90,10 @@ impl TypeName {
 ///
 /// Type parameters are used in generic types, written as `Array<T>`
 /// or `Map<K, V>` in type annotations.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeParameters {
     parameters: Vec<TypeParameter>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TypeParametersTokens>,
 }
 
@@ -208,7 +210,7 @@ impl<'a> IntoIterator for &'a TypeParameters {
 }
 
 /// Represents a type parameter in a generic type.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum TypeParameter {
     /// A single type parameter.
     Type(Type),
@@ -250,7 +252,7 @@ impl From<GenericTypePack> for TypeParameter {
 }
 
 /// Contains the tokens that define the type parameters syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypeParametersTokens {
     /// The opening angle bracket token.
     pub opening_list: Token,
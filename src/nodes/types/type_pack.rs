@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::iter::FromIterator;
 
 use crate::nodes::Token;
@@ -5,10 +6,11 @@ use crate::nodes::Token;
 use super::{Type, VariadicArgumentType};
 
 /// Represents a pack of types.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
 pub struct TypePack {
     types: Vec<Type>,
     variadic_type: Option<VariadicArgumentType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<TypePackTokens>,
 }
 
@@ -155,7 +157,7 @@ impl<'a> IntoIterator for &'a TypePack {
 /// Contains the tokens that define the type pack syntax.
 ///
 /// These tokens represent the parentheses and commas in a type pack.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct TypePackTokens {
     /// The left parenthesis token.
     pub left_parenthese: Token,
@@ -1,3 +1,4 @@
+use serde::Serialize;
 use std::iter;
 
 use crate::nodes::Token;
@@ -5,10 +6,11 @@ use crate::nodes::Token;
 use super::Type;
 
 /// Represents a union type annotation.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct UnionType {
     types: Vec<Type>,
     leading_operator: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
     tokens: Option<UnionTypeTokens>,
 }
 
@@ -151,9 +153,10 @@ impl iter::FromIterator<Type> for UnionType {
 }
 
 /// Contains the tokens that define the union type syntax.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct UnionTypeTokens {
     /// Optional leading `|` token before the first type.
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub leading_token: Option<Token>,
     /// The `|` tokens separating the type alternatives.
     pub separators: Vec<Token>,
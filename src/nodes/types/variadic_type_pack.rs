@@ -1,4 +1,5 @@
 use crate::nodes::Token;
+use serde::Serialize;
 
 use super::Type;
 
@@ -6,10 +7,11 @@ use super::Type;
 ///
 /// Variadic type packs represent an arbitrary number of values of the same type,
 /// written with a leading `...` and a type.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct VariadicTypePack {
     // ... type
     inner_type: Box<Type>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     token: Option<Token>,
 }
 
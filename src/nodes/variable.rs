@@ -1,7 +1,8 @@
 use crate::nodes::{FieldExpression, Identifier, IndexExpression, Token};
+use serde::Serialize;
 
 /// Represents a variable reference.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub enum Variable {
     /// A simple named variable (e.g., `x`, `count`, `self`).
     Identifier(Identifier),
@@ -1,6 +1,12 @@
 use std::fmt;
 
-use full_moon::{ast::Ast, LuaVersion};
+use full_moon::{
+    ast::{self as full_moon_ast, Ast},
+    tokenizer::Position,
+    visitors::Visitor,
+    LuaVersion,
+};
+use serde::Serialize;
 
 use crate::{
     ast_converter::{AstConverter, ConvertError},
@@ -9,21 +15,22 @@ use crate::{
 };
 
 /// A parser for Luau code that converts it into an abstract syntax tree.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Parser {
     hold_token_data: bool,
+    bitwise_operators: bool,
 }
 
 impl Parser {
     /// Parses Lua code into a [`Block`].
     pub fn parse(&self, code: &str) -> Result<Block, ParserError> {
         let full_moon_parse_timer = Timer::now();
-        let parse_result = full_moon::parse_fallible(code, LuaVersion::luau()).into_result();
+        let parse_result = self.parse_full_moon(code);
         log::trace!(
             "full-moon parsing done in {}",
             full_moon_parse_timer.duration_label()
         );
-        parse_result.map_err(ParserError::parsing).and_then(|ast| {
+        parse_result.and_then(|ast| {
             log::trace!("start converting full-moon AST");
             let conversion_timer = Timer::now();
             let block = self.convert_ast(ast).map_err(ParserError::converting);
@@ -41,20 +48,94 @@ impl Parser {
         self
     }
 
+    /// Configures the parser to read the bitwise operators of Lua 5.3 (`&`, `|`, `~`,
+    /// `<<` and `>>`). With these operators, `>>` is always read as the right shift
+    /// operator, so nested generic types like `Array<Array<number>>` need a space
+    /// between their closing brackets.
+    pub fn with_bitwise_operators(mut self) -> Self {
+        self.bitwise_operators = true;
+        self
+    }
+
     pub(crate) fn is_preserving_tokens(&self) -> bool {
         self.hold_token_data
     }
 
+    fn parse_full_moon(&self, code: &str) -> Result<Ast, ParserError> {
+        if self.bitwise_operators {
+            return full_moon::parse_fallible(code, LuaVersion::luau().with_lua53())
+                .into_result()
+                .map_err(ParserError::parsing);
+        }
+
+        if !code.contains(['&', '|']) {
+            return full_moon::parse_fallible(code, LuaVersion::luau())
+                .into_result()
+                .map_err(ParserError::parsing);
+        }
+
+        // full-moon panics when `&` or `|` follows an expression while the Lua 5.3
+        // operators are not enabled (these symbols are only valid in Luau types), so
+        // the code is read with these operators first to find them
+        if has_bitwise_operators(code) {
+            return Err(ParserError::unexpected_bitwise_operator());
+        }
+
+        full_moon::parse_fallible(code, LuaVersion::luau())
+            .into_result()
+            .map_err(ParserError::parsing)
+    }
+
     #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip_all))]
     fn convert_ast(&self, ast: Ast) -> Result<Block, ConvertError> {
         AstConverter::new(self.hold_token_data).convert(&ast)
     }
 }
 
+/// Finds the `&` and `|` binary operators of the code, read with the Lua 5.3 bitwise
+/// operators enabled.
+#[derive(Default)]
+struct FindBitwiseOperators {
+    found: bool,
+}
+
+impl Visitor for FindBitwiseOperators {
+    fn visit_expression(&mut self, expression: &full_moon_ast::Expression) {
+        if let full_moon_ast::Expression::BinaryOperator {
+            binop: full_moon_ast::BinOp::Ampersand(_) | full_moon_ast::BinOp::Pipe(_),
+            ..
+        } = expression
+        {
+            self.found = true;
+        }
+    }
+}
+
+fn has_bitwise_operators(code: &str) -> bool {
+    // the shift operators are split so that the closing brackets of nested generic
+    // types (like `Array<Array<number>>`) are read like in Luau
+    let mut split_code = String::with_capacity(code.len());
+    let mut previous = None;
+    for character in code.chars() {
+        if matches!(character, '<' | '>') && previous == Some(character) {
+            split_code.push(' ');
+        }
+        split_code.push(character);
+        previous = Some(character);
+    }
+
+    let parsed = full_moon::parse_fallible(&split_code, LuaVersion::luau().with_lua53());
+
+    let mut find_bitwise_operators = FindBitwiseOperators::default();
+    find_bitwise_operators.visit_ast(parsed.ast());
+    find_bitwise_operators.found
+}
+
 #[derive(Clone, Debug)]
 enum ParserErrorKind {
     Parsing(Vec<full_moon::Error>),
     Converting(ConvertError),
+    UnexpectedBitwiseOperator,
 }
 
 /// The error type that can occur when parsing code.
@@ -75,6 +156,131 @@ impl ParserError {
             kind: ParserErrorKind::Converting(err).into(),
         }
     }
+
+    fn unexpected_bitwise_operator() -> Self {
+        Self {
+            kind: ParserErrorKind::UnexpectedBitwiseOperator.into(),
+        }
+    }
+
+    /// Returns every syntax error found in the code, with the location of the code
+    /// that caused it when it is known.
+    pub fn syntax_errors(&self) -> Vec<SyntaxError> {
+        match &*self.kind {
+            ParserErrorKind::Parsing(errors) => errors
+                .iter()
+                .map(|error| {
+                    let (start, end) = error.range();
+                    SyntaxError {
+                        message: error.error_message().into_owned(),
+                        span: Some(SourceSpan {
+                            start: start.into(),
+                            end: end.into(),
+                        }),
+                    }
+                })
+                .collect(),
+            ParserErrorKind::Converting(_) | ParserErrorKind::UnexpectedBitwiseOperator => {
+                vec![SyntaxError {
+                    message: self.to_string(),
+                    span: None,
+                }]
+            }
+        }
+    }
+}
+
+/// A syntax error found while parsing code.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SyntaxError {
+    message: String,
+    span: Option<SourceSpan>,
+}
+
+impl SyntaxError {
+    /// Returns the message describing the error.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the location of the code that caused the error, if it is known.
+    pub fn span(&self) -> Option<&SourceSpan> {
+        self.span.as_ref()
+    }
+}
+
+/// The location of a piece of code, from its start to its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct SourceSpan {
+    start: SourcePosition,
+    end: SourcePosition,
+}
+
+impl SourceSpan {
+    /// Returns the position where the code starts.
+    pub fn start(&self) -> SourcePosition {
+        self.start
+    }
+
+    /// Returns the position where the code ends.
+    pub fn end(&self) -> SourcePosition {
+        self.end
+    }
+
+    /// Creates a span from a range of bytes of the given code.
+    pub(crate) fn from_range(code: &str, start: usize, end: usize) -> Self {
+        Self {
+            start: SourcePosition::from_offset(code, start),
+            end: SourcePosition::from_offset(code, end),
+        }
+    }
+}
+
+/// A position in the code. Lines and columns start at 1, and the offset is the
+/// number of bytes from the start of the code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct SourcePosition {
+    line: usize,
+    column: usize,
+    offset: usize,
+}
+
+impl SourcePosition {
+    /// Returns the line of the position, starting at 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns the column of the position, starting at 1.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Returns the number of bytes from the start of the code.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    fn from_offset(code: &str, offset: usize) -> Self {
+        let before = code.get(..offset).unwrap_or(code);
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            offset,
+        }
+    }
+}
+
+impl From<Position> for SourcePosition {
+    fn from(position: Position) -> Self {
+        Self {
+            line: position.line(),
+            column: position.character(),
+            offset: position.bytes(),
+        }
+    }
 }
 
 impl fmt::Display for ParserError {
@@ -87,6 +293,10 @@ impl fmt::Display for ParserError {
                 Ok(())
             }
             ParserErrorKind::Converting(err) => write!(f, "{}", err),
+            ParserErrorKind::UnexpectedBitwiseOperator => write!(
+                f,
+                "unexpected bitwise operator (the Lua 5.3 bitwise operators are not enabled)"
+            ),
         }
     }
 }
@@ -3677,4 +3887,190 @@ mod test {
             }),
         );
     }
+
+    mod bitwise_operators {
+        use super::*;
+
+        macro_rules! test_parse_bitwise {
+            ($($name:ident($input:literal) => $value:expr),* $(,)?) => {
+                $(
+                    #[test]
+                    fn $name() {
+                        let parser = Parser::default().with_bitwise_operators();
+                        let block = parser.parse($input)
+                            .expect(&format!("failed to parse `{}`", $input));
+
+                        let expect_block = $value.into();
+                        pretty_assertions::assert_eq!(block, expect_block);
+                    }
+                )*
+            };
+        }
+
+        test_parse_bitwise!(
+            return_bitwise_and("return a & b") => ReturnStatement::one(
+                BinaryExpression::new(BinaryOperator::BitwiseAnd, Expression::identifier("a"), Expression::identifier("b")),
+            ),
+            return_bitwise_or("return a | b") => ReturnStatement::one(
+                BinaryExpression::new(BinaryOperator::BitwiseOr, Expression::identifier("a"), Expression::identifier("b")),
+            ),
+            return_bitwise_xor("return a ~ b") => ReturnStatement::one(
+                BinaryExpression::new(BinaryOperator::BitwiseXor, Expression::identifier("a"), Expression::identifier("b")),
+            ),
+            return_left_shift("return a << 2") => ReturnStatement::one(
+                BinaryExpression::new(BinaryOperator::LeftShift, Expression::identifier("a"), 2),
+            ),
+            return_right_shift("return a >> 2") => ReturnStatement::one(
+                BinaryExpression::new(BinaryOperator::RightShift, Expression::identifier("a"), 2),
+            ),
+            return_bitwise_not("return ~a") => ReturnStatement::one(
+                UnaryExpression::new(UnaryOperator::BitwiseNot, Expression::identifier("a")),
+            ),
+            return_bitwise_or_and_precedence("return a | b & c") => ReturnStatement::one(
+                BinaryExpression::new(
+                    BinaryOperator::BitwiseOr,
+                    Expression::identifier("a"),
+                    BinaryExpression::new(BinaryOperator::BitwiseAnd, Expression::identifier("b"), Expression::identifier("c")),
+                ),
+            ),
+            nested_generic_type_with_space("local value: Array<Array<number> > = {}") => LocalAssignStatement::from_variable(
+                TypedIdentifier::new("value").with_type(TypeName::new("Array").with_type_parameter(TypeName::new("Array").with_type_parameter(TypeName::new("number"))))
+            ).with_value(TableExpression::default()),
+        );
+
+        #[test]
+        fn nested_generic_type_without_space_errors() {
+            assert!(Parser::default()
+                .with_bitwise_operators()
+                .parse("local value: Array<Array<number>> = {}")
+                .is_err());
+        }
+
+        #[test]
+        fn default_parser_errors_on_bitwise_and() {
+            let error = Parser::default().parse("return a & b").unwrap_err();
+
+            insta::assert_snapshot!(error.to_string(), @"unexpected bitwise operator (the Lua 5.3 bitwise operators are not enabled)");
+        }
+
+        #[test]
+        fn default_parser_error_gives_syntax_error_without_span() {
+            let error = Parser::default().parse("return a & b").unwrap_err();
+
+            pretty_assertions::assert_eq!(
+                error.syntax_errors(),
+                vec![SyntaxError {
+                    message: error.to_string(),
+                    span: None,
+                }]
+            );
+        }
+
+        #[test]
+        fn default_parser_parses_union_and_intersection_types() {
+            assert!(Parser::default()
+                .parse("type T = | A | B type U = A & B local value: (A & B) | nil = f() :: A | B")
+                .is_ok());
+        }
+
+        #[test]
+        fn default_parser_parses_union_type_with_nested_generic_type() {
+            assert!(Parser::default()
+                .parse("local value: Array<Array<number>> | nil = {}")
+                .is_ok());
+        }
+
+        #[test]
+        fn default_parser_errors_on_bitwise_or_after_nested_generic_type() {
+            assert!(Parser::default()
+                .parse("local value: Array<Array<number>> = {} return a | b")
+                .is_err());
+        }
+
+        #[test]
+        fn default_parser_errors_on_bitwise_or() {
+            assert!(Parser::default().parse("return a | b").is_err());
+        }
+
+        #[test]
+        fn default_parser_errors_on_bitwise_xor() {
+            assert!(Parser::default().parse("return a ~ b").is_err());
+        }
+    }
+
+    mod syntax_errors {
+        use super::*;
+
+        #[test]
+        fn reports_every_syntax_error_with_spans() {
+            let error = Parser::default()
+                .parse("local a = (\nlocal b = )")
+                .unwrap_err();
+
+            insta::assert_json_snapshot!(error.syntax_errors(), @r###"
+            [
+              {
+                "message": "expected an expression after `(`",
+                "span": {
+                  "start": {
+                    "line": 1,
+                    "column": 11,
+                    "offset": 10
+                  },
+                  "end": {
+                    "line": 1,
+                    "column": 12,
+                    "offset": 11
+                  }
+                }
+              },
+              {
+                "message": "expected an expression",
+                "span": {
+                  "start": {
+                    "line": 1,
+                    "column": 9,
+                    "offset": 8
+                  },
+                  "end": {
+                    "line": 1,
+                    "column": 10,
+                    "offset": 9
+                  }
+                }
+              },
+              {
+                "message": "expected an expression",
+                "span": {
+                  "start": {
+                    "line": 2,
+                    "column": 9,
+                    "offset": 20
+                  },
+                  "end": {
+                    "line": 2,
+                    "column": 10,
+                    "offset": 21
+                  }
+                }
+              },
+              {
+                "message": "unexpected token, this needs to be a statement",
+                "span": {
+                  "start": {
+                    "line": 2,
+                    "column": 11,
+                    "offset": 22
+                  },
+                  "end": {
+                    "line": 2,
+                    "column": 12,
+                    "offset": 23
+                  }
+                }
+              }
+            ]
+            "###);
+        }
+    }
 }
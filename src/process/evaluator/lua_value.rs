@@ -110,9 +110,18 @@ impl LuaValue {
 
     /// Attempt to convert the Lua value into a string value. This will convert numbers when
     /// possible and return the same value otherwise.
+    ///
+    /// Only integers below 1e15 are converted: the other numbers are written differently
+    /// by each Lua runtime (like `1e+15`, `0.33333333333333` or `nan`).
     pub fn string_coercion(self) -> Self {
         match &self {
-            Self::Number(value) => Some(Self::from(value.to_string())),
+            Self::Number(value)
+                if value.fract() == 0.0
+                    && value.abs() < 1e15
+                    && !(*value == 0.0 && value.is_sign_negative()) =>
+            {
+                Some(Self::from((*value as i64).to_string()))
+            }
             _ => None,
         }
         .unwrap_or(self)
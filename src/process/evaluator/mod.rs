@@ -1,4 +1,5 @@
 mod lua_value;
+mod string_library;
 
 pub use lua_value::*;
 
@@ -8,6 +9,7 @@ use crate::nodes::*;
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Evaluator {
     pure_metamethods: bool,
+    string_library: bool,
 }
 
 impl Evaluator {
@@ -20,6 +22,14 @@ impl Evaluator {
         self
     }
 
+    /// When evaluating function calls, this value tells the evaluator that the `string`
+    /// identifier refers to the Lua string library. Calls to `string.rep` and `string.format`
+    /// with known arguments can then be evaluated.
+    pub fn assume_string_library(mut self) -> Self {
+        self.string_library = true;
+        self
+    }
+
     pub fn evaluate(&self, expression: &Expression) -> LuaValue {
         match expression {
             Expression::False(_) => LuaValue::False,
@@ -69,8 +79,8 @@ impl Evaluator {
                 LuaValue::String(result)
             }
             Expression::TypeCast(type_cast) => self.evaluate(type_cast.get_expression()),
-            Expression::Call(_)
-            | Expression::Field(_)
+            Expression::Call(call) => self.evaluate_call(call),
+            Expression::Field(_)
             | Expression::Identifier(_)
             | Expression::Index(_)
             | Expression::VariableArguments(_) => LuaValue::Unknown,
@@ -229,8 +239,23 @@ impl Evaluator {
     }
 
     #[inline]
-    fn call_has_side_effects(&self, _call: &FunctionCall) -> bool {
-        true
+    fn call_has_side_effects(&self, call: &FunctionCall) -> bool {
+        self.get_string_function(call).is_none()
+            || self.arguments_have_side_effects(call.get_arguments())
+            || self.evaluate_call(call) == LuaValue::Unknown
+    }
+
+    fn arguments_have_side_effects(&self, arguments: &Arguments) -> bool {
+        match arguments {
+            Arguments::Tuple(tuple) => tuple
+                .iter_values()
+                .any(|argument| self.has_side_effects(argument)),
+            Arguments::String(_) => false,
+            Arguments::Table(table) => table
+                .get_entries()
+                .iter()
+                .any(|entry| self.table_entry_has_side_effects(entry)),
+        }
     }
 
     #[inline]
@@ -333,6 +358,11 @@ impl Evaluator {
             BinaryOperator::GreaterOrEqualThan => {
                 self.evaluate_relational(expression, |a, b| a >= b)
             }
+            BinaryOperator::BitwiseAnd
+            | BinaryOperator::BitwiseOr
+            | BinaryOperator::BitwiseXor
+            | BinaryOperator::LeftShift
+            | BinaryOperator::RightShift => LuaValue::Unknown,
         }
     }
 
@@ -428,6 +458,46 @@ impl Evaluator {
                 }
             }
             UnaryOperator::Length => self.evaluate(expression.get_expression()).length(),
+            UnaryOperator::BitwiseNot => LuaValue::Unknown,
+        }
+    }
+
+    /// Returns the name of the function when the call is made on the string library
+    /// (like `string.rep(...)`).
+    fn get_string_function<'a>(&self, call: &'a FunctionCall) -> Option<&'a str> {
+        if !self.string_library || call.has_method() {
+            return None;
+        }
+
+        match call.get_prefix() {
+            Prefix::Field(field) => match field.get_prefix() {
+                Prefix::Identifier(identifier) if identifier.get_name() == "string" => {
+                    Some(field.get_field().get_name().as_str())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn evaluate_call(&self, call: &FunctionCall) -> LuaValue {
+        match self.get_string_function(call) {
+            Some(name) => string_library::evaluate_string_function(
+                name,
+                self.evaluate_arguments(call.get_arguments()),
+            ),
+            None => LuaValue::Unknown,
+        }
+    }
+
+    fn evaluate_arguments(&self, arguments: &Arguments) -> Vec<LuaValue> {
+        match arguments {
+            Arguments::Tuple(tuple) => tuple
+                .iter_values()
+                .map(|argument| self.evaluate(argument))
+                .collect(),
+            Arguments::String(string) => vec![LuaValue::from(string.get_value())],
+            Arguments::Table(_) => vec![LuaValue::Table],
         }
     }
 
@@ -779,6 +849,21 @@ mod test {
                 11.0,
                 -33.0
             ) => LuaValue::from("11-33"),
+            concat_string_with_decimal(
+                BinaryOperator::Concat,
+                StringExpression::from_value("foo"),
+                0.1
+            ) => LuaValue::Unknown,
+            concat_string_with_1e15(
+                BinaryOperator::Concat,
+                StringExpression::from_value("foo"),
+                1e15
+            ) => LuaValue::Unknown,
+            concat_string_with_nan(
+                BinaryOperator::Concat,
+                StringExpression::from_value("foo"),
+                f64::NAN
+            ) => LuaValue::Unknown,
             concat_empty_strings(
                 BinaryOperator::Concat,
                 StringExpression::empty(),
@@ -1182,4 +1267,107 @@ mod test {
             field_index => FieldExpression::new(Identifier::new("var"), "field"),
         );
     }
+
+    mod assume_string_library {
+        use super::*;
+
+        fn string_call(name: &str, arguments: Vec<Expression>) -> FunctionCall {
+            FunctionCall::from_prefix(FieldExpression::new(Identifier::new("string"), name))
+                .with_arguments(TupleArguments::new(arguments))
+        }
+
+        macro_rules! evaluate_string_calls {
+            ($($name:ident ($function:literal, $($argument:expr),*) => $value:expr),* $(,)?) => {
+                $(
+                    #[test]
+                    fn $name() {
+                        let call = string_call($function, vec![$(Expression::from($argument)),*]);
+                        let evaluator = Evaluator::default().assume_string_library();
+                        assert_eq!($value, evaluator.evaluate(&call.into()));
+                    }
+                )*
+            };
+        }
+
+        evaluate_string_calls!(
+            rep_string("rep", StringExpression::from_value("ab"), 3.0) => LuaValue::from("ababab"),
+            rep_zero_times("rep", StringExpression::from_value("ab"), 0.0) => LuaValue::from(""),
+            rep_negative_times("rep", StringExpression::from_value("ab"), -2.0) => LuaValue::from(""),
+            rep_number("rep", 1.0, 2.0) => LuaValue::from("11"),
+            rep_with_string_count("rep", StringExpression::from_value("a"), StringExpression::from_value("2"))
+                => LuaValue::from("aa"),
+            rep_with_decimal_count("rep", StringExpression::from_value("a"), 1.5) => LuaValue::Unknown,
+            rep_without_count("rep", StringExpression::from_value("a")) => LuaValue::Unknown,
+            rep_too_long("rep", StringExpression::from_value("abcd"), 1000.0) => LuaValue::Unknown,
+            rep_with_separator("rep", StringExpression::from_value("a"), 3.0, StringExpression::from_value(","))
+                => LuaValue::Unknown,
+            rep_unknown_string("rep", Expression::identifier("var"), 2.0) => LuaValue::Unknown,
+            format_without_conversions("format", StringExpression::from_value("hello")) => LuaValue::from("hello"),
+            format_percent("format", StringExpression::from_value("100%%")) => LuaValue::from("100%"),
+            format_string("format", StringExpression::from_value("hello %s!"), StringExpression::from_value("world"))
+                => LuaValue::from("hello world!"),
+            format_string_with_integer("format", StringExpression::from_value("%s"), 12.0) => LuaValue::from("12"),
+            format_string_with_negative_integer("format", StringExpression::from_value("%s"), -7.0)
+                => LuaValue::from("-7"),
+            format_string_with_decimal("format", StringExpression::from_value("%s"), 0.5) => LuaValue::Unknown,
+            format_string_with_1e15("format", StringExpression::from_value("%s"), 1e15) => LuaValue::Unknown,
+            format_string_with_1e100("format", StringExpression::from_value("%s"), 1e100) => LuaValue::Unknown,
+            format_string_with_nan("format", StringExpression::from_value("%s"), f64::NAN) => LuaValue::Unknown,
+            format_string_with_infinity("format", StringExpression::from_value("%s"), f64::INFINITY)
+                => LuaValue::Unknown,
+            format_string_with_negative_zero("format", StringExpression::from_value("%s"), -0.0)
+                => LuaValue::Unknown,
+            format_integer("format", StringExpression::from_value("%d-%i"), 10.0, -3.0) => LuaValue::from("10--3"),
+            format_integer_with_decimal("format", StringExpression::from_value("%d"), 1.5) => LuaValue::Unknown,
+            format_missing_argument("format", StringExpression::from_value("%s")) => LuaValue::Unknown,
+            format_boolean_string("format", StringExpression::from_value("%s"), true) => LuaValue::Unknown,
+            format_with_width("format", StringExpression::from_value("%5d"), 1.0) => LuaValue::Unknown,
+            format_float("format", StringExpression::from_value("%f"), 1.0) => LuaValue::Unknown,
+            format_unknown_argument("format", StringExpression::from_value("%s"), Expression::identifier("var"))
+                => LuaValue::Unknown,
+            unsupported_function("upper", StringExpression::from_value("a")) => LuaValue::Unknown,
+        );
+
+        #[test]
+        fn rep_has_no_side_effects() {
+            let call = string_call(
+                "rep",
+                vec![StringExpression::from_value("a").into(), 2.0.into()],
+            );
+            let evaluator = Evaluator::default().assume_string_library();
+            assert!(!evaluator.has_side_effects(&call.into()));
+        }
+
+        #[test]
+        fn rep_with_call_argument_has_side_effects() {
+            let call = string_call(
+                "rep",
+                vec![
+                    StringExpression::from_value("a").into(),
+                    FunctionCall::from_name("count").into(),
+                ],
+            );
+            let evaluator = Evaluator::default().assume_string_library();
+            assert!(evaluator.has_side_effects(&call.into()));
+        }
+
+        #[test]
+        fn unsupported_function_has_side_effects() {
+            let call = string_call("upper", vec![StringExpression::from_value("a").into()]);
+            let evaluator = Evaluator::default().assume_string_library();
+            assert!(evaluator.has_side_effects(&call.into()));
+        }
+
+        #[test]
+        fn rep_is_not_evaluated_without_string_library() {
+            let call: Expression = string_call(
+                "rep",
+                vec![StringExpression::from_value("a").into(), 2.0.into()],
+            )
+            .into();
+            let evaluator = Evaluator::default();
+            assert_eq!(evaluator.evaluate(&call), LuaValue::Unknown);
+            assert!(evaluator.has_side_effects(&call));
+        }
+    }
 }
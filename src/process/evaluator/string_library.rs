@@ -0,0 +1,84 @@
+use super::LuaValue;
+
+/// The maximum length of a string produced by `string.rep`. Longer strings are
+/// not computed, since inlining them would make the code larger.
+const MAX_REPEATED_STRING_LENGTH: usize = 1024;
+
+/// Computes the result of a function from the `string` library, given the values
+/// of its arguments. Returns an unknown value if the function is not supported or
+/// if the arguments would produce an error.
+pub(crate) fn evaluate_string_function(name: &str, arguments: Vec<LuaValue>) -> LuaValue {
+    match name {
+        "rep" => evaluate_rep(arguments),
+        "format" => evaluate_format(arguments),
+        _ => LuaValue::Unknown,
+    }
+}
+
+fn evaluate_rep(arguments: Vec<LuaValue>) -> LuaValue {
+    // the separator argument is ignored in Lua 5.1 but not in Lua 5.2+ and Luau, so
+    // calls with more than two arguments are not computed
+    if arguments.len() > 2 {
+        return LuaValue::Unknown;
+    }
+
+    let mut arguments = arguments.into_iter();
+
+    let string = match arguments.next().map(LuaValue::string_coercion) {
+        Some(LuaValue::String(string)) => string,
+        _ => return LuaValue::Unknown,
+    };
+    let count = match arguments.next().map(LuaValue::number_coercion) {
+        Some(LuaValue::Number(count)) if count.fract() == 0.0 => count,
+        _ => return LuaValue::Unknown,
+    };
+
+    if count <= 0.0 || string.is_empty() {
+        return LuaValue::from("");
+    }
+
+    if count * string.len() as f64 > MAX_REPEATED_STRING_LENGTH as f64 {
+        return LuaValue::Unknown;
+    }
+
+    LuaValue::String(string.repeat(count as usize))
+}
+
+fn evaluate_format(arguments: Vec<LuaValue>) -> LuaValue {
+    let mut arguments = arguments.into_iter();
+
+    let format = match arguments.next() {
+        Some(LuaValue::String(format)) => format,
+        _ => return LuaValue::Unknown,
+    };
+
+    let mut result = Vec::with_capacity(format.len());
+    let mut characters = format.into_iter();
+
+    while let Some(character) = characters.next() {
+        if character != b'%' {
+            result.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some(b'%') => result.push(b'%'),
+            Some(b's') => match arguments.next().map(LuaValue::string_coercion) {
+                Some(LuaValue::String(string)) => result.extend_from_slice(&string),
+                _ => return LuaValue::Unknown,
+            },
+            Some(b'd') | Some(b'i') => match arguments.next().map(LuaValue::number_coercion) {
+                Some(LuaValue::Number(number))
+                    if number.fract() == 0.0 && number.abs() < i64::MAX as f64 =>
+                {
+                    result.extend_from_slice((number as i64).to_string().as_bytes());
+                }
+                _ => return LuaValue::Unknown,
+            },
+            // other conversions (and any flags, width or precision) are not supported
+            _ => return LuaValue::Unknown,
+        }
+    }
+
+    LuaValue::String(result)
+}
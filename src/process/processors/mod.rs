@@ -2,6 +2,8 @@
 
 mod find_identifier;
 mod find_usage;
+mod variable_usage;
 
 pub use find_identifier::*;
 pub(crate) use find_usage::*;
+pub(crate) use variable_usage::*;
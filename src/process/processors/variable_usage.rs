@@ -0,0 +1,70 @@
+use crate::{
+    nodes::{Expression, Identifier, Prefix},
+    process::NodeProcessor,
+};
+
+/// A processor that counts the uses of a variable and how many of them only read
+/// one of its fields.
+///
+/// Shadowing variables are not tracked, so their uses are also counted.
+pub(crate) struct VariableUsage<'a> {
+    name: &'a str,
+    uses: usize,
+    field_reads: usize,
+}
+
+impl<'a> VariableUsage<'a> {
+    pub(crate) fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            uses: 0,
+            field_reads: 0,
+        }
+    }
+
+    /// Returns true if the variable is only used to read its fields (like
+    /// `value.field` or `value[key]`), which means it is not assigned, mutated or
+    /// given to a function.
+    pub(crate) fn is_only_reading_fields(&self) -> bool {
+        self.uses == self.field_reads
+    }
+
+    fn is_variable(&self, prefix: &Prefix) -> bool {
+        matches!(prefix, Prefix::Identifier(identifier) if identifier.get_name() == self.name)
+    }
+}
+
+impl NodeProcessor for VariableUsage<'_> {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Field(field) if self.is_variable(field.get_prefix()) => {
+                self.field_reads += 1;
+            }
+            Expression::Index(index) if self.is_variable(index.get_prefix()) => {
+                self.field_reads += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        // prefixes like `value.field.other` or `value.call()` read a field of the
+        // variable, while a prefix only made of the variable (like `value:call()`)
+        // passes the variable itself
+        match prefix {
+            Prefix::Field(field) if self.is_variable(field.get_prefix()) => {
+                self.field_reads += 1;
+            }
+            Prefix::Index(index) if self.is_variable(index.get_prefix()) => {
+                self.field_reads += 1;
+            }
+            _ => {}
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if identifier.get_name() == self.name {
+            self.uses += 1;
+        }
+    }
+}
@@ -25,6 +25,14 @@ pub trait Scope {
     fn insert_local(&mut self, identifier: &mut String, value: Option<&mut Expression>);
     /// Called when a new local function is initialized.
     fn insert_local_function(&mut self, function: &mut LocalFunctionStatement);
+    /// Called with the identifier node right before its name is inserted with `insert` or
+    /// `insert_local`. This can be used to read the token attached to the identifier.
+    fn declare(&mut self, _identifier: &Identifier) {}
+}
+
+fn insert_identifier<T: Scope>(scope: &mut T, identifier: &mut Identifier) {
+    scope.declare(identifier);
+    scope.insert(identifier.mutate_name());
 }
 
 /// A visitor that can be used only with a NodeProcessor that also implements the Scope trait.
@@ -66,6 +74,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         }
 
         statement.for_each_assignment(|variable, expression| {
+            scope.declare(variable);
             scope.insert_local(variable.mutate_name(), expression)
         });
     }
@@ -92,7 +101,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         function
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(function.mutate_block(), None);
 
@@ -126,7 +135,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         statement
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -158,7 +167,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         statement
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -176,7 +185,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         scope.push();
         statement
             .iter_mut_identifiers()
-            .for_each(|identifier| scope.insert(identifier.mutate_name()));
+            .for_each(|identifier| insert_identifier(scope, identifier));
 
         for r#type in statement
             .iter_mut_identifiers()
@@ -206,7 +215,7 @@ impl<T: NodeProcessor + Scope> NodeVisitor<T> for ScopeVisitor {
         }
 
         scope.push();
-        scope.insert(statement.mutate_identifier().mutate_name());
+        insert_identifier(scope, statement.mutate_identifier());
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -272,6 +281,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         }
 
         statement.for_each_assignment(|variable, expression| {
+            scope.declare(variable);
             scope.insert_local(variable.mutate_name(), expression)
         });
 
@@ -300,7 +310,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         function
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(function.mutate_block(), None);
 
@@ -336,7 +346,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         statement
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -370,7 +380,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         statement
             .mutate_parameters()
             .iter_mut()
-            .for_each(|parameter| scope.insert(parameter.mutate_name()));
+            .for_each(|parameter| insert_identifier(scope, parameter));
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -390,7 +400,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         scope.push();
         statement
             .iter_mut_identifiers()
-            .for_each(|identifier| scope.insert(identifier.mutate_name()));
+            .for_each(|identifier| insert_identifier(scope, identifier));
 
         for r#type in statement
             .iter_mut_identifiers()
@@ -422,7 +432,7 @@ impl<T: NodeProcessor + NodePostProcessor + Scope> NodePostVisitor<T> for ScopeP
         }
 
         scope.push();
-        scope.insert(statement.mutate_identifier().mutate_name());
+        insert_identifier(scope, statement.mutate_identifier());
 
         scope.process_scope(statement.mutate_block(), None);
 
@@ -1,6 +1,11 @@
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::sync::OnceLock;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(target_arch = "wasm32")]
+use web_time::{SystemTime, UNIX_EPOCH};
 
 use crate::nodes::{Block, Token, TriviaKind};
 use crate::rules::{
@@ -8,7 +13,7 @@ use crate::rules::{
     RuleConfigurationError, RuleProcessResult, RuleProperties,
 };
 
-use super::{FlawlessRule, ShiftTokenLine};
+use super::{inject_build_data::GitValue, FlawlessRule, ShiftTokenLine};
 
 pub const APPEND_TEXT_COMMENT_RULE_NAME: &str = "append_text_comment";
 
@@ -16,6 +21,7 @@ pub const APPEND_TEXT_COMMENT_RULE_NAME: &str = "append_text_comment";
 #[derive(Debug, Default)]
 pub struct AppendTextComment {
     text_value: OnceLock<Result<String, String>>,
+    git_hash: OnceLock<Result<String, String>>,
     text_content: TextContent,
     location: AppendLocation,
 }
@@ -24,6 +30,7 @@ impl AppendTextComment {
     pub fn new(value: impl Into<String>) -> Self {
         Self {
             text_value: Default::default(),
+            git_hash: Default::default(),
             text_content: TextContent::Value(value.into()),
             location: Default::default(),
         }
@@ -32,6 +39,7 @@ impl AppendTextComment {
     pub fn from_file_content(file_path: impl Into<PathBuf>) -> Self {
         Self {
             text_value: Default::default(),
+            git_hash: Default::default(),
             text_content: TextContent::FilePath(file_path.into()),
             location: Default::default(),
         }
@@ -44,43 +52,143 @@ impl AppendTextComment {
 
     fn text(&self, project_path: &Path) -> Result<String, String> {
         self.text_value
-            .get_or_init(|| {
-                match &self.text_content {
-                    TextContent::None => Err("".to_owned()),
-                    TextContent::Value(value) => Ok(value.clone()),
-                    TextContent::FilePath(file_path) => {
-                        fs::read_to_string(project_path.join(file_path)).map_err(|err| {
-                            format!("unable to read file `{}`: {}", file_path.display(), err)
-                        })
-                    }
+            .get_or_init(|| match &self.text_content {
+                TextContent::None => Err("".to_owned()),
+                TextContent::Value(value) => Ok(value.clone()),
+                TextContent::FilePath(file_path) if cfg!(target_arch = "wasm32") => Err(format!(
+                    "unable to read file `{}`: the file system cannot be accessed from WebAssembly",
+                    file_path.display()
+                )),
+                TextContent::FilePath(file_path) => {
+                    fs::read_to_string(project_path.join(file_path)).map_err(|err| {
+                        format!("unable to read file `{}`: {}", file_path.display(), err)
+                    })
                 }
-                .map(|content| {
-                    if content.is_empty() {
-                        "".to_owned()
-                    } else if content.contains('\n') {
-                        let mut equal_count = 0;
-
-                        let close_comment = loop {
-                            let close_comment = format!("]{}]", "=".repeat(equal_count));
-                            if !content.contains(&close_comment) {
-                                break close_comment;
-                            }
-                            equal_count += 1;
-                        };
-
-                        format!(
-                            "--[{}[\n{}\n{}",
-                            "=".repeat(equal_count),
-                            content,
-                            close_comment
-                        )
-                    } else {
-                        format!("--{}", content)
-                    }
-                })
             })
             .clone()
     }
+
+    fn git_hash(&self, project_path: &Path) -> Result<String, String> {
+        self.git_hash
+            .get_or_init(|| {
+                GitValue::Commit
+                    .read(project_path)
+                    .map_err(|err| err.to_string())
+            })
+            .clone()
+    }
+
+    /// Replaces the template variables (like `{date}`) of the text. Text between
+    /// braces that is not a known variable is kept as is.
+    fn expand_variables(&self, text: &str, context: &Context) -> Result<String, String> {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find('{') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start..];
+
+            let variable = rest
+                .find('}')
+                .and_then(|end| TemplateVariable::parse(&rest[1..end]).map(|var| (var, end)));
+
+            if let Some((variable, end)) = variable {
+                let value = match variable {
+                    TemplateVariable::Date => current_date(),
+                    TemplateVariable::GitHash => self.git_hash(context.project_location())?,
+                    TemplateVariable::FilePath => {
+                        context.current_path().to_string_lossy().replace('\\', "/")
+                    }
+                };
+                result.push_str(&value);
+                rest = &rest[end + 1..];
+            } else {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+
+        result.push_str(rest);
+        Ok(result)
+    }
+}
+
+fn format_comment(content: &str) -> String {
+    if content.contains('\n') {
+        let mut equal_count = 0;
+
+        let close_comment = loop {
+            let close_comment = format!("]{}]", "=".repeat(equal_count));
+            if !content.contains(&close_comment) {
+                break close_comment;
+            }
+            equal_count += 1;
+        };
+
+        format!(
+            "--[{}[\n{}\n{}",
+            "=".repeat(equal_count),
+            content,
+            close_comment
+        )
+    } else {
+        format!("--{}", content)
+    }
+}
+
+/// Returns the current date (`YYYY-MM-DD`, in UTC). When defined, the `SOURCE_DATE_EPOCH`
+/// environment variable is used instead of the current time to allow reproducible builds.
+fn current_date() -> String {
+    let seconds = env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default()
+        });
+
+    format_date(seconds / 86_400)
+}
+
+/// Converts a number of days since the Unix epoch into a `YYYY-MM-DD` date.
+fn format_date(days: u64) -> String {
+    // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateVariable {
+    Date,
+    GitHash,
+    FilePath,
+}
+
+impl TemplateVariable {
+    fn parse(name: &str) -> Option<Self> {
+        match name {
+            "date" => Some(Self::Date),
+            "git_hash" => Some(Self::GitHash),
+            "file_path" => Some(Self::FilePath),
+            _ => None,
+        }
+    }
 }
 
 impl Rule for AppendTextComment {
@@ -91,17 +199,19 @@ impl Rule for AppendTextComment {
             return Ok(());
         }
 
-        let shift_lines = text.lines().count();
-        ShiftTokenLine::new(shift_lines as isize).flawless_process(block, context);
+        let text = format_comment(&self.expand_variables(&text, context)?);
 
         match self.location {
             AppendLocation::Start => {
+                let shift_lines = text.lines().count();
+                ShiftTokenLine::new(shift_lines as isize).flawless_process(block, context);
+
                 self.location
                     .append_comment(block.mutate_first_token(), text);
             }
             AppendLocation::End => {
                 self.location
-                    .append_comment(block.mutate_last_token(), text);
+                    .append_comment(block.mutate_final_token(), text);
             }
         }
 
@@ -199,7 +309,7 @@ impl AppendLocation {
                 token.insert_leading_trivia(1, TriviaKind::Whitespace.with_content("\n"));
             }
             AppendLocation::End => {
-                token.push_trailing_trivia(TriviaKind::Comment.with_content(comment));
+                token.push_leading_trivia(TriviaKind::Comment.with_content(comment));
             }
         }
     }
@@ -249,6 +359,21 @@ mod test {
         insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
     }
 
+    #[test]
+    fn format_date_at_unix_epoch() {
+        assert_eq!(format_date(0), "1970-01-01");
+    }
+
+    #[test]
+    fn format_date_on_leap_day() {
+        assert_eq!(format_date(19_782), "2024-02-29");
+    }
+
+    #[test]
+    fn format_date_at_end_of_year() {
+        assert_eq!(format_date(20_453), "2025-12-31");
+    }
+
     #[test]
     fn configure_with_invalid_location_error() {
         let result = json5::from_str::<Box<dyn Rule>>(
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::sync::{OnceLock, RwLock};
+
+use serde::Serialize;
+
+use crate::nodes::{Expression, StringExpression};
+use crate::process::to_expression;
+use crate::DarkluaError;
+
+type DataConverter = Box<dyn Fn(&str) -> Result<Expression, DarkluaError> + Send + Sync>;
+
+/// Extensions of the files that are bundled as Lua modules, which can not be
+/// handled by a data converter.
+const LUA_EXTENSIONS: [&str; 2] = ["lua", "luau"];
+
+fn data_converters() -> &'static RwLock<HashMap<String, DataConverter>> {
+    static DATA_CONVERTERS: OnceLock<RwLock<HashMap<String, DataConverter>>> = OnceLock::new();
+    DATA_CONVERTERS.get_or_init(|| RwLock::new(default_data_converters()))
+}
+
+fn default_data_converters() -> HashMap<String, DataConverter> {
+    let mut converters: HashMap<String, DataConverter> = HashMap::new();
+
+    for extension in ["json", "json5"] {
+        converters.insert(
+            extension.to_owned(),
+            Box::new(|content| transcode(json5::from_str::<serde_json::Value>(content))),
+        );
+    }
+    for extension in ["yml", "yaml"] {
+        converters.insert(
+            extension.to_owned(),
+            Box::new(|content| transcode(serde_yaml::from_str::<serde_yaml::Value>(content))),
+        );
+    }
+    converters.insert(
+        "toml".to_owned(),
+        Box::new(|content| transcode(toml::from_str::<toml::Value>(content))),
+    );
+    converters.insert(
+        "txt".to_owned(),
+        Box::new(|content| Ok(StringExpression::from_value(content).into())),
+    );
+
+    converters
+}
+
+fn transcode<T, E>(value: Result<T, E>) -> Result<Expression, DarkluaError>
+where
+    T: Serialize,
+    E: Into<DarkluaError>,
+{
+    let value = value.map_err(E::into)?;
+    to_expression(&value).map_err(DarkluaError::from)
+}
+
+/// Registers a converter used by the bundler when a module requires a file with
+/// the given extension (without the leading dot, like `csv`). The converter receives
+/// the content of the file and returns the expression that replaces the module.
+///
+/// darklua already converts `json`, `json5`, `yml`, `yaml`, `toml` and `txt` files.
+/// Registering a converter for one of these extensions replaces it. Returns false
+/// when the extension is `lua` or `luau`, in which case the converter is ignored.
+///
+/// ```rust
+/// # use darklua_core::nodes::{StringExpression, TableExpression};
+/// let registered = darklua_core::register_data_converter("csv", |content| {
+///     let rows = content
+///         .lines()
+///         .fold(TableExpression::default(), |table, line| {
+///             table.append_array_value(StringExpression::from_value(line))
+///         });
+///     Ok(rows.into())
+/// });
+///
+/// assert!(registered);
+/// ```
+pub fn register_data_converter<F>(extension: impl Into<String>, converter: F) -> bool
+where
+    F: Fn(&str) -> Result<Expression, DarkluaError> + Send + Sync + 'static,
+{
+    let extension = extension.into();
+
+    if LUA_EXTENSIONS.contains(&extension.as_str()) {
+        log::warn!(
+            "unable to register a data converter for `{}` files because they are bundled as Lua modules",
+            extension
+        );
+        return false;
+    }
+
+    data_converters()
+        .write()
+        .expect("data converters lock should not be poisoned")
+        .insert(extension, Box::new(converter));
+    true
+}
+
+/// Converts the content of a file into an expression, using the converter associated
+/// with its extension. Returns `None` when no converter exists for the extension.
+pub(crate) fn convert_data(
+    extension: &str,
+    content: &str,
+) -> Option<Result<Expression, DarkluaError>> {
+    data_converters()
+        .read()
+        .expect("data converters lock should not be poisoned")
+        .get(extension)
+        .map(|converter| converter(content))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn register_converter_for_lua_extension_is_rejected() {
+        assert!(!register_data_converter("lua", |_| Ok(Expression::nil())));
+        assert!(!register_data_converter("luau", |_| Ok(Expression::nil())));
+    }
+
+    #[test]
+    fn convert_txt_content() {
+        assert_eq!(
+            convert_data("txt", "hello").unwrap().unwrap(),
+            StringExpression::from_value("hello").into()
+        );
+    }
+
+    #[test]
+    fn convert_unknown_extension() {
+        assert!(convert_data("unknown_data_extension", "").is_none());
+    }
+
+    #[test]
+    fn convert_with_registered_converter() {
+        assert!(register_data_converter("test_data_extension", |content| {
+            Ok(Expression::from(content.len() as f64))
+        }));
+
+        assert_eq!(
+            convert_data("test_data_extension", "abc").unwrap().unwrap(),
+            Expression::from(3.0)
+        );
+    }
+}
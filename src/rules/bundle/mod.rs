@@ -1,3 +1,4 @@
+mod data_converters;
 pub(crate) mod path_require_mode;
 mod rename_type_declaration;
 mod require_mode;
@@ -10,17 +11,43 @@ use crate::rules::{
 };
 use crate::Parser;
 
+pub use data_converters::register_data_converter;
 pub(crate) use rename_type_declaration::RenameTypeDeclarationProcessor;
 pub use require_mode::BundleRequireMode;
 use wax::Pattern;
 
 pub const BUNDLER_RULE_NAME: &str = "bundler";
 
+/// Builds a matcher from the given globs, ignoring (with a warning) the invalid ones.
+fn build_globs<'a>(globs: impl Iterator<Item = &'a str>, kind: &str) -> Option<wax::Any<'static>> {
+    let globs: Vec<_> = globs
+        .filter_map(|glob| match wax::Glob::new(glob) {
+            Ok(glob) => Some(glob.into_owned()),
+            Err(err) => {
+                log::warn!("unable to create {} matcher from `{}`: {}", kind, glob, err);
+                None
+            }
+        })
+        .collect();
+
+    if globs.is_empty() {
+        None
+    } else {
+        let any_pattern = wax::any(globs)
+            .expect("bundle globs errors should be filtered and only emit a warning");
+        Some(any_pattern)
+    }
+}
+
 #[derive(Debug)]
 pub(crate) struct BundleOptions {
     parser: Parser,
     modules_identifier: String,
     excludes: Option<wax::Any<'static>>,
+    tree_shaking: bool,
+    require_polyfill: bool,
+    chunk_size: Option<usize>,
+    lazy_modules: Option<wax::Any<'static>>,
 }
 
 impl BundleOptions {
@@ -29,29 +56,14 @@ impl BundleOptions {
         modules_identifier: impl Into<String>,
         excludes: impl Iterator<Item = &'a str>,
     ) -> Self {
-        let excludes: Vec<_> = excludes
-            .filter_map(|exclusion| match wax::Glob::new(exclusion) {
-                Ok(glob) => Some(glob.into_owned()),
-                Err(err) => {
-                    log::warn!(
-                        "unable to create exclude matcher from `{}`: {}",
-                        exclusion,
-                        err
-                    );
-                    None
-                }
-            })
-            .collect();
         Self {
             parser,
             modules_identifier: modules_identifier.into(),
-            excludes: if excludes.is_empty() {
-                None
-            } else {
-                let any_pattern = wax::any(excludes)
-                    .expect("exclude globs errors should be filtered and only emit a warning");
-                Some(any_pattern)
-            },
+            excludes: build_globs(excludes, "exclude"),
+            tree_shaking: false,
+            require_polyfill: false,
+            chunk_size: None,
+            lazy_modules: None,
         }
     }
 
@@ -63,12 +75,31 @@ impl BundleOptions {
         &self.modules_identifier
     }
 
+    fn is_tree_shaking(&self) -> bool {
+        self.tree_shaking
+    }
+
+    fn is_require_polyfill(&self) -> bool {
+        self.require_polyfill
+    }
+
     fn is_excluded(&self, require: &Path) -> bool {
         self.excludes
             .as_ref()
             .map(|any| any.is_match(require))
             .unwrap_or(false)
     }
+
+    fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
+
+    fn is_lazy_module(&self, require: &Path) -> bool {
+        self.lazy_modules
+            .as_ref()
+            .map(|any| any.is_match(require))
+            .unwrap_or(false)
+    }
 }
 
 /// A rule that inlines required modules
@@ -94,6 +125,33 @@ impl Bundler {
         self.options.modules_identifier = modules_identifier.into();
         self
     }
+
+    pub(crate) fn with_tree_shaking(mut self, tree_shaking: bool) -> Self {
+        self.options.tree_shaking = tree_shaking;
+        self
+    }
+
+    pub(crate) fn with_require_polyfill(mut self, require_polyfill: bool) -> Self {
+        self.options.require_polyfill = require_polyfill;
+        self
+    }
+
+    /// Parses the bundled modules with their tokens, for example to generate a source
+    /// map of the bundle.
+    pub(crate) fn with_preserved_tokens(mut self) -> Self {
+        self.options.parser = self.options.parser.preserve_tokens();
+        self
+    }
+
+    pub(crate) fn with_chunks<'a>(
+        mut self,
+        chunk_size: Option<usize>,
+        lazy_modules: impl Iterator<Item = &'a str>,
+    ) -> Self {
+        self.options.chunk_size = chunk_size;
+        self.options.lazy_modules = build_globs(lazy_modules, "lazy module");
+        self
+    }
 }
 
 impl Rule for Bundler {
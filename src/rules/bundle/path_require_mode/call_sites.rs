@@ -0,0 +1,192 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+
+use crate::nodes::FunctionCall;
+use crate::process::{DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::require::match_path_require_call;
+use crate::{Parser, SourceSpan};
+
+/// A require call found while bundling, identified by the file it comes from, the
+/// required path and its position among the calls of that file requiring the same
+/// path. The calls of the main file are counted separately from the calls of the
+/// main file loaded as a module (through a cyclic require).
+///
+/// The bundled code does not always have tokens (for example with the dense
+/// generator), and the tokens of the bundled files are replaced before their require
+/// calls are processed. The span of the call is found from this information only
+/// when it is needed to report an error.
+#[derive(Debug, Clone)]
+pub(crate) struct RequireCall {
+    file: PathBuf,
+    is_main: bool,
+    literal_path: PathBuf,
+    occurrence: usize,
+}
+
+impl RequireCall {
+    pub(crate) fn file(&self) -> &Path {
+        &self.file
+    }
+
+    /// Returns true if the call comes from the main file (not loaded as a module).
+    pub(crate) fn is_main(&self) -> bool {
+        self.is_main
+    }
+
+    /// Returns the location of the call, from the spans of the calls of its file.
+    pub(crate) fn call_site(&self, spans: &RequireCallSpans) -> RequireCallSite {
+        RequireCallSite::new(&self.file, spans.get(self))
+    }
+}
+
+/// The location of a require call that loads a module.
+#[derive(Debug, Clone)]
+pub(crate) struct RequireCallSite {
+    file: PathBuf,
+    span: Option<SourceSpan>,
+}
+
+impl RequireCallSite {
+    fn new(file: impl Into<PathBuf>, span: Option<SourceSpan>) -> Self {
+        Self {
+            file: file.into(),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for RequireCallSite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`", self.file.display())?;
+
+        if let Some(span) = self.span {
+            write!(
+                f,
+                " (line {}, column {})",
+                span.start().line(),
+                span.start().column()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Counts the require calls of each file while they are bundled, to identify them
+/// as [`RequireCall`].
+#[derive(Debug, Default)]
+pub(crate) struct RequireCallCounter {
+    counts: HashMap<(PathBuf, bool, PathBuf), usize>,
+}
+
+impl RequireCallCounter {
+    /// Counts the given call if it is a require call with a path, and identifies it.
+    /// Every require call must be counted (even if it does not refer to the `require`
+    /// global) to match the calls found by [`RequireCallSpans`].
+    pub(crate) fn count(
+        &mut self,
+        file: &Path,
+        is_main: bool,
+        call: &FunctionCall,
+    ) -> Option<RequireCall> {
+        let literal_path = match_path_require_call(call)?;
+
+        let count = self
+            .counts
+            .entry((file.to_path_buf(), is_main, literal_path.clone()))
+            .or_default();
+        let occurrence = *count;
+        *count += 1;
+
+        Some(RequireCall {
+            file: file.to_path_buf(),
+            is_main,
+            literal_path,
+            occurrence,
+        })
+    }
+}
+
+/// The spans of the require calls of a file, for each required path in the order
+/// the calls are visited.
+#[derive(Debug, Default)]
+pub(crate) struct RequireCallSpans {
+    spans: HashMap<PathBuf, Vec<SourceSpan>>,
+}
+
+impl RequireCallSpans {
+    /// Parses the given code to find its require calls. The code must be visited the
+    /// same way as it was bundled: `use_scope` must be true for the main file, which
+    /// is visited with a [`ScopeVisitor`].
+    pub(crate) fn collect(parser: &Parser, code: &str, use_scope: bool) -> Self {
+        let Ok(mut block) = parser.clone().preserve_tokens().parse(code) else {
+            return Self::default();
+        };
+
+        let mut collector = RequireCallSpanCollector {
+            code,
+            spans: Default::default(),
+            identifier_tracker: Default::default(),
+        };
+
+        if use_scope {
+            ScopeVisitor::visit_block(&mut block, &mut collector);
+        } else {
+            DefaultVisitor::visit_block(&mut block, &mut collector);
+        }
+
+        Self {
+            spans: collector.spans,
+        }
+    }
+
+    fn get(&self, call: &RequireCall) -> Option<SourceSpan> {
+        self.spans
+            .get(&call.literal_path)
+            .and_then(|spans| spans.get(call.occurrence))
+            .copied()
+    }
+}
+
+struct RequireCallSpanCollector<'a> {
+    code: &'a str,
+    spans: HashMap<PathBuf, Vec<SourceSpan>>,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl Deref for RequireCallSpanCollector<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl DerefMut for RequireCallSpanCollector<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for RequireCallSpanCollector<'_> {
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        let Some(path) = match_path_require_call(call) else {
+            return;
+        };
+
+        let start = call
+            .mutate_first_token()
+            .get_range()
+            .map(|(start, _)| start);
+        let end = call.mutate_last_token().get_range().map(|(_, end)| end);
+
+        let Some((start, end)) = start.zip(end) else {
+            return;
+        };
+
+        let span = SourceSpan::from_range(self.code, start, end);
+        self.spans.entry(path).or_default().push(span);
+    }
+}
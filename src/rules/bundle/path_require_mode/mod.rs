@@ -1,22 +1,22 @@
+mod call_sites;
 mod module_definitions;
+mod token_source;
+mod tree_shaking;
 
+use call_sites::{RequireCall, RequireCallCounter, RequireCallSpans};
 use module_definitions::BuildModuleDefinitions;
+use token_source::SetTokenSourceProcessor;
 
 use std::collections::{HashMap, HashSet};
 use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
 use std::{iter, mem};
 
-use serde::Serialize;
-
 use crate::frontend::DarkluaResult;
 use crate::nodes::{
     Block, DoStatement, Expression, FunctionCall, LocalAssignStatement, Prefix, Statement,
-    StringExpression,
-};
-use crate::process::{
-    to_expression, DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor,
 };
+use crate::process::{DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
 use crate::rules::require::{is_require_call, match_path_require_call, PathLocator};
 use crate::rules::{
     Context, ContextBuilder, FlawlessRule, ReplaceReferencedTokens, RuleProcessResult,
@@ -24,6 +24,7 @@ use crate::rules::{
 use crate::utils::Timer;
 use crate::{DarkluaError, Resources};
 
+use super::data_converters::convert_data;
 use super::BundleOptions;
 
 pub(crate) enum RequiredResource {
@@ -31,6 +32,13 @@ pub(crate) enum RequiredResource {
     Expression(Expression),
 }
 
+/// A module that is being loaded, with the require call that loads it.
+#[derive(Debug)]
+struct LoadingModule {
+    path: PathBuf,
+    call: RequireCall,
+}
+
 #[derive(Debug)]
 struct RequirePathProcessor<'a, 'b, 'resources, PathLocatorImpl> {
     options: &'a BundleOptions,
@@ -39,7 +47,9 @@ struct RequirePathProcessor<'a, 'b, 'resources, PathLocatorImpl> {
     module_definitions: BuildModuleDefinitions,
     source: PathBuf,
     module_cache: HashMap<PathBuf, Expression>,
-    require_stack: Vec<PathBuf>,
+    require_stack: Vec<LoadingModule>,
+    require_calls: RequireCallCounter,
+    main_code: &'resources str,
     skip_module_paths: HashSet<PathBuf>,
     resources: &'resources Resources,
     errors: Vec<String>,
@@ -57,15 +67,21 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
         'context: 'b,
         'context: 'resources,
         'context: 'code,
+        'code: 'resources,
     {
         Self {
             options,
             identifier_tracker: IdentifierTracker::new(),
             path_locator,
-            module_definitions: BuildModuleDefinitions::new(options.modules_identifier()),
+            module_definitions: BuildModuleDefinitions::new(options.modules_identifier())
+                .with_tree_shaking(options.is_tree_shaking())
+                .with_require_polyfill(options.is_require_polyfill())
+                .with_chunk_size(options.chunk_size()),
             source: context.current_path().to_path_buf(),
             module_cache: Default::default(),
             require_stack: Default::default(),
+            require_calls: Default::default(),
+            main_code: context.original_code,
             skip_module_paths: Default::default(),
             resources: context.resources(),
             errors: Vec::new(),
@@ -90,6 +106,11 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
     }
 
     fn try_inline_call(&mut self, call: &FunctionCall) -> Option<Expression> {
+        // every require call is counted (even if it is not inlined) to find its
+        // location when reporting an error
+        let require_call =
+            self.require_calls
+                .count(&self.source, self.require_stack.is_empty(), call)?;
         let literal_require_path = self.require_call(call)?;
 
         if self.options.is_excluded(&literal_require_path) {
@@ -126,7 +147,9 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
             return None;
         }
 
-        match self.inline_require(&require_path, call) {
+        let is_lazy = self.options.is_lazy_module(&literal_require_path);
+
+        match self.inline_require(&require_path, call, require_call, is_lazy) {
             Ok(expression) => Some(expression),
             Err(error) => {
                 self.errors.push(error.to_string());
@@ -140,6 +163,8 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
         &mut self,
         require_path: &Path,
         call: &FunctionCall,
+        require_call: RequireCall,
+        is_lazy: bool,
     ) -> DarkluaResult<Expression> {
         if let Some(expression) = self.module_cache.get(require_path) {
             Ok(expression.clone())
@@ -147,32 +172,37 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
             if let Some(i) = self
                 .require_stack
                 .iter()
-                .enumerate()
-                .find(|(_, path)| **path == require_path)
-                .map(|(i, _)| i)
+                .position(|module| module.path == require_path)
             {
-                let require_stack_paths: Vec<_> = self
-                    .require_stack
-                    .iter()
-                    .skip(i)
-                    .map(|path| path.display().to_string())
-                    .chain(iter::once(require_path.display().to_string()))
-                    .collect();
-
-                return Err(DarkluaError::custom(format!(
-                    "cyclic require detected with `{}`",
-                    require_stack_paths.join("` > `")
-                )));
+                if self.options.is_require_polyfill() {
+                    log::debug!(
+                        "cyclic require to `{}` from `{}` is resolved when the bundle runs",
+                        require_path.display(),
+                        self.source.display()
+                    );
+                    return Ok(self
+                        .module_definitions
+                        .build_cyclic_require(require_path, call));
+                }
+
+                return Err(self.cyclic_require_error(i, &require_call));
             }
 
-            self.require_stack.push(require_path.to_path_buf());
+            self.require_stack.push(LoadingModule {
+                path: require_path.to_path_buf(),
+                call: require_call,
+            });
             let required_resource = self.require_resource(require_path);
             self.require_stack.pop();
 
+            let (required_resource, size) = required_resource?;
+
             let module_value = self.module_definitions.build_module_from_resource(
-                required_resource?,
+                required_resource,
                 require_path,
                 call,
+                size,
+                is_lazy,
             )?;
 
             self.module_cache
@@ -182,7 +212,77 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
         }
     }
 
-    fn require_resource(&mut self, path: impl AsRef<Path>) -> DarkluaResult<RequiredResource> {
+    /// Finds the spans of the require calls of a file, by parsing it again.
+    fn find_call_spans(&self, call: &RequireCall) -> RequireCallSpans {
+        let parser = self.options.parser();
+
+        if call.is_main() {
+            RequireCallSpans::collect(parser, self.main_code, true)
+        } else {
+            self.resources
+                .get(call.file())
+                .map(|code| RequireCallSpans::collect(parser, &code, false))
+                .unwrap_or_default()
+        }
+    }
+
+    /// Builds the error for a require call to the module at the given index of the
+    /// require stack, which describes each require call of the cycle.
+    fn cyclic_require_error(&self, index: usize, call: &RequireCall) -> DarkluaError {
+        let cycle = &self.require_stack[index..];
+
+        let mut call_spans: HashMap<(PathBuf, bool), RequireCallSpans> = HashMap::new();
+        let mut call_site = |call: &RequireCall| {
+            let spans = call_spans
+                .entry((call.file().to_path_buf(), call.is_main()))
+                .or_insert_with(|| self.find_call_spans(call));
+            call.call_site(spans)
+        };
+
+        let chain: Vec<_> = cycle
+            .iter()
+            .chain(iter::once(&cycle[0]))
+            .map(|module| format!("`{}`", module.path.display()))
+            .collect();
+
+        let closing_call_site = call_site(call);
+
+        let mut message = format!("cyclic require detected with {}", chain.join(" > "));
+
+        let required_paths = cycle.iter().skip(1).map(|module| &module.path);
+        let call_sites: Vec<_> = cycle
+            .iter()
+            .skip(1)
+            .map(|module| call_site(&module.call))
+            .collect();
+
+        for (required_path, call_site) in required_paths
+            .chain(iter::once(&cycle[0].path))
+            .zip(call_sites.iter().chain(iter::once(&closing_call_site)))
+        {
+            message.push_str(&format!(
+                "\n  {} requires `{}`",
+                call_site,
+                required_path.display()
+            ));
+        }
+
+        message.push_str(&format!(
+            "\n  help: break the cycle by removing the require of `{}` from {}, \
+            or enable `require_polyfill` to load the modules when the bundle runs",
+            cycle[0].path.display(),
+            closing_call_site,
+        ));
+
+        DarkluaError::custom(message)
+    }
+
+    /// Loads the resource at the given path, and returns it with the size of its
+    /// content.
+    fn require_resource(
+        &mut self,
+        path: impl AsRef<Path>,
+    ) -> DarkluaResult<(RequiredResource, usize)> {
         let path = path.as_ref();
         log::trace!("look for resource `{}`", path.display());
         let content = self.resources.get(path).map_err(DarkluaError::from)?;
@@ -220,6 +320,13 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
                             path.display(),
                             apply_replace_tokens_timer.duration_label()
                         );
+
+                        // keep track of the file of each token so that the source map of the
+                        // bundle can refer to it
+                        DefaultVisitor::visit_block(
+                            &mut block,
+                            &mut SetTokenSourceProcessor::new(path),
+                        );
                     }
 
                     let current_source = mem::replace(&mut self.source, path.to_path_buf());
@@ -235,22 +342,27 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator>
 
                     self.source = current_source;
 
-                    Ok(RequiredResource::Block(block))
+                    Ok((RequiredResource::Block(block), content.len()))
                 }
-                "json" | "json5" => {
-                    transcode("json", path, json5::from_str::<serde_json::Value>, &content)
+                extension => {
+                    log::trace!(
+                        "transcode {} data to Lua from `{}`",
+                        extension,
+                        path.display()
+                    );
+                    let transcode_duration = Timer::now();
+
+                    let expression = convert_data(extension, &content)
+                        .ok_or_else(|| DarkluaError::invalid_resource_extension(path))??;
+
+                    log::debug!(
+                        "transcoded {} data to Lua from `{}` in {}",
+                        extension,
+                        path.display(),
+                        transcode_duration.duration_label()
+                    );
+                    Ok((RequiredResource::Expression(expression), content.len()))
                 }
-                "yml" | "yaml" => transcode(
-                    "yaml",
-                    path,
-                    serde_yaml::from_str::<serde_yaml::Value>,
-                    &content,
-                ),
-                "toml" => transcode("toml", path, toml::from_str::<toml::Value>, &content),
-                "txt" => Ok(RequiredResource::Expression(
-                    StringExpression::from_value(content).into(),
-                )),
-                _ => Err(DarkluaError::invalid_resource_extension(path)),
             },
             None => unreachable!("extension should be defined"),
         }
@@ -275,31 +387,6 @@ impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator> DerefMut
     }
 }
 
-fn transcode<'a, T, E>(
-    label: &'static str,
-    path: &Path,
-    deserialize_value: impl Fn(&'a str) -> Result<T, E>,
-    content: &'a str,
-) -> Result<RequiredResource, DarkluaError>
-where
-    T: Serialize,
-    E: Into<DarkluaError>,
-{
-    log::trace!("transcode {} data to Lua from `{}`", label, path.display());
-    let transcode_duration = Timer::now();
-    let value = deserialize_value(content).map_err(E::into)?;
-    let expression = to_expression(&value)
-        .map(RequiredResource::Expression)
-        .map_err(DarkluaError::from);
-    log::debug!(
-        "transcoded {} data to Lua from `{}` in {}",
-        label,
-        path.display(),
-        transcode_duration.duration_label()
-    );
-    expression
-}
-
 impl<'a, 'b, 'resources, PathLocatorImpl: PathLocator> NodeProcessor
     for RequirePathProcessor<'a, 'b, 'resources, PathLocatorImpl>
 {
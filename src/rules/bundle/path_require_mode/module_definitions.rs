@@ -1,14 +1,16 @@
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use indexmap::IndexMap;
 
 use crate::frontend::DarkluaResult;
 use crate::nodes::{
-    Arguments, AssignStatement, Block, DoStatement, Expression, ExpressionType, FieldExpression,
-    FunctionCall, FunctionName, FunctionStatement, Identifier, IfStatement, LastStatement,
-    LocalAssignStatement, LocalFunctionStatement, Prefix, ReturnStatement, TableEntry,
-    TableExpression, Token, TupleArguments, TupleArgumentsTokens, TypeCastExpression, TypeName,
-    UnaryExpression, UnaryOperator,
+    Arguments, AssignStatement, BinaryExpression, BinaryOperator, Block, DoStatement, Expression,
+    ExpressionType, FieldExpression, FunctionCall, FunctionExpression, FunctionName,
+    FunctionStatement, Identifier, IfStatement, IndexExpression, LastStatement,
+    LocalAssignStatement, LocalFunctionStatement, Prefix, ReturnStatement, Statement,
+    StringExpression, TableEntry, TableExpression, Token, TupleArguments, TupleArgumentsTokens,
+    TypeCastExpression, TypeName, UnaryExpression, UnaryOperator,
 };
 use crate::process::utils::{generate_identifier, identifier_permutator, CharPermutator};
 use crate::rules::bundle::RenameTypeDeclarationProcessor;
@@ -16,12 +18,16 @@ use crate::rules::{Context, FlawlessRule, ShiftTokenLine};
 use crate::utils::lines;
 use crate::DarkluaError;
 
-use super::RequiredResource;
+use super::{tree_shaking, RequiredResource};
 
 #[derive(Debug)]
 pub(crate) struct BuildModuleDefinitions {
     modules_identifier: String,
+    tree_shaking: bool,
+    require_polyfill: bool,
+    chunk_size: Option<usize>,
     module_definitions: IndexMap<String, ModuleDefinition>,
+    reserved_module_names: HashMap<PathBuf, String>,
     module_name_permutator: CharPermutator,
     rename_type_declaration: RenameTypeDeclarationProcessor,
 }
@@ -30,32 +36,83 @@ pub(crate) struct BuildModuleDefinitions {
 struct ModuleDefinition {
     block: Block,
     path: PathBuf,
+    size: usize,
+    is_lazy: bool,
 }
 
 impl ModuleDefinition {
-    fn new(block: Block, path: PathBuf) -> Self {
-        Self { block, path }
+    fn new(block: Block, path: PathBuf, size: usize, is_lazy: bool) -> Self {
+        Self {
+            block,
+            path,
+            size,
+            is_lazy,
+        }
     }
 }
 
 const BUNDLE_MODULES_VARIABLE_CACHE_FIELD: &str = "cache";
+const LOCAL_MODULE_IMPL_NAME: &str = "__modImpl";
+const MODULE_CONTENT_ENTRY: &str = "c";
+const MODULE_CONTENT_VARIABLE: &str = "v";
+const LOAD_MODULE_FUNCTION_NAME: &str = "__loadMod";
 
 impl BuildModuleDefinitions {
     pub(crate) fn new(modules_identifier: impl Into<String>) -> Self {
         let modules_identifier = modules_identifier.into();
         Self {
             modules_identifier: modules_identifier.clone(),
+            tree_shaking: false,
+            require_polyfill: false,
+            chunk_size: None,
             module_definitions: Default::default(),
+            reserved_module_names: Default::default(),
             module_name_permutator: identifier_permutator(),
             rename_type_declaration: RenameTypeDeclarationProcessor::new(modules_identifier),
         }
     }
 
+    pub(crate) fn with_tree_shaking(mut self, tree_shaking: bool) -> Self {
+        self.tree_shaking = tree_shaking;
+        self
+    }
+
+    pub(crate) fn with_require_polyfill(mut self, require_polyfill: bool) -> Self {
+        self.require_polyfill = require_polyfill;
+        self
+    }
+
+    pub(crate) fn with_chunk_size(mut self, chunk_size: Option<usize>) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// Builds a call to a module that is still being bundled, which happens when
+    /// modules require each other. The module is loaded when the call is evaluated.
+    pub(crate) fn build_cyclic_require(
+        &mut self,
+        require_path: &Path,
+        call: &FunctionCall,
+    ) -> Expression {
+        let module_name = if let Some(module_name) = self.reserved_module_names.get(require_path) {
+            module_name.clone()
+        } else {
+            let module_name = self.generate_module_name();
+            self.reserved_module_names
+                .insert(require_path.to_path_buf(), module_name.clone());
+            module_name
+        };
+
+        self.build_require_call(module_name, call)
+    }
+
     pub(crate) fn build_module_from_resource(
         &mut self,
         required_resource: RequiredResource,
         require_path: &Path,
         call: &FunctionCall,
+        size: usize,
+        is_lazy: bool,
     ) -> DarkluaResult<Expression> {
         let mut block = match required_resource {
             RequiredResource::Block(block) => {
@@ -83,15 +140,22 @@ impl BuildModuleDefinitions {
             .rename_type_declaration
             .extract_exported_types(&mut block);
 
-        let module_name = self.generate_module_name();
+        let module_name = match self.reserved_module_names.remove(require_path) {
+            Some(module_name) => module_name,
+            None => self.generate_module_name(),
+        };
 
         self.module_definitions.insert(
             module_name.clone(),
-            ModuleDefinition::new(block, require_path.to_path_buf()),
+            ModuleDefinition::new(block, require_path.to_path_buf(), size, is_lazy),
         );
         self.rename_type_declaration
             .insert_module_types(module_name.clone(), exported_types);
 
+        Ok(self.build_require_call(module_name, call))
+    }
+
+    fn build_require_call(&self, module_name: String, call: &FunctionCall) -> Expression {
         let token_trivia_identifier = match call.get_prefix() {
             Prefix::Identifier(require_identifier) => require_identifier.get_token(),
             _ => None,
@@ -139,14 +203,12 @@ impl BuildModuleDefinitions {
             Arguments::Table(_) => TupleArguments::default(),
         };
 
-        let new_require_call = FunctionCall::from_prefix(FieldExpression::new(
+        FunctionCall::from_prefix(FieldExpression::new(
             Identifier::from(&self.modules_identifier),
             module_field_name,
         ))
         .with_arguments(arguments)
-        .into();
-
-        Ok(new_require_call)
+        .into()
     }
 
     fn generate_module_name(&mut self) -> String {
@@ -168,6 +230,17 @@ impl BuildModuleDefinitions {
             context.add_file_dependency(module.path.clone());
         }
 
+        if self.tree_shaking {
+            tree_shaking::shake_modules(
+                block,
+                self.module_definitions
+                    .iter_mut()
+                    .map(|(name, module)| (name, &mut module.block))
+                    .collect(),
+                &self.modules_identifier,
+            );
+        }
+
         self.rename_type_declaration.rename_types(block);
 
         let modules_identifier = Identifier::from(&self.modules_identifier);
@@ -183,74 +256,58 @@ impl BuildModuleDefinitions {
 
         ShiftTokenLine::new(shift_lines).flawless_process(block, context);
 
-        let statements = self
-            .module_definitions
-            .drain(..)
-            .map(|(module_name, module)| {
-                let function_name =
-                    FunctionName::from_name(modules_identifier.clone()).with_field(&module_name);
-
-                const MODULE_CONTENT_ENTRY: &str = "c";
-                const MODULE_CONTENT_VARIABLE: &str = "v";
-
-                let module_content_variable_identifier = Identifier::new(MODULE_CONTENT_VARIABLE);
+        let require_polyfill = self.require_polyfill;
+        let chunk_assignments = self.assign_chunks(context.original_code().len());
+        let chunk_names: Vec<_> = (1..=chunk_assignments
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |i| i + 1))
+            .map(|index| chunk_name(context.output_path(), index))
+            .collect();
+        let mut chunks: Vec<Vec<Statement>> = chunk_names.iter().map(|_| Vec::new()).collect();
 
-                let index_cache = FieldExpression::new(
-                    FieldExpression::new(
-                        modules_identifier.clone(),
-                        BUNDLE_MODULES_VARIABLE_CACHE_FIELD,
-                    ),
-                    &module_name,
+        let mut statements = Vec::new();
+        for ((module_name, module), chunk) in
+            self.module_definitions.drain(..).zip(chunk_assignments)
+        {
+            let module_statement =
+                build_module_statement(&modules_identifier, &module_name, module, require_polyfill);
+
+            if let Some(index) = chunk {
+                log::debug!(
+                    "move module `{}` to chunk `{}`",
+                    module_name,
+                    chunk_names[index]
                 );
+                chunks[index].push(module_statement);
+                statements.push(build_chunk_loader(
+                    &modules_identifier,
+                    &module_name,
+                    &chunk_names[index],
+                ));
+            } else {
+                statements.push(module_statement);
+            }
+        }
 
-                const LOCAL_MODULE_IMPL_NAME: &str = "__modImpl";
-
-                let cached_block = Block::default()
-                    .with_statement(
-                        LocalAssignStatement::from_variable(MODULE_CONTENT_VARIABLE)
-                            .with_value(index_cache.clone()),
-                    )
-                    .with_statement(IfStatement::create(
-                        UnaryExpression::new(
-                            UnaryOperator::Not,
-                            module_content_variable_identifier.clone(),
-                        ),
-                        Block::default()
-                            .with_statement(AssignStatement::from_variable(
-                                module_content_variable_identifier.clone(),
-                                TableExpression::default().append_entry(
-                                    TableEntry::from_string_key_and_value(
-                                        MODULE_CONTENT_ENTRY,
-                                        FunctionCall::from_name(LOCAL_MODULE_IMPL_NAME),
-                                    ),
-                                ),
-                            ))
-                            .with_statement(AssignStatement::from_variable(
-                                index_cache,
-                                module_content_variable_identifier.clone(),
-                            )),
-                    ))
-                    .with_last_statement(ReturnStatement::one(FieldExpression::new(
-                        module_content_variable_identifier,
-                        MODULE_CONTENT_ENTRY,
-                    )));
+        for (chunk_statements, chunk_name) in chunks.into_iter().zip(chunk_names.iter()) {
+            let mut chunk_block = Block::new(chunk_statements, None);
+            if require_polyfill {
+                chunk_block.insert_statement(0, self.build_load_module_function());
+            }
+            let chunk = Block::default().with_last_statement(ReturnStatement::one(
+                FunctionExpression::from_block(chunk_block)
+                    .with_parameter(self.modules_identifier.as_str()),
+            ));
+            context.add_output_chunk(chunk_path(context.output_path(), chunk_name), chunk);
+        }
 
-                DoStatement::new(Block::new(
-                    vec![
-                        LocalFunctionStatement::from_name(LOCAL_MODULE_IMPL_NAME, module.block)
-                            .into(),
-                        FunctionStatement::new(function_name, cached_block, Vec::new(), false)
-                            .with_return_type(ExpressionType::new(FunctionCall::from_name(
-                                LOCAL_MODULE_IMPL_NAME,
-                            )))
-                            .into(),
-                    ],
-                    None,
-                ))
-                .into()
-            })
-            .collect();
-        block.insert_statement(0, DoStatement::new(Block::new(statements, None)));
+        let mut modules_block = Block::new(statements, None);
+        if require_polyfill {
+            modules_block.insert_statement(0, self.build_load_module_function());
+        }
+        block.insert_statement(0, DoStatement::new(modules_block));
 
         let modules_table = self.build_modules_table();
         block.insert_statement(
@@ -268,6 +325,136 @@ impl BuildModuleDefinitions {
         }
     }
 
+    /// Returns the index of the chunk of each module, or `None` for the modules staying
+    /// in the main bundle. Lazy modules get their own chunk, and when a chunk size is
+    /// defined, the modules that do not fit in the main bundle are grouped into chunks
+    /// of at most that size.
+    fn assign_chunks(&self, main_size: usize) -> Vec<Option<usize>> {
+        let mut main_size = main_size;
+        let mut chunk_count = 0;
+        let mut current_chunk: Option<(usize, usize)> = None;
+
+        self.module_definitions
+            .values()
+            .map(|module| {
+                if module.is_lazy {
+                    chunk_count += 1;
+                    return Some(chunk_count - 1);
+                }
+
+                let chunk_size = self.chunk_size?;
+
+                if main_size + module.size <= chunk_size {
+                    main_size += module.size;
+                    return None;
+                }
+
+                match &mut current_chunk {
+                    Some((index, size)) if *size + module.size <= chunk_size => {
+                        *size += module.size;
+                        Some(*index)
+                    }
+                    _ => {
+                        chunk_count += 1;
+                        current_chunk = Some((chunk_count - 1, module.size));
+                        Some(chunk_count - 1)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Builds the function used by the modules to load themselves when the require
+    /// polyfill is enabled. Like `require` in Lua, it caches the value of each module
+    /// and errors when a module is required again while it is still loading:
+    /// ```lua
+    /// local function __loadMod(name, path, impl)
+    ///     local cache = __DARKLUA_BUNDLE_MODULES.cache
+    ///     local v = cache[name]
+    ///     if v == nil then
+    ///         cache[name] = false
+    ///         v = { c = impl() }
+    ///         cache[name] = v
+    ///     elseif not v then
+    ///         error("loop or previous error loading module '" .. path .. "'")
+    ///     end
+    ///     return v.c
+    /// end
+    /// ```
+    fn build_load_module_function(&self) -> LocalFunctionStatement {
+        const NAME: &str = "name";
+        const PATH: &str = "path";
+        const IMPL: &str = "impl";
+        const CACHE: &str = "cache";
+
+        let cache_entry = || IndexExpression::new(Identifier::new(CACHE), Identifier::new(NAME));
+
+        let load_block = Block::default()
+            .with_statement(AssignStatement::from_variable(cache_entry(), false))
+            .with_statement(AssignStatement::from_variable(
+                Identifier::new(MODULE_CONTENT_VARIABLE),
+                TableExpression::default().append_entry(TableEntry::from_string_key_and_value(
+                    MODULE_CONTENT_ENTRY,
+                    FunctionCall::from_name(IMPL),
+                )),
+            ))
+            .with_statement(AssignStatement::from_variable(
+                cache_entry(),
+                Identifier::new(MODULE_CONTENT_VARIABLE),
+            ));
+
+        let error_block = Block::default().with_statement(
+            FunctionCall::from_name("error").with_argument(BinaryExpression::new(
+                BinaryOperator::Concat,
+                StringExpression::from_value("loop or previous error loading module '"),
+                BinaryExpression::new(
+                    BinaryOperator::Concat,
+                    Identifier::new(PATH),
+                    StringExpression::from_value("'"),
+                ),
+            )),
+        );
+
+        LocalFunctionStatement::from_name(
+            LOAD_MODULE_FUNCTION_NAME,
+            Block::default()
+                .with_statement(LocalAssignStatement::from_variable(CACHE).with_value(
+                    FieldExpression::new(
+                        Identifier::from(&self.modules_identifier),
+                        BUNDLE_MODULES_VARIABLE_CACHE_FIELD,
+                    ),
+                ))
+                .with_statement(
+                    LocalAssignStatement::from_variable(MODULE_CONTENT_VARIABLE)
+                        .with_value(cache_entry()),
+                )
+                .with_statement(
+                    IfStatement::create(
+                        BinaryExpression::new(
+                            BinaryOperator::Equal,
+                            Identifier::new(MODULE_CONTENT_VARIABLE),
+                            Expression::nil(),
+                        ),
+                        load_block,
+                    )
+                    .with_new_branch(
+                        UnaryExpression::new(
+                            UnaryOperator::Not,
+                            Identifier::new(MODULE_CONTENT_VARIABLE),
+                        ),
+                        error_block,
+                    ),
+                )
+                .with_last_statement(ReturnStatement::one(FieldExpression::new(
+                    Identifier::new(MODULE_CONTENT_VARIABLE),
+                    MODULE_CONTENT_ENTRY,
+                ))),
+        )
+        .with_parameter(NAME)
+        .with_parameter(PATH)
+        .with_parameter(IMPL)
+    }
+
     fn build_modules_table(&self) -> TableExpression {
         TableExpression::default().append_entry(TableEntry::from_string_key_and_value(
             BUNDLE_MODULES_VARIABLE_CACHE_FIELD,
@@ -276,6 +463,129 @@ impl BuildModuleDefinitions {
     }
 }
 
+/// Builds the definition of a module: a do statement with the module implementation
+/// and the function that loads it.
+fn build_module_statement(
+    modules_identifier: &Identifier,
+    module_name: &str,
+    module: ModuleDefinition,
+    require_polyfill: bool,
+) -> Statement {
+    let function_name = FunctionName::from_name(modules_identifier.clone()).with_field(module_name);
+
+    let module_content_variable_identifier = Identifier::new(MODULE_CONTENT_VARIABLE);
+
+    let index_cache = FieldExpression::new(
+        FieldExpression::new(
+            modules_identifier.clone(),
+            BUNDLE_MODULES_VARIABLE_CACHE_FIELD,
+        ),
+        module_name,
+    );
+
+    let cached_block = if require_polyfill {
+        Block::default().with_last_statement(ReturnStatement::one(
+            FunctionCall::from_name(LOAD_MODULE_FUNCTION_NAME)
+                .with_argument(StringExpression::from_value(module_name))
+                .with_argument(StringExpression::from_value(
+                    module.path.display().to_string(),
+                ))
+                .with_argument(Identifier::new(LOCAL_MODULE_IMPL_NAME)),
+        ))
+    } else {
+        Block::default()
+            .with_statement(
+                LocalAssignStatement::from_variable(MODULE_CONTENT_VARIABLE)
+                    .with_value(index_cache.clone()),
+            )
+            .with_statement(IfStatement::create(
+                UnaryExpression::new(
+                    UnaryOperator::Not,
+                    module_content_variable_identifier.clone(),
+                ),
+                Block::default()
+                    .with_statement(AssignStatement::from_variable(
+                        module_content_variable_identifier.clone(),
+                        TableExpression::default().append_entry(
+                            TableEntry::from_string_key_and_value(
+                                MODULE_CONTENT_ENTRY,
+                                FunctionCall::from_name(LOCAL_MODULE_IMPL_NAME),
+                            ),
+                        ),
+                    ))
+                    .with_statement(AssignStatement::from_variable(
+                        index_cache,
+                        module_content_variable_identifier.clone(),
+                    )),
+            ))
+            .with_last_statement(ReturnStatement::one(FieldExpression::new(
+                module_content_variable_identifier,
+                MODULE_CONTENT_ENTRY,
+            )))
+    };
+
+    DoStatement::new(Block::new(
+        vec![
+            LocalFunctionStatement::from_name(LOCAL_MODULE_IMPL_NAME, module.block).into(),
+            FunctionStatement::new(function_name, cached_block, Vec::new(), false)
+                .with_return_type(ExpressionType::new(FunctionCall::from_name(
+                    LOCAL_MODULE_IMPL_NAME,
+                )))
+                .into(),
+        ],
+        None,
+    ))
+    .into()
+}
+
+/// Builds the function that loads a module from a chunk of the bundle. Loading the
+/// chunk replaces the functions of the modules it contains:
+/// ```lua
+/// function __DARKLUA_BUNDLE_MODULES.a()
+///     require("./main.chunk-1")(__DARKLUA_BUNDLE_MODULES)
+///     return __DARKLUA_BUNDLE_MODULES.a()
+/// end
+/// ```
+fn build_chunk_loader(
+    modules_identifier: &Identifier,
+    module_name: &str,
+    chunk_name: &str,
+) -> Statement {
+    let function_name = FunctionName::from_name(modules_identifier.clone()).with_field(module_name);
+
+    let load_chunk = FunctionCall::from_prefix(
+        FunctionCall::from_name("require")
+            .with_argument(StringExpression::from_value(format!("./{}", chunk_name))),
+    )
+    .with_argument(modules_identifier.clone());
+
+    let block = Block::default()
+        .with_statement(load_chunk)
+        .with_last_statement(ReturnStatement::one(FunctionCall::from_prefix(
+            FieldExpression::new(modules_identifier.clone(), module_name),
+        )));
+
+    FunctionStatement::new(function_name, block, Vec::new(), false).into()
+}
+
+/// Returns the file name (without its extension) of a chunk of the bundle.
+fn chunk_name(output_path: &Path, index: usize) -> String {
+    let stem = output_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "bundle".to_owned());
+    format!("{}.chunk-{}", stem, index)
+}
+
+/// Returns the path of a chunk of the bundle, next to the bundle output.
+fn chunk_path(output_path: &Path, chunk_name: &str) -> PathBuf {
+    let file_name = match output_path.extension() {
+        Some(extension) => format!("{}.{}", chunk_name, extension.to_string_lossy()),
+        None => chunk_name.to_owned(),
+    };
+    output_path.with_file_name(file_name)
+}
+
 fn transfer_trivia(mut receiving_token: Token, take_token: &Token) -> Token {
     for (content, kind) in take_token.iter_trailing_trivia().filter_map(|trivia| {
         trivia
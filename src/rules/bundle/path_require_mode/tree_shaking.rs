@@ -0,0 +1,467 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::nodes::{
+    Arguments, Block, Expression, FieldExpression, FunctionCall, Identifier, LastStatement,
+    LocalAssignStatement, Prefix, Statement, TableEntry, TableExpression, Variable,
+};
+use crate::process::{DefaultVisitor, Evaluator, NodeProcessor, NodeVisitor};
+
+#[derive(Debug, Default)]
+struct ModuleUsage {
+    fields: HashSet<String>,
+    calls: usize,
+    field_calls: usize,
+    escaped: bool,
+}
+
+/// Finds how the bundled modules are used. A module value is only considered
+/// safe to shake when it is indexed with a field directly from the module call
+/// (`modules.a().field`) or from a local variable that only gets indexed
+/// (`local lib = modules.a()` then `lib.field`).
+struct ModuleUsageCollector<'a> {
+    modules_identifier: &'a str,
+    usages: HashMap<String, ModuleUsage>,
+    aliases: HashMap<String, String>,
+    alias_uses: HashMap<String, (usize, usize)>,
+}
+
+impl<'a> ModuleUsageCollector<'a> {
+    fn new(modules_identifier: &'a str, module_names: impl Iterator<Item = &'a String>) -> Self {
+        Self {
+            modules_identifier,
+            usages: module_names
+                .map(|name| (name.clone(), ModuleUsage::default()))
+                .collect(),
+            aliases: HashMap::new(),
+            alias_uses: HashMap::new(),
+        }
+    }
+
+    fn get_module_name(&self, call: &FunctionCall) -> Option<String> {
+        if call.has_method() {
+            return None;
+        }
+        match call.get_arguments() {
+            Arguments::Tuple(tuple) if tuple.is_empty() => {}
+            _ => return None,
+        }
+        match call.get_prefix() {
+            Prefix::Field(field) => match field.get_prefix() {
+                Prefix::Identifier(identifier)
+                    if identifier.get_name() == self.modules_identifier =>
+                {
+                    let module_name = field.get_field().get_name();
+                    self.usages
+                        .contains_key(module_name)
+                        .then(|| module_name.to_owned())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn get_module_name_from_expression(&self, expression: &Expression) -> Option<String> {
+        match expression {
+            Expression::Call(call) => self.get_module_name(call),
+            _ => None,
+        }
+    }
+
+    fn into_used_fields(mut self) -> HashMap<String, Option<HashSet<String>>> {
+        let escaped_modules: Vec<_> = self
+            .alias_uses
+            .iter()
+            .filter(|(_, (total, field_uses))| total != field_uses)
+            .filter_map(|(alias, _)| self.aliases.get(alias).cloned())
+            .collect();
+
+        for module_name in escaped_modules {
+            if let Some(usage) = self.usages.get_mut(&module_name) {
+                usage.escaped = true;
+            }
+        }
+
+        self.usages
+            .into_iter()
+            .map(|(module_name, usage)| {
+                let escaped = usage.escaped || usage.calls != usage.field_calls;
+                (module_name, (!escaped).then_some(usage.fields))
+            })
+            .collect()
+    }
+}
+
+impl NodeProcessor for ModuleUsageCollector<'_> {
+    fn process_local_assign_statement(&mut self, assign: &mut LocalAssignStatement) {
+        if assign.variables_len() != 1 || assign.values_len() != 1 {
+            return;
+        }
+        let module_name = match assign
+            .iter_values()
+            .next()
+            .and_then(|value| self.get_module_name_from_expression(value))
+        {
+            Some(module_name) => module_name,
+            None => return,
+        };
+        let alias = assign.get_variables()[0].get_name().to_owned();
+
+        if let Some(usage) = self.usages.get_mut(&module_name) {
+            usage.field_calls += 1;
+        }
+
+        if let Some(previous_module) = self.aliases.insert(alias, module_name.clone()) {
+            if previous_module != module_name {
+                // the same name is used for different modules, so it is not possible
+                // to know which module is used from the identifier name only
+                for module in [previous_module, module_name] {
+                    if let Some(usage) = self.usages.get_mut(&module) {
+                        usage.escaped = true;
+                    }
+                }
+            }
+        }
+    }
+
+    fn process_statement(&mut self, statement: &mut Statement) {
+        // a module required only for its side effects does not use any field
+        if let Statement::Call(call) = statement {
+            if let Some(module_name) = self.get_module_name(call) {
+                if let Some(usage) = self.usages.get_mut(&module_name) {
+                    usage.field_calls += 1;
+                }
+            }
+        }
+    }
+
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        if let Some(module_name) = self.get_module_name(call) {
+            if let Some(usage) = self.usages.get_mut(&module_name) {
+                usage.calls += 1;
+            }
+        }
+    }
+
+    fn process_field_expression(&mut self, field: &mut FieldExpression) {
+        let field_name = field.get_field().get_name();
+
+        match field.get_prefix() {
+            Prefix::Call(call) => {
+                if let Some(module_name) = self.get_module_name(call) {
+                    if let Some(usage) = self.usages.get_mut(&module_name) {
+                        usage.field_calls += 1;
+                        usage.fields.insert(field_name.to_owned());
+                    }
+                }
+            }
+            Prefix::Identifier(identifier) => {
+                if let Some(module_name) = self.aliases.get(identifier.get_name()) {
+                    if let Some(usage) = self.usages.get_mut(module_name) {
+                        usage.fields.insert(field_name.to_owned());
+                    }
+                    self.alias_uses
+                        .entry(identifier.get_name().to_owned())
+                        .or_default()
+                        .1 += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if self.aliases.contains_key(identifier.get_name()) {
+            self.alias_uses
+                .entry(identifier.get_name().to_owned())
+                .or_default()
+                .0 += 1;
+        }
+    }
+}
+
+/// Counts the uses of a module table inside the module itself.
+#[derive(Debug)]
+struct InternalUsageCounter<'a> {
+    name: &'a str,
+    total: usize,
+    field_uses: usize,
+    fields: HashSet<String>,
+}
+
+impl<'a> InternalUsageCounter<'a> {
+    fn new(name: &'a str) -> Self {
+        Self {
+            name,
+            total: 0,
+            field_uses: 0,
+            fields: HashSet::new(),
+        }
+    }
+
+    fn has_escaped(&self) -> bool {
+        self.total != self.field_uses
+    }
+}
+
+impl NodeProcessor for InternalUsageCounter<'_> {
+    fn process_field_expression(&mut self, field: &mut FieldExpression) {
+        if let Prefix::Identifier(identifier) = field.get_prefix() {
+            if identifier.get_name() == self.name {
+                self.field_uses += 1;
+                self.fields.insert(field.get_field().get_name().to_owned());
+            }
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if identifier.get_name() == self.name {
+            self.total += 1;
+        }
+    }
+}
+
+/// Removes the fields of the bundled modules that are never used by the other modules.
+/// Returns the total number of removed fields.
+pub(crate) fn shake_modules(
+    main_block: &mut Block,
+    mut modules: Vec<(&String, &mut Block)>,
+    modules_identifier: &str,
+) -> usize {
+    let module_names: Vec<String> = modules.iter().map(|(name, _)| (*name).clone()).collect();
+    let mut collector = ModuleUsageCollector::new(modules_identifier, module_names.iter());
+
+    DefaultVisitor::visit_block(main_block, &mut collector);
+    for (_, block) in modules.iter_mut() {
+        DefaultVisitor::visit_block(block, &mut collector);
+    }
+
+    let mut used_fields = collector.into_used_fields();
+
+    let mut removed = 0;
+    for (module_name, block) in modules {
+        match used_fields.remove(module_name).flatten() {
+            Some(fields) => {
+                let module_removed = shake_module(block, &fields);
+                if module_removed > 0 {
+                    log::debug!(
+                        "tree shaking removed {} field{} from module `{}`",
+                        module_removed,
+                        if module_removed == 1 { "" } else { "s" },
+                        module_name,
+                    );
+                }
+                removed += module_removed;
+            }
+            None => {
+                log::trace!(
+                    "skip tree shaking for module `{}` because its value is not only indexed",
+                    module_name
+                );
+            }
+        }
+    }
+    removed
+}
+
+fn shake_module(block: &mut Block, used_fields: &HashSet<String>) -> usize {
+    let evaluator = Evaluator::default();
+
+    if let Some(LastStatement::Return(return_statement)) = block.mutate_last_statement() {
+        if return_statement.len() != 1 {
+            return 0;
+        }
+        match return_statement.iter_mut_expressions().next() {
+            Some(Expression::Table(table)) => {
+                return remove_table_fields(table, used_fields, &evaluator);
+            }
+            Some(Expression::Identifier(_)) => {}
+            _ => return 0,
+        }
+    }
+
+    let module_name = match block.get_last_statement() {
+        Some(LastStatement::Return(return_statement)) => {
+            match return_statement.iter_expressions().next() {
+                Some(Expression::Identifier(identifier)) => identifier.get_name().to_owned(),
+                _ => return 0,
+            }
+        }
+        _ => return 0,
+    };
+
+    let declarations: Vec<_> = block
+        .iter_statements()
+        .enumerate()
+        .filter(|(_, statement)| match statement {
+            Statement::LocalAssign(assign) => assign
+                .iter_variables()
+                .any(|variable| variable.get_name() == &module_name),
+            Statement::LocalFunction(function) => function.get_name() == module_name,
+            _ => false,
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    let declaration_index = match declarations.as_slice() {
+        [index] => *index,
+        _ => return 0,
+    };
+
+    match block.iter_statements().nth(declaration_index) {
+        Some(Statement::LocalAssign(assign))
+            if assign.variables_len() == 1
+                && assign.values_len() == 1
+                && matches!(assign.iter_values().next(), Some(Expression::Table(_))) => {}
+        _ => return 0,
+    }
+
+    let mut removed = 0;
+
+    loop {
+        let mut counter = InternalUsageCounter::new(&module_name);
+
+        for statement in block.iter_mut_statements() {
+            match get_definition(statement, &module_name) {
+                Some(_) => visit_definition_value(statement, &mut counter),
+                None => DefaultVisitor::visit_statement(statement, &mut counter),
+            }
+        }
+
+        if counter.has_escaped() {
+            return removed;
+        }
+
+        let is_used = |field: &str| used_fields.contains(field) || counter.fields.contains(field);
+
+        let mut removed_in_pass = 0;
+
+        if let Some(Statement::LocalAssign(assign)) =
+            block.iter_mut_statements().nth(declaration_index)
+        {
+            if let Some(Expression::Table(table)) = assign.iter_mut_values().next() {
+                removed_in_pass += remove_table_fields_with(table, &is_used, &evaluator);
+            }
+        }
+
+        let statements_before = block.statements_len();
+        block.filter_statements(|statement| match get_definition(statement, &module_name) {
+            Some(field) => is_used(&field) || definition_has_side_effects(statement, &evaluator),
+            None => true,
+        });
+        removed_in_pass += statements_before - block.statements_len();
+
+        if removed_in_pass == 0 {
+            break;
+        }
+        removed += removed_in_pass;
+    }
+
+    removed
+}
+
+/// Returns the name of the field defined by a statement like `module.field = value` or
+/// `function module.field() end`.
+fn get_definition(statement: &Statement, module_name: &str) -> Option<String> {
+    match statement {
+        Statement::Assign(assign) => {
+            if assign.variables_len() != 1 || assign.values_len() != 1 {
+                return None;
+            }
+            match assign.get_variables().first() {
+                Some(Variable::Field(field)) => match field.get_prefix() {
+                    Prefix::Identifier(identifier) if identifier.get_name() == module_name => {
+                        Some(field.get_field().get_name().to_owned())
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        Statement::Function(function) => {
+            let name = function.get_name();
+            if name.get_name().get_name() == module_name
+                && name.get_field_names().len() == 1
+                && !name.has_method()
+            {
+                name.get_field_names()
+                    .first()
+                    .map(|field| field.get_name().to_owned())
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn visit_definition_value(statement: &mut Statement, counter: &mut InternalUsageCounter) {
+    match statement {
+        Statement::Assign(assign) => {
+            for value in assign.iter_mut_values() {
+                DefaultVisitor::visit_expression(value, counter);
+            }
+        }
+        Statement::Function(function) => {
+            DefaultVisitor::visit_block(function.mutate_block(), counter);
+        }
+        _ => {}
+    }
+}
+
+fn definition_has_side_effects(statement: &Statement, evaluator: &Evaluator) -> bool {
+    match statement {
+        Statement::Assign(assign) => assign
+            .iter_values()
+            .any(|value| evaluator.has_side_effects(value)),
+        _ => false,
+    }
+}
+
+fn remove_table_fields(
+    table: &mut TableExpression,
+    used_fields: &HashSet<String>,
+    evaluator: &Evaluator,
+) -> usize {
+    remove_table_fields_with(table, &|field| used_fields.contains(field), evaluator)
+}
+
+fn remove_table_fields_with(
+    table: &mut TableExpression,
+    is_used: &dyn Fn(&str) -> bool,
+    evaluator: &Evaluator,
+) -> usize {
+    let removed_indexes: Vec<_> = table
+        .iter_entries()
+        .enumerate()
+        .filter(|(_, entry)| match entry {
+            TableEntry::Field(field) => {
+                !is_used(field.get_field().get_name())
+                    && !evaluator.has_side_effects(field.get_value())
+            }
+            TableEntry::Index(_) | TableEntry::Value(_) => false,
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    if removed_indexes.is_empty() {
+        return 0;
+    }
+
+    let mut tokens = table.get_tokens().cloned();
+
+    for index in removed_indexes.iter().rev() {
+        table.mutate_entries().remove(*index);
+        if let Some(tokens) = &mut tokens {
+            if *index < tokens.separators.len() {
+                tokens.separators.remove(*index);
+            }
+        }
+    }
+
+    if let Some(tokens) = tokens {
+        table.set_tokens(tokens);
+    }
+
+    removed_indexes.len()
+}
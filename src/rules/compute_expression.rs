@@ -1,18 +1,38 @@
+use std::ops;
+
 use crate::nodes::{BinaryOperator, Block, Expression};
-use crate::process::{DefaultVisitor, Evaluator, NodeProcessor, NodeVisitor};
+use crate::process::{Evaluator, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
 use crate::rules::{
     Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
 };
 
-use super::verify_no_rule_properties;
+const STRING_FUNCTIONS_PROPERTY: &str = "string_functions";
+const STRING_LIBRARY_IDENTIFIER: &str = "string";
 
 #[derive(Debug, Clone, Default)]
 struct Computer {
     evaluator: Evaluator,
+    string_functions: bool,
+    identifier_tracker: IdentifierTracker,
 }
 
 impl Computer {
+    fn new(string_functions: bool) -> Self {
+        Self {
+            string_functions,
+            ..Default::default()
+        }
+    }
+
     fn replace_with(&mut self, expression: &Expression) -> Option<Expression> {
+        // the string library can only be evaluated when it is not shadowed by a local variable
+        self.evaluator =
+            if self.string_functions && !self.is_identifier_used(STRING_LIBRARY_IDENTIFIER) {
+                Evaluator::default().assume_string_library()
+            } else {
+                Evaluator::default()
+            };
+
         match expression {
             Expression::Unary(_) => {
                 if !self.evaluator.has_side_effects(expression) {
@@ -93,7 +113,7 @@ impl Computer {
                     }
                 }
             }
-            Expression::If(_) => {
+            Expression::If(_) | Expression::Call(_) => {
                 if !self.evaluator.has_side_effects(expression) {
                     self.evaluator.evaluate(expression).to_expression()
                 } else {
@@ -105,6 +125,20 @@ impl Computer {
     }
 }
 
+impl ops::Deref for Computer {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Computer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
 impl NodeProcessor for Computer {
     fn process_expression(&mut self, expression: &mut Expression) {
         if let Some(replace_with) = self.replace_with(expression) {
@@ -117,18 +151,35 @@ pub const COMPUTE_EXPRESSIONS_RULE_NAME: &str = "compute_expression";
 
 /// A rule that compute expressions that do not have any side-effects.
 #[derive(Debug, Default, PartialEq, Eq)]
-pub struct ComputeExpression {}
+pub struct ComputeExpression {
+    string_functions: bool,
+}
+
+impl ComputeExpression {
+    /// Enables or disables the evaluation of calls to `string.rep` and `string.format`.
+    pub fn with_string_functions(mut self, value: bool) -> Self {
+        self.string_functions = value;
+        self
+    }
+}
 
 impl FlawlessRule for ComputeExpression {
     fn flawless_process(&self, block: &mut Block, _: &Context) {
-        let mut processor = Computer::default();
-        DefaultVisitor::visit_block(block, &mut processor);
+        let mut processor = Computer::new(self.string_functions);
+        ScopeVisitor::visit_block(block, &mut processor);
     }
 }
 
 impl RuleConfiguration for ComputeExpression {
     fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
-        verify_no_rule_properties(&properties)?;
+        for (key, value) in properties {
+            match key.as_str() {
+                STRING_FUNCTIONS_PROPERTY => {
+                    self.string_functions = value.expect_bool(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
 
         Ok(())
     }
@@ -138,7 +189,13 @@ impl RuleConfiguration for ComputeExpression {
     }
 
     fn serialize_to_properties(&self) -> RuleProperties {
-        RuleProperties::new()
+        let mut properties = RuleProperties::new();
+
+        if self.string_functions {
+            properties.insert(STRING_FUNCTIONS_PROPERTY.to_owned(), true.into());
+        }
+
+        properties
     }
 }
 
@@ -159,4 +216,27 @@ mod test {
 
         assert_json_snapshot!(rule, @r###""compute_expression""###);
     }
+
+    #[test]
+    fn serialize_rule_with_string_functions() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_string_functions(true));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "compute_expression",
+          "string_functions": true
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'compute_expression',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
 }
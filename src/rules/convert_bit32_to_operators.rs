@@ -0,0 +1,396 @@
+use crate::nodes::{
+    BinaryExpression, BinaryOperator, Block, Expression, FunctionCall, HexNumber, Prefix,
+    UnaryExpression, UnaryOperator,
+};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+pub const CONVERT_BIT32_TO_OPERATORS_RULE_NAME: &str = "convert_bit32_to_operators";
+
+const BIT32_LIBRARY: &str = "bit32";
+
+#[derive(Default)]
+struct Processor {
+    identifier_tracker: IdentifierTracker,
+}
+
+impl Processor {
+    /// Returns the name of the `bit32` function called, if the call is made on the
+    /// global `bit32` library.
+    fn get_bit32_function<'a>(&self, call: &'a FunctionCall) -> Option<&'a str> {
+        if call.has_method() {
+            return None;
+        }
+
+        let Prefix::Field(field_expr) = call.get_prefix() else {
+            return None;
+        };
+
+        let Prefix::Identifier(identifier) = field_expr.get_prefix() else {
+            return None;
+        };
+
+        if identifier.get_name() != BIT32_LIBRARY || self.is_identifier_used(BIT32_LIBRARY) {
+            return None;
+        }
+
+        Some(field_expr.get_field().get_name().as_str())
+    }
+
+    fn convert_call(&self, call: &FunctionCall) -> Option<Expression> {
+        let function = self.get_bit32_function(call)?;
+
+        let arguments = call.get_arguments().clone().to_expressions();
+
+        // the last argument could expand into multiple values
+        if matches!(
+            arguments.last(),
+            Some(Expression::Call(_) | Expression::VariableArguments(_))
+        ) {
+            return None;
+        }
+
+        match function {
+            "band" => {
+                // the result fits in 32 bits when one of the values does
+                let is_masked = arguments.iter().any(is_unsigned_32_bits_number);
+                let result = fold_arguments(BinaryOperator::BitwiseAnd, arguments)?;
+                Some(if is_masked { result } else { mask(result) })
+            }
+            "bor" => fold_arguments(BinaryOperator::BitwiseOr, arguments).map(mask),
+            "bxor" => fold_arguments(BinaryOperator::BitwiseXor, arguments).map(mask),
+            "lshift" => {
+                let (value, shift) = get_shift_arguments(arguments)?;
+                // a negative shift moves the bits to the right, so the value must
+                // be truncated to 32 bits first
+                let value = if is_non_negative_integer(&shift) {
+                    value
+                } else {
+                    mask_value(value)
+                };
+                Some(mask(
+                    BinaryExpression::new(BinaryOperator::LeftShift, value, shift).into(),
+                ))
+            }
+            "rshift" => {
+                let (value, shift) = get_shift_arguments(arguments)?;
+                let is_right_shift = is_non_negative_integer(&shift);
+                let result =
+                    BinaryExpression::new(BinaryOperator::RightShift, mask_value(value), shift)
+                        .into();
+                // a negative shift moves the bits to the left
+                Some(if is_right_shift { result } else { mask(result) })
+            }
+            "bnot" => {
+                let mut arguments = arguments.into_iter();
+                match (arguments.next(), arguments.next()) {
+                    (Some(value), None) => Some(mask(
+                        UnaryExpression::new(UnaryOperator::BitwiseNot, value).into(),
+                    )),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+const UNSIGNED_32_BITS_MASK: u64 = 0xFFFF_FFFF;
+
+fn get_number_value(expression: &Expression) -> Option<f64> {
+    match expression {
+        Expression::Number(number) => Some(number.compute_value()),
+        Expression::Parenthese(parenthese) => get_number_value(parenthese.inner_expression()),
+        _ => None,
+    }
+}
+
+fn is_non_negative_integer(expression: &Expression) -> bool {
+    get_number_value(expression).is_some_and(|value| value >= 0.0 && value.fract() == 0.0)
+}
+
+fn is_unsigned_32_bits_number(expression: &Expression) -> bool {
+    get_number_value(expression).is_some_and(|value| {
+        value >= 0.0 && value.fract() == 0.0 && value <= UNSIGNED_32_BITS_MASK as f64
+    })
+}
+
+/// Keeps the lower 32 bits of the result, since the `bit32` functions work on
+/// unsigned 32-bit integers while the operators work on 64-bit integers.
+fn mask(expression: Expression) -> Expression {
+    BinaryExpression::new(
+        BinaryOperator::BitwiseAnd,
+        expression,
+        HexNumber::new(UNSIGNED_32_BITS_MASK, false),
+    )
+    .into()
+}
+
+/// Truncates a value to 32 bits, unless it is a number that already fits.
+fn mask_value(expression: Expression) -> Expression {
+    if is_unsigned_32_bits_number(&expression) {
+        expression
+    } else {
+        mask(expression)
+    }
+}
+
+fn fold_arguments(operator: BinaryOperator, arguments: Vec<Expression>) -> Option<Expression> {
+    if arguments.len() < 2 {
+        return None;
+    }
+
+    arguments
+        .into_iter()
+        .reduce(|left, right| BinaryExpression::new(operator, left, right).into())
+}
+
+fn get_shift_arguments(arguments: Vec<Expression>) -> Option<(Expression, Expression)> {
+    let mut arguments = arguments.into_iter();
+
+    match (arguments.next(), arguments.next(), arguments.next()) {
+        (Some(value), Some(shift), None) => Some((value, shift)),
+        _ => None,
+    }
+}
+
+impl std::ops::Deref for Processor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl std::ops::DerefMut for Processor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::Call(call) = expression {
+            if let Some(new_expression) = self.convert_call(call) {
+                *expression = new_expression;
+            }
+        }
+    }
+}
+
+/// A rule that converts `bit32` library calls (like `bit32.band(a, b)`) to the
+/// bitwise operators of Lua 5.3 (like `a & b`). Results are truncated to 32 bits
+/// (like `~a & 0xffffffff`) when the operators could produce a different value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConvertBit32ToOperators {}
+
+impl FlawlessRule for ConvertBit32ToOperators {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor::default();
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for ConvertBit32ToOperators {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        CONVERT_BIT32_TO_OPERATORS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> ConvertBit32ToOperators {
+        ConvertBit32ToOperators::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""convert_bit32_to_operators""###);
+    }
+
+    /// Evaluates an integer expression with the semantics of the Lua 5.3 operators.
+    fn evaluate(expression: &Expression) -> i64 {
+        fn shift_left(value: i64, shift: i64) -> i64 {
+            if shift <= -64 || shift >= 64 {
+                0
+            } else if shift >= 0 {
+                ((value as u64) << shift) as i64
+            } else {
+                ((value as u64) >> -shift) as i64
+            }
+        }
+
+        match expression {
+            Expression::Number(number) => number.compute_value() as i64,
+            Expression::Parenthese(parenthese) => evaluate(parenthese.inner_expression()),
+            Expression::Unary(unary) => {
+                let value = evaluate(unary.get_expression());
+                match unary.operator() {
+                    UnaryOperator::Minus => value.wrapping_neg(),
+                    UnaryOperator::BitwiseNot => !value,
+                    operator => panic!("unexpected unary operator {:?}", operator),
+                }
+            }
+            Expression::Binary(binary) => {
+                let left = evaluate(binary.left());
+                let right = evaluate(binary.right());
+                match binary.operator() {
+                    BinaryOperator::Plus => left.wrapping_add(right),
+                    BinaryOperator::BitwiseAnd => left & right,
+                    BinaryOperator::BitwiseOr => left | right,
+                    BinaryOperator::BitwiseXor => left ^ right,
+                    BinaryOperator::LeftShift => shift_left(left, right),
+                    BinaryOperator::RightShift => shift_left(left, right.wrapping_neg()),
+                    operator => panic!("unexpected binary operator {:?}", operator),
+                }
+            }
+            _ => panic!("unexpected expression {:?}", expression),
+        }
+    }
+
+    /// Computes the result of the `bit32` functions of Lua 5.2.
+    fn bit32(function: &str, arguments: &[i64]) -> i64 {
+        let unsigned = |value: i64| value.rem_euclid(1 << 32);
+
+        match (function, arguments) {
+            ("band", _) => arguments
+                .iter()
+                .map(|value| unsigned(*value))
+                .fold(!0, |a, b| a & b),
+            ("bor", _) => arguments
+                .iter()
+                .map(|value| unsigned(*value))
+                .fold(0, |a, b| a | b),
+            ("bxor", _) => arguments
+                .iter()
+                .map(|value| unsigned(*value))
+                .fold(0, |a, b| a ^ b),
+            ("bnot", [value]) => !unsigned(*value) & 0xFFFF_FFFF,
+            ("lshift", [value, shift]) if *shift < 0 => bit32("rshift", &[*value, -shift]),
+            ("lshift", [value, shift]) if *shift >= 32 => 0,
+            ("lshift", [value, shift]) => (unsigned(*value) << shift) & 0xFFFF_FFFF,
+            ("rshift", [value, shift]) if *shift < 0 => bit32("lshift", &[*value, -shift]),
+            ("rshift", [value, shift]) if *shift >= 32 => 0,
+            ("rshift", [value, shift]) => unsigned(*value) >> shift,
+            _ => panic!("unexpected bit32 call {}({:?})", function, arguments),
+        }
+    }
+
+    /// Converts a call to the given function and evaluates the generated code (each
+    /// argument is written as a literal and as an expression that is not a literal).
+    fn verify_call(function: &str, arguments: &[i64]) {
+        use crate::generator::{DenseLuaGenerator, LuaGenerator};
+        use crate::Parser;
+
+        let expected = bit32(function, arguments);
+
+        for written_as_literal in [true, false] {
+            let arguments = arguments
+                .iter()
+                .map(|value| {
+                    if written_as_literal {
+                        value.to_string()
+                    } else {
+                        format!("0 + {}", value)
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let code = format!("return bit32.{}({})", function, arguments);
+
+            let mut block = Parser::default().parse(&code).unwrap();
+            let resources = crate::Resources::from_memory();
+            let context = crate::rules::ContextBuilder::new(".", &resources, &code).build();
+            new_rule().flawless_process(&mut block, &context);
+
+            let mut generator = DenseLuaGenerator::default();
+            generator.write_block(&block);
+            let converted_code = generator.into_string();
+
+            let block = Parser::default()
+                .with_bitwise_operators()
+                .parse(&converted_code)
+                .unwrap();
+            let Some(crate::nodes::LastStatement::Return(statement)) = block.get_last_statement()
+            else {
+                panic!("expected return statement in `{}`", converted_code);
+            };
+            let result = statement.iter_expressions().next().map(evaluate);
+
+            assert_eq!(
+                result,
+                Some(expected),
+                "`{}` converted to `{}`",
+                code,
+                converted_code
+            );
+        }
+    }
+
+    const VALUES: [i64; 11] = [
+        0,
+        1,
+        5,
+        -1,
+        -6,
+        0xFF,
+        0x7FFF_FFFF,
+        0x8000_0000,
+        0xFFFF_FFFF,
+        0x1_0000_0005,
+        -0x1_0000_0000,
+    ];
+
+    const SHIFTS: [i64; 12] = [0, 1, 4, 31, 32, 33, 63, 64, -1, -4, -32, -33];
+
+    #[test]
+    fn converted_calls_compute_bit32_results() {
+        for value in VALUES {
+            verify_call("bnot", &[value]);
+
+            for shift in SHIFTS {
+                verify_call("lshift", &[value, shift]);
+                verify_call("rshift", &[value, shift]);
+            }
+
+            for other in VALUES {
+                verify_call("band", &[value, other]);
+                verify_call("bor", &[value, other]);
+                verify_call("bxor", &[value, other]);
+                verify_call("band", &[value, other, 0xF0F0_F0F0]);
+            }
+        }
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_bit32_to_operators',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,161 @@
+use std::{mem, ops};
+
+use crate::nodes::{
+    BinaryOperator, Block, Expression, FieldExpression, FunctionCall, LocalAssignStatement, Prefix,
+    UnaryOperator,
+};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    verify_no_rule_properties, Context, FlawlessRule, RuleConfiguration, RuleConfigurationError,
+    RuleProperties,
+};
+
+const BIT32_LIBRARY: &str = "bit32";
+
+struct BitwiseOperatorsProcessor {
+    bit32_identifier: String,
+    define_bit32: bool,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl ops::Deref for BitwiseOperatorsProcessor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for BitwiseOperatorsProcessor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl BitwiseOperatorsProcessor {
+    fn new(bit32_identifier: impl Into<String>) -> Self {
+        Self {
+            bit32_identifier: bit32_identifier.into(),
+            define_bit32: false,
+            identifier_tracker: Default::default(),
+        }
+    }
+
+    fn build_bit32_call(&mut self, function: &str, arguments: Vec<Expression>) -> Expression {
+        let library = if self.is_identifier_used(BIT32_LIBRARY) {
+            self.define_bit32 = true;
+            Prefix::from_name(&self.bit32_identifier)
+        } else {
+            Prefix::from_name(BIT32_LIBRARY)
+        };
+
+        let call = FunctionCall::from_prefix(FieldExpression::new(library, function));
+
+        arguments
+            .into_iter()
+            .fold(call, |call, argument| call.with_argument(argument))
+            .into()
+    }
+}
+
+fn get_bit32_binary_function(operator: BinaryOperator) -> Option<&'static str> {
+    match operator {
+        BinaryOperator::BitwiseAnd => Some("band"),
+        BinaryOperator::BitwiseOr => Some("bor"),
+        BinaryOperator::BitwiseXor => Some("bxor"),
+        BinaryOperator::LeftShift => Some("lshift"),
+        BinaryOperator::RightShift => Some("rshift"),
+        _ => None,
+    }
+}
+
+impl NodeProcessor for BitwiseOperatorsProcessor {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::Binary(binary) => {
+                if let Some(function) = get_bit32_binary_function(binary.operator()) {
+                    let left = mem::replace(binary.mutate_left(), Expression::nil());
+                    let right = mem::replace(binary.mutate_right(), Expression::nil());
+
+                    *expression = self.build_bit32_call(function, vec![left, right]);
+                }
+            }
+            Expression::Unary(unary) if unary.operator() == UnaryOperator::BitwiseNot => {
+                let value = mem::replace(unary.mutate_expression(), Expression::nil());
+
+                *expression = self.build_bit32_call("bnot", vec![value]);
+            }
+            _ => {}
+        }
+    }
+}
+
+pub const CONVERT_BITWISE_OPERATORS_TO_BIT32_RULE_NAME: &str = "convert_bitwise_operators_to_bit32";
+
+/// A rule that converts the bitwise operators of Lua 5.3 (like `a & b`) to `bit32`
+/// library calls (like `bit32.band(a, b)`).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConvertBitwiseOperatorsToBit32 {}
+
+impl FlawlessRule for ConvertBitwiseOperatorsToBit32 {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        const BIT32_IDENTIFIER: &str = "__DARKLUA_BIT32";
+
+        let mut processor = BitwiseOperatorsProcessor::new(BIT32_IDENTIFIER);
+        ScopeVisitor::visit_block(block, &mut processor);
+
+        if processor.define_bit32 {
+            block.insert_statement(
+                0,
+                LocalAssignStatement::from_variable(BIT32_IDENTIFIER)
+                    .with_value(Prefix::from_name(BIT32_LIBRARY)),
+            );
+        }
+    }
+}
+
+impl RuleConfiguration for ConvertBitwiseOperatorsToBit32 {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        CONVERT_BITWISE_OPERATORS_TO_BIT32_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> ConvertBitwiseOperatorsToBit32 {
+        ConvertBitwiseOperatorsToBit32::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""convert_bitwise_operators_to_bit32""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_bitwise_operators_to_bit32',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::HashSet;
+use std::{mem, ops};
+
+use crate::nodes::{
+    Arguments, Block, DecimalNumber, Expression, FunctionCall, FunctionExpression,
+    FunctionStatement, GenericForStatement, Identifier, IndexExpression, LocalAssignStatement,
+    LocalFunctionStatement, NumericForStatement, Prefix, Statement, UnaryExpression, UnaryOperator,
+};
+use crate::process::processors::VariableUsage;
+use crate::process::{
+    DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor, Scope, ScopeVisitor,
+};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+use crate::LuaTarget;
+
+const IPAIRS_IDENTIFIER: &str = "ipairs";
+
+/// The form that loops over arrays are converted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoopForm {
+    /// `for i, v in ipairs(t) do`
+    Ipairs,
+    /// `for i = 1, #t do local v = t[i]`
+    Numeric,
+}
+
+impl LoopForm {
+    fn from_target(target: LuaTarget) -> Self {
+        match target {
+            LuaTarget::Luau => Self::Ipairs,
+            LuaTarget::Lua51 | LuaTarget::Lua53 | LuaTarget::LuaJIT => Self::Numeric,
+        }
+    }
+}
+
+fn is_identifier(expression: &Expression, name: &str) -> bool {
+    matches!(expression, Expression::Identifier(identifier) if identifier.get_name() == name)
+}
+
+fn is_one(expression: &Expression) -> bool {
+    matches!(expression, Expression::Number(number) if number.compute_value() == 1.0)
+}
+
+/// Returns true if the block only reads the fields of the given array, so that
+/// looping over it with `ipairs` or with its length gives the same values.
+fn only_reads_array(block: &mut Block, array: &str) -> bool {
+    let mut usage = VariableUsage::new(array);
+    DefaultVisitor::visit_block(block, &mut usage);
+    usage.is_only_reading_fields()
+}
+
+#[derive(Default)]
+struct FindCalls {
+    has_call: bool,
+}
+
+impl NodeProcessor for FindCalls {
+    fn process_function_call(&mut self, _: &mut FunctionCall) {
+        self.has_call = true;
+    }
+}
+
+fn has_call(block: &mut Block) -> bool {
+    let mut find_calls = FindCalls::default();
+    DefaultVisitor::visit_block(block, &mut find_calls);
+    find_calls.has_call
+}
+
+/// Collects the names of the local variables used by functions declared in the
+/// scope of these variables (the upvalues of the functions).
+#[derive(Default)]
+struct CapturedVariables {
+    /// The names declared in each scope, and whether the scope is a function body.
+    scopes: Vec<(bool, HashSet<String>)>,
+    entering_function: bool,
+    captured: HashSet<String>,
+}
+
+impl CapturedVariables {
+    fn insert_name(&mut self, name: &str) {
+        if let Some((_, names)) = self.scopes.last_mut() {
+            names.insert(name.to_owned());
+        }
+    }
+}
+
+impl NodeProcessor for CapturedVariables {
+    fn process_function_expression(&mut self, _: &mut FunctionExpression) {
+        self.entering_function = true;
+    }
+
+    fn process_function_statement(&mut self, _: &mut FunctionStatement) {
+        self.entering_function = true;
+    }
+
+    fn process_local_function_statement(&mut self, _: &mut LocalFunctionStatement) {
+        self.entering_function = true;
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        let name = identifier.get_name();
+        let mut is_in_function = false;
+
+        for (is_function, names) in self.scopes.iter().rev() {
+            if names.contains(name) {
+                if is_in_function {
+                    self.captured.insert(name.to_owned());
+                }
+                return;
+            }
+            is_in_function = is_in_function || *is_function;
+        }
+    }
+}
+
+impl Scope for CapturedVariables {
+    fn push(&mut self) {
+        let is_function = mem::take(&mut self.entering_function);
+        self.scopes.push((is_function, HashSet::new()));
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.insert_name(identifier);
+    }
+
+    fn insert_self(&mut self) {
+        self.insert_name("self");
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
+        self.insert_name(identifier);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.insert_name(function.get_name());
+    }
+}
+
+struct Processor {
+    form: LoopForm,
+    captured: HashSet<String>,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl ops::Deref for Processor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl Processor {
+    fn new(form: LoopForm, captured: HashSet<String>) -> Self {
+        Self {
+            form,
+            captured,
+            identifier_tracker: Default::default(),
+        }
+    }
+
+    /// Returns true if the loop body reads the same values in both forms: the
+    /// numeric form reads the length of the array once, while `ipairs` reads the
+    /// array until it finds a `nil` value. The body must not change the array, which
+    /// a function call could do unless the array is a local variable that no
+    /// function captures.
+    fn is_array_unchanged(&self, block: &mut Block, array: &str) -> bool {
+        if !only_reads_array(block, array) {
+            return false;
+        }
+
+        let is_uncaptured_local = self.is_identifier_used(array) && !self.captured.contains(array);
+
+        is_uncaptured_local || !has_call(block)
+    }
+
+    /// Returns the name of the array and of the value variable of a loop like
+    /// `for i = 1, #t do local v = t[i] ... end`.
+    fn match_numeric_loop(numeric_for: &NumericForStatement) -> Option<(String, String)> {
+        if !is_one(numeric_for.get_start()) || !numeric_for.get_step().is_none_or(is_one) {
+            return None;
+        }
+
+        let Expression::Unary(length) = numeric_for.get_end() else {
+            return None;
+        };
+
+        let Expression::Identifier(array) = length.get_expression() else {
+            return None;
+        };
+
+        if length.operator() != UnaryOperator::Length {
+            return None;
+        }
+
+        let index = numeric_for.get_identifier().get_name();
+        let array = array.get_name();
+
+        let Some(Statement::LocalAssign(assign)) = numeric_for.get_block().first_statement() else {
+            return None;
+        };
+
+        if assign.variables_len() != 1 || assign.values_len() != 1 {
+            return None;
+        }
+
+        let Some(Expression::Index(value)) = assign.last_value() else {
+            return None;
+        };
+
+        let is_array_prefix = matches!(
+            value.get_prefix(),
+            Prefix::Identifier(identifier) if identifier.get_name() == array
+        );
+
+        if !is_array_prefix || !is_identifier(value.get_index(), index) {
+            return None;
+        }
+
+        let variable = assign.get_variables().first()?.get_name();
+
+        if index == array || variable == array {
+            return None;
+        }
+
+        Some((array.to_owned(), variable.to_owned()))
+    }
+
+    fn convert_to_ipairs(&self, numeric_for: &mut NumericForStatement) -> Option<Statement> {
+        if self.is_identifier_used(IPAIRS_IDENTIFIER) {
+            return None;
+        }
+
+        let (array, _) = Self::match_numeric_loop(numeric_for)?;
+
+        let mut block = numeric_for.get_block().clone();
+        let Some(Statement::LocalAssign(assign)) = block.first_statement() else {
+            return None;
+        };
+        let variable = assign.get_variables().first()?.clone();
+        block.remove_statement(0);
+
+        if !self.is_array_unchanged(&mut block, &array) {
+            return None;
+        }
+
+        let index = numeric_for.get_identifier().clone();
+
+        Some(
+            GenericForStatement::new(
+                vec![index, variable],
+                vec![FunctionCall::from_name(IPAIRS_IDENTIFIER)
+                    .with_argument(Expression::identifier(array))
+                    .into()],
+                block,
+            )
+            .into(),
+        )
+    }
+
+    /// Returns the name of the array of a loop like `for i, v in ipairs(t) do`.
+    fn match_ipairs_loop(&self, generic_for: &GenericForStatement) -> Option<String> {
+        if generic_for.expressions_len() != 1
+            || !(1..=2).contains(&generic_for.identifiers_len())
+            || self.is_identifier_used(IPAIRS_IDENTIFIER)
+        {
+            return None;
+        }
+
+        let Some(Expression::Call(call)) = generic_for.iter_expressions().next() else {
+            return None;
+        };
+
+        if call.has_method()
+            || !matches!(
+                call.get_prefix(),
+                Prefix::Identifier(identifier) if identifier.get_name() == IPAIRS_IDENTIFIER
+            )
+        {
+            return None;
+        }
+
+        let Arguments::Tuple(arguments) = call.get_arguments() else {
+            return None;
+        };
+
+        if arguments.len() != 1 {
+            return None;
+        }
+
+        let Some(Expression::Identifier(array)) = arguments.iter_values().next() else {
+            return None;
+        };
+
+        let array = array.get_name();
+
+        if generic_for
+            .iter_identifiers()
+            .any(|identifier| identifier.get_name() == array)
+        {
+            return None;
+        }
+
+        Some(array.to_owned())
+    }
+
+    fn convert_to_numeric_for(&self, generic_for: &mut GenericForStatement) -> Option<Statement> {
+        let array = self.match_ipairs_loop(generic_for)?;
+
+        if !self.is_array_unchanged(generic_for.mutate_block(), &array) {
+            return None;
+        }
+
+        let index = generic_for.iter_identifiers().next()?.clone();
+        let variable = generic_for.iter_identifiers().nth(1).cloned();
+
+        let mut block = mem::take(generic_for.mutate_block());
+
+        if let Some(variable) = variable {
+            block.insert_statement(
+                0,
+                LocalAssignStatement::from_variable(variable).with_value(IndexExpression::new(
+                    Prefix::from_name(&array),
+                    Expression::identifier(index.get_name()),
+                )),
+            );
+        }
+
+        Some(
+            NumericForStatement::new(
+                index,
+                DecimalNumber::new(1.0),
+                UnaryExpression::new(UnaryOperator::Length, Expression::identifier(array)),
+                None,
+                block,
+            )
+            .into(),
+        )
+    }
+}
+
+impl NodeProcessor for Processor {
+    fn process_statement(&mut self, statement: &mut Statement) {
+        let replacement = match (self.form, &mut *statement) {
+            (LoopForm::Ipairs, Statement::NumericFor(numeric_for)) => {
+                self.convert_to_ipairs(numeric_for)
+            }
+            (LoopForm::Numeric, Statement::GenericFor(generic_for)) => {
+                self.convert_to_numeric_for(generic_for)
+            }
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *statement = replacement;
+        }
+    }
+}
+
+pub const CONVERT_NUMERIC_FOR_TO_OPTIMIZED_FORM_RULE_NAME: &str =
+    "convert_numeric_for_to_optimized_form";
+
+/// A rule that converts loops over arrays between the numeric form
+/// (`for i = 1, #t do local v = t[i]`) and the `ipairs` form (`for i, v in ipairs(t) do`).
+///
+/// Both forms only give the same values for arrays without holes: the numeric form
+/// stops at the length of the array, while `ipairs` stops at the first `nil` value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ConvertNumericForToOptimizedForm {
+    form: Option<LoopForm>,
+}
+
+impl ConvertNumericForToOptimizedForm {
+    /// Converts numeric loops over arrays into loops using `ipairs`.
+    pub fn convert_to_ipairs(mut self) -> Self {
+        self.form = Some(LoopForm::Ipairs);
+        self
+    }
+
+    /// Converts loops using `ipairs` into numeric loops.
+    pub fn convert_to_numeric_for(mut self) -> Self {
+        self.form = Some(LoopForm::Numeric);
+        self
+    }
+}
+
+impl FlawlessRule for ConvertNumericForToOptimizedForm {
+    fn flawless_process(&self, block: &mut Block, context: &Context) {
+        let form = self
+            .form
+            .unwrap_or_else(|| LoopForm::from_target(context.target()));
+
+        let mut captured = CapturedVariables::default();
+        ScopeVisitor::visit_block(block, &mut captured);
+
+        let mut processor = Processor::new(form, captured.captured);
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for ConvertNumericForToOptimizedForm {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "direction" => {
+                    self.form = match value.expect_string(&key)?.as_str() {
+                        "ipairs" => Some(LoopForm::Ipairs),
+                        "numeric" => Some(LoopForm::Numeric),
+                        unexpected => {
+                            return Err(RuleConfigurationError::UnexpectedValue {
+                                property: "direction".to_owned(),
+                                message: format!(
+                                    "invalid value `{}` (must be `ipairs` or `numeric`)",
+                                    unexpected
+                                ),
+                            })
+                        }
+                    };
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        CONVERT_NUMERIC_FOR_TO_OPTIMIZED_FORM_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        match self.form {
+            Some(LoopForm::Ipairs) => {
+                properties.insert("direction".to_owned(), "ipairs".into());
+            }
+            Some(LoopForm::Numeric) => {
+                properties.insert("direction".to_owned(), "numeric".into());
+            }
+            None => {}
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> ConvertNumericForToOptimizedForm {
+        ConvertNumericForToOptimizedForm::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "convert_numeric_for_to_optimized_form"
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_ipairs_direction() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().convert_to_ipairs());
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "convert_numeric_for_to_optimized_form",
+          "direction": "ipairs"
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_direction_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_numeric_for_to_optimized_form',
+            direction: 'pairs',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'direction': invalid value `pairs` (must be `ipairs` or `numeric`) at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_numeric_for_to_optimized_form',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+use std::ops::{Deref, DerefMut};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::nodes::{Block, Expression, FunctionCall, IndexExpression, Prefix, StringExpression};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::require::is_require_call;
+use crate::rules::{
+    Context, Rule, RuleConfiguration, RuleConfigurationError, RuleProcessResult, RuleProperties,
+    RulePropertyValue,
+};
+use crate::utils;
+
+use super::RequireMode;
+
+const DEFAULT_MAP_NAME: &str = "__MODULES";
+
+/// Returns the identifier of a module: its path relative to the project location,
+/// without its extension and using `/` as separator. A module folder (like
+/// `lib/init.lua`) gets the path of the folder.
+fn get_module_id(path: &Path, mode: &RequireMode, context: &Context) -> String {
+    let project_location = utils::normalize_path(context.project_location());
+    let path = utils::normalize_path(path);
+    let relative_path = path.strip_prefix(&project_location).unwrap_or(&path);
+
+    let module_path = if mode.is_module_folder_name(relative_path) {
+        relative_path.parent().unwrap_or(relative_path)
+    } else {
+        relative_path
+    };
+
+    let module_path = module_path.with_extension("");
+
+    module_path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            Component::ParentDir => Some("..".to_owned()),
+            Component::CurDir | Component::RootDir | Component::Prefix(_) => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+struct RequireToImportMap<'a, 'b> {
+    identifier_tracker: IdentifierTracker,
+    mode: &'a RequireMode,
+    map_name: &'a str,
+    context: &'a Context<'b, 'b, 'b>,
+    modules: BTreeMap<String, PathBuf>,
+}
+
+impl Deref for RequireToImportMap<'_, '_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl DerefMut for RequireToImportMap<'_, '_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl<'a, 'b> RequireToImportMap<'a, 'b> {
+    fn new(mode: &'a RequireMode, map_name: &'a str, context: &'a Context<'b, 'b, 'b>) -> Self {
+        Self {
+            identifier_tracker: IdentifierTracker::new(),
+            mode,
+            map_name,
+            context,
+            modules: BTreeMap::new(),
+        }
+    }
+
+    fn convert_require(&mut self, call: &FunctionCall) -> Option<IndexExpression> {
+        if !is_require_call(call, self) {
+            return None;
+        }
+
+        let require_path = match self.mode.find_require(call, self.context) {
+            Ok(Some(require_path)) => require_path,
+            Ok(None) => return None,
+            Err(err) => {
+                log::warn!("{}", err);
+                return None;
+            }
+        };
+
+        let module_id = get_module_id(&require_path, self.mode, self.context);
+        log::trace!(
+            "convert require to `{}` into import map entry `{}`",
+            require_path.display(),
+            module_id
+        );
+
+        let index = IndexExpression::new(
+            Prefix::from_name(self.map_name),
+            StringExpression::from_value(module_id.as_str()),
+        );
+
+        self.modules
+            .insert(module_id, utils::normalize_path(require_path));
+
+        Some(index)
+    }
+}
+
+impl NodeProcessor for RequireToImportMap<'_, '_> {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::Call(call) = expression {
+            if let Some(index) = self.convert_require(call) {
+                *expression = index.into();
+            }
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        if let Prefix::Call(call) = prefix {
+            if let Some(index) = self.convert_require(call) {
+                *prefix = index.into();
+            }
+        }
+    }
+}
+
+pub const CONVERT_REQUIRE_TO_IMPORT_MAP_RULE_NAME: &str = "convert_require_to_import_map";
+
+/// A rule that replaces require calls with an index into a table of preloaded
+/// modules (an import map), and optionally writes the modules of that table to a
+/// JSON file.
+#[derive(Debug)]
+pub struct ConvertRequireToImportMap {
+    current: RequireMode,
+    map_name: String,
+    map_file: Option<PathBuf>,
+    modules: Mutex<BTreeMap<String, PathBuf>>,
+}
+
+impl Default for ConvertRequireToImportMap {
+    fn default() -> Self {
+        Self {
+            current: RequireMode::Path(Default::default()),
+            map_name: DEFAULT_MAP_NAME.to_owned(),
+            map_file: None,
+            modules: Default::default(),
+        }
+    }
+}
+
+impl ConvertRequireToImportMap {
+    fn write_map_file(
+        &self,
+        map_file: &Path,
+        new_modules: BTreeMap<String, PathBuf>,
+        context: &Context,
+    ) -> RuleProcessResult {
+        let project_location = utils::normalize_path(context.project_location());
+        let mut modules = self.modules.lock().unwrap();
+        modules.extend(new_modules);
+
+        let content: BTreeMap<&str, String> = modules
+            .iter()
+            .map(|(module_id, path)| {
+                let relative_path = path.strip_prefix(&project_location).unwrap_or(path);
+                (
+                    module_id.as_str(),
+                    relative_path.to_string_lossy().replace('\\', "/"),
+                )
+            })
+            .collect();
+
+        let content = serde_json::to_string_pretty(&content).map_err(|err| err.to_string())?;
+
+        let map_path = context.project_location().join(map_file);
+        context
+            .resources()
+            .write(&map_path, &content)
+            .map_err(|err| format!("unable to write import map: {}", err))
+    }
+}
+
+impl Rule for ConvertRequireToImportMap {
+    fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+        let mut current_mode = self.current.clone();
+        current_mode
+            .initialize(context)
+            .map_err(|err| err.to_string())?;
+
+        let mut processor = RequireToImportMap::new(&current_mode, &self.map_name, context);
+        ScopeVisitor::visit_block(block, &mut processor);
+
+        if let Some(map_file) = &self.map_file {
+            self.write_map_file(map_file, processor.modules, context)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl RuleConfiguration for ConvertRequireToImportMap {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "current" => {
+                    self.current = value.expect_require_mode(&key)?;
+                }
+                "map_name" => {
+                    let map_name = value.expect_string(&key)?;
+                    if !crate::process::utils::is_valid_identifier(&map_name) {
+                        return Err(RuleConfigurationError::UnexpectedValue {
+                            property: key,
+                            message: format!("invalid identifier `{}`", map_name),
+                        });
+                    }
+                    self.map_name = map_name;
+                }
+                "map_file" => {
+                    self.map_file = Some(PathBuf::from(value.expect_string(&key)?));
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        CONVERT_REQUIRE_TO_IMPORT_MAP_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if self.current != RequireMode::Path(Default::default()) {
+            properties.insert(
+                "current".to_owned(),
+                RulePropertyValue::RequireMode(self.current.clone()),
+            );
+        }
+
+        if self.map_name != DEFAULT_MAP_NAME {
+            properties.insert(
+                "map_name".to_owned(),
+                RulePropertyValue::String(self.map_name.clone()),
+            );
+        }
+
+        if let Some(map_file) = &self.map_file {
+            properties.insert(
+                "map_file".to_owned(),
+                RulePropertyValue::String(map_file.to_string_lossy().into_owned()),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> ConvertRequireToImportMap {
+        ConvertRequireToImportMap::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""convert_require_to_import_map""###);
+    }
+
+    #[test]
+    fn serialize_rule_with_map_file() {
+        let rule: Box<dyn Rule> = Box::new(ConvertRequireToImportMap {
+            map_name: "MODULES".to_owned(),
+            map_file: Some(PathBuf::from("modules.json")),
+            ..new_rule()
+        });
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "convert_require_to_import_map",
+          "map_file": "modules.json",
+          "map_name": "MODULES"
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_map_name_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_require_to_import_map',
+            map_name: 'not valid',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'map_name': invalid identifier `not valid` at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'convert_require_to_import_map',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -1,7 +1,12 @@
-use crate::nodes::{FieldExpression, FunctionCall, Identifier, Prefix, StringExpression};
+use crate::nodes::{
+    Arguments, Expression, FieldExpression, FunctionCall, Identifier, IndexExpression, Prefix,
+    StringExpression,
+};
 
 use super::RobloxIndexStyle;
 
+use std::fmt;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) struct InstancePath {
     root: InstancePathRoot,
@@ -32,6 +37,91 @@ impl InstancePath {
             .push(InstancePathComponent::Child(child_name.into()));
     }
 
+    /// Reads an instance path from an expression like `script.Parent:FindFirstChild('value')`
+    /// or `game:GetService('ReplicatedStorage').Project`.
+    pub(crate) fn from_expression(expression: &Expression) -> Option<Self> {
+        match expression {
+            Expression::Call(call) => Self::from_call(call),
+            Expression::Field(field) => Self::from_field(field),
+            Expression::Identifier(identifier) => Self::from_identifier(identifier),
+            Expression::Index(index) => Self::from_index(index),
+            Expression::Parenthese(parenthese) => {
+                Self::from_expression(parenthese.inner_expression())
+            }
+            _ => None,
+        }
+    }
+
+    fn from_prefix(prefix: &Prefix) -> Option<Self> {
+        match prefix {
+            Prefix::Call(call) => Self::from_call(call),
+            Prefix::Field(field) => Self::from_field(field),
+            Prefix::Identifier(identifier) => Self::from_identifier(identifier),
+            Prefix::Index(index) => Self::from_index(index),
+            Prefix::Parenthese(parenthese) => Self::from_expression(parenthese.inner_expression()),
+        }
+    }
+
+    fn from_identifier(identifier: &Identifier) -> Option<Self> {
+        match identifier.get_name().as_str() {
+            "script" => Some(Self::from_script()),
+            "game" => Some(Self::from_root()),
+            _ => None,
+        }
+    }
+
+    fn from_field(field: &FieldExpression) -> Option<Self> {
+        let mut path = Self::from_prefix(field.get_prefix())?;
+        match field.get_field().get_name().as_str() {
+            "Parent" => path.parent(),
+            child_name => path.child(child_name),
+        }
+        Some(path)
+    }
+
+    fn from_index(index: &IndexExpression) -> Option<Self> {
+        let mut path = Self::from_prefix(index.get_prefix())?;
+        match index.get_index() {
+            Expression::String(string) => path.child(string.get_string_value()?),
+            _ => return None,
+        }
+        Some(path)
+    }
+
+    fn from_call(call: &FunctionCall) -> Option<Self> {
+        let mut path = Self::from_prefix(call.get_prefix())?;
+
+        let child_name = match call.get_arguments() {
+            Arguments::String(string) => string.get_string_value()?,
+            Arguments::Tuple(tuple) if tuple.len() == 1 => match tuple.iter_values().next()? {
+                Expression::String(string) => string.get_string_value()?,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        match call.get_method()?.get_name().as_str() {
+            "FindFirstChild" | "WaitForChild" => {}
+            "GetService" if path.is_root() => {}
+            _ => return None,
+        }
+
+        path.child(child_name);
+        Some(path)
+    }
+
+    fn is_root(&self) -> bool {
+        self.root == InstancePathRoot::Root && self.components.is_empty()
+    }
+
+    pub(crate) fn root(&self) -> &InstancePathRoot {
+        &self.root
+    }
+
+    pub(crate) fn components(&self) -> &[InstancePathComponent] {
+        &self.components
+    }
+
     pub(crate) fn convert(&self, index_style: &RobloxIndexStyle) -> Prefix {
         let mut components_iter = self.components.iter();
 
@@ -64,6 +154,22 @@ impl InstancePath {
     }
 }
 
+impl fmt::Display for InstancePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.root {
+            InstancePathRoot::Root => write!(f, "game")?,
+            InstancePathRoot::Script => write!(f, "script")?,
+        }
+        for component in &self.components {
+            match component {
+                InstancePathComponent::Parent => write!(f, ".Parent")?,
+                InstancePathComponent::Child(child_name) => write!(f, ".{}", child_name)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub(crate) enum InstancePathRoot {
     Root,
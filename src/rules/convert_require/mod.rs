@@ -1,3 +1,4 @@
+mod import_map;
 mod instance_path;
 mod roblox_index_style;
 mod roblox_require_mode;
@@ -11,6 +12,7 @@ use crate::process::{DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisit
 use crate::rules::require::is_require_call;
 use crate::rules::{Context, RuleConfiguration, RuleConfigurationError, RuleProperties};
 
+pub use import_map::*;
 use instance_path::InstancePath;
 pub use roblox_index_style::RobloxIndexStyle;
 pub use roblox_require_mode::RobloxRequireMode;
@@ -73,7 +75,7 @@ impl RequireMode {
         }
     }
 
-    fn initialize(&mut self, context: &Context) -> DarkluaResult<()> {
+    pub(crate) fn initialize(&mut self, context: &Context) -> DarkluaResult<()> {
         match self {
             RequireMode::Roblox(roblox_mode) => roblox_mode.initialize(context),
             RequireMode::Path(path_mode) => path_mode.initialize(context),
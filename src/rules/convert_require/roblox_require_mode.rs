@@ -11,13 +11,92 @@ use crate::{
     utils, DarkluaError,
 };
 
+use std::ffi::OsStr;
 use std::path::{Component, Path, PathBuf};
 
 use super::{
-    instance_path::{get_parent_instance, script_identifier},
+    instance_path::{
+        get_parent_instance, script_identifier, InstancePath, InstancePathComponent,
+        InstancePathRoot,
+    },
     RequireMode, RobloxIndexStyle,
 };
 
+fn match_roblox_require_call(call: &FunctionCall) -> Option<InstancePath> {
+    match call.get_arguments() {
+        Arguments::Tuple(tuple) if tuple.len() == 1 => {
+            InstancePath::from_expression(tuple.iter_values().next()?)
+        }
+        _ => None,
+    }
+}
+
+/// Finds the file of an instance path starting from `script`, assuming that
+/// the instances are organized like the files (as Rojo does by default).
+fn find_relative_require(
+    instance_path: &InstancePath,
+    source_path: &Path,
+    context: &Context,
+) -> DarkluaResult<PathBuf> {
+    if *instance_path.root() == InstancePathRoot::Root {
+        return Err(DarkluaError::custom(format!(
+            "unable to convert `{}` from `{}`: a Rojo sourcemap is required to convert requires starting from `game`",
+            instance_path,
+            source_path.display(),
+        )));
+    }
+
+    let is_module_folder = matches!(
+        source_path.file_stem().and_then(OsStr::to_str),
+        Some("init")
+    );
+
+    // the `script` instance of a module folder is the folder itself
+    let mut location = if is_module_folder {
+        get_relative_parent_path(source_path).to_path_buf()
+    } else {
+        source_path.to_path_buf()
+    };
+
+    for component in instance_path.components() {
+        match component {
+            InstancePathComponent::Parent => location.push(".."),
+            InstancePathComponent::Child(child_name) => location.push(child_name),
+        }
+    }
+
+    let location = utils::normalize_path(location);
+
+    if location == source_path {
+        return Ok(location);
+    }
+
+    let resources = context.resources();
+
+    let file_candidates = ["lua", "luau"].iter().map(|extension| {
+        let mut file_path = location.clone().into_os_string();
+        file_path.push(".");
+        file_path.push(extension);
+        PathBuf::from(file_path)
+    });
+    let folder_candidates = ["init.lua", "init.luau"]
+        .iter()
+        .map(|file_name| location.join(file_name));
+
+    for candidate in file_candidates.chain(folder_candidates) {
+        if resources.is_file(&candidate)? {
+            return Ok(candidate);
+        }
+    }
+
+    Err(DarkluaError::custom(format!(
+        "unable to find a file for `{}` from `{}` (expected a file at `{}` with a `.lua` or `.luau` extension, or a folder with an `init` file)",
+        instance_path,
+        source_path.display(),
+        location.display(),
+    )))
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(deny_unknown_fields, rename_all = "snake_case")]
 pub struct RobloxRequireMode {
@@ -60,11 +139,35 @@ impl RobloxRequireMode {
 
     pub(crate) fn find_require(
         &self,
-        _call: &FunctionCall,
-        _context: &Context,
+        call: &FunctionCall,
+        context: &Context,
     ) -> DarkluaResult<Option<PathBuf>> {
-        Err(DarkluaError::custom("unsupported initial require mode")
-            .context("Roblox require mode cannot be used as the current require mode"))
+        let instance_path = match match_roblox_require_call(call) {
+            Some(instance_path) => instance_path,
+            None => return Ok(None),
+        };
+
+        let source_path = utils::normalize_path(context.current_path());
+        log::trace!(
+            "find file for Roblox require `{}` from `{}`",
+            instance_path,
+            source_path.display(),
+        );
+
+        if let Some(sourcemap) = &self.cached_sourcemap {
+            sourcemap
+                .get_file_path(&source_path, &instance_path)
+                .map(|path| Some(path.to_path_buf()))
+                .ok_or_else(|| {
+                    DarkluaError::custom(format!(
+                        "unable to find a file for `{}` in sourcemap (from `{}`)",
+                        instance_path,
+                        source_path.display(),
+                    ))
+                })
+        } else {
+            find_relative_require(&instance_path, &source_path, context).map(Some)
+        }
     }
 
     pub(crate) fn generate_require(
@@ -1,9 +1,11 @@
+use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{utils, DarkluaError};
 
+use super::instance_path::{InstancePathComponent, InstancePathRoot};
 use super::InstancePath;
 
 type NodeId = usize;
@@ -185,6 +187,45 @@ impl RojoSourcemap {
         }
     }
 
+    /// Returns the Lua file of the instance found by following the given instance
+    /// path from the instance of `from_file`.
+    pub(crate) fn get_file_path(
+        &self,
+        from_file: impl AsRef<Path>,
+        instance_path: &InstancePath,
+    ) -> Option<&Path> {
+        let mut node = match instance_path.root() {
+            InstancePathRoot::Script => self.find_node(from_file.as_ref())?,
+            InstancePathRoot::Root if self.is_datamodel => &self.root_node,
+            InstancePathRoot::Root => return None,
+        };
+
+        for component in instance_path.components() {
+            node = match component {
+                InstancePathComponent::Parent => {
+                    if node.is_root() {
+                        return None;
+                    }
+                    self.root_node.get_descendant(node.parent_id())?
+                }
+                InstancePathComponent::Child(child_name) => node
+                    .children
+                    .iter()
+                    .find(|child| &child.name == child_name)?,
+            };
+        }
+
+        node.file_paths
+            .iter()
+            .find(|file_path| {
+                matches!(
+                    file_path.extension().and_then(OsStr::to_str),
+                    Some("lua") | Some("luau")
+                )
+            })
+            .map(PathBuf::as_path)
+    }
+
     fn index_descendants<'a>(
         &self,
         mut instance_path: InstancePath,
@@ -396,4 +437,82 @@ mod test {
             );
         }
     }
+
+    mod file_paths {
+        use super::*;
+
+        const SOURCEMAP: &str = r#"{
+            "name": "Project",
+            "className": "ModuleScript",
+            "filePaths": ["src/init.lua", "default.project.json"],
+            "children": [
+                {
+                    "name": "main",
+                    "className": "ModuleScript",
+                    "filePaths": ["src/main.lua"]
+                },
+                {
+                    "name": "Lib",
+                    "className": "Folder",
+                    "children": [
+                        {
+                            "name": "format",
+                            "className": "ModuleScript",
+                            "filePaths": ["src/Lib/format.luau"]
+                        }
+                    ]
+                }
+            ]
+        }"#;
+
+        #[test]
+        fn from_sibling_to_nested_sibling_module() {
+            let sourcemap = new_sourcemap(SOURCEMAP);
+            let mut instance_path = InstancePath::from_script();
+            instance_path.parent();
+            instance_path.child("Lib");
+            instance_path.child("format");
+
+            pretty_assertions::assert_eq!(
+                sourcemap.get_file_path("src/main.lua", &instance_path),
+                Some(Path::new("src/Lib/format.luau"))
+            );
+        }
+
+        #[test]
+        fn from_child_to_parent_skips_project_file() {
+            let sourcemap = new_sourcemap(SOURCEMAP);
+            let mut instance_path = InstancePath::from_script();
+            instance_path.parent();
+
+            pretty_assertions::assert_eq!(
+                sourcemap.get_file_path("src/main.lua", &instance_path),
+                Some(Path::new("src/init.lua"))
+            );
+        }
+
+        #[test]
+        fn parent_of_root_is_not_found() {
+            let sourcemap = new_sourcemap(SOURCEMAP);
+            let mut instance_path = InstancePath::from_script();
+            instance_path.parent();
+
+            pretty_assertions::assert_eq!(
+                sourcemap.get_file_path("src/init.lua", &instance_path),
+                None
+            );
+        }
+
+        #[test]
+        fn folder_without_file_is_not_found() {
+            let sourcemap = new_sourcemap(SOURCEMAP);
+            let mut instance_path = InstancePath::from_script();
+            instance_path.child("Lib");
+
+            pretty_assertions::assert_eq!(
+                sourcemap.get_file_path("src/init.lua", &instance_path),
+                None
+            );
+        }
+    }
 }
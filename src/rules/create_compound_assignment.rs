@@ -0,0 +1,165 @@
+use crate::nodes::{
+    Block, CompoundAssignStatement, CompoundAssignTokens, CompoundOperator, Expression, Prefix,
+    Statement, Variable,
+};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+/// Compares two prefixes made of identifiers and field or index accesses with
+/// simple keys, ignoring their tokens.
+fn is_same_prefix(a: &Prefix, b: &Prefix) -> bool {
+    match (a, b) {
+        (Prefix::Identifier(a), Prefix::Identifier(b)) => a.get_name() == b.get_name(),
+        (Prefix::Field(a), Prefix::Field(b)) => {
+            a.get_field().get_name() == b.get_field().get_name()
+                && is_same_prefix(a.get_prefix(), b.get_prefix())
+        }
+        (Prefix::Index(a), Prefix::Index(b)) => {
+            is_same_key(a.get_index(), b.get_index())
+                && is_same_prefix(a.get_prefix(), b.get_prefix())
+        }
+        _ => false,
+    }
+}
+
+fn is_same_key(a: &Expression, b: &Expression) -> bool {
+    match (a, b) {
+        (Expression::Identifier(a), Expression::Identifier(b)) => a.get_name() == b.get_name(),
+        (Expression::String(a), Expression::String(b)) => a.get_value() == b.get_value(),
+        (Expression::Number(a), Expression::Number(b)) => {
+            a.compute_value().to_bits() == b.compute_value().to_bits()
+        }
+        (Expression::True(_), Expression::True(_))
+        | (Expression::False(_), Expression::False(_)) => true,
+        _ => false,
+    }
+}
+
+fn is_same_variable(variable: &Variable, expression: &Expression) -> bool {
+    match (variable, expression) {
+        (Variable::Identifier(a), Expression::Identifier(b)) => a.get_name() == b.get_name(),
+        (Variable::Field(a), Expression::Field(b)) => {
+            a.get_field().get_name() == b.get_field().get_name()
+                && is_same_prefix(a.get_prefix(), b.get_prefix())
+        }
+        (Variable::Index(a), Expression::Index(b)) => {
+            is_same_key(a.get_index(), b.get_index())
+                && is_same_prefix(a.get_prefix(), b.get_prefix())
+        }
+        _ => false,
+    }
+}
+
+#[derive(Default)]
+struct Processor {}
+
+impl Processor {
+    fn convert(&self, statement: &Statement) -> Option<CompoundAssignStatement> {
+        let Statement::Assign(assign) = statement else {
+            return None;
+        };
+
+        if assign.variables_len() != 1 || assign.values_len() != 1 {
+            return None;
+        }
+
+        let (Some(variable), Some(Expression::Binary(binary))) =
+            (assign.get_variables().first(), assign.last_value())
+        else {
+            return None;
+        };
+
+        let operator = CompoundOperator::from_binary_operator(binary.operator())?;
+
+        if !is_same_variable(variable, binary.left()) {
+            return None;
+        }
+
+        let mut compound =
+            CompoundAssignStatement::new(operator, variable.clone(), binary.right().clone());
+
+        if let Some(tokens) = assign.get_tokens() {
+            let mut operator_token = tokens.equal.clone();
+            operator_token.replace_with_content(operator.to_str());
+            compound.set_tokens(CompoundAssignTokens {
+                operator: operator_token,
+            });
+        }
+
+        Some(compound)
+    }
+}
+
+impl NodeProcessor for Processor {
+    fn process_statement(&mut self, statement: &mut Statement) {
+        if let Some(compound) = self.convert(statement) {
+            *statement = compound.into();
+        }
+    }
+}
+
+pub const CREATE_COMPOUND_ASSIGNMENT_RULE_NAME: &str = "create_compound_assignment";
+
+/// A rule that converts assignments like `a = a + 1` into compound assignments (like
+/// `a += 1`).
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CreateCompoundAssignment {}
+
+impl FlawlessRule for CreateCompoundAssignment {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor::default();
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for CreateCompoundAssignment {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        CREATE_COMPOUND_ASSIGNMENT_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> CreateCompoundAssignment {
+        CreateCompoundAssignment::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "create_compound_assignment"
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'create_compound_assignment',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
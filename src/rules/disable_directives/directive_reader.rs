@@ -0,0 +1,267 @@
+use std::cell::RefCell;
+
+use crate::nodes::*;
+use crate::process::NodeProcessor;
+
+use super::Directive;
+
+/// Reads the directives from all the comments of a block.
+#[derive(Debug)]
+pub(super) struct DirectiveReader<'a> {
+    code: &'a str,
+    directives: RefCell<Vec<Directive>>,
+}
+
+impl<'a> DirectiveReader<'a> {
+    pub(super) fn new(code: &'a str) -> Self {
+        Self {
+            code,
+            directives: Default::default(),
+        }
+    }
+
+    pub(super) fn into_directives(self) -> Vec<Directive> {
+        let mut directives = self.directives.into_inner();
+        directives.sort_by_key(|directive| directive.line);
+        directives
+    }
+
+    // comments are read with `filter_comments` (keeping every comment), which visits
+    // all the comments of a node
+    fn read_trivia(&self, trivia: &Trivia) -> bool {
+        if let Some(line) = trivia.get_line_number() {
+            if let Some(directive) = Directive::parse(trivia.read(self.code), line) {
+                self.directives.borrow_mut().push(directive);
+            }
+        }
+        true
+    }
+}
+
+impl NodeProcessor for DirectiveReader<'_> {
+    fn process_block(&mut self, block: &mut Block) {
+        block.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        call.filter_comments(|trivia| self.read_trivia(trivia));
+        call.mutate_arguments()
+            .filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_assign_statement(&mut self, assign: &mut AssignStatement) {
+        assign.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_compound_assign_statement(&mut self, assign: &mut CompoundAssignStatement) {
+        assign.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_do_statement(&mut self, statement: &mut DoStatement) {
+        statement.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        function.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_generic_for_statement(&mut self, generic_for: &mut GenericForStatement) {
+        generic_for.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_if_statement(&mut self, if_statement: &mut IfStatement) {
+        if_statement.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_last_statement(&mut self, statement: &mut LastStatement) {
+        match statement {
+            LastStatement::Break(token) | LastStatement::Continue(token) => {
+                if let Some(token) = token {
+                    token.filter_comments(|trivia| self.read_trivia(trivia));
+                }
+            }
+            LastStatement::Return(statement) => {
+                statement.filter_comments(|trivia| self.read_trivia(trivia))
+            }
+        }
+    }
+
+    fn process_local_assign_statement(&mut self, assign: &mut LocalAssignStatement) {
+        assign.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_local_function_statement(&mut self, function: &mut LocalFunctionStatement) {
+        function.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_numeric_for_statement(&mut self, numeric_for: &mut NumericForStatement) {
+        numeric_for.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_repeat_statement(&mut self, repeat: &mut RepeatStatement) {
+        repeat.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_while_statement(&mut self, statement: &mut WhileStatement) {
+        statement.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_type_declaration(&mut self, type_declaration: &mut TypeDeclarationStatement) {
+        type_declaration.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_type_function(&mut self, type_function: &mut TypeFunctionStatement) {
+        type_function.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::False(token)
+            | Expression::Nil(token)
+            | Expression::True(token)
+            | Expression::VariableArguments(token) => {
+                if let Some(token) = token {
+                    token.filter_comments(|trivia| self.read_trivia(trivia))
+                }
+            }
+            Expression::Binary(_)
+            | Expression::Call(_)
+            | Expression::Field(_)
+            | Expression::Function(_)
+            | Expression::Identifier(_)
+            | Expression::If(_)
+            | Expression::Index(_)
+            | Expression::Number(_)
+            | Expression::Parenthese(_)
+            | Expression::String(_)
+            | Expression::InterpolatedString(_)
+            | Expression::Table(_)
+            | Expression::Unary(_)
+            | Expression::TypeCast(_) => {}
+        }
+    }
+
+    fn process_binary_expression(&mut self, binary: &mut BinaryExpression) {
+        binary.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_field_expression(&mut self, field: &mut FieldExpression) {
+        field.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_function_expression(&mut self, function: &mut FunctionExpression) {
+        function.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_if_expression(&mut self, if_expression: &mut IfExpression) {
+        if_expression.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        identifier.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_index_expression(&mut self, index: &mut IndexExpression) {
+        index.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_number_expression(&mut self, number: &mut NumberExpression) {
+        number.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_parenthese_expression(&mut self, expression: &mut ParentheseExpression) {
+        expression.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_string_expression(&mut self, string: &mut StringExpression) {
+        string.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_table_expression(&mut self, table: &mut TableExpression) {
+        table.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_unary_expression(&mut self, unary: &mut UnaryExpression) {
+        unary.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_interpolated_string_expression(
+        &mut self,
+        string: &mut InterpolatedStringExpression,
+    ) {
+        string.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_type_cast_expression(&mut self, type_cast: &mut TypeCastExpression) {
+        type_cast.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_prefix_expression(&mut self, _: &mut Prefix) {}
+
+    fn process_type(&mut self, r#type: &mut Type) {
+        match r#type {
+            Type::True(token) | Type::False(token) | Type::Nil(token) => {
+                if let Some(token) = token {
+                    token.filter_comments(|trivia| self.read_trivia(trivia));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn process_type_name(&mut self, type_name: &mut TypeName) {
+        type_name.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_type_field(&mut self, type_field: &mut TypeField) {
+        type_field.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_string_type(&mut self, string_type: &mut StringType) {
+        string_type.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_array_type(&mut self, array: &mut ArrayType) {
+        array.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_table_type(&mut self, table: &mut TableType) {
+        table.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_expression_type(&mut self, expression_type: &mut ExpressionType) {
+        expression_type.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_parenthese_type(&mut self, parenthese_type: &mut ParentheseType) {
+        parenthese_type.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_function_type(&mut self, function_type: &mut FunctionType) {
+        function_type.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_optional_type(&mut self, optional: &mut OptionalType) {
+        optional.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_intersection_type(&mut self, intersection: &mut IntersectionType) {
+        intersection.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_union_type(&mut self, union: &mut UnionType) {
+        union.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_type_pack(&mut self, type_pack: &mut TypePack) {
+        type_pack.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_generic_type_pack(&mut self, generic_type_pack: &mut GenericTypePack) {
+        generic_type_pack.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+
+    fn process_variadic_type_pack(&mut self, variadic_type_pack: &mut VariadicTypePack) {
+        variadic_type_pack.filter_comments(|trivia| self.read_trivia(trivia));
+    }
+}
@@ -0,0 +1,619 @@
+mod directive_reader;
+mod placeholder;
+
+use std::mem;
+
+use directive_reader::DirectiveReader;
+use placeholder::{get_placeholder_id, StatementNames, PLACEHOLDER_FUNCTION};
+
+use crate::nodes::{
+    Block, Expression, FunctionCall, Identifier, LastStatement, LocalAssignStatement,
+    ReturnStatement, Statement, Token, TypedIdentifier,
+};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{get_all_rule_names, get_custom_rule_names, Context, Rule, RuleProcessResult};
+
+const DISABLE_DIRECTIVE: &str = "darklua-disable";
+const ENABLE_DIRECTIVE: &str = "darklua-enable";
+
+/// Returns true if the code may contain directives. This is used to avoid
+/// parsing code with tokens when it is not needed.
+pub(crate) fn may_contain_directives(code: &str) -> bool {
+    code.contains(DISABLE_DIRECTIVE)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RuleSelection {
+    All,
+    Rules(Vec<String>),
+}
+
+impl RuleSelection {
+    fn contains(&self, rule_name: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Rules(rules) => rules.iter().any(|name| name == rule_name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Directive {
+    line: usize,
+    disable: bool,
+    rules: RuleSelection,
+}
+
+impl Directive {
+    fn parse(comment: &str, line: usize) -> Option<Self> {
+        let content = comment.strip_prefix("--")?;
+        if content.starts_with('[') {
+            return None;
+        }
+        let content = content.trim();
+
+        let (disable, rules) = if let Some(rules) = strip_directive(content, DISABLE_DIRECTIVE) {
+            (true, rules)
+        } else if let Some(rules) = strip_directive(content, ENABLE_DIRECTIVE) {
+            (false, rules)
+        } else {
+            return None;
+        };
+
+        let rules: Vec<String> = rules
+            .split(|character: char| character == ',' || character.is_whitespace())
+            .filter(|name| !name.is_empty())
+            .map(ToOwned::to_owned)
+            .collect();
+
+        for name in rules.iter() {
+            if !is_known_rule(name) {
+                log::warn!(
+                    "unknown rule `{}` in directive `{}` (line {})",
+                    name,
+                    comment,
+                    line
+                );
+            }
+        }
+
+        Some(Self {
+            line,
+            disable,
+            rules: if rules.is_empty() {
+                RuleSelection::All
+            } else {
+                RuleSelection::Rules(rules)
+            },
+        })
+    }
+}
+
+/// Returns true if the name is used by a darklua rule or by a registered custom rule.
+fn is_known_rule(name: &str) -> bool {
+    get_all_rule_names().contains(&name)
+        || get_custom_rule_names()
+            .iter()
+            .any(|custom_name| custom_name == name)
+}
+
+fn strip_directive<'a>(content: &'a str, directive: &str) -> Option<&'a str> {
+    let rest = content.strip_prefix(directive)?;
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+/// The comment directives found in a file, used to disable rules on some of its statements:
+///
+/// - `-- darklua-disable` disables all rules until the next `-- darklua-enable`
+/// - `-- darklua-disable rule_a, rule_b` disables only the given rules
+/// - `-- darklua-enable rule_a` enables the given rule again
+///
+/// Directives are read from all the comments of a file and apply to the statements
+/// (from any block) that start after them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct RuleDirectives {
+    directives: Vec<Directive>,
+}
+
+impl RuleDirectives {
+    /// Reads the directives from the comments of a block parsed with tokens. The block
+    /// is not modified.
+    pub(crate) fn from_block(block: &mut Block, code: &str) -> Self {
+        let mut reader = DirectiveReader::new(code);
+        DefaultVisitor::visit_block(block, &mut reader);
+
+        Self {
+            directives: reader.into_directives(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.directives.is_empty()
+    }
+
+    fn is_disabled(&self, rule_name: &str, line: usize) -> bool {
+        self.directives
+            .iter()
+            .take_while(|directive| directive.line < line)
+            .filter(|directive| directive.rules.contains(rule_name))
+            .last()
+            .map(|directive| directive.disable)
+            .unwrap_or(false)
+    }
+
+    fn is_token_disabled(&self, rule_name: &str, token: &Token) -> bool {
+        // tokens from other files (like bundled modules) are not affected by the
+        // directives of the file
+        token.get_source().is_none()
+            && token
+                .get_source_line_number()
+                .is_some_and(|line| self.is_disabled(rule_name, line))
+    }
+
+    /// Applies a rule to the given block, leaving the statements located in a region
+    /// where the rule is disabled unchanged.
+    ///
+    /// While the rule runs, each disabled statement is replaced with a placeholder
+    /// that uses the same variables, so the rule does not transform the statement
+    /// but still sees how it refers to the rest of the code. If the rule renames
+    /// these variables, the statement is renamed the same way. If the rule changes the
+    /// placeholders in any other way, the rule is not applied to the file (a warning
+    /// is logged).
+    pub(crate) fn process_rule(
+        &self,
+        rule: &dyn Rule,
+        block: &mut Block,
+        context: &Context,
+    ) -> RuleProcessResult {
+        if self.is_empty() {
+            return rule.process(block, context);
+        }
+
+        let rule_name = rule.get_name();
+
+        let original_block = block.clone();
+
+        let mut replacer = ReplaceDisabledStatements {
+            directives: self,
+            rule_name,
+            disabled: Vec::new(),
+            is_file_disabled: None,
+        };
+        DefaultVisitor::visit_block(block, &mut replacer);
+
+        if replacer.disabled.is_empty() {
+            return rule.process(block, context);
+        }
+
+        if replacer.is_file_disabled == Some(true) {
+            log::trace!("rule `{}` is disabled for the whole file", rule_name);
+            *block = original_block;
+            return Ok(());
+        }
+
+        log::trace!(
+            "rule `{}` is disabled for {} statement(s)",
+            rule_name,
+            replacer.disabled.len()
+        );
+
+        let mut restorer = RestoreDisabledStatements::new(replacer.disabled);
+
+        if let Err(err) = rule.process(block, context) {
+            *block = original_block;
+            return Err(err);
+        }
+
+        DefaultVisitor::visit_block(block, &mut restorer);
+
+        if !restorer.is_complete() {
+            log::warn!(
+                "rule `{}` was not applied to `{}` because it changed statements where it is disabled",
+                rule_name,
+                context.current_path().display(),
+            );
+            *block = original_block;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum DisabledStatement {
+    Statement(Box<Statement>),
+    LastStatement(LastStatement),
+}
+
+#[derive(Debug)]
+struct Disabled {
+    statement: DisabledStatement,
+    names: StatementNames,
+    restored: bool,
+}
+
+/// Replaces the disabled statements with placeholders, from the outer blocks to the
+/// inner blocks (the statements inside a disabled statement are not visited).
+struct ReplaceDisabledStatements<'a> {
+    directives: &'a RuleDirectives,
+    rule_name: &'a str,
+    disabled: Vec<Disabled>,
+    /// If all the statements of the file are disabled (known after visiting its block).
+    is_file_disabled: Option<bool>,
+}
+
+impl NodeProcessor for ReplaceDisabledStatements<'_> {
+    fn process_block(&mut self, block: &mut Block) {
+        let is_file = self.is_file_disabled.is_none();
+        let mut all_disabled = true;
+
+        for statement in block.iter_mut_statements() {
+            if !self
+                .directives
+                .is_token_disabled(self.rule_name, statement.mutate_first_token())
+            {
+                all_disabled = false;
+                continue;
+            }
+
+            let names = StatementNames::from_statement(statement);
+            let placeholder = names.placeholder_statement(self.disabled.len());
+
+            self.disabled.push(Disabled {
+                statement: DisabledStatement::Statement(Box::new(mem::replace(
+                    statement,
+                    placeholder,
+                ))),
+                names,
+                restored: false,
+            });
+        }
+
+        let is_last_statement_disabled = match block.mutate_last_statement() {
+            Some(LastStatement::Return(statement)) => self
+                .directives
+                .is_token_disabled(self.rule_name, statement.mutate_first_token()),
+            // `break` and `continue` statements do not have any content to protect, so
+            // they are not replaced
+            Some(LastStatement::Break(token)) | Some(LastStatement::Continue(token)) => {
+                all_disabled &= token
+                    .as_ref()
+                    .is_some_and(|token| self.directives.is_token_disabled(self.rule_name, token));
+                false
+            }
+            None => false,
+        };
+
+        if let Some(LastStatement::Return(_)) = block.get_last_statement() {
+            all_disabled &= is_last_statement_disabled;
+        }
+
+        if is_file {
+            self.is_file_disabled = Some(all_disabled);
+        }
+
+        if is_last_statement_disabled {
+            if let Some(statement) = block.take_last_statement() {
+                let names = StatementNames::from_last_statement(&statement);
+                let placeholder = ReturnStatement::one(names.placeholder_call(self.disabled.len()));
+                block.set_last_statement(placeholder);
+
+                self.disabled.push(Disabled {
+                    statement: DisabledStatement::LastStatement(statement),
+                    names,
+                    restored: false,
+                });
+            }
+        }
+    }
+}
+
+/// Puts back the disabled statements in place of their placeholders, after a rule has
+/// processed the block.
+struct RestoreDisabledStatements {
+    disabled: Vec<Disabled>,
+    is_valid: bool,
+}
+
+impl RestoreDisabledStatements {
+    fn new(disabled: Vec<Disabled>) -> Self {
+        Self {
+            disabled,
+            is_valid: true,
+        }
+    }
+
+    /// Returns true if every placeholder was found once and replaced.
+    fn is_complete(&self) -> bool {
+        self.is_valid && self.disabled.iter().all(|disabled| disabled.restored)
+    }
+
+    fn restore(
+        &mut self,
+        call: &FunctionCall,
+        declared: Option<&[TypedIdentifier]>,
+    ) -> Option<DisabledStatement> {
+        let restored = get_placeholder_id(call)
+            .and_then(|id| self.disabled.get_mut(id))
+            .filter(|disabled| !disabled.restored)
+            .and_then(|disabled| {
+                let renamed = disabled.names.read_placeholder(call, declared)?;
+                let mut statement = disabled.statement.clone();
+
+                let is_renamed = match &mut statement {
+                    DisabledStatement::Statement(statement) => {
+                        disabled.names.rename_statement(statement, &renamed)
+                    }
+                    DisabledStatement::LastStatement(statement) => {
+                        disabled.names.rename_last_statement(statement, &renamed)
+                    }
+                };
+
+                disabled.restored = is_renamed;
+                is_renamed.then_some(statement)
+            });
+
+        if restored.is_none() {
+            self.is_valid = false;
+        }
+
+        restored
+    }
+
+    fn restore_statement(
+        &mut self,
+        call: &FunctionCall,
+        declared: Option<&[TypedIdentifier]>,
+    ) -> Option<Statement> {
+        match self.restore(call, declared)? {
+            DisabledStatement::Statement(statement) => Some(*statement),
+            DisabledStatement::LastStatement(_) => {
+                self.is_valid = false;
+                None
+            }
+        }
+    }
+
+    /// Restores the placeholders found in the values of a local assignment, which can
+    /// be merged with other local assignments by a rule.
+    fn restore_local_assign(
+        &mut self,
+        assign: LocalAssignStatement,
+        statements: &mut Vec<Statement>,
+    ) {
+        let (mut variables, values) = assign.into_assignments();
+
+        if values.len() > variables.len() {
+            self.is_valid = false;
+            return;
+        }
+
+        let total_values = values.len();
+        let mut current = LocalAssignStatement::new(Vec::new(), Vec::new());
+
+        for (index, value) in values.into_iter().enumerate() {
+            let assigned_variables: Vec<_> = if index + 1 == total_values {
+                mem::take(&mut variables)
+            } else {
+                variables.drain(..1).collect()
+            };
+
+            match value {
+                Expression::Call(call) if get_placeholder_id(&call).is_some() => {
+                    if current.variables_len() > 0 {
+                        statements.push(
+                            mem::replace(
+                                &mut current,
+                                LocalAssignStatement::new(Vec::new(), Vec::new()),
+                            )
+                            .into(),
+                        );
+                    }
+                    if let Some(statement) =
+                        self.restore_statement(&call, Some(&assigned_variables))
+                    {
+                        statements.push(statement);
+                    }
+                }
+                value => {
+                    for variable in assigned_variables {
+                        current.push_variable(variable);
+                    }
+                    current.push_value(value);
+                }
+            }
+        }
+
+        if current.variables_len() > 0 {
+            statements.push(current.into());
+        }
+    }
+}
+
+impl NodeProcessor for RestoreDisabledStatements {
+    fn process_block(&mut self, block: &mut Block) {
+        let has_placeholder = block.iter_statements().any(|statement| match statement {
+            Statement::Call(call) => get_placeholder_id(call).is_some(),
+            Statement::LocalAssign(assign) => assign.iter_values().any(is_placeholder),
+            _ => false,
+        });
+
+        if has_placeholder {
+            let mut statements = Vec::with_capacity(block.statements_len());
+
+            for statement in block.take_statements() {
+                match statement {
+                    Statement::Call(call) if get_placeholder_id(&call).is_some() => {
+                        if let Some(statement) = self.restore_statement(&call, None) {
+                            statements.push(statement);
+                        }
+                    }
+                    Statement::LocalAssign(assign) if assign.iter_values().any(is_placeholder) => {
+                        self.restore_local_assign(assign, &mut statements);
+                    }
+                    statement => statements.push(statement),
+                }
+            }
+
+            block.set_statements(statements);
+        }
+
+        let placeholder_call = match block.get_last_statement() {
+            Some(LastStatement::Return(statement)) if statement.len() == 1 => {
+                match statement.iter_expressions().next() {
+                    Some(Expression::Call(call)) if get_placeholder_id(call).is_some() => {
+                        Some(call.as_ref().clone())
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+
+        if let Some(call) = placeholder_call {
+            match self.restore(&call, None) {
+                Some(DisabledStatement::LastStatement(statement)) => {
+                    block.set_last_statement(statement);
+                }
+                Some(DisabledStatement::Statement(_)) => {
+                    self.is_valid = false;
+                }
+                None => {}
+            }
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        // placeholders that could not be restored (for example if a rule moved them
+        // into an expression)
+        if identifier.get_name() == PLACEHOLDER_FUNCTION {
+            self.is_valid = false;
+        }
+    }
+}
+
+fn is_placeholder(expression: &Expression) -> bool {
+    matches!(expression, Expression::Call(call) if get_placeholder_id(call).is_some())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::Parser;
+
+    fn read_directives(code: &str) -> RuleDirectives {
+        let mut block = Parser::default().preserve_tokens().parse(code).unwrap();
+        RuleDirectives::from_block(&mut block, code)
+    }
+
+    #[test]
+    fn parse_disable_all_directive() {
+        pretty_assertions::assert_eq!(
+            Directive::parse("-- darklua-disable", 1),
+            Some(Directive {
+                line: 1,
+                disable: true,
+                rules: RuleSelection::All,
+            })
+        );
+    }
+
+    #[test]
+    fn parse_disable_rules_directive() {
+        pretty_assertions::assert_eq!(
+            Directive::parse("--darklua-disable remove_types, rename_variables", 2),
+            Some(Directive {
+                line: 2,
+                disable: true,
+                rules: RuleSelection::Rules(vec![
+                    "remove_types".to_owned(),
+                    "rename_variables".to_owned()
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_enable_rule_directive() {
+        pretty_assertions::assert_eq!(
+            Directive::parse("-- darklua-enable remove_types", 3),
+            Some(Directive {
+                line: 3,
+                disable: false,
+                rules: RuleSelection::Rules(vec!["remove_types".to_owned()]),
+            })
+        );
+    }
+
+    #[test]
+    fn ignore_unrelated_comments() {
+        assert_eq!(Directive::parse("-- darklua-disabled", 1), None);
+        assert_eq!(Directive::parse("-- disable darklua", 1), None);
+        assert_eq!(Directive::parse("--[[ darklua-disable ]]", 1), None);
+    }
+
+    #[test]
+    fn custom_rules_are_known_rules() {
+        assert!(!is_known_rule("test_directive_custom_rule"));
+
+        assert!(crate::rules::register_rule(
+            "test_directive_custom_rule",
+            || Box::<crate::rules::RemoveEmptyDo>::default()
+        ));
+
+        assert!(is_known_rule("test_directive_custom_rule"));
+        assert!(is_known_rule("remove_types"));
+    }
+
+    #[test]
+    fn read_directives_from_nested_blocks() {
+        let directives = read_directives(
+            "local function f()\n    -- darklua-disable\n    local a = 1\n    -- darklua-enable\nend\nreturn f",
+        );
+
+        assert!(directives.is_disabled("remove_types", 3));
+        assert!(!directives.is_disabled("remove_types", 6));
+    }
+
+    #[test]
+    fn code_without_directives_is_empty() {
+        assert!(read_directives("-- comment\nlocal a = 1").is_empty());
+    }
+
+    #[test]
+    fn rule_is_disabled_until_enabled() {
+        let directives = read_directives(
+            "local a = 1\n-- darklua-disable\nlocal b = 2\n-- darklua-enable\nreturn a + b",
+        );
+
+        assert!(!directives.is_disabled("remove_types", 1));
+        assert!(directives.is_disabled("remove_types", 3));
+        assert!(!directives.is_disabled("remove_types", 5));
+    }
+
+    #[test]
+    fn only_selected_rules_are_disabled() {
+        let directives =
+            read_directives("-- darklua-disable remove_types\nlocal a: number = 1\nreturn a");
+
+        assert!(directives.is_disabled("remove_types", 2));
+        assert!(!directives.is_disabled("rename_variables", 2));
+    }
+
+    #[test]
+    fn enable_one_rule_after_disabling_all() {
+        let directives = read_directives(
+            "-- darklua-disable\nlocal a = 1\n-- darklua-enable rename_variables\nreturn a",
+        );
+
+        assert!(directives.is_disabled("rename_variables", 2));
+        assert!(!directives.is_disabled("rename_variables", 4));
+        assert!(directives.is_disabled("remove_types", 4));
+    }
+}
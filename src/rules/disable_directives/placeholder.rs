@@ -0,0 +1,429 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::nodes::*;
+use crate::process::{NodeProcessor, NodeVisitor, Scope, ScopeVisitor};
+
+/// The name of the function called by the placeholders of the disabled statements.
+pub(super) const PLACEHOLDER_FUNCTION: &str = "__DARKLUA_DISABLED_STATEMENT";
+
+/// The variables that a statement shares with the code around it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct StatementNames {
+    /// The local variables declared by the statement.
+    declared: Vec<String>,
+    /// The variables declared outside of the statement that it assigns.
+    assigned: Vec<String>,
+    /// The variables declared outside of the statement that it uses.
+    used: Vec<String>,
+    variable_arguments: bool,
+}
+
+impl StatementNames {
+    pub(super) fn from_statement(statement: &Statement) -> Self {
+        let mut statement = statement.clone();
+        let mut collector = NameCollector::default();
+        ScopeVisitor::visit_statement(&mut statement, &mut collector);
+
+        let mut names = collector.names;
+        match &statement {
+            Statement::LocalAssign(assign) => {
+                names.declared = assign
+                    .iter_variables()
+                    .map(|variable| variable.get_name().to_owned())
+                    .collect();
+            }
+            Statement::LocalFunction(function) => {
+                // the function can refer to itself
+                let name = function.get_name().to_owned();
+                names.assigned.retain(|assigned| *assigned != name);
+                names.used.retain(|used| *used != name);
+                names.declared.push(name);
+            }
+            _ => {}
+        }
+        names
+    }
+
+    pub(super) fn from_last_statement(statement: &LastStatement) -> Self {
+        let mut statement = statement.clone();
+        let mut collector = NameCollector::default();
+        ScopeVisitor::visit_last_statement(&mut statement, &mut collector);
+        collector.names
+    }
+
+    fn has_declarations(&self) -> bool {
+        !self.declared.is_empty()
+    }
+
+    /// Builds the call made by the placeholder of a statement: the variables are given
+    /// to the call so that rules see them used (and assigned) like in the statement.
+    pub(super) fn placeholder_call(&self, id: usize) -> FunctionCall {
+        let mut call = FunctionCall::from_name(PLACEHOLDER_FUNCTION).with_argument(id as f64);
+
+        if !self.assigned.is_empty() {
+            let assign = AssignStatement::new(
+                self.assigned.iter().map(Variable::new).collect(),
+                self.assigned.iter().map(Expression::identifier).collect(),
+            );
+            call = call.with_argument(FunctionExpression::from_block(assign));
+        }
+
+        for name in self.used.iter() {
+            call = call.with_argument(Expression::identifier(name));
+        }
+
+        if self.variable_arguments {
+            call = call.with_argument(Expression::variable_arguments());
+        }
+
+        call
+    }
+
+    pub(super) fn placeholder_statement(&self, id: usize) -> Statement {
+        let call = self.placeholder_call(id);
+
+        if self.has_declarations() {
+            LocalAssignStatement::new(
+                self.declared.iter().map(TypedIdentifier::new).collect(),
+                vec![call.into()],
+            )
+            .into()
+        } else {
+            call.into()
+        }
+    }
+
+    /// Reads the variables from a placeholder call processed by a rule. The declared
+    /// variables are given separately because they are not part of the call. Returns
+    /// `None` if the call does not match the placeholder built from `self`.
+    pub(super) fn read_placeholder(
+        &self,
+        call: &FunctionCall,
+        declared: Option<&[TypedIdentifier]>,
+    ) -> Option<Self> {
+        let mut arguments = match call.get_arguments() {
+            Arguments::Tuple(tuple) => tuple.iter_values(),
+            Arguments::String(_) | Arguments::Table(_) => return None,
+        };
+        arguments.next()?;
+
+        let assigned = if self.assigned.is_empty() {
+            Vec::new()
+        } else {
+            read_assigned_names(arguments.next()?)?
+        };
+
+        let mut used = Vec::with_capacity(self.used.len());
+        for _ in self.used.iter() {
+            match arguments.next()? {
+                Expression::Identifier(identifier) => used.push(identifier.get_name().to_owned()),
+                _ => return None,
+            }
+        }
+
+        if self.variable_arguments {
+            arguments
+                .next()
+                .filter(|argument| matches!(argument, Expression::VariableArguments(_)))?;
+        }
+
+        if arguments.next().is_some() {
+            return None;
+        }
+
+        let declared = match declared {
+            Some(variables) => variables
+                .iter()
+                .map(|variable| variable.get_name().to_owned())
+                .collect(),
+            // the rule removed the local variables: the statement declares them again
+            None => self.declared.clone(),
+        };
+
+        let names = Self {
+            declared,
+            assigned,
+            used,
+            variable_arguments: self.variable_arguments,
+        };
+
+        (names.declared.len() == self.declared.len() && names.assigned.len() == self.assigned.len())
+            .then_some(names)
+    }
+
+    /// Renames the variables of a statement that were renamed in its placeholder.
+    /// Returns false if the variables cannot be renamed consistently.
+    pub(super) fn rename_statement(&self, statement: &mut Statement, renamed: &Self) -> bool {
+        let Some(mut renames) = self.get_renames(renamed) else {
+            return false;
+        };
+
+        let declared_renames: Vec<_> = self
+            .declared
+            .iter()
+            .zip(renamed.declared.iter())
+            .filter(|(name, new_name)| name != new_name)
+            .collect();
+
+        // the references of a local function to itself are renamed with it, while
+        // the values of local variables refer to the variables declared before them
+        if let Statement::LocalFunction(_) = statement {
+            for (name, new_name) in declared_renames.iter() {
+                renames.insert(name.to_string(), new_name.to_string());
+            }
+        }
+
+        if !renames.is_empty() {
+            let mut renamer = Renamer {
+                scopes: Vec::new(),
+                renames,
+            };
+            ScopeVisitor::visit_statement(statement, &mut renamer);
+        }
+
+        match statement {
+            Statement::LocalAssign(assign) => {
+                for variable in assign.iter_mut_variables() {
+                    if let Some((_, new_name)) = declared_renames
+                        .iter()
+                        .find(|(name, _)| *name == variable.get_name())
+                    {
+                        variable.set_name(new_name.as_str());
+                    }
+                }
+            }
+            Statement::LocalFunction(function) => {
+                if let Some((_, new_name)) = declared_renames.first() {
+                    function.mutate_identifier().set_name(new_name.as_str());
+                }
+            }
+            _ => {}
+        }
+
+        true
+    }
+
+    pub(super) fn rename_last_statement(
+        &self,
+        statement: &mut LastStatement,
+        renamed: &Self,
+    ) -> bool {
+        let Some(renames) = self.get_renames(renamed) else {
+            return false;
+        };
+
+        if !renames.is_empty() {
+            let mut renamer = Renamer {
+                scopes: Vec::new(),
+                renames,
+            };
+            ScopeVisitor::visit_last_statement(statement, &mut renamer);
+        }
+
+        true
+    }
+
+    fn get_renames(&self, renamed: &Self) -> Option<HashMap<String, String>> {
+        let mut renames = HashMap::new();
+
+        for (name, new_name) in self
+            .assigned
+            .iter()
+            .zip(renamed.assigned.iter())
+            .chain(self.used.iter().zip(renamed.used.iter()))
+        {
+            if let Some(existing) = renames.insert(name.clone(), new_name.clone()) {
+                if existing != *new_name {
+                    return None;
+                }
+            }
+        }
+
+        renames.retain(|name, new_name| name != new_name);
+
+        Some(renames)
+    }
+}
+
+fn read_assigned_names(expression: &Expression) -> Option<Vec<String>> {
+    let Expression::Function(function) = expression else {
+        return None;
+    };
+
+    let block = function.get_block();
+    if block.statements_len() != 1 || block.get_last_statement().is_some() {
+        return None;
+    }
+
+    let Some(Statement::Assign(assign)) = block.first_statement() else {
+        return None;
+    };
+
+    assign
+        .iter_variables()
+        .zip(assign.iter_values())
+        .map(|(variable, value)| match (variable, value) {
+            (Variable::Identifier(variable), Expression::Identifier(value))
+                if variable.get_name() == value.get_name() =>
+            {
+                Some(variable.get_name().to_owned())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns the identifier of a placeholder call.
+pub(super) fn get_placeholder_id(call: &FunctionCall) -> Option<usize> {
+    if call.get_method().is_some() {
+        return None;
+    }
+
+    match call.get_prefix() {
+        Prefix::Identifier(identifier) if identifier.get_name() == PLACEHOLDER_FUNCTION => {}
+        _ => return None,
+    }
+
+    match call.get_arguments() {
+        Arguments::Tuple(tuple) => match tuple.iter_values().next() {
+            Some(Expression::Number(number)) => {
+                let value = number.compute_value();
+                (value >= 0.0 && value.fract() == 0.0).then_some(value as usize)
+            }
+            _ => None,
+        },
+        Arguments::String(_) | Arguments::Table(_) => None,
+    }
+}
+
+fn push_name(names: &mut Vec<String>, name: &str) {
+    if !names.iter().any(|existing| existing == name) {
+        names.push(name.to_owned());
+    }
+}
+
+/// Finds the variables of a statement that are declared outside of it. The local
+/// variables declared by the statement itself are inserted before any scope is
+/// pushed, so they are ignored.
+#[derive(Debug, Default)]
+struct NameCollector {
+    scopes: Vec<HashSet<String>>,
+    names: StatementNames,
+}
+
+impl NameCollector {
+    fn is_declared(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(name))
+    }
+}
+
+impl NodeProcessor for NameCollector {
+    fn process_variable(&mut self, variable: &mut Variable) {
+        if let Variable::Identifier(identifier) = variable {
+            if !self.is_declared(identifier.get_name()) {
+                push_name(&mut self.names.assigned, identifier.get_name());
+            }
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let name = function.get_name();
+        if name.get_field_names().is_empty()
+            && !name.has_method()
+            && !self.is_declared(name.get_name().get_name())
+        {
+            push_name(&mut self.names.assigned, name.get_name().get_name());
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if !self.is_declared(identifier.get_name()) {
+            push_name(&mut self.names.used, identifier.get_name());
+        }
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::VariableArguments(_) = expression {
+            self.names.variable_arguments = true;
+        }
+    }
+}
+
+impl Scope for NameCollector {
+    fn push(&mut self) {
+        self.scopes.push(HashSet::new());
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(identifier.clone());
+        }
+    }
+
+    fn insert_self(&mut self) {
+        self.insert(&mut "self".to_owned());
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
+        self.insert(identifier);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.insert(function.mutate_identifier().mutate_name());
+    }
+}
+
+/// Renames the variables of a statement that are declared outside of it.
+#[derive(Debug)]
+struct Renamer {
+    scopes: Vec<HashSet<String>>,
+    renames: HashMap<String, String>,
+}
+
+impl NodeProcessor for Renamer {
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if self
+            .scopes
+            .iter()
+            .any(|scope| scope.contains(identifier.get_name()))
+        {
+            return;
+        }
+
+        if let Some(new_name) = self.renames.get(identifier.get_name()) {
+            identifier.set_name(new_name.as_str());
+        }
+    }
+}
+
+impl Scope for Renamer {
+    fn push(&mut self) {
+        self.scopes.push(HashSet::new());
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(identifier.clone());
+        }
+    }
+
+    fn insert_self(&mut self) {
+        self.insert(&mut "self".to_owned());
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
+        self.insert(identifier);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.insert(function.mutate_identifier().mutate_name());
+    }
+}
@@ -0,0 +1,325 @@
+use std::path::{Path, PathBuf};
+
+use wax::Pattern;
+
+use crate::nodes::Block;
+use crate::rules::{
+    Context, Rule, RuleConfiguration, RuleConfigurationError, RuleProcessResult, RuleProperties,
+    RulePropertyValue,
+};
+use crate::utils::normalize_path;
+
+const INCLUDE_PROPERTY: &str = "include_paths";
+const EXCLUDE_PROPERTY: &str = "exclude_paths";
+
+/// Returns true if the properties of a rule contain one of the properties handled
+/// by [`FilteredRule`].
+pub(crate) fn has_filter_properties(properties: &RuleProperties) -> bool {
+    properties.contains_key(INCLUDE_PROPERTY) || properties.contains_key(EXCLUDE_PROPERTY)
+}
+
+fn build_matcher(globs: &[String], kind: &str) -> Option<wax::Any<'static>> {
+    let globs: Vec<_> = globs
+        .iter()
+        .filter_map(|glob| match wax::Glob::new(glob) {
+            Ok(glob) => Some(glob.into_owned()),
+            Err(err) => {
+                log::warn!("unable to create {} matcher from `{}`: {}", kind, glob, err);
+                None
+            }
+        })
+        .collect();
+
+    if globs.is_empty() {
+        None
+    } else {
+        Some(
+            wax::any(globs)
+                .expect("rule filter globs errors should be filtered and only emit a warning"),
+        )
+    }
+}
+
+/// A rule that is only applied to the files matching its `include_paths` globs (or to
+/// every file when no `include_paths` glob is given) and not matching any of its
+/// `exclude_paths` globs. The globs are matched against the file paths relative to the
+/// configuration file.
+///
+/// When a rule defined in a configuration has an `include_paths` or `exclude_paths`
+/// property, it gets wrapped into a filtered rule.
+#[derive(Debug)]
+pub struct FilteredRule {
+    rule: Box<dyn Rule>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    include_matcher: Option<wax::Any<'static>>,
+    exclude_matcher: Option<wax::Any<'static>>,
+}
+
+impl FilteredRule {
+    /// Creates a filtered rule that applies the given rule to every file.
+    pub fn new(rule: Box<dyn Rule>) -> Self {
+        Self {
+            rule,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            include_matcher: None,
+            exclude_matcher: None,
+        }
+    }
+
+    /// Only applies the rule to the files matching the given glob (or any of the other
+    /// `include_paths` globs).
+    pub fn with_include_path(mut self, include: impl Into<String>) -> Self {
+        self.include.push(include.into());
+        self.include_matcher = build_matcher(&self.include, INCLUDE_PROPERTY);
+        self
+    }
+
+    /// Skips the files matching the given glob.
+    pub fn with_exclude_path(mut self, exclude: impl Into<String>) -> Self {
+        self.exclude.push(exclude.into());
+        self.exclude_matcher = build_matcher(&self.exclude, EXCLUDE_PROPERTY);
+        self
+    }
+
+    /// Returns the filtered rule.
+    pub fn inner_rule(&self) -> &dyn Rule {
+        self.rule.as_ref()
+    }
+
+    fn is_match(&self, path: &Path) -> bool {
+        let is_included = self.include.is_empty()
+            || self
+                .include_matcher
+                .as_ref()
+                .is_some_and(|matcher| matcher.is_match(path));
+
+        is_included
+            && !self
+                .exclude_matcher
+                .as_ref()
+                .is_some_and(|matcher| matcher.is_match(path))
+    }
+
+    fn get_relative_path(context: &Context) -> PathBuf {
+        let path = normalize_path(context.current_path());
+
+        // globs are relative to the configuration file location
+        context
+            .project_location
+            .as_ref()
+            .and_then(|location| path.strip_prefix(normalize_path(location)).ok())
+            .map(Path::to_path_buf)
+            .unwrap_or(path)
+    }
+}
+
+impl Rule for FilteredRule {
+    fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+        let path = Self::get_relative_path(context);
+
+        if !self.is_match(&path) {
+            log::trace!(
+                "skip rule `{}` for `{}` (filtered by its `include_paths` and `exclude_paths` globs)",
+                self.get_name(),
+                path.display()
+            );
+            return Ok(());
+        }
+
+        self.rule.process(block, context)
+    }
+
+    fn require_content(&self, current_source: &Path, current_block: &Block) -> Vec<PathBuf> {
+        self.rule.require_content(current_source, current_block)
+    }
+}
+
+impl RuleConfiguration for FilteredRule {
+    fn configure(&mut self, mut properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        if let Some(value) = properties.remove(INCLUDE_PROPERTY) {
+            self.include = Vec::new();
+            for include in value.expect_string_list(INCLUDE_PROPERTY)? {
+                validate_glob(INCLUDE_PROPERTY, &include)?;
+                self.include.push(include);
+            }
+            self.include_matcher = build_matcher(&self.include, INCLUDE_PROPERTY);
+        }
+
+        if let Some(value) = properties.remove(EXCLUDE_PROPERTY) {
+            self.exclude = Vec::new();
+            for exclude in value.expect_string_list(EXCLUDE_PROPERTY)? {
+                validate_glob(EXCLUDE_PROPERTY, &exclude)?;
+                self.exclude.push(exclude);
+            }
+            self.exclude_matcher = build_matcher(&self.exclude, EXCLUDE_PROPERTY);
+        }
+
+        self.rule.configure(properties)
+    }
+
+    fn get_name(&self) -> &'static str {
+        self.rule.get_name()
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = self.rule.serialize_to_properties();
+
+        if !self.include.is_empty() {
+            properties.insert(
+                INCLUDE_PROPERTY.to_owned(),
+                RulePropertyValue::StringList(self.include.clone()),
+            );
+        }
+
+        if !self.exclude.is_empty() {
+            properties.insert(
+                EXCLUDE_PROPERTY.to_owned(),
+                RulePropertyValue::StringList(self.exclude.clone()),
+            );
+        }
+
+        properties
+    }
+}
+
+fn validate_glob(property: &str, glob: &str) -> Result<(), RuleConfigurationError> {
+    wax::Glob::new(glob)
+        .map(|_| ())
+        .map_err(|err| RuleConfigurationError::UnexpectedValue {
+            property: property.to_owned(),
+            message: format!("invalid glob `{}`: {}", glob, err),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::{RemoveComments, RemoveSpaces};
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> FilteredRule {
+        FilteredRule::new(Box::new(RemoveSpaces::default()))
+    }
+
+    #[test]
+    fn serialize_rule_with_include() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_include_path("src/**"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_spaces",
+          "include_paths": [
+            "src/**"
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_exclude() {
+        let rule: Box<dyn Rule> = Box::new(
+            FilteredRule::new(Box::new(RemoveComments::default().with_exception("^!")))
+                .with_exclude_path("**/__tests__/**"),
+        );
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_comments",
+          "except": [
+            "^!"
+          ],
+          "exclude_paths": [
+            "**/__tests__/**"
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_rule_with_include_and_exclude() {
+        let rule = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_spaces',
+            include_paths: ['src/**'],
+            exclude_paths: ['**/__tests__/**'],
+        }"#,
+        )
+        .unwrap();
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_spaces",
+          "exclude_paths": [
+            "**/__tests__/**"
+          ],
+          "include_paths": [
+            "src/**"
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn match_included_path() {
+        let rule = new_rule().with_include_path("src/**");
+
+        assert!(rule.is_match(Path::new("src/main.lua")));
+        assert!(!rule.is_match(Path::new("tests/main.lua")));
+    }
+
+    #[test]
+    fn match_any_path_without_include() {
+        let rule = new_rule().with_exclude_path("**/__tests__/**");
+
+        assert!(rule.is_match(Path::new("src/main.lua")));
+        assert!(!rule.is_match(Path::new("src/__tests__/main.spec.lua")));
+        assert!(!rule.is_match(Path::new("__tests__/main.spec.lua")));
+    }
+
+    #[test]
+    fn exclude_takes_precedence_over_include() {
+        let rule = new_rule()
+            .with_include_path("src/**")
+            .with_exclude_path("src/vendor/**");
+
+        assert!(rule.is_match(Path::new("src/main.lua")));
+        assert!(!rule.is_match(Path::new("src/vendor/lib.lua")));
+    }
+
+    #[test]
+    fn configure_with_invalid_include_type_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_spaces',
+            include_paths: 'src/**',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"list of string expected for field 'include_paths' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_glob_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_spaces',
+            exclude_paths: ['src/**/**{'],
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'exclude_paths': invalid glob `src/**/**{`: failed to parse glob expression at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_spaces',
+            include_paths: ['src/**'],
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
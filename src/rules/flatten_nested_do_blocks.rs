@@ -0,0 +1,208 @@
+use std::mem;
+
+use crate::nodes::{Block, LastStatement, Statement, Token};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+/// Returns true when the block declares locals (or types) that are scoped to it.
+fn declares_locals(block: &Block) -> bool {
+    block.iter_statements().any(|statement| {
+        matches!(
+            statement,
+            Statement::LocalAssign(_)
+                | Statement::LocalFunction(_)
+                | Statement::TypeDeclaration(_)
+                | Statement::TypeFunction(_)
+        )
+    })
+}
+
+type StatementEntry = (Statement, Option<Token>);
+type LastStatementEntry = (LastStatement, Option<Token>);
+
+/// Takes the statements and the last statement out of the block, with the tokens
+/// of the semicolons following them.
+fn take_entries(block: &mut Block) -> (Vec<StatementEntry>, Option<LastStatementEntry>) {
+    let (semicolons, last_semicolon) = match block.mutate_tokens() {
+        Some(tokens) => (
+            mem::take(&mut tokens.semicolons),
+            tokens.last_semicolon.take(),
+        ),
+        None => (Vec::new(), None),
+    };
+
+    let mut semicolons = semicolons.into_iter();
+    let statements = block
+        .take_statements()
+        .into_iter()
+        .map(|statement| (statement, semicolons.next().flatten()))
+        .collect();
+
+    let last_statement = block
+        .take_last_statement()
+        .map(|last_statement| (last_statement, last_semicolon));
+
+    (statements, last_statement)
+}
+
+/// Pushes the statement into `statements`, replacing do statements that can be
+/// removed with their own statements. The last statement of a do block can only
+/// be moved when `can_take_last_statement` is true.
+fn splice_statement(
+    (statement, semicolon): StatementEntry,
+    can_take_last_statement: bool,
+    statements: &mut Vec<StatementEntry>,
+    last_statement: &mut Option<LastStatementEntry>,
+) {
+    let mut do_statement = match statement {
+        Statement::Do(do_statement) => do_statement,
+        statement => {
+            statements.push((statement, semicolon));
+            return;
+        }
+    };
+
+    let block = do_statement.mutate_block();
+
+    if block.is_empty() {
+        return;
+    }
+
+    if declares_locals(block) || (block.get_last_statement().is_some() && !can_take_last_statement)
+    {
+        statements.push((Statement::Do(do_statement), semicolon));
+        return;
+    }
+
+    let (mut inner_statements, mut inner_last_statement) = take_entries(block);
+
+    // keep the semicolon following the do statement after its last statement
+    match (inner_last_statement.as_mut(), inner_statements.last_mut()) {
+        (Some((_, last_semicolon)), _) | (None, Some((_, last_semicolon))) => {
+            if last_semicolon.is_none() {
+                *last_semicolon = semicolon;
+            }
+        }
+        (None, None) => {}
+    }
+
+    let inner_statements_len = inner_statements.len();
+
+    for (index, inner_statement) in inner_statements.into_iter().enumerate() {
+        let is_last = index + 1 == inner_statements_len && inner_last_statement.is_none();
+
+        splice_statement(
+            inner_statement,
+            can_take_last_statement && is_last,
+            statements,
+            last_statement,
+        );
+    }
+
+    if inner_last_statement.is_some() {
+        *last_statement = inner_last_statement;
+    }
+}
+
+#[derive(Debug, Default)]
+struct Processor;
+
+impl NodeProcessor for Processor {
+    fn process_block(&mut self, block: &mut Block) {
+        if !block
+            .iter_statements()
+            .any(|statement| matches!(statement, Statement::Do(_)))
+        {
+            return;
+        }
+
+        let (statements, mut last_statement) = take_entries(block);
+        let has_last_statement = last_statement.is_some();
+        let statements_len = statements.len();
+
+        let mut new_statements = Vec::with_capacity(statements_len);
+
+        for (index, statement) in statements.into_iter().enumerate() {
+            let is_last = index + 1 == statements_len && !has_last_statement;
+
+            splice_statement(statement, is_last, &mut new_statements, &mut last_statement);
+        }
+
+        let (new_statements, semicolons): (Vec<_>, Vec<_>) = new_statements.into_iter().unzip();
+        block.set_statements(new_statements);
+
+        let last_semicolon = last_statement.and_then(|(last_statement, last_semicolon)| {
+            block.set_last_statement(last_statement);
+            last_semicolon
+        });
+
+        if let Some(tokens) = block.mutate_tokens() {
+            tokens.semicolons = semicolons;
+            tokens.last_semicolon = last_semicolon;
+        }
+    }
+}
+
+pub const FLATTEN_NESTED_DO_BLOCKS_RULE_NAME: &str = "flatten_nested_do_blocks";
+
+/// A rule that removes do statements that do not declare locals by moving their
+/// statements into the parent block.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct FlattenNestedDoBlocks {}
+
+impl FlawlessRule for FlattenNestedDoBlocks {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor;
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for FlattenNestedDoBlocks {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        FLATTEN_NESTED_DO_BLOCKS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> FlattenNestedDoBlocks {
+        FlattenNestedDoBlocks::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""flatten_nested_do_blocks""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'flatten_nested_do_blocks',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
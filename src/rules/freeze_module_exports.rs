@@ -0,0 +1,315 @@
+use regex::Regex;
+
+use crate::nodes::{
+    Block, Expression, FieldExpression, FunctionCall, FunctionStatement, LastStatement, Prefix,
+    Statement, TableEntry, TableExpression, Variable,
+};
+use crate::process::processors::VariableUsage;
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+use crate::LuaTarget;
+
+const TABLE_LIBRARY: &str = "table";
+
+fn declares_variable(statement: &Statement, name: &str) -> bool {
+    match statement {
+        Statement::LocalAssign(assign) => assign
+            .iter_variables()
+            .any(|variable| variable.get_name() == name),
+        Statement::LocalFunction(function) => function.get_name() == name,
+        _ => false,
+    }
+}
+
+/// Finds assignments to the fields of a variable (like `self.value = 1` or
+/// `function self.call() end`).
+struct FieldAssignment<'a> {
+    name: &'a str,
+    found: bool,
+}
+
+impl<'a> FieldAssignment<'a> {
+    fn new(name: &'a str) -> Self {
+        Self { name, found: false }
+    }
+
+    fn is_variable(&self, prefix: &Prefix) -> bool {
+        matches!(prefix, Prefix::Identifier(identifier) if identifier.get_name() == self.name)
+    }
+}
+
+impl NodeProcessor for FieldAssignment<'_> {
+    fn process_variable(&mut self, variable: &mut Variable) {
+        let is_field_of_variable = match variable {
+            Variable::Identifier(_) => false,
+            Variable::Field(field) => self.is_variable(field.get_prefix()),
+            Variable::Index(index) => self.is_variable(index.get_prefix()),
+        };
+        if is_field_of_variable {
+            self.found = true;
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let name = function.get_name();
+        if name.get_name().get_name() == self.name
+            && (!name.get_field_names().is_empty() || name.has_method())
+        {
+            self.found = true;
+        }
+    }
+}
+
+/// Returns true if a function of the table assigns a field of its first parameter. These
+/// functions are usually methods (like `function(self) self.value = 1 end`) that would
+/// error at runtime when called on the frozen table.
+fn has_method_mutating_self(table: &mut TableExpression) -> bool {
+    table.iter_mut_entries().any(|entry| {
+        let value = match entry {
+            TableEntry::Field(entry) => entry.mutate_value(),
+            TableEntry::Index(entry) => entry.mutate_value(),
+            TableEntry::Value(value) => value.as_mut(),
+        };
+
+        let Expression::Function(function) = value else {
+            return false;
+        };
+        let Some(parameter) = function
+            .iter_parameters()
+            .next()
+            .map(|parameter| parameter.get_name().to_owned())
+        else {
+            return false;
+        };
+
+        let mut assignment = FieldAssignment::new(&parameter);
+        DefaultVisitor::visit_block(function.mutate_block(), &mut assignment);
+        assignment.found
+    })
+}
+
+/// Returns true if the table assigned to the given local variable at the root of the
+/// block is never mutated (or passed to a function that could mutate it) after its
+/// construction.
+fn is_unmutated_local_table(block: &mut Block, name: &str) -> bool {
+    let Some(index) = block
+        .iter_statements()
+        .enumerate()
+        .filter(|(_, statement)| declares_variable(statement, name))
+        .map(|(index, _)| index)
+        .last()
+    else {
+        return false;
+    };
+
+    match block.iter_mut_statements().nth(index) {
+        Some(Statement::LocalAssign(assign))
+            if assign.variables_len() == 1 && assign.values_len() == 1 =>
+        {
+            match assign.iter_mut_values().next() {
+                Some(Expression::Table(table)) => {
+                    if has_method_mutating_self(table) {
+                        return false;
+                    }
+                }
+                _ => return false,
+            }
+        }
+        _ => return false,
+    }
+
+    let mut usage = VariableUsage::new(name);
+
+    for statement in block.iter_mut_statements().skip(index + 1) {
+        DefaultVisitor::visit_statement(statement, &mut usage);
+    }
+
+    usage.is_only_reading_fields()
+}
+
+fn freeze(expression: Expression) -> Expression {
+    FunctionCall::from_prefix(FieldExpression::new(
+        Prefix::from_name(TABLE_LIBRARY),
+        "freeze",
+    ))
+    .with_argument(expression)
+    .into()
+}
+
+pub const FREEZE_MODULE_EXPORTS_RULE_NAME: &str = "freeze_module_exports";
+
+/// A rule that wraps the table returned by a module in a `table.freeze` call.
+#[derive(Debug, Default)]
+pub struct FreezeModuleExports {
+    exclude: Vec<Regex>,
+}
+
+impl FreezeModuleExports {
+    /// Skips the modules where the path matches the given regular expression.
+    pub fn with_exclude(mut self, exclude_pattern: &str) -> Self {
+        match Regex::new(exclude_pattern) {
+            Ok(regex_value) => {
+                self.exclude.push(regex_value);
+            }
+            Err(err) => {
+                log::warn!(
+                    "unable to compile regex pattern '{}': {}",
+                    exclude_pattern,
+                    err
+                );
+            }
+        };
+
+        self
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        self.exclude.iter().any(|pattern| pattern.is_match(path))
+    }
+}
+
+impl FlawlessRule for FreezeModuleExports {
+    fn flawless_process(&self, block: &mut Block, context: &Context) {
+        // `table.freeze` only exists in Luau
+        if context.target() != LuaTarget::Luau {
+            return;
+        }
+
+        let path = context.current_path().to_string_lossy().replace('\\', "/");
+        if self.is_excluded(&path) {
+            return;
+        }
+
+        if block
+            .iter_statements()
+            .any(|statement| declares_variable(statement, TABLE_LIBRARY))
+        {
+            return;
+        }
+
+        let Some(LastStatement::Return(statement)) = block.get_last_statement() else {
+            return;
+        };
+
+        if statement.len() != 1 {
+            return;
+        }
+
+        let exported_variable = match block.mutate_last_statement() {
+            Some(LastStatement::Return(statement)) => {
+                match statement.iter_mut_expressions().next() {
+                    Some(Expression::Table(table)) => {
+                        if has_method_mutating_self(table) {
+                            return;
+                        }
+                        None
+                    }
+                    Some(Expression::Identifier(identifier)) => {
+                        Some(identifier.get_name().to_owned())
+                    }
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+
+        if let Some(name) = exported_variable {
+            if !is_unmutated_local_table(block, &name) {
+                return;
+            }
+        }
+
+        if let Some(LastStatement::Return(statement)) = block.mutate_last_statement() {
+            if let Some(expression) = statement.iter_mut_expressions().next() {
+                let exported = std::mem::replace(expression, Expression::nil());
+                *expression = freeze(exported);
+            }
+        }
+    }
+}
+
+impl RuleConfiguration for FreezeModuleExports {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "exclude" => {
+                    self.exclude = value.expect_regex_list(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        FREEZE_MODULE_EXPORTS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if !self.exclude.is_empty() {
+            properties.insert(
+                "exclude".to_owned(),
+                RulePropertyValue::StringList(
+                    self.exclude
+                        .iter()
+                        .map(|regex| regex.as_str().to_owned())
+                        .collect(),
+                ),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> FreezeModuleExports {
+        FreezeModuleExports::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "freeze_module_exports"
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_exclude() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_exclude("^src/mutable/"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "freeze_module_exports",
+          "exclude": [
+            "^src/mutable/"
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'freeze_module_exports',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -66,10 +66,20 @@ impl GroupLocalProcessor {
             .map(|variable| variable.get_name().as_str())
             .collect();
 
-        next.iter_mut_values().all(|expression| {
-            DefaultVisitor::visit_expression(expression, &mut find_variables);
-            !find_variables.has_found_usage()
-        })
+        // a type annotation like `typeof(foo)` can also refer to a previous variable
+        let types_are_independent = next
+            .iter_mut_variables()
+            .filter_map(|variable| variable.mutate_type())
+            .all(|r#type| {
+                DefaultVisitor::visit_type(r#type, &mut find_variables);
+                !find_variables.has_found_usage()
+            });
+
+        types_are_independent
+            && next.iter_mut_values().all(|expression| {
+                DefaultVisitor::visit_expression(expression, &mut find_variables);
+                !find_variables.has_found_usage()
+            })
     }
 
     fn merge(&self, first: &mut LocalAssignStatement, mut other: LocalAssignStatement) {
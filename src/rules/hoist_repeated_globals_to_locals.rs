@@ -0,0 +1,527 @@
+use std::collections::{HashMap, HashSet};
+use std::ops;
+
+use crate::nodes::{
+    Block, Expression, FieldExpression, FunctionExpression, FunctionStatement, Identifier,
+    LocalAssignStatement, LocalFunctionStatement, Prefix, Variable,
+};
+use crate::process::{
+    IdentifierTracker, NodePostProcessor, NodePostVisitor, NodeProcessor, Scope, ScopePostVisitor,
+};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+
+pub const HOIST_REPEATED_GLOBALS_TO_LOCALS_RULE_NAME: &str = "hoist_repeated_globals_to_locals";
+
+const DEFAULT_MINIMUM_OCCURRENCES: usize = 3;
+
+/// The standard libraries whose fields can be hoisted. Other globals are hoisted as a
+/// whole, because indexing them could fail where they are not defined.
+const LIBRARIES: [&str; 9] = [
+    "bit32",
+    "buffer",
+    "coroutine",
+    "debug",
+    "math",
+    "os",
+    "string",
+    "table",
+    "utf8",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum GlobalValue {
+    Global(String),
+    LibraryField(String, String),
+}
+
+impl GlobalValue {
+    fn to_expression(&self) -> Expression {
+        match self {
+            Self::Global(name) => Expression::identifier(name),
+            Self::LibraryField(library, field) => {
+                FieldExpression::new(Prefix::from_name(library), field.as_str()).into()
+            }
+        }
+    }
+}
+
+fn get_root_identifier(prefix: &Prefix) -> Option<&Identifier> {
+    let mut current = prefix;
+    loop {
+        match current {
+            Prefix::Identifier(identifier) => break Some(identifier),
+            Prefix::Field(field) => current = field.get_prefix(),
+            Prefix::Index(index) => current = index.get_prefix(),
+            Prefix::Call(_) | Prefix::Parenthese(_) => break None,
+        }
+    }
+}
+
+/// Returns the global value accessed by an identifier or a library field, if the
+/// identifier is not a local variable.
+fn get_global_value(
+    tracker: &IdentifierTracker,
+    identifier: &Identifier,
+    field: Option<&Identifier>,
+) -> Option<GlobalValue> {
+    let name = identifier.get_name();
+
+    if tracker.is_identifier_used(name) {
+        return None;
+    }
+
+    let is_library = LIBRARIES.contains(&name.as_str());
+
+    match field {
+        Some(field) if is_library => Some(GlobalValue::LibraryField(
+            name.to_owned(),
+            field.get_name().to_owned(),
+        )),
+        None if !is_library => Some(GlobalValue::Global(name.to_owned())),
+        _ => None,
+    }
+}
+
+fn get_expression_global_value(
+    tracker: &IdentifierTracker,
+    expression: &Expression,
+) -> Option<GlobalValue> {
+    match expression {
+        Expression::Identifier(identifier) => get_global_value(tracker, identifier, None),
+        Expression::Field(field) => get_field_global_value(tracker, field),
+        _ => None,
+    }
+}
+
+fn get_prefix_global_value(tracker: &IdentifierTracker, prefix: &Prefix) -> Option<GlobalValue> {
+    match prefix {
+        Prefix::Identifier(identifier) => get_global_value(tracker, identifier, None),
+        Prefix::Field(field) => get_field_global_value(tracker, field),
+        _ => None,
+    }
+}
+
+fn get_field_global_value(
+    tracker: &IdentifierTracker,
+    field: &FieldExpression,
+) -> Option<GlobalValue> {
+    if let Prefix::Identifier(identifier) = field.get_prefix() {
+        get_global_value(tracker, identifier, Some(field.get_field()))
+    } else {
+        None
+    }
+}
+
+/// Counts the global values accessed directly in each function (and in the root block),
+/// with the names of the globals assigned and the identifiers used in the block.
+#[derive(Default)]
+struct CountGlobals {
+    identifier_tracker: IdentifierTracker,
+    functions: Vec<Vec<(GlobalValue, usize)>>,
+    function_stack: Vec<usize>,
+    assigned_globals: HashSet<String>,
+    identifiers: HashSet<String>,
+}
+
+impl CountGlobals {
+    fn begin_function(&mut self) {
+        self.function_stack.push(self.functions.len());
+        self.functions.push(Vec::new());
+    }
+
+    fn end_function(&mut self) {
+        self.function_stack.pop();
+    }
+
+    fn count(&mut self, value: GlobalValue) {
+        let Some(current) = self.function_stack.last() else {
+            return;
+        };
+        let counts = &mut self.functions[*current];
+
+        if let Some((_, count)) = counts.iter_mut().find(|(other, _)| *other == value) {
+            *count += 1;
+        } else {
+            counts.push((value, 1));
+        }
+    }
+
+    fn is_hoistable(&self, value: &GlobalValue) -> bool {
+        match value {
+            GlobalValue::Global(name) | GlobalValue::LibraryField(name, _) => {
+                !self.assigned_globals.contains(name)
+            }
+        }
+    }
+}
+
+impl Scope for CountGlobals {
+    fn push(&mut self) {
+        self.identifier_tracker.push();
+    }
+
+    fn pop(&mut self) {
+        self.identifier_tracker.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.identifiers.insert(identifier.clone());
+        self.identifier_tracker.insert(identifier);
+    }
+
+    fn insert_self(&mut self) {
+        self.identifier_tracker.insert_self();
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, value: Option<&mut Expression>) {
+        self.identifiers.insert(identifier.clone());
+        self.identifier_tracker.insert_local(identifier, value);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.identifiers
+            .insert(function.get_identifier().get_name().to_owned());
+        self.identifier_tracker.insert_local_function(function);
+    }
+}
+
+impl NodeProcessor for CountGlobals {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Some(value) = get_expression_global_value(&self.identifier_tracker, expression) {
+            self.count(value);
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        if let Some(value) = get_prefix_global_value(&self.identifier_tracker, prefix) {
+            self.count(value);
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        self.identifiers.insert(identifier.get_name().to_owned());
+    }
+
+    fn process_variable(&mut self, variable: &mut Variable) {
+        let root = match variable {
+            Variable::Identifier(identifier) => Some(&*identifier),
+            Variable::Field(field) => get_root_identifier(field.get_prefix()),
+            Variable::Index(index) => get_root_identifier(index.get_prefix()),
+        };
+
+        if let Some(identifier) = root {
+            self.assigned_globals
+                .insert(identifier.get_name().to_owned());
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        self.assigned_globals
+            .insert(function.get_name().get_name().get_name().to_owned());
+        self.begin_function();
+    }
+
+    fn process_function_expression(&mut self, _: &mut FunctionExpression) {
+        self.begin_function();
+    }
+
+    fn process_local_function_statement(&mut self, _: &mut LocalFunctionStatement) {
+        self.begin_function();
+    }
+}
+
+impl NodePostProcessor for CountGlobals {
+    fn process_after_function_statement(&mut self, _: &mut FunctionStatement) {
+        self.end_function();
+    }
+
+    fn process_after_function_expression(&mut self, _: &mut FunctionExpression) {
+        self.end_function();
+    }
+
+    fn process_after_local_function_statement(&mut self, _: &mut LocalFunctionStatement) {
+        self.end_function();
+    }
+}
+
+/// Replaces the hoisted library fields with their local variable, and declares the
+/// local variables at the top of each function.
+struct HoistGlobals {
+    identifier_tracker: IdentifierTracker,
+    functions: Vec<Vec<(GlobalValue, String)>>,
+    function_stack: Vec<usize>,
+    next_function: usize,
+}
+
+impl ops::Deref for HoistGlobals {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for HoistGlobals {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl HoistGlobals {
+    fn new(functions: Vec<Vec<(GlobalValue, String)>>) -> Self {
+        Self {
+            identifier_tracker: IdentifierTracker::new(),
+            functions,
+            function_stack: Vec::new(),
+            next_function: 0,
+        }
+    }
+
+    fn begin_function(&mut self) {
+        self.function_stack.push(self.next_function);
+        self.next_function += 1;
+    }
+
+    fn end_function(&mut self, block: &mut Block) {
+        if let Some(function) = self.function_stack.pop() {
+            let hoisted = std::mem::take(&mut self.functions[function]);
+
+            for (value, name) in hoisted.into_iter().rev() {
+                block.insert_statement(
+                    0,
+                    LocalAssignStatement::from_variable(name).with_value(value.to_expression()),
+                );
+            }
+        }
+    }
+
+    fn get_local_name(&self, value: &GlobalValue) -> Option<&str> {
+        let current = self.function_stack.last()?;
+
+        self.functions[*current]
+            .iter()
+            .find(|(other, _)| other == value)
+            .map(|(_, name)| name.as_str())
+    }
+
+    fn get_replacement(&self, value: Option<GlobalValue>) -> Option<Identifier> {
+        let value = value?;
+
+        if matches!(value, GlobalValue::Global(_)) {
+            return None;
+        }
+
+        self.get_local_name(&value).map(Identifier::new)
+    }
+}
+
+impl NodeProcessor for HoistGlobals {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        let value = get_expression_global_value(&self.identifier_tracker, expression);
+
+        if let Some(identifier) = self.get_replacement(value) {
+            *expression = identifier.into();
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        let value = get_prefix_global_value(&self.identifier_tracker, prefix);
+
+        if let Some(identifier) = self.get_replacement(value) {
+            *prefix = identifier.into();
+        }
+    }
+
+    fn process_function_statement(&mut self, _: &mut FunctionStatement) {
+        self.begin_function();
+    }
+
+    fn process_function_expression(&mut self, _: &mut FunctionExpression) {
+        self.begin_function();
+    }
+
+    fn process_local_function_statement(&mut self, _: &mut LocalFunctionStatement) {
+        self.begin_function();
+    }
+}
+
+impl NodePostProcessor for HoistGlobals {
+    fn process_after_function_statement(&mut self, function: &mut FunctionStatement) {
+        self.end_function(function.mutate_block());
+    }
+
+    fn process_after_function_expression(&mut self, function: &mut FunctionExpression) {
+        self.end_function(function.mutate_block());
+    }
+
+    fn process_after_local_function_statement(&mut self, function: &mut LocalFunctionStatement) {
+        self.end_function(function.mutate_block());
+    }
+}
+
+/// A rule that declares local variables for the globals and the standard library
+/// fields (like `math.floor`) that are accessed many times in a function.
+#[derive(Debug, PartialEq, Eq)]
+pub struct HoistRepeatedGlobalsToLocals {
+    minimum_occurrences: usize,
+}
+
+impl Default for HoistRepeatedGlobalsToLocals {
+    fn default() -> Self {
+        Self {
+            minimum_occurrences: DEFAULT_MINIMUM_OCCURRENCES,
+        }
+    }
+}
+
+impl HoistRepeatedGlobalsToLocals {
+    /// Sets the number of times a value must be accessed in a function to be hoisted.
+    pub fn with_minimum_occurrences(mut self, minimum_occurrences: usize) -> Self {
+        self.minimum_occurrences = minimum_occurrences;
+        self
+    }
+}
+
+impl FlawlessRule for HoistRepeatedGlobalsToLocals {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut counter = CountGlobals::default();
+        counter.begin_function();
+        ScopePostVisitor::visit_block(block, &mut counter);
+        counter.end_function();
+
+        let mut names: HashMap<GlobalValue, String> = HashMap::new();
+        let mut used_names = counter.identifiers.clone();
+
+        let functions: Vec<Vec<(GlobalValue, String)>> = counter
+            .functions
+            .iter()
+            .map(|counts| {
+                counts
+                    .iter()
+                    .filter(|(value, count)| {
+                        *count >= self.minimum_occurrences.max(1) && counter.is_hoistable(value)
+                    })
+                    .map(|(value, _)| {
+                        let name = names
+                            .entry(value.clone())
+                            .or_insert_with(|| match value {
+                                GlobalValue::Global(name) => name.clone(),
+                                GlobalValue::LibraryField(library, field) => {
+                                    let base = format!("{}_{}", library, field);
+                                    let mut name = base.clone();
+                                    let mut suffix = 1;
+                                    while used_names.contains(&name) {
+                                        name = format!("{}{}", base, suffix);
+                                        suffix += 1;
+                                    }
+                                    used_names.insert(name.clone());
+                                    name
+                                }
+                            })
+                            .clone();
+                        (value.clone(), name)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        if functions.iter().all(Vec::is_empty) {
+            return;
+        }
+
+        let mut hoist = HoistGlobals::new(functions);
+        hoist.begin_function();
+        ScopePostVisitor::visit_block(block, &mut hoist);
+        hoist.end_function(block);
+    }
+}
+
+impl RuleConfiguration for HoistRepeatedGlobalsToLocals {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "minimum_occurrences" => {
+                    self.minimum_occurrences = value.expect_usize(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        HOIST_REPEATED_GLOBALS_TO_LOCALS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if self.minimum_occurrences != DEFAULT_MINIMUM_OCCURRENCES {
+            properties.insert(
+                "minimum_occurrences".to_owned(),
+                RulePropertyValue::Usize(self.minimum_occurrences),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> HoistRepeatedGlobalsToLocals {
+        HoistRepeatedGlobalsToLocals::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "hoist_repeated_globals_to_locals"
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_minimum_occurrences() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_minimum_occurrences(10));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "hoist_repeated_globals_to_locals",
+          "minimum_occurrences": 10
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_minimum_occurrences_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'hoist_repeated_globals_to_locals',
+            minimum_occurrences: 'many',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unsigned integer expected for field 'minimum_occurrences' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'hoist_repeated_globals_to_locals',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
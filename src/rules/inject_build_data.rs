@@ -0,0 +1,418 @@
+use crate::frontend::DarkluaResult;
+use crate::nodes::{Block, Expression, StringExpression};
+use crate::process::{to_expression, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, Rule, RuleConfiguration, RuleConfigurationError, RuleProcessResult, RuleProperties,
+    RulePropertyValue,
+};
+use crate::DarkluaError;
+
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::inject_value::ValueInjection;
+use super::{
+    verify_property_collisions, verify_required_any_properties, verify_required_properties,
+};
+
+/// Information about the current git commit that can be injected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) enum GitValue {
+    Commit,
+    ShortCommit,
+    Tag,
+}
+
+impl GitValue {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "commit" => Some(Self::Commit),
+            "short_commit" => Some(Self::ShortCommit),
+            "tag" => Some(Self::Tag),
+            _ => None,
+        }
+    }
+
+    fn arguments(&self) -> &'static [&'static str] {
+        match self {
+            Self::Commit => &["rev-parse", "HEAD"],
+            Self::ShortCommit => &["rev-parse", "--short", "HEAD"],
+            Self::Tag => &["describe", "--tags", "--abbrev=0"],
+        }
+    }
+
+    pub(crate) fn read(&self, location: &Path) -> DarkluaResult<String> {
+        let arguments = self.arguments();
+
+        if cfg!(target_arch = "wasm32") {
+            return Err(DarkluaError::custom(format!(
+                "unable to run `git {}`: commands cannot run from WebAssembly",
+                arguments.join(" ")
+            )));
+        }
+
+        let location = if location.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            location
+        };
+
+        let output = Command::new("git")
+            .args(arguments)
+            .current_dir(location)
+            .output()
+            .map_err(|err| DarkluaError::custom(format!("unable to run git: {}", err)))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        } else {
+            Err(DarkluaError::custom(format!(
+                "`git {}` failed: {}",
+                arguments.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))
+        }
+    }
+}
+
+/// The git information used by the rules of a configuration, read once each time
+/// files are processed (so that it is up to date in watch mode).
+#[derive(Debug, Default)]
+pub(crate) struct GitValues {
+    location: PathBuf,
+    values: HashMap<GitValue, Result<String, String>>,
+}
+
+impl GitValues {
+    /// Reads the git information used by the given rules, from the given location.
+    pub(crate) fn read<'a>(
+        rules: impl Iterator<Item = &'a dyn Rule>,
+        location: impl Into<PathBuf>,
+    ) -> Self {
+        let location = location.into();
+        let mut values = HashMap::new();
+
+        for git_value in rules.filter_map(get_git_value) {
+            values
+                .entry(git_value)
+                .or_insert_with(|| git_value.read(&location).map_err(|err| err.to_string()));
+        }
+
+        Self { location, values }
+    }
+
+    /// Returns the git information read for the given location.
+    pub(crate) fn get(
+        &self,
+        git_value: GitValue,
+        location: &Path,
+    ) -> Option<Result<String, String>> {
+        if self.location == location {
+            self.values.get(&git_value).cloned()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the values sorted by kind, to include them in the hash of a configuration.
+    pub(crate) fn sorted_values(&self) -> Vec<(GitValue, &Result<String, String>)> {
+        let mut values: Vec<_> = self
+            .values
+            .iter()
+            .map(|(git_value, result)| (*git_value, result))
+            .collect();
+        values.sort_by_key(|(git_value, _)| *git_value);
+        values
+    }
+}
+
+fn get_git_value(rule: &dyn Rule) -> Option<GitValue> {
+    if rule.get_name() != INJECT_BUILD_DATA_RULE_NAME {
+        return None;
+    }
+
+    match rule.serialize_to_properties().get("git") {
+        Some(RulePropertyValue::String(value)) => GitValue::parse(value),
+        _ => None,
+    }
+}
+
+/// Where the injected value comes from.
+#[derive(Debug, Clone, PartialEq)]
+enum BuildSource {
+    Env(String),
+    File {
+        path: PathBuf,
+        field: Option<String>,
+    },
+    Git(GitValue),
+}
+
+fn get_json_field<'a>(value: &'a serde_json::Value, field: &str) -> Option<&'a serde_json::Value> {
+    field
+        .split('.')
+        .try_fold(value, |value, segment| match value {
+            serde_json::Value::Array(array) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| array.get(index)),
+            _ => value.get(segment),
+        })
+}
+
+pub const INJECT_BUILD_DATA_RULE_NAME: &str = "inject_build_data";
+
+/// A rule to replace global variables with build data read while processing files:
+/// an environment variable, a value from a JSON file or git information.
+#[derive(Debug, Default)]
+pub struct InjectBuildData {
+    identifier: String,
+    source: Option<BuildSource>,
+    default_value: Option<Expression>,
+    original_properties: RuleProperties,
+}
+
+impl InjectBuildData {
+    fn read_file(
+        &self,
+        path: &Path,
+        field: Option<&str>,
+        context: &Context,
+    ) -> DarkluaResult<Expression> {
+        let path = context.project_location().join(path);
+        context.add_file_dependency(path.clone());
+
+        let content = context.resources().get(&path)?;
+        let data = json5::from_str::<serde_json::Value>(&content).map_err(|err| {
+            DarkluaError::from(err).context(format!("unable to parse `{}`", path.display()))
+        })?;
+
+        let data = if let Some(field) = field {
+            get_json_field(&data, field).ok_or_else(|| {
+                DarkluaError::custom(format!(
+                    "unable to find field `{}` in `{}`",
+                    field,
+                    path.display()
+                ))
+            })?
+        } else {
+            &data
+        };
+
+        to_expression(data).map_err(DarkluaError::from)
+    }
+
+    fn resolve_value(&self, context: &Context) -> Result<Expression, String> {
+        let result = match &self.source {
+            None => return Ok(Expression::nil()),
+            Some(BuildSource::Env(variable_name)) => match env::var(variable_name) {
+                Ok(value) => Ok(StringExpression::from_value(value).into()),
+                Err(err) => Err(format!("environment variable `{}`: {}", variable_name, err)),
+            },
+            Some(BuildSource::File { path, field }) => self
+                .read_file(path, field.as_deref(), context)
+                .map_err(|err| err.to_string()),
+            Some(BuildSource::Git(git_value)) => context
+                .git_value(*git_value)
+                .map(|value| StringExpression::from_value(value).into()),
+        };
+
+        match (result, &self.default_value) {
+            (Ok(value), _) => Ok(value),
+            (Err(err), Some(default_value)) => {
+                log::warn!(
+                    "unable to read the value of `{}` ({}). The rule `{}` will use the default value",
+                    self.identifier,
+                    err,
+                    INJECT_BUILD_DATA_RULE_NAME,
+                );
+                Ok(default_value.clone())
+            }
+            (Err(err), None) => Err(format!(
+                "unable to read the value of `{}`: {}",
+                self.identifier, err
+            )),
+        }
+    }
+}
+
+impl Rule for InjectBuildData {
+    fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+        let value = self.resolve_value(context)?;
+        let mut processor = ValueInjection::new(&self.identifier, value);
+        ScopeVisitor::visit_block(block, &mut processor);
+        Ok(())
+    }
+}
+
+impl RuleConfiguration for InjectBuildData {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_required_properties(&properties, &["identifier"])?;
+        verify_required_any_properties(&properties, &["env", "file", "git"])?;
+        verify_property_collisions(&properties, &["env", "file", "git"])?;
+        if properties.contains_key("field") {
+            verify_required_properties(&properties, &["file"])?;
+        }
+
+        let mut field = None;
+
+        self.original_properties = properties.clone();
+
+        for (key, value) in properties {
+            match key.as_str() {
+                "identifier" => {
+                    self.identifier = value.expect_string(&key)?;
+                }
+                "default_value" => {
+                    if let Some(expr) = value.into_expression() {
+                        self.default_value = Some(expr);
+                    } else {
+                        return Err(RuleConfigurationError::UnexpectedValueType(key));
+                    }
+                }
+                "env" => {
+                    self.source = Some(BuildSource::Env(value.expect_string(&key)?));
+                }
+                "file" => {
+                    self.source = Some(BuildSource::File {
+                        path: PathBuf::from(value.expect_string(&key)?),
+                        field: None,
+                    });
+                }
+                "field" => {
+                    field = Some(value.expect_string(&key)?);
+                }
+                "git" => {
+                    let git_value = value.expect_string(&key)?;
+                    self.source = Some(BuildSource::Git(GitValue::parse(&git_value).ok_or_else(
+                        || RuleConfigurationError::UnexpectedValue {
+                            property: key,
+                            message: format!(
+                                "invalid value `{}` (must be `commit`, `short_commit` or `tag`)",
+                                git_value
+                            ),
+                        },
+                    )?));
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        if let Some(BuildSource::File {
+            field: file_field, ..
+        }) = &mut self.source
+        {
+            *file_field = field;
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        INJECT_BUILD_DATA_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        self.original_properties.clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use insta::assert_json_snapshot;
+
+    #[test]
+    fn serialize_rule_with_git_value() {
+        let rule: Box<dyn Rule> =
+            json5::from_str("{ rule: 'inject_build_data', identifier: 'COMMIT', git: 'commit' }")
+                .unwrap();
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "inject_build_data",
+          "git": "commit",
+          "identifier": "COMMIT"
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_without_source_property_should_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inject_build_data',
+            identifier: 'VERSION',
+        }"#,
+        );
+
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"missing one field from `env`, `file` and `git` at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_env_and_file_properties_should_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inject_build_data',
+            identifier: 'VERSION',
+            env: 'VERSION',
+            file: 'build.json',
+        }"#,
+        );
+
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"the fields `env` and `file` cannot be defined together at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_field_without_file_property_should_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inject_build_data',
+            identifier: 'VERSION',
+            git: 'tag',
+            field: 'version',
+        }"#,
+        );
+
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"missing required field 'file' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_git_value_should_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inject_build_data',
+            identifier: 'COMMIT',
+            git: 'branch',
+        }"#,
+        );
+
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'git': invalid value `branch` (must be `commit`, `short_commit` or `tag`) at line 1 column 1");
+    }
+
+    #[test]
+    fn git_values_only_read_values_used_by_rules() {
+        let rules: Vec<Box<dyn Rule>> = json5::from_str(
+            r#"[
+            'remove_spaces',
+            { rule: 'inject_build_data', identifier: 'COMMIT', git: 'short_commit' },
+            { rule: 'inject_build_data', identifier: 'VERSION', file: 'build.json' },
+        ]"#,
+        )
+        .unwrap();
+
+        let git_values = GitValues::read(rules.iter().map(AsRef::as_ref), "");
+
+        assert_eq!(
+            git_values
+                .sorted_values()
+                .into_iter()
+                .map(|(git_value, _)| git_value)
+                .collect::<Vec<_>>(),
+            vec![GitValue::ShortCommit]
+        );
+    }
+}
@@ -12,7 +12,7 @@ use std::{env, ops};
 use super::{verify_property_collisions, verify_required_properties};
 
 #[derive(Debug, Clone)]
-struct ValueInjection {
+pub(crate) struct ValueInjection {
     identifier: String,
     expression: Expression,
     identifier_tracker: IdentifierTracker,
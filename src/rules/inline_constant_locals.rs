@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+
+use crate::nodes::{
+    Block, Expression, Identifier, LocalAssignStatement, LocalFunctionStatement, Prefix, Statement,
+    UnaryExpression, UnaryOperator,
+};
+use crate::process::{
+    Evaluator, NodePostProcessor, NodePostVisitor, NodeProcessor, Scope, ScopePostVisitor,
+};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+
+pub const INLINE_CONSTANT_LOCALS_RULE_NAME: &str = "inline_constant_locals";
+
+const DEFAULT_MAX_STRING_LENGTH: usize = 32;
+
+fn is_literal(expression: &Expression) -> bool {
+    match expression {
+        Expression::False(_)
+        | Expression::Nil(_)
+        | Expression::Number(_)
+        | Expression::String(_)
+        | Expression::True(_) => true,
+        Expression::Unary(unary) => is_negative_number(unary),
+        _ => false,
+    }
+}
+
+fn is_negative_number(unary: &UnaryExpression) -> bool {
+    unary.operator() == UnaryOperator::Minus
+        && matches!(unary.get_expression(), Expression::Number(_))
+}
+
+#[derive(Debug)]
+struct Declaration {
+    value: Option<Expression>,
+    references: usize,
+    replaceable_references: usize,
+}
+
+/// Keeps track of the declarations in scope, identified by the order in which they
+/// are visited. Both passes of the rule visit the same declarations in the same order.
+#[derive(Debug, Default)]
+struct DeclarationScopes {
+    scopes: Vec<HashMap<String, usize>>,
+    count: usize,
+}
+
+impl DeclarationScopes {
+    fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn declare(&mut self, name: &str) -> usize {
+        if self.scopes.is_empty() {
+            self.push();
+        }
+
+        let index = self.count;
+        self.count += 1;
+        self.scopes
+            .last_mut()
+            .expect("a scope should exist")
+            .insert(name.to_owned(), index);
+        index
+    }
+
+    fn resolve(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+    }
+}
+
+#[derive(Default)]
+struct CollectConstants {
+    scopes: DeclarationScopes,
+    declarations: Vec<Declaration>,
+    single_local_assign: bool,
+    evaluator: Evaluator,
+}
+
+impl CollectConstants {
+    fn add(&mut self, name: &str, value: Option<Expression>) {
+        self.scopes.declare(name);
+        self.declarations.push(Declaration {
+            value,
+            references: 0,
+            replaceable_references: 0,
+        });
+    }
+
+    fn into_constants(self, max_string_length: usize) -> Vec<Option<Expression>> {
+        self.declarations
+            .into_iter()
+            .map(|declaration| {
+                if declaration.references != declaration.replaceable_references {
+                    return None;
+                }
+                let value = declaration.value?;
+
+                match &value {
+                    Expression::String(string)
+                        if string.get_value().len() > max_string_length
+                            && declaration.references > 1 =>
+                    {
+                        None
+                    }
+                    _ => Some(value),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Scope for CollectConstants {
+    fn push(&mut self) {
+        self.scopes.push();
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.add(identifier, None);
+    }
+
+    fn insert_self(&mut self) {
+        self.add("self", None);
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, value: Option<&mut Expression>) {
+        let constant = value
+            .filter(|value| self.single_local_assign && is_literal(value))
+            .and_then(|value| self.evaluator.evaluate(value).to_expression());
+
+        self.add(identifier, constant);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.add(function.get_name(), None);
+    }
+}
+
+impl NodeProcessor for CollectConstants {
+    fn process_local_assign_statement(&mut self, statement: &mut LocalAssignStatement) {
+        self.single_local_assign = statement.variables_len() == 1 && statement.values_len() == 1;
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::Identifier(identifier) = expression {
+            if let Some(index) = self.scopes.resolve(identifier.get_name()) {
+                self.declarations[index].replaceable_references += 1;
+            }
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        if let Prefix::Identifier(identifier) = prefix {
+            if let Some(index) = self.scopes.resolve(identifier.get_name()) {
+                self.declarations[index].replaceable_references += 1;
+            }
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if let Some(index) = self.scopes.resolve(identifier.get_name()) {
+            self.declarations[index].references += 1;
+        }
+    }
+}
+
+impl NodePostProcessor for CollectConstants {}
+
+#[derive(Debug, Default)]
+struct BlockStatements {
+    next_index: usize,
+    removed: Vec<usize>,
+}
+
+struct InlineConstants {
+    scopes: DeclarationScopes,
+    constants: Vec<Option<Expression>>,
+    blocks: Vec<BlockStatements>,
+}
+
+impl InlineConstants {
+    fn new(constants: Vec<Option<Expression>>) -> Self {
+        Self {
+            scopes: DeclarationScopes::default(),
+            constants,
+            blocks: Vec::new(),
+        }
+    }
+
+    fn get_constant(&self, identifier: &Identifier) -> Option<&Expression> {
+        self.scopes
+            .resolve(identifier.get_name())
+            .and_then(|index| self.constants.get(index))
+            .and_then(Option::as_ref)
+    }
+}
+
+impl Scope for InlineConstants {
+    fn push(&mut self) {
+        self.scopes.push();
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.scopes.declare(identifier);
+    }
+
+    fn insert_self(&mut self) {
+        self.scopes.declare("self");
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
+        let index = self.scopes.declare(identifier);
+
+        if matches!(self.constants.get(index), Some(Some(_))) {
+            // the values of constant declarations do not contain any block, so the
+            // current block is the one containing the local assignment
+            if let Some(block) = self.blocks.last_mut() {
+                block.removed.push(block.next_index - 1);
+            }
+        }
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        self.scopes.declare(function.get_name());
+    }
+}
+
+impl NodeProcessor for InlineConstants {
+    fn process_block(&mut self, _: &mut Block) {
+        self.blocks.push(BlockStatements::default());
+    }
+
+    fn process_statement(&mut self, _: &mut Statement) {
+        if let Some(block) = self.blocks.last_mut() {
+            block.next_index += 1;
+        }
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::Identifier(identifier) = expression {
+            if let Some(value) = self.get_constant(identifier) {
+                *expression = value.clone();
+            }
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        if let Prefix::Identifier(identifier) = prefix {
+            if let Some(value) = self.get_constant(identifier) {
+                *prefix = Prefix::from(value.clone());
+            }
+        }
+    }
+}
+
+impl NodePostProcessor for InlineConstants {
+    fn process_after_block(&mut self, block: &mut Block) {
+        let Some(statements) = self.blocks.pop() else {
+            return;
+        };
+
+        if statements.removed.is_empty() {
+            return;
+        }
+
+        let mut index = 0;
+        block.filter_statements(|_| {
+            let keep = !statements.removed.contains(&index);
+            index += 1;
+            keep
+        });
+    }
+}
+
+/// A rule that replaces local variables assigned to a literal value and never
+/// reassigned with their value, and removes their declaration.
+#[derive(Debug, PartialEq, Eq)]
+pub struct InlineConstantLocals {
+    max_string_length: usize,
+}
+
+impl Default for InlineConstantLocals {
+    fn default() -> Self {
+        Self {
+            max_string_length: DEFAULT_MAX_STRING_LENGTH,
+        }
+    }
+}
+
+impl InlineConstantLocals {
+    /// Sets the length of the longest string that can be inlined in more than one
+    /// place.
+    pub fn with_max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+}
+
+impl FlawlessRule for InlineConstantLocals {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut collect = CollectConstants::default();
+        ScopePostVisitor::visit_block(block, &mut collect);
+
+        let constants = collect.into_constants(self.max_string_length);
+
+        if constants.iter().all(Option::is_none) {
+            return;
+        }
+
+        let mut inline = InlineConstants::new(constants);
+        ScopePostVisitor::visit_block(block, &mut inline);
+    }
+}
+
+impl RuleConfiguration for InlineConstantLocals {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "max_string_length" => {
+                    self.max_string_length = value.expect_usize(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        INLINE_CONSTANT_LOCALS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if self.max_string_length != DEFAULT_MAX_STRING_LENGTH {
+            properties.insert(
+                "max_string_length".to_owned(),
+                RulePropertyValue::Usize(self.max_string_length),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> InlineConstantLocals {
+        InlineConstantLocals::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "inline_constant_locals"
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_max_string_length() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_max_string_length(8));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "inline_constant_locals",
+          "max_string_length": 8
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_max_string_length_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inline_constant_locals',
+            max_string_length: 'long',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unsigned integer expected for field 'max_string_length' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'inline_constant_locals',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,297 @@
+use crate::nodes::{
+    BinaryExpression, BinaryOperator, Block, Expression, FieldExpression, FunctionCall, Identifier,
+    UnaryOperator,
+};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+pub const LOWER_MATH_OPERATIONS_RULE_NAME: &str = "lower_math_operations";
+
+const MATH_LIBRARY: &str = "math";
+const MATH_SQRT_NAME: &str = "sqrt";
+
+const SQUARE_ROOT_PROPERTY: &str = "square_root";
+const SQUARE_PROPERTY: &str = "square";
+const DIVISION_PROPERTY: &str = "division";
+
+fn get_number(expression: &Expression) -> Option<f64> {
+    match expression {
+        Expression::Number(number) => Some(number.compute_value()),
+        Expression::Parenthese(parenthese) => get_number(parenthese.inner_expression()),
+        Expression::Unary(unary) if unary.operator() == UnaryOperator::Minus => {
+            get_number(unary.get_expression()).map(|value| -value)
+        }
+        _ => None,
+    }
+}
+
+fn remove_parentheses(expression: &Expression) -> &Expression {
+    match expression {
+        Expression::Parenthese(parenthese) => remove_parentheses(parenthese.inner_expression()),
+        _ => expression,
+    }
+}
+
+/// Returns the reciprocal of a power of two (like `0.25` for `4`), which can
+/// replace a division by a multiplication without changing the result.
+fn get_exact_reciprocal(divider: f64) -> Option<f64> {
+    if !divider.is_finite() || divider == 0.0 || divider.abs() == 1.0 {
+        return None;
+    }
+
+    let reciprocal = 1.0 / divider;
+
+    (reciprocal.is_normal() && is_power_of_two(divider.abs())).then_some(reciprocal)
+}
+
+fn is_power_of_two(value: f64) -> bool {
+    let bits = value.to_bits();
+    // a normal power of two has an empty mantissa
+    value.is_normal() && bits & ((1 << 52) - 1) == 0
+}
+
+struct Processor<'a> {
+    rule: &'a LowerMathOperations,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl<'a> Processor<'a> {
+    fn new(rule: &'a LowerMathOperations) -> Self {
+        Self {
+            rule,
+            identifier_tracker: IdentifierTracker::default(),
+        }
+    }
+
+    fn lower_binary(&self, binary: &BinaryExpression) -> Option<Expression> {
+        match binary.operator() {
+            BinaryOperator::Caret => {
+                let exponent = get_number(binary.right())?;
+
+                if self.rule.square_root
+                    && exponent == 0.5
+                    && !self.is_identifier_used(MATH_LIBRARY)
+                {
+                    Some(
+                        FunctionCall::from_prefix(FieldExpression::new(
+                            Identifier::new(MATH_LIBRARY),
+                            MATH_SQRT_NAME,
+                        ))
+                        // `math.sqrt` ignores any extra values, so truncating
+                        // the value with parentheses is not needed
+                        .with_argument(remove_parentheses(binary.left()).clone())
+                        .into(),
+                    )
+                } else if self.rule.square
+                    && exponent == 2.0
+                    && matches!(binary.left(), Expression::Identifier(_))
+                {
+                    // the value is only duplicated when reading it again is free
+                    Some(
+                        BinaryExpression::new(
+                            BinaryOperator::Asterisk,
+                            binary.left().clone(),
+                            binary.left().clone(),
+                        )
+                        .into(),
+                    )
+                } else {
+                    None
+                }
+            }
+            BinaryOperator::Slash if self.rule.division => {
+                let reciprocal = get_exact_reciprocal(get_number(binary.right())?)?;
+
+                Some(
+                    BinaryExpression::new(
+                        BinaryOperator::Asterisk,
+                        binary.left().clone(),
+                        Expression::from(reciprocal),
+                    )
+                    .into(),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+impl std::ops::Deref for Processor<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl std::ops::DerefMut for Processor<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor<'_> {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::Binary(binary) = expression {
+            if let Some(lowered) = self.lower_binary(binary) {
+                *expression = lowered;
+            }
+        }
+    }
+}
+
+/// A rule that replaces math operations with faster equivalents, like `x ^ 0.5`
+/// with `math.sqrt(x)` or `x ^ 2` with `x * x`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LowerMathOperations {
+    square_root: bool,
+    square: bool,
+    division: bool,
+}
+
+impl Default for LowerMathOperations {
+    fn default() -> Self {
+        Self {
+            square_root: true,
+            square: true,
+            division: true,
+        }
+    }
+}
+
+impl LowerMathOperations {
+    /// Enables or disables converting `x ^ 0.5` into `math.sqrt(x)`.
+    pub fn with_square_root(mut self, value: bool) -> Self {
+        self.square_root = value;
+        self
+    }
+
+    /// Enables or disables converting `x ^ 2` into `x * x`.
+    pub fn with_square(mut self, value: bool) -> Self {
+        self.square = value;
+        self
+    }
+
+    /// Enables or disables converting divisions by a power of two (like `x / 2`)
+    /// into multiplications (like `x * 0.5`).
+    pub fn with_division(mut self, value: bool) -> Self {
+        self.division = value;
+        self
+    }
+}
+
+impl FlawlessRule for LowerMathOperations {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor::new(self);
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for LowerMathOperations {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                SQUARE_ROOT_PROPERTY => {
+                    self.square_root = value.expect_bool(&key)?;
+                }
+                SQUARE_PROPERTY => {
+                    self.square = value.expect_bool(&key)?;
+                }
+                DIVISION_PROPERTY => {
+                    self.division = value.expect_bool(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        LOWER_MATH_OPERATIONS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if !self.square_root {
+            properties.insert(SQUARE_ROOT_PROPERTY.to_owned(), false.into());
+        }
+        if !self.square {
+            properties.insert(SQUARE_PROPERTY.to_owned(), false.into());
+        }
+        if !self.division {
+            properties.insert(DIVISION_PROPERTY.to_owned(), false.into());
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> LowerMathOperations {
+        LowerMathOperations::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""lower_math_operations""###);
+    }
+
+    #[test]
+    fn serialize_rule_with_disabled_patterns() {
+        let rule: Box<dyn Rule> = Box::new(
+            new_rule()
+                .with_square_root(false)
+                .with_square(false)
+                .with_division(false),
+        );
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "lower_math_operations",
+          "division": false,
+          "square": false,
+          "square_root": false
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'lower_math_operations',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+
+    #[test]
+    fn reciprocal_of_powers_of_two() {
+        assert_eq!(get_exact_reciprocal(2.0), Some(0.5));
+        assert_eq!(get_exact_reciprocal(-4.0), Some(-0.25));
+        assert_eq!(get_exact_reciprocal(0.5), Some(2.0));
+        assert_eq!(get_exact_reciprocal(1024.0), Some(1.0 / 1024.0));
+    }
+
+    #[test]
+    fn no_reciprocal_for_other_numbers() {
+        assert_eq!(get_exact_reciprocal(3.0), None);
+        assert_eq!(get_exact_reciprocal(1.0), None);
+        assert_eq!(get_exact_reciprocal(0.0), None);
+        assert_eq!(get_exact_reciprocal(f64::INFINITY), None);
+        assert_eq!(get_exact_reciprocal(f64::NAN), None);
+    }
+}
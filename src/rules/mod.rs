@@ -9,15 +9,27 @@ pub mod bundle;
 mod call_parens;
 mod compute_expression;
 mod configuration_error;
+mod convert_bit32_to_operators;
+mod convert_bitwise_operators_to_bit32;
 mod convert_index_to_field;
 mod convert_luau_number;
+mod convert_numeric_for_to_optimized_form;
 mod convert_require;
 mod convert_square_root_call;
+mod create_compound_assignment;
+mod disable_directives;
 mod empty_do;
 mod filter_early_return;
+mod filtered_rule;
+mod flatten_nested_do_blocks;
+mod freeze_module_exports;
 mod global_function_to_assign;
 mod group_local;
+mod hoist_repeated_globals_to_locals;
+mod inject_build_data;
 mod inject_value;
+mod inline_constant_locals;
+mod lower_math_operations;
 mod method_def;
 mod no_local_function;
 mod remove_assertions;
@@ -25,20 +37,33 @@ mod remove_call_match;
 mod remove_comments;
 mod remove_compound_assign;
 mod remove_continue;
+mod remove_debug_calls;
 mod remove_debug_profiling;
 mod remove_floor_division;
+mod remove_generalized_iteration;
 mod remove_if_expression;
 mod remove_interpolated_string;
 mod remove_method_call;
 mod remove_nil_declarations;
+mod remove_redundant_parentheses;
+mod remove_redundant_return;
 mod remove_spaces;
+mod remove_spread_table_packing;
+mod remove_type_function_calls;
 mod remove_types;
+mod remove_unreachable_code;
+mod remove_unused_function_parameters;
 mod remove_unused_variable;
+mod rename_globals;
+mod rename_project_symbols;
 mod rename_variables;
 mod replace_referenced_tokens;
 pub(crate) mod require;
 mod rule_property;
+mod rule_registry;
 mod shift_token_line;
+mod sort_requires;
+mod string_obfuscation;
 mod unused_if_branch;
 mod unused_while;
 
@@ -46,40 +71,65 @@ pub use append_text_comment::*;
 pub use call_parens::*;
 pub use compute_expression::*;
 pub use configuration_error::RuleConfigurationError;
+pub use convert_bit32_to_operators::*;
+pub use convert_bitwise_operators_to_bit32::*;
 pub use convert_index_to_field::*;
 pub use convert_luau_number::*;
+pub use convert_numeric_for_to_optimized_form::*;
 pub use convert_require::*;
 pub use convert_square_root_call::*;
+pub use create_compound_assignment::*;
+pub(crate) use disable_directives::*;
 pub use empty_do::*;
 pub use filter_early_return::*;
+pub use filtered_rule::*;
+pub use flatten_nested_do_blocks::*;
+pub use freeze_module_exports::*;
 pub use global_function_to_assign::*;
 pub use group_local::*;
+pub use hoist_repeated_globals_to_locals::*;
+pub use inject_build_data::*;
 pub use inject_value::*;
+pub use inline_constant_locals::*;
+pub use lower_math_operations::*;
 pub use method_def::*;
 pub use no_local_function::*;
 pub use remove_assertions::*;
 pub use remove_comments::*;
 pub use remove_compound_assign::*;
 pub use remove_continue::*;
+pub use remove_debug_calls::*;
 pub use remove_debug_profiling::*;
 pub use remove_floor_division::*;
+pub use remove_generalized_iteration::*;
 pub use remove_if_expression::*;
 pub use remove_interpolated_string::*;
 pub use remove_method_call::*;
 pub use remove_nil_declarations::*;
+pub use remove_redundant_parentheses::*;
+pub use remove_redundant_return::*;
 pub use remove_spaces::*;
+pub use remove_spread_table_packing::*;
+pub use remove_type_function_calls::*;
 pub use remove_types::*;
+pub use remove_unreachable_code::*;
+pub use remove_unused_function_parameters::*;
 pub use remove_unused_variable::*;
+pub use rename_globals::*;
+pub use rename_project_symbols::*;
 pub use rename_variables::*;
 pub(crate) use replace_referenced_tokens::*;
 pub use require::PathRequireMode;
 pub use rule_property::*;
+pub use rule_registry::{get_custom_rule_names, register_rule};
 pub(crate) use shift_token_line::*;
+pub use sort_requires::*;
+pub use string_obfuscation::*;
 pub use unused_if_branch::*;
 pub use unused_while::*;
 
 use crate::nodes::Block;
-use crate::Resources;
+use crate::{utils, DarkluaError, LuaTarget, Resources};
 
 use serde::de::{self, MapAccess, Visitor};
 use serde::ser::SerializeMap;
@@ -88,6 +138,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::sync::Arc;
 
 /// A builder for creating a [`Context`] with optional configuration.
 ///
@@ -100,6 +151,9 @@ pub struct ContextBuilder<'a, 'resources, 'code> {
     original_code: &'code str,
     blocks: HashMap<PathBuf, &'a Block>,
     project_location: Option<PathBuf>,
+    output_path: Option<PathBuf>,
+    target: LuaTarget,
+    git_values: Option<Arc<GitValues>>,
 }
 
 impl<'a, 'resources, 'code> ContextBuilder<'a, 'resources, 'code> {
@@ -115,6 +169,9 @@ impl<'a, 'resources, 'code> ContextBuilder<'a, 'resources, 'code> {
             original_code,
             blocks: Default::default(),
             project_location: None,
+            output_path: None,
+            target: LuaTarget::default(),
+            git_values: None,
         }
     }
 
@@ -124,6 +181,25 @@ impl<'a, 'resources, 'code> ContextBuilder<'a, 'resources, 'code> {
         self
     }
 
+    /// Sets the path where the current file is written once processed.
+    pub fn with_output_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output_path = Some(path.into());
+        self
+    }
+
+    /// Sets the Lua dialect that the processed code must be compatible with.
+    pub fn with_target(mut self, target: LuaTarget) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Sets the git information read for the current process, so that rules do not
+    /// need to read it again for each file.
+    pub(crate) fn with_git_values(mut self, git_values: Arc<GitValues>) -> Self {
+        self.git_values = Some(git_values);
+        self
+    }
+
     /// Builds the final context with all configured options.
     pub fn build(self) -> Context<'a, 'resources, 'code> {
         Context {
@@ -132,7 +208,13 @@ impl<'a, 'resources, 'code> ContextBuilder<'a, 'resources, 'code> {
             original_code: self.original_code,
             blocks: self.blocks,
             project_location: self.project_location,
+            output_path: self.output_path,
+            target: self.target,
+            git_values: self.git_values,
             dependencies: Default::default(),
+            output_chunks: Default::default(),
+            output_files: Default::default(),
+            luau_aliases: Default::default(),
         }
     }
 
@@ -153,7 +235,13 @@ pub struct Context<'a, 'resources, 'code> {
     original_code: &'code str,
     blocks: HashMap<PathBuf, &'a Block>,
     project_location: Option<PathBuf>,
+    output_path: Option<PathBuf>,
+    target: LuaTarget,
+    git_values: Option<Arc<GitValues>>,
     dependencies: std::cell::RefCell<Vec<PathBuf>>,
+    output_chunks: std::cell::RefCell<Vec<(PathBuf, Block)>>,
+    output_files: std::cell::RefCell<Vec<(PathBuf, String)>>,
+    luau_aliases: std::cell::OnceCell<Option<HashMap<String, PathBuf>>>,
 }
 
 impl Context<'_, '_, '_> {
@@ -167,6 +255,20 @@ impl Context<'_, '_, '_> {
         self.path.as_ref()
     }
 
+    /// Returns the path where the current file is written once processed. When no
+    /// output path is defined, the file is processed in place and this is the same as
+    /// [`Context::current_path`].
+    pub fn output_path(&self) -> &Path {
+        self.output_path
+            .as_deref()
+            .unwrap_or_else(|| self.current_path())
+    }
+
+    /// Returns the Lua dialect that the processed code must be compatible with.
+    pub fn target(&self) -> LuaTarget {
+        self.target
+    }
+
     /// Adds a file dependency to the context.
     ///
     /// This is used to track which files are required by the current file being processed.
@@ -184,6 +286,48 @@ impl Context<'_, '_, '_> {
         self.dependencies.into_inner().into_iter()
     }
 
+    /// Adds a block to write at the given path, next to the output of the current file
+    /// (like the chunks of a bundle).
+    pub(crate) fn add_output_chunk(&self, path: PathBuf, block: Block) {
+        log::trace!("add output chunk {}", path.display());
+        self.output_chunks.borrow_mut().push((path, block));
+    }
+
+    /// Takes the blocks added with [`Context::add_output_chunk`].
+    pub(crate) fn take_output_chunks(&self) -> Vec<(PathBuf, Block)> {
+        self.output_chunks.take()
+    }
+
+    /// Adds a file to write once the current rule is applied (like the declaration
+    /// files of `remove_types`). Errors while writing the file are reported by the
+    /// worker that processes the file.
+    pub(crate) fn add_output_file(&self, path: PathBuf, content: String) {
+        log::trace!("add output file {}", path.display());
+        self.output_files.borrow_mut().push((path, content));
+    }
+
+    /// Takes the files added with [`Context::add_output_file`].
+    pub(crate) fn take_output_files(&self) -> Vec<(PathBuf, String)> {
+        self.output_files.take()
+    }
+
+    /// Returns the aliases defined by the `.luaurc` files that apply to the current file.
+    ///
+    /// Alias names are prefixed with `@` (like `@pkg`) and their paths are resolved
+    /// from the directory of the configuration file that defines them. When `.luaurc`
+    /// files are nested, the closest file overrides the aliases of its parents.
+    /// Returns `None` if no `.luaurc` file is found.
+    pub fn luau_aliases(&self) -> Result<Option<&HashMap<String, PathBuf>>, DarkluaError> {
+        if let Some(aliases) = self.luau_aliases.get() {
+            return Ok(aliases.as_ref());
+        }
+
+        let aliases = utils::find_luau_configuration(self.current_path(), self.resources)?
+            .map(|config| config.aliases);
+
+        Ok(self.luau_aliases.get_or_init(|| aliases).as_ref())
+    }
+
     fn resources(&self) -> &Resources {
         self.resources
     }
@@ -192,6 +336,17 @@ impl Context<'_, '_, '_> {
         self.original_code
     }
 
+    /// Returns the git information of the project, using the values read for the
+    /// current process when available.
+    fn git_value(&self, git_value: GitValue) -> Result<String, String> {
+        let location = self.project_location();
+
+        self.git_values
+            .as_ref()
+            .and_then(|git_values| git_values.get(git_value, location))
+            .unwrap_or_else(|| git_value.read(location).map_err(|err| err.to_string()))
+    }
+
     fn project_location(&self) -> &Path {
         self.project_location.as_deref().unwrap_or_else(|| {
             let source = self.current_path();
@@ -212,8 +367,9 @@ pub type RuleProcessResult = Result<(), String>;
 /// Defines an interface for rules that can transform Lua blocks.
 ///
 /// Rules implement this trait to define how they process blocks and how their configuration
-/// can be serialized and deserialized.
-pub trait Rule: RuleConfiguration + fmt::Debug {
+/// can be serialized and deserialized. Rules must be `Send` and `Sync` because files are
+/// processed in parallel.
+pub trait Rule: RuleConfiguration + fmt::Debug + Send + Sync {
     /// Processes the given block to apply the rule's transformation.
     ///
     /// Returns `Ok(())` if the transformation was successful, or an error message if it failed.
@@ -260,7 +416,7 @@ pub trait FlawlessRule {
     fn flawless_process(&self, block: &mut Block, context: &Context);
 }
 
-impl<T: FlawlessRule + RuleConfiguration + fmt::Debug> Rule for T {
+impl<T: FlawlessRule + RuleConfiguration + fmt::Debug + Send + Sync> Rule for T {
     fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
         self.flawless_process(block, context);
         Ok(())
@@ -296,34 +452,57 @@ pub fn get_all_rule_names() -> Vec<&'static str> {
     vec![
         APPEND_TEXT_COMMENT_RULE_NAME,
         COMPUTE_EXPRESSIONS_RULE_NAME,
+        CONVERT_BIT32_TO_OPERATORS_RULE_NAME,
+        CONVERT_BITWISE_OPERATORS_TO_BIT32_RULE_NAME,
         CONVERT_FUNCTION_TO_ASSIGNMENT_RULE_NAME,
         CONVERT_INDEX_TO_FIELD_RULE_NAME,
         CONVERT_LOCAL_FUNCTION_TO_ASSIGN_RULE_NAME,
         CONVERT_LUAU_NUMBER_RULE_NAME,
+        CONVERT_NUMERIC_FOR_TO_OPTIMIZED_FORM_RULE_NAME,
         CONVERT_REQUIRE_RULE_NAME,
+        CONVERT_REQUIRE_TO_IMPORT_MAP_RULE_NAME,
         CONVERT_SQUARE_ROOT_CALL_RULE_NAME,
+        CREATE_COMPOUND_ASSIGNMENT_RULE_NAME,
         FILTER_AFTER_EARLY_RETURN_RULE_NAME,
+        FLATTEN_NESTED_DO_BLOCKS_RULE_NAME,
+        FREEZE_MODULE_EXPORTS_RULE_NAME,
         GROUP_LOCAL_ASSIGNMENT_RULE_NAME,
+        HOIST_REPEATED_GLOBALS_TO_LOCALS_RULE_NAME,
         INJECT_GLOBAL_VALUE_RULE_NAME,
+        INJECT_BUILD_DATA_RULE_NAME,
+        INLINE_CONSTANT_LOCALS_RULE_NAME,
+        LOWER_MATH_OPERATIONS_RULE_NAME,
         REMOVE_ASSERTIONS_RULE_NAME,
         REMOVE_COMMENTS_RULE_NAME,
         REMOVE_COMPOUND_ASSIGNMENT_RULE_NAME,
+        REMOVE_DEBUG_CALLS_RULE_NAME,
         REMOVE_DEBUG_PROFILING_RULE_NAME,
         REMOVE_EMPTY_DO_RULE_NAME,
         REMOVE_FLOOR_DIVISION_RULE_NAME,
         REMOVE_FUNCTION_CALL_PARENS_RULE_NAME,
+        REMOVE_GENERALIZED_ITERATION_RULE_NAME,
         REMOVE_INTERPOLATED_STRING_RULE_NAME,
         REMOVE_METHOD_CALL_RULE_NAME,
         REMOVE_METHOD_DEFINITION_RULE_NAME,
         REMOVE_NIL_DECLARATION_RULE_NAME,
         REMOVE_SPACES_RULE_NAME,
+        REMOVE_SPREAD_TABLE_PACKING_RULE_NAME,
+        REMOVE_TYPE_FUNCTION_CALLS_RULE_NAME,
         REMOVE_TYPES_RULE_NAME,
+        REMOVE_UNREACHABLE_CODE_RULE_NAME,
         REMOVE_UNUSED_IF_BRANCH_RULE_NAME,
         REMOVE_UNUSED_VARIABLE_RULE_NAME,
+        REMOVE_UNUSED_FUNCTION_PARAMETERS_RULE_NAME,
         REMOVE_UNUSED_WHILE_RULE_NAME,
+        RENAME_GLOBALS_RULE_NAME,
+        RENAME_PROJECT_SYMBOLS_RULE_NAME,
         RENAME_VARIABLES_RULE_NAME,
         REMOVE_IF_EXPRESSION_RULE_NAME,
         REMOVE_CONTINUE_RULE_NAME,
+        STRING_OBFUSCATION_RULE_NAME,
+        REMOVE_REDUNDANT_PARENTHESES_RULE_NAME,
+        REMOVE_REDUNDANT_RETURN_RULE_NAME,
+        SORT_REQUIRES_RULE_NAME,
     ]
 }
 
@@ -334,37 +513,71 @@ impl FromStr for Box<dyn Rule> {
         let rule: Box<dyn Rule> = match string {
             APPEND_TEXT_COMMENT_RULE_NAME => Box::<AppendTextComment>::default(),
             COMPUTE_EXPRESSIONS_RULE_NAME => Box::<ComputeExpression>::default(),
+            CONVERT_BIT32_TO_OPERATORS_RULE_NAME => Box::<ConvertBit32ToOperators>::default(),
+            CONVERT_BITWISE_OPERATORS_TO_BIT32_RULE_NAME => {
+                Box::<ConvertBitwiseOperatorsToBit32>::default()
+            }
             CONVERT_FUNCTION_TO_ASSIGNMENT_RULE_NAME => Box::<ConvertFunctionToAssign>::default(),
             CONVERT_INDEX_TO_FIELD_RULE_NAME => Box::<ConvertIndexToField>::default(),
             CONVERT_LOCAL_FUNCTION_TO_ASSIGN_RULE_NAME => {
                 Box::<ConvertLocalFunctionToAssign>::default()
             }
             CONVERT_LUAU_NUMBER_RULE_NAME => Box::<ConvertLuauNumber>::default(),
+            CONVERT_NUMERIC_FOR_TO_OPTIMIZED_FORM_RULE_NAME => {
+                Box::<ConvertNumericForToOptimizedForm>::default()
+            }
             CONVERT_REQUIRE_RULE_NAME => Box::<ConvertRequire>::default(),
+            CONVERT_REQUIRE_TO_IMPORT_MAP_RULE_NAME => Box::<ConvertRequireToImportMap>::default(),
             CONVERT_SQUARE_ROOT_CALL_RULE_NAME => Box::<ConvertSquareRootCall>::default(),
+            CREATE_COMPOUND_ASSIGNMENT_RULE_NAME => Box::<CreateCompoundAssignment>::default(),
             FILTER_AFTER_EARLY_RETURN_RULE_NAME => Box::<FilterAfterEarlyReturn>::default(),
+            FLATTEN_NESTED_DO_BLOCKS_RULE_NAME => Box::<FlattenNestedDoBlocks>::default(),
+            FREEZE_MODULE_EXPORTS_RULE_NAME => Box::<FreezeModuleExports>::default(),
             GROUP_LOCAL_ASSIGNMENT_RULE_NAME => Box::<GroupLocalAssignment>::default(),
+            HOIST_REPEATED_GLOBALS_TO_LOCALS_RULE_NAME => {
+                Box::<HoistRepeatedGlobalsToLocals>::default()
+            }
             INJECT_GLOBAL_VALUE_RULE_NAME => Box::<InjectGlobalValue>::default(),
+            INJECT_BUILD_DATA_RULE_NAME => Box::<InjectBuildData>::default(),
+            INLINE_CONSTANT_LOCALS_RULE_NAME => Box::<InlineConstantLocals>::default(),
+            LOWER_MATH_OPERATIONS_RULE_NAME => Box::<LowerMathOperations>::default(),
             REMOVE_ASSERTIONS_RULE_NAME => Box::<RemoveAssertions>::default(),
             REMOVE_COMMENTS_RULE_NAME => Box::<RemoveComments>::default(),
             REMOVE_COMPOUND_ASSIGNMENT_RULE_NAME => Box::<RemoveCompoundAssignment>::default(),
+            REMOVE_DEBUG_CALLS_RULE_NAME => Box::<RemoveDebugCalls>::default(),
             REMOVE_DEBUG_PROFILING_RULE_NAME => Box::<RemoveDebugProfiling>::default(),
             REMOVE_EMPTY_DO_RULE_NAME => Box::<RemoveEmptyDo>::default(),
             REMOVE_FLOOR_DIVISION_RULE_NAME => Box::<RemoveFloorDivision>::default(),
             REMOVE_FUNCTION_CALL_PARENS_RULE_NAME => Box::<RemoveFunctionCallParens>::default(),
+            REMOVE_GENERALIZED_ITERATION_RULE_NAME => Box::<RemoveGeneralizedIteration>::default(),
             REMOVE_INTERPOLATED_STRING_RULE_NAME => Box::<RemoveInterpolatedString>::default(),
             REMOVE_METHOD_CALL_RULE_NAME => Box::<RemoveMethodCall>::default(),
             REMOVE_METHOD_DEFINITION_RULE_NAME => Box::<RemoveMethodDefinition>::default(),
             REMOVE_NIL_DECLARATION_RULE_NAME => Box::<RemoveNilDeclaration>::default(),
+            REMOVE_REDUNDANT_PARENTHESES_RULE_NAME => Box::<RemoveRedundantParentheses>::default(),
+            REMOVE_REDUNDANT_RETURN_RULE_NAME => Box::<RemoveRedundantReturn>::default(),
             REMOVE_SPACES_RULE_NAME => Box::<RemoveSpaces>::default(),
+            REMOVE_SPREAD_TABLE_PACKING_RULE_NAME => Box::<RemoveSpreadTablePacking>::default(),
+            REMOVE_TYPE_FUNCTION_CALLS_RULE_NAME => Box::<RemoveTypeFunctionCalls>::default(),
             REMOVE_TYPES_RULE_NAME => Box::<RemoveTypes>::default(),
+            REMOVE_UNREACHABLE_CODE_RULE_NAME => Box::<RemoveUnreachableCode>::default(),
             REMOVE_UNUSED_IF_BRANCH_RULE_NAME => Box::<RemoveUnusedIfBranch>::default(),
             REMOVE_UNUSED_VARIABLE_RULE_NAME => Box::<RemoveUnusedVariable>::default(),
+            REMOVE_UNUSED_FUNCTION_PARAMETERS_RULE_NAME => {
+                Box::<RemoveUnusedFunctionParameters>::default()
+            }
             REMOVE_UNUSED_WHILE_RULE_NAME => Box::<RemoveUnusedWhile>::default(),
+            RENAME_GLOBALS_RULE_NAME => Box::<RenameGlobals>::default(),
+            RENAME_PROJECT_SYMBOLS_RULE_NAME => Box::<RenameProjectSymbols>::default(),
             RENAME_VARIABLES_RULE_NAME => Box::<RenameVariables>::default(),
             REMOVE_IF_EXPRESSION_RULE_NAME => Box::<RemoveIfExpression>::default(),
             REMOVE_CONTINUE_RULE_NAME => Box::<RemoveContinue>::default(),
-            _ => return Err(format!("invalid rule name: {}", string)),
+            STRING_OBFUSCATION_RULE_NAME => Box::<StringObfuscation>::default(),
+            SORT_REQUIRES_RULE_NAME => Box::<SortRequires>::default(),
+            _ => match rule_registry::create_custom_rule(string) {
+                Some(rule) => rule,
+                None => return Err(format!("invalid rule name: {}", string)),
+            },
         };
 
         Ok(rule)
@@ -453,6 +666,10 @@ impl<'de> Deserialize<'de> for Box<dyn Rule> {
                     let mut rule: Self::Value =
                         FromStr::from_str(&rule_name).map_err(de::Error::custom)?;
 
+                    if has_filter_properties(&properties) {
+                        rule = Box::new(FilteredRule::new(rule));
+                    }
+
                     rule.configure(properties).map_err(de::Error::custom)?;
 
                     Ok(rule)
@@ -559,6 +776,42 @@ mod test {
         );
     }
 
+    mod context {
+        use super::*;
+
+        #[test]
+        fn luau_aliases_without_luaurc_is_none() {
+            utils::clear_luau_configuration_cache();
+            let resources = Resources::from_memory();
+            resources.write("src/init.lua", "").unwrap();
+            let context = ContextBuilder::new("src/init.lua", &resources, "").build();
+
+            assert_eq!(context.luau_aliases().unwrap(), None);
+        }
+
+        #[test]
+        fn luau_aliases_merge_nested_luaurc() {
+            utils::clear_luau_configuration_cache();
+            let resources = Resources::from_memory();
+            resources
+                .write(
+                    ".luaurc",
+                    r#"{ "aliases": { "pkg": "packages", "lib": "lib" } }"#,
+                )
+                .unwrap();
+            resources
+                .write("src/.luaurc", r#"{ "aliases": { "lib": "./modules" } }"#)
+                .unwrap();
+            let context = ContextBuilder::new("src/init.lua", &resources, "").build();
+
+            let aliases = context.luau_aliases().unwrap().unwrap();
+
+            assert_eq!(aliases.len(), 2);
+            assert_eq!(aliases.get("@pkg"), Some(&PathBuf::from("packages")));
+            assert_eq!(aliases.get("@lib"), Some(&PathBuf::from("src/modules")));
+        }
+    }
+
     #[test]
     fn get_all_rule_names_are_deserializable() {
         for name in get_all_rule_names() {
@@ -2,9 +2,10 @@ use std::collections::HashMap;
 use std::iter::{self, FromIterator};
 
 use crate::nodes::{Block, Expression, FunctionCall, Prefix, TupleArguments};
-use crate::process::{IdentifierTracker, NodeVisitor, ScopeVisitor};
+use crate::process::{Evaluator, IdentifierTracker, NodeVisitor, ScopeVisitor};
 use crate::rules::{
     Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
 };
 
 use super::remove_call_match::{CallMatch, RemoveFunctionCallProcessor};
@@ -13,30 +14,75 @@ const ASSERT_FUNCTION_NAME: &str = "assert";
 
 pub const REMOVE_ASSERTIONS_RULE_NAME: &str = "remove_assertions";
 
-/// A rule that removes `assert` calls.
+/// A rule that removes `assert` calls (and calls to other assertion-like functions).
 #[derive(Debug, PartialEq, Eq)]
 pub struct RemoveAssertions {
     preserve_args_side_effects: bool,
+    keep_condition_only: bool,
+    functions: Vec<String>,
 }
 
 impl Default for RemoveAssertions {
     fn default() -> Self {
         Self {
             preserve_args_side_effects: true,
+            keep_condition_only: false,
+            functions: Vec::new(),
         }
     }
 }
 
-struct AssertMatcher;
+impl RemoveAssertions {
+    /// Removes the calls to the given function, in addition to the calls to `assert`.
+    pub fn with_function(mut self, name: impl Into<String>) -> Self {
+        self.functions.push(name.into());
+        self
+    }
 
-impl CallMatch<()> for AssertMatcher {
-    fn matches(&self, identifiers: &IdentifierTracker, prefix: &Prefix) -> bool {
-        if identifiers.is_identifier_used(ASSERT_FUNCTION_NAME) {
-            return false;
+    /// When used as expressions, replace assertions with their condition (the first
+    /// argument) instead of all their arguments.
+    pub fn keep_condition_only(mut self) -> Self {
+        self.keep_condition_only = true;
+        self
+    }
+}
+
+struct AssertMatcher<'a> {
+    functions: &'a [String],
+    keep_condition_only: bool,
+    preserve_args_side_effects: bool,
+    evaluator: Evaluator,
+}
+
+impl<'a> AssertMatcher<'a> {
+    fn new(rule: &'a RemoveAssertions) -> Self {
+        Self {
+            functions: &rule.functions,
+            keep_condition_only: rule.keep_condition_only,
+            preserve_args_side_effects: rule.preserve_args_side_effects,
+            evaluator: Evaluator::default(),
         }
+    }
 
+    fn can_keep_condition_only(&self, messages: &[Expression]) -> bool {
+        self.keep_condition_only
+            && (!self.preserve_args_side_effects
+                || messages
+                    .iter()
+                    .all(|message| !self.evaluator.has_side_effects(message)))
+    }
+}
+
+impl CallMatch<()> for AssertMatcher<'_> {
+    fn matches(&self, identifiers: &IdentifierTracker, prefix: &Prefix) -> bool {
         match prefix {
-            Prefix::Identifier(identifier) => identifier.get_name() == ASSERT_FUNCTION_NAME,
+            Prefix::Identifier(identifier) => {
+                let name = identifier.get_name();
+
+                (name == ASSERT_FUNCTION_NAME
+                    || self.functions.iter().any(|function| function == name))
+                    && !identifiers.is_identifier_used(name)
+            }
             _ => false,
         }
     }
@@ -46,7 +92,7 @@ impl CallMatch<()> for AssertMatcher {
         call: &FunctionCall,
         mappings: &HashMap<&'static str, String>,
     ) -> Option<Expression> {
-        let expressions = call.get_arguments().clone().to_expressions();
+        let mut expressions = call.get_arguments().clone().to_expressions();
 
         Some(match expressions.len() {
             0 => Expression::nil(),
@@ -54,6 +100,17 @@ impl CallMatch<()> for AssertMatcher {
                 .into_iter()
                 .next()
                 .expect("at least one expression is expected"),
+            _ if self.can_keep_condition_only(&expressions[1..]) => {
+                let condition = expressions.swap_remove(0);
+                match condition {
+                    // the condition was truncated to a single value when followed
+                    // by other arguments
+                    Expression::Call(_) | Expression::VariableArguments(_) => {
+                        condition.in_parentheses()
+                    }
+                    _ => condition,
+                }
+            }
             _ => FunctionCall::from_name(
                 mappings
                     .get("select")
@@ -68,14 +125,18 @@ impl CallMatch<()> for AssertMatcher {
     }
 
     fn reserve_globals(&self) -> impl Iterator<Item = &'static str> {
+        // `select` is never needed when conditions are always kept alone
         iter::once("select")
+            .filter(move |_| !self.keep_condition_only || self.preserve_args_side_effects)
     }
 }
 
 impl FlawlessRule for RemoveAssertions {
     fn flawless_process(&self, block: &mut Block, _: &Context) {
-        let mut processor =
-            RemoveFunctionCallProcessor::new(self.preserve_args_side_effects, AssertMatcher);
+        let mut processor = RemoveFunctionCallProcessor::new(
+            self.preserve_args_side_effects,
+            AssertMatcher::new(self),
+        );
         ScopeVisitor::visit_block(block, &mut processor);
 
         if let Some(statement) = processor.extract_reserved_globals() {
@@ -91,6 +152,12 @@ impl RuleConfiguration for RemoveAssertions {
                 "preserve_arguments_side_effects" => {
                     self.preserve_args_side_effects = value.expect_bool(&key)?;
                 }
+                "keep_condition_only" => {
+                    self.keep_condition_only = value.expect_bool(&key)?;
+                }
+                "functions" => {
+                    self.functions = value.expect_string_list(&key)?;
+                }
                 _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
             }
         }
@@ -108,6 +175,15 @@ impl RuleConfiguration for RemoveAssertions {
         if !self.preserve_args_side_effects {
             properties.insert("preserve_arguments_side_effects".to_owned(), false.into());
         }
+        if self.keep_condition_only {
+            properties.insert("keep_condition_only".to_owned(), true.into());
+        }
+        if !self.functions.is_empty() {
+            properties.insert(
+                "functions".to_owned(),
+                RulePropertyValue::StringList(self.functions.clone()),
+            );
+        }
 
         properties
     }
@@ -135,6 +211,7 @@ mod test {
     fn serialize_rule_without_side_effects() {
         let rule: Box<dyn Rule> = Box::new(RemoveAssertions {
             preserve_args_side_effects: false,
+            ..Default::default()
         });
 
         assert_json_snapshot!(rule, @r###"
@@ -145,6 +222,38 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn serialize_rule_with_functions_and_keep_condition_only() {
+        let rule: Box<dyn Rule> = Box::new(
+            new_rule()
+                .with_function("expect")
+                .with_function("invariant")
+                .keep_condition_only(),
+        );
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_assertions",
+          "functions": [
+            "expect",
+            "invariant"
+          ],
+          "keep_condition_only": true
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_functions_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_assertions',
+            functions: 'expect',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"list of string expected for field 'functions' at line 1 column 1");
+    }
+
     #[test]
     fn configure_with_extra_field_error() {
         let result = json5::from_str::<Box<dyn Rule>>(
@@ -4,6 +4,7 @@ use crate::nodes::*;
 use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
 use crate::rules::{
     Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
 };
 
 #[derive(Debug, Default)]
@@ -237,20 +238,30 @@ impl NodeProcessor for RemoveCommentProcessor {
 #[derive(Debug)]
 pub(crate) struct FilterCommentProcessor<'a> {
     original_code: &'a str,
-    except: &'a Vec<Regex>,
+    except: &'a [Regex],
+    preserve_markers: &'a [String],
 }
 
 impl<'a> FilterCommentProcessor<'a> {
-    pub(crate) fn new(original_code: &'a str, except: &'a Vec<Regex>) -> Self {
+    pub(crate) fn new(
+        original_code: &'a str,
+        except: &'a [Regex],
+        preserve_markers: &'a [String],
+    ) -> Self {
         Self {
             original_code,
             except,
+            preserve_markers,
         }
     }
 
     fn ignore_trivia(&self, trivia: &Trivia) -> bool {
         let content = trivia.read(self.original_code);
         self.except.iter().any(|pattern| pattern.is_match(content))
+            || self
+                .preserve_markers
+                .iter()
+                .any(|marker| content.contains(marker.as_str()))
     }
 }
 
@@ -485,9 +496,15 @@ impl NodeProcessor for FilterCommentProcessor<'_> {
 pub const REMOVE_COMMENTS_RULE_NAME: &str = "remove_comments";
 
 /// A rule that removes comments associated with AST nodes.
+///
+/// Comments can be preserved by matching them with regular expressions (`except`),
+/// by looking for a marker substring (`preserve_markers`) or by keeping the comments
+/// located before the first token of the file (`keep_banner`).
 #[derive(Debug, Default)]
 pub struct RemoveComments {
     except: Vec<Regex>,
+    preserve_markers: Vec<String>,
+    keep_banner: bool,
 }
 
 impl RemoveComments {
@@ -507,17 +524,60 @@ impl RemoveComments {
 
         self
     }
+
+    /// Preserves comments that contain the given marker (like `@preserve`).
+    pub fn with_preserve_marker(mut self, marker: impl Into<String>) -> Self {
+        self.preserve_markers.push(marker.into());
+        self
+    }
+
+    /// Preserves the comments located before the first token of the file
+    /// (usually a license banner).
+    pub fn keep_banner(mut self) -> Self {
+        self.keep_banner = true;
+        self
+    }
+
+    fn take_banner(&self, block: &mut Block) -> Option<Vec<Trivia>> {
+        // blocks without tokens do not have any comments to preserve, and getting
+        // the first token of such block would create new tokens
+        if !self.keep_banner || block.get_tokens().is_none() {
+            return None;
+        }
+
+        let banner: Vec<_> = block.mutate_first_token().drain_leading_trivia().collect();
+
+        Some(banner)
+    }
+
+    fn restore_banner(block: &mut Block, banner: Vec<Trivia>) {
+        let first_token = block.mutate_first_token();
+
+        for (index, trivia) in banner.into_iter().enumerate() {
+            first_token.insert_leading_trivia(index, trivia);
+        }
+    }
 }
 
 impl FlawlessRule for RemoveComments {
     fn flawless_process(&self, block: &mut Block, context: &Context) {
-        if self.except.is_empty() {
+        let banner = self.take_banner(block);
+
+        if self.except.is_empty() && self.preserve_markers.is_empty() {
             let mut processor = RemoveCommentProcessor::default();
             DefaultVisitor::visit_block(block, &mut processor);
         } else {
-            let mut processor = FilterCommentProcessor::new(context.original_code(), &self.except);
+            let mut processor = FilterCommentProcessor::new(
+                context.original_code(),
+                &self.except,
+                &self.preserve_markers,
+            );
             DefaultVisitor::visit_block(block, &mut processor);
         }
+
+        if let Some(banner) = banner {
+            Self::restore_banner(block, banner);
+        }
     }
 }
 
@@ -528,6 +588,12 @@ impl RuleConfiguration for RemoveComments {
                 "except" => {
                     self.except = value.expect_regex_list(&key)?;
                 }
+                "preserve_markers" => {
+                    self.preserve_markers = value.expect_string_list(&key)?;
+                }
+                "keep_banner" => {
+                    self.keep_banner = value.expect_bool(&key)?;
+                }
                 _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
             }
         }
@@ -540,7 +606,32 @@ impl RuleConfiguration for RemoveComments {
     }
 
     fn serialize_to_properties(&self) -> RuleProperties {
-        RuleProperties::new()
+        let mut properties = RuleProperties::new();
+
+        if !self.except.is_empty() {
+            properties.insert(
+                "except".to_owned(),
+                RulePropertyValue::StringList(
+                    self.except
+                        .iter()
+                        .map(|regex| regex.as_str().to_owned())
+                        .collect(),
+                ),
+            );
+        }
+
+        if !self.preserve_markers.is_empty() {
+            properties.insert(
+                "preserve_markers".to_owned(),
+                RulePropertyValue::StringList(self.preserve_markers.clone()),
+            );
+        }
+
+        if self.keep_banner {
+            properties.insert("keep_banner".to_owned(), true.into());
+        }
+
+        properties
     }
 }
 
@@ -566,6 +657,36 @@ mod test {
         assert_json_snapshot!(rule, @r###""remove_comments""###);
     }
 
+    #[test]
+    fn serialize_rule_with_exception() {
+        let rule: Box<dyn Rule> = Box::new(new_rule().with_exception("^--!"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_comments",
+          "except": [
+            "^--!"
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_banner_and_preserve_marker() {
+        let rule: Box<dyn Rule> =
+            Box::new(new_rule().keep_banner().with_preserve_marker("@preserve"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_comments",
+          "keep_banner": true,
+          "preserve_markers": [
+            "@preserve"
+          ]
+        }
+        "###);
+    }
+
     #[test]
     fn configure_with_extra_field_error() {
         let result = json5::from_str::<Box<dyn Rule>>(
@@ -0,0 +1,319 @@
+use std::ops;
+
+use crate::nodes::{Block, DoStatement, Prefix, Statement};
+use crate::process::utils::is_valid_identifier;
+use crate::process::{Evaluator, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+use crate::utils::{expressions_as_statement, preserve_arguments_side_effects};
+
+const DEFAULT_FUNCTIONS: [&str; 3] = ["print", "warn", "debug.traceback"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum SideEffectsStrategy {
+    /// Replace the call with its arguments that have side effects.
+    #[default]
+    Hoist,
+    /// Keep the call when any of its arguments has side effects.
+    Keep,
+}
+
+/// Returns the names that lead to the called function (like `["debug", "traceback"]`),
+/// when the prefix is made of an identifier followed by fields.
+fn get_prefix_path(prefix: &Prefix) -> Option<Vec<&str>> {
+    match prefix {
+        Prefix::Identifier(identifier) => Some(vec![identifier.get_name().as_str()]),
+        Prefix::Field(field) => {
+            let mut path = get_prefix_path(field.get_prefix())?;
+            path.push(field.get_field().get_name());
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+struct Processor<'a> {
+    functions: &'a [Vec<String>],
+    strategy: SideEffectsStrategy,
+    evaluator: Evaluator,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl<'a> Processor<'a> {
+    fn new(functions: &'a [Vec<String>], strategy: SideEffectsStrategy) -> Self {
+        Self {
+            functions,
+            strategy,
+            evaluator: Evaluator::default(),
+            identifier_tracker: IdentifierTracker::default(),
+        }
+    }
+
+    fn is_debug_function(&self, prefix: &Prefix) -> bool {
+        let path = match get_prefix_path(prefix) {
+            Some(path) => path,
+            None => return false,
+        };
+
+        if self.is_identifier_used(path[0]) {
+            return false;
+        }
+
+        self.functions.iter().any(|function| {
+            function.len() == path.len()
+                && function
+                    .iter()
+                    .zip(path.iter())
+                    .all(|(name, segment)| name == segment)
+        })
+    }
+}
+
+impl ops::Deref for Processor<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor<'_> {
+    fn process_statement(&mut self, statement: &mut Statement) {
+        let call = match statement {
+            Statement::Call(call) if !call.has_method() => call,
+            _ => return,
+        };
+
+        if !self.is_debug_function(call.get_prefix()) {
+            return;
+        }
+
+        let side_effects = preserve_arguments_side_effects(&self.evaluator, call.get_arguments());
+
+        *statement = if side_effects.is_empty() {
+            DoStatement::default().into()
+        } else {
+            match self.strategy {
+                SideEffectsStrategy::Hoist => expressions_as_statement(side_effects),
+                SideEffectsStrategy::Keep => return,
+            }
+        };
+    }
+}
+
+pub const REMOVE_DEBUG_CALLS_RULE_NAME: &str = "remove_debug_calls";
+
+/// A rule that removes calls to debugging functions (like `print`) used as statements.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RemoveDebugCalls {
+    functions: Vec<Vec<String>>,
+    strategy: SideEffectsStrategy,
+}
+
+impl Default for RemoveDebugCalls {
+    fn default() -> Self {
+        Self {
+            functions: DEFAULT_FUNCTIONS
+                .iter()
+                .map(|function| parse_function_path(function).expect("invalid default function"))
+                .collect(),
+            strategy: SideEffectsStrategy::default(),
+        }
+    }
+}
+
+fn parse_function_path(path: &str) -> Option<Vec<String>> {
+    let segments: Vec<String> = path.split('.').map(ToOwned::to_owned).collect();
+
+    segments
+        .iter()
+        .all(|segment| is_valid_identifier(segment))
+        .then_some(segments)
+}
+
+impl RemoveDebugCalls {
+    /// Replaces the functions to remove. A function can be a global name (like `print`)
+    /// or a path to a field of a global (like `debug.traceback`).
+    pub fn with_functions<I: IntoIterator<Item = T>, T: AsRef<str>>(
+        mut self,
+        functions: I,
+    ) -> Self {
+        self.functions = functions
+            .into_iter()
+            .map(|function| parse_function_path(function.as_ref()).expect("invalid function name"))
+            .collect();
+        self
+    }
+
+    fn has_default_functions(&self) -> bool {
+        self.functions.len() == DEFAULT_FUNCTIONS.len()
+            && self
+                .functions
+                .iter()
+                .zip(DEFAULT_FUNCTIONS.iter())
+                .all(|(function, default)| function.join(".") == *default)
+    }
+}
+
+impl FlawlessRule for RemoveDebugCalls {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        if self.functions.is_empty() {
+            return;
+        }
+
+        let mut processor = Processor::new(&self.functions, self.strategy);
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveDebugCalls {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "functions" => {
+                    self.functions = value
+                        .expect_string_list(&key)?
+                        .iter()
+                        .map(|function| {
+                            parse_function_path(function).ok_or_else(|| {
+                                RuleConfigurationError::UnexpectedValue {
+                                    property: "functions".to_owned(),
+                                    message: format!(
+                                        "invalid function name `{}` (expected an identifier or a path like `debug.traceback`)",
+                                        function
+                                    ),
+                                }
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "strategy" => {
+                    self.strategy = match value.expect_string(&key)?.as_str() {
+                        "hoist" => SideEffectsStrategy::Hoist,
+                        "keep" => SideEffectsStrategy::Keep,
+                        unexpected => {
+                            return Err(RuleConfigurationError::UnexpectedValue {
+                                property: "strategy".to_owned(),
+                                message: format!(
+                                    "invalid value `{}` (must be `hoist` or `keep`)",
+                                    unexpected
+                                ),
+                            })
+                        }
+                    };
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_DEBUG_CALLS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if !self.has_default_functions() {
+            properties.insert(
+                "functions".to_owned(),
+                RulePropertyValue::StringList(
+                    self.functions
+                        .iter()
+                        .map(|function| function.join("."))
+                        .collect(),
+                ),
+            );
+        }
+
+        match self.strategy {
+            SideEffectsStrategy::Hoist => {}
+            SideEffectsStrategy::Keep => {
+                properties.insert("strategy".to_owned(), "keep".into());
+            }
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveDebugCalls {
+        RemoveDebugCalls::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_debug_calls""###);
+    }
+
+    #[test]
+    fn serialize_rule_with_functions_and_keep_strategy() {
+        let rule: Box<dyn Rule> = Box::new(RemoveDebugCalls {
+            strategy: SideEffectsStrategy::Keep,
+            ..new_rule().with_functions(["print", "logger.debug"])
+        });
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_debug_calls",
+          "functions": [
+            "print",
+            "logger.debug"
+          ],
+          "strategy": "keep"
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_invalid_function_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_debug_calls',
+            functions: ['logger:debug'],
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'functions': invalid function name `logger:debug` (expected an identifier or a path like `debug.traceback`) at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_strategy_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_debug_calls',
+            strategy: 'remove',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'strategy': invalid value `remove` (must be `hoist` or `keep`) at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_debug_calls',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
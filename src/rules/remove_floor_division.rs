@@ -85,7 +85,7 @@ impl NodeProcessor for RemoveFloorDivisionProcessor {
 
 pub const REMOVE_FLOOR_DIVISION_RULE_NAME: &str = "remove_floor_division";
 
-/// A rule that removes interpolated strings.
+/// A rule that removes floor divisions (`//` and `//=`).
 #[derive(Debug, Default, PartialEq, Eq)]
 pub struct RemoveFloorDivision {}
 
@@ -0,0 +1,171 @@
+use std::mem;
+
+use crate::nodes::{
+    BinaryExpression, BinaryOperator, Block, Expression, FieldExpression, FunctionCall,
+    IfStatement, LocalAssignStatement, LocalFunctionStatement, Prefix, ReturnStatement, Statement,
+    StringExpression,
+};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    verify_no_rule_properties, Context, FlawlessRule, RuleConfiguration, RuleConfigurationError,
+    RuleProperties,
+};
+
+const ITERATOR_IDENTIFIER: &str = "__DARKLUA_ITER";
+
+#[derive(Default)]
+struct RemoveGeneralizedIterationProcessor {
+    define_iterator: bool,
+}
+
+impl NodeProcessor for RemoveGeneralizedIterationProcessor {
+    fn process_statement(&mut self, statement: &mut Statement) {
+        if let Statement::GenericFor(generic_for) = statement {
+            if generic_for.expressions_len() != 1 {
+                return;
+            }
+
+            let expression = generic_for
+                .iter_mut_expressions()
+                .next()
+                .expect("generic for should have one expression");
+
+            let value = mem::replace(expression, Expression::nil());
+            *expression = FunctionCall::from_name(ITERATOR_IDENTIFIER)
+                .with_argument(value)
+                .into();
+
+            self.define_iterator = true;
+        }
+    }
+}
+
+/// Builds the following helper function, which also returns the values following
+/// a value that is not a table (like the state of the iterator returned by a call
+/// to `pairs`):
+/// ```lua
+/// local function __DARKLUA_ITER(value, ...)
+///     if type(value) == 'table' then
+///         local metatable = getmetatable(value)
+///         if type(metatable) == 'table' and metatable.__iter then
+///             return metatable.__iter(value)
+///         end
+///         return next, value
+///     end
+///     return value, ...
+/// end
+/// ```
+fn build_iterator_function() -> LocalFunctionStatement {
+    const VALUE: &str = "value";
+    const METATABLE: &str = "metatable";
+
+    let is_table = |name: &str| {
+        BinaryExpression::new(
+            BinaryOperator::Equal,
+            FunctionCall::from_name("type").with_argument(Expression::identifier(name)),
+            StringExpression::from_value("table"),
+        )
+    };
+
+    let metatable_iter = || FieldExpression::new(Prefix::from_name(METATABLE), "__iter");
+
+    let use_iter_metamethod = IfStatement::create(
+        BinaryExpression::new(BinaryOperator::And, is_table(METATABLE), metatable_iter()),
+        Block::default().with_last_statement(ReturnStatement::one(
+            FunctionCall::from_prefix(metatable_iter())
+                .with_argument(Expression::identifier(VALUE)),
+        )),
+    );
+
+    let iterate_table = IfStatement::create(
+        is_table(VALUE),
+        Block::default()
+            .with_statement(
+                LocalAssignStatement::from_variable(METATABLE).with_value(
+                    FunctionCall::from_name("getmetatable")
+                        .with_argument(Expression::identifier(VALUE)),
+                ),
+            )
+            .with_statement(use_iter_metamethod)
+            .with_last_statement(ReturnStatement::new(vec![
+                Expression::identifier("next"),
+                Expression::identifier(VALUE),
+            ])),
+    );
+
+    LocalFunctionStatement::from_name(
+        ITERATOR_IDENTIFIER,
+        Block::default()
+            .with_statement(iterate_table)
+            .with_last_statement(ReturnStatement::new(vec![
+                Expression::identifier(VALUE),
+                Expression::variable_arguments(),
+            ])),
+    )
+    .with_parameter(VALUE)
+    .variadic()
+}
+
+pub const REMOVE_GENERALIZED_ITERATION_RULE_NAME: &str = "remove_generalized_iteration";
+
+/// A rule that converts generalized iteration (`for k, v in t do`) into code
+/// compatible with Lua 5.1.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveGeneralizedIteration {}
+
+impl FlawlessRule for RemoveGeneralizedIteration {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = RemoveGeneralizedIterationProcessor::default();
+        DefaultVisitor::visit_block(block, &mut processor);
+
+        if processor.define_iterator {
+            block.insert_statement(0, build_iterator_function());
+        }
+    }
+}
+
+impl RuleConfiguration for RemoveGeneralizedIteration {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_GENERALIZED_ITERATION_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveGeneralizedIteration {
+        RemoveGeneralizedIteration::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_generalized_iteration""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_generalized_iteration',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
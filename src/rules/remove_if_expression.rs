@@ -7,14 +7,29 @@ use crate::rules::{
     Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
 };
 
-use super::verify_no_rule_properties;
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum ConversionStrategy {
+    /// Uses `condition and result or else_result` only when the result is known to be
+    /// truthy and falls back to indexing tables otherwise.
+    #[default]
+    Safe,
+    /// Always uses `condition and result or else_result`.
+    Fast,
+}
 
-#[derive(Default)]
 struct Processor {
     evaluator: Evaluator,
+    strategy: ConversionStrategy,
 }
 
 impl Processor {
+    fn new(strategy: ConversionStrategy) -> Self {
+        Self {
+            evaluator: Evaluator::default(),
+            strategy,
+        }
+    }
+
     fn wrap_in_table(&self, expression: Expression) -> Expression {
         TableExpression::new(vec![TableEntry::from_value({
             if self.evaluator.can_return_multiple_values(&expression) {
@@ -32,11 +47,12 @@ impl Processor {
         result: Expression,
         else_result: Expression,
     ) -> Expression {
-        if self
-            .evaluator
-            .evaluate(&result)
-            .is_truthy()
-            .unwrap_or_default()
+        if self.strategy == ConversionStrategy::Fast
+            || self
+                .evaluator
+                .evaluate(&result)
+                .is_truthy()
+                .unwrap_or_default()
         {
             BinaryExpression::new(
                 BinaryOperator::Or,
@@ -87,20 +103,41 @@ impl NodeProcessor for Processor {
 
 pub const REMOVE_IF_EXPRESSION_RULE_NAME: &str = "remove_if_expression";
 
-/// A rule that removes trailing `nil` in local assignments.
+/// A rule that replaces if expressions with equivalent `and`/`or` expressions.
 #[derive(Debug, Default, PartialEq, Eq)]
-pub struct RemoveIfExpression {}
+pub struct RemoveIfExpression {
+    strategy: ConversionStrategy,
+}
 
 impl FlawlessRule for RemoveIfExpression {
     fn flawless_process(&self, block: &mut Block, _: &Context) {
-        let mut processor = Processor::default();
+        let mut processor = Processor::new(self.strategy);
         DefaultVisitor::visit_block(block, &mut processor);
     }
 }
 
 impl RuleConfiguration for RemoveIfExpression {
     fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
-        verify_no_rule_properties(&properties)?;
+        for (key, value) in properties {
+            match key.as_str() {
+                "strategy" => {
+                    self.strategy = match value.expect_string(&key)?.as_str() {
+                        "safe" => ConversionStrategy::Safe,
+                        "fast" => ConversionStrategy::Fast,
+                        unexpected => {
+                            return Err(RuleConfigurationError::UnexpectedValue {
+                                property: "strategy".to_owned(),
+                                message: format!(
+                                    "invalid value `{}` (must be `safe` or `fast`)",
+                                    unexpected
+                                ),
+                            })
+                        }
+                    };
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
 
         Ok(())
     }
@@ -110,7 +147,16 @@ impl RuleConfiguration for RemoveIfExpression {
     }
 
     fn serialize_to_properties(&self) -> RuleProperties {
-        RuleProperties::new()
+        let mut properties = RuleProperties::new();
+
+        match self.strategy {
+            ConversionStrategy::Safe => {}
+            ConversionStrategy::Fast => {
+                properties.insert("strategy".to_owned(), "fast".into());
+            }
+        }
+
+        properties
     }
 }
 
@@ -132,6 +178,20 @@ mod test {
         assert_json_snapshot!(rule, @r###""remove_if_expression""###);
     }
 
+    #[test]
+    fn serialize_rule_with_fast_strategy() {
+        let rule: Box<dyn Rule> = Box::new(RemoveIfExpression {
+            strategy: ConversionStrategy::Fast,
+        });
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_if_expression",
+          "strategy": "fast"
+        }
+        "###);
+    }
+
     #[test]
     fn configure_with_extra_field_error() {
         let result = json5::from_str::<Box<dyn Rule>>(
@@ -142,4 +202,15 @@ mod test {
         );
         insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
     }
+
+    #[test]
+    fn configure_with_invalid_strategy_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_if_expression',
+            strategy: 'function',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'strategy': invalid value `function` (must be `safe` or `fast`) at line 1 column 1");
+    }
 }
@@ -0,0 +1,281 @@
+use std::mem;
+
+use crate::nodes::{
+    Arguments, AssignStatement, BinaryExpression, Block, CompoundAssignStatement, Expression,
+    FunctionCall, GenericForStatement, IfExpression, IfStatement, IndexExpression, LastStatement,
+    LocalAssignStatement, NumericForStatement, Prefix, RepeatStatement, TableEntry,
+    TableExpression, UnaryExpression, WhileStatement,
+};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+/// Returns true when the expression can produce multiple values, which are truncated
+/// to the first value when the expression is wrapped in parentheses.
+fn is_multiple_values(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Call(_) | Expression::VariableArguments(_)
+    )
+}
+
+/// Returns true when the parentheses around the expression may be needed because of
+/// the operator precedence of the expression containing it.
+fn depends_on_precedence(expression: &Expression) -> bool {
+    matches!(
+        expression,
+        Expression::Binary(_) | Expression::Unary(_) | Expression::If(_) | Expression::TypeCast(_)
+    )
+}
+
+fn unwrap_parentheses(expression: &mut Expression) {
+    if let Expression::Parenthese(parenthese) = expression {
+        let inner = mem::replace(parenthese.mutate_inner_expression(), Expression::nil());
+        *expression = inner;
+    }
+}
+
+/// Removes the parentheses around an expression whose value is used on its own (like
+/// a condition or an assigned value). When `keep_truncation` is true, the parentheses
+/// truncating multiple values into a single value are kept.
+fn remove_value_parentheses(expression: &mut Expression, keep_truncation: bool) {
+    while let Expression::Parenthese(parenthese) = expression {
+        if keep_truncation && is_multiple_values(parenthese.inner_expression()) {
+            break;
+        }
+        unwrap_parentheses(expression);
+    }
+}
+
+/// Removes the parentheses around the expressions of a list, where only the last
+/// expression can expand into multiple values.
+fn remove_list_parentheses<'a>(expressions: impl Iterator<Item = &'a mut Expression>) {
+    let mut expressions = expressions.peekable();
+
+    while let Some(expression) = expressions.next() {
+        remove_value_parentheses(expression, expressions.peek().is_none());
+    }
+}
+
+fn remove_operand_parentheses(
+    expression: &mut Expression,
+    needs_parentheses: impl Fn(&Expression) -> bool,
+) {
+    while let Expression::Parenthese(parenthese) = expression {
+        let inner = parenthese.inner_expression();
+
+        let can_remove = match inner {
+            Expression::Binary(_) | Expression::Unary(_) => !needs_parentheses(inner),
+            Expression::If(_) | Expression::TypeCast(_) => false,
+            _ => true,
+        };
+
+        if !can_remove {
+            break;
+        }
+        unwrap_parentheses(expression);
+    }
+}
+
+#[derive(Default)]
+struct Processor;
+
+impl NodeProcessor for Processor {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        while let Expression::Parenthese(parenthese) = expression {
+            let inner = parenthese.inner_expression();
+
+            if is_multiple_values(inner) || depends_on_precedence(inner) {
+                break;
+            }
+            unwrap_parentheses(expression);
+        }
+    }
+
+    fn process_binary_expression(&mut self, binary: &mut BinaryExpression) {
+        let operator = binary.operator();
+
+        remove_operand_parentheses(binary.mutate_left(), |left| {
+            operator.left_needs_parentheses(left)
+        });
+        remove_operand_parentheses(binary.mutate_right(), |right| {
+            operator.right_needs_parentheses(right)
+        });
+    }
+
+    fn process_unary_expression(&mut self, unary: &mut UnaryExpression) {
+        remove_operand_parentheses(unary.mutate_expression(), |expression| match expression {
+            Expression::Binary(binary) => !binary.operator().precedes_unary_expression(),
+            _ => false,
+        });
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        while let Prefix::Parenthese(parenthese) = prefix {
+            let can_remove = matches!(
+                parenthese.inner_expression(),
+                Expression::Call(_)
+                    | Expression::Field(_)
+                    | Expression::Identifier(_)
+                    | Expression::Index(_)
+                    | Expression::Parenthese(_)
+            );
+
+            if !can_remove {
+                break;
+            }
+
+            let inner = mem::replace(parenthese.mutate_inner_expression(), Expression::nil());
+            *prefix = Prefix::from(inner);
+        }
+    }
+
+    fn process_index_expression(&mut self, index: &mut IndexExpression) {
+        remove_value_parentheses(index.mutate_index(), false);
+    }
+
+    fn process_table_expression(&mut self, table: &mut TableExpression) {
+        let mut entries = table.iter_mut_entries().peekable();
+
+        while let Some(entry) = entries.next() {
+            match entry {
+                TableEntry::Field(field) => remove_value_parentheses(field.mutate_value(), false),
+                TableEntry::Index(index) => {
+                    remove_value_parentheses(index.mutate_key(), false);
+                    remove_value_parentheses(index.mutate_value(), false);
+                }
+                TableEntry::Value(value) => {
+                    remove_value_parentheses(value, entries.peek().is_none())
+                }
+            }
+        }
+    }
+
+    fn process_if_expression(&mut self, if_expression: &mut IfExpression) {
+        remove_value_parentheses(if_expression.mutate_condition(), false);
+        remove_value_parentheses(if_expression.mutate_result(), false);
+
+        for branch in if_expression.iter_mut_branches() {
+            remove_value_parentheses(branch.mutate_condition(), false);
+            remove_value_parentheses(branch.mutate_result(), false);
+        }
+
+        remove_value_parentheses(if_expression.mutate_else_result(), false);
+    }
+
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        if let Arguments::Tuple(tuple) = call.mutate_arguments() {
+            remove_list_parentheses(tuple.iter_mut_values());
+        }
+    }
+
+    fn process_assign_statement(&mut self, assign: &mut AssignStatement) {
+        remove_list_parentheses(assign.iter_mut_values());
+    }
+
+    fn process_compound_assign_statement(&mut self, assign: &mut CompoundAssignStatement) {
+        remove_value_parentheses(assign.mutate_value(), false);
+    }
+
+    fn process_local_assign_statement(&mut self, assign: &mut LocalAssignStatement) {
+        remove_list_parentheses(assign.iter_mut_values());
+    }
+
+    fn process_generic_for_statement(&mut self, generic_for: &mut GenericForStatement) {
+        remove_list_parentheses(generic_for.iter_mut_expressions());
+    }
+
+    fn process_numeric_for_statement(&mut self, numeric_for: &mut NumericForStatement) {
+        remove_value_parentheses(numeric_for.mutate_start(), false);
+        remove_value_parentheses(numeric_for.mutate_end(), false);
+
+        if let Some(step) = numeric_for.mutate_step() {
+            remove_value_parentheses(step, false);
+        }
+    }
+
+    fn process_if_statement(&mut self, if_statement: &mut IfStatement) {
+        for branch in if_statement.mutate_branches().iter_mut() {
+            remove_value_parentheses(branch.mutate_condition(), false);
+        }
+    }
+
+    fn process_while_statement(&mut self, while_statement: &mut WhileStatement) {
+        remove_value_parentheses(while_statement.mutate_condition(), false);
+    }
+
+    fn process_repeat_statement(&mut self, repeat: &mut RepeatStatement) {
+        remove_value_parentheses(repeat.mutate_condition(), false);
+    }
+
+    fn process_last_statement(&mut self, statement: &mut LastStatement) {
+        if let LastStatement::Return(return_statement) = statement {
+            remove_list_parentheses(return_statement.iter_mut_expressions());
+        }
+    }
+}
+
+pub const REMOVE_REDUNDANT_PARENTHESES_RULE_NAME: &str = "remove_redundant_parentheses";
+
+/// A rule that removes parentheses that do not change the precedence of operations
+/// or truncate multiple values into a single value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveRedundantParentheses {}
+
+impl FlawlessRule for RemoveRedundantParentheses {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor;
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveRedundantParentheses {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_REDUNDANT_PARENTHESES_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveRedundantParentheses {
+        RemoveRedundantParentheses::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "remove_redundant_parentheses"
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_redundant_parentheses',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
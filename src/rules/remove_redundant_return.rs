@@ -0,0 +1,157 @@
+use std::mem;
+
+use crate::nodes::{
+    Block, Expression, FunctionExpression, FunctionStatement, LastStatement,
+    LocalFunctionStatement, ReturnStatement, Statement, TypeFunctionStatement,
+};
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+/// Returns true when the expression can produce multiple values, which are truncated
+/// to the first value when the expression is wrapped in parentheses.
+fn is_multiple_values(expression: &Expression) -> bool {
+    match expression {
+        Expression::Call(_) | Expression::VariableArguments(_) => true,
+        Expression::TypeCast(type_cast) => is_multiple_values(type_cast.get_expression()),
+        _ => false,
+    }
+}
+
+/// Removes the empty return statement at the end of a block where returning is the
+/// same as reaching the end of the block. Since the last statement of such a block
+/// also ends the function, the empty return statements at the end of the `do` and `if`
+/// statements placed at the end of the block are also removed.
+fn remove_final_empty_return(block: &mut Block) {
+    match block.get_last_statement() {
+        Some(LastStatement::Return(statement)) if statement.is_empty() => {
+            block.take_last_statement();
+        }
+        Some(_) => {}
+        None => match block.iter_mut_statements().last() {
+            Some(Statement::Do(do_statement)) => {
+                remove_final_empty_return(do_statement.mutate_block());
+            }
+            Some(Statement::If(if_statement)) => {
+                for branch_block in if_statement.mutate_all_blocks() {
+                    remove_final_empty_return(branch_block);
+                }
+            }
+            _ => {}
+        },
+    }
+}
+
+/// Converts `return (value)` into `return value` when the parentheses do not truncate
+/// multiple values into a single value.
+fn remove_return_parentheses(statement: &mut ReturnStatement) {
+    if statement.len() != 1 {
+        return;
+    }
+
+    let Some(expression) = statement.iter_mut_expressions().next() else {
+        return;
+    };
+
+    while let Expression::Parenthese(parenthese) = expression {
+        if is_multiple_values(parenthese.inner_expression()) {
+            break;
+        }
+        let inner = mem::replace(parenthese.mutate_inner_expression(), Expression::nil());
+        *expression = inner;
+    }
+}
+
+#[derive(Default)]
+struct Processor;
+
+impl NodeProcessor for Processor {
+    fn process_function_expression(&mut self, function: &mut FunctionExpression) {
+        remove_final_empty_return(function.mutate_block());
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        remove_final_empty_return(function.mutate_block());
+    }
+
+    fn process_local_function_statement(&mut self, function: &mut LocalFunctionStatement) {
+        remove_final_empty_return(function.mutate_block());
+    }
+
+    fn process_type_function(&mut self, function: &mut TypeFunctionStatement) {
+        remove_final_empty_return(function.mutate_block());
+    }
+
+    fn process_last_statement(&mut self, statement: &mut LastStatement) {
+        if let LastStatement::Return(statement) = statement {
+            remove_return_parentheses(statement);
+        }
+    }
+}
+
+pub const REMOVE_REDUNDANT_RETURN_RULE_NAME: &str = "remove_redundant_return";
+
+/// A rule that removes empty return statements at the end of functions and simplifies
+/// the parentheses around the returned value.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveRedundantReturn {}
+
+impl FlawlessRule for RemoveRedundantReturn {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        remove_final_empty_return(block);
+
+        let mut processor = Processor;
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveRedundantReturn {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_REDUNDANT_RETURN_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveRedundantReturn {
+        RemoveRedundantReturn::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "remove_redundant_return"
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_redundant_return',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,262 @@
+use std::ops;
+
+use crate::nodes::{
+    BinaryExpression, BinaryOperator, Block, Expression, FieldExpression, FunctionCall,
+    FunctionExpression, Identifier, LocalAssignStatement, Prefix, ReturnStatement,
+    StringExpression, TableExpression,
+};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    verify_no_rule_properties, Context, FlawlessRule, RuleConfiguration, RuleConfigurationError,
+    RuleProperties,
+};
+use crate::LuaTarget;
+
+const TABLE_LIBRARY: &str = "table";
+const PACK_IDENTIFIER: &str = "__DARKLUA_PACK";
+const UNPACK_IDENTIFIER: &str = "__DARKLUA_UNPACK";
+
+struct RemoveSpreadTablePackingProcessor {
+    shim_table_library: bool,
+    define_pack: bool,
+    define_unpack: bool,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl ops::Deref for RemoveSpreadTablePackingProcessor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for RemoveSpreadTablePackingProcessor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl RemoveSpreadTablePackingProcessor {
+    fn new(target: LuaTarget) -> Self {
+        Self {
+            // Lua 5.3 is the only target without the `unpack` global, but it
+            // provides both `table.pack` and `table.unpack`
+            shim_table_library: target != LuaTarget::Lua53,
+            define_pack: false,
+            define_unpack: false,
+            identifier_tracker: Default::default(),
+        }
+    }
+
+    fn get_table_function<'a>(&self, field: &'a FieldExpression) -> Option<&'a str> {
+        match field.get_prefix() {
+            Prefix::Identifier(identifier)
+                if identifier.get_name() == TABLE_LIBRARY
+                    && !self.is_identifier_used(TABLE_LIBRARY) =>
+            {
+                Some(field.get_field().get_name().as_str())
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts `table.pack(...).n` into `select("#", ...)`.
+    fn replace_packed_length(&self, field: &FieldExpression) -> Option<Expression> {
+        if field.get_field().get_name() != "n" {
+            return None;
+        }
+
+        let Prefix::Call(call) = field.get_prefix() else {
+            return None;
+        };
+
+        match call.get_prefix() {
+            Prefix::Field(pack) if !call.has_method() => {
+                if self.get_table_function(pack) != Some("pack") {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+
+        let select = call
+            .get_arguments()
+            .clone()
+            .to_expressions()
+            .into_iter()
+            .fold(
+                FunctionCall::from_name("select").with_argument(StringExpression::from_value("#")),
+                |select, argument| select.with_argument(argument),
+            );
+
+        Some(select.into())
+    }
+
+    fn replace_table_function(&mut self, field: &FieldExpression) -> Option<Identifier> {
+        if !self.shim_table_library {
+            return None;
+        }
+
+        match self.get_table_function(field)? {
+            "pack" => {
+                self.define_pack = true;
+                Some(Identifier::new(PACK_IDENTIFIER))
+            }
+            "unpack" => {
+                self.define_unpack = true;
+                Some(Identifier::new(UNPACK_IDENTIFIER))
+            }
+            _ => None,
+        }
+    }
+
+    fn replace_unpack_global(&mut self, identifier: &Identifier) -> Option<Identifier> {
+        if self.shim_table_library
+            || identifier.get_name() != "unpack"
+            || self.is_identifier_used("unpack")
+        {
+            return None;
+        }
+
+        self.define_unpack = true;
+        Some(Identifier::new(UNPACK_IDENTIFIER))
+    }
+}
+
+impl NodeProcessor for RemoveSpreadTablePackingProcessor {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        let replacement = match expression {
+            Expression::Field(field) => self
+                .replace_packed_length(field)
+                .or_else(|| self.replace_table_function(field).map(Expression::from)),
+            Expression::Identifier(identifier) => {
+                self.replace_unpack_global(identifier).map(Expression::from)
+            }
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *expression = replacement;
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        let replacement = match prefix {
+            Prefix::Field(field) => self.replace_table_function(field),
+            Prefix::Identifier(identifier) => self.replace_unpack_global(identifier),
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *prefix = replacement.into();
+        }
+    }
+}
+
+fn table_function(name: &str) -> FieldExpression {
+    FieldExpression::new(Prefix::from_name(TABLE_LIBRARY), name)
+}
+
+/// Builds `local __DARKLUA_UNPACK = table.unpack or unpack`.
+fn build_unpack_shim() -> LocalAssignStatement {
+    LocalAssignStatement::from_variable(UNPACK_IDENTIFIER).with_value(BinaryExpression::new(
+        BinaryOperator::Or,
+        table_function("unpack"),
+        Expression::identifier("unpack"),
+    ))
+}
+
+/// Builds the following shim:
+/// ```lua
+/// local __DARKLUA_PACK = table.pack or function(...)
+///     return { n = select("#", ...), ... }
+/// end
+/// ```
+fn build_pack_shim() -> LocalAssignStatement {
+    let packed = TableExpression::default()
+        .append_field(
+            "n",
+            FunctionCall::from_name("select")
+                .with_argument(StringExpression::from_value("#"))
+                .with_argument(Expression::variable_arguments()),
+        )
+        .append_array_value(Expression::variable_arguments());
+
+    LocalAssignStatement::from_variable(PACK_IDENTIFIER).with_value(BinaryExpression::new(
+        BinaryOperator::Or,
+        table_function("pack"),
+        FunctionExpression::from_block(
+            Block::default().with_last_statement(ReturnStatement::one(packed)),
+        )
+        .variadic(),
+    ))
+}
+
+pub const REMOVE_SPREAD_TABLE_PACKING_RULE_NAME: &str = "remove_spread_table_packing";
+
+/// A rule that makes `table.pack`, `table.unpack` and `unpack` work on the configured
+/// target by replacing them with shims, and converts `table.pack(...).n` into
+/// `select("#", ...)`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveSpreadTablePacking {}
+
+impl FlawlessRule for RemoveSpreadTablePacking {
+    fn flawless_process(&self, block: &mut Block, context: &Context) {
+        let mut processor = RemoveSpreadTablePackingProcessor::new(context.target());
+        ScopeVisitor::visit_block(block, &mut processor);
+
+        if processor.define_pack {
+            block.insert_statement(0, build_pack_shim());
+        }
+        if processor.define_unpack {
+            block.insert_statement(0, build_unpack_shim());
+        }
+    }
+}
+
+impl RuleConfiguration for RemoveSpreadTablePacking {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_SPREAD_TABLE_PACKING_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveSpreadTablePacking {
+        RemoveSpreadTablePacking::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_spread_table_packing""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_spread_table_packing',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,167 @@
+use std::ops;
+
+use crate::nodes::{BinaryOperator, Block, Expression, FunctionCall, Prefix, StringExpression};
+use crate::process::{
+    Evaluator, IdentifierTracker, LuaValue, NodePostProcessor, NodePostVisitor, NodeProcessor,
+    ScopePostVisitor,
+};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+const TYPE_FUNCTIONS: [&str; 2] = ["type", "typeof"];
+
+#[derive(Default)]
+struct Processor {
+    evaluator: Evaluator,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl ops::Deref for Processor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl Processor {
+    /// Returns the name of the type of the value given to a `type` or `typeof` call,
+    /// when the value is a literal that can be removed.
+    fn evaluate_type_call(&self, call: &FunctionCall) -> Option<&'static str> {
+        if call.has_method() {
+            return None;
+        }
+
+        let Prefix::Identifier(identifier) = call.get_prefix() else {
+            return None;
+        };
+
+        let name = identifier.get_name();
+        if !TYPE_FUNCTIONS.contains(&name.as_str()) || self.is_identifier_used(name) {
+            return None;
+        }
+
+        let arguments = call.get_arguments().clone().to_expressions();
+
+        let [value] = arguments.as_slice() else {
+            return None;
+        };
+
+        if self.evaluator.has_side_effects(value) {
+            return None;
+        }
+
+        match self.evaluator.evaluate(value) {
+            LuaValue::False | LuaValue::True => Some("boolean"),
+            LuaValue::Function => Some("function"),
+            LuaValue::Nil => Some("nil"),
+            LuaValue::Number(_) => Some("number"),
+            LuaValue::String(_) => Some("string"),
+            LuaValue::Table => Some("table"),
+            LuaValue::Unknown => None,
+        }
+    }
+}
+
+impl NodeProcessor for Processor {}
+
+impl NodePostProcessor for Processor {
+    fn process_after_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::TypeCast(type_cast) => {
+                let value = type_cast.get_expression();
+                if self.evaluator.can_return_multiple_values(value) {
+                    *expression = value.clone().in_parentheses();
+                } else {
+                    *expression = value.clone();
+                }
+            }
+            Expression::Call(call) => {
+                if let Some(type_name) = self.evaluate_type_call(call) {
+                    *expression = StringExpression::from_value(type_name).into();
+                }
+            }
+            Expression::Binary(binary)
+                if matches!(
+                    binary.operator(),
+                    BinaryOperator::Equal | BinaryOperator::NotEqual
+                ) && matches!(binary.left(), Expression::String(_))
+                    && matches!(binary.right(), Expression::String(_)) =>
+            {
+                if let Some(value) = self.evaluator.evaluate(expression).to_expression() {
+                    *expression = value;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub const REMOVE_TYPE_FUNCTION_CALLS_RULE_NAME: &str = "remove_type_function_calls";
+
+/// A rule that removes type assertions and replaces `type` and `typeof` calls on
+/// literal values with the name of their type.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveTypeFunctionCalls {}
+
+impl FlawlessRule for RemoveTypeFunctionCalls {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor::default();
+        ScopePostVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveTypeFunctionCalls {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_TYPE_FUNCTION_CALLS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveTypeFunctionCalls {
+        RemoveTypeFunctionCalls::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_type_function_calls""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_type_function_calls',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,434 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::nodes::*;
+use crate::process::{DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor};
+use crate::rules::require::is_require_call;
+
+/// The maximum number of local variables followed to find the type of a value.
+const MAX_LOCAL_DEPTH: usize = 8;
+
+fn any_type() -> Type {
+    TypeName::new("any").into()
+}
+
+fn get_function_type(
+    has_self: bool,
+    parameters: &[TypedIdentifier],
+    is_variadic: bool,
+    variadic_type: Option<&FunctionVariadicType>,
+    return_type: Option<&FunctionReturnType>,
+    generic_parameters: Option<&GenericParameters>,
+) -> Type {
+    let return_type = return_type
+        .cloned()
+        .unwrap_or_else(|| VariadicTypePack::new(any_type()).into());
+
+    let mut function_type = FunctionType::new(return_type);
+
+    if has_self {
+        function_type = function_type.with_named_argument("self", any_type());
+    }
+
+    for parameter in parameters {
+        function_type = function_type.with_named_argument(
+            parameter.get_name().as_str(),
+            parameter.get_type().cloned().unwrap_or_else(any_type),
+        );
+    }
+
+    match variadic_type {
+        Some(FunctionVariadicType::Type(r#type)) => {
+            function_type.set_variadic_type(VariadicTypePack::new(r#type.as_ref().clone()));
+        }
+        Some(FunctionVariadicType::GenericTypePack(generic_type_pack)) => {
+            function_type.set_variadic_type(generic_type_pack.clone());
+        }
+        None if is_variadic => {
+            function_type.set_variadic_type(VariadicTypePack::new(any_type()));
+        }
+        None => {}
+    }
+
+    if let Some(generic_parameters) = generic_parameters {
+        function_type.set_generic_parameters(generic_parameters.clone());
+    }
+
+    function_type.into()
+}
+
+fn get_function_expression_type(function: &FunctionExpression) -> Type {
+    get_function_type(
+        false,
+        function.get_parameters(),
+        function.is_variadic(),
+        function.get_variadic_type(),
+        function.get_return_type(),
+        function.get_generic_parameters(),
+    )
+}
+
+fn get_function_statement_type(function: &FunctionStatement) -> Type {
+    get_function_type(
+        function.get_name().has_method(),
+        function.get_parameters(),
+        function.is_variadic(),
+        function.get_variadic_type(),
+        function.get_return_type(),
+        function.get_generic_parameters(),
+    )
+}
+
+fn get_local_function_type(function: &LocalFunctionStatement) -> Type {
+    get_function_type(
+        false,
+        function.get_parameters(),
+        function.is_variadic(),
+        function.get_variadic_type(),
+        function.get_return_type(),
+        function.get_generic_parameters(),
+    )
+}
+
+/// A local variable declared at the top level of a module.
+enum LocalValue<'a> {
+    Function(&'a LocalFunctionStatement),
+    Variable {
+        variable: &'a TypedIdentifier,
+        value: Option<&'a Expression>,
+    },
+}
+
+/// The properties of a table type, in the order they are defined.
+#[derive(Default)]
+struct TableProperties {
+    properties: Vec<(String, Type)>,
+}
+
+impl TableProperties {
+    fn insert(&mut self, name: &str, r#type: Type) {
+        if let Some((_, existing)) = self
+            .properties
+            .iter_mut()
+            .find(|(existing_name, _)| existing_name == name)
+        {
+            *existing = r#type;
+        } else {
+            self.properties.push((name.to_owned(), r#type));
+        }
+    }
+
+    fn into_type(self) -> Type {
+        self.properties
+            .into_iter()
+            .fold(TableType::default(), |table, (name, r#type)| {
+                table.with_new_property(name, r#type)
+            })
+            .into()
+    }
+}
+
+struct ModuleTypes<'a> {
+    locals: HashMap<&'a str, LocalValue<'a>>,
+}
+
+impl<'a> ModuleTypes<'a> {
+    fn new(block: &'a Block) -> Self {
+        let mut locals = HashMap::new();
+
+        for statement in block.iter_statements() {
+            match statement {
+                Statement::LocalFunction(function) => {
+                    locals.insert(function.get_name(), LocalValue::Function(function));
+                }
+                Statement::LocalAssign(assign) => {
+                    let mut values = assign.iter_values();
+                    for variable in assign.iter_variables() {
+                        locals.insert(
+                            variable.get_name().as_str(),
+                            LocalValue::Variable {
+                                variable,
+                                value: values.next(),
+                            },
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { locals }
+    }
+
+    fn get_local_type(&self, name: &str, depth: usize) -> Type {
+        match self.locals.get(name) {
+            Some(LocalValue::Function(function)) => get_local_function_type(function),
+            Some(LocalValue::Variable { variable, value }) => {
+                if let Some(r#type) = variable.get_type() {
+                    r#type.clone()
+                } else if let Some(value) = value {
+                    self.get_expression_type(value, depth + 1)
+                } else {
+                    any_type()
+                }
+            }
+            None => any_type(),
+        }
+    }
+
+    fn get_table_properties(&self, table: &TableExpression, depth: usize) -> TableProperties {
+        let mut properties = TableProperties::default();
+
+        for entry in table.iter_entries() {
+            if let TableEntry::Field(field) = entry {
+                properties.insert(
+                    field.get_field().get_name(),
+                    self.get_expression_type(field.get_value(), depth),
+                );
+            }
+        }
+
+        properties
+    }
+
+    fn get_expression_type(&self, expression: &Expression, depth: usize) -> Type {
+        if depth > MAX_LOCAL_DEPTH {
+            return any_type();
+        }
+
+        match expression {
+            Expression::Function(function) => get_function_expression_type(function),
+            Expression::Table(table) => self.get_table_properties(table, depth).into_type(),
+            Expression::TypeCast(type_cast) => type_cast.get_type().clone(),
+            Expression::Parenthese(parenthese) => {
+                self.get_expression_type(parenthese.inner_expression(), depth)
+            }
+            Expression::Identifier(identifier) => self.get_local_type(identifier.get_name(), depth),
+            Expression::String(_) | Expression::InterpolatedString(_) => {
+                TypeName::new("string").into()
+            }
+            Expression::Number(_) => TypeName::new("number").into(),
+            Expression::True(_) | Expression::False(_) => TypeName::new("boolean").into(),
+            Expression::Nil(_) => Type::nil(),
+            _ => any_type(),
+        }
+    }
+
+    /// Returns the type of a module table defined with a local variable, with the
+    /// functions and values assigned to its fields.
+    fn get_module_table_type(&self, block: &Block, name: &str) -> Option<Type> {
+        let Some(LocalValue::Variable {
+            variable,
+            value: Some(Expression::Table(table)),
+        }) = self.locals.get(name)
+        else {
+            return None;
+        };
+
+        if variable.get_type().is_some() {
+            return None;
+        }
+
+        let mut properties = self.get_table_properties(table, 0);
+
+        for statement in block.iter_statements() {
+            match statement {
+                Statement::Function(function) => {
+                    let function_name = function.get_name();
+
+                    if function_name.get_name().get_name() != name {
+                        continue;
+                    }
+
+                    let field = match (
+                        function_name.get_field_names().as_slice(),
+                        function_name.get_method(),
+                    ) {
+                        ([field], None) | ([], Some(field)) => field,
+                        _ => continue,
+                    };
+
+                    properties.insert(field.get_name(), get_function_statement_type(function));
+                }
+                Statement::Assign(assign) => {
+                    for (variable, value) in assign.iter_variables().zip(assign.iter_values()) {
+                        if let Variable::Field(field) = variable {
+                            if matches!(
+                                field.get_prefix(),
+                                Prefix::Identifier(identifier) if identifier.get_name() == name
+                            ) {
+                                properties.insert(
+                                    field.get_field().get_name(),
+                                    self.get_expression_type(value, 0),
+                                );
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(properties.into_type())
+    }
+
+    fn get_module_type(&self, block: &Block) -> Option<Type> {
+        let LastStatement::Return(statement) = block.get_last_statement()? else {
+            return None;
+        };
+
+        let mut expressions = statement.iter_expressions();
+        let (Some(expression), None) = (expressions.next(), expressions.next()) else {
+            return None;
+        };
+
+        let module_type = match expression {
+            Expression::Identifier(identifier) => self
+                .get_module_table_type(block, identifier.get_name())
+                .unwrap_or_else(|| self.get_expression_type(expression, 0)),
+            _ => self.get_expression_type(expression, 0),
+        };
+
+        Some(module_type)
+    }
+}
+
+/// Collects the names of the types and of the type namespaces (like `Module` in
+/// `Module.Type`) used in a type.
+#[derive(Default)]
+struct TypeReferences {
+    type_names: HashSet<String>,
+    namespaces: HashSet<String>,
+}
+
+impl NodeProcessor for TypeReferences {
+    fn process_type_name(&mut self, type_name: &mut TypeName) {
+        self.type_names
+            .insert(type_name.get_type_name().get_name().to_owned());
+    }
+
+    fn process_type_field(&mut self, type_field: &mut TypeField) {
+        self.namespaces
+            .insert(type_field.get_namespace().get_name().to_owned());
+    }
+}
+
+/// Replaces `typeof(...)` types, which refer to values that are not part of the
+/// declarations.
+struct ReplaceTypeOf;
+
+impl NodeProcessor for ReplaceTypeOf {
+    fn process_type(&mut self, r#type: &mut Type) {
+        if let Type::TypeOf(_) = r#type {
+            *r#type = any_type();
+        }
+    }
+}
+
+fn get_declared_type_name(statement: &Statement) -> Option<(&str, bool)> {
+    match statement {
+        Statement::TypeDeclaration(declaration) => {
+            Some((declaration.get_name().get_name(), declaration.is_exported()))
+        }
+        Statement::TypeFunction(function) => {
+            Some((function.get_identifier().get_name(), function.is_exported()))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of a statement like `local Module = require(...)`.
+fn get_required_module_name(statement: &Statement) -> Option<&str> {
+    let Statement::LocalAssign(assign) = statement else {
+        return None;
+    };
+
+    if assign.variables_len() != 1 || assign.values_len() != 1 {
+        return None;
+    }
+
+    match assign.last_value() {
+        Some(Expression::Call(call)) if is_require_call(call, &IdentifierTracker::new()) => assign
+            .get_variables()
+            .first()
+            .map(|variable| variable.get_name().as_str()),
+        _ => None,
+    }
+}
+
+/// Builds the declarations of a module: its exported types (with the types and the
+/// required modules they use) and a `return` statement typed like the value returned
+/// by the module. The function bodies and the other values are not included.
+///
+/// Returns `None` when the module has nothing to declare.
+pub(crate) fn build_declarations(block: &Block) -> Option<Block> {
+    let module_types = ModuleTypes::new(block);
+    let mut module_type = module_types.get_module_type(block);
+
+    let statements: Vec<_> = block.iter_statements().collect();
+
+    let mut kept = vec![false; statements.len()];
+    let mut pending = Vec::new();
+    let mut references = TypeReferences::default();
+
+    if let Some(module_type) = module_type.as_mut() {
+        DefaultVisitor::visit_type(module_type, &mut ReplaceTypeOf);
+        DefaultVisitor::visit_type(module_type, &mut references);
+    }
+
+    for (index, statement) in statements.iter().enumerate() {
+        if let Some((_, true)) = get_declared_type_name(statement) {
+            pending.push(index);
+        }
+    }
+
+    let mut declarations: Vec<Option<Statement>> = vec![None; statements.len()];
+
+    loop {
+        while let Some(index) = pending.pop() {
+            if kept[index] {
+                continue;
+            }
+            kept[index] = true;
+
+            let mut statement = statements[index].clone();
+            DefaultVisitor::visit_statement(&mut statement, &mut ReplaceTypeOf);
+            DefaultVisitor::visit_statement(&mut statement, &mut references);
+            declarations[index] = Some(statement);
+        }
+
+        for (index, statement) in statements.iter().enumerate() {
+            if kept[index] {
+                continue;
+            }
+
+            let is_used = get_declared_type_name(statement)
+                .map(|(name, _)| references.type_names.contains(name))
+                .or_else(|| {
+                    get_required_module_name(statement)
+                        .map(|name| references.namespaces.contains(name))
+                })
+                .unwrap_or_default();
+
+            if is_used {
+                pending.push(index);
+            }
+        }
+
+        if pending.is_empty() {
+            break;
+        }
+    }
+
+    let mut declarations = Block::new(declarations.into_iter().flatten().collect(), None);
+
+    if let Some(module_type) = module_type {
+        let value = TypeCastExpression::new(
+            Expression::from(TypeCastExpression::new(Expression::nil(), any_type()))
+                .in_parentheses(),
+            module_type,
+        );
+        declarations.set_last_statement(ReturnStatement::one(value));
+    }
+
+    (!declarations.is_empty()).then_some(declarations)
+}
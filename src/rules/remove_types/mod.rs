@@ -0,0 +1,224 @@
+mod declarations;
+
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+
+use crate::generator::{LuaGenerator, ReadableLuaGenerator};
+use crate::nodes::*;
+use crate::process::{DefaultVisitor, Evaluator, NodeProcessor, NodeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, Rule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+
+use declarations::build_declarations;
+
+const DECLARATION_FILE_EXTENSION: &str = "d.luau";
+
+/// Returns the path of the declaration file written next to the given file.
+fn get_declaration_file_path(path: &Path) -> PathBuf {
+    let file_stem = path.file_stem().and_then(OsStr::to_str).unwrap_or_default();
+
+    path.with_file_name(format!("{}.{}", file_stem, DECLARATION_FILE_EXTENSION))
+}
+
+/// Returns true if the path is a Luau declaration file (ending with `.d.luau`).
+pub(crate) fn is_declaration_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|name| name.ends_with(&format!(".{}", DECLARATION_FILE_EXTENSION)))
+}
+
+/// Returns true if the rule writes declaration files, which replace the declaration
+/// files of the input.
+pub(crate) fn writes_declaration_file(rule: &dyn Rule) -> bool {
+    rule.get_name() == REMOVE_TYPES_RULE_NAME
+        && matches!(
+            rule.serialize_to_properties().get("declaration_file"),
+            Some(RulePropertyValue::Boolean(true))
+        )
+}
+
+#[derive(Default)]
+struct RemoveTypesProcessor {
+    evaluator: Evaluator,
+}
+
+impl NodeProcessor for RemoveTypesProcessor {
+    fn process_block(&mut self, block: &mut Block) {
+        block.filter_statements(|statement| {
+            !matches!(
+                statement,
+                Statement::TypeDeclaration(_) | Statement::TypeFunction(_)
+            )
+        });
+    }
+
+    fn process_local_assign_statement(&mut self, local_assign: &mut LocalAssignStatement) {
+        local_assign.clear_types();
+    }
+
+    fn process_numeric_for_statement(&mut self, numeric_for: &mut NumericForStatement) {
+        numeric_for.clear_types();
+    }
+
+    fn process_generic_for_statement(&mut self, generic_for: &mut GenericForStatement) {
+        generic_for.clear_types();
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        function.clear_types();
+    }
+
+    fn process_local_function_statement(&mut self, function: &mut LocalFunctionStatement) {
+        function.clear_types();
+    }
+
+    fn process_function_expression(&mut self, function: &mut FunctionExpression) {
+        function.clear_types();
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        match expression {
+            Expression::TypeCast(type_cast) => {
+                let value = type_cast.get_expression();
+                if self.evaluator.can_return_multiple_values(value) {
+                    *expression = value.clone().in_parentheses();
+                } else {
+                    *expression = value.clone();
+                }
+            }
+            Expression::Function(function) => {
+                function.clear_types();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub const REMOVE_TYPES_RULE_NAME: &str = "remove_types";
+
+/// A rule that removes Luau types from all AST nodes.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveTypes {
+    declaration_file: bool,
+}
+
+impl RemoveTypes {
+    fn add_declaration_file(&self, block: &Block, context: &Context) {
+        let Some(declarations) = build_declarations(block) else {
+            return;
+        };
+
+        let mut generator = ReadableLuaGenerator::default();
+        generator.write_block(&declarations);
+
+        context.add_output_file(
+            get_declaration_file_path(context.output_path()),
+            generator.into_string(),
+        );
+    }
+}
+
+impl FlawlessRule for RemoveTypes {
+    fn flawless_process(&self, block: &mut Block, context: &Context) {
+        if self.declaration_file {
+            self.add_declaration_file(block, context);
+        }
+
+        let mut processor = RemoveTypesProcessor::default();
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveTypes {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        for (key, value) in properties {
+            match key.as_str() {
+                "declaration_file" => {
+                    self.declaration_file = value.expect_bool(&key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_TYPES_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if self.declaration_file {
+            properties.insert(
+                "declaration_file".to_owned(),
+                RulePropertyValue::Boolean(true),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveTypes {
+        RemoveTypes::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_types""###);
+    }
+
+    #[test]
+    fn serialize_rule_with_declaration_file() {
+        let rule: Box<dyn Rule> = Box::new(RemoveTypes {
+            declaration_file: true,
+        });
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "remove_types",
+          "declaration_file": true
+        }
+        "###);
+    }
+
+    #[test]
+    fn declaration_file_path_of_lua_file() {
+        assert_eq!(
+            get_declaration_file_path(Path::new("src/module.lua")),
+            PathBuf::from("src/module.d.luau")
+        );
+    }
+
+    #[test]
+    fn declaration_file_path_of_luau_file() {
+        assert_eq!(
+            get_declaration_file_path(Path::new("src/module.luau")),
+            PathBuf::from("src/module.d.luau")
+        );
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_types',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
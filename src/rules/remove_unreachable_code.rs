@@ -0,0 +1,306 @@
+use std::ops;
+
+use crate::nodes::{
+    Block, Expression, FunctionCall, FunctionStatement, Identifier, LastStatement,
+    NumericForStatement, Prefix, Statement, Variable,
+};
+use crate::process::{
+    DefaultVisitor, Evaluator, IdentifierTracker, LuaValue, NodeProcessor, NodeVisitor,
+    ScopeVisitor,
+};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+const ERROR_FUNCTION: &str = "error";
+const GLOBAL_TABLE: &str = "_G";
+const ENVIRONMENT_FUNCTIONS: [&str; 2] = ["getfenv", "setfenv"];
+
+fn declares_error_function(statement: &Statement) -> bool {
+    match statement {
+        Statement::LocalAssign(assign) => assign
+            .iter_variables()
+            .any(|variable| variable.get_name() == ERROR_FUNCTION),
+        Statement::LocalFunction(function) => function.get_name() == ERROR_FUNCTION,
+        _ => false,
+    }
+}
+
+fn is_global_error_field(prefix: &Prefix, field: &str) -> bool {
+    field == ERROR_FUNCTION
+        && matches!(prefix, Prefix::Identifier(identifier) if identifier.get_name() == GLOBAL_TABLE)
+}
+
+/// Finds if the `error` function may be replaced somewhere in the code: when it is
+/// assigned (in any scope) or when the environment of a function can be changed.
+#[derive(Default)]
+struct FindErrorAssignment {
+    found: bool,
+}
+
+impl NodeProcessor for FindErrorAssignment {
+    fn process_variable(&mut self, variable: &mut Variable) {
+        match variable {
+            Variable::Identifier(identifier) if identifier.get_name() == ERROR_FUNCTION => {
+                self.found = true;
+            }
+            Variable::Field(field)
+                if is_global_error_field(field.get_prefix(), field.get_field().get_name()) =>
+            {
+                self.found = true;
+            }
+            _ => {}
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let name = function.get_name();
+
+        let is_error = match (name.get_field_names().as_slice(), name.get_method()) {
+            ([], None) => name.get_name().get_name() == ERROR_FUNCTION,
+            ([field], None) => {
+                name.get_name().get_name() == GLOBAL_TABLE && field.get_name() == ERROR_FUNCTION
+            }
+            _ => false,
+        };
+
+        if is_error {
+            self.found = true;
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        if ENVIRONMENT_FUNCTIONS.contains(&identifier.get_name().as_str()) {
+            self.found = true;
+        }
+    }
+}
+
+fn is_error_call(call: &FunctionCall) -> bool {
+    !call.has_method()
+        && matches!(call.get_prefix(), Prefix::Identifier(identifier) if identifier.get_name() == ERROR_FUNCTION)
+}
+
+/// Returns true when the block contains a `break` statement that exits the
+/// loop owning the block.
+fn has_break(block: &Block) -> bool {
+    matches!(block.get_last_statement(), Some(LastStatement::Break(_)))
+        || block.iter_statements().any(|statement| match statement {
+            Statement::Do(do_statement) => has_break(do_statement.get_block()),
+            Statement::If(if_statement) => {
+                if_statement
+                    .iter_branches()
+                    .any(|branch| has_break(branch.get_block()))
+                    || if_statement.get_else_block().is_some_and(has_break)
+            }
+            _ => false,
+        })
+}
+
+struct Processor {
+    evaluator: Evaluator,
+    identifier_tracker: IdentifierTracker,
+    /// True when calls to `error` may not refer to the original function.
+    error_replaced: bool,
+}
+
+impl Processor {
+    fn new(error_replaced: bool) -> Self {
+        Self {
+            evaluator: Evaluator::default(),
+            identifier_tracker: IdentifierTracker::default(),
+            error_replaced,
+        }
+    }
+
+    /// Finds the first statement after which the rest of the block can not be reached.
+    fn find_exit_index(&self, block: &Block, mut error_shadowed: bool) -> Option<usize> {
+        for (i, statement) in block.iter_statements().enumerate() {
+            if self.statement_exits(statement, error_shadowed) {
+                return Some(i);
+            }
+            if declares_error_function(statement) {
+                error_shadowed = true;
+            }
+        }
+        None
+    }
+
+    fn block_exits(&self, block: &Block, error_shadowed: bool) -> bool {
+        block.get_last_statement().is_some()
+            || self.find_exit_index(block, error_shadowed).is_some()
+    }
+
+    fn statement_exits(&self, statement: &Statement, error_shadowed: bool) -> bool {
+        match statement {
+            Statement::Do(do_statement) => {
+                self.block_exits(do_statement.get_block(), error_shadowed)
+            }
+            Statement::If(if_statement) => {
+                if_statement.get_else_block().is_some_and(|else_block| {
+                    self.block_exits(else_block, error_shadowed)
+                        && if_statement
+                            .iter_branches()
+                            .all(|branch| self.block_exits(branch.get_block(), error_shadowed))
+                })
+            }
+            Statement::Call(call) => !error_shadowed && is_error_call(call),
+            Statement::While(while_statement) => {
+                self.evaluator
+                    .evaluate(while_statement.get_condition())
+                    .is_truthy()
+                    == Some(true)
+                    && !has_break(while_statement.get_block())
+            }
+            Statement::Repeat(repeat_statement) => {
+                self.evaluator
+                    .evaluate(repeat_statement.get_condition())
+                    .is_truthy()
+                    == Some(false)
+                    && !has_break(repeat_statement.get_block())
+            }
+            _ => false,
+        }
+    }
+
+    fn get_number(&self, expression: &Expression) -> Option<f64> {
+        match self.evaluator.evaluate(expression) {
+            LuaValue::Number(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn is_empty_numeric_for(&self, numeric_for: &NumericForStatement) -> bool {
+        let (Some(start), Some(end)) = (
+            self.get_number(numeric_for.get_start()),
+            self.get_number(numeric_for.get_end()),
+        ) else {
+            return false;
+        };
+        let step = match numeric_for.get_step() {
+            Some(step) => match self.get_number(step) {
+                Some(step) => step,
+                None => return false,
+            },
+            None => 1.0,
+        };
+
+        (step > 0.0 && start > end) || (step < 0.0 && start < end)
+    }
+
+    fn is_never_executed(&self, statement: &Statement) -> bool {
+        match statement {
+            Statement::While(while_statement) => {
+                let condition = while_statement.get_condition();
+
+                !self.evaluator.has_side_effects(condition)
+                    && self.evaluator.evaluate(condition).is_truthy() == Some(false)
+            }
+            Statement::NumericFor(numeric_for) => self.is_empty_numeric_for(numeric_for),
+            _ => false,
+        }
+    }
+}
+
+impl ops::Deref for Processor {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor {
+    fn process_block(&mut self, block: &mut Block) {
+        block.filter_statements(|statement| !self.is_never_executed(statement));
+
+        let error_shadowed = self.error_replaced || self.is_identifier_used(ERROR_FUNCTION);
+
+        if let Some(exit_index) = self.find_exit_index(block, error_shadowed) {
+            block.take_last_statement();
+
+            let mut index = 0;
+            // type declarations are visible in their whole scope, so they are kept
+            block.filter_statements(|statement| {
+                let keep = index <= exit_index
+                    || matches!(
+                        statement,
+                        Statement::TypeDeclaration(_) | Statement::TypeFunction(_)
+                    );
+                index += 1;
+                keep
+            });
+        }
+    }
+}
+
+pub const REMOVE_UNREACHABLE_CODE_RULE_NAME: &str = "remove_unreachable_code";
+
+/// A rule that removes statements that can never be executed, like statements following an
+/// `if` statement that returns in every branch, or loops that never run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveUnreachableCode {}
+
+impl FlawlessRule for RemoveUnreachableCode {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut find_error_assignment = FindErrorAssignment::default();
+        DefaultVisitor::visit_block(block, &mut find_error_assignment);
+
+        let mut processor = Processor::new(find_error_assignment.found);
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveUnreachableCode {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_UNREACHABLE_CODE_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveUnreachableCode {
+        RemoveUnreachableCode::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_unreachable_code""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_unreachable_code',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::nodes::{
+    Block, FunctionExpression, FunctionReturnType, FunctionStatement, FunctionVariadicType,
+    LocalFunctionStatement, TypedIdentifier,
+};
+use crate::process::processors::FindUsage;
+use crate::process::{DefaultVisitor, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+const UNUSED_PARAMETER_NAME: &str = "_";
+
+/// Gives access to the parts of the different function nodes that can
+/// reference a parameter.
+trait FunctionParameters {
+    fn is_variadic(&self) -> bool;
+    fn mutate_parameters(&mut self) -> &mut Vec<TypedIdentifier>;
+    fn mutate_block(&mut self) -> &mut Block;
+    fn mutate_return_type(&mut self) -> Option<&mut FunctionReturnType>;
+    fn mutate_variadic_type(&mut self) -> Option<&mut FunctionVariadicType>;
+}
+
+macro_rules! impl_function_parameters {
+    ($($node:ty),*) => {
+        $(
+            impl FunctionParameters for $node {
+                fn is_variadic(&self) -> bool {
+                    <$node>::is_variadic(self)
+                }
+
+                fn mutate_parameters(&mut self) -> &mut Vec<TypedIdentifier> {
+                    <$node>::mutate_parameters(self)
+                }
+
+                fn mutate_block(&mut self) -> &mut Block {
+                    <$node>::mutate_block(self)
+                }
+
+                fn mutate_return_type(&mut self) -> Option<&mut FunctionReturnType> {
+                    <$node>::mutate_return_type(self)
+                }
+
+                fn mutate_variadic_type(&mut self) -> Option<&mut FunctionVariadicType> {
+                    <$node>::mutate_variadic_type(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_function_parameters!(
+    FunctionExpression,
+    FunctionStatement,
+    LocalFunctionStatement
+);
+
+fn is_identifier_used<F: FunctionParameters>(function: &mut F, name: &str) -> bool {
+    let mut find_usage = FindUsage::new(name);
+
+    ScopeVisitor::visit_block(function.mutate_block(), &mut find_usage);
+
+    for parameter in function.mutate_parameters().iter_mut() {
+        if let Some(r#type) = parameter.mutate_type() {
+            ScopeVisitor::visit_type(r#type, &mut find_usage);
+        }
+    }
+
+    if let Some(return_type) = function.mutate_return_type() {
+        ScopeVisitor::visit_function_return_type(return_type, &mut find_usage);
+    }
+
+    if let Some(variadic_type) = function.mutate_variadic_type() {
+        ScopeVisitor::visit_function_variadic_type(variadic_type, &mut find_usage);
+    }
+
+    find_usage.has_found_usage()
+}
+
+fn remove_unused_parameters<F: FunctionParameters>(function: &mut F) {
+    let names: Vec<String> = function
+        .mutate_parameters()
+        .iter()
+        .map(|parameter| parameter.get_name().to_owned())
+        .collect();
+
+    if names.is_empty() {
+        return;
+    }
+
+    // when a name appears multiple times, only the last parameter can be referenced
+    let has_duplicates = names
+        .iter()
+        .enumerate()
+        .any(|(i, name)| names[i + 1..].contains(name));
+
+    if has_duplicates {
+        return;
+    }
+
+    let used: Vec<bool> = names
+        .iter()
+        .map(|name| is_identifier_used(function, name))
+        .collect();
+
+    let mut keep_count = used.len();
+
+    // removing parameters before `...` would change the values it contains
+    if !function.is_variadic() {
+        while keep_count > 0 && !used[keep_count - 1] {
+            keep_count -= 1;
+        }
+    }
+
+    let has_unused_name_to_rename = names[..keep_count]
+        .iter()
+        .zip(used.iter())
+        .any(|(name, used)| !used && name != UNUSED_PARAMETER_NAME);
+
+    // renaming is not possible if `_` refers to a variable outside of the function
+    let can_rename =
+        has_unused_name_to_rename && !is_identifier_used(function, UNUSED_PARAMETER_NAME);
+
+    let parameters = function.mutate_parameters();
+    parameters.truncate(keep_count);
+
+    if can_rename {
+        for (parameter, used) in parameters.iter_mut().zip(used) {
+            if !used && parameter.get_name() != UNUSED_PARAMETER_NAME {
+                parameter.set_name(UNUSED_PARAMETER_NAME);
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Processor;
+
+impl NodeProcessor for Processor {
+    fn process_function_expression(&mut self, function: &mut FunctionExpression) {
+        remove_unused_parameters(function);
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        remove_unused_parameters(function);
+    }
+
+    fn process_local_function_statement(&mut self, function: &mut LocalFunctionStatement) {
+        remove_unused_parameters(function);
+    }
+}
+
+pub const REMOVE_UNUSED_FUNCTION_PARAMETERS_RULE_NAME: &str = "remove_unused_function_parameters";
+
+/// A rule that removes trailing function parameters that are never used, and
+/// renames the other unused parameters to `_`.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RemoveUnusedFunctionParameters {}
+
+impl FlawlessRule for RemoveUnusedFunctionParameters {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor;
+        DefaultVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RemoveUnusedFunctionParameters {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        REMOVE_UNUSED_FUNCTION_PARAMETERS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RemoveUnusedFunctionParameters {
+        RemoveUnusedFunctionParameters::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###""remove_unused_function_parameters""###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'remove_unused_function_parameters',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::BTreeMap;
+use std::{fmt, ops};
+
+use crate::nodes::{
+    Block, Expression, FieldExpression, FunctionName, FunctionStatement, Identifier, Prefix,
+    Variable,
+};
+use crate::process::utils::is_valid_identifier;
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+
+use super::verify_required_properties;
+
+pub const RENAME_GLOBALS_RULE_NAME: &str = "rename_globals";
+
+const GLOBALS_PROPERTY: &str = "globals";
+
+/// A global name (like `wait`) or a path to a field of a global (like `table.getn`).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct GlobalPath(Vec<String>);
+
+impl GlobalPath {
+    fn parse(path: &str) -> Option<Self> {
+        let segments: Vec<String> = path.split('.').map(ToOwned::to_owned).collect();
+
+        segments
+            .iter()
+            .all(|segment| is_valid_identifier(segment))
+            .then_some(Self(segments))
+    }
+
+    fn starts_with(&self, other: &GlobalPath) -> bool {
+        self.0.starts_with(&other.0)
+    }
+
+    fn to_prefix(&self) -> Prefix {
+        let mut segments = self.0.iter();
+        let root = segments.next().expect("global path should not be empty");
+
+        segments.fold(Prefix::from_name(root), |prefix, field| {
+            FieldExpression::new(prefix, Identifier::new(field)).into()
+        })
+    }
+
+    fn to_expression(&self) -> Expression {
+        self.to_prefix().into()
+    }
+
+    fn to_variable(&self) -> Variable {
+        match self.to_prefix() {
+            Prefix::Identifier(identifier) => Variable::Identifier(identifier),
+            Prefix::Field(field) => Variable::Field(field),
+            _ => unreachable!("global path should only produce identifiers and field expressions"),
+        }
+    }
+}
+
+impl fmt::Display for GlobalPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.join("."))
+    }
+}
+
+/// Returns the path of a field expression when it starts from an identifier.
+fn get_field_path(field: &FieldExpression) -> Option<Vec<&str>> {
+    let mut path = match field.get_prefix() {
+        Prefix::Identifier(identifier) => vec![identifier.get_name().as_str()],
+        Prefix::Field(field) => get_field_path(field)?,
+        _ => return None,
+    };
+    path.push(field.get_field().get_name());
+    Some(path)
+}
+
+struct Processor<'a> {
+    globals: &'a BTreeMap<GlobalPath, GlobalPath>,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl<'a> Processor<'a> {
+    fn new(globals: &'a BTreeMap<GlobalPath, GlobalPath>) -> Self {
+        Self {
+            globals,
+            identifier_tracker: IdentifierTracker::default(),
+        }
+    }
+
+    fn find_replacement(&self, path: &[&str]) -> Option<&'a GlobalPath> {
+        let root = path.first()?;
+        if self.is_identifier_used(root) {
+            return None;
+        }
+
+        self.globals
+            .iter()
+            .find(|(global, _)| {
+                global.0.len() == path.len()
+                    && global.0.iter().zip(path.iter()).all(|(a, b)| a == b)
+            })
+            .map(|(_, replacement)| replacement)
+    }
+
+    fn find_prefix_replacement(&self, prefix: &Prefix) -> Option<&'a GlobalPath> {
+        match prefix {
+            Prefix::Identifier(identifier) => {
+                self.find_replacement(&[identifier.get_name().as_str()])
+            }
+            Prefix::Field(field) => self.find_replacement(&get_field_path(field)?),
+            _ => None,
+        }
+    }
+}
+
+impl ops::Deref for Processor<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor<'_> {
+    fn process_expression(&mut self, expression: &mut Expression) {
+        let replacement = match expression {
+            Expression::Identifier(identifier) => {
+                self.find_replacement(&[identifier.get_name().as_str()])
+            }
+            Expression::Field(field) => {
+                get_field_path(field).and_then(|path| self.find_replacement(&path))
+            }
+            _ => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *expression = replacement.to_expression();
+        }
+    }
+
+    fn process_prefix_expression(&mut self, prefix: &mut Prefix) {
+        if let Some(replacement) = self.find_prefix_replacement(prefix) {
+            *prefix = replacement.to_prefix();
+        }
+    }
+
+    fn process_variable(&mut self, variable: &mut Variable) {
+        let replacement = match variable {
+            Variable::Identifier(identifier) => {
+                self.find_replacement(&[identifier.get_name().as_str()])
+            }
+            Variable::Field(field) => {
+                get_field_path(field).and_then(|path| self.find_replacement(&path))
+            }
+            Variable::Index(_) => None,
+        };
+
+        if let Some(replacement) = replacement {
+            *variable = replacement.to_variable();
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let name = function.get_name();
+        let path: Vec<&str> = std::iter::once(name.get_name().get_name().as_str())
+            .chain(
+                name.get_field_names()
+                    .iter()
+                    .map(|field| field.get_name().as_str()),
+            )
+            .collect();
+
+        let replaced = (1..=path.len()).rev().find_map(|length| {
+            self.find_replacement(&path[..length])
+                .map(|replacement| (length, replacement))
+        });
+
+        if let Some((length, replacement)) = replaced {
+            let mut segments = replacement.0.iter();
+            let root = segments.next().expect("global path should not be empty");
+
+            let fields = segments
+                .map(Identifier::new)
+                .chain(
+                    name.get_field_names()
+                        .iter()
+                        .skip(length - 1)
+                        .map(|field| Identifier::new(field.get_name())),
+                )
+                .collect();
+
+            let method = name.get_method().cloned();
+
+            *function.mutate_function_name() =
+                FunctionName::new(Identifier::new(root), fields, method);
+        }
+    }
+}
+
+/// A rule that renames global variables (or fields of global variables) using a
+/// mapping of their old names to their new names.
+#[derive(Debug, Default)]
+pub struct RenameGlobals {
+    globals: BTreeMap<GlobalPath, GlobalPath>,
+}
+
+impl RenameGlobals {
+    /// Renames the global `from` to `to`. Both names can be paths to a field of
+    /// a global like `task.wait`.
+    pub fn with_global(mut self, from: &str, to: &str) -> Self {
+        let from = GlobalPath::parse(from).expect("invalid global name");
+        let to = GlobalPath::parse(to).expect("invalid global name");
+        self.globals.insert(from, to);
+        self
+    }
+}
+
+impl FlawlessRule for RenameGlobals {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        if self.globals.is_empty() {
+            return;
+        }
+
+        let mut processor = Processor::new(&self.globals);
+        ScopeVisitor::visit_block(block, &mut processor);
+    }
+}
+
+impl RuleConfiguration for RenameGlobals {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_required_properties(&properties, &[GLOBALS_PROPERTY])?;
+
+        for (key, value) in properties {
+            match key.as_str() {
+                GLOBALS_PROPERTY => {
+                    let map = match value {
+                        RulePropertyValue::Map(map) => map,
+                        _ => return Err(RuleConfigurationError::UnexpectedValueType(key)),
+                    };
+
+                    for (from, to) in map {
+                        let to = match to {
+                            serde_json::Value::String(to) => to,
+                            _ => {
+                                return Err(RuleConfigurationError::UnexpectedValue {
+                                    property: key,
+                                    message: format!(
+                                        "the new name of `{}` should be a string",
+                                        from
+                                    ),
+                                })
+                            }
+                        };
+
+                        let parse = |name: &str| {
+                            GlobalPath::parse(name).ok_or_else(|| {
+                                RuleConfigurationError::UnexpectedValue {
+                                    property: key.clone(),
+                                    message: format!(
+                                        "invalid global name `{}` (expected an identifier or a path like `task.wait`)",
+                                        name
+                                    ),
+                                }
+                            })
+                        };
+
+                        let from_path = parse(&from)?;
+                        let to_path = parse(&to)?;
+                        self.globals.insert(from_path, to_path);
+                    }
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        // a new name must not be renamed again by another entry
+        for replacement in self.globals.values() {
+            if let Some(renamed) = self
+                .globals
+                .keys()
+                .find(|global| replacement.starts_with(global))
+            {
+                return Err(RuleConfigurationError::UnexpectedValue {
+                    property: GLOBALS_PROPERTY.to_owned(),
+                    message: format!(
+                        "the new name `{}` cannot start with `{}` because it is also renamed",
+                        replacement, renamed,
+                    ),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        RENAME_GLOBALS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        properties.insert(
+            GLOBALS_PROPERTY.to_owned(),
+            RulePropertyValue::Map(
+                self.globals
+                    .iter()
+                    .map(|(from, to)| (from.to_string(), serde_json::Value::String(to.to_string())))
+                    .collect(),
+            ),
+        );
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RenameGlobals {
+        RenameGlobals::default()
+            .with_global("wait", "task.wait")
+            .with_global("table.getn", "rawlen")
+    }
+
+    #[test]
+    fn serialize_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "rename_globals",
+          "globals": {
+            "table.getn": "rawlen",
+            "wait": "task.wait"
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_without_globals_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_globals',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"missing required field 'globals' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_global_name_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_globals',
+            globals: { wait: 'task wait' },
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'globals': invalid global name `task wait` (expected an identifier or a path like `task.wait`) at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_renamed_replacement_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_globals',
+            globals: { wait: 'task.wait', task: 'scheduler' },
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'globals': the new name `task.wait` cannot start with `task` because it is also renamed at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_globals',
+            globals: {},
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
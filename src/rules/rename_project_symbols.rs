@@ -0,0 +1,522 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::nodes::{
+    Block, Expression, FieldExpression, FunctionCall, FunctionStatement, Identifier,
+    IndexExpression, LastStatement, LocalFunctionStatement, Prefix, StringExpression, TableEntry,
+    TableExpression,
+};
+use crate::process::utils::is_valid_identifier;
+use crate::process::{NodeProcessor, NodeVisitor, Scope, ScopeVisitor};
+use crate::rules::{
+    Context, RequireMode, Rule, RuleConfiguration, RuleConfigurationError, RuleProcessResult,
+    RuleProperties, RulePropertyValue,
+};
+use crate::utils;
+
+use super::verify_required_properties;
+
+pub const RENAME_PROJECT_SYMBOLS_RULE_NAME: &str = "rename_project_symbols";
+
+const SYMBOLS_PROPERTY: &str = "symbols";
+const REQUIRE_MODE_PROPERTY: &str = "require_mode";
+const REQUIRE_FUNCTION_IDENTIFIER: &str = "require";
+
+type FieldRenames = BTreeMap<String, String>;
+
+/// Tracks the local variables in scope, with the index of the renamed module they
+/// hold when they are assigned a renamed module.
+struct Processor<'a> {
+    modules: &'a [(PathBuf, &'a FieldRenames)],
+    current_module: Option<usize>,
+    exported_name: Option<String>,
+    require_mode: &'a RequireMode,
+    context: &'a Context<'a, 'a, 'a>,
+    scopes: Vec<HashMap<String, Option<usize>>>,
+}
+
+impl<'a> Processor<'a> {
+    fn new(
+        modules: &'a [(PathBuf, &'a FieldRenames)],
+        require_mode: &'a RequireMode,
+        context: &'a Context,
+    ) -> Self {
+        let current_path = utils::normalize_path(context.current_path());
+        let current_module = modules.iter().position(|(path, _)| path == &current_path);
+
+        Self {
+            modules,
+            current_module,
+            exported_name: None,
+            require_mode,
+            context,
+            scopes: Vec::new(),
+        }
+    }
+
+    fn insert_binding(&mut self, name: &str, module: Option<usize>) {
+        if self.scopes.is_empty() {
+            self.scopes.push(HashMap::new());
+        }
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_owned(), module);
+        }
+    }
+
+    fn get_binding(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .copied()
+            .flatten()
+    }
+
+    fn is_require_call(&self, call: &FunctionCall) -> bool {
+        if call.has_method() {
+            return false;
+        }
+
+        match call.get_prefix() {
+            Prefix::Identifier(identifier) => {
+                identifier.get_name() == REQUIRE_FUNCTION_IDENTIFIER
+                    && !self
+                        .scopes
+                        .iter()
+                        .any(|scope| scope.contains_key(REQUIRE_FUNCTION_IDENTIFIER))
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the index of the renamed module loaded by a require call.
+    fn find_required_module(&self, call: &FunctionCall) -> Option<usize> {
+        if !self.is_require_call(call) {
+            return None;
+        }
+
+        match self.require_mode.find_require(call, self.context) {
+            Ok(Some(path)) => {
+                let path = utils::normalize_path(path);
+                self.modules
+                    .iter()
+                    .position(|(module_path, _)| module_path == &path)
+            }
+            Ok(None) => None,
+            Err(err) => {
+                log::debug!("unable to resolve require call: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Returns the renamed module that a prefix evaluates to, if any.
+    fn find_prefix_module(&self, prefix: &Prefix) -> Option<&'a FieldRenames> {
+        let index = match prefix {
+            Prefix::Identifier(identifier) => self.get_binding(identifier.get_name()),
+            Prefix::Call(call) => self.find_required_module(call),
+            _ => None,
+        }?;
+
+        Some(self.modules[index].1)
+    }
+
+    fn find_expression_module(&self, expression: &Expression) -> Option<usize> {
+        match expression {
+            Expression::Call(call) => self.find_required_module(call),
+            Expression::Identifier(identifier) => self.get_binding(identifier.get_name()),
+            _ => None,
+        }
+    }
+}
+
+fn rename_identifier(identifier: &mut Identifier, renames: &FieldRenames) {
+    if let Some(new_name) = renames.get(identifier.get_name()) {
+        identifier.set_name(new_name);
+    }
+}
+
+fn rename_table_entries(table: &mut TableExpression, renames: &FieldRenames) {
+    for entry in table.iter_mut_entries() {
+        match entry {
+            TableEntry::Field(field) => rename_identifier(field.mutate_field(), renames),
+            TableEntry::Index(index) => rename_string_key(index.mutate_key(), renames),
+            TableEntry::Value(_) => {}
+        }
+    }
+}
+
+fn rename_string_key(key: &mut Expression, renames: &FieldRenames) {
+    let Expression::String(string) = key else {
+        return;
+    };
+
+    if let Some(new_name) = string
+        .get_string_value()
+        .and_then(|value| renames.get(value))
+    {
+        *key = StringExpression::from_value(new_name.as_str()).into();
+    }
+}
+
+/// Returns the name of the local variable returned at the end of a module.
+fn get_exported_name(block: &Block) -> Option<String> {
+    let Some(LastStatement::Return(statement)) = block.get_last_statement() else {
+        return None;
+    };
+
+    if statement.len() != 1 {
+        return None;
+    }
+
+    match statement.iter_expressions().next() {
+        Some(Expression::Identifier(identifier)) => Some(identifier.get_name().to_owned()),
+        _ => None,
+    }
+}
+
+impl Scope for Processor<'_> {
+    fn push(&mut self) {
+        self.scopes.push(HashMap::new());
+    }
+
+    fn pop(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.insert_binding(identifier, None);
+    }
+
+    fn insert_self(&mut self) {
+        self.insert_binding("self", None);
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, value: Option<&mut Expression>) {
+        let is_root = self.scopes.len() <= 1;
+
+        let module = if is_root && self.exported_name.as_ref() == Some(identifier) {
+            let module = self.current_module;
+
+            if let (Some(index), Some(Expression::Table(table))) = (module, value) {
+                rename_table_entries(table, self.modules[index].1);
+            }
+
+            module
+        } else {
+            value.and_then(|value| self.find_expression_module(value))
+        };
+
+        self.insert_binding(identifier, module);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        let name = function.get_identifier().get_name().clone();
+        self.insert_binding(&name, None);
+    }
+}
+
+impl NodeProcessor for Processor<'_> {
+    fn process_field_expression(&mut self, field: &mut FieldExpression) {
+        if let Some(renames) = self.find_prefix_module(field.get_prefix()) {
+            rename_identifier(field.mutate_field(), renames);
+        }
+    }
+
+    fn process_index_expression(&mut self, index: &mut IndexExpression) {
+        if let Some(renames) = self.find_prefix_module(index.get_prefix()) {
+            rename_string_key(index.mutate_index(), renames);
+        }
+    }
+
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        if !call.has_method() {
+            return;
+        }
+
+        if let Some(renames) = self.find_prefix_module(call.get_prefix()) {
+            if let Some(method) = call.mutate_method() {
+                rename_identifier(method, renames);
+            }
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let Some(index) = self.get_binding(function.get_name().get_name().get_name()) else {
+            return;
+        };
+        let renames = self.modules[index].1;
+
+        let name = function.mutate_function_name();
+
+        if let Some(field) = name.mutate_field_names().first_mut() {
+            rename_identifier(field, renames);
+        } else if let Some(method) = name.mutate_method() {
+            rename_identifier(method, renames);
+        }
+    }
+}
+
+/// A rule that renames the fields exported by modules of the project, in the modules
+/// that define them and in every file that requires them.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RenameProjectSymbols {
+    symbols: BTreeMap<PathBuf, FieldRenames>,
+    require_mode: Option<RequireMode>,
+}
+
+impl RenameProjectSymbols {
+    /// Renames the field `from` exported by the module at the given path to `to`.
+    pub fn with_symbol(mut self, module: impl Into<PathBuf>, from: &str, to: &str) -> Self {
+        assert!(is_valid_identifier(from), "invalid field name `{}`", from);
+        assert!(is_valid_identifier(to), "invalid field name `{}`", to);
+
+        self.symbols
+            .entry(module.into())
+            .or_default()
+            .insert(from.to_owned(), to.to_owned());
+        self
+    }
+
+    /// Sets the require mode used to find the modules loaded by require calls.
+    pub fn with_require_mode(mut self, require_mode: impl Into<RequireMode>) -> Self {
+        self.require_mode = Some(require_mode.into());
+        self
+    }
+}
+
+impl Rule for RenameProjectSymbols {
+    fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+        if self.symbols.is_empty() {
+            return Ok(());
+        }
+
+        let mut require_mode = self
+            .require_mode
+            .clone()
+            .unwrap_or_else(|| RequireMode::Path(Default::default()));
+        require_mode
+            .initialize(context)
+            .map_err(|err| err.to_string())?;
+
+        let modules: Vec<_> = self
+            .symbols
+            .iter()
+            .map(|(path, renames)| {
+                (
+                    utils::normalize_path(context.project_location().join(path)),
+                    renames,
+                )
+            })
+            .collect();
+
+        let mut processor = Processor::new(&modules, &require_mode, context);
+
+        if let Some(index) = processor.current_module {
+            processor.exported_name = get_exported_name(block);
+
+            if let Some(LastStatement::Return(statement)) = block.mutate_last_statement() {
+                if statement.len() == 1 {
+                    if let Some(Expression::Table(table)) = statement.iter_mut_expressions().next()
+                    {
+                        rename_table_entries(table, modules[index].1);
+                    }
+                }
+            }
+        }
+
+        ScopeVisitor::visit_block(block, &mut processor);
+
+        Ok(())
+    }
+}
+
+impl RuleConfiguration for RenameProjectSymbols {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_required_properties(&properties, &[SYMBOLS_PROPERTY])?;
+
+        for (key, value) in properties {
+            match key.as_str() {
+                SYMBOLS_PROPERTY => {
+                    let RulePropertyValue::Map(map) = value else {
+                        return Err(RuleConfigurationError::UnexpectedValueType(key));
+                    };
+
+                    for (module, renames) in map {
+                        let serde_json::Value::Object(renames) = renames else {
+                            return Err(RuleConfigurationError::UnexpectedValue {
+                                property: key,
+                                message: format!(
+                                    "the symbols of `{}` should be an object mapping field names to their new names",
+                                    module
+                                ),
+                            });
+                        };
+
+                        let mut fields = FieldRenames::new();
+
+                        for (from, to) in renames {
+                            let serde_json::Value::String(to) = to else {
+                                return Err(RuleConfigurationError::UnexpectedValue {
+                                    property: key,
+                                    message: format!(
+                                        "the new name of `{}` in `{}` should be a string",
+                                        from, module
+                                    ),
+                                });
+                            };
+
+                            if let Some(invalid) =
+                                [&from, &to].iter().find(|name| !is_valid_identifier(name))
+                            {
+                                return Err(RuleConfigurationError::UnexpectedValue {
+                                    property: key,
+                                    message: format!("invalid field name `{}`", invalid),
+                                });
+                            }
+
+                            fields.insert(from, to);
+                        }
+
+                        self.symbols.insert(PathBuf::from(module), fields);
+                    }
+                }
+                REQUIRE_MODE_PROPERTY => {
+                    self.require_mode = Some(value.expect_require_mode(&key)?);
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        RENAME_PROJECT_SYMBOLS_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        properties.insert(
+            SYMBOLS_PROPERTY.to_owned(),
+            RulePropertyValue::Map(
+                self.symbols
+                    .iter()
+                    .map(|(module, renames)| {
+                        (
+                            module_key(module),
+                            serde_json::Value::Object(
+                                renames
+                                    .iter()
+                                    .map(|(from, to)| {
+                                        (from.clone(), serde_json::Value::String(to.clone()))
+                                    })
+                                    .collect(),
+                            ),
+                        )
+                    })
+                    .collect(),
+            ),
+        );
+
+        if let Some(require_mode) = &self.require_mode {
+            properties.insert(REQUIRE_MODE_PROPERTY.to_owned(), require_mode.into());
+        }
+
+        properties
+    }
+}
+
+fn module_key(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> RenameProjectSymbols {
+        RenameProjectSymbols::default().with_symbol("src/utils.lua", "deepCopy", "clone")
+    }
+
+    #[test]
+    fn serialize_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "rename_project_symbols",
+          "symbols": {
+            "src/utils.lua": {
+              "deepCopy": "clone"
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_require_mode() {
+        let rule: Box<dyn Rule> =
+            Box::new(new_rule().with_require_mode(RequireMode::Luau(Default::default())));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "rename_project_symbols",
+          "require_mode": "luau",
+          "symbols": {
+            "src/utils.lua": {
+              "deepCopy": "clone"
+            }
+          }
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_without_symbols_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_project_symbols',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"missing required field 'symbols' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_field_name_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_project_symbols',
+            symbols: { 'src/utils.lua': { deepCopy: 'deep copy' } },
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'symbols': invalid field name `deep copy` at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_module_symbols_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_project_symbols',
+            symbols: { 'src/utils.lua': 'clone' },
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'symbols': the symbols of `src/utils.lua` should be an object mapping field names to their new names at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_project_symbols',
+            symbols: {},
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -1,27 +1,55 @@
 mod function_names;
 mod globals;
+mod name_allocator;
 mod rename_processor;
 
-use rename_processor::RenameProcessor;
+use name_allocator::CollectDeclarations;
+use rename_processor::{RenameProcessor, RenamedIdentifier};
 
-use crate::nodes::Block;
+use crate::frontend::DarkluaResult;
+use crate::nodes::{Block, Expression, LastStatement, TableEntry};
 use crate::process::utils::is_valid_identifier;
 use crate::process::{DefaultVisitor, NodeVisitor, ScopeVisitor};
 use crate::rules::{
-    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    Context, Rule, RuleConfiguration, RuleConfigurationError, RuleProcessResult, RuleProperties,
     RulePropertyValue,
 };
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::iter::FromIterator;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 pub const RENAME_VARIABLES_RULE_NAME: &str = "rename_variables";
 
 /// Rename all identifiers to small and meaningless names.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug)]
 pub struct RenameVariables {
     globals: Vec<String>,
     include_functions: bool,
+    reserved: Vec<String>,
+    keep_exports: bool,
+    name_map_file: Option<PathBuf>,
+    name_map: Mutex<BTreeMap<String, Vec<RenamedIdentifier>>>,
+}
+
+impl PartialEq for RenameVariables {
+    fn eq(&self, other: &Self) -> bool {
+        self.globals == other.globals
+            && self.include_functions == other.include_functions
+            && self.reserved == other.reserved
+            && self.keep_exports == other.keep_exports
+            && self.name_map_file == other.name_map_file
+    }
+}
+
+impl Eq for RenameVariables {}
+
+/// Returns true if the rule writes a name map, which needs the tokens of the code
+/// to find the lines where the variables are declared.
+pub(crate) fn writes_name_map(rule: &dyn Rule) -> bool {
+    rule.get_name() == RENAME_VARIABLES_RULE_NAME
+        && rule.serialize_to_properties().contains_key("name_map_file")
 }
 
 impl RenameVariables {
@@ -29,6 +57,10 @@ impl RenameVariables {
         Self {
             globals: Vec::from_iter(iter),
             include_functions: false,
+            reserved: Vec::new(),
+            keep_exports: false,
+            name_map_file: None,
+            name_map: Default::default(),
         }
     }
 
@@ -37,6 +69,59 @@ impl RenameVariables {
         self
     }
 
+    /// Keeps the names of the variables named like one of the given identifiers, and
+    /// never generates these names for other variables.
+    pub fn with_reserved_names<I: IntoIterator<Item = String>>(mut self, iter: I) -> Self {
+        self.reserved.extend(iter);
+        self
+    }
+
+    /// Keeps the names of the local variables returned from the module.
+    pub fn with_kept_exports(mut self) -> Self {
+        self.keep_exports = true;
+        self
+    }
+
+    /// Writes the original names of the renamed identifiers (with the line where they
+    /// are declared) to a JSON file, grouped by source file.
+    pub fn with_name_map_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.name_map_file = Some(path.into());
+        self
+    }
+
+    fn write_name_map(
+        &self,
+        name_map_file: &Path,
+        names: Vec<RenamedIdentifier>,
+        context: &Context,
+    ) -> DarkluaResult<()> {
+        // files are processed in parallel: the map is built in memory and written
+        // while it is locked, so that the file always contains every entry
+        let mut name_map = self.name_map.lock().unwrap();
+        name_map.insert(context.current_path().display().to_string(), names);
+
+        let path = context.project_location().join(name_map_file);
+        context
+            .resources()
+            .write(&path, &serde_json::to_string_pretty(&*name_map)?)?;
+
+        Ok(())
+    }
+
+    fn set_reserved(&mut self, list: Vec<String>) -> Result<(), RuleConfigurationError> {
+        for value in list {
+            if !is_valid_identifier(&value) {
+                return Err(RuleConfigurationError::UnexpectedValue {
+                    property: "reserved".to_owned(),
+                    message: format!("invalid identifier `{}`", value),
+                });
+            }
+            self.reserved.push(value);
+        }
+
+        Ok(())
+    }
+
     fn set_globals(&mut self, list: Vec<String>) -> Result<(), RuleConfigurationError> {
         for value in list {
             match value.as_str() {
@@ -87,15 +172,47 @@ impl RenameVariables {
     }
 }
 
+/// Returns the names of the identifiers returned by the block, directly or as values
+/// of a returned table.
+fn get_exported_names(block: &Block) -> Vec<String> {
+    let Some(LastStatement::Return(statement)) = block.get_last_statement() else {
+        return Vec::new();
+    };
+
+    let mut names = Vec::new();
+
+    for expression in statement.iter_expressions() {
+        match expression {
+            Expression::Identifier(identifier) => names.push(identifier.get_name().to_owned()),
+            Expression::Table(table) => {
+                for entry in table.iter_entries() {
+                    let value = match entry {
+                        TableEntry::Field(field) => field.get_value(),
+                        TableEntry::Index(index) => index.get_value(),
+                        TableEntry::Value(value) => value,
+                    };
+
+                    if let Expression::Identifier(identifier) = value {
+                        names.push(identifier.get_name().to_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    names
+}
+
 impl Default for RenameVariables {
     fn default() -> Self {
         Self::new(globals::DEFAULT.iter().map(|string| (*string).to_owned()))
     }
 }
 
-impl FlawlessRule for RenameVariables {
-    fn flawless_process(&self, block: &mut Block, _: &Context) {
-        let avoid_identifiers = if self.include_functions {
+impl Rule for RenameVariables {
+    fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+        let function_names: Vec<String> = if self.include_functions {
             Vec::new()
         } else {
             let mut collect_functions = function_names::CollectFunctionNames::default();
@@ -103,11 +220,38 @@ impl FlawlessRule for RenameVariables {
             collect_functions.into()
         };
 
-        let mut processor = RenameProcessor::new(
-            self.globals.clone().into_iter().chain(avoid_identifiers),
-            self.include_functions,
-        );
-        ScopeVisitor::visit_block(block, &mut processor);
+        let reserved: HashSet<String> = self.reserved.iter().cloned().collect();
+        let exports = if self.keep_exports {
+            get_exported_names(block)
+        } else {
+            Vec::new()
+        };
+
+        let mut collect_declarations =
+            CollectDeclarations::new(self.include_functions, &reserved, &exports);
+        ScopeVisitor::visit_block(block, &mut collect_declarations);
+
+        let avoid_identifiers: HashSet<String> = self
+            .globals
+            .iter()
+            .cloned()
+            .chain(function_names)
+            .chain(reserved.iter().cloned())
+            .collect();
+        let names = collect_declarations.allocate_names(&avoid_identifiers);
+
+        let mut processor = RenameProcessor::new(names, self.include_functions);
+
+        if let Some(name_map_file) = &self.name_map_file {
+            processor = processor.record_names();
+            ScopeVisitor::visit_block(block, &mut processor);
+            self.write_name_map(name_map_file, processor.into_name_map(), context)
+                .map_err(|err| err.to_string())?;
+        } else {
+            ScopeVisitor::visit_block(block, &mut processor);
+        }
+
+        Ok(())
     }
 }
 
@@ -121,6 +265,15 @@ impl RuleConfiguration for RenameVariables {
                 "include_functions" => {
                     self.include_functions = value.expect_bool(&key)?;
                 }
+                "reserved" => {
+                    self.set_reserved(value.expect_string_list(&key)?)?;
+                }
+                "keep_exports" => {
+                    self.keep_exports = value.expect_bool(&key)?;
+                }
+                "name_map_file" => {
+                    self.name_map_file = Some(PathBuf::from(value.expect_string(&key)?));
+                }
                 _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
             }
         }
@@ -150,6 +303,27 @@ impl RuleConfiguration for RenameVariables {
             );
         }
 
+        if !self.reserved.is_empty() {
+            properties.insert(
+                "reserved".to_owned(),
+                RulePropertyValue::StringList(self.reserved.clone()),
+            );
+        }
+
+        if self.keep_exports {
+            properties.insert(
+                "keep_exports".to_owned(),
+                RulePropertyValue::Boolean(self.keep_exports),
+            );
+        }
+
+        if let Some(name_map_file) = &self.name_map_file {
+            properties.insert(
+                "name_map_file".to_owned(),
+                RulePropertyValue::String(name_map_file.display().to_string()),
+            );
+        }
+
         properties
     }
 }
@@ -201,6 +375,38 @@ mod test {
         );
     }
 
+    #[test]
+    fn serialize_with_name_map_file() {
+        let rule: Box<dyn Rule> =
+            Box::new(RenameVariables::default().with_name_map_file("names.json"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "rename_variables",
+          "name_map_file": "names.json"
+        }
+        "###);
+    }
+
+    #[test]
+    fn serialize_with_reserved_names_and_kept_exports() {
+        let rule: Box<dyn Rule> = Box::new(
+            RenameVariables::default()
+                .with_reserved_names(vec!["module".to_owned()])
+                .with_kept_exports(),
+        );
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "rename_variables",
+          "keep_exports": true,
+          "reserved": [
+            "module"
+          ]
+        }
+        "###);
+    }
+
     #[test]
     fn serialize_skip_functions() {
         let rule = Box::new(RenameVariables::new(
@@ -0,0 +1,251 @@
+use crate::nodes::{Expression, Identifier, LocalFunctionStatement, TypeField};
+use crate::process::utils::{identifier_permutator, CharPermutator, KEYWORDS};
+use crate::process::{NodeProcessor, Scope};
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug)]
+struct Declaration {
+    name: String,
+    start: usize,
+    end: usize,
+    references: usize,
+    keep_name: bool,
+}
+
+#[derive(Debug, Default)]
+struct ScopeDeclarations {
+    names: HashMap<String, usize>,
+    declarations: Vec<usize>,
+}
+
+/// Collects every declaration in the order the rename processor visits them, with
+/// the number of references to each of them and the range of the traversal where
+/// they are in scope.
+#[derive(Debug)]
+pub struct CollectDeclarations<'a> {
+    declarations: Vec<Declaration>,
+    scopes: Vec<ScopeDeclarations>,
+    time: usize,
+    free_names: HashSet<String>,
+    include_functions: bool,
+    reserved: &'a HashSet<String>,
+    exports: &'a [String],
+}
+
+impl<'a> CollectDeclarations<'a> {
+    pub fn new(
+        include_functions: bool,
+        reserved: &'a HashSet<String>,
+        exports: &'a [String],
+    ) -> Self {
+        Self {
+            declarations: Vec::new(),
+            scopes: Vec::new(),
+            time: 0,
+            free_names: HashSet::new(),
+            include_functions,
+            reserved,
+            exports,
+        }
+    }
+
+    fn add(&mut self, name: String, keep_name: bool) {
+        if self.scopes.is_empty() {
+            self.scopes.push(ScopeDeclarations::default());
+        }
+
+        self.time += 1;
+        let index = self.declarations.len();
+        let keep_name = keep_name || self.reserved.contains(&name);
+
+        let scope = self.scopes.last_mut().expect("a scope should exist");
+        scope.names.insert(name.clone(), index);
+        scope.declarations.push(index);
+
+        self.declarations.push(Declaration {
+            name,
+            start: self.time,
+            end: usize::MAX,
+            references: 0,
+            keep_name,
+        });
+    }
+
+    fn reference(&mut self, name: &str) {
+        let declaration = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.names.get(name).copied());
+
+        if let Some(index) = declaration {
+            self.declarations[index].references += 1;
+        } else if !self.free_names.contains(name) {
+            self.free_names.insert(name.to_owned());
+        }
+    }
+
+    /// Assigns a name to each collected declaration, in the order they were collected.
+    ///
+    /// The most referenced declarations get the shortest names. A name is never
+    /// given to two declarations that are in scope at the same time, and the names of
+    /// the globals referenced in the block are never generated.
+    pub fn allocate_names(self, avoid_identifiers: &HashSet<String>) -> Vec<String> {
+        let Self {
+            declarations,
+            free_names,
+            ..
+        } = self;
+
+        let mut candidates = Candidates::new(|identifier: &str| {
+            !avoid_identifiers.contains(identifier)
+                && !free_names.contains(identifier)
+                && !KEYWORDS.contains(&identifier)
+                && !identifier.chars().next().unwrap().is_ascii_digit()
+        });
+
+        // since declarations are collected in traversal order, the declarations in
+        // scope when a declaration starts are its ancestors, and the ones that start
+        // while it is in scope directly follow it
+        let mut parents: Vec<Option<usize>> = Vec::with_capacity(declarations.len());
+        let mut open: Vec<usize> = Vec::new();
+
+        for (index, declaration) in declarations.iter().enumerate() {
+            while let Some(parent) = open.last() {
+                if declarations[*parent].end >= declaration.start {
+                    break;
+                }
+                open.pop();
+            }
+            parents.push(open.last().copied());
+            open.push(index);
+        }
+
+        let mut names: Vec<Option<String>> = declarations
+            .iter()
+            .map(|declaration| declaration.keep_name.then(|| declaration.name.clone()))
+            .collect();
+
+        let mut order: Vec<usize> = (0..declarations.len())
+            .filter(|index| !declarations[*index].keep_name)
+            .collect();
+        order.sort_by_key(|index| (Reverse(declarations[*index].references), *index));
+
+        for index in order {
+            let end = declarations[index].end;
+
+            let mut used: HashSet<&str> = HashSet::new();
+
+            let mut parent = parents[index];
+            while let Some(parent_index) = parent {
+                if let Some(name) = &names[parent_index] {
+                    used.insert(name);
+                }
+                parent = parents[parent_index];
+            }
+
+            used.extend(
+                declarations
+                    .iter()
+                    .enumerate()
+                    .skip(index + 1)
+                    .take_while(|(_, declaration)| declaration.start <= end)
+                    .filter_map(|(other, _)| names[other].as_deref()),
+            );
+
+            let name = candidates.find(|candidate| !used.contains(candidate));
+            names[index] = Some(name);
+        }
+
+        names
+            .into_iter()
+            .map(|name| name.expect("all declarations should have a name"))
+            .collect()
+    }
+}
+
+/// The list of generated identifiers, ordered from the shortest to the longest.
+struct Candidates<F> {
+    permutator: CharPermutator,
+    generated: Vec<String>,
+    filter: F,
+}
+
+impl<F: Fn(&str) -> bool> Candidates<F> {
+    fn new(filter: F) -> Self {
+        Self {
+            permutator: identifier_permutator(),
+            generated: Vec::new(),
+            filter,
+        }
+    }
+
+    fn find(&mut self, predicate: impl Fn(&str) -> bool) -> String {
+        if let Some(candidate) = self.generated.iter().find(|name| predicate(name)) {
+            return candidate.clone();
+        }
+
+        loop {
+            let generated = self.permutator.next().unwrap();
+
+            if (self.filter)(&generated) {
+                self.generated.push(generated.clone());
+
+                if predicate(&generated) {
+                    return generated;
+                }
+            }
+        }
+    }
+}
+
+impl Scope for CollectDeclarations<'_> {
+    fn push(&mut self) {
+        self.scopes.push(ScopeDeclarations::default());
+    }
+
+    fn pop(&mut self) {
+        if let Some(scope) = self.scopes.pop() {
+            if self.scopes.is_empty() {
+                for name in self.exports {
+                    if let Some(index) = scope.names.get(name) {
+                        self.declarations[*index].keep_name = true;
+                    }
+                }
+            }
+
+            for index in scope.declarations {
+                self.declarations[index].end = self.time;
+            }
+        }
+    }
+
+    fn insert(&mut self, identifier: &mut String) {
+        self.add(identifier.clone(), false);
+    }
+
+    fn insert_self(&mut self) {
+        self.add("self".to_owned(), true);
+    }
+
+    fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
+        self.add(identifier.clone(), false);
+    }
+
+    fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
+        let name = function.get_identifier().get_name().clone();
+        self.add(name, !self.include_functions);
+    }
+}
+
+impl NodeProcessor for CollectDeclarations<'_> {
+    fn process_variable_expression(&mut self, variable: &mut Identifier) {
+        self.reference(variable.get_name());
+    }
+
+    fn process_type_field(&mut self, type_field: &mut TypeField) {
+        self.reference(type_field.get_namespace().get_name());
+    }
+}
@@ -1,41 +1,57 @@
 use crate::nodes::{Expression, Identifier, LocalFunctionStatement, TypeField};
-use crate::process::utils::{identifier_permutator, CharPermutator};
-use crate::process::{utils::KEYWORDS, NodeProcessor, Scope};
+use crate::process::{NodeProcessor, Scope};
 
-use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
-use std::iter::FromIterator;
-use std::mem;
+use serde::{Deserialize, Serialize};
+
+use std::collections::HashMap;
+use std::{mem, vec};
+
+/// A renamed identifier, recorded when a name map is requested.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenamedIdentifier {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub renamed: String,
+}
 
 #[derive(Debug)]
 pub struct RenameProcessor {
-    real_to_obfuscated: Vec<HashMap<String, (String, bool)>>,
-    permutator: CharPermutator,
-    avoid_identifier: HashSet<String>,
-    reuse_identifiers: Vec<String>,
+    real_to_obfuscated: Vec<HashMap<String, String>>,
+    names: vec::IntoIter<String>,
     include_functions: bool,
+    name_map: Option<Vec<RenamedIdentifier>>,
+    declared_line: Option<usize>,
 }
 
 impl RenameProcessor {
-    pub fn new<I: IntoIterator<Item = String>>(iter: I, include_functions: bool) -> Self {
-        let mut avoid_identifier = HashSet::from_iter(iter);
-        avoid_identifier.extend(KEYWORDS.iter().map(|s| (*s).to_owned()));
-
+    /// Creates a processor that gives the names to the declarations in the order
+    /// they are visited.
+    pub fn new<I: IntoIterator<Item = String>>(names: I, include_functions: bool) -> Self {
         Self {
             real_to_obfuscated: Vec::new(),
-            permutator: identifier_permutator(),
-            avoid_identifier,
-            reuse_identifiers: Vec::new(),
+            names: names.into_iter().collect::<Vec<_>>().into_iter(),
             include_functions,
+            name_map: None,
+            declared_line: None,
         }
     }
 
-    pub fn add(&mut self, real: String, obfuscated: String, reuse: bool) {
+    pub fn record_names(mut self) -> Self {
+        self.name_map = Some(Vec::new());
+        self
+    }
+
+    pub fn into_name_map(self) -> Vec<RenamedIdentifier> {
+        self.name_map.unwrap_or_default()
+    }
+
+    pub fn add(&mut self, real: String, obfuscated: String) {
         if let Some(dictionary) = self.real_to_obfuscated.last_mut() {
-            dictionary.insert(real, (obfuscated, reuse));
+            dictionary.insert(real, obfuscated);
         } else {
             let mut dictionary = HashMap::new();
-            dictionary.insert(real, (obfuscated, reuse));
+            dictionary.insert(real, obfuscated);
             self.real_to_obfuscated.push(dictionary);
         }
     }
@@ -44,78 +60,31 @@ impl RenameProcessor {
         self.real_to_obfuscated
             .iter()
             .rev()
-            .find_map(|dictionary| dictionary.get(real).map(|(name, _)| name))
+            .find_map(|dictionary| dictionary.get(real))
     }
 
-    pub fn generate_identifier(&mut self) -> String {
-        if let Some(identifier) = self.reuse_identifiers.pop() {
-            identifier
-        } else {
-            let generated = self.permutator.next().unwrap();
-
-            if self.filter_identifier(&generated) {
-                generated
-            } else {
-                self.generate_identifier()
-            }
-        }
-    }
-
-    fn filter_identifier(&self, identifier: &str) -> bool {
-        !self.avoid_identifier.contains(identifier)
-            && !identifier.chars().next().unwrap().is_ascii_digit()
+    fn next_name(&mut self, real: &str) -> String {
+        self.names.next().unwrap_or_else(|| real.to_owned())
     }
 
     fn replace_identifier(&mut self, identifier: &mut String) {
         let original = mem::take(identifier);
-        let obfuscated_name = self.generate_identifier();
+        let obfuscated_name = self.next_name(&original);
 
         identifier.push_str(&obfuscated_name);
 
-        self.add(original, obfuscated_name, true);
-    }
-}
-
-fn sort_char(a: char, b: char) -> Ordering {
-    if a == b {
-        Ordering::Equal
-    } else if a.is_ascii_digit() && b.is_ascii_digit()
-        || a.is_lowercase() && b.is_lowercase()
-        || a.is_uppercase() && b.is_uppercase()
-    {
-        a.cmp(&b)
-    } else {
-        match (a, b) {
-            (a, _) if a.is_ascii_digit() => Ordering::Greater,
-            (_, b) if b.is_ascii_digit() => Ordering::Less,
-            ('_', _) => Ordering::Greater,
-            (_, '_') => Ordering::Less,
-            (a, _) if a.is_lowercase() => Ordering::Less,
-            (_, b) if b.is_lowercase() => Ordering::Greater,
-            _ => Ordering::Equal,
-        }
-    }
-}
-
-fn sort_identifiers(a: &str, b: &str) -> Ordering {
-    let mut b_chars = b.chars();
-
-    for a_char in a.chars() {
-        if let Some(b_char) = b_chars.next() {
-            match sort_char(a_char, b_char) {
-                Ordering::Less => return Ordering::Less,
-                Ordering::Greater => return Ordering::Greater,
-                Ordering::Equal => {}
+        let line = self.declared_line.take();
+        if let Some(name_map) = &mut self.name_map {
+            if original != obfuscated_name {
+                name_map.push(RenamedIdentifier {
+                    name: original.clone(),
+                    line,
+                    renamed: obfuscated_name.clone(),
+                });
             }
-        } else {
-            return Ordering::Greater;
         }
-    }
 
-    if b_chars.next().is_some() {
-        Ordering::Less
-    } else {
-        Ordering::Equal
+        self.add(original, obfuscated_name);
     }
 }
 
@@ -125,15 +94,7 @@ impl Scope for RenameProcessor {
     }
 
     fn pop(&mut self) {
-        if let Some(dictionary) = self.real_to_obfuscated.pop() {
-            self.reuse_identifiers.extend(
-                dictionary
-                    .into_values()
-                    .filter_map(|(name, reuse)| reuse.then_some(name)),
-            );
-            self.reuse_identifiers
-                .sort_by(|a, b| sort_identifiers(a, b).reverse());
-        }
+        self.real_to_obfuscated.pop();
     }
 
     fn insert(&mut self, identifier: &mut String) {
@@ -141,7 +102,8 @@ impl Scope for RenameProcessor {
     }
 
     fn insert_self(&mut self) {
-        self.add("self".to_owned(), "self".to_owned(), false);
+        let name = self.next_name("self");
+        self.add("self".to_owned(), name);
     }
 
     fn insert_local(&mut self, identifier: &mut String, _value: Option<&mut Expression>) {
@@ -150,12 +112,20 @@ impl Scope for RenameProcessor {
 
     fn insert_local_function(&mut self, function: &mut LocalFunctionStatement) {
         if self.include_functions {
+            self.declare(function.get_identifier());
             self.replace_identifier(function.mutate_identifier().mutate_name());
         } else {
-            let name = function.mutate_identifier().get_name();
-            self.add(name.clone(), name.to_owned(), false);
+            let name = function.get_identifier().get_name().clone();
+            let obfuscated_name = self.next_name(&name);
+            self.add(name, obfuscated_name);
         }
     }
+
+    fn declare(&mut self, identifier: &Identifier) {
+        self.declared_line = identifier
+            .get_token()
+            .and_then(|token| token.get_line_number());
+    }
 }
 
 impl NodeProcessor for RenameProcessor {
@@ -193,7 +163,7 @@ mod test {
         let real = "a".to_owned();
         let obfuscated = "b".to_owned();
 
-        scope.add(real.clone(), obfuscated.clone(), true);
+        scope.add(real.clone(), obfuscated.clone());
 
         assert_eq!(&obfuscated, scope.get_obfuscated_name(&real).unwrap());
     }
@@ -205,7 +175,7 @@ mod test {
         let obfuscated = "def".to_owned();
 
         scope.push();
-        scope.add(real.clone(), obfuscated, true);
+        scope.add(real.clone(), obfuscated);
         scope.pop();
 
         assert_eq!(None, scope.get_obfuscated_name(&real));
@@ -218,74 +188,26 @@ mod test {
         let obfuscated = "b".to_owned();
         let other_obfuscated = "c".to_owned();
 
-        scope.add(real.clone(), obfuscated.clone(), true);
+        scope.add(real.clone(), obfuscated.clone());
 
         scope.push();
-        scope.add(real.clone(), other_obfuscated, true);
+        scope.add(real.clone(), other_obfuscated);
         scope.pop();
 
         assert_eq!(&obfuscated, scope.get_obfuscated_name(&real).unwrap());
     }
 
     #[test]
-    fn sort_char_digits() {
-        assert_eq!(sort_char('0', '1'), Ordering::Less);
-        assert_eq!(sort_char('1', '2'), Ordering::Less);
-        assert_eq!(sort_char('4', '2'), Ordering::Greater);
-        assert_eq!(sort_char('5', '5'), Ordering::Equal);
-    }
+    fn inserted_identifiers_get_the_given_names() {
+        let mut scope = RenameProcessor::new(vec!["b".to_owned(), "a".to_owned()], true);
+        let mut first = "foo".to_owned();
+        let mut second = "bar".to_owned();
 
-    #[test]
-    fn sort_char_lowercase_letters() {
-        assert_eq!(sort_char('a', 'f'), Ordering::Less);
-        assert_eq!(sort_char('y', 'i'), Ordering::Greater);
-        assert_eq!(sort_char('t', 't'), Ordering::Equal);
-    }
+        scope.insert_local(&mut first, None);
+        scope.insert(&mut second);
 
-    #[test]
-    fn sort_char_uppercase_letters() {
-        assert_eq!(sort_char('A', 'F'), Ordering::Less);
-        assert_eq!(sort_char('Y', 'I'), Ordering::Greater);
-        assert_eq!(sort_char('T', 'T'), Ordering::Equal);
-    }
-
-    #[test]
-    fn sort_char_underscore_is_less_than_digit() {
-        for digit in "0123456789".chars() {
-            assert_eq!(sort_char('_', digit), Ordering::Less);
-        }
-    }
-
-    #[test]
-    fn sort_char_underscore_is_greather_than_letters() {
-        assert_eq!(sort_char('_', 'a'), Ordering::Greater);
-        assert_eq!(sort_char('_', 'A'), Ordering::Greater);
-        assert_eq!(sort_char('a', '_'), Ordering::Less);
-        assert_eq!(sort_char('A', '_'), Ordering::Less);
-    }
-
-    #[test]
-    fn sort_char_lowercase_is_less_than_uppercase() {
-        assert_eq!(sort_char('a', 'A'), Ordering::Less);
-        assert_eq!(sort_char('A', 'a'), Ordering::Greater);
-        assert_eq!(sort_char('z', 'A'), Ordering::Less);
-        assert_eq!(sort_char('A', 'z'), Ordering::Greater);
-    }
-
-    #[test]
-    fn sort_identifiers_compare_chars() {
-        assert_eq!(sort_identifiers("foo", "foo"), Ordering::Equal);
-        assert_eq!(sort_identifiers("aA", "ab"), Ordering::Greater);
-        assert_eq!(sort_identifiers("foo1", "foo9"), Ordering::Less);
-    }
-
-    #[test]
-    fn sort_identifiers_shorter_is_less() {
-        assert_eq!(sort_identifiers("foo", "fooo"), Ordering::Less);
-    }
-
-    #[test]
-    fn sort_identifiers_longer_is_greather() {
-        assert_eq!(sort_identifiers("foo", "fo"), Ordering::Greater);
+        assert_eq!(first, "b");
+        assert_eq!(second, "a");
+        assert_eq!(Some(&"b".to_owned()), scope.get_obfuscated_name("foo"));
     }
 }
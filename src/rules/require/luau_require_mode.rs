@@ -57,14 +57,8 @@ impl LuauRequireMode {
             return Ok(());
         }
 
-        // Load aliases from .luaurc configuration
-        if let Some(config) =
-            utils::find_luau_configuration(context.current_path(), context.resources())?
-        {
-            self.luau_rc_aliases.replace(config.aliases);
-        } else {
-            self.luau_rc_aliases.take();
-        }
+        // load aliases from .luaurc configuration files
+        self.luau_rc_aliases = context.luau_aliases()?.cloned();
 
         Ok(())
     }
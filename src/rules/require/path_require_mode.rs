@@ -74,13 +74,7 @@ impl PathRequireMode {
             return Ok(());
         }
 
-        if let Some(config) =
-            utils::find_luau_configuration(context.current_path(), context.resources())?
-        {
-            self.luau_rc_aliases.replace(config.aliases);
-        } else {
-            self.luau_rc_aliases.take();
-        }
+        self.luau_rc_aliases = context.luau_aliases()?.cloned();
 
         Ok(())
     }
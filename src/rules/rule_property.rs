@@ -34,7 +34,8 @@ pub enum RulePropertyValue {
 }
 
 impl RulePropertyValue {
-    pub(crate) fn expect_bool(self, key: &str) -> Result<bool, RuleConfigurationError> {
+    /// Returns the boolean value, or an error mentioning the given property name.
+    pub fn expect_bool(self, key: &str) -> Result<bool, RuleConfigurationError> {
         if let Self::Boolean(value) = self {
             Ok(value)
         } else {
@@ -42,7 +43,8 @@ impl RulePropertyValue {
         }
     }
 
-    pub(crate) fn expect_string(self, key: &str) -> Result<String, RuleConfigurationError> {
+    /// Returns the string value, or an error mentioning the given property name.
+    pub fn expect_string(self, key: &str) -> Result<String, RuleConfigurationError> {
         if let Self::String(value) = self {
             Ok(value)
         } else {
@@ -50,10 +52,17 @@ impl RulePropertyValue {
         }
     }
 
-    pub(crate) fn expect_string_list(
-        self,
-        key: &str,
-    ) -> Result<Vec<String>, RuleConfigurationError> {
+    /// Returns the unsigned integer value, or an error mentioning the given property name.
+    pub fn expect_usize(self, key: &str) -> Result<usize, RuleConfigurationError> {
+        if let Self::Usize(value) = self {
+            Ok(value)
+        } else {
+            Err(RuleConfigurationError::UsizeExpected(key.to_owned()))
+        }
+    }
+
+    /// Returns the list of strings, or an error mentioning the given property name.
+    pub fn expect_string_list(self, key: &str) -> Result<Vec<String>, RuleConfigurationError> {
         if let Self::StringList(value) = self {
             Ok(value)
         } else {
@@ -61,7 +70,9 @@ impl RulePropertyValue {
         }
     }
 
-    pub(crate) fn expect_regex_list(self, key: &str) -> Result<Vec<Regex>, RuleConfigurationError> {
+    /// Returns the list of strings parsed as regular expressions, or an error mentioning
+    /// the given property name.
+    pub fn expect_regex_list(self, key: &str) -> Result<Vec<Regex>, RuleConfigurationError> {
         if let Self::StringList(value) = self {
             value
                 .into_iter()
@@ -77,10 +88,9 @@ impl RulePropertyValue {
         }
     }
 
-    pub(crate) fn expect_require_mode(
-        self,
-        key: &str,
-    ) -> Result<RequireMode, RuleConfigurationError> {
+    /// Returns the require mode (which can also be given by its name), or an error
+    /// mentioning the given property name.
+    pub fn expect_require_mode(self, key: &str) -> Result<RequireMode, RuleConfigurationError> {
         match self {
             Self::RequireMode(require_mode) => Ok(require_mode),
             Self::String(value) => {
@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+use super::{get_all_rule_names, Rule};
+
+type RuleConstructor = Arc<dyn Fn() -> Box<dyn Rule> + Send + Sync>;
+
+fn custom_rules() -> &'static RwLock<HashMap<String, RuleConstructor>> {
+    static CUSTOM_RULES: OnceLock<RwLock<HashMap<String, RuleConstructor>>> = OnceLock::new();
+    CUSTOM_RULES.get_or_init(Default::default)
+}
+
+/// Registers a custom rule, so that it can be used by name in configurations
+/// (like `{ rules: ["my_rule"] }` in a configuration file).
+///
+/// The constructor is called each time the rule is created from its name. The
+/// created rule is then configured with the properties given in the configuration.
+/// Registering a rule with the name of another custom rule replaces it.
+///
+/// Returns false when the name is already used by a darklua rule, in which case the
+/// constructor is ignored.
+///
+/// ```rust
+/// # use darklua_core::nodes::Block;
+/// # use darklua_core::rules::*;
+/// #[derive(Debug, Default)]
+/// struct MyRule;
+///
+/// impl FlawlessRule for MyRule {
+///     fn flawless_process(&self, _block: &mut Block, _context: &Context) {}
+/// }
+///
+/// impl RuleConfiguration for MyRule {
+///     fn configure(&mut self, _properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+///         Ok(())
+///     }
+///
+///     fn get_name(&self) -> &'static str {
+///         "my_rule"
+///     }
+///
+///     fn serialize_to_properties(&self) -> RuleProperties {
+///         RuleProperties::new()
+///     }
+/// }
+///
+/// assert!(darklua_core::register_rule("my_rule", || Box::new(MyRule::default())));
+///
+/// let rule: Box<dyn Rule> = "my_rule".parse().unwrap();
+/// assert_eq!(rule.get_name(), "my_rule");
+/// ```
+pub fn register_rule<F>(name: impl Into<String>, constructor: F) -> bool
+where
+    F: Fn() -> Box<dyn Rule> + Send + Sync + 'static,
+{
+    let name = name.into();
+
+    if get_all_rule_names().contains(&name.as_str()) {
+        log::warn!(
+            "unable to register custom rule `{}` because darklua already has a rule with that name",
+            name
+        );
+        return false;
+    }
+
+    custom_rules()
+        .write()
+        .expect("custom rules lock should not be poisoned")
+        .insert(name, Arc::new(constructor));
+    true
+}
+
+/// Returns the names of the custom rules registered with [`register_rule`].
+pub fn get_custom_rule_names() -> Vec<String> {
+    let mut names: Vec<_> = custom_rules()
+        .read()
+        .expect("custom rules lock should not be poisoned")
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+pub(crate) fn create_custom_rule(name: &str) -> Option<Box<dyn Rule>> {
+    // the lock is released before calling the constructor, so that it can use the
+    // registry (for example to register or create other rules)
+    let constructor = custom_rules()
+        .read()
+        .expect("custom rules lock should not be poisoned")
+        .get(name)
+        .cloned()?;
+
+    Some(constructor())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::RemoveEmptyDo;
+
+    #[test]
+    fn register_rule_with_builtin_name_is_rejected() {
+        assert!(!register_rule("remove_empty_do", || Box::new(
+            RemoveEmptyDo::default()
+        )));
+    }
+
+    #[test]
+    fn unknown_custom_rule_is_not_created() {
+        assert!(create_custom_rule("unregistered_custom_rule").is_none());
+    }
+
+    #[test]
+    fn registered_custom_rule_is_created() {
+        assert!(register_rule("test_registry_custom_rule", || Box::new(
+            RemoveEmptyDo::default()
+        )));
+
+        assert!(create_custom_rule("test_registry_custom_rule").is_some());
+        assert!(get_custom_rule_names().contains(&"test_registry_custom_rule".to_owned()));
+    }
+
+    #[test]
+    fn custom_rule_constructor_can_register_rules() {
+        assert!(register_rule("test_registry_registering_rule", || {
+            register_rule("test_registry_registered_rule", || {
+                Box::new(RemoveEmptyDo::default())
+            });
+            Box::new(RemoveEmptyDo::default())
+        }));
+
+        assert!(create_custom_rule("test_registry_registering_rule").is_some());
+        assert!(create_custom_rule("test_registry_registered_rule").is_some());
+    }
+}
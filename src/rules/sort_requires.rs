@@ -0,0 +1,430 @@
+use std::collections::{HashMap, HashSet};
+use std::ops;
+
+use crate::generator::{DenseLuaGenerator, LuaGenerator};
+use crate::nodes::{
+    Arguments, Block, Expression, FunctionStatement, Identifier, LastStatement, Statement,
+    TypeField, Variable,
+};
+use crate::process::processors::FindUsage;
+use crate::process::{DefaultVisitor, IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::require::is_require_call;
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+};
+
+use super::verify_no_rule_properties;
+
+const REQUIRE_FUNCTION_IDENTIFIER: &str = "require";
+
+fn declared_names(statement: &Statement) -> Vec<&str> {
+    match statement {
+        Statement::LocalAssign(assign) => assign
+            .iter_variables()
+            .map(|variable| variable.get_name().as_str())
+            .collect(),
+        Statement::LocalFunction(function) => vec![function.get_name()],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns true if the statement contains a `return` statement that can exit the
+/// function owning the statement (the returns of nested functions are ignored).
+fn may_return(statement: &Statement) -> bool {
+    match statement {
+        Statement::Do(do_statement) => block_may_return(do_statement.get_block()),
+        Statement::If(if_statement) => {
+            if_statement
+                .iter_branches()
+                .any(|branch| block_may_return(branch.get_block()))
+                || if_statement.get_else_block().is_some_and(block_may_return)
+        }
+        Statement::While(while_statement) => block_may_return(while_statement.get_block()),
+        Statement::Repeat(repeat_statement) => block_may_return(repeat_statement.get_block()),
+        Statement::NumericFor(numeric_for) => block_may_return(numeric_for.get_block()),
+        Statement::GenericFor(generic_for) => block_may_return(generic_for.get_block()),
+        _ => false,
+    }
+}
+
+fn block_may_return(block: &Block) -> bool {
+    matches!(block.get_last_statement(), Some(LastStatement::Return(_)))
+        || block.iter_statements().any(may_return)
+}
+
+/// Returns the arguments of a statement like `local name = require(...)`. The value
+/// must be the require call itself: values using the result of the call (like
+/// `require(...)(config)` or `require(...).field`) are not matched.
+fn match_require_assignment(statement: &Statement) -> Option<(&str, &Arguments)> {
+    let Statement::LocalAssign(assign) = statement else {
+        return None;
+    };
+
+    if assign.variables_len() != 1 || assign.values_len() != 1 {
+        return None;
+    }
+
+    let Some(Expression::Call(call)) = assign.last_value() else {
+        return None;
+    };
+
+    if !is_require_call(call, &IdentifierTracker::new()) {
+        return None;
+    }
+
+    let arguments = call.get_arguments();
+    match arguments {
+        Arguments::String(_) => {}
+        Arguments::Tuple(tuple) if tuple.len() == 1 => {}
+        _ => return None,
+    }
+
+    let name = assign.get_variables().first()?.get_name();
+
+    Some((name, arguments))
+}
+
+/// Returns the value used to sort and compare require calls: the path for string
+/// arguments, or the generated code of the argument otherwise.
+fn get_require_key(arguments: &Arguments) -> String {
+    let argument = match arguments {
+        Arguments::String(string) => {
+            return String::from_utf8_lossy(string.get_value()).into_owned();
+        }
+        Arguments::Tuple(tuple) => tuple.iter_values().next(),
+        Arguments::Table(_) => None,
+    };
+
+    match argument {
+        Some(Expression::String(string)) => {
+            String::from_utf8_lossy(string.get_value()).into_owned()
+        }
+        Some(expression) => {
+            let mut generator = DenseLuaGenerator::default();
+            generator.write_expression(expression);
+            generator.into_string()
+        }
+        None => String::new(),
+    }
+}
+
+#[derive(Default)]
+struct VariableCollector {
+    names: HashSet<String>,
+}
+
+impl NodeProcessor for VariableCollector {
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        self.names.insert(identifier.get_name().to_owned());
+    }
+}
+
+fn get_argument_variables(arguments: &Arguments) -> HashSet<String> {
+    let mut collector = VariableCollector::default();
+
+    for mut expression in arguments.clone().to_expressions() {
+        DefaultVisitor::visit_expression(&mut expression, &mut collector);
+    }
+
+    collector.names
+}
+
+/// A processor that renames the uses of a variable, and finds if renaming would change
+/// the meaning of the code.
+struct RenameVariable<'a> {
+    from: &'a str,
+    to: &'a str,
+    apply: bool,
+    conflict: bool,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl ops::Deref for RenameVariable<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for RenameVariable<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl<'a> RenameVariable<'a> {
+    fn new(from: &'a str, to: &'a str, apply: bool) -> Self {
+        Self {
+            from,
+            to,
+            apply,
+            conflict: false,
+            identifier_tracker: Default::default(),
+        }
+    }
+
+    fn refers_to(&self, identifier: &Identifier, name: &str) -> bool {
+        identifier.get_name() == name && !self.is_identifier_used(name)
+    }
+
+    fn rename(&mut self, identifier: &mut Identifier) {
+        if !self.refers_to(identifier, self.from) {
+            return;
+        }
+
+        if self.is_identifier_used(self.to) {
+            self.conflict = true;
+        } else if self.apply {
+            identifier.set_name(self.to);
+        }
+    }
+
+    /// An assignment to one of the variables would give them different values.
+    fn verify_assignment(&mut self, identifier: &Identifier) {
+        if self.refers_to(identifier, self.from) || self.refers_to(identifier, self.to) {
+            self.conflict = true;
+        }
+    }
+}
+
+impl NodeProcessor for RenameVariable<'_> {
+    fn process_variable(&mut self, variable: &mut Variable) {
+        if let Variable::Identifier(identifier) = variable {
+            self.verify_assignment(identifier);
+        }
+    }
+
+    fn process_function_statement(&mut self, function: &mut FunctionStatement) {
+        let name = function.get_name();
+
+        if name.get_field_names().is_empty() && !name.has_method() {
+            self.verify_assignment(name.get_name());
+        }
+    }
+
+    fn process_variable_expression(&mut self, identifier: &mut Identifier) {
+        self.rename(identifier);
+    }
+
+    fn process_type_field(&mut self, type_field: &mut TypeField) {
+        self.rename(type_field.mutate_namespace());
+    }
+}
+
+/// Renames a variable in the given statements (visited separately, so that only the
+/// variables declared inside them are tracked). Returns false without renaming when
+/// the rename is not possible.
+fn rename_variable(block: &mut Block, skip: &HashSet<usize>, from: &str, to: &str) -> bool {
+    for apply in [false, true] {
+        for (index, statement) in block.iter_mut_statements().enumerate() {
+            if skip.contains(&index) {
+                continue;
+            }
+
+            let mut processor = RenameVariable::new(from, to, apply);
+            ScopeVisitor::visit_statement(statement, &mut processor);
+
+            if processor.conflict {
+                return false;
+            }
+        }
+
+        if let Some(last_statement) = block.mutate_last_statement() {
+            let mut processor = RenameVariable::new(from, to, apply);
+            ScopeVisitor::visit_last_statement(last_statement, &mut processor);
+
+            if processor.conflict {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+struct RequireAssignment {
+    index: usize,
+    name: String,
+    key: String,
+}
+
+/// Returns true if one of the statements before the given index reads or assigns the
+/// global variable that would be replaced by moving the local variable above it.
+fn is_used_before(block: &mut Block, index: usize, name: &str) -> bool {
+    block.iter_mut_statements().take(index).any(|statement| {
+        let mut find_usage = FindUsage::new(name);
+        ScopeVisitor::visit_statement(statement, &mut find_usage);
+
+        let mut find_assignment = RenameVariable::new(name, name, false);
+        ScopeVisitor::visit_statement(statement, &mut find_assignment);
+
+        find_usage.has_found_usage() || find_assignment.conflict
+    })
+}
+
+pub const SORT_REQUIRES_RULE_NAME: &str = "sort_requires";
+
+/// A rule that moves the require calls assigned to local variables to the top of the
+/// module, sorts them and merges the requires of the same module.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct SortRequires {}
+
+impl FlawlessRule for SortRequires {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut declaration_counts: HashMap<String, usize> = HashMap::new();
+        for statement in block.iter_statements() {
+            for name in declared_names(statement) {
+                *declaration_counts.entry(name.to_owned()).or_default() += 1;
+            }
+        }
+
+        if declaration_counts.contains_key(REQUIRE_FUNCTION_IDENTIFIER) {
+            return;
+        }
+
+        // moving a require above a statement that can return would load the module
+        // even when the file returns early
+        let first_return_index = block
+            .iter_statements()
+            .position(may_return)
+            .unwrap_or(usize::MAX);
+
+        let candidates: Vec<_> = block
+            .iter_statements()
+            .enumerate()
+            .take_while(|(index, _)| *index < first_return_index)
+            .filter_map(|(index, statement)| {
+                let (name, arguments) = match_require_assignment(statement)?;
+
+                // moving a require before the declaration of a variable used to find
+                // the module would change the module
+                let uses_local_variable = get_argument_variables(arguments)
+                    .iter()
+                    .any(|variable| declaration_counts.contains_key(variable));
+
+                (declaration_counts.get(name) == Some(&1) && !uses_local_variable).then(|| {
+                    RequireAssignment {
+                        index,
+                        name: name.to_owned(),
+                        key: get_require_key(arguments),
+                    }
+                })
+            })
+            .collect();
+
+        let mut requires: Vec<_> = candidates
+            .into_iter()
+            .filter(|require| !is_used_before(block, require.index, &require.name))
+            .collect();
+
+        if requires.is_empty() {
+            return;
+        }
+
+        requires.sort_by(|a, b| a.key.cmp(&b.key).then_with(|| a.name.cmp(&b.name)));
+
+        let require_indexes: HashSet<_> = requires.iter().map(|require| require.index).collect();
+
+        let mut removed_indexes = HashSet::new();
+        for (position, require) in requires.iter().enumerate() {
+            let Some(kept) = requires[..position]
+                .iter()
+                .find(|other| other.key == require.key && !removed_indexes.contains(&other.index))
+            else {
+                continue;
+            };
+
+            if rename_variable(block, &require_indexes, &require.name, &kept.name) {
+                removed_indexes.insert(require.index);
+            }
+        }
+
+        let first_statement_is_moved = require_indexes.contains(&0);
+        let has_tokens = block.get_tokens().is_some();
+
+        let mut statements: Vec<_> = block.take_statements().into_iter().map(Some).collect();
+
+        let mut sorted_statements: Vec<_> = requires
+            .iter()
+            .filter(|require| !removed_indexes.contains(&require.index))
+            .filter_map(|require| statements[require.index].take())
+            .collect();
+
+        for (index, statement) in statements.into_iter().enumerate() {
+            if let Some(statement) = statement.filter(|_| !require_indexes.contains(&index)) {
+                sorted_statements.push(statement);
+            }
+        }
+
+        // keep the comments at the beginning of the module (like `--!strict`) first
+        if has_tokens && !first_statement_is_moved {
+            if let Some(moved_trivia) = sorted_statements
+                .get_mut(requires.len() - removed_indexes.len())
+                .map(|statement| {
+                    statement
+                        .mutate_first_token()
+                        .drain_leading_trivia()
+                        .collect::<Vec<_>>()
+                })
+            {
+                if let Some(first_statement) = sorted_statements.first_mut() {
+                    let first_token = first_statement.mutate_first_token();
+                    for (index, trivia) in moved_trivia.into_iter().enumerate() {
+                        first_token.insert_leading_trivia(index, trivia);
+                    }
+                }
+            }
+        }
+
+        block.set_statements(sorted_statements);
+    }
+}
+
+impl RuleConfiguration for SortRequires {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        verify_no_rule_properties(&properties)?;
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        SORT_REQUIRES_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        RuleProperties::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> SortRequires {
+        SortRequires::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "sort_requires"
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'sort_requires',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+}
@@ -0,0 +1,480 @@
+use std::ops;
+
+use bstr::ByteSlice;
+use regex::Regex;
+
+use crate::nodes::{
+    Arguments, Block, Expression, FunctionCall, Prefix, StringExpression, TableExpression,
+    TupleArguments,
+};
+use crate::process::{IdentifierTracker, NodeProcessor, NodeVisitor, ScopeVisitor};
+use crate::rules::require::is_require_call;
+use crate::rules::{
+    Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties,
+    RulePropertyValue,
+};
+use crate::Parser;
+
+const DECODER_IDENTIFIER: &str = "__DARKLUA_STR_DEC";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum StringEncoding {
+    /// Writes strings as a table of bytes converted back with `string.char`.
+    #[default]
+    ByteEscape,
+    /// Writes strings as base64 strings.
+    Base64,
+    /// Writes strings as a table of bytes combined with a key using a xor operation.
+    Xor(Vec<u8>),
+}
+
+impl StringEncoding {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ByteEscape => "byte_escape",
+            Self::Base64 => "base64",
+            Self::Xor(_) => "xor",
+        }
+    }
+
+    fn encode(&self, value: &[u8]) -> Expression {
+        match self {
+            Self::ByteEscape => bytes_to_table(value.iter().copied()),
+            Self::Base64 => StringExpression::from_value(encode_base64(value)).into(),
+            Self::Xor(key) => bytes_to_table(
+                value
+                    .iter()
+                    .zip(key.iter().cycle())
+                    .map(|(byte, key_byte)| byte ^ key_byte),
+            ),
+        }
+    }
+
+    fn decoder_code(&self) -> String {
+        match self {
+            Self::ByteEscape => format!(
+                r#"local function {name}(bytes)
+    local characters = {{}}
+    for i = 1, #bytes do
+        characters[i] = string.char(bytes[i])
+    end
+    return table.concat(characters)
+end"#,
+                name = DECODER_IDENTIFIER
+            ),
+            Self::Base64 => format!(
+                r#"local function {name}(data)
+    local alphabet = "{alphabet}"
+    local characters = {{}}
+    local buffer, bits = 0, 0
+    for i = 1, #data do
+        local value = string.find(alphabet, string.sub(data, i, i), 1, true)
+        if value then
+            buffer = buffer * 64 + value - 1
+            bits = bits + 6
+            if bits >= 8 then
+                bits = bits - 8
+                local byte = math.floor(buffer / 2 ^ bits)
+                buffer = buffer - byte * 2 ^ bits
+                characters[#characters + 1] = string.char(byte)
+            end
+        end
+    end
+    return table.concat(characters)
+end"#,
+                name = DECODER_IDENTIFIER,
+                alphabet = BASE64_ALPHABET.to_str_lossy(),
+            ),
+            Self::Xor(key) => format!(
+                r#"local function {name}(bytes)
+    local key = {{ {key} }}
+    local characters = {{}}
+    for i = 1, #bytes do
+        local a, b = bytes[i], key[(i - 1) % #key + 1]
+        local result, bit = 0, 1
+        while a > 0 or b > 0 do
+            if a % 2 ~= b % 2 then
+                result = result + bit
+            end
+            a, b, bit = math.floor(a / 2), math.floor(b / 2), bit * 2
+        end
+        characters[i] = string.char(result)
+    end
+    return table.concat(characters)
+end"#,
+                name = DECODER_IDENTIFIER,
+                key = key
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        }
+    }
+}
+
+fn bytes_to_table(bytes: impl Iterator<Item = u8>) -> Expression {
+    bytes
+        .fold(TableExpression::default(), |table, byte| {
+            table.append_array_value(Expression::from(byte as f64))
+        })
+        .into()
+}
+
+fn encode_base64(value: &[u8]) -> String {
+    let mut encoded = String::with_capacity(value.len().div_ceil(3) * 4);
+
+    for chunk in value.chunks(3) {
+        let buffer = chunk.iter().enumerate().fold(0u32, |buffer, (i, byte)| {
+            buffer | (*byte as u32) << (16 - i * 8)
+        });
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(BASE64_ALPHABET[(buffer >> (18 - i * 6)) as usize & 0x3F] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Returns true when the first argument is a string expression that will be visited
+/// next (string arguments like `require "module"` are not visited as expressions).
+fn has_first_string_expression(arguments: &Arguments) -> bool {
+    match arguments {
+        Arguments::Tuple(tuple) => {
+            matches!(tuple.iter_values().next(), Some(Expression::String(_)))
+        }
+        Arguments::String(_) | Arguments::Table(_) => false,
+    }
+}
+
+struct Processor<'a> {
+    encoding: &'a StringEncoding,
+    exclude: &'a [Regex],
+    skip_next_string: bool,
+    define_decoder: bool,
+    identifier_tracker: IdentifierTracker,
+}
+
+impl<'a> Processor<'a> {
+    fn new(encoding: &'a StringEncoding, exclude: &'a [Regex]) -> Self {
+        Self {
+            encoding,
+            exclude,
+            skip_next_string: false,
+            define_decoder: false,
+            identifier_tracker: IdentifierTracker::default(),
+        }
+    }
+
+    fn obfuscate(&mut self, string: &StringExpression) -> Option<Expression> {
+        let value = string.get_value();
+
+        if value.is_empty() {
+            return None;
+        }
+
+        let text = value.to_str_lossy();
+        if self.exclude.iter().any(|pattern| pattern.is_match(&text)) {
+            return None;
+        }
+
+        self.define_decoder = true;
+
+        Some(
+            FunctionCall::from_name(DECODER_IDENTIFIER)
+                .with_argument(self.encoding.encode(value))
+                .into(),
+        )
+    }
+
+    fn is_decoder_call(call: &FunctionCall) -> bool {
+        !call.has_method()
+            && matches!(call.get_prefix(), Prefix::Identifier(identifier) if identifier.get_name() == DECODER_IDENTIFIER)
+    }
+}
+
+impl ops::Deref for Processor<'_> {
+    type Target = IdentifierTracker;
+
+    fn deref(&self) -> &Self::Target {
+        &self.identifier_tracker
+    }
+}
+
+impl ops::DerefMut for Processor<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.identifier_tracker
+    }
+}
+
+impl NodeProcessor for Processor<'_> {
+    fn process_function_call(&mut self, call: &mut FunctionCall) {
+        // the first string argument of require calls is a module path, and the
+        // argument of the decoder calls is already encoded
+        if is_require_call(call, self) || Self::is_decoder_call(call) {
+            self.skip_next_string = has_first_string_expression(call.get_arguments());
+            return;
+        }
+
+        if let Arguments::String(string) = call.get_arguments() {
+            if let Some(expression) = self.obfuscate(string) {
+                call.set_arguments(TupleArguments::default().with_argument(expression).into());
+            }
+        }
+    }
+
+    fn process_expression(&mut self, expression: &mut Expression) {
+        if let Expression::String(string) = expression {
+            if self.skip_next_string {
+                self.skip_next_string = false;
+            } else if let Some(obfuscated) = self.obfuscate(string) {
+                *expression = obfuscated;
+            }
+        }
+    }
+}
+
+pub const STRING_OBFUSCATION_RULE_NAME: &str = "string_obfuscation";
+
+/// A rule that replaces string literals with calls to a decoder function defined
+/// at the top of the file.
+#[derive(Debug, Default)]
+pub struct StringObfuscation {
+    encoding: StringEncoding,
+    exclude: Vec<Regex>,
+}
+
+impl StringObfuscation {
+    /// Encodes strings as base64 strings instead of tables of bytes.
+    pub fn with_base64_encoding(mut self) -> Self {
+        self.encoding = StringEncoding::Base64;
+        self
+    }
+
+    /// Encodes strings as tables of bytes combined with the given key.
+    pub fn with_xor_encoding(mut self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        assert!(!key.is_empty(), "xor key should not be empty");
+        self.encoding = StringEncoding::Xor(key.into_bytes());
+        self
+    }
+
+    /// Keeps the strings matching the given regular expression.
+    pub fn with_exclude(mut self, exclude_pattern: &str) -> Self {
+        match Regex::new(exclude_pattern) {
+            Ok(regex_value) => {
+                self.exclude.push(regex_value);
+            }
+            Err(err) => {
+                log::warn!(
+                    "unable to compile regex pattern '{}': {}",
+                    exclude_pattern,
+                    err
+                );
+            }
+        };
+
+        self
+    }
+}
+
+impl FlawlessRule for StringObfuscation {
+    fn flawless_process(&self, block: &mut Block, _: &Context) {
+        let mut processor = Processor::new(&self.encoding, &self.exclude);
+        ScopeVisitor::visit_block(block, &mut processor);
+
+        if processor.define_decoder {
+            let decoder = Parser::default()
+                .parse(&self.encoding.decoder_code())
+                .expect("decoder code should be valid")
+                .take_statements();
+
+            for (index, statement) in decoder.into_iter().enumerate() {
+                block.insert_statement(index, statement);
+            }
+        }
+    }
+}
+
+impl RuleConfiguration for StringObfuscation {
+    fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+        let mut encoding = None;
+        let mut key = None;
+
+        for (property_key, value) in properties {
+            match property_key.as_str() {
+                "encoding" => {
+                    encoding = Some(value.expect_string(&property_key)?);
+                }
+                "key" => {
+                    key = Some(value.expect_string(&property_key)?);
+                }
+                "exclude" => {
+                    self.exclude = value.expect_regex_list(&property_key)?;
+                }
+                _ => return Err(RuleConfigurationError::UnexpectedProperty(property_key)),
+            }
+        }
+
+        self.encoding = match (encoding.as_deref().unwrap_or("byte_escape"), key) {
+            ("xor", Some(key)) if !key.is_empty() => StringEncoding::Xor(key.into_bytes()),
+            ("xor", Some(_)) => {
+                return Err(RuleConfigurationError::UnexpectedValue {
+                    property: "key".to_owned(),
+                    message: "the xor key should not be empty".to_owned(),
+                })
+            }
+            ("xor", None) => return Err(RuleConfigurationError::MissingProperty("key".to_owned())),
+            (_, Some(_)) => {
+                return Err(RuleConfigurationError::UnexpectedValue {
+                    property: "key".to_owned(),
+                    message: "a key can only be used with the `xor` encoding".to_owned(),
+                })
+            }
+            ("byte_escape", None) => StringEncoding::ByteEscape,
+            ("base64", None) => StringEncoding::Base64,
+            (unexpected, None) => {
+                return Err(RuleConfigurationError::UnexpectedValue {
+                    property: "encoding".to_owned(),
+                    message: format!(
+                        "invalid value `{}` (must be `byte_escape`, `base64` or `xor`)",
+                        unexpected
+                    ),
+                })
+            }
+        };
+
+        Ok(())
+    }
+
+    fn get_name(&self) -> &'static str {
+        STRING_OBFUSCATION_RULE_NAME
+    }
+
+    fn serialize_to_properties(&self) -> RuleProperties {
+        let mut properties = RuleProperties::new();
+
+        if self.encoding != StringEncoding::default() {
+            properties.insert("encoding".to_owned(), self.encoding.name().into());
+        }
+
+        if let StringEncoding::Xor(key) = &self.encoding {
+            properties.insert("key".to_owned(), key.to_str_lossy().as_ref().into());
+        }
+
+        if !self.exclude.is_empty() {
+            properties.insert(
+                "exclude".to_owned(),
+                RulePropertyValue::StringList(
+                    self.exclude
+                        .iter()
+                        .map(|regex| regex.as_str().to_owned())
+                        .collect(),
+                ),
+            );
+        }
+
+        properties
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rules::Rule;
+
+    use insta::assert_json_snapshot;
+
+    fn new_rule() -> StringObfuscation {
+        StringObfuscation::default()
+    }
+
+    #[test]
+    fn serialize_default_rule() {
+        let rule: Box<dyn Rule> = Box::new(new_rule());
+
+        assert_json_snapshot!(rule, @r###"
+        "string_obfuscation"
+        "###);
+    }
+
+    #[test]
+    fn serialize_rule_with_xor_encoding_and_exclude() {
+        let rule: Box<dyn Rule> =
+            Box::new(new_rule().with_xor_encoding("secret").with_exclude("^@"));
+
+        assert_json_snapshot!(rule, @r###"
+        {
+          "rule": "string_obfuscation",
+          "encoding": "xor",
+          "exclude": [
+            "^@"
+          ],
+          "key": "secret"
+        }
+        "###);
+    }
+
+    #[test]
+    fn configure_with_extra_field_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'string_obfuscation',
+            prop: "something",
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected field 'prop' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_with_invalid_encoding_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'string_obfuscation',
+            encoding: 'rot13',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'encoding': invalid value `rot13` (must be `byte_escape`, `base64` or `xor`) at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_xor_without_key_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'string_obfuscation',
+            encoding: 'xor',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"missing required field 'key' at line 1 column 1");
+    }
+
+    #[test]
+    fn configure_key_without_xor_error() {
+        let result = json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'string_obfuscation',
+            encoding: 'base64',
+            key: 'secret',
+        }"#,
+        );
+        insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'key': a key can only be used with the `xor` encoding at line 1 column 1");
+    }
+
+    #[test]
+    fn encode_base64_with_padding() {
+        assert_eq!(encode_base64(b""), "");
+        assert_eq!(encode_base64(b"a"), "YQ==");
+        assert_eq!(encode_base64(b"ab"), "YWI=");
+        assert_eq!(encode_base64(b"abc"), "YWJj");
+        assert_eq!(encode_base64(b"hello world"), "aGVsbG8gd29ybGQ=");
+        assert_eq!(encode_base64(&[0, 255, 128]), "AP+A");
+    }
+}
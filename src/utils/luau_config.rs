@@ -16,49 +16,62 @@ pub(crate) struct LuauConfiguration {
     pub(crate) aliases: HashMap<String, PathBuf>,
 }
 
+fn read_luau_configuration(
+    directory: &Path,
+    config_path: &Path,
+    resources: &Resources,
+) -> Result<LuauConfiguration, DarkluaError> {
+    let config = resources.get(config_path)?;
+    log::trace!(
+        "attempt to parse luau configuration at '{}'",
+        config_path.display()
+    );
+
+    let mut config: LuauConfiguration = serde_json::from_str(&config)?;
+    log::debug!("found luau configuration at '{}'", config_path.display());
+
+    config.aliases = config
+        .aliases
+        .into_iter()
+        .map(|(mut key, value)| {
+            key.insert(0, '@');
+            (key, normalize_path(directory.join(value)))
+        })
+        .inspect(|(key, value)| log::trace!(" ⨽ parsed alias `{}` (`{}`)", key, value.display()))
+        .collect();
+
+    Ok(config)
+}
+
 fn find_luau_configuration_private(
     luau_file: &Path,
     resources: &Resources,
 ) -> Result<Option<LuauConfiguration>, DarkluaError> {
     log::debug!(
-        "find closest {} for '{}'",
+        "find {} files for '{}'",
         LUAU_RC_FILE_NAME,
         luau_file.display()
     );
 
+    let mut configurations = Vec::new();
+
     for ancestor in luau_file.ancestors() {
         let config_path = ancestor.join(LUAU_RC_FILE_NAME);
 
         if resources.exists(&config_path)? {
-            let config = resources.get(&config_path)?;
-            log::trace!(
-                "attempt to parse luau configuration at '{}'",
-                config_path.display()
-            );
-
-            return serde_json::from_str(&config)
-                .map(|mut config: LuauConfiguration| {
-                    log::debug!("found luau configuration at '{}'", config_path.display());
-
-                    config.aliases = config
-                        .aliases
-                        .into_iter()
-                        .map(|(mut key, value)| {
-                            key.insert(0, '@');
-                            (key, normalize_path(ancestor.join(value)))
-                        })
-                        .inspect(|(key, value)| {
-                            log::trace!(" ⨽ parsed alias `{}` (`{}`)", key, value.display())
-                        })
-                        .collect();
-
-                    Some(config)
-                })
-                .map_err(Into::into);
+            configurations.push(read_luau_configuration(ancestor, &config_path, resources)?);
         }
     }
 
-    Ok(None)
+    // configuration files closer to the luau file override the aliases
+    // defined by the configuration files of their parent directories
+    Ok(configurations
+        .into_iter()
+        .rev()
+        .reduce(|mut merged, config| {
+            merged.aliases.extend(config.aliases);
+            merged
+        }))
 }
 
 thread_local! {
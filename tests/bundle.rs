@@ -67,7 +67,8 @@ mod without_rules {
 
     use darklua_core::{
         generator::{LuaGenerator, ReadableLuaGenerator},
-        nodes::{Expression, ReturnStatement},
+        nodes::{Expression, ReturnStatement, StringExpression, TableExpression},
+        DarkluaError,
     };
 
     use crate::ast_fuzzer::{AstFuzzer, FuzzBudget};
@@ -623,6 +624,44 @@ data:
         process_main(&resources, "require_txt_file");
     }
 
+    #[test]
+    fn require_file_with_registered_data_converter() {
+        darklua_core::register_data_converter("lines", |content| {
+            Ok(content
+                .lines()
+                .fold(TableExpression::default(), |table, line| {
+                    table.append_array_value(StringExpression::from_value(line))
+                })
+                .into())
+        });
+
+        let resources = memory_resources!(
+            "src/value.lines" => "first\nsecond\n",
+            "src/main.lua" => "local value = require('./value.lines')",
+            ".darklua.json" => DARKLUA_BUNDLE_ONLY_READABLE_CONFIG,
+        );
+
+        process_main(&resources, "require_file_with_registered_data_converter");
+    }
+
+    #[test]
+    fn require_file_with_registered_data_converter_error() {
+        darklua_core::register_data_converter("invalid_data", |_| {
+            Err(DarkluaError::custom("invalid data"))
+        });
+
+        let resources = memory_resources!(
+            "src/value.invalid_data" => "",
+            "src/main.lua" => "local value = require('./value.invalid_data')",
+            ".darklua.json" => DARKLUA_BUNDLE_ONLY_READABLE_CONFIG,
+        );
+
+        process_main_with_errors(
+            &resources,
+            "require_file_with_registered_data_converter_error",
+        );
+    }
+
     #[test]
     fn require_value_and_override_require_function() {
         let resources = memory_resources!(
@@ -841,5 +880,363 @@ data:
 
             process_main_with_error(&resources, "two_different_direct_cycles");
         }
+
+        #[test]
+        fn transitive_cycle_with_call_locations() {
+            let resources = memory_resources!(
+                "src/value1.lua" => "local constant = require('./constant')\nreturn constant",
+                "src/value2.lua" => "-- value2\n\nreturn {\n  value = require('./value1'),\n}",
+                "src/constant.lua" => "return require('./value2.lua')",
+                "src/main.lua" => "local value = require('./value1.lua')",
+                ".darklua.json" => DARKLUA_BUNDLE_ONLY_RETAIN_LINES_CONFIG,
+            );
+
+            process_main_with_error(&resources, "transitive_cycle_with_call_locations");
+        }
+
+        #[test]
+        fn transitive_cycle_with_call_locations_with_dense_generator() {
+            let resources = memory_resources!(
+                "src/value1.lua" => "local constant = require('./constant')\nreturn constant",
+                "src/value2.lua" => "-- value2\n\nreturn {\n  value = require('./value1'),\n}",
+                "src/constant.lua" => "return require('./value2.lua')",
+                "src/main.lua" => "local function load(require)\n  return require('./value1.lua')\nend\nlocal value = require('./value1.lua')",
+                ".darklua.json" => "{ rules: [], generator: 'dense', bundle: { require_mode: 'path' } }",
+            );
+
+            process_main_with_error(
+                &resources,
+                "transitive_cycle_with_call_locations_with_dense_generator",
+            );
+        }
+
+        #[test]
+        fn cycle_through_main_file_with_call_locations_with_dense_generator() {
+            let resources = memory_resources!(
+                "src/value.lua" => "local main = require('./main')\nreturn main",
+                "src/main.lua" => "local function load(require)\n  return require('./value')\nend\nlocal value = require('./value')\nreturn value",
+                ".darklua.json" => "{ rules: [], generator: 'dense', bundle: { require_mode: 'path' } }",
+            );
+
+            process_main_with_error(
+                &resources,
+                "cycle_through_main_file_with_call_locations_with_dense_generator",
+            );
+        }
+    }
+
+    mod tree_shaking {
+        use super::*;
+
+        const TREE_SHAKING_CONFIG: &str = "{ rules: [], generator: 'readable', bundle: { require_mode: 'path', tree_shaking: true } }";
+
+        fn process_main_with_tree_shaking(resources: &Resources, snapshot_name: &'static str) {
+            resources
+                .write(".darklua.json", TREE_SHAKING_CONFIG)
+                .unwrap();
+            process_main(resources, snapshot_name);
+        }
+
+        #[test]
+        fn remove_unused_module_function() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nfunction Lib.used() return 1 end\nfunction Lib.unused() return 2 end\nLib.constant = 10\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nprint(lib.used())",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_remove_unused_module_function",
+            );
+        }
+
+        #[test]
+        fn remove_unused_fields_from_returned_table() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "return { used = function() end, unused = 1, effect = print('side effect') }",
+                "src/main.lua" => "require('./lib').used()",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_remove_unused_fields_from_returned_table",
+            );
+        }
+
+        #[test]
+        fn remove_unused_fields_from_module_table_constructor() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = { used = true, unused = false }\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nreturn lib.used",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_remove_unused_fields_from_module_table_constructor",
+            );
+        }
+
+        #[test]
+        fn keep_fields_used_internally() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nfunction Lib.helper() return 1 end\nfunction Lib.other() return 2 end\nfunction Lib.used() return Lib.helper() end\nfunction Lib.unused() return Lib.other() end\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nprint(lib.used())",
+            );
+
+            process_main_with_tree_shaking(&resources, "tree_shaking_keep_fields_used_internally");
+        }
+
+        #[test]
+        fn keep_module_when_value_escapes() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nfunction Lib.a() end\nfunction Lib.b() end\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nfor key in pairs(lib) do print(key) end",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_keep_module_when_value_escapes",
+            );
+        }
+
+        #[test]
+        fn keep_module_when_calling_method() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nfunction Lib:a() return self.b end\nLib.b = 1\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nprint(lib:a())",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_keep_module_when_calling_method",
+            );
+        }
+
+        #[test]
+        fn keep_module_when_module_table_escapes_internally() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nLib.__index = Lib\nfunction Lib.new() return setmetatable({}, Lib) end\nfunction Lib.unused() end\nreturn Lib",
+                "src/main.lua" => "local lib = require('./lib')\nprint(lib.new())",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_keep_module_when_module_table_escapes_internally",
+            );
+        }
+
+        #[test]
+        fn remove_all_fields_of_module_required_for_side_effects() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "print('loaded')\nreturn { value = 1 }",
+                "src/main.lua" => "require('./lib')",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_remove_all_fields_of_module_required_for_side_effects",
+            );
+        }
+
+        #[test]
+        fn aggregate_fields_used_by_nested_modules() {
+            let resources = memory_resources!(
+                "src/lib.lua" => "local Lib = {}\nfunction Lib.a() end\nfunction Lib.b() end\nfunction Lib.c() end\nreturn Lib",
+                "src/other.lua" => "local lib = require('./lib')\nreturn { run = function() lib.b() end }",
+                "src/main.lua" => "local lib = require('./lib')\nlocal other = require('./other')\nlib.a()\nother.run()",
+            );
+
+            process_main_with_tree_shaking(
+                &resources,
+                "tree_shaking_aggregate_fields_used_by_nested_modules",
+            );
+        }
+    }
+
+    mod require_polyfill {
+        use super::*;
+
+        const REQUIRE_POLYFILL_CONFIG: &str = "{ rules: [], generator: 'readable', bundle: { require_mode: 'path', require_polyfill: true } }";
+
+        fn process_main_with_require_polyfill(resources: &Resources, snapshot_name: &'static str) {
+            resources
+                .write(".darklua.json", REQUIRE_POLYFILL_CONFIG)
+                .unwrap();
+            process_main(resources, snapshot_name);
+        }
+
+        #[test]
+        fn require_module() {
+            let resources = memory_resources!(
+                "src/value.lua" => "return true",
+                "src/main.lua" => "local value = require('./value')",
+            );
+
+            process_main_with_require_polyfill(&resources, "require_polyfill_require_module");
+        }
+
+        #[test]
+        fn direct_cycle() {
+            let resources = memory_resources!(
+                "src/a.lua" => "local b = require('./b')\nreturn { name = 'a', getOther = function() return b.name end }",
+                "src/b.lua" => "return { name = 'b', getOther = function() return require('./a').name end }",
+                "src/main.lua" => "local a = require('./a')\nprint(a.getOther())",
+            );
+
+            process_main_with_require_polyfill(&resources, "require_polyfill_direct_cycle");
+        }
+
+        #[test]
+        fn transitive_cycle() {
+            let resources = memory_resources!(
+                "src/a.lua" => "local b = require('./b')\nreturn { run = function() return b end }",
+                "src/b.lua" => "local c = require('./c')\nreturn { run = function() return c end }",
+                "src/c.lua" => "return { run = function() return require('./a') end }",
+                "src/main.lua" => "local a = require('./a')\nlocal c = require('./c')",
+            );
+
+            process_main_with_require_polyfill(&resources, "require_polyfill_transitive_cycle");
+        }
+    }
+
+    mod chunks {
+        use super::*;
+
+        fn process_main_with_chunks(
+            resources: &Resources,
+            bundle_config: &str,
+            chunks: &[&str],
+            snapshot_name: &'static str,
+        ) {
+            resources
+                .write(
+                    ".darklua.json",
+                    format!(
+                        "{{ rules: [], generator: 'readable', bundle: {{ require_mode: 'path', {} }} }}",
+                        bundle_config
+                    )
+                    .as_str(),
+                )
+                .unwrap();
+            process(
+                resources,
+                Options::new("src/main.lua").with_output("out/main.lua"),
+            )
+            .unwrap()
+            .result()
+            .unwrap();
+
+            let mut output = resources.get("out/main.lua").unwrap();
+
+            for chunk in chunks {
+                output.push_str(&format!("\n-- {}\n", chunk));
+                output.push_str(&resources.get(chunk).unwrap());
+            }
+
+            insta::assert_snapshot!(format!("bundle_chunks_{}", snapshot_name), output);
+        }
+
+        #[test]
+        fn lazy_module() {
+            let resources = memory_resources!(
+                "src/value.lua" => "return true",
+                "src/level.lua" => "return { name = 'forest' }",
+                "src/main.lua" => "local value = require('./value')\nlocal level = require('./level')",
+            );
+
+            process_main_with_chunks(
+                &resources,
+                "lazy_modules: ['./level']",
+                &["out/main.chunk-1.lua"],
+                "lazy_module",
+            );
+        }
+
+        #[test]
+        fn lazy_module_requiring_main_module() {
+            let resources = memory_resources!(
+                "src/value.lua" => "return true",
+                "src/level.lua" => "local value = require('./value')\nreturn { value = value }",
+                "src/main.lua" => "local value = require('./value')\nlocal level = require('./level')",
+            );
+
+            process_main_with_chunks(
+                &resources,
+                "lazy_modules: ['./level']",
+                &["out/main.chunk-1.lua"],
+                "lazy_module_requiring_main_module",
+            );
+        }
+
+        #[test]
+        fn lazy_module_with_require_polyfill() {
+            let resources = memory_resources!(
+                "src/level.lua" => "return { name = 'forest' }",
+                "src/main.lua" => "local level = require('./level')",
+            );
+
+            process_main_with_chunks(
+                &resources,
+                "lazy_modules: ['./level'], require_polyfill: true",
+                &["out/main.chunk-1.lua"],
+                "lazy_module_with_require_polyfill",
+            );
+        }
+
+        #[test]
+        fn modules_over_chunk_size() {
+            let resources = memory_resources!(
+                "src/a.lua" => "return 'aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa'",
+                "src/b.lua" => "return 'bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb'",
+                "src/c.lua" => "return 'ccccccccccccccccccccccccccccccccccccccccccccc'",
+                "src/main.lua" => "local a = require('./a')\nlocal b = require('./b')\nlocal c = require('./c')",
+            );
+
+            process_main_with_chunks(
+                &resources,
+                "chunk_size: 120",
+                &["out/main.chunk-1.lua", "out/main.chunk-2.lua"],
+                "modules_over_chunk_size",
+            );
+        }
+
+        #[test]
+        fn modules_within_chunk_size() {
+            let resources = memory_resources!(
+                "src/value.lua" => "return true",
+                "src/main.lua" => "local value = require('./value')",
+            );
+
+            process_main_with_chunks(
+                &resources,
+                "chunk_size: 10000",
+                &[],
+                "modules_within_chunk_size",
+            );
+
+            assert!(!resources.exists("out/main.chunk-1.lua").unwrap());
+        }
+
+        #[test]
+        fn chunks_are_processed_by_rules() {
+            let resources = memory_resources!(
+                "src/level.lua" => "local name: string = 'forest'\nreturn { name = name }",
+                "src/main.lua" => "local level = require('./level')",
+                ".darklua.json" => "{ rules: ['remove_types'], generator: 'readable', bundle: { require_mode: 'path', lazy_modules: ['./level'] } }",
+            );
+
+            process(
+                &resources,
+                Options::new("src/main.lua").with_output("out/main.lua"),
+            )
+            .unwrap()
+            .result()
+            .unwrap();
+
+            insta::assert_snapshot!(
+                "bundle_chunks_processed_by_rules",
+                resources.get("out/main.chunk-1.lua").unwrap()
+            );
+        }
     }
 }
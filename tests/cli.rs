@@ -40,6 +40,11 @@ impl Context {
         self
     }
 
+    pub fn stdin(mut self, content: &str) -> Self {
+        self.command.write_stdin(content);
+        self
+    }
+
     pub fn with_cwd(mut self, cwd: impl AsRef<Path>) -> Self {
         let full_dir = self.working_directory.path().join(cwd.as_ref());
         self.command.current_dir(full_dir);
@@ -152,6 +157,21 @@ impl Context {
         self
     }
 
+    pub fn expect_exit_code(mut self, code: i32) -> Self {
+        self.command.assert().code(code);
+        self
+    }
+
+    pub fn expect_no_file<P: AsRef<Path>>(&self, file_path: P) -> &Self {
+        let file_path = self.path_from_working_directory(file_path.as_ref());
+        assert!(
+            !file_path.exists(),
+            "file `{}` should not exist",
+            file_path.display()
+        );
+        self
+    }
+
     pub fn replace_snapshot_content(
         mut self,
         matcher: impl Into<String>,
@@ -210,6 +230,22 @@ fn snapshot_convert_help_command() {
         .snapshot_command("convert_help_command");
 }
 
+#[test]
+fn snapshot_parse_help_command() {
+    Context::default()
+        .arg("parse")
+        .arg("--help")
+        .snapshot_command("parse_help_command");
+}
+
+#[test]
+fn snapshot_serve_help_command() {
+    Context::default()
+        .arg("serve")
+        .arg("--help")
+        .snapshot_command("serve_help_command");
+}
+
 #[test]
 fn run_minify_command() {
     Context::default()
@@ -381,6 +417,321 @@ fn run_process_single_file_custom_config_command_deprecated_config_path() {
         .snapshot_file("run_process_custom_config_command_out", "out.lua");
 }
 
+#[test]
+fn run_process_check_command_with_missing_output() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("--check")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .replace_backslashes()
+        .expect_exit_code(1)
+        .snapshot_command("run_process_check_command_with_missing_output")
+        .expect_no_file("out/init.lua");
+}
+
+#[test]
+fn run_process_check_command_with_up_to_date_output() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .write_file("out/init.lua", "return 2")
+        .arg("process")
+        .arg("--check")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .expect_success()
+        .snapshot_command("run_process_check_command_with_up_to_date_output");
+}
+
+#[test]
+fn run_process_check_diff_command_with_outdated_output() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .write_file("out/init.lua", "return 1")
+        .arg("process")
+        .arg("--check")
+        .arg("--diff")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .replace_backslashes()
+        .expect_exit_code(1)
+        .snapshot_command("run_process_check_diff_command_with_outdated_output")
+        .snapshot_file(
+            "run_process_check_diff_command_with_outdated_output_init_out",
+            "out/init.lua",
+        );
+}
+
+#[test]
+fn run_process_diff_command_without_check_errors() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("--diff")
+        .arg("src")
+        .arg("out")
+        .expect_exit_code(2)
+        .snapshot_command("run_process_diff_command_without_check_errors");
+}
+
+#[test]
+fn run_process_verify_command_with_missing_snapshot() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("--verify")
+        .arg("snapshots")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .replace_backslashes()
+        .expect_exit_code(1)
+        .snapshot_command("run_process_verify_command_with_missing_snapshot")
+        .expect_no_file("out/init.lua")
+        .expect_no_file("snapshots/init.lua");
+}
+
+#[test]
+fn run_process_verify_command_with_matching_snapshot() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .write_file("snapshots/init.lua", "return 2")
+        .arg("process")
+        .arg("--verify")
+        .arg("snapshots")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .expect_success()
+        .snapshot_command("run_process_verify_command_with_matching_snapshot")
+        .expect_no_file("out/init.lua");
+}
+
+#[test]
+fn run_process_verify_command_with_outdated_snapshots() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .write_file("src/value.lua", "return 'value'\n")
+        .write_file("snapshots/init.lua", "return 1")
+        .write_file("snapshots/value.lua", "return 'value'")
+        .write_file("snapshots/removed.lua", "return nil")
+        .arg("process")
+        .arg("--verify")
+        .arg("snapshots")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .replace_backslashes()
+        .expect_exit_code(1)
+        .snapshot_command("run_process_verify_command_with_outdated_snapshots");
+}
+
+#[test]
+fn run_process_verify_command_with_single_file() {
+    Context::default()
+        .write_file("init.lua", "return 1 + 1\n")
+        .write_file("snapshots/out.lua", "return 2")
+        .arg("process")
+        .arg("--verify")
+        .arg("snapshots")
+        .arg("init.lua")
+        .arg("out.lua")
+        .replace_duration_labels()
+        .expect_success()
+        .snapshot_command("run_process_verify_command_with_single_file");
+}
+
+#[test]
+fn run_process_update_snapshots_command() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .write_file("snapshots/init.lua", "return 1")
+        .write_file("snapshots/removed.lua", "return nil")
+        .arg("process")
+        .arg("--verify")
+        .arg("snapshots")
+        .arg("--update-snapshots")
+        .arg("src")
+        .arg("out")
+        .replace_duration_labels()
+        .snapshot_command("run_process_update_snapshots_command")
+        .snapshot_file(
+            "run_process_update_snapshots_command_init_snapshot",
+            "snapshots/init.lua",
+        )
+        .expect_no_file("snapshots/removed.lua")
+        .expect_no_file("out/init.lua");
+}
+
+#[test]
+fn run_process_update_snapshots_command_without_verify_errors() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("--update-snapshots")
+        .arg("src")
+        .arg("out")
+        .expect_exit_code(2)
+        .snapshot_command("run_process_update_snapshots_command_without_verify_errors");
+}
+
+#[test]
+fn run_process_command_from_stdin_to_stdout() {
+    Context::default()
+        .stdin("local value = 1 + 1\nreturn value\n")
+        .arg("process")
+        .arg("-")
+        .arg("-")
+        .expect_success()
+        .snapshot_command("run_process_command_from_stdin_to_stdout");
+}
+
+#[test]
+fn run_process_command_from_stdin_to_file() {
+    Context::default()
+        .stdin("return 1 + 1\n")
+        .arg("process")
+        .arg("-")
+        .arg("out/init.lua")
+        .replace_duration_labels()
+        .expect_success()
+        .snapshot_file("run_process_command_init_out", "out/init.lua");
+}
+
+#[test]
+fn run_process_command_from_file_to_stdout() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("src/init.lua")
+        .arg("-")
+        .expect_success()
+        .snapshot_command("run_process_command_from_file_to_stdout")
+        .expect_no_file("stdout.lua");
+}
+
+#[test]
+fn run_process_command_from_stdin_with_custom_config() {
+    Context::default()
+        .stdin("return _G.CONSTANT\n")
+        .write_file(
+            "custom.json5",
+            "{ rules: [{ rule: 'inject_global_value', identifier: 'CONSTANT', value: true }] }",
+        )
+        .arg("process")
+        .arg("--config")
+        .arg("custom.json5")
+        .arg("-")
+        .arg("-")
+        .expect_success()
+        .snapshot_command("run_process_command_from_stdin_with_custom_config");
+}
+
+#[test]
+fn run_process_command_from_stdin_with_parsing_error() {
+    Context::default()
+        .stdin("return (\n")
+        .arg("process")
+        .arg("-")
+        .arg("-")
+        .expect_exit_code(1)
+        .snapshot_command("run_process_command_from_stdin_with_parsing_error");
+}
+
+#[test]
+fn run_process_command_continue_on_error() {
+    Context::default()
+        .write_file("src/invalid.lua", "local a = (\nlocal b = )\n")
+        .write_file("src/valid.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("src")
+        .arg("out")
+        .arg("--continue-on-error")
+        .expect_exit_code(1)
+        .replace_duration_labels()
+        .replace_backslashes()
+        .snapshot_command("run_process_command_continue_on_error")
+        .snapshot_file(
+            "run_process_command_continue_on_error_valid_out",
+            "out/valid.lua",
+        )
+        .expect_no_file("out/invalid.lua");
+}
+
+#[test]
+fn run_process_command_from_directory_to_stdout_errors() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("src")
+        .arg("-")
+        .expect_exit_code(1)
+        .snapshot_command("run_process_command_from_directory_to_stdout_errors");
+}
+
+#[test]
+fn run_process_command_with_json_output_format() {
+    Context::default()
+        .write_file("src/init.lua", "return 1 + 1\n")
+        .arg("process")
+        .arg("src")
+        .arg("out")
+        .arg("--output-format")
+        .arg("json")
+        .snapshot_command("run_process_command_with_json_output_format");
+}
+
+#[test]
+fn run_process_command_with_json_output_format_and_syntax_error() {
+    Context::default()
+        .write_file("src/init.lua", "local a = (\n")
+        .arg("process")
+        .arg("src")
+        .arg("out")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .replace_backslashes()
+        .snapshot_command("run_process_command_with_json_output_format_and_syntax_error");
+}
+
+#[test]
+fn run_process_command_with_json_output_format_and_require_error() {
+    Context::default()
+        .write_file(
+            ".darklua.json",
+            "{ \"bundle\": { \"require_mode\": \"path\" } }",
+        )
+        .write_file("src/init.lua", "return require('./missing')\n")
+        .arg("process")
+        .arg("src/init.lua")
+        .arg("out.lua")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .replace_backslashes()
+        .snapshot_command("run_process_command_with_json_output_format_and_require_error");
+}
+
+#[test]
+fn run_process_command_from_stdin_to_stdout_with_json_output_format_and_syntax_error() {
+    Context::default()
+        .stdin("return +\n")
+        .arg("process")
+        .arg("-")
+        .arg("-")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .snapshot_command(
+            "run_process_command_from_stdin_to_stdout_with_json_output_format_and_syntax_error",
+        );
+}
+
 #[test]
 fn run_convert_command_on_json_file_with_output() {
     Context::default()
@@ -407,6 +758,30 @@ fn run_convert_command_on_json_without_extension_file_with_output() {
         .snapshot_file("run_convert_command_on_json_file_out", "out.lua");
 }
 
+#[test]
+fn run_convert_command_with_json_output_format_and_invalid_data() {
+    Context::default()
+        .write_file("data.json", "{ \"property\": }")
+        .arg("convert")
+        .arg("data.json")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .snapshot_command("run_convert_command_with_json_output_format_and_invalid_data");
+}
+
+#[test]
+fn run_convert_command_with_json_output_format_and_unknown_extension() {
+    Context::default()
+        .write_file("data.txt", "{ \"property\": true }")
+        .arg("convert")
+        .arg("data.txt")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .snapshot_command("run_convert_command_with_json_output_format_and_unknown_extension");
+}
+
 #[test]
 fn run_convert_command_on_json_file() {
     Context::default()
@@ -438,3 +813,140 @@ fn run_convert_command_errors_when_unrecognized_extension() {
         .replace_duration_labels()
         .snapshot_command("run_convert_command_errors_when_unrecognized_extension");
 }
+
+#[test]
+fn run_parse_command() {
+    Context::default()
+        .write_file("src/init.lua", "local value = 'hello'\nreturn value .. 1\n")
+        .arg("parse")
+        .arg("src/init.lua")
+        .expect_success()
+        .snapshot_command("run_parse_command");
+}
+
+#[test]
+fn run_parse_command_with_output() {
+    Context::default()
+        .write_file("src/init.lua", "return true\n")
+        .arg("parse")
+        .arg("--format")
+        .arg("json")
+        .arg("src/init.lua")
+        .arg("ast.json")
+        .expect_success()
+        .snapshot_file("run_parse_command_with_output", "ast.json");
+}
+
+#[test]
+fn run_parse_command_with_preserved_tokens() {
+    Context::default()
+        .write_file("src/init.lua", "-- comment\nreturn nil\n")
+        .arg("parse")
+        .arg("--preserve-tokens")
+        .arg("src/init.lua")
+        .expect_success()
+        .snapshot_command("run_parse_command_with_preserved_tokens");
+}
+
+#[test]
+fn run_parse_command_errors_when_code_is_invalid() {
+    Context::default()
+        .write_file("src/init.lua", "return (\n")
+        .arg("parse")
+        .arg("src/init.lua")
+        .expect_exit_code(1)
+        .replace_backslashes()
+        .snapshot_command("run_parse_command_errors_when_code_is_invalid");
+}
+
+#[test]
+fn run_parse_command_with_json_output_format_errors_when_code_is_invalid() {
+    Context::default()
+        .write_file("src/init.lua", "return +\n")
+        .arg("parse")
+        .arg("src/init.lua")
+        .arg("--output-format")
+        .arg("json")
+        .expect_exit_code(1)
+        .replace_backslashes()
+        .snapshot_command("run_parse_command_with_json_output_format_errors_when_code_is_invalid");
+}
+
+#[test]
+fn run_parse_command_errors_when_format_is_unknown() {
+    Context::default()
+        .write_file("src/init.lua", "return true\n")
+        .arg("parse")
+        .arg("--format")
+        .arg("xml")
+        .arg("src/init.lua")
+        .expect_exit_code(2)
+        .snapshot_command("run_parse_command_errors_when_format_is_unknown");
+}
+
+fn serve_messages(messages: &[&str]) -> String {
+    messages
+        .iter()
+        .map(|message| format!("Content-Length: {}\r\n\r\n{}", message.len(), message))
+        .collect()
+}
+
+#[test]
+fn run_serve_command_process_request() {
+    Context::default()
+        .write_file("src/value.lua", "return 'value'\n")
+        .arg("serve")
+        .stdin(&serve_messages(&[
+            r#"{"jsonrpc":"2.0","id":1,"method":"process","params":{"path":"src/init.lua","code":"-- comment\nreturn 1 + 2","config":{"rules":["remove_comments","compute_expression"],"generator":"dense"}}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"process","params":{"path":"src/init.lua","code":"return require('./value')","config":{"bundle":{"require_mode":"path"}}}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#,
+        ]))
+        .expect_success()
+        .replace_snapshot_content("\r", "")
+        .snapshot_command("run_serve_command_process_request");
+}
+
+#[test]
+fn run_serve_command_process_request_with_syntax_errors() {
+    Context::default()
+        .arg("serve")
+        .stdin(&serve_messages(&[
+            r#"{"jsonrpc":"2.0","id":1,"method":"process","params":{"path":"src/init.lua","code":"local a = (\n"}}"#,
+        ]))
+        .expect_success()
+        .replace_snapshot_content("\r", "")
+        .replace_backslashes()
+        .snapshot_command("run_serve_command_process_request_with_syntax_errors");
+}
+
+#[test]
+fn run_serve_command_with_configuration_file() {
+    Context::default()
+        .write_file("serve.json", "{ rules: ['remove_comments'], generator: 'retain_lines' }")
+        .arg("serve")
+        .arg("--config")
+        .arg("serve.json")
+        .stdin(&serve_messages(&[
+            r#"{"jsonrpc":"2.0","id":1,"method":"process","params":{"path":"init.lua","code":"return 1 -- comment"}}"#,
+            r#"{"jsonrpc":"2.0","method":"exit"}"#,
+        ]))
+        .expect_success()
+        .replace_snapshot_content("\r", "")
+        .snapshot_command("run_serve_command_with_configuration_file");
+}
+
+#[test]
+fn run_serve_command_errors() {
+    Context::default()
+        .arg("serve")
+        .stdin(&serve_messages(&[
+            "{ not json",
+            r#"{"jsonrpc":"2.0","id":1,"method":"format"}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"process","params":{"code":"return"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"process","params":{"path":"init.lua","code":"return","config":{"rules":["unknown_rule"]}}}"#,
+            r#"{"jsonrpc":"2.0","method":"format"}"#,
+        ]))
+        .expect_success()
+        .replace_snapshot_content("\r", "")
+        .snapshot_command("run_serve_command_errors");
+}
@@ -259,6 +259,35 @@ mod errors {
         );
     }
 
+    #[test]
+    fn snapshot_missing_extended_configuration_file() {
+        let resources = memory_resources!(
+            "src/init.lua" => "return ''",
+            ".darklua.json" => "{ extends: './missing.json' }",
+        );
+
+        assert_errors(
+            "missing_extended_configuration_file",
+            &resources,
+            Options::new("src"),
+        );
+    }
+
+    #[test]
+    fn snapshot_configuration_extending_itself() {
+        let resources = memory_resources!(
+            "src/init.lua" => "return ''",
+            ".darklua.json" => "{ extends: './base.json' }",
+            "base.json" => "{ extends: './.darklua.json' }",
+        );
+
+        assert_errors(
+            "configuration_extending_itself",
+            &resources,
+            Options::new("src"),
+        );
+    }
+
     #[test]
     fn snapshot_multiple_configuration_file_found() {
         let resources = memory_resources!(
@@ -274,3 +303,1235 @@ mod errors {
         );
     }
 }
+
+#[test]
+fn generate_source_map_next_to_output() {
+    let resources = memory_resources!(
+        "src/test.lua" => "local a = 1\n\nlocal b = 2\nreturn a + b",
+        ".darklua.json" => "{ rules: [], generator: 'dense' }",
+    );
+
+    process(
+        &resources,
+        Options::new("src").with_output("output").with_source_map(),
+    )
+    .unwrap()
+    .result()
+    .unwrap();
+
+    assert_eq!(
+        resources.get("output/test.lua").unwrap(),
+        "local a=1 local b=2 return a+b"
+    );
+    insta::assert_snapshot!(
+        resources.get("output/test.lua.map").unwrap(),
+        @r###"
+    {"version":3,"file":"test.lua","sources":["../src/test.lua"],"sourcesContent":["local a = 1\n\nlocal b = 2\nreturn a + b"],"names":[],"mappings":"AAAA,UAEA,UACA"}
+    "###
+    );
+}
+
+#[test]
+fn generate_source_map_of_bundle_with_each_module_file() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = require('./value')\nlocal other = require('./other')\nprint('é', value, other)",
+        "src/value.lua" => "local result = 1\n\nreturn result",
+        "src/other.lua" => "\nreturn '😀'",
+        ".darklua.json" => "{ rules: [], generator: 'retain_lines', bundle: { require_mode: 'path' } }",
+    );
+
+    process(
+        &resources,
+        Options::new("src/main.lua")
+            .with_output("out/main.lua")
+            .with_source_map(),
+    )
+    .unwrap()
+    .result()
+    .unwrap();
+
+    insta::assert_snapshot!(resources.get("out/main.lua").unwrap(), @r###"
+    local __DARKLUA_BUNDLE_MODULES={cache={}::any}do do local function __modImpl()local result = 1
+
+    return result end function __DARKLUA_BUNDLE_MODULES.a():typeof(__modImpl())local v=__DARKLUA_BUNDLE_MODULES.cache.a if not v then v={c=__modImpl()}__DARKLUA_BUNDLE_MODULES.cache.a=v end return v.c end end do local function __modImpl()
+
+    return '😀'end function __DARKLUA_BUNDLE_MODULES.b():typeof(__modImpl())local v=__DARKLUA_BUNDLE_MODULES.cache.b if not v then v={c=__modImpl()}__DARKLUA_BUNDLE_MODULES.cache.b=v end return v.c end end end
+    local value = __DARKLUA_BUNDLE_MODULES.a()
+    local other = __DARKLUA_BUNDLE_MODULES.b()
+    print('é', value, other)
+    "###);
+    insta::assert_snapshot!(resources.get("out/main.lua.map").unwrap(), @r###"
+    {"version":3,"file":"main.lua","sources":["../src/main.lua","../src/value.lua","../src/other.lua"],"sourcesContent":["local value = require('./value')\nlocal other = require('./other')\nprint('é', value, other)","local result = 1\n\nreturn result","\nreturn '😀'"],"names":[],"mappings":"8ECAA;;AAEA;;ACDA;AFDA;AACA;AACA"}
+    "###);
+}
+
+#[test]
+fn do_not_generate_source_map_by_default() {
+    let resources = memory_resources!(
+        "src/test.lua" => ANY_CODE,
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    assert!(!resources.exists("output/test.lua.map").unwrap());
+}
+
+#[test]
+fn report_metrics_of_each_rule() {
+    let resources = memory_resources!(
+        "src/a.lua" => "-- comment\nlocal a = 1 do end return a",
+        "src/b.lua" => "return true",
+        ".darklua.json" => "{ rules: ['remove_comments', 'remove_empty_do'], generator: 'dense' }",
+    );
+
+    let worker_tree = process(&resources, Options::new("src").with_report()).unwrap();
+
+    let report = worker_tree.report();
+    let files: Vec<_> = report.iter_files().collect();
+
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].source(), std::path::Path::new("src/a.lua"));
+    assert_eq!(files[1].source(), std::path::Path::new("src/b.lua"));
+
+    let rules: Vec<_> = files[0]
+        .iter_rules()
+        .map(|rule| {
+            (
+                rule.rule_name(),
+                rule.node_count_difference(),
+                rule.bytes_saved(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        rules,
+        vec![("remove_comments", 0, 0), ("remove_empty_do", 1, 7)]
+    );
+
+    let totals: Vec<_> = report
+        .rule_totals()
+        .into_iter()
+        .map(|rule| rule.rule_name().to_owned())
+        .collect();
+
+    assert_eq!(totals, vec!["remove_comments", "remove_empty_do"]);
+}
+
+#[test]
+fn do_not_report_metrics_by_default() {
+    let resources = memory_resources!(
+        "src/test.lua" => ANY_CODE,
+    );
+
+    let worker_tree = process(&resources, Options::new("src")).unwrap();
+
+    assert!(worker_tree.report().is_empty());
+}
+
+#[test]
+fn disable_rule_with_directives_in_region() {
+    let resources = memory_resources!(
+        "src/test.lua" => "print(1 + 1)\n-- darklua-disable compute_expression\nprint(2 + 2)\n-- darklua-enable\nprint(3 + 3)\nreturn 4 + 4",
+        ".darklua.json" => "{ rules: ['compute_expression'], generator: 'retain_lines' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    print(2)
+    -- darklua-disable compute_expression
+    print(2 + 2)
+    -- darklua-enable
+    print(6)
+    return 8
+    "###);
+}
+
+#[test]
+fn disable_rule_with_directive_for_whole_file() {
+    let resources = memory_resources!(
+        "src/test.lua" => "-- darklua-disable remove_types\nlocal a: number = 1\nreturn a :: number",
+        ".darklua.json" => "{ rules: ['remove_types'], generator: 'retain_lines' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    -- darklua-disable remove_types
+    local a: number = 1
+    return a :: number
+    "###);
+}
+
+#[test]
+fn disable_all_rules_with_directives_and_dense_generator() {
+    let resources = memory_resources!(
+        "src/test.lua" => "local a = 1 + 1\n-- darklua-disable\nlocal b = 2 + 2\n-- darklua-enable\nreturn a + b",
+        ".darklua.json" => "{ rules: ['compute_expression', 'remove_comments'], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @"local a=2 local b=2+2 return a+b");
+}
+
+fn process_code_with_directives(code: &str, rule: &str) -> String {
+    let resources = memory_resources!(
+        "src/test.lua" => code,
+        ".darklua.json" => &format!("{{ rules: ['{}'], generator: 'dense' }}", rule),
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    resources.get("output/test.lua").unwrap()
+}
+
+#[test]
+fn disabled_statement_keeps_references_renamed_by_rule() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "local value = 1\n-- darklua-disable\nprint(value)\n-- darklua-enable\nreturn value",
+            "rename_variables"
+        ),
+        @"local a=1 print(a)return a"
+    );
+}
+
+#[test]
+fn disabled_local_is_renamed_with_its_references() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "-- darklua-disable\nlocal value = compute()\n-- darklua-enable\nprint(value)",
+            "rename_variables"
+        ),
+        @"local a=compute()print(a)"
+    );
+}
+
+#[test]
+fn disabled_statement_keeps_used_variables() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "local value = 1 + 1\nlocal unused = 2\n-- darklua-disable\nprint(value)",
+            "remove_unused_variable"
+        ),
+        @"local value=1+1 print(value)"
+    );
+}
+
+#[test]
+fn disabled_statement_is_not_merged_or_duplicated() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "local a = 1\n-- darklua-disable\nlocal b = { 2 }\n-- darklua-enable\nlocal c = 3\nreturn a, b, c",
+            "group_local_assignment"
+        ),
+        @"local a=1 local b={2}local c=3 return a,b,c"
+    );
+}
+
+#[test]
+fn disabled_statement_in_nested_block() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "local function compute()\n    -- darklua-disable compute_expression\n    print(1 + 1)\n    -- darklua-enable\n    return 2 + 2\nend\nreturn compute",
+            "compute_expression"
+        ),
+        @"local function compute()print(1+1)return 4 end return compute"
+    );
+}
+
+#[test]
+fn rule_is_not_applied_when_it_changes_disabled_statements() {
+    insta::assert_snapshot!(
+        process_code_with_directives(
+            "local value = 1\nprint(value)\n-- darklua-disable\nprint(value)\n-- darklua-enable\nreturn value",
+            "inline_constant_locals"
+        ),
+        @"local value=1 print(value)print(value)return value"
+    );
+}
+
+#[test]
+fn apply_preset_from_configuration_file() {
+    let resources = memory_resources!(
+        "src/test.lua" => "local value: number = 1\nvalue += 2\nreturn if value > 2 then `big {value}` else 'small'",
+        ".darklua.json" => "{ preset: 'lua51-compat', rules: ['remove_spaces'], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    local value=1 value=value+2 return(value>2 and{(string.format('big %s',tostring(
+    value)))}or{'small'})[1]
+    "###);
+}
+
+#[test]
+fn target_adds_rules_for_unsupported_syntax() {
+    let resources = memory_resources!(
+        "src/test.lua" => "local value: number = 0b1\nvalue //= 2\nreturn value",
+        ".darklua.json" => "{ rules: [], target: 'lua51', generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @"local value=0x1 value=math.floor(value/2)return value");
+}
+
+#[test]
+fn lua53_target_keeps_floor_division() {
+    let resources = memory_resources!(
+        "src/test.lua" => "local value: number = 1\nvalue //= 2\nreturn value",
+        ".darklua.json" => "{ rules: [], target: 'lua53', generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @"local value=1 value=value//2 return value");
+}
+
+#[test]
+fn readable_generator_keeps_directive_comments() {
+    let resources = memory_resources!(
+        "src/test.lua" => "--!strict\n-- comment\nlocal value = 1\nreturn value",
+        ".darklua.json" => "{ rules: [], generator: { name: 'readable', keep_comments: 'directives' } }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    --!strict
+    local value = 1
+
+    return value
+    "###);
+}
+
+#[test]
+fn dense_generator_keeps_appended_text_comments() {
+    let resources = memory_resources!(
+        "src/test.lua" => "-- comment\nlocal value = 1\nreturn value",
+        ".darklua.json" => "{ rules: [{ rule: 'append_text_comment', text: 'header: {file_path}' }, { rule: 'append_text_comment', text: 'footer', location: 'end' }], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    --header: src/test.lua
+    local value=1 return value
+    --footer
+    "###);
+}
+
+#[test]
+fn readable_generator_keeps_appended_text_comments() {
+    let resources = memory_resources!(
+        "src/test.lua" => "--!strict\n-- comment\nlocal value = 1\nreturn value",
+        ".darklua.json" => "{ rules: [{ rule: 'append_text_comment', text: 'license' }], generator: { name: 'readable', keep_comments: 'directives' } }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @r###"
+    --license
+    --!strict
+    local value = 1
+
+    return value
+    "###);
+}
+
+#[test]
+fn overrides_apply_different_rules_to_matching_files() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = 1 + 1\nreturn value",
+        "src/tests/main.spec.lua" => "local value = 1 + 1\nreturn value",
+        ".darklua.json" => "{ rules: ['compute_expression'], overrides: [{ include: ['src/tests/**'], rules: [] }], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+    insta::assert_snapshot!(resources.get("output/tests/main.spec.lua").unwrap(), @"local value=1+1 return value");
+}
+
+#[test]
+fn overrides_use_first_matching_override() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = 1 + 1\nreturn value",
+        ".darklua.json" => "{ rules: [], overrides: [{ include: ['**/main.lua'], rules: ['compute_expression'] }, { include: ['src/**'], rules: ['remove_unused_variable'] }], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+}
+
+#[test]
+fn overrides_are_relative_to_configuration_file() {
+    let resources = memory_resources!(
+        "project/src/main.lua" => "local value = 1 + 1\nreturn value",
+        "project/.darklua.json" => "{ rules: [], overrides: [{ include: ['src/**'], rules: ['compute_expression'] }], generator: 'dense' }",
+    );
+
+    process(
+        &resources,
+        Options::new("project/src")
+            .with_output("output")
+            .with_configuration_at("project/.darklua.json"),
+    )
+    .unwrap()
+    .result()
+    .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+}
+
+#[test]
+fn overrides_get_rules_required_by_target() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value: number = 1\nreturn value",
+        ".darklua.json" => "{ rules: [], target: 'lua51', overrides: [{ include: ['src/**'], rules: [] }], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=1 return value");
+}
+
+#[test]
+fn rule_exclude_skips_matching_files() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = 1 + 1\nreturn value",
+        "src/__tests__/main.spec.lua" => "local value = 1 + 1\nreturn value",
+        ".darklua.json" => "{ rules: [{ rule: 'compute_expression', exclude_paths: ['**/__tests__/**'] }], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+    insta::assert_snapshot!(resources.get("output/__tests__/main.spec.lua").unwrap(), @"local value=1+1 return value");
+}
+
+#[test]
+fn rule_include_applies_rule_to_matching_files() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = 1 + 1\nreturn value",
+        "src/server/main.lua" => "local value = 1 + 1\nreturn value",
+        ".darklua.json" => "{ rules: [{ rule: 'compute_expression', include_paths: ['src/server/**'] }, 'remove_unused_variable'], generator: 'dense' }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=1+1 return value");
+    insta::assert_snapshot!(resources.get("output/server/main.lua").unwrap(), @"local value=2 return value");
+}
+
+#[test]
+fn rule_filters_are_relative_to_configuration_file() {
+    let resources = memory_resources!(
+        "project/src/main.lua" => "local value = 1 + 1\nreturn value",
+        "project/src/generated/main.lua" => "local value = 1 + 1\nreturn value",
+        "project/.darklua.json" => "{ rules: [{ rule: 'compute_expression', include_paths: ['src/**'], exclude_paths: ['src/generated/**'] }], generator: 'dense' }",
+    );
+
+    process(
+        &resources,
+        Options::new("project/src")
+            .with_output("output")
+            .with_configuration_at("project/.darklua.json"),
+    )
+    .unwrap()
+    .result()
+    .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+    insta::assert_snapshot!(resources.get("output/generated/main.lua").unwrap(), @"local value=1+1 return value");
+}
+
+#[test]
+fn extends_merges_base_configuration() {
+    let resources = memory_resources!(
+        "src/main.lua" => "-- comment\nlocal value = 1 + 1\nreturn value",
+        "base.darklua.json" => "{ rules: ['remove_comments', 'remove_spaces'], generator: 'dense' }",
+        ".darklua.json" => "{ extends: './base.darklua.json', rules: ['compute_expression'] }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+}
+
+#[test]
+fn extends_is_relative_to_configuration_file() {
+    let resources = memory_resources!(
+        "packages/a/src/main.lua" => "local value = 1 + 1\nreturn value",
+        "config/base.json5" => "{ rules: ['compute_expression'], generator: 'dense' }",
+        "packages/a/.darklua.json" => "{ extends: '../../config/base.json5' }",
+    );
+
+    process(
+        &resources,
+        Options::new("packages/a/src")
+            .with_output("output")
+            .with_configuration_at("packages/a/.darklua.json"),
+    )
+    .unwrap()
+    .result()
+    .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+}
+
+#[test]
+fn extends_list_applies_configurations_in_order() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = 1 + 1\nreturn value",
+        "a.json" => "{ rules: ['compute_expression'], generator: 'readable' }",
+        "b.json" => "{ generator: 'dense' }",
+        ".darklua.json" => "{ extends: ['./a.json', './b.json'] }",
+    );
+
+    process(&resources, Options::new("src").with_output("output"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("output/main.lua").unwrap(), @"local value=2 return value");
+}
+
+mod custom_rules {
+    use darklua_core::{
+        nodes::{Block, Expression, ReturnStatement},
+        register_rule,
+        rules::{Context, FlawlessRule, RuleConfiguration, RuleConfigurationError, RuleProperties},
+    };
+
+    use super::*;
+
+    /// Replaces the file content with `return <value>`.
+    #[derive(Debug, Default)]
+    struct ReturnValueRule {
+        value: String,
+    }
+
+    impl FlawlessRule for ReturnValueRule {
+        fn flawless_process(&self, block: &mut Block, _: &Context) {
+            *block = Block::default()
+                .with_last_statement(ReturnStatement::one(Expression::identifier(&self.value)));
+        }
+    }
+
+    impl RuleConfiguration for ReturnValueRule {
+        fn configure(&mut self, properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+            for (key, value) in properties {
+                match key.as_str() {
+                    "value" => {
+                        self.value = value.expect_string(&key)?;
+                    }
+                    _ => return Err(RuleConfigurationError::UnexpectedProperty(key)),
+                }
+            }
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "test_return_value"
+        }
+
+        fn serialize_to_properties(&self) -> RuleProperties {
+            RuleProperties::new()
+        }
+    }
+
+    #[test]
+    fn use_registered_rule_from_configuration_file() {
+        assert!(register_rule("test_return_value", || Box::new(
+            ReturnValueRule::default()
+        )));
+
+        let resources = memory_resources!(
+            "src/test.lua" => "return true",
+            ".darklua.json" => "{ rules: [{ rule: 'test_return_value', value: 'custom' }] }",
+        );
+
+        process(&resources, Options::new("src").with_output("output"))
+            .unwrap()
+            .result()
+            .unwrap();
+
+        insta::assert_snapshot!(resources.get("output/test.lua").unwrap(), @"return custom");
+    }
+
+    #[test]
+    fn unregistered_rule_in_configuration_file_errors() {
+        let resources = memory_resources!(
+            "src/test.lua" => "return true",
+            ".darklua.json" => "{ rules: ['test_unregistered_rule'] }",
+        );
+
+        let errors = process(&resources, Options::new("src"))
+            .map_err(|err| vec![err])
+            .and_then(|worker_tree| worker_tree.result())
+            .unwrap_err();
+
+        insta::assert_snapshot!(
+            errors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n"),
+            @"invalid configuration file at `.darklua.json` (invalid rule name: test_unregistered_rule at line 1 column 11)"
+        );
+    }
+}
+
+mod incremental {
+    use std::{
+        path::Path,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+    };
+
+    use darklua_core::{
+        nodes::Block,
+        rules::{
+            Context, PathRequireMode, Rule, RuleConfiguration, RuleConfigurationError,
+            RuleProcessResult, RuleProperties,
+        },
+        BundleConfiguration, Configuration, GeneratorParameters, WorkerTree,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::{memory_resources, Options, Resources};
+
+    #[derive(Debug, Default, Clone)]
+    struct CountProcessRule {
+        count: Arc<AtomicUsize>,
+    }
+
+    impl CountProcessRule {
+        fn count(&self) -> usize {
+            self.count.load(Ordering::SeqCst)
+        }
+    }
+
+    impl RuleConfiguration for CountProcessRule {
+        fn configure(&mut self, _properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "count-process"
+        }
+
+        fn serialize_to_properties(&self) -> RuleProperties {
+            Default::default()
+        }
+    }
+
+    impl Rule for CountProcessRule {
+        fn process(&self, _: &mut Block, _: &Context) -> RuleProcessResult {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn process_tree(
+        worker_tree: &mut WorkerTree,
+        resources: &Resources,
+        rule: &CountProcessRule,
+        configuration: Configuration,
+        options: Options,
+    ) {
+        let rule: Box<dyn Rule> = Box::new(rule.clone());
+        let options = options.with_configuration(configuration.with_rule(rule));
+
+        worker_tree.collect_work(resources, &options).unwrap();
+        worker_tree.process(resources, options).unwrap();
+
+        assert!(worker_tree.collect_errors().is_empty());
+    }
+
+    fn process_src(worker_tree: &mut WorkerTree, resources: &Resources, rule: &CountProcessRule) {
+        process_tree(
+            worker_tree,
+            resources,
+            rule,
+            Configuration::empty(),
+            Options::new("src").with_output("output"),
+        );
+    }
+
+    #[test]
+    fn unchanged_files_are_not_processed_again() {
+        let resources = memory_resources!(
+            "src/a.lua" => "return 'a'",
+            "src/b.lua" => "return 'b'",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_src(&mut worker_tree, &resources, &rule);
+        assert_eq!(rule.count(), 2);
+
+        worker_tree.source_changed("src/a.lua");
+        worker_tree.source_changed("src/b.lua");
+        process_src(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 2);
+        assert_eq!(resources.get("output/a.lua").unwrap(), "return 'a'");
+        assert_eq!(resources.get("output/b.lua").unwrap(), "return 'b'");
+        assert_eq!(worker_tree.success_count(), 2);
+    }
+
+    #[test]
+    fn changed_file_is_processed_again() {
+        let resources = memory_resources!(
+            "src/a.lua" => "return 'a'",
+            "src/b.lua" => "return 'b'",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_src(&mut worker_tree, &resources, &rule);
+
+        resources.write("src/a.lua", "return 'new a'").unwrap();
+        worker_tree.source_changed("src/a.lua");
+        worker_tree.source_changed("src/b.lua");
+        process_src(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 3);
+        assert_eq!(resources.get("output/a.lua").unwrap(), "return 'new a'");
+    }
+
+    #[test]
+    fn modified_output_is_generated_again() {
+        let resources = memory_resources!(
+            "src/a.lua" => "return 'a'",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_src(&mut worker_tree, &resources, &rule);
+
+        resources.write("output/a.lua", "return 'edited'").unwrap();
+        worker_tree.source_changed("src/a.lua");
+        process_src(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 2);
+        assert_eq!(resources.get("output/a.lua").unwrap(), "return 'a'");
+    }
+
+    #[test]
+    fn unchanged_file_processed_in_place_is_not_processed_again() {
+        let resources = memory_resources!(
+            "src/a.lua" => "return 'a'",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        let process_in_place = |worker_tree: &mut WorkerTree| {
+            process_tree(
+                worker_tree,
+                &resources,
+                &rule,
+                Configuration::empty(),
+                Options::new("src"),
+            )
+        };
+
+        process_in_place(&mut worker_tree);
+        worker_tree.source_changed("src/a.lua");
+        process_in_place(&mut worker_tree);
+
+        assert_eq!(rule.count(), 1);
+    }
+
+    #[test]
+    fn configuration_change_processes_files_again() {
+        let resources = memory_resources!(
+            "src/a.lua" => "return 'a'",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_src(&mut worker_tree, &resources, &rule);
+        process_tree(
+            &mut worker_tree,
+            &resources,
+            &rule,
+            Configuration::empty().with_generator(GeneratorParameters::default_dense()),
+            Options::new("src").with_output("output"),
+        );
+
+        assert_eq!(rule.count(), 2);
+    }
+
+    fn process_bundle(
+        worker_tree: &mut WorkerTree,
+        resources: &Resources,
+        rule: &CountProcessRule,
+    ) {
+        process_tree(
+            worker_tree,
+            resources,
+            rule,
+            Configuration::empty()
+                .with_bundle_configuration(BundleConfiguration::new(PathRequireMode::default())),
+            Options::new("src/main.lua").with_output("output.lua"),
+        );
+    }
+
+    #[test]
+    fn bundle_is_not_processed_again_when_modules_are_unchanged() {
+        let resources = memory_resources!(
+            "src/main.lua" => "local value = require('./value') return value",
+            "src/value.lua" => "return true",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_bundle(&mut worker_tree, &resources, &rule);
+        let output = resources.get("output.lua").unwrap();
+
+        worker_tree.source_changed("src/value.lua");
+        process_bundle(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 1);
+        assert_eq!(resources.get("output.lua").unwrap(), output);
+    }
+
+    #[test]
+    fn bundle_is_processed_again_when_a_module_changes() {
+        let resources = memory_resources!(
+            "src/main.lua" => "local value = require('./value') return value",
+            "src/value.lua" => "return true",
+        );
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_bundle(&mut worker_tree, &resources, &rule);
+
+        resources.write("src/value.lua", "return false").unwrap();
+        worker_tree.source_changed("src/value.lua");
+        process_bundle(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 2);
+        assert!(resources.get("output.lua").unwrap().contains("false"));
+    }
+
+    fn process_bundle_entries(
+        worker_tree: &mut WorkerTree,
+        resources: &Resources,
+        rule: &CountProcessRule,
+    ) {
+        process_tree(
+            worker_tree,
+            resources,
+            rule,
+            Configuration::empty()
+                .with_bundle_configuration(BundleConfiguration::new(PathRequireMode::default())),
+            Options::new("entries").with_output("output"),
+        );
+    }
+
+    fn bundle_entries_resources() -> Resources {
+        memory_resources!(
+            "entries/a.lua" => "local value = require('../modules/a') return value",
+            "entries/b.lua" => "local value = require('../modules/b') return value",
+            "modules/a.lua" => "local shared = require('./shared') return shared",
+            "modules/b.lua" => "return 'b'",
+            "modules/shared.lua" => "return 'shared'",
+        )
+    }
+
+    #[test]
+    fn only_entry_points_requiring_a_changed_module_are_bundled_again() {
+        let resources = bundle_entries_resources();
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_bundle_entries(&mut worker_tree, &resources, &rule);
+        assert_eq!(rule.count(), 2);
+        let output_b = resources.get("output/b.lua").unwrap();
+
+        resources
+            .write("modules/shared.lua", "return 'new shared'")
+            .unwrap();
+        worker_tree.source_changed("modules/shared.lua");
+        process_bundle_entries(&mut worker_tree, &resources, &rule);
+
+        assert_eq!(rule.count(), 3);
+        assert!(resources
+            .get("output/a.lua")
+            .unwrap()
+            .contains("new shared"));
+        assert_eq!(resources.get("output/b.lua").unwrap(), output_b);
+    }
+
+    #[test]
+    fn dependency_graph_contains_transitively_bundled_modules() {
+        let resources = bundle_entries_resources();
+        let rule = CountProcessRule::default();
+        let mut worker_tree = WorkerTree::default();
+
+        process_bundle_entries(&mut worker_tree, &resources, &rule);
+
+        let graph = worker_tree.dependency_graph();
+
+        assert_eq!(
+            graph.iter_sources().collect::<Vec<_>>(),
+            vec![Path::new("entries/a.lua"), Path::new("entries/b.lua")]
+        );
+        assert_eq!(
+            graph.iter_dependencies("entries/a.lua").collect::<Vec<_>>(),
+            vec![Path::new("modules/a.lua"), Path::new("modules/shared.lua")]
+        );
+        assert_eq!(
+            graph.affected_sources("modules/shared.lua"),
+            vec![Path::new("entries/a.lua")]
+        );
+        assert_eq!(
+            graph.affected_sources("modules/b.lua"),
+            vec![Path::new("entries/b.lua")]
+        );
+    }
+}
+
+mod parallel {
+    use std::path::{Path, PathBuf};
+
+    use darklua_core::{
+        nodes::Block,
+        rules::{
+            Context, RenameVariables, Rule, RuleConfiguration, RuleConfigurationError,
+            RuleProcessResult, RuleProperties,
+        },
+        Configuration, GeneratorParameters, WorkerTree,
+    };
+
+    use pretty_assertions::assert_eq;
+
+    use super::{process, Options, Resources};
+
+    const FILE_COUNT: usize = 24;
+
+    fn file_path(index: usize) -> String {
+        format!("src/module_{}.lua", index)
+    }
+
+    fn modules_resources(invalid_every: Option<usize>) -> Resources {
+        let resources = Resources::from_memory();
+
+        for index in 0..FILE_COUNT {
+            let content = match invalid_every {
+                Some(every) if index % every == 0 => "return +".to_owned(),
+                _ => format!("do end local value = {} return value", index),
+            };
+            resources.write(file_path(index), &content).unwrap();
+        }
+
+        resources
+    }
+
+    fn process_outputs(resources: &Resources, jobs: usize) -> Vec<String> {
+        let output = format!("output_{}", jobs);
+
+        process(
+            resources,
+            Options::new("src").with_output(&output).with_jobs(jobs),
+        )
+        .unwrap()
+        .result()
+        .unwrap();
+
+        (0..FILE_COUNT)
+            .map(|index| {
+                resources
+                    .get(format!("{}/module_{}.lua", output, index))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn process_errors(resources: &Resources, options: Options) -> Vec<String> {
+        process(resources, options)
+            .unwrap()
+            .result()
+            .unwrap_err()
+            .into_iter()
+            .map(|err| err.to_string().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn outputs_do_not_depend_on_the_number_of_jobs() {
+        let resources = modules_resources(None);
+
+        let expected = process_outputs(&resources, 1);
+
+        assert_eq!(expected[3], "local a=3 return a");
+        assert_eq!(process_outputs(&resources, 4), expected);
+        assert_eq!(process_outputs(&resources, 64), expected);
+    }
+
+    #[test]
+    fn errors_do_not_depend_on_the_number_of_jobs() {
+        let resources = modules_resources(Some(5));
+
+        let expected = process_errors(&resources, Options::new("src").with_jobs(1));
+
+        assert_eq!(expected.len(), 5);
+        assert_eq!(
+            process_errors(&resources, Options::new("src").with_jobs(3)),
+            expected
+        );
+        assert_eq!(
+            process_errors(&resources, Options::new("src").with_jobs(16)),
+            expected
+        );
+    }
+
+    #[test]
+    fn fail_fast_with_single_job_reports_first_error() {
+        let resources = modules_resources(Some(5));
+
+        let errors = process_errors(&resources, Options::new("src").with_jobs(1).fail_fast());
+
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn fail_fast_reports_same_error_with_multiple_jobs() {
+        let resources = modules_resources(Some(5));
+
+        let expected = process_errors(&resources, Options::new("src").with_jobs(1).fail_fast());
+
+        for jobs in [2, 3, 16] {
+            assert_eq!(
+                process_errors(&resources, Options::new("src").with_jobs(jobs).fail_fast()),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn name_map_contains_every_file_with_multiple_jobs() {
+        let resources = Resources::from_memory();
+
+        for index in 0..FILE_COUNT {
+            resources
+                .write(
+                    file_path(index),
+                    &format!("\nlocal value = {}\nreturn value", index),
+                )
+                .unwrap();
+        }
+
+        let rule: Box<dyn Rule> =
+            Box::new(RenameVariables::default().with_name_map_file("names.json"));
+        let options = Options::new("src")
+            .with_output("output")
+            .with_jobs(8)
+            .with_generator_override(GeneratorParameters::default_dense())
+            .with_configuration(Configuration::empty().with_rule(rule).with_location("."));
+
+        process(&resources, options).unwrap().result().unwrap();
+
+        let name_map: serde_json::Value =
+            serde_json::from_str(&resources.get("names.json").unwrap()).unwrap();
+        let name_map = name_map.as_object().unwrap();
+
+        assert_eq!(name_map.len(), FILE_COUNT);
+
+        for index in 0..FILE_COUNT {
+            assert_eq!(
+                name_map[&file_path(index)],
+                serde_json::json!([{ "name": "value", "line": 2, "renamed": "a" }])
+            );
+        }
+    }
+
+    #[derive(Debug)]
+    struct CopySharedModule;
+
+    const SHARED_MODULE: &str = "src/shared.lua";
+
+    impl RuleConfiguration for CopySharedModule {
+        fn configure(&mut self, _properties: RuleProperties) -> Result<(), RuleConfigurationError> {
+            Ok(())
+        }
+
+        fn get_name(&self) -> &'static str {
+            "copy-shared-module"
+        }
+
+        fn serialize_to_properties(&self) -> RuleProperties {
+            Default::default()
+        }
+    }
+
+    impl Rule for CopySharedModule {
+        fn process(&self, block: &mut Block, context: &Context) -> RuleProcessResult {
+            if let Some(shared) = context.block(SHARED_MODULE) {
+                *block = shared.clone();
+            }
+            Ok(())
+        }
+
+        fn require_content(&self, current_source: &Path, _: &Block) -> Vec<PathBuf> {
+            if current_source == Path::new(SHARED_MODULE) {
+                Vec::new()
+            } else {
+                vec![SHARED_MODULE.into()]
+            }
+        }
+    }
+
+    #[test]
+    fn required_content_is_available_with_multiple_jobs() {
+        let resources = modules_resources(None);
+        resources.write(SHARED_MODULE, "return 'shared'").unwrap();
+
+        let rule: Box<dyn Rule> = Box::new(CopySharedModule);
+        let options = Options::new("src")
+            .with_output("output")
+            .with_jobs(4)
+            .with_generator_override(GeneratorParameters::default_dense())
+            .with_configuration(Configuration::empty().with_rule(rule));
+
+        let mut worker_tree = WorkerTree::default();
+        worker_tree.collect_work(&resources, &options).unwrap();
+        worker_tree.process(&resources, options).unwrap();
+        worker_tree.result().unwrap();
+
+        let shared_output = resources.get("output/shared.lua").unwrap();
+
+        for index in 0..FILE_COUNT {
+            assert_eq!(
+                resources
+                    .get(format!("output/module_{}.lua", index))
+                    .unwrap(),
+                shared_output
+            );
+        }
+    }
+}
+
+#[cfg(feature = "wasm")]
+mod process_code {
+    use darklua_core::{process_code, Resources};
+
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn process_empty_code() {
+        assert_eq!(process_code("", ""), Ok("".to_owned()));
+    }
+
+    #[test]
+    fn process_with_default_configuration() {
+        assert_eq!(process_code("return 1 + 1", ""), Ok("return 2".to_owned()));
+    }
+
+    #[test]
+    fn process_with_configuration() {
+        assert_eq!(
+            process_code("return 1 + 1", r#"{ "rules": [] }"#),
+            Ok("return 1 + 1".to_owned())
+        );
+    }
+
+    #[test]
+    fn process_with_json5_configuration() {
+        assert_eq!(
+            process_code(
+                "return CONSTANT",
+                "{ rules: [{ rule: 'inject_global_value', identifier: 'CONSTANT', value: true }] }"
+            ),
+            Ok("return true".to_owned())
+        );
+    }
+
+    #[test]
+    fn process_invalid_code_returns_diagnostics() {
+        let diagnostics = process_code("return +", "").unwrap_err();
+
+        assert!(!diagnostics.is_empty());
+        for diagnostic in diagnostics {
+            assert_eq!(diagnostic.file(), Some("main.lua"));
+            assert!(diagnostic.span().is_some());
+        }
+    }
+
+    #[test]
+    fn process_with_invalid_configuration_returns_diagnostics() {
+        let diagnostics = process_code("return 1", "{ rules: [").unwrap_err();
+
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn file_system_resources_still_read_files() {
+        let resources = Resources::from_file_system();
+
+        assert_eq!(resources.exists("Cargo.toml"), Ok(true));
+    }
+}
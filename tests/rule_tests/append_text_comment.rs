@@ -90,6 +90,36 @@ test_rule_with_tokens!(
     empty_do("do end") => "--[[\n1\n2\n]]\ndo end",
 );
 
+test_rule_with_tokens!(
+    append_text_comment_with_file_path_variable,
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'append_text_comment',
+        text: 'file: {file_path}',
+    }"#).unwrap(),
+    test_file_name = "src/module.lua",
+    local_assign("local a") => "--file: src/module.lua\nlocal a",
+);
+
+test_rule_with_tokens!(
+    append_text_comment_with_unknown_variable,
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'append_text_comment',
+        text: '{unknown} {file_path',
+    }"#).unwrap(),
+    local_assign("local a") => "--{unknown} {file_path\nlocal a",
+);
+
+test_rule_with_tokens!(
+    append_text_comment_end,
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'append_text_comment',
+        text: 'hello',
+        location: 'end',
+    }"#).unwrap(),
+    local_assign("local a") => "local a--hello",
+    local_assign_with_new_line("local a\n") => "local a\n--hello",
+);
+
 test_rule_without_effects!(
     json5::from_str::<Box<dyn Rule>>(
         r#"{
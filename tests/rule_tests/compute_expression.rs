@@ -37,11 +37,57 @@ test_rule!(
     preserve_negative_zero("return -0") => "return -0",
     addition_preserve_negative_zero("return -0 + -0") => "return -0",
     subtract_preserve_negative_zero("return -0 - 0") => "return -0",
+    concat_strings("return 'a' .. 'b'") => "return 'ab'",
+    concat_multiple_strings("return 'a' .. 'b' .. 'c'") => "return 'abc'",
+    concat_string_and_number("return 'v' .. 1") => "return 'v1'",
+    concat_string_and_negative_integer("return 'v' .. -12") => "return 'v-12'",
+    length_of_string("return #'hello'") => "return 5",
+);
+
+test_rule_without_effects!(
+    ComputeExpression::default(),
+    string_rep_without_string_functions("return string.rep('a', 3)"),
+    string_format_without_string_functions("return string.format('%s', 'a')"),
+);
+
+test_rule!(
+    compute_expression_with_string_functions,
+    ComputeExpression::default().with_string_functions(true),
+    string_rep("return string.rep('ab', 3)") => "return 'ababab'",
+    string_rep_zero("return string.rep('ab', 0)") => "return ''",
+    string_format("return string.format('%s = %d', 'value', 10)") => "return 'value = 10'",
+    string_format_percent("return string.format('%d%%', 50)") => "return '50%'",
+    string_rep_in_concat("return string.rep('-', 3) .. '>'") => "return '--->'",
+    length_of_string_rep("return #string.rep('a', 4)") => "return 4",
+    string_format_with_string_rep("return string.format('[%s]', string.rep('x', 2))") => "return '[xx]'",
+    string_rep_with_shadowed_string_in_other_scope("do local string = {} end return string.rep('a', 2)")
+        => "do local string = {} end return 'aa'",
+);
+
+test_rule_without_effects!(
+    ComputeExpression::default().with_string_functions(true),
+    string_rep_with_unknown_count("return string.rep('a', count)"),
+    string_rep_too_long("return string.rep('abcd', 1000)"),
+    string_rep_with_separator("return string.rep('a', 3, ',')"),
+    string_rep_with_nil_separator("return string.rep('a', 3, nil)"),
+    string_format_unsupported_conversion("return string.format('%.2f', 1)"),
+    string_format_missing_argument("return string.format('%s')"),
+    string_format_with_call_argument("return string.format('%s', call())"),
+    string_format_with_decimal("return string.format('%s', 0.1)"),
+    string_format_with_large_number("return string.format('%s', 1e100)"),
+    string_format_with_1e15("return string.format('%s', 1e15)"),
+    string_format_with_nan("return string.format('%s', 0/0)"),
+    string_method_call("return ('a'):rep(2)"),
+    shadowed_string_library("local string = { rep = print } return string.rep('a', 2)"),
+    shadowed_string_library_parameter("local function f(string) return string.rep('a', 2) end"),
 );
 
 test_rule_without_effects!(
     ComputeExpression::default(),
     if_expression_unknown_condition("return if condition then func() else func2()"),
+    concat_string_and_decimal("return 'v' .. 0.1"),
+    concat_string_and_large_number("return 'v' .. 1e100"),
+    concat_string_and_nan("return 'v' .. 0/0"),
 );
 
 #[test]
@@ -54,6 +100,17 @@ fn deserialize_from_object_notation() {
     .unwrap();
 }
 
+#[test]
+fn deserialize_with_string_functions() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'compute_expression',
+        string_functions: true,
+    }"#,
+    )
+    .unwrap();
+}
+
 #[test]
 fn deserialize_from_string() {
     json5::from_str::<Box<dyn Rule>>("'compute_expression'").unwrap();
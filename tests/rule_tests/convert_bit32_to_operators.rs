@@ -0,0 +1,37 @@
+use darklua_core::rules::ConvertBit32ToOperators;
+
+test_rule_with_bitwise_operators!(
+    convert_bit32_to_operators,
+    ConvertBit32ToOperators::default(),
+    band("return bit32.band(a, b)") => "return a & b & 0xFFFFFFFF",
+    band_with_number("return bit32.band(flags, 0xFF)") => "return flags & 0xFF",
+    bor("return bit32.bor(a, b)") => "return (a | b) & 0xFFFFFFFF",
+    bxor("return bit32.bxor(a, b)") => "return (a ~ b) & 0xFFFFFFFF",
+    lshift("return bit32.lshift(a, 4)") => "return a << 4 & 0xFFFFFFFF",
+    lshift_with_variable_shift("return bit32.lshift(a, n)") => "return (a & 0xFFFFFFFF) << n & 0xFFFFFFFF",
+    rshift("return bit32.rshift(a, 4)") => "return (a & 0xFFFFFFFF) >> 4",
+    rshift_number("return bit32.rshift(0xFF00, 8)") => "return 0xFF00 >> 8",
+    rshift_with_variable_shift("return bit32.rshift(a, n)") => "return (a & 0xFFFFFFFF) >> n & 0xFFFFFFFF",
+    bnot("return bit32.bnot(a)") => "return ~a & 0xFFFFFFFF",
+    band_with_three_arguments("return bit32.band(a, b, c)") => "return a & b & c & 0xFFFFFFFF",
+    nested_calls("return bit32.bor(bit32.lshift(a, 8), b)") => "return (a << 8 & 0xFFFFFFFF | b) & 0xFFFFFFFF",
+    binary_argument("return bit32.band(a + b, 0xFF)") => "return a + b & 0xFF",
+    logical_argument("return bit32.band(a or b, c)") => "return (a or b) & c & 0xFFFFFFFF",
+    bnot_binary_argument("return bit32.bnot(a | b)") => "return ~(a | b) & 0xFFFFFFFF",
+    in_local_assign("local value = bit32.bxor(a, b)") => "local value = (a ~ b) & 0xFFFFFFFF",
+    in_function_argument("print(bit32.band(a, b))") => "print(a & b & 0xFFFFFFFF)",
+    string_argument("return bit32.bnot'1'") => "return ~'1' & 0xFFFFFFFF",
+    band_with_one_argument("return bit32.band(a)") => "return bit32.band(a)",
+    lshift_with_one_argument("return bit32.lshift(a)") => "return bit32.lshift(a)",
+    bnot_with_two_arguments("return bit32.bnot(a, b)") => "return bit32.bnot(a, b)",
+    last_argument_is_call("return bit32.band(a, call())") => "return bit32.band(a, call())",
+    last_argument_is_variadic("return bit32.bor(a, ...)") => "return bit32.bor(a, ...)",
+    unsupported_function("return bit32.arshift(a, 2)") => "return bit32.arshift(a, 2)",
+    method_call("return bit32:band(a, b)") => "return bit32:band(a, b)",
+    local_bit32_is_not_converted("local bit32 = {} return bit32.band(a, b)")
+        => "local bit32 = {} return bit32.band(a, b)",
+    bit32_parameter_is_not_converted("local function f(bit32) return bit32.bor(a, b) end")
+        => "local function f(bit32) return bit32.bor(a, b) end",
+    global_bit32_after_local_scope("do local bit32 = {} end return bit32.band(a, b)")
+        => "do local bit32 = {} end return a & b & 0xFFFFFFFF",
+);
@@ -0,0 +1,21 @@
+use darklua_core::rules::ConvertBitwiseOperatorsToBit32;
+
+test_rule_with_bitwise_operators!(
+    convert_bitwise_operators_to_bit32,
+    ConvertBitwiseOperatorsToBit32::default(),
+    bitwise_and("return a & b") => "return bit32.band(a, b)",
+    bitwise_or("return a | b") => "return bit32.bor(a, b)",
+    bitwise_xor("return a ~ b") => "return bit32.bxor(a, b)",
+    left_shift("return a << 4") => "return bit32.lshift(a, 4)",
+    right_shift("return a >> 4") => "return bit32.rshift(a, 4)",
+    bitwise_not("return ~a") => "return bit32.bnot(a)",
+    chained_operators("return a & b & c") => "return bit32.band(bit32.band(a, b), c)",
+    mixed_operators("return a << 8 | b") => "return bit32.bor(bit32.lshift(a, 8), b)",
+    parenthese_operand("return (a + b) & 0xFF") => "return bit32.band((a + b), 0xFF)",
+    bitwise_not_of_binary("return ~(a | b)") => "return bit32.bnot((bit32.bor(a, b)))",
+    nested_in_function("local function f(a) return a ~ 1 end")
+        => "local function f(a) return bit32.bxor(a, 1) end",
+    local_bit32_defines_alias("local bit32 = {} return a & b")
+        => "local __DARKLUA_BIT32 = bit32 local bit32 = {} return __DARKLUA_BIT32.band(a, b)",
+    unary_minus_is_not_converted("return -a") => "return -a",
+);
@@ -53,6 +53,16 @@ test_rule!(
         => "local r = 0x123456789A",
     hexadecimal_twelve_digits("local s = 0x123_456_789_ABC")
         => "local s = 0x123456789ABC",
+    decimal_with_fraction_and_underscores("local t = 1_000.000_5")
+        => "local t = 1000.0005",
+    decimal_with_exponent_and_underscores("local u = 1_0e1_0")
+        => "local u = 10e10",
+    binary_literal_sixty_four_bits("local v = 0b1111111111111111111111111111111111111111111111111111111111111111")
+        => "local v = 0xFFFFFFFFFFFFFFFF",
+    binary_literal_zero("local w = 0b0")
+        => "local w = 0x0",
+    numbers_in_expressions("return 0b11 + 1_000 * 0xF_F")
+        => "return 0x3 + 1000 * 0xFF",
 );
 
 #[test]
@@ -0,0 +1,135 @@
+use darklua_core::{
+    generator::{DenseLuaGenerator, LuaGenerator},
+    rules::{ContextBuilder, ConvertNumericForToOptimizedForm, Rule},
+    LuaTarget, Parser, Resources,
+};
+
+test_rule!(
+    convert_numeric_for_to_ipairs,
+    ConvertNumericForToOptimizedForm::default().convert_to_ipairs(),
+    numeric_for_over_array("local list = {} for i = 1, #list do local value = list[i] print(i, value) end")
+        => "local list = {} for i, value in ipairs(list) do print(i, value) end",
+    numeric_for_with_step_of_one("local list = {} for i = 1, #list, 1 do local value = list[i] print(value) end")
+        => "local list = {} for i, value in ipairs(list) do print(value) end",
+    numeric_for_reading_other_fields("local list = {} for i = 1, #list do local value = list[i] print(value, list[i + 1], list.n) end")
+        => "local list = {} for i, value in ipairs(list) do print(value, list[i + 1], list.n) end",
+    nested_numeric_for("local rows = {} for i = 1, #rows do local row = rows[i] for j = 1, #row do local cell = row[j] print(cell) end end")
+        => "local rows = {} for i, row in ipairs(rows) do for j, cell in ipairs(row) do print(cell) end end",
+    numeric_for_over_global_array_without_calls("for i = 1, #list do local value = list[i] total = total + value end")
+        => "for i, value in ipairs(list) do total = total + value end",
+    numeric_for_over_local_captured_without_calls("local list = {} local function get() return list end for i = 1, #list do local value = list[i] total = total + value end")
+        => "local list = {} local function get() return list end for i, value in ipairs(list) do total = total + value end",
+);
+
+test_rule_without_effects!(
+    ConvertNumericForToOptimizedForm::default().convert_to_ipairs(),
+    numeric_for_without_value("for i = 1, #list do print(list[i]) end"),
+    numeric_for_starting_at_zero("for i = 0, #list do local value = list[i] end"),
+    numeric_for_with_step("for i = 1, #list, 2 do local value = list[i] end"),
+    numeric_for_with_constant_end("for i = 1, 10 do local value = list[i] end"),
+    numeric_for_over_other_array("for i = 1, #list do local value = other[i] end"),
+    numeric_for_with_other_index("for i = 1, #list do local value = list[i + 1] end"),
+    numeric_for_with_multiple_values("for i = 1, #list do local value, other = list[i], 1 end"),
+    numeric_for_assigning_array("for i = 1, #list do local value = list[i] list[i] = nil end"),
+    numeric_for_passing_array("for i = 1, #list do local value = list[i] table.remove(list) end"),
+    numeric_for_with_value_named_like_array("for i = 1, #list do local list = list[i] end"),
+    numeric_for_with_shadowed_ipairs(
+        "local ipairs = f for i = 1, #list do local value = list[i] end"
+    ),
+    numeric_for_over_global_array_with_call("for i = 1, #list do local value = list[i] g() end"),
+    numeric_for_over_captured_local_with_call(
+        "local list = {} local function g() list = {} end for i = 1, #list do local value = list[i] g() end"
+    ),
+    numeric_for_over_local_captured_by_function_expression_with_call(
+        "local list = {} callback = function() list[1] = nil end for i = 1, #list do local value = list[i] g() end"
+    ),
+    numeric_for_over_upvalue_with_call(
+        "local list = {} local function run() for i = 1, #list do local value = list[i] g() end end"
+    ),
+);
+
+test_rule!(
+    convert_ipairs_to_numeric_for,
+    ConvertNumericForToOptimizedForm::default().convert_to_numeric_for(),
+    ipairs_with_value("local list = {} for i, value in ipairs(list) do print(i, value) end")
+        => "local list = {} for i = 1, #list do local value = list[i] print(i, value) end",
+    ipairs_without_value("local list = {} for i in ipairs(list) do print(i) end")
+        => "local list = {} for i = 1, #list do print(i) end",
+    ipairs_reading_other_fields("local list = {} for _, value in ipairs(list) do print(value, list.n) end")
+        => "local list = {} for _ = 1, #list do local value = list[_] print(value, list.n) end",
+    ipairs_over_global_array_without_calls("for _, value in ipairs(list) do total = total + value end")
+        => "for _ = 1, #list do local value = list[_] total = total + value end",
+);
+
+test_rule_without_effects!(
+    ConvertNumericForToOptimizedForm::default().convert_to_numeric_for(),
+    pairs_call("for key, value in pairs(list) do end"),
+    ipairs_with_expression("for i, value in ipairs(get()) do end"),
+    ipairs_with_multiple_arguments("for i, value in ipairs(list, 1) do end"),
+    ipairs_method("for i, value in object:ipairs(list) do end"),
+    ipairs_with_three_identifiers("for i, value, other in ipairs(list) do end"),
+    ipairs_assigning_array("for i, value in ipairs(list) do list[i] = nil end"),
+    ipairs_passing_array("for i, value in ipairs(list) do table.remove(list) end"),
+    ipairs_with_value_named_like_array("for i, list in ipairs(list) do end"),
+    ipairs_shadowed("local ipairs = f for i, value in ipairs(list) do end"),
+    ipairs_over_global_array_with_call("for i, value in ipairs(list) do g() end"),
+    ipairs_over_captured_local_with_call(
+        "local list = {} local function g() list[1] = nil end for i, value in ipairs(list) do g() end"
+    ),
+);
+
+fn process_with_target(code: &str, target: LuaTarget) -> String {
+    let mut block = Parser::default().parse(code).unwrap();
+    let resources = Resources::from_memory();
+    let context = ContextBuilder::new("src/init.lua", &resources, code)
+        .with_target(target)
+        .build();
+
+    ConvertNumericForToOptimizedForm::default()
+        .process(&mut block, &context)
+        .unwrap();
+
+    let mut generator = DenseLuaGenerator::default();
+    generator.write_block(&block);
+    generator.into_string()
+}
+
+#[test]
+fn luau_target_converts_to_ipairs() {
+    insta::assert_snapshot!(
+        process_with_target("for i = 1, #list do local value = list[i] end", LuaTarget::Luau),
+        @"for i,value in ipairs(list)do end"
+    );
+}
+
+#[test]
+fn lua51_target_converts_to_numeric_for() {
+    insta::assert_snapshot!(
+        process_with_target("for i, value in ipairs(list) do end", LuaTarget::Lua51),
+        @"for i=1,#list do local value=list[i]end"
+    );
+}
+
+#[test]
+fn luajit_target_converts_to_numeric_for() {
+    insta::assert_snapshot!(
+        process_with_target("for i, value in ipairs(list) do end", LuaTarget::LuaJIT),
+        @"for i=1,#list do local value=list[i]end"
+    );
+}
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'convert_numeric_for_to_optimized_form',
+        direction: 'ipairs',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'convert_numeric_for_to_optimized_form'").unwrap();
+}
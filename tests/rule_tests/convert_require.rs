@@ -619,6 +619,25 @@ mod luau_to_roblox {
                 "local value = require(script:FindFirstChild('value'):FindFirstChild('default'))",
             );
         }
+
+        #[test]
+        fn convert_alias_module_from_nested_luaurc() {
+            let resources = memory_resources!(        "src/lib/init.lua" => "local value = require('@value')",        "src/lib/value.lua" => "return nil",        ".luaurc" => r#"{ "aliases": { "value": "src/value.lua" } }"#,        "src/lib/.luaurc" => r#"{ "aliases": { "value": "./value.lua" } }"#,        ".darklua.json" => CONVERT_LUAU_TO_ROBLOX_DEFAULT_CONFIG,    );
+            expect_file_process(
+                &resources,
+                "src/lib/init.lua",
+                "local value = require(script:FindFirstChild('value'))",
+            );
+        }
+        #[test]
+        fn convert_alias_module_inherited_from_parent_luaurc() {
+            let resources = memory_resources!(        "src/lib/init.lua" => "local value = require('@value')",        "src/lib/value.lua" => "return nil",        ".luaurc" => r#"{ "aliases": { "value": "src/lib/value.lua" } }"#,        "src/lib/.luaurc" => r#"{ "aliases": { "other": "./other" } }"#,        ".darklua.json" => CONVERT_LUAU_TO_ROBLOX_DEFAULT_CONFIG,    );
+            expect_file_process(
+                &resources,
+                "src/lib/init.lua",
+                "local value = require(script:FindFirstChild('value'))",
+            );
+        }
     }
 }
 
@@ -684,6 +703,132 @@ mod luaurc {
             "local value = require(script:FindFirstChild('value'):FindFirstChild('default'))",
         );
     }
+
+    #[test]
+    fn convert_alias_module_from_nested_luaurc() {
+        let resources = memory_resources!(        "src/lib/init.lua" => "local value = require('@value')",        "src/lib/value.lua" => "return nil",        ".luaurc" => r#"{ "aliases": { "value": "src/value.lua" } }"#,        "src/lib/.luaurc" => r#"{ "aliases": { "value": "./value.lua" } }"#,        ".darklua.json" => CONVERT_PATH_TO_ROBLOX_DEFAULT_CONFIG,    );
+        expect_file_process(
+            &resources,
+            "src/lib/init.lua",
+            "local value = require(script:FindFirstChild('value'))",
+        );
+    }
+    #[test]
+    fn convert_alias_module_inherited_from_parent_luaurc() {
+        let resources = memory_resources!(        "src/lib/init.lua" => "local value = require('@value')",        "src/lib/value.lua" => "return nil",        ".luaurc" => r#"{ "aliases": { "value": "src/lib/value.lua" } }"#,        "src/lib/.luaurc" => r#"{ "aliases": { "other": "./other" } }"#,        ".darklua.json" => CONVERT_PATH_TO_ROBLOX_DEFAULT_CONFIG,    );
+        expect_file_process(
+            &resources,
+            "src/lib/init.lua",
+            "local value = require(script:FindFirstChild('value'))",
+        );
+    }
+}
+
+mod roblox_to_path {
+    use super::*;
+
+    test_rule!(
+        convert_roblox_require_to_path,
+        json5::from_str::<Box<dyn Rule>>(
+            r#"{
+                rule: 'convert_require',
+                current: 'roblox',
+                target: 'path',
+            }"#
+        ).unwrap(),
+        resources = memory_resources!(
+            "src/test/init.lua" => "return nil",
+            "src/test/module.lua" => "return nil",
+            "src/test/luau_module.luau" => "return nil",
+            "src/test/folder/init.lua" => "return nil",
+            "src/test/folder/lib.lua" => "return nil",
+            "src/sub/lib.lua" => "return nil",
+            "src/format.lua" => "return nil",
+        ),
+        test_file_name = "src/test/runner.lua",
+        sibling_module_property("local module = require(script.Parent.module)")
+            => "local module = require('./module')",
+        sibling_module_find_first_child("local module = require(script.Parent:FindFirstChild('module'))")
+            => "local module = require('./module')",
+        sibling_module_wait_for_child("local module = require(script.Parent:WaitForChild('module'))")
+            => "local module = require('./module')",
+        sibling_module_index("local module = require(script.Parent['module'])")
+            => "local module = require('./module')",
+        sibling_luau_module("local module = require(script.Parent.luau_module)")
+            => "local module = require('./luau_module')",
+        sibling_folder_module("local module = require(script.Parent.folder)")
+            => "local module = require('./folder')",
+        module_nested_in_sibling_folder("local module = require(script.Parent.folder:FindFirstChild('lib'))")
+            => "local module = require('./folder/lib')",
+        module_in_parent("local module = require(script.Parent.Parent.format)")
+            => "local module = require('../format')",
+        module_in_parent_sibling_folder("local module = require(script.Parent.Parent.sub.lib)")
+            => "local module = require('../sub/lib')",
+    );
+
+    test_rule_without_effects!(
+        json5::from_str::<Box<dyn Rule>>(
+            r#"{
+                rule: 'convert_require',
+                current: 'roblox',
+                target: 'path',
+            }"#
+        )
+        .unwrap(),
+        missing_module("local module = require(script.Parent.missing)"),
+        datamodel_require_without_sourcemap(
+            "local module = require(game:GetService('ReplicatedStorage').module)"
+        ),
+        require_with_variable("local module = require(moduleScript)"),
+        require_with_string("local module = require('./module')"),
+        require_with_unknown_method("local module = require(script.Parent:GetChildren())"),
+    );
+
+    #[test]
+    fn convert_sibling_module_from_init_module() {
+        let resources = memory_resources!(
+            "src/init.lua" => "local value = require(script.value)",
+            "src/value.lua" => "return nil",
+            ".darklua.json" => "{ rules: [{ rule: 'convert_require', current: 'roblox', target: 'path' }], generator: 'retain_lines' }",
+        );
+        expect_file_process(
+            &resources,
+            "src/init.lua",
+            "local value = require('./value')",
+        );
+    }
+
+    #[test]
+    fn convert_requires_with_sourcemap() {
+        let resources = memory_resources!(
+            "src/init.lua" => "local a = require(script.a)\nlocal d1 = require(script.d.d1)",
+            "src/a.lua" => "return nil",
+            "src/d/init.lua" => "return nil",
+            "src/d/d1.lua" => "return nil",
+            "main.server.lua" => "local Project = require(game:GetService('ReplicatedStorage'):WaitForChild('Project'))",
+            ".darklua.json" => r#"{
+                generator: 'retain_lines',
+                rules: [
+                    {
+                        rule: 'convert_require',
+                        current: { name: 'roblox', rojo_sourcemap: './sourcemap.json' },
+                        target: 'path',
+                    }
+                ]
+            }"#,
+            "sourcemap.json" => include_str!("../test_cases/sourcemap/place-sourcemap.json"),
+        );
+        expect_file_process(
+            &resources,
+            "src/init.lua",
+            "local a = require('./a')\nlocal d1 = require('./d/d1')",
+        );
+        expect_file_process(
+            &resources,
+            "main.server.lua",
+            "local Project = require('./src')",
+        );
+    }
 }
 
 mod sourcemap {
@@ -0,0 +1,71 @@
+use darklua_core::{rules::Rule, Options, Resources};
+
+use super::memory_resources;
+
+test_rule!(
+    convert_require_to_import_map,
+    json5::from_str::<Box<dyn Rule>>("'convert_require_to_import_map'").unwrap(),
+    resources = memory_resources!(
+        "src/value.lua" => "return nil",
+        "src/lib/init.lua" => "return nil",
+        "src/lib/format.luau" => "return nil",
+    ),
+    test_file_name = "src/main.lua",
+    require_sibling_module("local value = require('./value')")
+        => "local value = __MODULES['value']",
+    require_module_folder("local lib = require('./lib')")
+        => "local lib = __MODULES['lib']",
+    require_nested_module("local format = require('./lib/format')")
+        => "local format = __MODULES['lib/format']",
+    require_module_with_extension("local value = require('./value.lua')")
+        => "local value = __MODULES['value']",
+    require_module_field("local format = require('./lib').format")
+        => "local format = __MODULES['lib'].format",
+    require_module_in_call_argument("print(require('./value'))")
+        => "print(__MODULES['value'])",
+);
+
+test_rule!(
+    convert_require_to_import_map_with_map_name,
+    json5::from_str::<Box<dyn Rule>>(
+        "{ rule: 'convert_require_to_import_map', map_name: 'Modules' }"
+    )
+    .unwrap(),
+    resources = memory_resources!(
+        "src/value.lua" => "return nil",
+    ),
+    test_file_name = "src/main.lua",
+    require_sibling_module("local value = require('./value')")
+        => "local value = Modules['value']",
+);
+
+test_rule_without_effects!(
+    json5::from_str::<Box<dyn Rule>>("'convert_require_to_import_map'").unwrap(),
+    require_call_statement("require('./value')"),
+    local_require_function("local require = function() end local value = require('./value')"),
+    require_unknown_module("local value = require('./unknown')"),
+    require_with_variable("local value = require(name)"),
+);
+
+#[test]
+fn write_import_map_file() {
+    let resources = memory_resources!(
+        "src/main.lua" => "local value = require('./value')\nlocal lib = require('./lib')\nreturn value",
+        "src/other.lua" => "return require('./value')",
+        "src/value.lua" => "return nil",
+        "src/lib/init.lua" => "return nil",
+        ".darklua.json" => "{ rules: [{ rule: 'convert_require_to_import_map', map_file: 'modules.json' }] }",
+    );
+
+    darklua_core::process(&resources, Options::new("src").with_output("out"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("modules.json").unwrap(), @r###"
+    {
+      "src/lib": "src/lib/init.lua",
+      "src/value": "src/value.lua"
+    }
+    "###);
+}
@@ -0,0 +1,54 @@
+use darklua_core::rules::{CreateCompoundAssignment, Rule};
+
+test_rule!(
+    create_compound_assignment,
+    CreateCompoundAssignment::default(),
+    addition("a = a + 1") => "a += 1",
+    subtraction("a = a - 1") => "a -= 1",
+    multiplication("a = a * 2") => "a *= 2",
+    division("a = a / 2") => "a /= 2",
+    floor_division("a = a // 2") => "a //= 2",
+    modulo("a = a % 2") => "a %= 2",
+    exponentiation("a = a ^ 2") => "a ^= 2",
+    concatenation("a = a .. 'suffix'") => "a ..= 'suffix'",
+    field("t.n = t.n .. s") => "t.n ..= s",
+    nested_field("self.stats.count = self.stats.count + 1") => "self.stats.count += 1",
+    index_with_string("t['key'] = t['key'] + 1") => "t['key'] += 1",
+    index_with_number("t[1] = t[1] * 2") => "t[1] *= 2",
+    index_with_identifier("t[i] = t[i] + value") => "t[i] += value",
+    right_side_expression("a = a - (b + c)") => "a -= (b + c)",
+    concatenation_chain("a = a .. b .. c") => "a ..= b .. c",
+    in_nested_block("local function f() count = count + 1 end")
+        => "local function f() count += 1 end",
+);
+
+test_rule_without_effects!(
+    CreateCompoundAssignment::default(),
+    different_variable("a = b + 1"),
+    variable_on_right_side("a = 1 + a"),
+    left_associative_chain("a = a + b + c"),
+    comparison("a = a == b"),
+    logical_operator("a = a or b"),
+    multiple_variables("a, b = a + 1, b + 1"),
+    different_field("t.a = t.b + 1"),
+    different_prefix("a.n = b.n + 1"),
+    index_with_call("t[f()] = t[f()] + 1"),
+    prefix_with_call("f().n = f().n + 1"),
+    different_index("t[i] = t[j] + 1"),
+    local_assignment("local a = a + 1"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'create_compound_assignment',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'create_compound_assignment'").unwrap();
+}
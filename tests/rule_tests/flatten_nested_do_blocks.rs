@@ -0,0 +1,50 @@
+use darklua_core::rules::{FlattenNestedDoBlocks, Rule};
+
+test_rule!(
+    flatten_nested_do_blocks,
+    FlattenNestedDoBlocks::default(),
+    empty_do("do end") => "",
+    multiple_empty_do("do end do end") => "",
+    do_without_locals("do print('hello') end") => "print('hello')",
+    do_between_statements("a() do b() end c()") => "a() b() c()",
+    nested_do_without_locals("do do print('hello') end end") => "print('hello')",
+    nested_do_with_locals("do do local a = 1 print(a) end end") => "do local a = 1 print(a) end",
+    do_with_nested_local_function("do f = function() local a end end") => "f = function() local a end",
+    do_in_function("local function f() do g() end end") => "local function f() g() end",
+    do_in_while("while true do do break end end") => "while true do break end",
+    return_in_last_do("do return 1 end") => "return 1",
+    return_in_nested_last_do("a() do do return 1 end end") => "a() return 1",
+    empty_do_in_numeric_for("for i = 1, 10 do do end end") => "for i = 1, 10 do end",
+);
+
+test_rule_without_effects!(
+    FlattenNestedDoBlocks::default(),
+    do_with_local("do local a = 1 end"),
+    do_with_local_function("do local function f() end end"),
+    do_with_type("do type T = number end"),
+    do_with_return_before_statement("do return end print('unreachable')"),
+    do_with_break_before_last_statement("while true do do break end return end"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'flatten_nested_do_blocks',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'flatten_nested_do_blocks'").unwrap();
+}
+
+test_rule_with_tokens!(
+    flatten_nested_do_blocks_with_tokens,
+    FlattenNestedDoBlocks::default(),
+    do_without_locals("do print('hello') end print('end')") => "print('hello') print('end')",
+    do_with_semicolons("a(); do b(); end; c()") => "a(); b(); c()",
+    do_with_semicolon_after_end("a() do b() end; c()") => "a() b() ; c()",
+);
@@ -0,0 +1,135 @@
+use darklua_core::{
+    generator::{DenseLuaGenerator, LuaGenerator},
+    rules::{ContextBuilder, FreezeModuleExports, Rule},
+    LuaTarget, Parser, Resources,
+};
+
+test_rule!(
+    freeze_module_exports,
+    FreezeModuleExports::default(),
+    return_table("return { value = 1 }") => "return table.freeze({ value = 1 })",
+    return_empty_table("return {}") => "return table.freeze({})",
+    return_local_table("local module = { value = 1 } return module")
+        => "local module = { value = 1 } return table.freeze(module)",
+    return_local_table_with_field_reads(
+        "local module = { value = 1, call = function() end } print(module.value, module['value']) module.call() return module"
+    ) => "local module = { value = 1, call = function() end } print(module.value, module['value']) module.call() return table.freeze(module)",
+    return_local_table_with_nested_field_assignment(
+        "local module = { config = {} } module.config.value = true return module"
+    ) => "local module = { config = {} } module.config.value = true return table.freeze(module)",
+    return_redeclared_local_table("local module = nil local module = {} return module")
+        => "local module = nil local module = {} return table.freeze(module)",
+    return_table_with_method_reading_self("return { get = function(self) return self.value end }")
+        => "return table.freeze({ get = function(self) return self.value end })",
+    return_table_with_function_assigning_other_parameter(
+        "return { set = function(self, other) other.value = self.value end }"
+    ) => "return table.freeze({ set = function(self, other) other.value = self.value end })",
+    return_local_table_with_method_assigning_nested_field(
+        "local module = { init = function(self) self.config.value = 1 end } return module"
+    ) => "local module = { init = function(self) self.config.value = 1 end } return table.freeze(module)",
+);
+
+test_rule_without_effects!(
+    FreezeModuleExports::default(),
+    return_nothing("print('hello')"),
+    return_frozen_table("return table.freeze({})"),
+    return_call("return setmetatable({}, {})"),
+    return_multiple_tables("return {}, {}"),
+    return_global("return module"),
+    return_local_number("local module = 1 return module"),
+    return_local_without_value("local module return module"),
+    return_local_function("local function module() end return module"),
+    return_local_from_multiple_assignment("local module, other = {}, {} return module"),
+    return_local_table_with_field_assignment("local module = {} module.value = 1 return module"),
+    return_local_table_with_index_assignment("local module = {} module['value'] = 1 return module"),
+    return_local_table_with_function_statement(
+        "local module = {} function module.call() end return module"
+    ),
+    return_local_table_with_method_statement(
+        "local module = {} function module:call() end return module"
+    ),
+    return_local_table_with_method_call("local module = {} module:init() return module"),
+    return_local_table_passed_to_function(
+        "local module = {} setmetatable(module, {}) return module"
+    ),
+    return_local_table_reassigned("local module = {} module = {} return module"),
+    return_local_table_assigned_in_function(
+        "local module = {} local function init() module.value = 1 end return module"
+    ),
+    return_local_table_with_compound_assignment(
+        "local module = { count = 0 } module.count += 1 return module"
+    ),
+    return_table_with_shadowed_table_library("local table = {} return {}"),
+    return_table_with_method_assigning_self("return { init = function(self) self.value = 1 end }"),
+    return_table_with_method_assigning_self_index(
+        "return { init = function(self, key) self[key] = true end }"
+    ),
+    return_table_with_method_compound_assigning_self(
+        "return { count = 0, increment = function(self) self.count += 1 end }"
+    ),
+    return_table_with_method_declaring_function_on_self(
+        "return { init = function(self) function self.call() end end }"
+    ),
+    return_local_table_with_method_assigning_self(
+        "local module = { init = function(self) self.value = 1 end } return module"
+    ),
+    return_local_table_with_nested_method_assigning_self(
+        "local module = { init = function(self) if not self.value then self.value = 1 end end } return module"
+    ),
+);
+
+test_rule!(
+    freeze_module_exports_with_exclude,
+    FreezeModuleExports::default().with_exclude("^src/mutable/"),
+    test_file_name = "src/init.lua",
+    included_file("return {}") => "return table.freeze({})",
+);
+
+test_rule!(
+    freeze_module_exports_with_excluded_file,
+    FreezeModuleExports::default().with_exclude("^src/mutable/"),
+    test_file_name = "src/mutable/init.lua",
+    excluded_file("return {}") => "return {}",
+);
+
+fn process_with_target(code: &str, target: LuaTarget) -> String {
+    let mut block = Parser::default().parse(code).unwrap();
+    let resources = Resources::from_memory();
+    let context = ContextBuilder::new("src/init.lua", &resources, code)
+        .with_target(target)
+        .build();
+
+    FreezeModuleExports::default()
+        .process(&mut block, &context)
+        .unwrap();
+
+    let mut generator = DenseLuaGenerator::default();
+    generator.write_block(&block);
+    generator.into_string()
+}
+
+#[test]
+fn lua51_target_keeps_returned_table() {
+    insta::assert_snapshot!(process_with_target("return {}", LuaTarget::Lua51), @"return{}");
+}
+
+#[test]
+fn luau_target_freezes_returned_table() {
+    insta::assert_snapshot!(process_with_target("return {}", LuaTarget::Luau), @"return table.freeze({})");
+}
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'freeze_module_exports',
+        exclude: ['^src/mutable/'],
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'freeze_module_exports'").unwrap();
+}
@@ -7,13 +7,23 @@ test_rule!(
     two_locals("local foo = 1 local bar = 2") => "local foo, bar = 1, 2",
     three_locals("local foo = 1 local bar = 2 local baz = 3") => "local foo, bar, baz = 1, 2, 3",
     local_with_no_value_and_local_with_value("local a local b = 7") => "local a, b = nil, 7",
-    local_with_no_values_are_set_to_nil("local a local b = true local c") => "local a, b, c = nil, true, nil"
+    local_with_no_values_are_set_to_nil("local a local b = true local c") => "local a, b, c = nil, true, nil",
+    typed_locals("local foo: number = 1 local bar: string = 'bar'")
+        => "local foo: number, bar: string = 1, 'bar'",
+    local_using_variable_in_function("local foo = 1 local bar = function() return baz end")
+        => "local foo, bar = 1, function() return baz end",
+    stop_group_at_local_using_earlier_variable("local foo = 1 local bar = 2 local baz = foo")
+        => "local foo, bar = 1, 2 local baz = foo",
 );
 
 test_rule_without_effects!(
     GroupLocalAssignment::default(),
     two_local_using_the_other("local foo = 1 local bar = foo"),
-    multiple_return_values("local a, b = call() local c = 0")
+    multiple_return_values("local a, b = call() local c = 0"),
+    local_using_previous_variable_in_function(
+        "local foo = 1 local bar = function() return foo end"
+    ),
+    local_using_previous_variable_in_type("local foo = 1 local bar: typeof(foo) = 2"),
 );
 
 #[test]
@@ -0,0 +1,53 @@
+use darklua_core::rules::{HoistRepeatedGlobalsToLocals, Rule};
+
+test_rule!(
+    hoist_repeated_globals_to_locals,
+    HoistRepeatedGlobalsToLocals::default(),
+    library_field_in_function(
+        "local function round(a, b, c) return math.floor(a), math.floor(b), math.floor(c) end"
+    ) => "local function round(a, b, c) local math_floor = math.floor return math_floor(a), math_floor(b), math_floor(c) end",
+    library_field_in_root("print(math.floor(a)) print(math.floor(b)) print(math.floor(c))")
+        => "local print = print local math_floor = math.floor print(math_floor(a)) print(math_floor(b)) print(math_floor(c))",
+    global_in_loop("for i = 1, 10 do print(i) print(i) print(i) end")
+        => "local print = print for i = 1, 10 do print(i) print(i) print(i) end",
+    library_field_as_value("local a, b, c = math.pi, math.pi, math.pi")
+        => "local math_pi = math.pi local a, b, c = math_pi, math_pi, math_pi",
+    not_enough_occurrences("return math.floor(a), math.floor(b)")
+        => "return math.floor(a), math.floor(b)",
+    occurrences_are_counted_per_function(
+        "local function f() return math.floor(a) end local function g() return math.floor(b), math.floor(c) end"
+    ) => "local function f() return math.floor(a) end local function g() return math.floor(b), math.floor(c) end",
+    nested_function_is_counted_separately(
+        "local function f() math.abs(a) math.abs(b) return function() return math.abs(c) end end"
+    ) => "local function f() math.abs(a) math.abs(b) return function() return math.abs(c) end end",
+    local_library_is_not_hoisted("local math = {} return math.floor(a), math.floor(b), math.floor(c)")
+        => "local math = {} return math.floor(a), math.floor(b), math.floor(c)",
+    parameter_shadowing_global_is_not_hoisted("local function f(print) print(a) print(b) print(c) end")
+        => "local function f(print) print(a) print(b) print(c) end",
+    assigned_global_is_not_hoisted("value = 1 return value + value + value")
+        => "value = 1 return value + value + value",
+    global_with_assigned_field_is_not_hoisted("config.x = 1 return config.a, config.b, config.c")
+        => "config.x = 1 return config.a, config.b, config.c",
+    library_with_assigned_field_is_not_hoisted("function math.sign(n) return n end return math.floor(a), math.floor(b), math.floor(c)")
+        => "function math.sign(n) return n end return math.floor(a), math.floor(b), math.floor(c)",
+    library_itself_is_not_hoisted("return math, math, math") => "return math, math, math",
+    unknown_global_is_hoisted_without_its_fields("return game.Workspace, game.Workspace, game.Workspace")
+        => "local game = game return game.Workspace, game.Workspace, game.Workspace",
+    avoid_identifier_conflict("local math_floor = 1 return math.floor(a), math.floor(b), math.floor(c), math_floor")
+        => "local math_floor1 = math.floor local math_floor = 1 return math_floor1(a), math_floor1(b), math_floor1(c), math_floor",
+    method_call_on_library_field("return string.format:upper(), string.format:lower(), string.format:len()")
+        => "local string_format = string.format return string_format:upper(), string_format:lower(), string_format:len()",
+);
+
+test_rule!(
+    hoist_repeated_globals_to_locals_with_minimum_occurrences,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'hoist_repeated_globals_to_locals',
+        minimum_occurrences: 2,
+    }"#,
+    ).unwrap(),
+    hoist_with_two_occurrences("return math.floor(a), math.floor(b)")
+        => "local math_floor = math.floor return math_floor(a), math_floor(b)",
+    keep_single_occurrence("return math.floor(a)") => "return math.floor(a)",
+);
@@ -0,0 +1,135 @@
+use darklua_core::rules::{ContextBuilder, Rule};
+use darklua_core::{Parser, Resources};
+
+use super::memory_resources;
+
+test_rule!(
+    inject_build_data_using_file,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inject_build_data',
+        identifier: 'BUILD',
+        file: 'build.json',
+    }"#,
+    )
+    .unwrap(),
+    resources = memory_resources!(
+        "src/build.json" => "{ version: '1.2.0', debug: false }",
+    ),
+    inject_file_value("return BUILD") => "return { debug = false, version = '1.2.0' }",
+);
+
+test_rule!(
+    inject_build_data_using_file_field,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inject_build_data',
+        identifier: 'BUILD_VERSION',
+        file: 'build.json',
+        field: 'package.versions.1',
+    }"#,
+    )
+    .unwrap(),
+    resources = memory_resources!(
+        "src/build.json" => "{ package: { versions: ['1.0.0', '1.2.0'] } }",
+    ),
+    inject_file_field("return _G.BUILD_VERSION") => "return '1.2.0'",
+);
+
+test_rule!(
+    inject_build_data_using_missing_file_with_default_value,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inject_build_data',
+        identifier: 'BUILD_VERSION',
+        file: 'build.json',
+        default_value: 'dev',
+    }"#,
+    )
+    .unwrap(),
+    inject_default_value("return BUILD_VERSION") => "return 'dev'",
+);
+
+test_rule!(
+    inject_build_data_using_undefined_env_with_default_value,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inject_build_data',
+        identifier: 'BUILD_VERSION',
+        env: 'DARKLUA_INJECT_BUILD_DATA_UNDEFINED_VARIABLE',
+        default_value: 'dev',
+    }"#,
+    )
+    .unwrap(),
+    inject_default_value("return BUILD_VERSION") => "return 'dev'",
+);
+
+fn process_rule(rule: &str, resources: &Resources, file_name: &str) -> Result<String, String> {
+    use darklua_core::generator::{DenseLuaGenerator, LuaGenerator};
+
+    let rule = json5::from_str::<Box<dyn Rule>>(rule).unwrap();
+    let code = "return BUILD";
+    let mut block = Parser::default().parse(code).unwrap();
+    let context = ContextBuilder::new(file_name, resources, code).build();
+
+    rule.process(&mut block, &context)?;
+
+    let mut generator = DenseLuaGenerator::default();
+    generator.write_block(&block);
+    Ok(generator.into_string())
+}
+
+#[test]
+fn inject_build_data_using_git_commit() {
+    let expected = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| format!("return'{}'", String::from_utf8_lossy(&output.stdout).trim()));
+
+    let result = process_rule(
+        "{ rule: 'inject_build_data', identifier: 'BUILD', git: 'commit' }",
+        &Resources::from_file_system(),
+        "src/test.lua",
+    );
+
+    if let Some(expected) = expected {
+        pretty_assertions::assert_eq!(result, Ok(expected));
+    } else {
+        assert!(result.is_err());
+    }
+}
+
+#[test]
+fn inject_build_data_using_missing_file_errors() {
+    let resources = memory_resources!("src/build.json" => "{ version: '1.0.0' }",);
+
+    pretty_assertions::assert_eq!(
+        process_rule(
+            "{ rule: 'inject_build_data', identifier: 'BUILD', file: 'build.json' }",
+            &resources,
+            "src/other/test.lua",
+        ),
+        Err(
+            "unable to read the value of `BUILD`: unable to find `src/other/build.json`".to_owned()
+        )
+    );
+}
+
+#[test]
+fn inject_build_data_using_missing_file_field_errors() {
+    let resources = memory_resources!("src/build.json" => "{ version: '1.0.0' }",);
+
+    pretty_assertions::assert_eq!(
+        process_rule(
+            "{ rule: 'inject_build_data', identifier: 'BUILD', file: 'build.json', field: 'commit' }",
+            &resources,
+            "src/test.lua",
+        ),
+        Err(
+            "unable to read the value of `BUILD`: unable to find field `commit` in `src/build.json`"
+                .to_owned()
+        )
+    );
+}
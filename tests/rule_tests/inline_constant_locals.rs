@@ -0,0 +1,75 @@
+use darklua_core::rules::{InlineConstantLocals, Rule};
+
+test_rule!(
+    inline_constant_locals,
+    InlineConstantLocals::default(),
+    inline_number("local limit = 10 return limit") => "return 10",
+    inline_negative_number("local offset = -1 return offset") => "return -1",
+    inline_string("local name = 'darklua' print(name)") => "print('darklua')",
+    inline_true("local enabled = true if enabled then print() end") => "if true then print() end",
+    inline_false("local enabled = false return enabled") => "return false",
+    inline_nil("local value = nil return value") => "return nil",
+    inline_in_nested_function("local limit = 10 local function f() return limit end")
+        => "local function f() return 10 end",
+    inline_in_nested_block("local limit = 10 do local value = limit return value end")
+        => "do local value = 10 return value end",
+    inline_string_method_call("local name = 'darklua' return name:upper()")
+        => "return ('darklua'):upper()",
+    inline_string_field("local name = 'darklua' return name.len") => "return ('darklua').len",
+    inline_in_binary_expression("local offset = -1 return offset ^ 2") => "return (-1) ^ 2",
+    inline_unused_constant("local limit = 10") => "",
+    inline_in_own_shadowing_declaration("local value = 1 local value = value + 1 return value")
+        => "local value = 1 + 1 return value",
+    inline_in_interpolated_string("local name = 'darklua' return `hello {name}`")
+        => "return `hello {'darklua'}`",
+    inline_long_string_used_once("local text = 'this string is longer than thirty-two bytes' print(text)")
+        => "print('this string is longer than thirty-two bytes')",
+    keep_shadowing_parameter("local value = 1 local function f(value) return value end return f, value")
+        => "local function f(value) return value end return f, 1",
+);
+
+test_rule_without_effects!(
+    InlineConstantLocals::default(),
+    reassigned_variable("local count = 0 count = count + 1 return count"),
+    compound_assigned_variable("local count = 0 count += 1 return count"),
+    reassigned_in_function("local count = 0 local function increment() count = 1 end return count"),
+    table_value("local values = {} return values"),
+    identifier_value("local value = other return value"),
+    call_value("local value = call() return value"),
+    multiple_variables("local a, b = 1, 2 return a, b"),
+    uninitialized_variable("local value return value"),
+    function_name_prefix("local module = nil function module.call() end"),
+    long_string_used_many_times(
+        "local text = 'this string is longer than thirty-two bytes' print(text) print(text)"
+    ),
+);
+
+test_rule!(
+    inline_constant_locals_with_max_string_length,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inline_constant_locals',
+        max_string_length: 3,
+    }"#,
+    ).unwrap(),
+    inline_short_string_many_times("local name = 'abc' print(name) print(name)")
+        => "print('abc') print('abc')",
+    keep_long_string_used_many_times("local name = 'abcd' print(name) print(name)")
+        => "local name = 'abcd' print(name) print(name)",
+    inline_long_string_used_once("local name = 'abcd' print(name)") => "print('abcd')",
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'inline_constant_locals',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'inline_constant_locals'").unwrap();
+}
@@ -0,0 +1,64 @@
+use darklua_core::rules::{LowerMathOperations, Rule};
+
+test_rule!(
+    lower_math_operations,
+    LowerMathOperations::default(),
+    square_root("return x ^ 0.5") => "return math.sqrt(x)",
+    square_root_of_expression("return (a + b) ^ 0.5") => "return math.sqrt(a + b)",
+    square_root_of_call("return getValue() ^ 0.5") => "return math.sqrt(getValue())",
+    square_root_with_parenthesized_exponent("return x ^ (0.5)") => "return math.sqrt(x)",
+    square("return x ^ 2") => "return x * x",
+    square_in_expression("return x ^ 2 + y ^ 2") => "return x * x + y * y",
+    negative_square("return -x ^ 2") => "return -(x * x)",
+    square_with_hex_exponent("return x ^ 0x2") => "return x * x",
+    division_by_two("return x / 2") => "return x * 0.5",
+    division_by_four("return x / 4") => "return x * 0.25",
+    division_by_negative_power_of_two("return x / -8") => "return x * -0.125",
+    division_by_half("return x / 0.5") => "return x * 2",
+    square_root_of_division("return (x / 2) ^ 0.5") => "return math.sqrt(x * 0.5)",
+    math_shadowed_in_other_scope("do local math = {} end return x ^ 0.5")
+        => "do local math = {} end return math.sqrt(x)",
+);
+
+test_rule_without_effects!(
+    LowerMathOperations::default(),
+    other_exponent("return x ^ 3"),
+    square_of_call("return getValue() ^ 2"),
+    square_of_field("return object.value ^ 2"),
+    square_root_with_shadowed_math("local math = {} return x ^ 0.5"),
+    division_by_three("return x / 3"),
+    division_by_one("return x / 1"),
+    division_by_zero("return x / 0"),
+    division_by_variable("return x / y"),
+    floor_division_by_two("return x // 2"),
+);
+
+test_rule_without_effects!(
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'lower_math_operations',
+        square_root: false,
+        square: false,
+        division: false,
+    }"#,
+    )
+    .unwrap(),
+    disabled_square_root("return x ^ 0.5"),
+    disabled_square("return x ^ 2"),
+    disabled_division("return x / 2"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'lower_math_operations',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'lower_math_operations'").unwrap();
+}
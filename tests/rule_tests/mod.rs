@@ -426,6 +426,118 @@ macro_rules! test_rule {
     };
 }
 
+macro_rules! test_rule_with_parser_and_generator {
+    (
+        $rule:expr,
+        $parser:expr,
+        $generator:expr,
+        $name:ident,
+        $input:literal,
+        $output:literal
+    ) => {
+        #[test]
+        fn $name() {
+            use darklua_core::generator::LuaGenerator;
+            use darklua_core::rules::Rule;
+
+            let parse = |code: &str| {
+                darklua_core::Parser::default()
+                    .with_bitwise_operators()
+                    .parse(code)
+                    .unwrap_or_else(|error| {
+                        panic!("could not parse content: {:?}\ncontent:\n{}", error, code)
+                    })
+            };
+
+            let expect_block = parse($output);
+
+            let mut block = $parser.parse($input).unwrap_or_else(|error| {
+                panic!("could not parse content: {:?}\ncontent:\n{}", error, $input)
+            });
+
+            let resources = darklua_core::Resources::from_memory();
+            let context =
+                darklua_core::rules::ContextBuilder::new("src/test.lua", &resources, $input)
+                    .build();
+
+            $rule
+                .process(&mut block, &context)
+                .expect("rule should succeed");
+
+            let create_generator = $generator;
+            let mut generator = create_generator($input);
+            generator.write_block(&block);
+            let lua_code = generator.into_string();
+
+            pretty_assertions::assert_eq!(
+                parse(&lua_code),
+                expect_block,
+                "\nexpected code:\n{}\nbut received:\n{}",
+                $output,
+                lua_code
+            );
+        }
+    };
+}
+
+macro_rules! test_rule_with_bitwise_operators {
+    ($rule_name:ident, $rule:expr, $($name:ident ($input:literal) => $output:literal),* $(,)?) => {
+        paste::paste! {
+
+        mod [<$rule_name _with_readable_generator>] {
+            use super::*;
+
+        $(
+            test_rule_with_parser_and_generator!(
+                $rule,
+                darklua_core::Parser::default().with_bitwise_operators(),
+                |_| darklua_core::generator::ReadableLuaGenerator::default(),
+                $name,
+                $input,
+                $output
+            );
+        )*
+
+        }
+
+        mod [<$rule_name _with_dense_generator>] {
+            use super::*;
+
+        $(
+            test_rule_with_parser_and_generator!(
+                $rule,
+                darklua_core::Parser::default().with_bitwise_operators(),
+                |_| darklua_core::generator::DenseLuaGenerator::default(),
+                $name,
+                $input,
+                $output
+            );
+        )*
+
+        }
+
+        mod [<$rule_name _with_token_based_generator>] {
+            use super::*;
+
+        $(
+            test_rule_with_parser_and_generator!(
+                $rule,
+                darklua_core::Parser::default()
+                    .with_bitwise_operators()
+                    .preserve_tokens(),
+                |input| darklua_core::generator::TokenBasedLuaGenerator::new(input),
+                $name,
+                $input,
+                $output
+            );
+        )*
+
+        }
+    }
+
+    };
+}
+
 macro_rules! test_rule_without_effects {
     ($rule:expr, $($name:ident ($input:literal)),* $(,)?) => {
         $(
@@ -534,30 +646,53 @@ macro_rules! test_rule_snapshot {
 
 mod append_text_comment;
 mod compute_expression;
+mod convert_bit32_to_operators;
+mod convert_bitwise_operators_to_bit32;
 mod convert_index_to_field;
 mod convert_luau_number;
+mod convert_numeric_for_to_optimized_form;
 mod convert_require;
+mod convert_require_to_import_map;
 mod convert_square_root_call;
+mod create_compound_assignment;
 mod filter_early_return;
+mod flatten_nested_do_blocks;
+mod freeze_module_exports;
 mod global_function_to_assign;
 mod group_local_assignment;
+mod hoist_repeated_globals_to_locals;
+mod inject_build_data;
 mod inject_value;
+mod inline_constant_locals;
+mod lower_math_operations;
 mod no_local_function;
 mod remove_assertions;
 mod remove_call_parens;
 mod remove_comments;
 mod remove_compound_assignment;
 mod remove_continue;
+mod remove_debug_calls;
 mod remove_debug_profiling;
 mod remove_empty_do;
 mod remove_floor_division;
+mod remove_generalized_iteration;
 mod remove_if_expression;
 mod remove_interpolated_string;
 mod remove_method_call;
 mod remove_method_definition;
 mod remove_nil_declaration;
+mod remove_redundant_parentheses;
+mod remove_redundant_return;
+mod remove_spread_table_packing;
+mod remove_type_function_calls;
 mod remove_types;
+mod remove_unreachable_code;
+mod remove_unused_function_parameters;
 mod remove_unused_if_branch;
 mod remove_unused_variable;
 mod remove_unused_while;
+mod rename_globals;
+mod rename_project_symbols;
 mod rename_variables;
+mod sort_requires;
+mod string_obfuscation;
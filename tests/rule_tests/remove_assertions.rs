@@ -32,6 +32,58 @@ test_rule!(
     remove_variable_condition_with_function_call_message("assert(condition, formatter(condition))") => "do end",
 );
 
+test_rule!(
+    remove_assertions_with_functions,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_assertions',
+        functions: ['expect', 'invariant'],
+    }"#,
+    )
+    .unwrap(),
+    remove_assert_call("assert(condition)") => "do end",
+    remove_additional_function_call("expect(condition)") => "do end",
+    remove_other_additional_function_call("invariant(condition, 'condition is false')") => "do end",
+    remove_additional_function_call_preserve_side_effects("invariant(validate(value))") => "validate(value)",
+    as_expression_remove_additional_function_call("return expect(condition)") => "return condition",
+);
+
+test_rule_without_effects!(
+    RemoveAssertions::default().with_function("expect"),
+    additional_function_used("local function expect() end expect(condition)"),
+    additional_function_field_call("test.expect(condition)"),
+);
+
+test_rule!(
+    remove_assertions_keep_condition_only,
+    RemoveAssertions::default().keep_condition_only(),
+    remove_variable_condition("assert(condition, 'message')") => "do end",
+    as_expression_keep_condition("return assert(condition, 'message')") => "return condition",
+    as_expression_keep_condition_in_table("return { assert(condition, 'message') }") => "return { condition }",
+    as_expression_keep_truncated_call_condition("local value = assert(find(key), 'not found')")
+        => "local value = (find(key))",
+    as_expression_keep_truncated_variadic_condition("return assert(..., 'missing argument')")
+        => "return (...)",
+    as_expression_with_message_side_effects("return assert(condition, formatter(condition))")
+        => "return select(1, condition, formatter(condition))",
+);
+
+test_rule!(
+    remove_assertions_keep_condition_only_without_side_effects,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_assertions',
+        keep_condition_only: true,
+        preserve_arguments_side_effects: false,
+    }"#,
+    )
+    .unwrap(),
+    as_expression_with_message_side_effects("return assert(condition, formatter(condition))")
+        => "return condition",
+    as_expression_when_select_is_used("local select = true\nreturn assert(condition, formatter(condition))")
+        => "local select = true\nreturn condition",
+);
+
 test_rule_without_effects!(
     RemoveAssertions::default(),
     assert_function_used("local function assert() end assert('label')"),
@@ -56,6 +56,43 @@ test_remove_comments_rule!(
     keep_one_comment_before_empty_do("--!native\n-- comment\ndo end") => "--!native\n\ndo end",
 );
 
+test_remove_comments_rule!(
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'remove_comments',
+        keep_banner: true,
+    }"#,
+    )
+    .unwrap(),
+    keep_banner_before_statement("-- license\n-- MIT\nlocal a = 1 -- comment\n") => "-- license\n-- MIT\nlocal a = 1 \n",
+    keep_banner_in_empty_file("--[[ license ]]\n") => "--[[ license ]]\n",
+    keep_banner_before_return("-- license\nreturn -- value\nnil") => "-- license\nreturn \nnil",
+    remove_comments_after_banner("-- license\nlocal a = 1\n-- comment\nreturn a") => "-- license\nlocal a = 1\n\nreturn a",
+);
+
+test_remove_comments_rule!(
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'remove_comments',
+        preserve_markers: ['@preserve'],
+    }"#,
+    )
+    .unwrap(),
+    keep_comment_with_marker("-- @preserve keep\n-- comment\ndo end") => "-- @preserve keep\n\ndo end",
+    keep_comment_with_marker_in_expression("return 1 + --[[ @preserve ]] 2 -- remove") => "return 1 + --[[ @preserve ]] 2 ",
+);
+
+test_remove_comments_rule!(
+    json5::from_str::<Box<dyn Rule>>(r#"{
+        rule: 'remove_comments',
+        keep_banner: true,
+        preserve_markers: ['@preserve'],
+        except: ['^--!'],
+    }"#,
+    )
+    .unwrap(),
+    keep_banner_directive_and_marker("-- license\nlocal a = 1\n--!native\n-- @preserve\n-- comment\nreturn a")
+        => "-- license\nlocal a = 1\n--!native\n-- @preserve\n\nreturn a",
+);
+
 #[test]
 fn deserialize_from_object_notation() {
     json5::from_str::<Box<dyn Rule>>(
@@ -70,3 +107,18 @@ fn deserialize_from_object_notation() {
 fn deserialize_from_string() {
     json5::from_str::<Box<dyn Rule>>("'remove_comments'").unwrap();
 }
+
+#[test]
+fn deserialize_with_invalid_keep_banner() {
+    let result = json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_comments',
+        keep_banner: 'yes',
+    }"#,
+    );
+
+    pretty_assertions::assert_eq!(
+        result.unwrap_err().to_string(),
+        "boolean value expected for field 'keep_banner' at line 1 column 1"
+    );
+}
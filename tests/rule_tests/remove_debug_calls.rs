@@ -0,0 +1,92 @@
+use darklua_core::rules::{RemoveDebugCalls, Rule};
+
+test_rule!(
+    remove_debug_calls,
+    RemoveDebugCalls::default(),
+    remove_print("print('hello')") => "do end",
+    remove_print_without_arguments("print()") => "do end",
+    remove_warn("warn('something is wrong')") => "do end",
+    remove_debug_traceback("debug.traceback()") => "do end",
+    remove_print_with_string_argument("print 'hello'") => "do end",
+    remove_print_with_table_argument("print { value = 1 }") => "do end",
+    remove_print_in_function("local function f() print('called') return true end")
+        => "local function f() do end return true end",
+    remove_print_with_call_argument("print('value', getValue())") => "getValue()",
+    remove_print_with_multiple_call_arguments("print(getValue(), getOther())")
+        => "do getValue() getOther() end",
+);
+
+test_rule!(
+    remove_debug_calls_with_keep_strategy,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_debug_calls',
+        strategy: 'keep',
+    }"#,
+    )
+    .unwrap(),
+    remove_print("print('hello')") => "do end",
+    remove_print_with_variable_argument("print('value', value)") => "do end",
+);
+
+test_rule!(
+    remove_debug_calls_with_functions,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_debug_calls',
+        functions: ['logger.debug', 'log'],
+    }"#,
+    )
+    .unwrap(),
+    remove_field_function("logger.debug('hello')") => "do end",
+    remove_global_function("log('hello')") => "do end",
+);
+
+test_rule_without_effects!(
+    RemoveDebugCalls::default(),
+    print_used_as_expression("local result = print('hello')"),
+    print_returned("return print('hello')"),
+    shadowed_print("local print = function() end print('hello')"),
+    shadowed_debug_library("local debug = {} debug.traceback()"),
+    print_method_call("print:call('hello')"),
+    other_debug_function("debug.info(1, 's')"),
+    nested_field_of_print("print.value('hello')"),
+);
+
+test_rule_without_effects!(
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_debug_calls',
+        strategy: 'keep',
+    }"#,
+    )
+    .unwrap(),
+    keep_print_with_call_argument("print('value', getValue())"),
+);
+
+test_rule_without_effects!(
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_debug_calls',
+        functions: ['logger.debug'],
+    }"#,
+    )
+    .unwrap(),
+    keep_print_when_not_listed("print('hello')"),
+    keep_logger_field_with_other_name("logger.info('hello')"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_debug_calls',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_debug_calls'").unwrap();
+}
@@ -26,6 +26,13 @@ test_rule!(
         => "do local __DARKLUA_VAR = object[call()] __DARKLUA_VAR[key] = math.floor(__DARKLUA_VAR[key] / 1) end",
     floor_division_with_index_with_side_effects_in_prefix_and_index("object[call()][getKey()] //= 1")
         => "do local __DARKLUA_VAR, __DARKLUA_VAR0 = object[call()], getKey() __DARKLUA_VAR[__DARKLUA_VAR0] = math.floor(__DARKLUA_VAR[__DARKLUA_VAR0] / 1) end",
+    nested_floor_division("return a // b // c") => "return math.floor(math.floor(a / b) / c)",
+    floor_division_when_math_is_shadowed("local math = {} return a // b")
+        => "local __DARKLUA_MATH_FLOOR = math.floor local math = {} return __DARKLUA_MATH_FLOOR(a / b)",
+    compound_floor_division_when_math_is_shadowed("local math = {} value //= 2")
+        => "local __DARKLUA_MATH_FLOOR = math.floor local math = {} value = __DARKLUA_MATH_FLOOR(value / 2)",
+    floor_division_when_math_is_shadowed_in_function("local function f(math) return a // b end return 1 // 2")
+        => "local __DARKLUA_MATH_FLOOR = math.floor local function f(math) return __DARKLUA_MATH_FLOOR(a / b) end return math.floor(1 / 2)",
 );
 
 #[test]
@@ -0,0 +1,50 @@
+use darklua_core::rules::{RemoveGeneralizedIteration, Rule};
+
+test_rule!(
+    remove_generalized_iteration,
+    RemoveGeneralizedIteration::default(),
+    iterate_identifier("for k, v in t do end") => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for k, v in __DARKLUA_ITER(t) do end",
+    iterate_field("for _, v in object.list do print(v) end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for _, v in __DARKLUA_ITER(object.list) do print(v) end",
+    iterate_table("for _, v in { 1, 2 } do end") => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for _, v in __DARKLUA_ITER({ 1, 2 }) do end",
+    iterate_in_nested_function("local function f(t) for k in t do end end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end local function f(t) for k in __DARKLUA_ITER(t) do end end",
+    iterate_twice("for k in a do end for k in b do end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for k in __DARKLUA_ITER(a) do end for k in __DARKLUA_ITER(b) do end",
+    iterate_with_pairs("for k, v in pairs(t) do end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for k, v in __DARKLUA_ITER(pairs(t)) do end",
+    iterate_with_ipairs("for i, v in ipairs(t) do end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for i, v in __DARKLUA_ITER(ipairs(t)) do end",
+    iterate_with_method_call("for _, child in instance:GetChildren() do print(child) end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for _, child in __DARKLUA_ITER(instance:GetChildren()) do print(child) end",
+    iterate_with_iterator_method_call("for word in text:gmatch('%a+') do end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for word in __DARKLUA_ITER(text:gmatch('%a+')) do end",
+    iterate_with_function_call("for _, value in getList() do end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for _, value in __DARKLUA_ITER(getList()) do end",
+    iterate_variable_arguments("local function f(...) for k, v in ... do end end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end local function f(...) for k, v in __DARKLUA_ITER(...) do end end",
+    iterate_nested_loops("for _, row in rows do for _, cell in row do end end")
+        => "local function __DARKLUA_ITER(value, ...) if type(value) == 'table' then local metatable = getmetatable(value) if type(metatable) == 'table' and metatable.__iter then return metatable.__iter(value) end return next, value end return value, ... end for _, row in __DARKLUA_ITER(rows) do for _, cell in __DARKLUA_ITER(row) do end end",
+);
+
+test_rule_without_effects!(
+    RemoveGeneralizedIteration::default(),
+    iterate_with_explicit_state("for k, v in next, t do end"),
+    iterate_with_explicit_state_and_control("for k, v in next, t, nil do end"),
+    numeric_for("for i = 1, 10 do end"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_generalized_iteration',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_generalized_iteration'").unwrap();
+}
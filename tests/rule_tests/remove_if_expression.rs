@@ -23,6 +23,27 @@ test_rule!(
         => "local function f(...: string) return (condition(...) and {(...)} or { ((condition2(...) and {(...)} or { (transform(...)) })[1]) }) [1] end"
 );
 
+test_rule!(
+    remove_if_expression_with_fast_strategy,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_if_expression',
+        strategy: 'fast',
+    }"#,
+    )
+    .unwrap(),
+    if_with_truthy_result("local a = if condition() then 1 else 2")
+        => "local a = condition() and 1 or 2",
+    if_with_unknown_result_else_unknown("local a = if condition() then update() else default()")
+        => "local a = condition() and update() or default()",
+    if_with_nil_result_else_false("local a = if condition() then nil else false")
+        => "local a = condition() and nil or false",
+    assign_if_expression_with_elseif("local a = if a then b elseif c then d else e")
+        => "local a = a and b or (c and d or e)",
+    if_expression_with_varargs("local function f(...: string) return if condition(...) then ... else transform(...) end")
+        => "local function f(...: string) return condition(...) and ... or transform(...) end"
+);
+
 #[test]
 fn deserialize_from_object_notation() {
     json5::from_str::<Box<dyn Rule>>(
@@ -8,7 +8,10 @@ test_rule!(
     name_with_field_and_method("function foo.bar:baz() end") => "function foo.bar.baz(self) end",
     with_arguments("function foo:bar(a, b, c) end") => "function foo.bar(self, a, b, c) end",
     variadic_function("function foo:bar(...) end") => "function foo.bar(self, ...) end",
-    variadic_with_arguments("function foo:bar(a, b, c, ...) end") => "function foo.bar(self, a, b, c, ...) end"
+    variadic_with_arguments("function foo:bar(a, b, c, ...) end") => "function foo.bar(self, a, b, c, ...) end",
+    with_typed_arguments("function foo:bar(a: number): string end") => "function foo.bar(self, a: number): string end",
+    with_generic_arguments("function foo:bar<T>(a: T): T end") => "function foo.bar<T>(self, a: T): T end",
+    nested_method("function foo:bar() function self:baz() end end") => "function foo.bar(self) function self.baz(self) end end"
 );
 
 #[test]
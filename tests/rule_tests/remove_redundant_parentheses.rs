@@ -0,0 +1,86 @@
+use darklua_core::rules::{RemoveRedundantParentheses, Rule};
+
+test_rule!(
+    remove_redundant_parentheses,
+    RemoveRedundantParentheses::default(),
+    return_identifier("return (a)") => "return a",
+    return_nested_parentheses("return ((a))") => "return a",
+    return_binary("return (a + b)") => "return a + b",
+    return_call_in_first_position("return (f()), b") => "return f(), b",
+    return_nested_call_parentheses("return ((f()))") => "return (f())",
+    local_assign_binary("local value = (a and b)") => "local value = a and b",
+    local_assign_values("local a, b = (1), ('b')") => "local a, b = 1, 'b'",
+    assign_unary("value = (not condition)") => "value = not condition",
+    compound_assign_call("value += (f())") => "value += f()",
+    call_argument("print((a), (b))") => "print(a, b)",
+    call_argument_variadic_in_first_position("print((...), b)") => "print(..., b)",
+    if_condition("if (a == b) then end") => "if a == b then end",
+    elseif_condition("if a then elseif (b or c) then end") => "if a then elseif b or c then end",
+    while_condition("while (running) do end") => "while running do end",
+    repeat_condition("repeat until (done)") => "repeat until done",
+    numeric_for_bounds("for i = (1), (n - 1), (2) do end") => "for i = 1, n - 1, 2 do end",
+    generic_for_first_expression("for k, v in (next), t do end") => "for k, v in next, t do end",
+    index_key("return t[(key .. suffix)]") => "return t[key .. suffix]",
+    table_field_value("return { field = (f()) }") => "return { field = f() }",
+    table_index_entry("return { [(key)] = (value) }") => "return { [key] = value }",
+    table_value_in_first_position("return { (f()), 1 }") => "return { f(), 1 }",
+    field_prefix("return (object).field") => "return object.field",
+    call_prefix("(callback)()") => "callback()",
+    method_call_prefix("return (object):method()") => "return object:method()",
+    call_result_prefix("return (f()).field") => "return f().field",
+    binary_left_with_higher_precedence("return (a * b) + c") => "return a * b + c",
+    binary_right_with_higher_precedence("return a + (b * c)") => "return a + b * c",
+    binary_left_associative("return (a - b) - c") => "return a - b - c",
+    binary_right_associative_concat("return a .. (b .. c)") => "return a .. b .. c",
+    binary_right_associative_caret("return a ^ (b ^ c)") => "return a ^ b ^ c",
+    binary_left_unary("return (not a) == b") => "return not a == b",
+    binary_operand_call("return (f()) + 1") => "return f() + 1",
+    binary_operand_variadic("return (...) + 1") => "return ... + 1",
+    unary_operand_identifier("return -(a)") => "return -a",
+    unary_operand_caret("return -(a ^ 2)") => "return -a ^ 2",
+    unary_operand_unary("return not (not a)") => "return not not a",
+    if_expression_parts("return if (a) then (b + c) else (d or e)") => "return if a then b + c else d or e",
+    parentheses_in_function_body("local function f() return (value) end") => "local function f() return value end",
+);
+
+test_rule_without_effects!(
+    RemoveRedundantParentheses::default(),
+    return_call("return (f())"),
+    return_variadic("return (...)"),
+    return_call_in_last_position("return a, (f())"),
+    local_assign_call("local a, b = (f())"),
+    call_argument_call_in_last_position("print(a, (f()))"),
+    call_argument_variadic_in_last_position("print(a, (...))"),
+    table_value_call_in_last_position("return { 1, (f()) }"),
+    generic_for_call_in_last_position("for k, v in (pairs(t)) do end"),
+    binary_left_with_lower_precedence("return (a + b) * c"),
+    binary_right_with_lower_precedence("return a * (b + c)"),
+    binary_right_left_associative("return a - (b - c)"),
+    binary_left_right_associative_concat("return (a .. b) .. c"),
+    binary_left_right_associative_caret("return (a ^ b) ^ c"),
+    binary_left_unary_with_caret("return (-a) ^ 2"),
+    binary_operand_if_expression("return (if a then b else c) + 1"),
+    binary_operand_type_cast("return (a :: number) + 1"),
+    unary_operand_binary("return not (a == b)"),
+    unary_operand_addition("return -(a + b)"),
+    string_prefix("return ('abc'):upper()"),
+    table_prefix("return ({}).field"),
+    function_prefix("return (function() end)()"),
+    variadic_prefix("return (...).field"),
+    type_cast_prefix("return (value :: any).field"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_redundant_parentheses',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_redundant_parentheses'").unwrap();
+}
@@ -0,0 +1,59 @@
+use darklua_core::rules::{RemoveRedundantReturn, Rule};
+
+test_rule!(
+    remove_redundant_return,
+    RemoveRedundantReturn::default(),
+    empty_return_at_end_of_local_function("local function f() print('a') return end")
+        => "local function f() print('a') end",
+    empty_return_at_end_of_function_statement("function f() return end") => "function f() end",
+    empty_return_at_end_of_method("function obj:f() self.x = 1 return end")
+        => "function obj:f() self.x = 1 end",
+    empty_return_at_end_of_function_expression("return function() work() return end")
+        => "return function() work() end",
+    empty_return_at_end_of_file("print('a') return") => "print('a')",
+    empty_return_in_final_if_statement("local function f() if x then work() return end end")
+        => "local function f() if x then work() end end",
+    empty_return_in_final_if_statement_branches("local function f() if x then return elseif y then return else a() return end end")
+        => "local function f() if x then elseif y then else a() end end",
+    empty_return_in_final_do_statement("local function f() do work() return end end")
+        => "local function f() do work() end end",
+    empty_return_in_nested_final_statements("local function f() if x then do return end end end")
+        => "local function f() if x then do end end end",
+    parenthese_around_value("return (a)") => "return a",
+    parenthese_around_binary_expression("local function f() return (a + b) end")
+        => "local function f() return a + b end",
+    nested_parentheses("return ((a))") => "return a",
+    nested_parentheses_around_call("return ((f()))") => "return (f())",
+    parenthese_around_table("return ({})") => "return {}",
+);
+
+test_rule_without_effects!(
+    RemoveRedundantReturn::default(),
+    return_with_value("local function f() return 1 end"),
+    empty_return_in_the_middle_of_function("local function f() if x then return end work() end"),
+    empty_return_in_while_loop("local function f() while x do return end end"),
+    empty_return_in_numeric_for("local function f() for i = 1, 10 do return end end"),
+    empty_return_in_generic_for("local function f() for _, v in ipairs(t) do return end end"),
+    empty_return_in_repeat("local function f() repeat return until x end"),
+    empty_return_in_function_call_argument("call(function() end)"),
+    parenthese_around_call("return (f())"),
+    parenthese_around_method_call("return (obj:f())"),
+    parenthese_around_variable_arguments("return (...)"),
+    parenthese_around_type_cast_call("return (f() :: number)"),
+    parentheses_with_multiple_values("return (a), (b)"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_redundant_return',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_redundant_return'").unwrap();
+}
@@ -0,0 +1,95 @@
+use darklua_core::{
+    generator::{DenseLuaGenerator, LuaGenerator},
+    rules::{ContextBuilder, RemoveSpreadTablePacking, Rule},
+    LuaTarget, Parser, Resources,
+};
+
+test_rule!(
+    remove_spread_table_packing,
+    RemoveSpreadTablePacking::default(),
+    table_pack_call("local args = table.pack(...)")
+        => "local __DARKLUA_PACK = table.pack or function(...) return { n = select('#', ...), ... } end local args = __DARKLUA_PACK(...)",
+    table_unpack_call("return table.unpack(args, 1, args.n)")
+        => "local __DARKLUA_UNPACK = table.unpack or unpack return __DARKLUA_UNPACK(args, 1, args.n)",
+    table_unpack_value("local unpack = table.unpack")
+        => "local __DARKLUA_UNPACK = table.unpack or unpack local unpack = __DARKLUA_UNPACK",
+    table_pack_and_unpack("local args = table.pack(...) return table.unpack(args)")
+        => "local __DARKLUA_UNPACK = table.unpack or unpack local __DARKLUA_PACK = table.pack or function(...) return { n = select('#', ...), ... } end local args = __DARKLUA_PACK(...) return __DARKLUA_UNPACK(args)",
+    table_pack_length("return table.pack(...).n") => "return select('#', ...)",
+    table_pack_length_with_values("return table.pack(a, b, ...).n") => "return select('#', a, b, ...)",
+    table_pack_length_in_function("local function count(...) return table.pack(...).n end")
+        => "local function count(...) return select('#', ...) end",
+);
+
+test_rule_without_effects!(
+    RemoveSpreadTablePacking::default(),
+    select_count("return select('#', ...)"),
+    unpack_global("return unpack(args)"),
+    other_table_function("table.insert(list, value)"),
+    shadowed_table("local table = {} return table.unpack(args)"),
+    shadowed_table_parameter("local function f(table) return table.pack(...).n end"),
+    table_pack_method("return table:pack(...).n"),
+);
+
+fn process_with_target(code: &str, target: LuaTarget) -> String {
+    let mut block = Parser::default().parse(code).unwrap();
+    let resources = Resources::from_memory();
+    let context = ContextBuilder::new("src/init.lua", &resources, code)
+        .with_target(target)
+        .build();
+
+    RemoveSpreadTablePacking::default()
+        .process(&mut block, &context)
+        .unwrap();
+
+    let mut generator = DenseLuaGenerator::default();
+    generator.write_block(&block);
+    generator.into_string()
+}
+
+#[test]
+fn lua53_target_keeps_table_library() {
+    insta::assert_snapshot!(
+        process_with_target("local args = table.pack(...) return table.unpack(args)", LuaTarget::Lua53),
+        @"local args=table.pack(...)return table.unpack(args)"
+    );
+}
+
+#[test]
+fn lua53_target_replaces_unpack_global() {
+    insta::assert_snapshot!(
+        process_with_target("return unpack(args)", LuaTarget::Lua53),
+        @"local __DARKLUA_UNPACK=table.unpack or unpack return __DARKLUA_UNPACK(args)"
+    );
+}
+
+#[test]
+fn lua53_target_keeps_shadowed_unpack() {
+    insta::assert_snapshot!(
+        process_with_target("local unpack = f return unpack(args)", LuaTarget::Lua53),
+        @"local unpack=f return unpack(args)"
+    );
+}
+
+#[test]
+fn lua51_target_replaces_table_library() {
+    insta::assert_snapshot!(
+        process_with_target("return table.unpack(args)", LuaTarget::Lua51),
+        @"local __DARKLUA_UNPACK=table.unpack or unpack return __DARKLUA_UNPACK(args)"
+    );
+}
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_spread_table_packing',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_spread_table_packing'").unwrap();
+}
@@ -0,0 +1,57 @@
+use darklua_core::rules::{RemoveTypeFunctionCalls, Rule};
+
+test_rule!(
+    remove_type_function_calls,
+    RemoveTypeFunctionCalls::default(),
+    remove_type_cast("return value :: string") => "return value",
+    remove_type_cast_on_call("return call() :: string") => "return (call())",
+    remove_type_cast_on_variable_arguments("return ... :: string") => "return (...)",
+    remove_nested_type_casts("return (value :: any) :: string") => "return (value)",
+    typeof_string("return typeof('hello')") => "return 'string'",
+    type_number("return type(1)") => "return 'number'",
+    type_boolean("return type(true)") => "return 'boolean'",
+    type_nil("return type(nil)") => "return 'nil'",
+    type_table("return type({})") => "return 'table'",
+    type_table_argument("return type {}") => "return 'table'",
+    type_string_argument("return type 'hello'") => "return 'string'",
+    type_function("return type(function() end)") => "return 'function'",
+    type_computed_number("return type(1 + 2)") => "return 'number'",
+    typeof_after_type_cast("return typeof(1 :: any)") => "return 'number'",
+    typeof_equal_to_string("return typeof('hello') == 'string'") => "return true",
+    typeof_not_equal_to_string("return typeof('hello') ~= 'string'") => "return false",
+    typeof_equal_to_other_type("return typeof(1) == 'string'") => "return false",
+    string_equal_to_typeof("return 'number' == typeof(1)") => "return true",
+    typeof_condition_in_if("if typeof(1 :: any) == 'string' then print('string') end")
+        => "if false then print('string') end",
+    typeof_in_not("return not (typeof(1) == 'number')") => "return not (true)",
+);
+
+test_rule_without_effects!(
+    RemoveTypeFunctionCalls::default(),
+    typeof_identifier("return typeof(value)"),
+    typeof_call("return typeof(call())"),
+    typeof_variable_arguments("return typeof(...)"),
+    typeof_table_with_call("return typeof({ call() })"),
+    typeof_without_arguments("return typeof()"),
+    typeof_with_two_arguments("return typeof(1, 2)"),
+    typeof_method("return typeof:call(1)"),
+    shadowed_typeof("local typeof = function() end return typeof(1)"),
+    shadowed_type("local function f(type) return type(1) end"),
+    other_function_call("return tostring(1)"),
+    string_comparison_with_identifier("return typeof(value) == 'string'"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_type_function_calls',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_type_function_calls'").unwrap();
+}
@@ -3,9 +3,10 @@ use std::time::Duration;
 use darklua_core::{
     generator::{LuaGenerator, ReadableLuaGenerator},
     nodes::Type,
+    nodes::{Block, Expression, LastStatement, Statement},
     process::{DefaultVisitor, NodeProcessor, NodeVisitor},
     rules::{ContextBuilder, FlawlessRule, RemoveTypes, Rule},
-    Resources,
+    Options, Parser, Resources,
 };
 
 use crate::{
@@ -13,6 +14,8 @@ use crate::{
     utils,
 };
 
+use super::memory_resources;
+
 test_rule!(
     remove_types,
     RemoveTypes::default(),
@@ -113,3 +116,165 @@ fn fuzz_bundle() {
         assert!(!processor.found_type);
     })
 }
+
+/// Processes the module and returns its declaration file, after verifying that it
+/// only contains declarations: types, required modules and a typed `return`.
+fn process_declaration_file(resources: &Resources) -> String {
+    darklua_core::process(resources, Options::new("src").with_output("out"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    assert!(!resources.exists("src/module.d.luau").unwrap());
+
+    let declarations = resources.get("out/module.d.luau").unwrap();
+    let block: Block = Parser::default()
+        .parse(&declarations)
+        .expect("declaration file should parse");
+
+    for statement in block.iter_statements() {
+        match statement {
+            Statement::TypeDeclaration(_) | Statement::TypeFunction(_) => {}
+            Statement::LocalAssign(assign) => {
+                assert!(
+                    matches!(assign.last_value(), Some(Expression::Call(_))),
+                    "unexpected local assignment in declaration file:\n{}",
+                    declarations
+                );
+            }
+            _ => panic!(
+                "unexpected statement in declaration file:\n{}",
+                declarations
+            ),
+        }
+    }
+
+    if let Some(last_statement) = block.get_last_statement() {
+        let LastStatement::Return(statement) = last_statement else {
+            panic!(
+                "unexpected last statement in declaration file:\n{}",
+                declarations
+            );
+        };
+        assert!(
+            matches!(
+                statement.iter_expressions().collect::<Vec<_>>().as_slice(),
+                [Expression::TypeCast(_)]
+            ),
+            "unexpected return statement in declaration file:\n{}",
+            declarations
+        );
+    }
+
+    declarations
+}
+
+#[test]
+fn write_declaration_file_next_to_output() {
+    let resources = memory_resources!(
+        "src/module.luau" => "export type Point = { x: number, y: number }\ntype Scale = number\ntype Unused = string\nlocal Module = {}\nfunction Module.new(x: number, y: number): Point\n    return { x = x, y = y }\nend\nlocal function scale(point: Point, factor: Scale): Point\n    return Module.new(point.x * factor, point.y * factor)\nend\nModule.scale = scale\nreturn Module",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }], generator: 'readable' }",
+    );
+
+    insta::assert_snapshot!(process_declaration_file(&resources), @r###"
+    export type Point = {x: number, y: number}
+    type Scale = number
+
+    return (nil::any)::{new: (x:number, y:number) -> Point, scale: (point:Point, factor:Scale) -> Point}
+    "###);
+}
+
+#[test]
+fn write_declaration_file_with_types_used_by_exported_types() {
+    let resources = memory_resources!(
+        "src/module.luau" => "local Types = require('./types')\nlocal Other = require('./other')\ntype Id = string\nexport type User = { id: Id, name: Types.Name, friends: typeof(setmetatable({}, {})) }\nreturn nil",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }], generator: 'readable' }",
+    );
+
+    insta::assert_snapshot!(process_declaration_file(&resources), @r###"
+    local Types = require('./types')
+
+    type Id = string
+    export type User = {id: Id, name: Types.Name, friends: any}
+
+    return (nil::any)::nil
+    "###);
+}
+
+#[test]
+fn write_declaration_file_of_module_with_methods_and_variadic_functions() {
+    let resources = memory_resources!(
+        "src/module.luau" => "local Class = { name = 'class', count = 0 }\nfunction Class:getName(): string\n    return self.name\nend\nfunction Class.sum(...: number): number\n    return 0\nend\nfunction Class.print(...)\n    print(...)\nend\nfunction Class.identity<T>(value: T): T\n    return value\nend\nClass.other = function(value) return value end\nreturn Class",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }], generator: 'readable' }",
+    );
+
+    insta::assert_snapshot!(process_declaration_file(&resources), @"return (nil::any)::{name: string, count: number, getName: (self:any) -> string, sum: (...number) -> number, print: (...any) -> ...any, identity: <T>(value:T) -> T, other: (value:any) -> ...any}");
+}
+
+#[test]
+fn write_declaration_file_of_module_returning_typed_value() {
+    let resources = memory_resources!(
+        "src/module.luau" => "export type Config = { timeout: number }\nlocal config: Config = { timeout = 10 }\nreturn config",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }], generator: 'readable' }",
+    );
+
+    insta::assert_snapshot!(process_declaration_file(&resources), @r###"
+    export type Config = {timeout: number}
+
+    return (nil::any)::Config
+    "###);
+}
+
+#[test]
+fn do_not_write_empty_declaration_file() {
+    let resources = memory_resources!(
+        "src/module.luau" => "local value: number = 1\nprint(value)",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }] }",
+    );
+
+    darklua_core::process(&resources, Options::new("src").with_output("out"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    assert!(!resources.exists("out/module.d.luau").unwrap());
+}
+
+#[test]
+fn replace_declaration_files_of_input_with_generated_declaration_files() {
+    let resources = memory_resources!(
+        "src/module.luau" => "export type Value = number\nreturn 1",
+        "src/module.d.luau" => "export type Value = string\nreturn (nil :: any) :: number",
+        ".darklua.json" => "{ rules: [{ rule: 'remove_types', declaration_file: true }], generator: 'readable' }",
+    );
+
+    darklua_core::process(&resources, Options::new("src").with_output("out"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("out/module.d.luau").unwrap(), @r###"
+    export type Value = number
+
+    return (nil::any)::number
+    "###);
+}
+
+#[test]
+fn process_declaration_files_of_input_without_declaration_file_parameter() {
+    let resources = memory_resources!(
+        "src/module.luau" => "export type Value = number\nreturn 1",
+        "src/module.d.luau" => "export type Value = string\nreturn (nil :: any) :: number",
+        ".darklua.json" => "{ rules: [], generator: 'retain_lines' }",
+    );
+
+    darklua_core::process(&resources, Options::new("src").with_output("out"))
+        .unwrap()
+        .result()
+        .unwrap();
+
+    insta::assert_snapshot!(resources.get("out/module.d.luau").unwrap(), @r###"
+    export type Value = string
+    return (nil :: any) :: number
+    "###);
+}
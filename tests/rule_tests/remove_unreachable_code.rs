@@ -0,0 +1,74 @@
+use darklua_core::rules::{RemoveUnreachableCode, Rule};
+
+test_rule!(
+    remove_unreachable_code,
+    RemoveUnreachableCode::default(),
+    return_in_do("do return true end return false") => "do return true end",
+    break_in_do("while condition do do break end call() end") => "while condition do do break end end",
+    continue_in_do("for _ = 1, 10 do do continue end call() end") => "for _ = 1, 10 do do continue end end",
+    if_returning_in_every_branch(
+        "if a then return 1 elseif b then return 2 else return 3 end call() return 4"
+    ) => "if a then return 1 elseif b then return 2 else return 3 end",
+    if_exiting_with_return_and_break(
+        "while true do if a then return 1 else break end call() end"
+    ) => "while true do if a then return 1 else break end end",
+    error_call("error('oops') call()") => "error('oops')",
+    error_call_in_every_branch(
+        "if a then error('a') else return end call()"
+    ) => "if a then error('a') else return end",
+    statements_after_error_in_nested_function(
+        "local function fail() error('oops') return nil end"
+    ) => "local function fail() error('oops') end",
+    infinite_while_loop("while true do call() end return") => "while true do call() end",
+    break_in_nested_loop_does_not_exit_infinite_loop(
+        "while true do for _ = 1, 2 do break end end call()"
+    ) => "while true do for _ = 1, 2 do break end end",
+    infinite_repeat_loop("repeat call() until false print()") => "repeat call() until false",
+    while_false_loop("while false do call() end return") => "return",
+    while_nil_loop("while nil do call() end") => "",
+    empty_numeric_for("for i = 10, 1 do call(i) end") => "",
+    empty_numeric_for_with_negative_step("for i = 1, 10, -1 do call(i) end") => "",
+    keep_type_declarations_after_exit(
+        "do return end local value: Value = {} type Value = {}"
+    ) => "do return end type Value = {}",
+);
+
+test_rule_without_effects!(
+    RemoveUnreachableCode::default(),
+    return_in_condition("if condition then return 'ok' end return nil"),
+    if_without_else("if condition then return 1 elseif other then return 2 end call()"),
+    if_with_branch_not_exiting("if condition then return 1 else call() end call()"),
+    local_error_function("local error = print error('oops') call()"),
+    local_error_function_in_parent_scope("local function error() end do error('oops') call() end"),
+    error_method_call("logger:error('oops') call()"),
+    error_field_call("logger.error('oops') call()"),
+    error_assigned_in_function("local function f() error = print error('x') print(3) end"),
+    error_assigned_after_call("error('x') print(3) error = print"),
+    error_function_statement("function error() end error('x') print(3)"),
+    error_assigned_to_global_table("_G.error = print error('x') print(3)"),
+    environment_changed_with_setfenv("setfenv(1, env) error('x') print(3)"),
+    environment_read_with_getfenv("getfenv().error = print error('x') print(3)"),
+    while_loop_with_break("while true do if done then break end end call()"),
+    while_loop_with_nested_break("while true do do break end end call()"),
+    repeat_loop_with_break("repeat if done then break end until false call()"),
+    while_loop_with_unknown_condition("while condition do call() end call()"),
+    while_loop_with_side_effects("while call() and false do end"),
+    numeric_for("for i = 1, 10 do call(i) end"),
+    numeric_for_with_unknown_bounds("for i = 10, count do call(i) end"),
+    numeric_for_with_unknown_step("for i = 10, 1, step do call(i) end"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_unreachable_code',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_unreachable_code'").unwrap();
+}
@@ -0,0 +1,62 @@
+use darklua_core::rules::{RemoveUnusedFunctionParameters, Rule};
+
+test_rule!(
+    remove_unused_function_parameters,
+    RemoveUnusedFunctionParameters::default(),
+    remove_single_unused_parameter("local function f(a) end") => "local function f() end",
+    remove_trailing_unused_parameters("local function f(a, b, c) return a end")
+        => "local function f(a) return a end",
+    rename_unused_parameter_before_used_parameter("local function f(a, b) return b end")
+        => "local function f(_, b) return b end",
+    rename_and_remove("local function f(a, b, c) return b end")
+        => "local function f(_, b) return b end",
+    remove_parameter_of_function_expression("return function(value) return 1 end")
+        => "return function() return 1 end",
+    remove_parameter_of_function_statement("function module.run(options) end")
+        => "function module.run() end",
+    remove_parameter_of_method("function Class:method(value) return self end")
+        => "function Class:method() return self end",
+    rename_unused_parameters_of_variadic_function("local function f(a, ...) return ... end")
+        => "local function f(_, ...) return ... end",
+    remove_parameter_shadowed_by_local("local function f(a) local a = 1 return a end")
+        => "local function f() local a = 1 return a end",
+    remove_parameter_shadowed_in_nested_function(
+        "local function f(a) return function(a) return a end end"
+    ) => "local function f() return function(a) return a end end",
+    remove_unused_parameter_named_underscore("local function f(a, _) return a end")
+        => "local function f(a) return a end",
+    remove_parameter_of_nested_function(
+        "local function f(a) return function(b) return a end end"
+    ) => "local function f(a) return function() return a end end",
+    remove_typed_parameter("local function f(a: number, b: string): number return a end")
+        => "local function f(a: number): number return a end",
+);
+
+test_rule_without_effects!(
+    RemoveUnusedFunctionParameters::default(),
+    used_parameter("local function f(a) return a end"),
+    used_parameter_in_nested_function("local function f(a) return function() return a end end"),
+    parameter_used_in_assignment("local function f(a) a = 1 end"),
+    parameter_used_in_parameter_type("local function f(a, b: typeof(a)) return b end"),
+    unused_parameter_of_variadic_function_named_underscore(
+        "local function f(_, ...) return ... end"
+    ),
+    unused_parameter_when_underscore_is_used("local function f(a, b) return _, b end"),
+    duplicated_parameter_names("local function f(a, a) end"),
+    functions_without_parameters("local function f() end return function(...) end"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'remove_unused_function_parameters',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'remove_unused_function_parameters'").unwrap();
+}
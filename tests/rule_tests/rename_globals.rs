@@ -0,0 +1,62 @@
+use darklua_core::rules::{RenameGlobals, Rule};
+
+test_rule!(
+    rename_globals,
+    RenameGlobals::default()
+        .with_global("wait", "task.wait")
+        .with_global("spawn", "task.spawn")
+        .with_global("table.getn", "rawlen")
+        .with_global("oldLib", "newLib"),
+    rename_call("wait(1)") => "task.wait(1)",
+    rename_expression("local yield = wait") => "local yield = task.wait",
+    rename_assignment("wait = nil") => "task.wait = nil",
+    rename_field_call("local n = table.getn(list)") => "local n = rawlen(list)",
+    rename_prefix_of_field_access("return oldLib.member") => "return newLib.member",
+    rename_prefix_of_method_call("oldLib:method()") => "newLib:method()",
+    rename_prefix_of_field_assignment("oldLib.value = true") => "newLib.value = true",
+    rename_prefix_of_index("return oldLib['value']") => "return newLib['value']",
+    rename_global_function("function spawn() end") => "function task.spawn() end",
+    rename_global_function_field("function oldLib.create() end") => "function newLib.create() end",
+    rename_global_function_method("function oldLib.Class:new() end")
+        => "function newLib.Class:new() end",
+    rename_field_function("function table.getn() end") => "function rawlen() end",
+    rename_when_local_is_out_of_scope("do local wait = 0 end wait()")
+        => "do local wait = 0 end task.wait()",
+    rename_in_nested_function("local function f() return spawn(wait) end")
+        => "local function f() return task.spawn(task.wait) end",
+);
+
+test_rule_without_effects!(
+    RenameGlobals::default()
+        .with_global("wait", "task.wait")
+        .with_global("table.getn", "rawlen"),
+    does_not_rename_local_variable("local wait = function() end wait()"),
+    does_not_rename_parameter("local function f(wait) return wait() end"),
+    does_not_rename_field_of_local_variable("local table = {} return table.getn(t)"),
+    does_not_rename_other_fields("return table.insert"),
+    does_not_rename_table_field_names("return { wait = 1 }"),
+    does_not_rename_field_named_like_global("return object.wait"),
+);
+
+test_rule!(
+    rename_globals_from_configuration,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'rename_globals',
+        globals: {
+            wait: 'task.wait',
+            'string.len': 'utf8.len',
+        },
+    }"#
+    )
+    .unwrap(),
+    rename_call_from_configuration("wait()") => "task.wait()",
+    rename_field_from_configuration("return string.len(s)") => "return utf8.len(s)",
+);
+
+#[test]
+fn deserialize_from_string_fails() {
+    let err = json5::from_str::<Box<dyn Rule>>(r#"'rename_globals'"#).unwrap_err();
+
+    insta::assert_snapshot!(err.to_string(), @"missing required field 'globals' at line 1 column 1")
+}
@@ -0,0 +1,245 @@
+use darklua_core::{rules::Rule, Options, Resources};
+
+use super::memory_resources;
+
+const CONFIG: &str = r#"{
+    rules: [{
+        rule: 'rename_project_symbols',
+        symbols: { 'src/utils.lua': { deepCopy: 'clone', merge: 'assign' } },
+    }],
+    generator: 'retain_lines',
+}"#;
+
+fn process_project(resources: &Resources) {
+    darklua_core::process(resources, Options::new("src"))
+        .unwrap()
+        .result()
+        .unwrap();
+}
+
+fn expect_files(resources: &Resources, files: &[(&str, &str)]) {
+    for (path, expect_content) in files {
+        pretty_assertions::assert_eq!(
+            resources.get(path).unwrap(),
+            *expect_content,
+            "unexpected content for `{}`",
+            path
+        );
+    }
+}
+
+#[test]
+fn rename_function_statement_and_call_site() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "local Utils = {}\nfunction Utils.deepCopy(t) return t end\nreturn Utils",
+        "src/main.lua" => "local Utils = require('./utils')\nreturn Utils.deepCopy({})",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[
+            (
+                "src/utils.lua",
+                "local Utils = {}\nfunction Utils.clone(t) return t end\nreturn Utils",
+            ),
+            (
+                "src/main.lua",
+                "local Utils = require('./utils')\nreturn Utils.clone({})",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn rename_fields_of_table_assigned_to_exported_local() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "local Utils = { deepCopy = function() end, ['merge'] = function() end }\nUtils.other = Utils.deepCopy\nreturn Utils",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/utils.lua",
+            "local Utils = { clone = function() end, ['assign'] = function() end }\nUtils.other = Utils.clone\nreturn Utils",
+        )],
+    );
+}
+
+#[test]
+fn rename_fields_of_returned_table() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "local function deepCopy() end\nreturn { deepCopy = deepCopy, other = true }",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/utils.lua",
+            "local function deepCopy() end\nreturn { clone = deepCopy, other = true }",
+        )],
+    );
+}
+
+#[test]
+fn rename_method_definition_and_method_call() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "local Utils = {}\nfunction Utils:merge(t) return t end\nreturn Utils",
+        "src/main.lua" => "local Utils = require('./utils')\nreturn Utils:merge({})",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[
+            (
+                "src/utils.lua",
+                "local Utils = {}\nfunction Utils:assign(t) return t end\nreturn Utils",
+            ),
+            (
+                "src/main.lua",
+                "local Utils = require('./utils')\nreturn Utils:assign({})",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn rename_call_sites_in_nested_folder() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/lib/init.lua" => "local utils = require('../utils')\nlocal copy = utils.deepCopy\nutils.merge = nil\nreturn utils['deepCopy'](copy)",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/lib/init.lua",
+            "local utils = require('../utils')\nlocal copy = utils.clone\nutils.assign = nil\nreturn utils['clone'](copy)",
+        )],
+    );
+}
+
+#[test]
+fn rename_field_of_direct_require_call() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/main.lua" => "return require('./utils').deepCopy({})",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[("src/main.lua", "return require('./utils').clone({})")],
+    );
+}
+
+#[test]
+fn rename_field_of_local_alias() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/main.lua" => "local Utils = require('./utils')\nlocal Alias = Utils\nreturn Alias.deepCopy({})",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/main.lua",
+            "local Utils = require('./utils')\nlocal Alias = Utils\nreturn Alias.clone({})",
+        )],
+    );
+}
+
+#[test]
+fn does_not_rename_shadowed_module_variable() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/main.lua" => "local Utils = require('./utils')\nlocal function f(Utils) return Utils.deepCopy end\ndo local Utils = {} Utils.deepCopy() end",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/main.lua",
+            "local Utils = require('./utils')\nlocal function f(Utils) return Utils.deepCopy end\ndo local Utils = {} Utils.deepCopy() end",
+        )],
+    );
+}
+
+#[test]
+fn does_not_rename_fields_of_other_modules() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/other.lua" => "local Other = {}\nfunction Other.deepCopy() end\nreturn Other",
+        "src/main.lua" => "local Other = require('./other')\nreturn Other.deepCopy(), deepCopy",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[
+            (
+                "src/other.lua",
+                "local Other = {}\nfunction Other.deepCopy() end\nreturn Other",
+            ),
+            (
+                "src/main.lua",
+                "local Other = require('./other')\nreturn Other.deepCopy(), deepCopy",
+            ),
+        ],
+    );
+}
+
+#[test]
+fn does_not_rename_when_require_is_shadowed() {
+    let resources = memory_resources!(
+        "src/utils.lua" => "return {}",
+        "src/main.lua" => "local require = function() end\nlocal Utils = require('./utils')\nreturn Utils.deepCopy",
+        ".darklua.json5" => CONFIG,
+    );
+
+    process_project(&resources);
+
+    expect_files(
+        &resources,
+        &[(
+            "src/main.lua",
+            "local require = function() end\nlocal Utils = require('./utils')\nreturn Utils.deepCopy",
+        )],
+    );
+}
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'rename_project_symbols',
+        symbols: { 'src/utils.lua': { deepCopy: 'clone' } },
+        require_mode: 'path',
+    }"#,
+    )
+    .unwrap();
+}
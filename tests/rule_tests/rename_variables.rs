@@ -25,7 +25,7 @@ test_rule!(
     generic_for_identifiers("for key, value in t do return key end")
         => "for a, b in t do return a end",
     repeat_condition_is_from_block("local foo repeat local bar until bar")
-        => "local a repeat local b until b",
+        => "local b repeat local a until a",
     while_statement("local foo while foo do local foo end") => "local a while a do local b end",
     if_statement("local foo if foo then return foo end") => "local a if a then return a end",
     if_with_else("local foo if foo then local foo else return foo end")
@@ -72,7 +72,7 @@ test_rule!(
     generic_for_identifiers("for key, value in t do return key end")
         => "for a, b in t do return a end",
     repeat_condition_is_from_block("local foo repeat local bar until bar")
-        => "local a repeat local b until b",
+        => "local b repeat local a until a",
     while_statement("local foo while foo do local foo end") => "local a while a do local b end",
     if_statement("local foo if foo then return foo end") => "local a if a then return a end",
     if_with_else("local foo if foo then local foo else return foo end")
@@ -96,6 +96,86 @@ test_rule!(
         => "local a = require('@pkg/@jsdotlua/react') type Props = { children: a.ReactNode }",
 );
 
+test_rule!(
+    rename_variables_by_usage,
+    RenameVariables::default(),
+    most_used_variable_gets_shortest_name("local foo, bar = 1, 2 return bar + bar")
+        => "local b, a = 1, 2 return a + a",
+    most_used_parameter_gets_shortest_name("return function(foo, bar) return bar(bar) end")
+        => "return function(b, a) return a(a) end",
+    avoid_referenced_global("local foo = 1 return a + foo") => "local b = 1 return a + b",
+    avoid_global_referenced_in_nested_function("local foo = 1 return function() return a(foo) end")
+        => "local b = 1 return function() return a(b) end",
+    reuse_name_in_sibling_scopes("do local foo = 1 print(foo) end do local bar = 2 print(bar) end")
+        => "do local a = 1 print(a) end do local a = 2 print(a) end",
+    nested_scope_does_not_reuse_outer_name("local foo = 1 do local bar = foo return bar end")
+        => "local a = 1 do local b = a return b end",
+);
+
+test_rule!(
+    rename_variables_with_reserved,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'rename_variables',
+        reserved: ['a', 'module'],
+    }"#,
+    ).unwrap(),
+    reserved_name_is_not_generated("local foo return foo") => "local b return b",
+    reserved_variable_is_not_renamed("local module = {} local foo = 1 return module, foo")
+        => "local module = {} local b = 1 return module, b",
+);
+
+test_rule!(
+    rename_variables_keep_exports,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'rename_variables',
+        keep_exports: true,
+    }"#,
+    ).unwrap(),
+    keep_returned_variable("local module = {} local foo = 1 module.foo = foo return module")
+        => "local module = {} local a = 1 module.foo = a return module",
+    keep_variables_in_returned_table("local foo, bar, baz = 1, 2, 3 return { foo = foo, bar, [baz] = baz }")
+        => "local foo, bar, baz = 1, 2, 3 return { foo = foo, bar, [baz] = baz }",
+    rename_shadowed_variable("local foo = 1 local foo = 2 return foo")
+        => "local a = 1 local foo = 2 return foo",
+    rename_variables_in_nested_return("local function f() local value = 1 return value end return f")
+        => "local function f() local a = 1 return a end return f",
+);
+
+#[test]
+fn renaming_is_deterministic() {
+    use darklua_core::{
+        generator::{DenseLuaGenerator, LuaGenerator},
+        rules::ContextBuilder,
+        Parser, Resources,
+    };
+
+    let code = "local a, b, c, d, e = 1, 2, 3, 4, 5 \
+        local function f(x, y, z) return x + y + z + a + b end \
+        do local u, v, w = c, d, e print(u, v, w) end \
+        return f(e, d, c)";
+
+    let rename = || {
+        let rule = RenameVariables::default();
+        let resources = Resources::from_memory();
+        let mut block = Parser::default().parse(code).unwrap();
+        let context = ContextBuilder::new("src/test.lua", &resources, code).build();
+
+        rule.process(&mut block, &context).unwrap();
+
+        let mut generator = DenseLuaGenerator::default();
+        generator.write_block(&block);
+        generator.into_string()
+    };
+
+    let expected = rename();
+
+    for _ in 0..10 {
+        pretty_assertions::assert_eq!(rename(), expected);
+    }
+}
+
 test_rule_without_effects!(
     RenameVariables::default(),
     local_function_name("local function foo() end"),
@@ -124,6 +204,18 @@ fn deserialize_with_special_default_globals() {
     .unwrap();
 }
 
+#[test]
+fn deserialize_with_invalid_reserved_name() {
+    let result = json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'rename_variables',
+        reserved: ['not valid']
+    }"#,
+    );
+
+    insta::assert_snapshot!(result.unwrap_err().to_string(), @"unexpected value for field 'reserved': invalid identifier `not valid` at line 1 column 1");
+}
+
 #[test]
 fn deserialize_with_special_roblox_globals() {
     json5::from_str::<Box<dyn Rule>>(
@@ -134,3 +226,134 @@ fn deserialize_with_special_roblox_globals() {
     )
     .unwrap();
 }
+
+mod name_map_file {
+    use super::*;
+    use darklua_core::{rules::ContextBuilder, Parser, Resources};
+
+    fn new_rule() -> Box<dyn Rule> {
+        json5::from_str::<Box<dyn Rule>>(
+            r#"{
+            rule: 'rename_variables',
+            name_map_file: 'names.json',
+        }"#,
+        )
+        .unwrap()
+    }
+
+    fn process_with_rule(rule: &dyn Rule, resources: &Resources, path: &str, code: &str) {
+        resources.write(path, code).unwrap();
+        let mut block = Parser::default().preserve_tokens().parse(code).unwrap();
+        let context = ContextBuilder::new(path, resources, code)
+            .with_project_location(".")
+            .build();
+
+        rule.process(&mut block, &context).unwrap();
+    }
+
+    fn process(resources: &Resources, path: &str, code: &str) {
+        process_with_rule(new_rule().as_ref(), resources, path, code);
+    }
+
+    #[test]
+    fn writes_renamed_identifiers_with_lines() {
+        let resources = Resources::from_memory();
+
+        process(
+            &resources,
+            "src/main.lua",
+            "local counter = 0\nlocal function increment(amount)\n    counter += amount\nend\n",
+        );
+
+        insta::assert_snapshot!(resources.get("names.json").unwrap(), @r###"
+        {
+          "src/main.lua": [
+            {
+              "name": "counter",
+              "line": 1,
+              "renamed": "a"
+            },
+            {
+              "name": "amount",
+              "line": 2,
+              "renamed": "b"
+            }
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn keeps_names_of_other_files() {
+        let resources = Resources::from_memory();
+
+        let rule = new_rule();
+
+        process_with_rule(
+            rule.as_ref(),
+            &resources,
+            "src/a.lua",
+            "local value = 1 return value",
+        );
+        process_with_rule(
+            rule.as_ref(),
+            &resources,
+            "src/b.lua",
+            "for index, item in items do end",
+        );
+        process_with_rule(
+            rule.as_ref(),
+            &resources,
+            "src/a.lua",
+            "local other = 1 return other",
+        );
+
+        insta::assert_snapshot!(resources.get("names.json").unwrap(), @r###"
+        {
+          "src/a.lua": [
+            {
+              "name": "other",
+              "line": 1,
+              "renamed": "a"
+            }
+          ],
+          "src/b.lua": [
+            {
+              "name": "index",
+              "line": 1,
+              "renamed": "a"
+            },
+            {
+              "name": "item",
+              "line": 1,
+              "renamed": "b"
+            }
+          ]
+        }
+        "###);
+    }
+
+    #[test]
+    fn writes_without_lines_when_tokens_are_missing() {
+        let resources = Resources::from_memory();
+        let code = "local value = 1 return value";
+        let rule = RenameVariables::default().with_name_map_file("names.json");
+        let mut block = Parser::default().parse(code).unwrap();
+        let context = ContextBuilder::new("main.lua", &resources, code)
+            .with_project_location(".")
+            .build();
+
+        rule.process(&mut block, &context).unwrap();
+
+        insta::assert_snapshot!(resources.get("names.json").unwrap(), @r###"
+        {
+          "main.lua": [
+            {
+              "name": "value",
+              "renamed": "a"
+            }
+          ]
+        }
+        "###);
+    }
+}
@@ -0,0 +1,97 @@
+use darklua_core::rules::{Rule, SortRequires};
+
+test_rule!(
+    sort_requires,
+    SortRequires::default(),
+    sort_by_path("local B = require('b') local A = require('a')")
+        => "local A = require('a') local B = require('b')",
+    sort_by_name_when_same_path_cannot_merge(
+        "local b = require('a') local a = require('a') b = nil"
+    ) => "local a = require('a') local b = require('a') b = nil",
+    move_requires_to_the_top("local Value = 1 local A = require('a') print(A, Value)")
+        => "local A = require('a') local Value = 1 print(A, Value)",
+    sort_instance_paths(
+        "local B = require(script.Parent.B) local A = require(script.Parent.A)"
+    ) => "local A = require(script.Parent.A) local B = require(script.Parent.B)",
+    sort_string_and_instance_paths("local C = require(script.C) local A = require('./a')")
+        => "local A = require('./a') local C = require(script.C)",
+    merge_same_path("local A = require('a') local Other = require('a') return Other.value")
+        => "local A = require('a') return A.value",
+    merge_same_path_after_function(
+        "local A = require('a') local function f() return A end local Other = require('a') return Other"
+    ) => "local A = require('a') local function f() return A end return A",
+    merge_same_path_used_in_nested_function(
+        "local A = require('a') local Other = require('a') return function() return Other.value end"
+    ) => "local A = require('a') return function() return A.value end",
+    merge_same_path_with_type_field(
+        "local A = require('a') local Other = require('a') local v: Other.Type = nil return v"
+    ) => "local A = require('a') local v: A.Type = nil return v",
+    keep_require_used_before(
+        "print(A) local B = require('b') local A = require('a')"
+    ) => "local B = require('b') print(A) local A = require('a')",
+    keep_require_assigned_before(
+        "A = 1 local B = require('b') local A = require('a')"
+    ) => "local B = require('b') A = 1 local A = require('a')",
+    keep_require_after_early_return(
+        "local c = require('./c') local a = require('./a') if x then return end local b = require('./b')"
+    ) => "local a = require('./a') local c = require('./c') if x then return end local b = require('./b')",
+    keep_require_after_loop_returning(
+        "local c = require('./c') for _, v in t do if v then return v end end local b = require('./b')"
+    ) => "local c = require('./c') for _, v in t do if v then return v end end local b = require('./b')",
+    move_require_after_function_returning(
+        "local function f() return 1 end local b = require('./b')"
+    ) => "local b = require('./b') local function f() return 1 end",
+);
+
+test_rule_with_tokens!(
+    sort_requires_with_tokens,
+    SortRequires::default(),
+    keep_leading_comment_at_the_top("--!strict\nlocal Value = 1\nlocal A = require('a')\n")
+        => "--!strict\n\nlocal A = require('a')\nlocal Value = 1\n",
+);
+
+test_rule_without_effects!(
+    SortRequires::default(),
+    sorted_requires("local A = require('a') local B = require('b')"),
+    require_in_function("local function f() local B = require('b') local A = require('a') end"),
+    require_with_multiple_variables("local B = require('b') local A, C = require('a')"),
+    require_with_method("local B = require('b') local A = obj:require('a')"),
+    require_with_multiple_arguments("local B = require('b') local A = require('a', 'c')"),
+    require_is_local("local require = custom local B = require('b') local A = require('a')"),
+    keep_require_shadowing_conflict(
+        "local A = require('a') local Other = require('a') local function f(A) return Other end"
+    ),
+    keep_require_assigned("local A = require('a') local Other = require('a') Other = nil"),
+    keep_require_redefined_with_function(
+        "local A = require('a') local Other = require('a') function A() end"
+    ),
+    keep_require_using_local_variable(
+        "local B = require('b') local Packages = script.Parent local A = require(Packages.A)"
+    ),
+    keep_require_declared_twice("local A = require('b') local A = require('a')"),
+    require_local_function(
+        "local function require() end local B = require('b') local A = require('a')"
+    ),
+    require_after_early_return(
+        "local a = require('./a') if x then return end local b = require('./b')"
+    ),
+    require_after_do_return("local c = require('./c') do return end local b = require('./b')"),
+    require_called_with_config("local c = require('./c') local b = require('./b')(config)"),
+    require_with_field("local c = require('./c') local b = require('./b').value"),
+    require_with_method_call("local c = require('./c') local b = require('./b'):new()"),
+);
+
+#[test]
+fn deserialize_from_object_notation() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'sort_requires',
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'sort_requires'").unwrap();
+}
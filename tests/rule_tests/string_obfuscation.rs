@@ -0,0 +1,70 @@
+use darklua_core::rules::{Rule, StringObfuscation};
+
+test_rule_snapshot!(
+    string_obfuscation,
+    StringObfuscation::default(),
+    return_string("return 'hello'"),
+    two_strings("local a, b = 'a', 'b'"),
+    string_call_argument("print 'hello'"),
+    string_in_nested_function("local function greet() return 'hi' end"),
+    string_in_table("return { key = 'value', ['index'] = true }"),
+);
+
+test_rule_snapshot!(
+    string_obfuscation_with_base64_encoding,
+    StringObfuscation::default().with_base64_encoding(),
+    return_string("return 'hello'"),
+    string_call_argument("print 'hello'"),
+);
+
+test_rule_snapshot!(
+    string_obfuscation_with_xor_encoding,
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'string_obfuscation',
+        encoding: 'xor',
+        key: 'key',
+    }"#,
+    )
+    .unwrap(),
+    return_string("return 'hello'"),
+    string_call_argument("print 'hello'"),
+);
+
+test_rule_snapshot!(
+    string_obfuscation_with_exclude,
+    StringObfuscation::default().with_exclude("^@"),
+    excluded_and_obfuscated_strings("return '@config', 'value'"),
+);
+
+test_rule_without_effects!(
+    StringObfuscation::default(),
+    no_strings("return 1 + 2"),
+    empty_string("return ''"),
+    require_call("local module = require('./module')"),
+    require_call_with_string_argument("local module = require './module'"),
+);
+
+test_rule_without_effects!(
+    StringObfuscation::default()
+        .with_exclude("^@")
+        .with_exclude("config"),
+    excluded_strings("return '@value', 'my-config'"),
+);
+
+#[test]
+fn deserialize_from_object() {
+    json5::from_str::<Box<dyn Rule>>(
+        r#"{
+        rule: 'string_obfuscation',
+        encoding: 'base64',
+        exclude: ['^@'],
+    }"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn deserialize_from_string() {
+    json5::from_str::<Box<dyn Rule>>("'string_obfuscation'").unwrap();
+}